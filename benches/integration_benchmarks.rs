@@ -1,7 +1,8 @@
+use chronophoton::bench::{self, Solver};
 use chronophoton::core::systems::DrivenTLS;
 use chronophoton::core::{IntegratorType, QuantumState};
 use chronophoton::simulation::SimulationBuilder;
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use std::hint::black_box;
 
 fn benchmark_driven_tls(c: &mut Criterion) {
@@ -24,5 +25,18 @@ fn benchmark_driven_tls(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, benchmark_driven_tls);
+fn benchmark_solver_scaling(c: &mut Criterion) {
+    for solver in Solver::ALL {
+        let mut group = c.benchmark_group(solver.name());
+        group.sample_size(10);
+        for case in bench::cases(&bench::DIMS, &[solver]) {
+            group.bench_with_input(BenchmarkId::from_parameter(case.dim), &case, |b, case| {
+                b.iter(|| case.run().unwrap())
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, benchmark_driven_tls, benchmark_solver_scaling);
 criterion_main!(benches);
@@ -0,0 +1,22 @@
+fn main() {
+    #[cfg(feature = "capi")]
+    generate_header();
+}
+
+// Only src/capi.rs is parsed, so the header holds the `Cp*` handles and `cp_*`
+// functions and nothing else from the crate. The header lands in OUT_DIR; the
+// checked-in include/chronophoton.h is compared against it by the capi tests.
+#[cfg(feature = "capi")]
+fn generate_header() {
+    let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src/capi.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    cbindgen::Builder::new()
+        .with_src(format!("{}/src/capi.rs", crate_dir))
+        .with_config(cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).unwrap())
+        .generate()
+        .expect("Unable to generate C header")
+        .write_to_file(format!("{}/chronophoton.h", out_dir));
+}
@@ -0,0 +1,88 @@
+#ifndef CHRONOPHOTON_H
+#define CHRONOPHOTON_H
+
+/* Generated by cbindgen from src/capi.rs. Do not edit by hand. */
+
+#include <stddef.h>
+
+typedef struct CpConfig CpConfig;
+
+typedef struct CpResults CpResults;
+
+/**
+ * Returns the message of the last error raised on this thread, or null.
+ * The string stays valid until the next failing call on the same thread.
+ */
+const char *cp_last_error(void);
+
+/**
+ * Parses and validates a TOML configuration. Returns null on error.
+ *
+ * # Safety
+ * `toml` must be a valid NUL-terminated string.
+ */
+struct CpConfig *cp_config_from_toml(const char *toml);
+
+/**
+ * # Safety
+ * `config` must be null or a handle returned by `cp_config_from_toml`
+ * that has not been freed yet.
+ */
+void cp_config_free(struct CpConfig *config);
+
+/**
+ * Runs the simulation described by `config`. Returns null on error.
+ *
+ * # Safety
+ * `config` must be a live handle returned by `cp_config_from_toml`.
+ */
+struct CpResults *cp_run(const struct CpConfig *config);
+
+/**
+ * # Safety
+ * `results` must be null or a handle returned by `cp_run` that has not
+ * been freed yet.
+ */
+void cp_results_free(struct CpResults *results);
+
+/**
+ * # Safety
+ * `results` must be a live handle returned by `cp_run`.
+ */
+size_t cp_results_observable_count(const struct CpResults *results);
+
+/**
+ * Returns the name of the `index`-th observable, or null if out of range.
+ * The string is owned by `results`.
+ *
+ * # Safety
+ * `results` must be a live handle returned by `cp_run`.
+ */
+const char *cp_results_observable_name(const struct CpResults *results, size_t index);
+
+/**
+ * Returns the number of samples of observable `name`, or 0 if it is unknown.
+ *
+ * # Safety
+ * `results` must be a live handle returned by `cp_run` and `name` a valid
+ * NUL-terminated string.
+ */
+size_t cp_results_series_len(const struct CpResults *results, const char *name);
+
+/**
+ * Copies up to `len` samples of observable `name` into the caller's
+ * buffers. Any of `times`, `re` and `im` may be null to skip that column.
+ * Returns the number of samples copied, or -1 on error.
+ *
+ * # Safety
+ * `results` must be a live handle returned by `cp_run`, `name` a valid
+ * NUL-terminated string, and each non-null buffer must hold `len` doubles.
+ */
+int cp_results_series(const struct CpResults *results,
+                      const char *name,
+                      double *times,
+                      double *re,
+                      double *im,
+                      size_t len);
+
+#endif  /* CHRONOPHOTON_H */
@@ -0,0 +1,249 @@
+use crate::core::lindblad::{LindbladOperator, LindbladSolver};
+use crate::core::systems::DrivenCavity;
+use crate::core::{integrator, IntegratorType, QuantumState};
+use crate::gpu::BatchExecutor;
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Instant;
+
+pub const DIMS: [usize; 4] = [2, 16, 64, 256];
+
+const CLOSED_STEPS: usize = 200;
+const LINDBLAD_STEPS: usize = 10;
+const BATCH_SIZE: usize = 8;
+const TIMESTEP: f64 = 1e-3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Solver {
+    Closed,
+    Lindblad,
+    Batched,
+}
+
+impl Solver {
+    pub const ALL: [Solver; 3] = [Solver::Closed, Solver::Lindblad, Solver::Batched];
+
+    pub fn parse(name: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|s| s.name() == name)
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "Unknown benchmark solver '{}', expected closed, lindblad or batched",
+                    name
+                ))
+            })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Solver::Closed => "closed",
+            Solver::Lindblad => "lindblad",
+            Solver::Batched => "batched",
+        }
+    }
+
+    pub fn steps(&self) -> usize {
+        match self {
+            Solver::Closed => CLOSED_STEPS,
+            Solver::Lindblad => LINDBLAD_STEPS,
+            Solver::Batched => CLOSED_STEPS * BATCH_SIZE,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchCase {
+    pub solver: Solver,
+    pub dim: usize,
+}
+
+impl BenchCase {
+    pub fn new(solver: Solver, dim: usize) -> Self {
+        Self { solver, dim }
+    }
+
+    pub fn name(&self) -> String {
+        format!("{}_dim{}", self.solver.name(), self.dim)
+    }
+
+    pub fn run(&self) -> Result<()> {
+        match self.solver {
+            Solver::Closed => run_closed(self.dim, CLOSED_STEPS),
+            Solver::Lindblad => {
+                let hamiltonian = Box::new(cavity(self.dim));
+                let decay = vec![LindbladOperator::annihilation(self.dim, 0.1)?];
+                let solver = LindbladSolver::new(hamiltonian, decay)?;
+                let mut rho = QuantumState::ground_state(self.dim).to_density_matrix();
+                for k in 0..LINDBLAD_STEPS {
+                    solver.step(&mut rho, k as f64 * TIMESTEP, TIMESTEP)?;
+                }
+                Ok(())
+            }
+            Solver::Batched => {
+                let dim = self.dim;
+                let jobs = (0..BATCH_SIZE)
+                    .map(|_| move || run_closed(dim, CLOSED_STEPS))
+                    .collect();
+                BatchExecutor::new(BATCH_SIZE, false).execute(jobs)?;
+                Ok(())
+            }
+        }
+    }
+}
+
+fn cavity(dim: usize) -> DrivenCavity {
+    DrivenCavity::new(1.0, 2.0, 0.1, dim)
+}
+
+fn run_closed(dim: usize, steps: usize) -> Result<()> {
+    let hamiltonian = cavity(dim);
+    let integrator = integrator::create_integrator(IntegratorType::RK4);
+    let mut state = QuantumState::ground_state(dim);
+    for k in 0..steps {
+        integrator.step(&hamiltonian, &mut state, k as f64 * TIMESTEP, TIMESTEP)?;
+    }
+    Ok(())
+}
+
+pub fn cases(dims: &[usize], solvers: &[Solver]) -> Vec<BenchCase> {
+    solvers
+        .iter()
+        .flat_map(|&solver| dims.iter().map(move |&dim| BenchCase::new(solver, dim)))
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub solver: String,
+    pub dim: usize,
+    pub steps: usize,
+    pub samples: usize,
+    pub mean_seconds: f64,
+    pub min_seconds: f64,
+    pub steps_per_second: f64,
+}
+
+pub fn measure(case: &BenchCase, samples: usize) -> Result<Measurement> {
+    if samples == 0 {
+        return Err(Error::InvalidParameter(
+            "Benchmark needs at least one sample".to_string(),
+        ));
+    }
+    case.run()?;
+
+    let mut timings = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        let start = Instant::now();
+        case.run()?;
+        timings.push(start.elapsed().as_secs_f64());
+    }
+    let mean_seconds = timings.iter().sum::<f64>() / samples as f64;
+    let min_seconds = timings.iter().copied().fold(f64::INFINITY, f64::min);
+    let steps = case.solver.steps();
+    Ok(Measurement {
+        solver: case.solver.name().to_string(),
+        dim: case.dim,
+        steps,
+        samples,
+        mean_seconds,
+        min_seconds,
+        steps_per_second: steps as f64 / min_seconds.max(f64::MIN_POSITIVE),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub version: String,
+    pub measurements: Vec<Measurement>,
+}
+
+impl BenchReport {
+    pub fn run(cases: &[BenchCase], samples: usize) -> Result<Self> {
+        let measurements = cases
+            .iter()
+            .map(|case| {
+                tracing::debug!("Benchmarking {}", case.name());
+                measure(case, samples)
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            measurements,
+        })
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| Error::Serialization(e.to_string()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        serde_json::from_str(&text).map_err(|e| Error::Serialization(e.to_string()))
+    }
+
+    pub fn regressions(&self, baseline: &BenchReport, tolerance: f64) -> Vec<Regression> {
+        self.measurements
+            .iter()
+            .filter_map(|current| {
+                let previous = baseline
+                    .measurements
+                    .iter()
+                    .find(|m| m.solver == current.solver && m.dim == current.dim)?;
+                let slowdown = current.min_seconds / previous.min_seconds;
+                (slowdown > 1.0 + tolerance).then(|| Regression {
+                    solver: current.solver.clone(),
+                    dim: current.dim,
+                    baseline_seconds: previous.min_seconds,
+                    current_seconds: current.min_seconds,
+                    slowdown,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Regression {
+    pub solver: String,
+    pub dim: usize,
+    pub baseline_seconds: f64,
+    pub current_seconds: f64,
+    pub slowdown: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_report_round_trips_and_flags_regressions() {
+        let cases = cases(&[2, 4], &[Solver::Closed, Solver::Lindblad]);
+        assert_eq!(cases.len(), 4);
+        assert_eq!(cases[3].name(), "lindblad_dim4");
+
+        let report = BenchReport::run(&cases, 1).unwrap();
+        let json = report.to_json().unwrap();
+        let parsed: BenchReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.version, report.version);
+        assert_eq!(parsed.measurements.len(), 4);
+        assert_relative_eq!(
+            parsed.measurements[1].min_seconds,
+            report.measurements[1].min_seconds,
+            max_relative = 1e-12
+        );
+        assert!(report.regressions(&report, 0.0).is_empty());
+
+        let mut faster = report.clone();
+        faster.measurements[0].min_seconds /= 2.0;
+        let regressions = report.regressions(&faster, 0.2);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(
+            (regressions[0].solver.as_str(), regressions[0].dim),
+            ("closed", 2)
+        );
+        assert!(Solver::parse("stochastic").is_err());
+    }
+}
@@ -0,0 +1,262 @@
+use crate::data::Config;
+use crate::simulation::{SimulationBuilder, SimulationResults};
+use crate::utils::{Error, Result};
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+pub struct CpConfig {
+    config: Config,
+}
+
+pub struct CpResults {
+    results: SimulationResults,
+    names: Vec<CString>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: String) {
+    let message = CString::new(message.replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|slot| *slot.borrow_mut() = Some(message));
+}
+
+fn guarded<T>(f: impl FnOnce() -> Result<T>) -> Option<T> {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(e)) => {
+            set_last_error(e.to_string());
+            None
+        }
+        Err(_) => {
+            set_last_error("Internal panic in chronophoton".to_string());
+            None
+        }
+    }
+}
+
+unsafe fn borrow<'a, T>(ptr: *const T, what: &str) -> Result<&'a T> {
+    ptr.as_ref()
+        .ok_or_else(|| Error::InvalidParameter(format!("{} handle is null", what)))
+}
+
+unsafe fn borrow_str<'a>(ptr: *const c_char, what: &str) -> Result<&'a str> {
+    if ptr.is_null() {
+        return Err(Error::InvalidParameter(format!("{} is null", what)));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| Error::InvalidParameter(format!("{} is not valid UTF-8", what)))
+}
+
+/// Returns the message of the last error raised on this thread, or null.
+/// The string stays valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn cp_last_error() -> *const c_char {
+    LAST_ERROR.with(|slot| slot.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
+
+/// Parses and validates a TOML configuration. Returns null on error.
+///
+/// # Safety
+/// `toml` must be a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cp_config_from_toml(toml: *const c_char) -> *mut CpConfig {
+    guarded(|| {
+        let config = Config::from_toml_str(borrow_str(toml, "TOML string")?)?;
+        config.validate()?;
+        Ok(Box::into_raw(Box::new(CpConfig { config })))
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// # Safety
+/// `config` must be null or a handle returned by `cp_config_from_toml`
+/// that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn cp_config_free(config: *mut CpConfig) {
+    if !config.is_null() {
+        drop(Box::from_raw(config));
+    }
+}
+
+/// Runs the simulation described by `config`. Returns null on error.
+///
+/// # Safety
+/// `config` must be a live handle returned by `cp_config_from_toml`.
+#[no_mangle]
+pub unsafe extern "C" fn cp_run(config: *const CpConfig) -> *mut CpResults {
+    guarded(|| {
+        let config = &borrow(config, "Config")?.config;
+        let mut results = SimulationBuilder::from_config(config)?.run()?;
+        results.set_steady_state_options(config.observables.steady_state.clone());
+
+        let names = results
+            .observable_names()
+            .into_iter()
+            .map(|name| CString::new(name.as_str()).unwrap_or_default())
+            .collect();
+        Ok(Box::into_raw(Box::new(CpResults { results, names })))
+    })
+    .unwrap_or(ptr::null_mut())
+}
+
+/// # Safety
+/// `results` must be null or a handle returned by `cp_run` that has not
+/// been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn cp_results_free(results: *mut CpResults) {
+    if !results.is_null() {
+        drop(Box::from_raw(results));
+    }
+}
+
+/// # Safety
+/// `results` must be a live handle returned by `cp_run`.
+#[no_mangle]
+pub unsafe extern "C" fn cp_results_observable_count(results: *const CpResults) -> usize {
+    results.as_ref().map_or(0, |r| r.names.len())
+}
+
+/// Returns the name of the `index`-th observable, or null if out of range.
+/// The string is owned by `results`.
+///
+/// # Safety
+/// `results` must be a live handle returned by `cp_run`.
+#[no_mangle]
+pub unsafe extern "C" fn cp_results_observable_name(
+    results: *const CpResults,
+    index: usize,
+) -> *const c_char {
+    results
+        .as_ref()
+        .and_then(|r| r.names.get(index))
+        .map_or(ptr::null(), |name| name.as_ptr())
+}
+
+/// Returns the number of samples of observable `name`, or 0 if it is unknown.
+///
+/// # Safety
+/// `results` must be a live handle returned by `cp_run` and `name` a valid
+/// NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn cp_results_series_len(
+    results: *const CpResults,
+    name: *const c_char,
+) -> usize {
+    guarded(|| {
+        let results = &borrow(results, "Results")?.results;
+        Ok(results
+            .series(borrow_str(name, "Observable name")?)
+            .map_or(0, |series| series.len()))
+    })
+    .unwrap_or(0)
+}
+
+/// Copies up to `len` samples of observable `name` into the caller's
+/// buffers. Any of `times`, `re` and `im` may be null to skip that column.
+/// Returns the number of samples copied, or -1 on error.
+///
+/// # Safety
+/// `results` must be a live handle returned by `cp_run`, `name` a valid
+/// NUL-terminated string, and each non-null buffer must hold `len` doubles.
+#[no_mangle]
+pub unsafe extern "C" fn cp_results_series(
+    results: *const CpResults,
+    name: *const c_char,
+    times: *mut f64,
+    re: *mut f64,
+    im: *mut f64,
+    len: usize,
+) -> c_int {
+    guarded(|| {
+        let results = &borrow(results, "Results")?.results;
+        let name = borrow_str(name, "Observable name")?;
+        let series = results
+            .series(name)
+            .ok_or_else(|| Error::InvalidParameter(format!("Unknown observable '{}'", name)))?;
+
+        let n = series.len().min(len);
+        for (k, (t, value)) in series.iter().take(n).enumerate() {
+            if !times.is_null() {
+                *times.add(k) = t;
+            }
+            if !re.is_null() {
+                *re.add(k) = value.re;
+            }
+            if !im.is_null() {
+                *im.add(k) = value.im;
+            }
+        }
+        Ok(n as c_int)
+    })
+    .unwrap_or(-1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex64;
+
+    fn results_handle() -> CpResults {
+        let mut results = SimulationResults::new();
+        for k in 0..4 {
+            results.add_observable("sz", k as f64, Complex64::new(k as f64, -1.0));
+        }
+        CpResults {
+            results,
+            names: vec![CString::new("sz").unwrap()],
+        }
+    }
+
+    #[test]
+    fn test_series_roundtrip() {
+        let handle = results_handle();
+        let name = CString::new("sz").unwrap();
+        let (mut times, mut re) = ([0.0; 4], [0.0; 4]);
+
+        unsafe {
+            assert_eq!(cp_results_observable_count(&handle), 1);
+            assert_eq!(cp_results_series_len(&handle, name.as_ptr()), 4);
+            let copied = cp_results_series(
+                &handle,
+                name.as_ptr(),
+                times.as_mut_ptr(),
+                re.as_mut_ptr(),
+                ptr::null_mut(),
+                4,
+            );
+            assert_eq!(copied, 4);
+        }
+        assert_eq!(times, [0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(re, times);
+    }
+
+    #[test]
+    fn test_errors_are_reported() {
+        let toml = CString::new("[simulation]\nname = 1").unwrap();
+        let config = unsafe { cp_config_from_toml(toml.as_ptr()) };
+        assert!(config.is_null());
+
+        let message = unsafe { CStr::from_ptr(cp_last_error()) };
+        assert!(message.to_str().unwrap().contains("simulation.name"));
+    }
+
+    #[test]
+    fn test_checked_in_header_is_current() {
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/chronophoton.h"));
+        assert_eq!(
+            include_str!("../include/chronophoton.h"),
+            generated,
+            "include/chronophoton.h is stale; copy the header cbindgen wrote to OUT_DIR"
+        );
+        assert_eq!(
+            generated.matches("#define").count(),
+            1,
+            "only the include guard"
+        );
+    }
+}
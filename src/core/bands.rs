@@ -0,0 +1,366 @@
+use crate::core::lattice::Lattice;
+use crate::utils::math::hermitian_eigen;
+use crate::utils::{Error, Result};
+use ndarray::{s, Array1, Array2};
+use num_complex::Complex64;
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::io::Write;
+use std::path::Path;
+
+const COUPLING_TOLERANCE: f64 = 1e-12;
+const GAP_TOLERANCE: f64 = 1e-9;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hopping {
+    pub from: usize,
+    pub to: usize,
+    pub offset: i64,
+    pub coupling: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitCell {
+    onsite: Vec<f64>,
+    hoppings: Vec<Hopping>,
+}
+
+impl UnitCell {
+    pub fn new(onsite: Vec<f64>) -> Result<Self> {
+        if onsite.is_empty() {
+            return Err(Error::InvalidParameter(
+                "Unit cell needs at least one site".to_string(),
+            ));
+        }
+        Ok(Self {
+            onsite,
+            hoppings: Vec::new(),
+        })
+    }
+
+    pub fn ssh(j1: f64, j2: f64) -> Self {
+        Self {
+            onsite: vec![0.0; 2],
+            hoppings: vec![
+                Hopping {
+                    from: 0,
+                    to: 1,
+                    offset: 0,
+                    coupling: j1,
+                },
+                Hopping {
+                    from: 1,
+                    to: 0,
+                    offset: 1,
+                    coupling: j2,
+                },
+            ],
+        }
+    }
+
+    pub fn add_hopping(
+        &mut self,
+        from: usize,
+        to: usize,
+        offset: i64,
+        coupling: f64,
+    ) -> Result<()> {
+        if from >= self.sites() || to >= self.sites() || (from == to && offset == 0) {
+            return Err(Error::InvalidParameter(format!(
+                "Hopping {} -> {} (offset {}) is invalid for a unit cell of {} sites",
+                from,
+                to,
+                offset,
+                self.sites()
+            )));
+        }
+        self.hoppings.push(Hopping {
+            from,
+            to,
+            offset,
+            coupling,
+        });
+        Ok(())
+    }
+
+    pub fn from_lattice(lattice: &Lattice, cell_sites: usize) -> Result<Self> {
+        let n = lattice.num_sites();
+        if cell_sites == 0 || !n.is_multiple_of(cell_sites) || n / cell_sites < 2 {
+            return Err(Error::InvalidParameter(format!(
+                "A lattice of {} sites cannot be split into at least two cells of {} sites",
+                n, cell_sites
+            )));
+        }
+        let cells = (n / cell_sites) as i64;
+        let onsite = lattice.onsite()[..cell_sites].to_vec();
+        if let Some(site) = (0..n)
+            .find(|&i| (lattice.onsite()[i] - onsite[i % cell_sites]).abs() > COUPLING_TOLERANCE)
+        {
+            return Err(not_invariant(
+                cell_sites,
+                format!("on-site detuning of site {}", site),
+            ));
+        }
+
+        let mut templates: BTreeMap<(usize, usize, i64), (f64, i64)> = BTreeMap::new();
+        let mut periodic = false;
+        for bond in lattice.bonds() {
+            let [a, b] = bond.sites;
+            let (ca, cb) = ((a / cell_sites) as i64, (b / cell_sites) as i64);
+            let key = if cells > 2 && cb - ca == cells - 1 {
+                periodic = true;
+                (b % cell_sites, a % cell_sites, 1)
+            } else {
+                (a % cell_sites, b % cell_sites, cb - ca)
+            };
+            let entry = templates.entry(key).or_insert((bond.coupling, 0));
+            if (entry.0 - bond.coupling).abs() > COUPLING_TOLERANCE {
+                return Err(not_invariant(
+                    cell_sites,
+                    format!("coupling of bond ({}, {})", a, b),
+                ));
+            }
+            entry.1 += 1;
+        }
+
+        let mut cell = Self::new(onsite)?;
+        for (&(from, to, offset), &(coupling, count)) in &templates {
+            let expected = if periodic { cells } else { cells - offset };
+            if count != expected {
+                return Err(not_invariant(
+                    cell_sites,
+                    format!(
+                        "hopping {} -> {} (offset {}) appears in {} of {} cells",
+                        from, to, offset, count, expected
+                    ),
+                ));
+            }
+            cell.add_hopping(from, to, offset, coupling)?;
+        }
+        Ok(cell)
+    }
+
+    pub fn detect(lattice: &Lattice) -> Result<Self> {
+        (1..=lattice.num_sites() / 2)
+            .filter(|&c| lattice.num_sites().is_multiple_of(c))
+            .find_map(|c| Self::from_lattice(lattice, c).ok())
+            .ok_or_else(|| {
+                Error::InvalidParameter(format!(
+                    "Lattice of {} sites is not translation invariant along its site order",
+                    lattice.num_sites()
+                ))
+            })
+    }
+
+    pub fn sites(&self) -> usize {
+        self.onsite.len()
+    }
+
+    pub fn hoppings(&self) -> &[Hopping] {
+        &self.hoppings
+    }
+
+    pub fn is_chiral(&self) -> bool {
+        self.sites() == 2
+            && self.onsite.iter().all(|&e| e.abs() <= COUPLING_TOLERANCE)
+            && self.hoppings.iter().all(|h| h.from != h.to)
+    }
+
+    pub fn bloch_hamiltonian(&self, k: f64) -> Array2<Complex64> {
+        let diagonal: Array1<Complex64> = self
+            .onsite
+            .iter()
+            .map(|&e| Complex64::new(e, 0.0))
+            .collect();
+        let mut h = Array2::from_diag(&diagonal);
+        for hop in &self.hoppings {
+            let term = Complex64::from_polar(hop.coupling, k * hop.offset as f64);
+            h[[hop.from, hop.to]] += term;
+            h[[hop.to, hop.from]] += term.conj();
+        }
+        h
+    }
+}
+
+fn not_invariant(cell_sites: usize, what: String) -> Error {
+    Error::InvalidParameter(format!(
+        "Lattice is not translation invariant with {}-site cells: {}",
+        cell_sites, what
+    ))
+}
+
+#[derive(Debug, Clone)]
+pub struct BandStructure {
+    pub k: Vec<f64>,
+    pub energies: Vec<Vec<f64>>,
+}
+
+impl BandStructure {
+    pub fn compute(cell: &UnitCell, num_k: usize) -> Result<Self> {
+        if num_k < 2 {
+            return Err(Error::InvalidParameter(
+                "Band structure needs at least two k points".to_string(),
+            ));
+        }
+        let k: Vec<f64> = (0..num_k)
+            .map(|i| -PI + 2.0 * PI * i as f64 / (num_k - 1) as f64)
+            .collect();
+        let energies = k
+            .iter()
+            .map(|&k| hermitian_eigen(&cell.bloch_hamiltonian(k).view()).0)
+            .collect();
+        Ok(Self { k, energies })
+    }
+
+    pub fn num_bands(&self) -> usize {
+        self.energies.first().map_or(0, Vec::len)
+    }
+
+    pub fn band(&self, n: usize) -> Vec<f64> {
+        self.energies.iter().map(|e| e[n]).collect()
+    }
+
+    pub fn gap(&self, n: usize) -> f64 {
+        let upper = self.band(n + 1).into_iter().fold(f64::INFINITY, f64::min);
+        let lower = self.band(n).into_iter().fold(f64::NEG_INFINITY, f64::max);
+        upper - lower
+    }
+
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let header: Vec<String> = (0..self.num_bands())
+            .map(|n| format!("band{}", n))
+            .collect();
+        writeln!(writer, "k,{}", header.join(","))?;
+        for (k, energies) in self.k.iter().zip(&self.energies) {
+            let row: Vec<String> = energies.iter().map(f64::to_string).collect();
+            writeln!(writer, "{},{}", k, row.join(","))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+pub fn winding_number(cell: &UnitCell, num_k: usize) -> Result<i64> {
+    if !cell.is_chiral() {
+        return Err(Error::InvalidParameter(
+            "Winding number needs a two-site unit cell with chiral symmetry".to_string(),
+        ));
+    }
+    let num_k = num_k.max(3);
+    let q: Vec<Complex64> = (0..=num_k)
+        .map(|i| cell.bloch_hamiltonian(-PI + 2.0 * PI * i as f64 / num_k as f64)[[1, 0]])
+        .collect();
+    if q.iter().any(|q| q.norm() < GAP_TOLERANCE) {
+        return Err(Error::Numerical(
+            "Band gap closes, the winding number is undefined".to_string(),
+        ));
+    }
+    let total: f64 = q.windows(2).map(|w| (w[1] / w[0]).arg()).sum();
+    Ok((total / (2.0 * PI)).round() as i64)
+}
+
+#[derive(Debug, Clone)]
+pub struct EdgeMode {
+    pub energy: f64,
+    pub edge_weight: f64,
+    pub probabilities: Vec<f64>,
+}
+
+pub fn chain_edges(num_sites: usize, depth: usize) -> Vec<usize> {
+    (0..num_sites)
+        .filter(|&i| i < depth || i + depth >= num_sites)
+        .collect()
+}
+
+pub fn edge_modes(
+    lattice: &Lattice,
+    edge_sites: &[usize],
+    threshold: f64,
+) -> Result<Vec<EdgeMode>> {
+    let n = lattice.num_sites();
+    if let Some(&site) = edge_sites.iter().find(|&&s| s >= n) {
+        return Err(Error::InvalidParameter(format!(
+            "Edge site {} is outside a lattice of {} sites",
+            site, n
+        )));
+    }
+    let h = lattice.hamiltonian(0.0);
+    let single = h.matrix().slice(s![1.., 1..]);
+    let (energies, vectors) = hermitian_eigen(&single);
+
+    Ok(energies
+        .into_iter()
+        .zip(vectors.columns())
+        .map(|(energy, vector)| {
+            let probabilities: Vec<f64> = vector.iter().map(|a| a.norm_sqr()).collect();
+            let edge_weight = edge_sites.iter().map(|&s| probabilities[s]).sum();
+            EdgeMode {
+                energy,
+                edge_weight,
+                probabilities,
+            }
+        })
+        .filter(|mode| mode.edge_weight >= threshold)
+        .collect())
+}
+
+pub fn write_edge_modes_csv(modes: &[EdgeMode], path: &Path) -> Result<()> {
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let sites = modes.first().map_or(0, |m| m.probabilities.len());
+    let header: Vec<String> = (0..sites).map(|i| format!("p{}", i)).collect();
+    writeln!(writer, "energy,edge_weight,{}", header.join(","))?;
+    for mode in modes {
+        let row: Vec<String> = mode.probabilities.iter().map(f64::to_string).collect();
+        writeln!(
+            writer,
+            "{},{},{}",
+            mode.energy,
+            mode.edge_weight,
+            row.join(",")
+        )?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::systems::CoupledCavities;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_ssh_bands_and_winding() {
+        let lattice = CoupledCavities::ssh(5.0, 0.4, 1.0, 20).lattice().unwrap();
+        let cell = UnitCell::detect(&lattice).unwrap();
+        assert_eq!(cell, UnitCell::ssh(0.4, 1.0));
+
+        let bands = BandStructure::compute(&cell, 101).unwrap();
+        assert_eq!(bands.num_bands(), 2);
+        assert_relative_eq!(bands.gap(0), 2.0 * (1.0 - 0.4), epsilon = 1e-10);
+        assert_relative_eq!(bands.band(1)[50], 1.4, epsilon = 1e-10);
+
+        assert_eq!(winding_number(&cell, 64).unwrap(), 1);
+        assert_eq!(winding_number(&UnitCell::ssh(1.0, 0.4), 64).unwrap(), 0);
+        assert!(winding_number(&UnitCell::ssh(1.0, 1.0), 64).is_err());
+        assert!(
+            UnitCell::detect(&Lattice::chain(6, 1.0).unwrap()).is_ok_and(|cell| cell.sites() == 1)
+        );
+    }
+
+    #[test]
+    fn test_topological_chain_has_edge_modes() {
+        let edges = chain_edges(20, 2);
+        let topological = CoupledCavities::ssh(5.0, 0.4, 1.0, 20).lattice().unwrap();
+        let modes = edge_modes(&topological, &edges, 0.6).unwrap();
+        assert_eq!(modes.len(), 2);
+        for mode in &modes {
+            assert!(mode.energy.abs() < 1e-3);
+            assert_relative_eq!(mode.probabilities.iter().sum::<f64>(), 1.0, epsilon = 1e-10);
+        }
+
+        let trivial = CoupledCavities::ssh(5.0, 1.0, 0.4, 20).lattice().unwrap();
+        assert!(edge_modes(&trivial, &edges, 0.6).unwrap().is_empty());
+    }
+}
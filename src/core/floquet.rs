@@ -1,6 +1,8 @@
-use crate::core::Hamiltonian;
+use crate::core::lindblad::LindbladSolver;
+use crate::core::{DensityMatrix, Hamiltonian};
+use crate::utils::math::{complex_eigenvalues, eigenvector, hermitian_eigen, identity};
 use crate::utils::{Error, Result};
-use ndarray::Array2;
+use ndarray::{Array1, Array2};
 use num_complex::Complex64;
 
 pub struct FloquetSpectrum {
@@ -11,17 +13,59 @@ pub struct FloquetSpectrum {
     pub period: f64,
 }
 
+const EIGEN_MIXING: f64 = 0.754_877_666_246_692_7;
+
 impl FloquetSpectrum {
-    pub fn compute(hamiltonian: &dyn Hamiltonian, _period: f64, _num_steps: usize) -> Result<Self> {
+    pub fn compute(hamiltonian: &dyn Hamiltonian, period: f64, num_steps: usize) -> Result<Self> {
         if !hamiltonian.is_time_independent() && hamiltonian.period().is_none() {
             return Err(Error::InvalidParameter(
                 "Hamiltonian must be time-periodic for Floquet analysis".to_string(),
             ));
         }
+        if !(period > 0.0 && period.is_finite()) || num_steps == 0 {
+            return Err(Error::InvalidParameter(format!(
+                "Floquet analysis needs a positive period and step count, got T = {}, {} steps",
+                period, num_steps
+            )));
+        }
 
-        Err(Error::NotImplemented(
-            "FloquetSpectrum::compute".to_string(),
-        ))
+        Self::from_propagator(
+            &one_period_propagator(hamiltonian, period, num_steps),
+            period,
+        )
+    }
+
+    pub fn from_propagator(propagator: &Array2<Complex64>, period: f64) -> Result<Self> {
+        let dim = propagator.nrows();
+        let adjoint = propagator.t().mapv(|x| x.conj());
+        let cos_part = (propagator + &adjoint).mapv(|x| x * 0.5);
+        let sin_part = (propagator - &adjoint).mapv(|x| x * Complex64::new(0.0, -0.5));
+        let mixed = &cos_part + &sin_part.mapv(|x| x * EIGEN_MIXING);
+        let (_, vectors) = hermitian_eigen(&mixed.view());
+
+        let mut levels: Vec<(f64, usize)> = (0..dim)
+            .map(|k| {
+                let v = vectors.column(k);
+                let lambda: Complex64 = v
+                    .iter()
+                    .zip(propagator.dot(&v).iter())
+                    .map(|(a, b)| a.conj() * b)
+                    .sum();
+                (-lambda.arg() / period, k)
+            })
+            .collect();
+        if levels.iter().any(|(e, _)| !e.is_finite()) {
+            return Err(Error::Numerical(
+                "Floquet propagator has non-finite eigenvalues".to_string(),
+            ));
+        }
+        levels.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Ok(Self {
+            quasi_energies: levels.iter().map(|(e, _)| *e).collect(),
+            modes: Array2::from_shape_fn((dim, dim), |(row, col)| vectors[[row, levels[col].1]]),
+            period,
+        })
     }
 
     pub fn num_levels(&self) -> usize {
@@ -37,6 +81,214 @@ impl FloquetSpectrum {
     }
 }
 
+pub fn one_period_propagator(
+    hamiltonian: &dyn Hamiltonian,
+    period: f64,
+    num_steps: usize,
+) -> Array2<Complex64> {
+    let dt = period / num_steps as f64;
+    (0..num_steps).fold(identity(hamiltonian.dim()), |propagator, step| {
+        step_propagator(hamiltonian, step as f64 * dt, dt).dot(&propagator)
+    })
+}
+
+// One fourth-order Runge-Kutta step of the propagator over [t, t + dt].
+pub fn step_propagator(hamiltonian: &dyn Hamiltonian, t: f64, dt: f64) -> Array2<Complex64> {
+    let dim = hamiltonian.dim();
+    let mut h = Array2::zeros((dim, dim));
+    let mut generator = |t: f64| {
+        crate::core::integrator::compute_hamiltonian(hamiltonian, t, &mut h);
+        h.mapv(|x| x * Complex64::new(0.0, -1.0))
+    };
+
+    let a1 = generator(t);
+    let a2 = generator(t + dt / 2.0);
+    let a4 = generator(t + dt);
+
+    let id = identity(dim);
+    let k1 = a1;
+    let k2 = a2.dot(&(&id + &k1.mapv(|x| x * (dt / 2.0))));
+    let k3 = a2.dot(&(&id + &k2.mapv(|x| x * (dt / 2.0))));
+    let k4 = a4.dot(&(&id + &k3.mapv(|x| x * dt)));
+    let increment = &k1 + &k2.mapv(|x| x * 2.0) + &k3.mapv(|x| x * 2.0) + &k4;
+    &id + &increment.mapv(|x| x * (dt / 6.0))
+}
+
+pub fn high_frequency_hamiltonian(
+    hamiltonian: &dyn Hamiltonian,
+    period: f64,
+    samples: usize,
+) -> Result<Array2<Complex64>> {
+    if samples < 3 {
+        return Err(Error::InvalidParameter(format!(
+            "High-frequency expansion needs at least 3 samples per period, got {}",
+            samples
+        )));
+    }
+    if !(period > 0.0 && period.is_finite()) {
+        return Err(Error::InvalidParameter(format!(
+            "Drive period must be positive, got {}",
+            period
+        )));
+    }
+
+    let dim = hamiltonian.dim();
+    let omega = 2.0 * std::f64::consts::PI / period;
+    let mut h = Array2::zeros((dim, dim));
+    let snapshots: Vec<(f64, Array2<Complex64>)> = (0..samples)
+        .map(|k| {
+            let t = k as f64 * period / samples as f64;
+            crate::core::integrator::compute_hamiltonian(hamiltonian, t, &mut h);
+            (t, h.clone())
+        })
+        .collect();
+    let harmonic = |m: f64| {
+        let mut component = Array2::zeros((dim, dim));
+        for (t, snapshot) in &snapshots {
+            let phase = Complex64::from_polar(1.0 / samples as f64, -m * omega * t);
+            component.scaled_add(phase, snapshot);
+        }
+        component
+    };
+
+    let mut effective = harmonic(0.0);
+    for m in 1..=(samples - 1) / 2 {
+        let plus = harmonic(m as f64);
+        let minus = harmonic(-(m as f64));
+        let commutator = plus.dot(&minus) - minus.dot(&plus);
+        effective.scaled_add(Complex64::new(1.0 / (m as f64 * omega), 0.0), &commutator);
+    }
+    Ok(effective)
+}
+
+pub struct DissipativeFloquetMap {
+    pub eigenvalues: Vec<Complex64>,
+
+    pub eigenvectors: Vec<Array1<Complex64>>,
+
+    pub period: f64,
+}
+
+impl DissipativeFloquetMap {
+    pub fn compute(
+        solver: &LindbladSolver,
+        period: f64,
+        num_steps: usize,
+        count: usize,
+    ) -> Result<Self> {
+        if !(period > 0.0 && period.is_finite()) || num_steps == 0 {
+            return Err(Error::InvalidParameter(format!(
+                "Floquet map needs a positive period and step count, got T = {}, {} steps",
+                period, num_steps
+            )));
+        }
+        Self::from_map(
+            &one_period_liouvillian_map(solver, period, num_steps)?,
+            period,
+            count,
+        )
+    }
+
+    pub fn from_map(map: &Array2<Complex64>, period: f64, count: usize) -> Result<Self> {
+        let mut eigenvalues = complex_eigenvalues(&map.view())?;
+        if eigenvalues.iter().any(|e| !e.is_finite()) {
+            return Err(Error::Numerical(
+                "Floquet map has non-finite eigenvalues".to_string(),
+            ));
+        }
+        eigenvalues.sort_by(|a, b| b.norm().total_cmp(&a.norm()));
+        eigenvalues.truncate(count);
+
+        Ok(Self {
+            eigenvectors: eigenvalues
+                .iter()
+                .map(|&lambda| eigenvector(&map.view(), lambda))
+                .collect(),
+            eigenvalues,
+            period,
+        })
+    }
+
+    pub fn decay_rates(&self) -> Vec<f64> {
+        self.eigenvalues
+            .iter()
+            .map(|lambda| -lambda.norm().ln() / self.period)
+            .collect()
+    }
+}
+
+pub fn one_period_liouvillian_map(
+    solver: &LindbladSolver,
+    period: f64,
+    num_steps: usize,
+) -> Result<Array2<Complex64>> {
+    let dim = solver.dim();
+    let dt = period / num_steps as f64;
+    let mut map = Array2::zeros((dim * dim, dim * dim));
+
+    for (column, mut target) in map.columns_mut().into_iter().enumerate() {
+        let mut basis = Array2::zeros((dim, dim));
+        basis[[column / dim, column % dim]] = Complex64::new(1.0, 0.0);
+        let mut rho = DensityMatrix::new_unchecked(basis);
+        for step in 0..num_steps {
+            solver.step(&mut rho, step as f64 * dt, dt)?;
+        }
+        target.assign(&Array1::from_iter(rho.data().iter().copied()));
+    }
+    Ok(map)
+}
+
+pub fn track_eigenvalues(maps: &[DissipativeFloquetMap]) -> Vec<Vec<Complex64>> {
+    let Some(first) = maps.first() else {
+        return Vec::new();
+    };
+    let mut previous: Vec<&Array1<Complex64>> = first.eigenvectors.iter().collect();
+    let mut tracks = vec![first.eigenvalues.clone()];
+
+    for map in &maps[1..] {
+        let mut pairs: Vec<(f64, usize, usize)> = previous
+            .iter()
+            .enumerate()
+            .flat_map(|(branch, old)| {
+                map.eigenvectors
+                    .iter()
+                    .enumerate()
+                    .map(move |(k, new)| (overlap(old, new), branch, k))
+            })
+            .collect();
+        pairs.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut assigned: Vec<Option<usize>> = vec![None; previous.len()];
+        let mut taken = vec![false; map.eigenvalues.len()];
+        for (_, branch, k) in pairs {
+            if assigned[branch].is_none() && !taken[k] {
+                assigned[branch] = Some(k);
+                taken[k] = true;
+            }
+        }
+
+        let mut row = Vec::with_capacity(previous.len());
+        for (branch, slot) in assigned.into_iter().enumerate() {
+            match slot {
+                Some(k) => {
+                    row.push(map.eigenvalues[k]);
+                    previous[branch] = &map.eigenvectors[k];
+                }
+                None => row.push(Complex64::new(f64::NAN, f64::NAN)),
+            }
+        }
+        tracks.push(row);
+    }
+    tracks
+}
+
+fn overlap(a: &Array1<Complex64>, b: &Array1<Complex64>) -> f64 {
+    let dot: Complex64 = a.iter().zip(b.iter()).map(|(x, y)| x.conj() * y).sum();
+    let norms =
+        a.iter().map(|x| x.norm_sqr()).sum::<f64>() * b.iter().map(|x| x.norm_sqr()).sum::<f64>();
+    dot.norm() / norms.sqrt().max(f64::MIN_POSITIVE)
+}
+
 pub struct FloquetHamiltonian {
     pub n_fourier: usize,
 
@@ -68,6 +320,83 @@ impl FloquetHamiltonian {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_static_hamiltonian_quasi_energies() {
+        use crate::core::hamiltonian::TimeIndependentHamiltonian;
+
+        let mut h = Array2::zeros((2, 2));
+        h[[0, 0]] = Complex64::new(-0.5, 0.0);
+        h[[1, 1]] = Complex64::new(0.5, 0.0);
+        h[[0, 1]] = Complex64::new(0.3, 0.0);
+        h[[1, 0]] = Complex64::new(0.3, 0.0);
+        let spectrum =
+            FloquetSpectrum::compute(&TimeIndependentHamiltonian::new(h), 1.0, 200).unwrap();
+
+        let e = (0.25f64 + 0.09).sqrt();
+        assert_relative_eq!(spectrum.quasi_energies[0], -e, epsilon = 1e-8);
+        assert_relative_eq!(spectrum.level_spacing(0).unwrap(), 2.0 * e, epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_decaying_qubit_floquet_map() {
+        use crate::core::hamiltonian::TimeIndependentHamiltonian;
+        use crate::core::lindblad::LindbladOperator;
+
+        let (omega, gamma, period) = (2.0, 0.3, 1.0);
+        let mut h = Array2::zeros((2, 2));
+        h[[1, 1]] = Complex64::new(omega, 0.0);
+        let solver = LindbladSolver::new(
+            Box::new(TimeIndependentHamiltonian::new(h)),
+            vec![LindbladOperator::annihilation(2, gamma).unwrap()],
+        )
+        .unwrap();
+
+        let map = DissipativeFloquetMap::compute(&solver, period, 200, 4).unwrap();
+        assert_relative_eq!(map.eigenvalues[0].re, 1.0, epsilon = 1e-8);
+        assert_relative_eq!(
+            map.eigenvalues[1].norm(),
+            (-gamma / 2.0).exp(),
+            epsilon = 1e-8
+        );
+        assert_relative_eq!(map.eigenvalues[1].arg().abs(), omega, epsilon = 1e-8);
+        assert_relative_eq!(map.decay_rates()[3], gamma, epsilon = 1e-8);
+
+        let tracks = track_eigenvalues(&[
+            DissipativeFloquetMap::compute(&solver, period, 200, 4).unwrap(),
+            map,
+        ]);
+        assert_eq!(tracks.len(), 2);
+        for (a, b) in tracks[0].iter().zip(&tracks[1]) {
+            assert_relative_eq!((a - b).norm(), 0.0, epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_circular_drive_effective_hamiltonian() {
+        struct CircularDrive(f64);
+        impl Hamiltonian for CircularDrive {
+            fn dim(&self) -> usize {
+                2
+            }
+            fn compute(&self, t: f64, out: &mut Array2<Complex64>) {
+                let field = Complex64::from_polar(1.0, -self.0 * t);
+                out[[0, 0]] = Complex64::new(0.5, 0.0);
+                out[[1, 1]] = Complex64::new(-0.5, 0.0);
+                out[[0, 1]] = field;
+                out[[1, 0]] = field.conj();
+            }
+        }
+
+        let omega = 20.0;
+        let period = 2.0 * std::f64::consts::PI / omega;
+        let effective = high_frequency_hamiltonian(&CircularDrive(omega), period, 16).unwrap();
+        assert_relative_eq!(effective[[0, 0]].re, 0.5 - 1.0 / omega, epsilon = 1e-12);
+        assert_relative_eq!(effective[[1, 1]].re, -0.5 + 1.0 / omega, epsilon = 1e-12);
+        assert_relative_eq!(effective[[0, 1]].norm(), 0.0, epsilon = 1e-12);
+        assert!(high_frequency_hamiltonian(&CircularDrive(omega), period, 2).is_err());
+    }
 
     #[test]
     fn test_floquet_hamiltonian_creation() {
@@ -1,12 +1,39 @@
-use crate::utils::Result;
-use ndarray::Array2;
-use num_complex::Complex64;
+use crate::utils::{Error, Result, Tolerances};
+use ndarray::{Array1, Array2};
+use num_complex::{Complex32, Complex64};
+use std::sync::Arc;
+use std::time::Instant;
+
+const NORM_SAMPLES_PER_PERIOD: usize = 8;
+const DERIVATIVE_STEP: f64 = 1e-6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TermCost {
+    pub label: String,
+    pub time_dependent: bool,
+    pub seconds_per_eval: f64,
+    pub fraction: f64,
+}
 
 pub trait Hamiltonian: Send + Sync {
     fn dim(&self) -> usize;
 
     fn compute(&self, t: f64, out: &mut Array2<Complex64>);
 
+    fn apply(&self, t: f64, psi: &Array1<Complex64>, out: &mut Array1<Complex64>) {
+        let mut h = Array2::zeros((self.dim(), self.dim()));
+        self.compute(t, &mut h);
+        out.assign(&h.dot(psi));
+    }
+
+    // The f32 integrator fills its matrix through this. Built-in systems write it
+    // directly; the default narrows a full f64 evaluation.
+    fn compute_single(&self, t: f64, out: &mut Array2<Complex32>) {
+        let mut h = Array2::zeros((self.dim(), self.dim()));
+        self.compute(t, &mut h);
+        out.zip_mut_with(&h, |o, &x| *o = narrow(x));
+    }
+
     fn is_time_independent(&self) -> bool {
         false
     }
@@ -15,13 +42,102 @@ pub trait Hamiltonian: Send + Sync {
         None
     }
 
+    fn compute_dt(&self, t: f64, out: &mut Array2<Complex64>) {
+        if self.is_time_independent() {
+            out.fill(Complex64::new(0.0, 0.0));
+            return;
+        }
+        let step = DERIVATIVE_STEP * t.abs().max(1.0);
+        let mut lower = Array2::zeros(out.raw_dim());
+        self.compute(t + step, out);
+        self.compute(t - step, &mut lower);
+        central_difference(out, &lower, step);
+    }
+
+    fn parameter_names(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    fn parameter(&self, _name: &str) -> Option<f64> {
+        None
+    }
+
+    fn with_parameter(&self, name: &str, _value: f64) -> Result<Box<dyn Hamiltonian>> {
+        Err(Error::NotImplemented(format!(
+            "Changing parameter '{}' of {}",
+            name,
+            self.label()
+        )))
+    }
+
+    fn compute_derivative_param(
+        &self,
+        t: f64,
+        param: &str,
+        out: &mut Array2<Complex64>,
+    ) -> Result<()> {
+        let value = self.parameter(param).ok_or_else(|| {
+            Error::InvalidParameter(format!(
+                "{} has no parameter '{}', expected one of: {}",
+                self.label(),
+                param,
+                self.parameter_names().join(", ")
+            ))
+        })?;
+        let step = DERIVATIVE_STEP * value.abs().max(1.0);
+        let mut lower = Array2::zeros(out.raw_dim());
+        self.with_parameter(param, value + step)?.compute(t, out);
+        self.with_parameter(param, value - step)?
+            .compute(t, &mut lower);
+        central_difference(out, &lower, step);
+        Ok(())
+    }
+
+    fn spectral_norm_estimate(&self) -> f64 {
+        use crate::utils::math::spectral_norm;
+
+        let times: Vec<f64> = match self.period() {
+            Some(period) if !self.is_time_independent() => (0..NORM_SAMPLES_PER_PERIOD)
+                .map(|k| k as f64 * period / NORM_SAMPLES_PER_PERIOD as f64)
+                .collect(),
+            _ => vec![0.0],
+        };
+
+        let mut h = Array2::zeros((self.dim(), self.dim()));
+        times
+            .into_iter()
+            .map(|t| {
+                self.compute(t, &mut h);
+                spectral_norm(&h.view(), 100, 1e-6)
+            })
+            .fold(0.0, f64::max)
+    }
+
+    fn label(&self) -> String {
+        let name = std::any::type_name::<Self>();
+        name.rsplit("::").next().unwrap_or(name).to_string()
+    }
+
+    fn term_costs(&self, samples: usize) -> Vec<TermCost> {
+        vec![TermCost {
+            label: self.label(),
+            time_dependent: !self.is_time_independent(),
+            seconds_per_eval: time_evaluations(self, self.period(), samples),
+            fraction: 1.0,
+        }]
+    }
+
     fn validate(&self) -> Result<()> {
+        self.validate_with(&Tolerances::global())
+    }
+
+    fn validate_with(&self, tolerances: &Tolerances) -> Result<()> {
         use crate::utils::math::is_hermitian;
 
         let mut h = Array2::zeros((self.dim(), self.dim()));
         self.compute(0.0, &mut h);
 
-        if !is_hermitian(&h.view(), 1e-10) {
+        if !is_hermitian(&h.view(), tolerances.hermiticity) {
             return Err(crate::utils::Error::Hamiltonian(
                 "Hamiltonian is not Hermitian".to_string(),
             ));
@@ -31,6 +147,31 @@ pub trait Hamiltonian: Send + Sync {
     }
 }
 
+fn central_difference(upper: &mut Array2<Complex64>, lower: &Array2<Complex64>, step: f64) {
+    ndarray::Zip::from(upper)
+        .and(lower)
+        .for_each(|u, &l| *u = (*u - l) / (2.0 * step));
+}
+
+fn time_evaluations<H: Hamiltonian + ?Sized>(
+    hamiltonian: &H,
+    period: Option<f64>,
+    samples: usize,
+) -> f64 {
+    let samples = samples.max(1);
+    let span = period.unwrap_or(1.0);
+    let mut h = Array2::zeros((hamiltonian.dim(), hamiltonian.dim()));
+    let start = Instant::now();
+    for k in 0..samples {
+        hamiltonian.compute(k as f64 * span / samples as f64, &mut h);
+    }
+    start.elapsed().as_secs_f64() / samples as f64
+}
+
+pub(crate) fn narrow(x: Complex64) -> Complex32 {
+    Complex32::new(x.re as f32, x.im as f32)
+}
+
 pub struct TimeIndependentHamiltonian {
     matrix: Array2<Complex64>,
 }
@@ -50,11 +191,93 @@ impl Hamiltonian for TimeIndependentHamiltonian {
         out.assign(&self.matrix);
     }
 
+    fn apply(&self, _t: f64, psi: &Array1<Complex64>, out: &mut Array1<Complex64>) {
+        ndarray::linalg::general_mat_vec_mul(
+            Complex64::new(1.0, 0.0),
+            &self.matrix,
+            psi,
+            Complex64::new(0.0, 0.0),
+            out,
+        );
+    }
+
+    fn compute_single(&self, _t: f64, out: &mut Array2<Complex32>) {
+        out.zip_mut_with(&self.matrix, |o, &x| *o = narrow(x));
+    }
+
     fn is_time_independent(&self) -> bool {
         true
     }
 }
 
+// Lets one Hamiltonian drive both the runner and an open-system solver.
+impl<H: Hamiltonian + ?Sized> Hamiltonian for Arc<H> {
+    fn dim(&self) -> usize {
+        (**self).dim()
+    }
+
+    fn compute(&self, t: f64, out: &mut Array2<Complex64>) {
+        (**self).compute(t, out)
+    }
+
+    fn apply(&self, t: f64, psi: &Array1<Complex64>, out: &mut Array1<Complex64>) {
+        (**self).apply(t, psi, out)
+    }
+
+    fn compute_single(&self, t: f64, out: &mut Array2<Complex32>) {
+        (**self).compute_single(t, out)
+    }
+
+    fn is_time_independent(&self) -> bool {
+        (**self).is_time_independent()
+    }
+
+    fn period(&self) -> Option<f64> {
+        (**self).period()
+    }
+
+    fn compute_dt(&self, t: f64, out: &mut Array2<Complex64>) {
+        (**self).compute_dt(t, out)
+    }
+
+    fn parameter_names(&self) -> Vec<&'static str> {
+        (**self).parameter_names()
+    }
+
+    fn parameter(&self, name: &str) -> Option<f64> {
+        (**self).parameter(name)
+    }
+
+    fn with_parameter(&self, name: &str, value: f64) -> Result<Box<dyn Hamiltonian>> {
+        (**self).with_parameter(name, value)
+    }
+
+    fn compute_derivative_param(
+        &self,
+        t: f64,
+        param: &str,
+        out: &mut Array2<Complex64>,
+    ) -> Result<()> {
+        (**self).compute_derivative_param(t, param, out)
+    }
+
+    fn spectral_norm_estimate(&self) -> f64 {
+        (**self).spectral_norm_estimate()
+    }
+
+    fn label(&self) -> String {
+        (**self).label()
+    }
+
+    fn term_costs(&self, samples: usize) -> Vec<TermCost> {
+        (**self).term_costs(samples)
+    }
+
+    fn validate_with(&self, tolerances: &Tolerances) -> Result<()> {
+        (**self).validate_with(tolerances)
+    }
+}
+
 pub struct CompositeHamiltonian {
     terms: Vec<Box<dyn Hamiltonian>>,
     dim: usize,
@@ -96,6 +319,67 @@ impl Hamiltonian for CompositeHamiltonian {
             *out += &temp;
         }
     }
+
+    fn apply(&self, t: f64, psi: &Array1<Complex64>, out: &mut Array1<Complex64>) {
+        out.fill(Complex64::new(0.0, 0.0));
+
+        let mut temp = Array1::zeros(self.dim);
+        for term in &self.terms {
+            term.apply(t, psi, &mut temp);
+            *out += &temp;
+        }
+    }
+
+    fn compute_single(&self, t: f64, out: &mut Array2<Complex32>) {
+        out.fill(Complex32::new(0.0, 0.0));
+
+        let mut temp = Array2::zeros((self.dim, self.dim));
+        for term in &self.terms {
+            term.compute_single(t, &mut temp);
+            *out += &temp;
+        }
+    }
+
+    fn is_time_independent(&self) -> bool {
+        self.terms.iter().all(|term| term.is_time_independent())
+    }
+
+    fn period(&self) -> Option<f64> {
+        self.terms.iter().find_map(|term| term.period())
+    }
+
+    fn compute_dt(&self, t: f64, out: &mut Array2<Complex64>) {
+        out.fill(Complex64::new(0.0, 0.0));
+
+        let mut temp = Array2::zeros((self.dim, self.dim));
+        for term in self.terms.iter().filter(|term| !term.is_time_independent()) {
+            term.compute_dt(t, &mut temp);
+            *out += &temp;
+        }
+    }
+
+    fn term_costs(&self, samples: usize) -> Vec<TermCost> {
+        let period = self.period();
+        let mut costs: Vec<TermCost> = self
+            .terms
+            .iter()
+            .map(|term| TermCost {
+                label: term.label(),
+                time_dependent: !term.is_time_independent(),
+                seconds_per_eval: time_evaluations(term.as_ref(), period, samples),
+                fraction: 0.0,
+            })
+            .collect();
+        let total: f64 = costs.iter().map(|c| c.seconds_per_eval).sum();
+        for cost in &mut costs {
+            cost.fraction = if total > 0.0 {
+                cost.seconds_per_eval / total
+            } else {
+                1.0 / self.terms.len() as f64
+            };
+        }
+        costs
+    }
 }
 
 #[cfg(test)]
@@ -118,4 +402,115 @@ mod tests {
         assert_relative_eq!(out[[0, 0]].re, 1.0);
         assert_relative_eq!(out[[1, 1]].re, -1.0);
     }
+
+    #[test]
+    fn test_shared_hamiltonian_forwards_its_f32_matrix() {
+        // Writes a different matrix in f32, so the narrowed default would be caught.
+        struct Marked;
+        impl Hamiltonian for Marked {
+            fn dim(&self) -> usize {
+                1
+            }
+            fn compute(&self, _t: f64, out: &mut Array2<Complex64>) {
+                out.fill(Complex64::new(1.0, 0.0));
+            }
+            fn compute_single(&self, _t: f64, out: &mut Array2<Complex32>) {
+                out.fill(Complex32::new(2.0, 0.0));
+            }
+        }
+
+        let shared: Arc<dyn Hamiltonian> = Arc::new(Marked);
+        let mut out = Array2::zeros((1, 1));
+        shared.compute_single(0.0, &mut out);
+        assert_eq!(out[[0, 0]], Complex32::new(2.0, 0.0));
+    }
+
+    #[test]
+    fn test_composite_term_costs() {
+        let dim = 64;
+        let composite = CompositeHamiltonian::new(vec![
+            Box::new(TimeIndependentHamiltonian::new(Array2::zeros((dim, dim)))),
+            Box::new(crate::core::systems::DrivenCavity::new(1.0, 2.0, 0.1, dim)),
+        ])
+        .unwrap();
+        assert!(!composite.is_time_independent());
+
+        let costs = composite.term_costs(8);
+        assert_eq!(costs.len(), 2);
+        assert_eq!(costs[0].label, "TimeIndependentHamiltonian");
+        assert_eq!(costs[1].label, "DrivenCavity");
+        assert!(!costs[0].time_dependent && costs[1].time_dependent);
+        assert_relative_eq!(
+            costs.iter().map(|c| c.fraction).sum::<f64>(),
+            1.0,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_finite_difference_derivatives_match_analytic() {
+        use crate::core::systems::DrivenTLS;
+
+        struct Numeric(DrivenTLS);
+        impl Hamiltonian for Numeric {
+            fn dim(&self) -> usize {
+                2
+            }
+            fn compute(&self, t: f64, out: &mut Array2<Complex64>) {
+                self.0.compute(t, out)
+            }
+            fn parameter(&self, name: &str) -> Option<f64> {
+                self.0.parameter(name)
+            }
+            fn with_parameter(&self, name: &str, value: f64) -> Result<Box<dyn Hamiltonian>> {
+                let mut tls = DrivenTLS::with_phase(
+                    self.0.omega_0,
+                    self.0.omega_d,
+                    self.0.rabi_freq,
+                    self.0.phase,
+                );
+                *tls.parameter_mut(name).unwrap() = value;
+                Ok(Box::new(Numeric(tls)))
+            }
+        }
+
+        let tls = DrivenTLS::with_phase(5.0, 4.0, 0.7, 0.3);
+        let numeric = Numeric(DrivenTLS::with_phase(5.0, 4.0, 0.7, 0.3));
+        let (mut exact, mut approx) = (Array2::zeros((2, 2)), Array2::zeros((2, 2)));
+        tls.compute_dt(1.3, &mut exact);
+        numeric.compute_dt(1.3, &mut approx);
+        assert_relative_eq!(
+            (&exact - &approx).mapv(|x| x.norm()).sum(),
+            0.0,
+            epsilon = 1e-7
+        );
+
+        for param in tls.parameter_names() {
+            tls.compute_derivative_param(1.3, param, &mut exact)
+                .unwrap();
+            numeric
+                .compute_derivative_param(1.3, param, &mut approx)
+                .unwrap();
+            assert_relative_eq!(
+                (&exact - &approx).mapv(|x| x.norm()).sum(),
+                0.0,
+                epsilon = 1e-7
+            );
+        }
+        assert!(tls.compute_derivative_param(0.0, "g", &mut exact).is_err());
+
+        let constant = TimeIndependentHamiltonian::new(Array2::eye(2));
+        constant.compute_dt(0.0, &mut exact);
+        assert_relative_eq!(exact.mapv(|x| x.norm()).sum(), 0.0);
+    }
+
+    #[test]
+    fn test_spectral_norm_estimate() {
+        let tls = crate::core::systems::DrivenTLS::new(5.0, 5.0, 0.5);
+        assert_relative_eq!(
+            tls.spectral_norm_estimate(),
+            (2.5f64.powi(2) + 0.5f64.powi(2)).sqrt(),
+            epsilon = 1e-5
+        );
+    }
 }
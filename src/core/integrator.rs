@@ -1,15 +1,109 @@
+use crate::core::hamiltonian::narrow;
 use crate::core::{Hamiltonian, QuantumState};
-use crate::utils::Result;
-use ndarray::Array2;
-use num_complex::Complex64;
+use crate::utils::math::SpectralDecomposition;
+use crate::utils::{Error, Result};
+use ndarray::{Array1, Array2};
+use num_complex::{Complex32, Complex64};
+use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Clone, Copy)]
 pub enum IntegratorType {
     RK4,
 
-    Magnus2,
+    AdaptiveRK45 { atol: f64, rtol: f64 },
 
-    Magnus4,
+    Exact,
+
+    Krylov { dim: usize, tol: f64 },
+}
+
+impl IntegratorType {
+    pub const DEFAULT_ATOL: f64 = 1e-8;
+    pub const DEFAULT_RTOL: f64 = 1e-6;
+    pub const DEFAULT_KRYLOV_DIM: usize = 30;
+    pub const DEFAULT_KRYLOV_TOL: f64 = 1e-10;
+
+    pub fn parse(name: &str, atol: f64, rtol: f64) -> Result<Self> {
+        match name {
+            "rk4" => Ok(Self::RK4),
+            "magnus2" | "magnus4" => Err(Error::NotImplemented(format!(
+                "The {} integrator, use rk4 until Magnus schemes are available",
+                name
+            ))),
+            "exact" => Ok(Self::Exact),
+            "krylov" | "lanczos" => {
+                Self::krylov(Self::DEFAULT_KRYLOV_DIM, Self::DEFAULT_KRYLOV_TOL)
+            }
+            "rk45" | "dopri5" => {
+                if !(atol > 0.0 && atol.is_finite() && rtol >= 0.0 && rtol.is_finite()) {
+                    return Err(Error::InvalidParameter(format!(
+                        "Adaptive integrator needs atol > 0 and rtol >= 0, got atol = {} and \
+                         rtol = {}",
+                        atol, rtol
+                    )));
+                }
+                Ok(Self::AdaptiveRK45 { atol, rtol })
+            }
+            other => Err(Error::Config(format!(
+                "Unknown integrator '{}', expected rk4, rk45, exact or krylov",
+                other
+            ))),
+        }
+    }
+
+    pub fn krylov(dim: usize, tol: f64) -> Result<Self> {
+        if dim < 2 || !(tol > 0.0 && tol.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "Krylov integrator needs a subspace dimension >= 2 and tol > 0, got {} and {}",
+                dim, tol
+            )));
+        }
+        Ok(Self::Krylov { dim, tol })
+    }
+
+    pub fn is_adaptive(&self) -> bool {
+        matches!(self, Self::AdaptiveRK45 { .. })
+    }
+
+    pub fn is_exact(&self) -> bool {
+        matches!(self, Self::Exact)
+    }
+
+    pub fn is_krylov(&self) -> bool {
+        matches!(self, Self::Krylov { .. })
+    }
+
+    pub fn has_single_precision(&self) -> bool {
+        matches!(self, Self::RK4)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    #[default]
+    Double,
+
+    Single,
+}
+
+impl Precision {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "f64" | "double" => Ok(Self::Double),
+            "f32" | "single" => Ok(Self::Single),
+            other => Err(Error::Config(format!(
+                "Unknown precision '{}', expected f64 or f32",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Double => "f64",
+            Self::Single => "f32",
+        }
+    }
 }
 
 pub trait Integrator: Send + Sync {
@@ -22,6 +116,37 @@ pub trait Integrator: Send + Sync {
     ) -> Result<()>;
 
     fn integrator_type(&self) -> IntegratorType;
+
+    fn precision(&self) -> Precision {
+        Precision::Double
+    }
+
+    // Integrators that cache work for a time-independent Hamiltonian key it by the
+    // Hamiltonian's address. Callers clear it before stepping a Hamiltonian that may
+    // live where an earlier one did.
+    fn clear_cache(&self) {}
+
+    fn step_adaptive(
+        &self,
+        hamiltonian: &dyn Hamiltonian,
+        state: &mut QuantumState,
+        t: f64,
+        dt: f64,
+    ) -> Result<AdaptiveStep> {
+        self.step(hamiltonian, state, t, dt)?;
+        Ok(AdaptiveStep {
+            taken: dt,
+            next: dt,
+            rejected: 0,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveStep {
+    pub taken: f64,
+    pub next: f64,
+    pub rejected: usize,
 }
 
 pub struct RK4Integrator;
@@ -46,46 +171,540 @@ impl Integrator for RK4Integrator {
         t: f64,
         dt: f64,
     ) -> Result<()> {
+        let _span = tracing::trace_span!("integrator_step").entered();
         let dim = hamiltonian.dim();
         let mut h = Array2::zeros((dim, dim));
 
-        hamiltonian.compute(t, &mut h);
+        compute_hamiltonian(hamiltonian, t, &mut h);
         let k1 = apply_hamiltonian(&h, state);
 
         let mut temp_state = state.clone();
         add_scaled_to_state(&mut temp_state, &k1, dt / 2.0);
-        hamiltonian.compute(t + dt / 2.0, &mut h);
+        compute_hamiltonian(hamiltonian, t + dt / 2.0, &mut h);
         let k2 = apply_hamiltonian(&h, &temp_state);
 
         let mut temp_state = state.clone();
         add_scaled_to_state(&mut temp_state, &k2, dt / 2.0);
-        hamiltonian.compute(t + dt / 2.0, &mut h);
+        compute_hamiltonian(hamiltonian, t + dt / 2.0, &mut h);
         let k3 = apply_hamiltonian(&h, &temp_state);
 
         let mut temp_state = state.clone();
         add_scaled_to_state(&mut temp_state, &k3, dt);
-        hamiltonian.compute(t + dt, &mut h);
+        compute_hamiltonian(hamiltonian, t + dt, &mut h);
         let k4 = apply_hamiltonian(&h, &temp_state);
 
-        let data = state.data().to_owned();
         let factor = Complex64::new(dt / 6.0, 0.0);
         let increment = &k1 + &k2.mapv(|x| x * 2.0) + &k3.mapv(|x| x * 2.0) + &k4;
-        let new_data = &data + &increment.mapv(|x| x * factor);
+        for (psi, delta) in state.as_mut_slice().iter_mut().zip(increment.iter()) {
+            *psi += delta * factor;
+        }
+        state.normalize();
+
+        Ok(())
+    }
+
+    fn integrator_type(&self) -> IntegratorType {
+        IntegratorType::RK4
+    }
+}
+
+// Everything the f32 step touches stays in f32 between steps: the state, the
+// stage vectors and one Hamiltonian matrix. The runner's f64 state is only
+// narrowed in and widened out once per step.
+struct SingleWorkspace {
+    hamiltonian: Option<usize>,
+    h: Array2<Complex32>,
+    psi: Array1<Complex32>,
+    stage: Array1<Complex32>,
+    k: Array1<Complex32>,
+    sum: Array1<Complex32>,
+}
+
+impl SingleWorkspace {
+    fn new(dim: usize) -> Self {
+        Self {
+            hamiltonian: None,
+            h: Array2::zeros((dim, dim)),
+            psi: Array1::zeros(dim),
+            stage: Array1::zeros(dim),
+            k: Array1::zeros(dim),
+            sum: Array1::zeros(dim),
+        }
+    }
+
+    // k = -i H(t) stage, with H(t) refilled unless it is time-independent and cached.
+    fn derivative(&mut self, hamiltonian: &dyn Hamiltonian, key: usize, t: f64) {
+        if !(hamiltonian.is_time_independent() && self.hamiltonian == Some(key)) {
+            let _span = tracing::trace_span!("hamiltonian_compute").entered();
+            hamiltonian.compute_single(t, &mut self.h);
+            self.hamiltonian = hamiltonian.is_time_independent().then_some(key);
+        }
+        ndarray::linalg::general_mat_vec_mul(
+            Complex32::new(0.0, -1.0),
+            &self.h,
+            &self.stage,
+            Complex32::new(0.0, 0.0),
+            &mut self.k,
+        );
+    }
+
+    // stage = psi + scale * k, and sum += weight * k
+    fn accumulate(&mut self, weight: f32, scale: f32) {
+        self.sum.scaled_add(Complex32::new(weight, 0.0), &self.k);
+        ndarray::Zip::from(&mut self.stage)
+            .and(&self.psi)
+            .and(&self.k)
+            .for_each(|s, &p, &k| *s = p + k * scale);
+    }
+}
+
+#[derive(Default)]
+pub struct RK4SingleIntegrator {
+    workspace: Mutex<Option<SingleWorkspace>>,
+}
 
-        let norm: f64 = new_data.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
-        let normalized = new_data.mapv(|x| x / norm);
+impl RK4SingleIntegrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Integrator for RK4SingleIntegrator {
+    fn step(
+        &self,
+        hamiltonian: &dyn Hamiltonian,
+        state: &mut QuantumState,
+        t: f64,
+        dt: f64,
+    ) -> Result<()> {
+        let _span = tracing::trace_span!("integrator_step_f32").entered();
+        let dim = hamiltonian.dim();
+        let key = hamiltonian as *const dyn Hamiltonian as *const () as usize;
+        let mut guard = self.workspace.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.as_ref().is_none_or(|w| w.psi.len() != dim) {
+            *guard = Some(SingleWorkspace::new(dim));
+        }
+        let w = guard.as_mut().expect("workspace allocated above");
+
+        for (p, &x) in w.psi.iter_mut().zip(state.as_slice()) {
+            *p = narrow(x);
+        }
+        w.stage.assign(&w.psi);
+        w.sum.fill(Complex32::new(0.0, 0.0));
 
-        *state = QuantumState::new(normalized)?;
+        let dt32 = dt as f32;
+        w.derivative(hamiltonian, key, t);
+        w.accumulate(1.0, dt32 / 2.0);
+        w.derivative(hamiltonian, key, t + dt / 2.0);
+        w.accumulate(2.0, dt32 / 2.0);
+        w.derivative(hamiltonian, key, t + dt / 2.0);
+        w.accumulate(2.0, dt32);
+        w.derivative(hamiltonian, key, t + dt);
+        w.sum += &w.k;
 
+        w.psi.scaled_add(Complex32::new(dt32 / 6.0, 0.0), &w.sum);
+        let norm = w.psi.iter().map(|x| x.norm_sqr()).sum::<f32>().sqrt();
+        for (out, &p) in state.as_mut_slice().iter_mut().zip(&w.psi) {
+            *out = Complex64::new((p.re / norm) as f64, (p.im / norm) as f64);
+        }
         Ok(())
     }
 
     fn integrator_type(&self) -> IntegratorType {
         IntegratorType::RK4
     }
+
+    fn clear_cache(&self) {
+        if let Some(w) = self
+            .workspace
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_mut()
+        {
+            w.hamiltonian = None;
+        }
+    }
+
+    fn precision(&self) -> Precision {
+        Precision::Single
+    }
+}
+
+const DOPRI_C: [f64; 7] = [0.0, 1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+const DOPRI_A: [[f64; 6]; 7] = [
+    [0.0; 6],
+    [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+    [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+    [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+    [
+        19372.0 / 6561.0,
+        -25360.0 / 2187.0,
+        64448.0 / 6561.0,
+        -212.0 / 729.0,
+        0.0,
+        0.0,
+    ],
+    [
+        9017.0 / 3168.0,
+        -355.0 / 33.0,
+        46732.0 / 5247.0,
+        49.0 / 176.0,
+        -5103.0 / 18656.0,
+        0.0,
+    ],
+    [
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+    ],
+];
+const DOPRI_ERROR: [f64; 7] = [
+    71.0 / 57600.0,
+    0.0,
+    -71.0 / 16695.0,
+    71.0 / 1920.0,
+    -17253.0 / 339200.0,
+    22.0 / 525.0,
+    -1.0 / 40.0,
+];
+const STEP_SAFETY: f64 = 0.9;
+const MIN_STEP_FACTOR: f64 = 0.2;
+const MAX_STEP_FACTOR: f64 = 5.0;
+
+pub struct DormandPrinceIntegrator {
+    atol: f64,
+    rtol: f64,
+}
+
+impl DormandPrinceIntegrator {
+    pub fn new(atol: f64, rtol: f64) -> Self {
+        Self { atol, rtol }
+    }
+
+    fn attempt(
+        &self,
+        hamiltonian: &dyn Hamiltonian,
+        psi: &Array1<Complex64>,
+        t: f64,
+        dt: f64,
+    ) -> (Array1<Complex64>, f64) {
+        let dim = hamiltonian.dim();
+        let mut h = Array2::zeros((dim, dim));
+        let constant = hamiltonian.is_time_independent();
+        if constant {
+            compute_hamiltonian(hamiltonian, t, &mut h);
+        }
+        let mut derivative = |time: f64, y: &Array1<Complex64>| {
+            if !constant {
+                compute_hamiltonian(hamiltonian, time, &mut h);
+            }
+            h.dot(y).mapv(|x| Complex64::new(x.im, -x.re))
+        };
+
+        let mut k: Vec<Array1<Complex64>> = Vec::with_capacity(7);
+        let mut y = psi.clone();
+        for (stage, (c, a)) in DOPRI_C.iter().zip(&DOPRI_A).enumerate() {
+            y.assign(psi);
+            for (kj, &aj) in k.iter().zip(a).take(stage) {
+                y.scaled_add(Complex64::new(aj * dt, 0.0), kj);
+            }
+            k.push(derivative(t + c * dt, &y));
+        }
+
+        let mut error = Array1::<Complex64>::zeros(psi.len());
+        for (kj, &ej) in k.iter().zip(&DOPRI_ERROR) {
+            error.scaled_add(Complex64::new(ej * dt, 0.0), kj);
+        }
+        let norm = error
+            .iter()
+            .zip(psi.iter().zip(y.iter()))
+            .map(|(e, (old, new))| e.norm() / (self.atol + self.rtol * old.norm().max(new.norm())))
+            .fold(0.0, f64::max);
+        (y, norm)
+    }
+}
+
+impl Integrator for DormandPrinceIntegrator {
+    fn step(
+        &self,
+        hamiltonian: &dyn Hamiltonian,
+        state: &mut QuantumState,
+        t: f64,
+        dt: f64,
+    ) -> Result<()> {
+        let (y, _) = self.attempt(hamiltonian, &state.data().to_owned(), t, dt);
+        state.as_mut_slice().copy_from_slice(&y.to_vec());
+        state.normalize();
+        Ok(())
+    }
+
+    fn integrator_type(&self) -> IntegratorType {
+        IntegratorType::AdaptiveRK45 {
+            atol: self.atol,
+            rtol: self.rtol,
+        }
+    }
+
+    fn step_adaptive(
+        &self,
+        hamiltonian: &dyn Hamiltonian,
+        state: &mut QuantumState,
+        t: f64,
+        dt: f64,
+    ) -> Result<AdaptiveStep> {
+        let _span = tracing::trace_span!("integrator_step_adaptive").entered();
+        let psi = state.data().to_owned();
+        let mut dt = dt;
+        let mut rejected = 0;
+        loop {
+            let (y, error) = self.attempt(hamiltonian, &psi, t, dt);
+            let error = if error.is_finite() {
+                error
+            } else {
+                f64::INFINITY
+            };
+            let factor = if error == 0.0 {
+                MAX_STEP_FACTOR
+            } else {
+                (STEP_SAFETY * error.powf(-0.2)).clamp(MIN_STEP_FACTOR, MAX_STEP_FACTOR)
+            };
+            if error <= 1.0 {
+                state.as_mut_slice().copy_from_slice(&y.to_vec());
+                state.normalize();
+                return Ok(AdaptiveStep {
+                    taken: dt,
+                    next: dt * factor,
+                    rejected,
+                });
+            }
+            rejected += 1;
+            dt *= factor;
+            if dt <= 16.0 * f64::EPSILON * t.abs().max(1.0) {
+                return Err(Error::Numerical(format!(
+                    "Adaptive step size underflow at t = {} after {} rejected steps",
+                    t, rejected
+                )));
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ExactIntegrator {
+    cached: Mutex<Option<(usize, Arc<SpectralDecomposition>)>>,
+}
+
+impl ExactIntegrator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn decomposition(&self, hamiltonian: &dyn Hamiltonian, t: f64) -> Arc<SpectralDecomposition> {
+        let dim = hamiltonian.dim();
+        let key = hamiltonian as *const dyn Hamiltonian as *const () as usize;
+        let mut cached = self.cached.lock().unwrap_or_else(|e| e.into_inner());
+        match cached.as_ref() {
+            Some((k, spectral)) if *k == key && spectral.dim() == dim => spectral.clone(),
+            _ => {
+                let _span = tracing::debug_span!("exact_diagonalize", dim).entered();
+                let mut h = Array2::zeros((dim, dim));
+                compute_hamiltonian(hamiltonian, t, &mut h);
+                let spectral = Arc::new(SpectralDecomposition::new(&h.view()));
+                *cached = Some((key, spectral.clone()));
+                spectral
+            }
+        }
+    }
+}
+
+impl Integrator for ExactIntegrator {
+    fn step(
+        &self,
+        hamiltonian: &dyn Hamiltonian,
+        state: &mut QuantumState,
+        t: f64,
+        dt: f64,
+    ) -> Result<()> {
+        if !hamiltonian.is_time_independent() {
+            return Err(Error::InvalidParameter(
+                "The exact integrator needs a time-independent Hamiltonian, use rk4 or rk45 \
+                 for time-dependent systems"
+                    .to_string(),
+            ));
+        }
+        let _span = tracing::trace_span!("integrator_step_exact").entered();
+        let psi = self
+            .decomposition(hamiltonian, t)
+            .evolve(&state.data().to_owned(), dt);
+        state.as_mut_slice().copy_from_slice(&psi.to_vec());
+        state.normalize();
+        Ok(())
+    }
+
+    fn integrator_type(&self) -> IntegratorType {
+        IntegratorType::Exact
+    }
+
+    fn clear_cache(&self) {
+        *self.cached.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+}
+
+pub struct KrylovIntegrator {
+    dim: usize,
+    tol: f64,
+}
+
+struct KrylovSpace {
+    basis: Vec<Array1<Complex64>>,
+    spectral: SpectralDecomposition,
+    residual: f64,
+}
+
+impl KrylovSpace {
+    fn lanczos(hamiltonian: &dyn Hamiltonian, t: f64, psi: &Array1<Complex64>, dim: usize) -> Self {
+        let mut basis = vec![psi.clone()];
+        let (mut alpha, mut beta) = (Vec::new(), Vec::new());
+        let mut w = Array1::zeros(psi.len());
+        let mut residual = 0.0;
+        for j in 0..dim.min(psi.len()) {
+            hamiltonian.apply(t, &basis[j], &mut w);
+            alpha.push(vdot(&basis[j], &w).re);
+            // Reorthogonalize twice, a single Gram-Schmidt pass loses orthogonality on
+            // long chains.
+            for _ in 0..2 {
+                for v in &basis {
+                    let overlap = vdot(v, &w);
+                    w.scaled_add(-overlap, v);
+                }
+            }
+            residual = w.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+            let scale = alpha
+                .iter()
+                .chain(&beta)
+                .fold(1.0f64, |m, x| m.max(x.abs()));
+            if residual <= 1e-14 * scale || j + 1 == dim {
+                break;
+            }
+            beta.push(residual);
+            basis.push(w.mapv(|x| x / residual));
+        }
+
+        let k = alpha.len();
+        let tridiagonal = Array2::from_shape_fn((k, k), |(i, j)| match (i, j) {
+            _ if i == j => Complex64::new(alpha[i], 0.0),
+            _ if i == j + 1 => Complex64::new(beta[j], 0.0),
+            _ if j == i + 1 => Complex64::new(beta[i], 0.0),
+            _ => Complex64::new(0.0, 0.0),
+        });
+        basis.truncate(k);
+        let residual = if k == dim { residual } else { 0.0 };
+        Self {
+            basis,
+            spectral: SpectralDecomposition::new(&tridiagonal.view()),
+            residual,
+        }
+    }
+
+    fn propagate(&self, tau: f64) -> (Array1<Complex64>, f64) {
+        let mut e1 = Array1::zeros(self.basis.len());
+        e1[0] = Complex64::new(1.0, 0.0);
+        let coefficients = self.spectral.evolve(&e1, tau);
+        let error = self.residual * coefficients[coefficients.len() - 1].norm();
+        let mut psi = Array1::zeros(self.basis[0].len());
+        for (c, v) in coefficients.iter().zip(&self.basis) {
+            psi.scaled_add(*c, v);
+        }
+        (psi, error)
+    }
+}
+
+impl KrylovIntegrator {
+    pub fn new(dim: usize, tol: f64) -> Self {
+        Self { dim, tol }
+    }
+}
+
+impl Integrator for KrylovIntegrator {
+    // Lanczos on H at the substep midpoint, which is exact for time-independent H and
+    // second order otherwise. Substeps are halved until the Krylov error estimate
+    // beta_m |[exp(-iT tau) e1]_m| is below tol.
+    fn step(
+        &self,
+        hamiltonian: &dyn Hamiltonian,
+        state: &mut QuantumState,
+        t: f64,
+        dt: f64,
+    ) -> Result<()> {
+        let _span = tracing::trace_span!("integrator_step_krylov").entered();
+        let constant = hamiltonian.is_time_independent();
+        let norm = state
+            .data()
+            .iter()
+            .map(|x| x.norm_sqr())
+            .sum::<f64>()
+            .sqrt();
+        let mut psi = state.data().mapv(|x| x / norm);
+        let (mut elapsed, mut tau) = (0.0, dt);
+        while (dt - elapsed).abs() > 1e-12 * dt.abs() {
+            tau = if tau.abs() < (dt - elapsed).abs() {
+                tau
+            } else {
+                dt - elapsed
+            };
+            let mut space =
+                KrylovSpace::lanczos(hamiltonian, t + elapsed + tau / 2.0, &psi, self.dim);
+            loop {
+                let (next, error) = space.propagate(tau);
+                if error <= self.tol {
+                    psi = next;
+                    elapsed += tau;
+                    break;
+                }
+                tau /= 2.0;
+                if tau.abs() <= 16.0 * f64::EPSILON * (t.abs() + dt.abs()).max(1.0) {
+                    return Err(Error::Numerical(format!(
+                        "Krylov substep underflow at t = {}, increase the subspace dimension",
+                        t + elapsed
+                    )));
+                }
+                if !constant {
+                    space =
+                        KrylovSpace::lanczos(hamiltonian, t + elapsed + tau / 2.0, &psi, self.dim);
+                }
+            }
+        }
+        state.as_mut_slice().copy_from_slice(&psi.to_vec());
+        state.normalize();
+        Ok(())
+    }
+
+    fn integrator_type(&self) -> IntegratorType {
+        IntegratorType::Krylov {
+            dim: self.dim,
+            tol: self.tol,
+        }
+    }
+}
+
+fn vdot(a: &Array1<Complex64>, b: &Array1<Complex64>) -> Complex64 {
+    a.iter().zip(b).map(|(x, y)| x.conj() * y).sum()
+}
+
+pub(crate) fn compute_hamiltonian(
+    hamiltonian: &dyn Hamiltonian,
+    t: f64,
+    out: &mut Array2<Complex64>,
+) {
+    let _span = tracing::trace_span!("hamiltonian_compute").entered();
+    hamiltonian.compute(t, out);
 }
 
 fn apply_hamiltonian(h: &Array2<Complex64>, state: &QuantumState) -> ndarray::Array1<Complex64> {
+    let _span = tracing::trace_span!("derivative").entered();
     let dim = h.nrows();
     let psi = state.data();
     let mut result = ndarray::Array1::zeros(dim);
@@ -104,19 +723,33 @@ fn apply_hamiltonian(h: &Array2<Complex64>, state: &QuantumState) -> ndarray::Ar
 }
 
 fn add_scaled_to_state(state: &mut QuantumState, delta: &ndarray::Array1<Complex64>, scale: f64) {
-    let scaled_delta = delta.mapv(|x| x * Complex64::new(scale, 0.0));
-    let mut data = state.data().to_owned() + &scaled_delta;
-
-    let norm: f64 = data.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
-    data.mapv_inplace(|x| x / norm);
+    for (psi, d) in state.as_mut_slice().iter_mut().zip(delta.iter()) {
+        *psi += d * scale;
+    }
+    state.normalize();
+}
 
-    *state = QuantumState::new(data).unwrap();
+pub fn create_integrator_with_precision(
+    integrator_type: IntegratorType,
+    precision: Precision,
+) -> Result<Box<dyn Integrator>> {
+    match precision {
+        Precision::Single if !integrator_type.has_single_precision() => Err(Error::Config(
+            format!("The {:?} integrator runs in f64 only", integrator_type),
+        )),
+        Precision::Double => Ok(create_integrator(integrator_type)),
+        Precision::Single => Ok(Box::new(RK4SingleIntegrator::new())),
+    }
 }
 
 pub fn create_integrator(integrator_type: IntegratorType) -> Box<dyn Integrator> {
     match integrator_type {
         IntegratorType::RK4 => Box::new(RK4Integrator::new()),
-        IntegratorType::Magnus2 | IntegratorType::Magnus4 => Box::new(RK4Integrator::new()),
+        IntegratorType::AdaptiveRK45 { atol, rtol } => {
+            Box::new(DormandPrinceIntegrator::new(atol, rtol))
+        }
+        IntegratorType::Exact => Box::new(ExactIntegrator::new()),
+        IntegratorType::Krylov { dim, tol } => Box::new(KrylovIntegrator::new(dim, tol)),
     }
 }
 
@@ -143,4 +776,194 @@ mod tests {
         let norm_sq: f64 = state.data().iter().map(|x| x.norm_sqr()).sum();
         assert_relative_eq!(norm_sq, 1.0, epsilon = 1e-10);
     }
+
+    #[test]
+    fn test_single_precision_tracks_double_precision() {
+        use crate::core::systems::DrivenTLS;
+
+        for hamiltonian in [
+            Box::new(DrivenTLS::new(1.0, 1.0, 0.5)) as Box<dyn Hamiltonian>,
+            Box::new(TimeIndependentHamiltonian::new(Array2::from_elem(
+                (2, 2),
+                Complex64::new(0.5, 0.0),
+            ))),
+        ] {
+            let single =
+                create_integrator_with_precision(IntegratorType::RK4, Precision::Single).unwrap();
+            let double = create_integrator(IntegratorType::RK4);
+            let mut psi_single = QuantumState::ground_state(2);
+            let mut psi_double = QuantumState::ground_state(2);
+            for step in 0..500 {
+                let t = step as f64 * 0.01;
+                single
+                    .step(hamiltonian.as_ref(), &mut psi_single, t, 0.01)
+                    .unwrap();
+                double
+                    .step(hamiltonian.as_ref(), &mut psi_double, t, 0.01)
+                    .unwrap();
+            }
+            for (a, b) in psi_single.as_slice().iter().zip(psi_double.as_slice()) {
+                assert_relative_eq!((a - b).norm(), 0.0, epsilon = 1e-4);
+            }
+        }
+        let single =
+            create_integrator_with_precision(IntegratorType::RK4, Precision::Single).unwrap();
+        assert_eq!(single.precision(), Precision::Single);
+        assert!(matches!(
+            create_integrator_with_precision(IntegratorType::Exact, Precision::Single),
+            Err(Error::Config(_))
+        ));
+        assert_eq!(
+            create_integrator(IntegratorType::RK4).precision(),
+            Precision::Double
+        );
+
+        use crate::core::systems::DrivenCavity;
+        let cavity = DrivenCavity::new(1.0, 2.0, 0.3, 6);
+        let mut h = Array2::zeros((6, 6));
+        let mut h_single = Array2::zeros((6, 6));
+        cavity.compute(0.4, &mut h);
+        cavity.compute_single(0.4, &mut h_single);
+        for (a, b) in h.iter().zip(h_single.iter()) {
+            assert_eq!(narrow(*a), *b);
+        }
+        assert_eq!(Precision::parse("f32").unwrap(), Precision::Single);
+        assert!(Precision::parse("f16").is_err());
+    }
+
+    #[test]
+    fn test_exact_integrator_takes_arbitrarily_long_steps() {
+        use crate::core::systems::DrivenTLS;
+
+        let mut h = Array2::zeros((2, 2));
+        h[[0, 1]] = Complex64::new(1.0, 0.0);
+        h[[1, 0]] = Complex64::new(1.0, 0.0);
+        let hamiltonian = TimeIndependentHamiltonian::new(h);
+        let integrator = create_integrator(IntegratorType::parse("exact", 0.0, 0.0).unwrap());
+
+        let mut state = QuantumState::ground_state(2);
+        integrator
+            .step(&hamiltonian, &mut state, 0.0, 40.0)
+            .unwrap();
+        integrator
+            .step(&hamiltonian, &mut state, 40.0, 0.5)
+            .unwrap();
+        let t: f64 = 40.5;
+        let exact = [Complex64::new(t.cos(), 0.0), Complex64::new(0.0, -t.sin())];
+        for (a, b) in state.as_slice().iter().zip(exact) {
+            assert_relative_eq!((a - b).norm(), 0.0, epsilon = 1e-12);
+        }
+
+        let err = integrator
+            .step(&DrivenTLS::new(1.0, 1.0, 0.5), &mut state, 0.0, 0.1)
+            .unwrap_err();
+        assert!(err.to_string().contains("time-independent"), "{}", err);
+    }
+
+    #[test]
+    fn test_cleared_cache_sees_a_new_hamiltonian_at_the_same_address() {
+        let coupling = |x: f64| {
+            let mut h = Array2::zeros((2, 2));
+            h[[0, 1]] = Complex64::new(x, 0.0);
+            h[[1, 0]] = Complex64::new(x, 0.0);
+            TimeIndependentHamiltonian::new(h)
+        };
+        for integrator in [
+            create_integrator(IntegratorType::Exact),
+            create_integrator_with_precision(IntegratorType::RK4, Precision::Single).unwrap(),
+        ] {
+            let mut hamiltonian = coupling(1.0);
+            let mut state = QuantumState::ground_state(2);
+            integrator.step(&hamiltonian, &mut state, 0.0, 0.1).unwrap();
+
+            // Reassigning keeps the address, so only clearing tells the caches apart.
+            hamiltonian = coupling(0.0);
+            integrator.clear_cache();
+            let before = state.clone();
+            integrator.step(&hamiltonian, &mut state, 0.1, 0.1).unwrap();
+            for (a, b) in state.as_slice().iter().zip(before.as_slice()) {
+                assert_relative_eq!((a - b).norm(), 0.0, epsilon = 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_krylov_matches_exact_and_reverses_on_long_chain() {
+        use crate::core::lattice::Lattice;
+        use crate::core::systems::DrivenTLS;
+
+        let h = Array2::from_shape_fn((40, 40), |(i, j)| {
+            let (a, b) = (i.min(j) as f64, i.max(j) as f64);
+            let phase = (j as f64 - i as f64).clamp(-1.0, 1.0);
+            Complex64::new((a + 2.0 * b).sin(), phase * (a * b).cos() / 3.0)
+        });
+        let hamiltonian = TimeIndependentHamiltonian::new(h);
+        let mut krylov = QuantumState::ground_state(40);
+        let mut exact = krylov.clone();
+        create_integrator(IntegratorType::krylov(12, 1e-12).unwrap())
+            .step(&hamiltonian, &mut krylov, 0.0, 3.0)
+            .unwrap();
+        ExactIntegrator::new()
+            .step(&hamiltonian, &mut exact, 0.0, 3.0)
+            .unwrap();
+        for (a, b) in krylov.as_slice().iter().zip(exact.as_slice()) {
+            assert_relative_eq!((a - b).norm(), 0.0, epsilon = 1e-9);
+        }
+
+        let chain = Lattice::chain(1000, 1.0).unwrap().hamiltonian(5.0);
+        let integrator = KrylovIntegrator::new(30, 1e-10);
+        let mut state = QuantumState::ground_state(1001);
+        state.as_mut_slice().fill(Complex64::new(0.0, 0.0));
+        state.as_mut_slice()[500] = Complex64::new(1.0, 0.0);
+        integrator.step(&chain, &mut state, 0.0, 20.0).unwrap();
+        assert!(state.as_slice()[500].norm() < 0.5);
+        integrator.step(&chain, &mut state, 20.0, -20.0).unwrap();
+        assert_relative_eq!(state.as_slice()[500].norm(), 1.0, epsilon = 1e-8);
+
+        let drive = DrivenTLS::new(1.0, 1.0, 0.5);
+        let mut krylov = QuantumState::ground_state(2);
+        let mut rk4 = krylov.clone();
+        let krylov_integrator = IntegratorType::parse("krylov", 0.0, 0.0).unwrap();
+        let krylov_integrator = create_integrator(krylov_integrator);
+        for k in 0..500 {
+            let t = k as f64 * 0.01;
+            krylov_integrator
+                .step(&drive, &mut krylov, t, 0.01)
+                .unwrap();
+            RK4Integrator::new()
+                .step(&drive, &mut rk4, t, 0.01)
+                .unwrap();
+        }
+        for (a, b) in krylov.as_slice().iter().zip(rk4.as_slice()) {
+            assert_relative_eq!((a - b).norm(), 0.0, epsilon = 1e-4);
+        }
+        assert!(IntegratorType::krylov(1, 1e-10).is_err());
+    }
+
+    #[test]
+    fn test_dormand_prince_meets_tolerance_on_rabi_oscillation() {
+        let mut h = Array2::zeros((2, 2));
+        h[[0, 1]] = Complex64::new(1.0, 0.0);
+        h[[1, 0]] = Complex64::new(1.0, 0.0);
+        let hamiltonian = TimeIndependentHamiltonian::new(h);
+        let integrator = create_integrator(IntegratorType::parse("rk45", 1e-10, 1e-8).unwrap());
+
+        let mut state = QuantumState::ground_state(2);
+        let (mut t, mut dt, mut steps) = (0.0f64, 1.0f64, 0);
+        while t < 10.0 {
+            let outcome = integrator
+                .step_adaptive(&hamiltonian, &mut state, t, dt.min(10.0 - t))
+                .unwrap();
+            t += outcome.taken;
+            dt = outcome.next;
+            steps += 1;
+        }
+        let exact = [Complex64::new(t.cos(), 0.0), Complex64::new(0.0, -t.sin())];
+        for (a, b) in state.as_slice().iter().zip(exact) {
+            assert_relative_eq!((a - b).norm(), 0.0, epsilon = 1e-6);
+        }
+        assert!(steps < 200, "took {} steps", steps);
+        assert!(IntegratorType::parse("rk45", 0.0, 1e-6).is_err());
+        assert!(IntegratorType::parse("euler", 1e-8, 1e-6).is_err());
+    }
 }
@@ -0,0 +1,505 @@
+use crate::core::observables::{Concurrence, Localization, SiteCorrelations};
+use crate::core::Hamiltonian;
+use crate::utils::performance::resolve_seed;
+use crate::utils::{Error, Result};
+use ndarray::{Array1, Array2};
+use num_complex::Complex64;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::StandardNormal;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bond {
+    pub sites: [usize; 2],
+    pub coupling: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lattice {
+    num_sites: usize,
+    bonds: Vec<Bond>,
+    onsite: Vec<f64>,
+    positions: Vec<[f64; 2]>,
+}
+
+impl Lattice {
+    pub fn new(num_sites: usize) -> Result<Self> {
+        if num_sites == 0 {
+            return Err(Error::InvalidParameter(
+                "Lattice needs at least one site".to_string(),
+            ));
+        }
+        Ok(Self {
+            num_sites,
+            bonds: Vec::new(),
+            onsite: vec![0.0; num_sites],
+            positions: (0..num_sites).map(|i| [i as f64, 0.0]).collect(),
+        })
+    }
+
+    pub fn chain(num_sites: usize, coupling: f64) -> Result<Self> {
+        let mut lattice = Self::new(num_sites)?;
+        for i in 1..num_sites {
+            lattice.set_bond(i - 1, i, coupling)?;
+        }
+        Ok(lattice)
+    }
+
+    pub fn ring(num_sites: usize, coupling: f64) -> Result<Self> {
+        let mut lattice = Self::chain(num_sites, coupling)?;
+        if num_sites > 2 {
+            lattice.set_bond(num_sites - 1, 0, coupling)?;
+        }
+        Ok(lattice)
+    }
+
+    pub fn square(width: usize, height: usize, periodic: bool, coupling: f64) -> Result<Self> {
+        let mut lattice = Self::new(width * height)?;
+        let site = |x: usize, y: usize| y * width + x;
+        for (i, position) in lattice.positions.iter_mut().enumerate() {
+            *position = [(i % width) as f64, (i / width) as f64];
+        }
+        for y in 0..height {
+            for x in 0..width {
+                if x + 1 < width {
+                    lattice.set_bond(site(x, y), site(x + 1, y), coupling)?;
+                } else if periodic && width > 2 {
+                    lattice.set_bond(site(x, y), site(0, y), coupling)?;
+                }
+                if y + 1 < height {
+                    lattice.set_bond(site(x, y), site(x, y + 1), coupling)?;
+                } else if periodic && height > 2 {
+                    lattice.set_bond(site(x, y), site(x, 0), coupling)?;
+                }
+            }
+        }
+        Ok(lattice)
+    }
+
+    pub fn set_bond(&mut self, a: usize, b: usize, coupling: f64) -> Result<()> {
+        if a >= self.num_sites || b >= self.num_sites || a == b {
+            return Err(Error::InvalidParameter(format!(
+                "Bond ({}, {}) is invalid for a lattice of {} sites",
+                a, b, self.num_sites
+            )));
+        }
+        let sites = [a.min(b), a.max(b)];
+        match self.bonds.iter_mut().find(|bond| bond.sites == sites) {
+            Some(bond) => bond.coupling = coupling,
+            None => self.bonds.push(Bond { sites, coupling }),
+        }
+        Ok(())
+    }
+
+    pub fn set_onsite(&mut self, site: usize, detuning: f64) -> Result<()> {
+        if site >= self.num_sites {
+            return Err(Error::InvalidParameter(format!(
+                "Site {} is invalid for a lattice of {} sites",
+                site, self.num_sites
+            )));
+        }
+        self.onsite[site] = detuning;
+        Ok(())
+    }
+
+    pub fn num_sites(&self) -> usize {
+        self.num_sites
+    }
+
+    pub fn bonds(&self) -> &[Bond] {
+        &self.bonds
+    }
+
+    pub fn onsite(&self) -> &[f64] {
+        &self.onsite
+    }
+
+    pub fn positions(&self) -> &[[f64; 2]] {
+        &self.positions
+    }
+
+    pub fn localization(&self) -> Localization {
+        Localization::new(
+            self.num_sites + 1,
+            (1..=self.num_sites).collect(),
+            self.positions.clone(),
+        )
+        .expect("lattice sites fit the single-excitation basis")
+    }
+
+    pub fn correlations(&self) -> SiteCorrelations {
+        SiteCorrelations::chain(self.num_sites + 1).expect("lattice has at least one site")
+    }
+
+    pub fn concurrence(&self) -> Concurrence {
+        Concurrence::chain(self.num_sites + 1).expect("lattice has at least one site")
+    }
+
+    pub fn hamiltonian(&self, omega_c: f64) -> LatticeHamiltonian {
+        let dim = self.num_sites + 1;
+        let mut matrix = Array2::zeros((dim, dim));
+        for (i, detuning) in self.onsite.iter().enumerate() {
+            matrix[[i + 1, i + 1]] = Complex64::new(omega_c + detuning, 0.0);
+        }
+        for bond in &self.bonds {
+            let [a, b] = bond.sites.map(|s| s + 1);
+            matrix[[a, b]] += Complex64::new(bond.coupling, 0.0);
+            matrix[[b, a]] += Complex64::new(bond.coupling, 0.0);
+        }
+        let entries = matrix
+            .indexed_iter()
+            .filter(|(_, value)| value.norm() > 0.0)
+            .map(|((i, j), value)| (i, j, *value))
+            .collect();
+        LatticeHamiltonian { matrix, entries }
+    }
+}
+
+pub struct LatticeHamiltonian {
+    matrix: Array2<Complex64>,
+    entries: Vec<(usize, usize, Complex64)>,
+}
+
+impl LatticeHamiltonian {
+    pub fn matrix(&self) -> &Array2<Complex64> {
+        &self.matrix
+    }
+}
+
+impl Hamiltonian for LatticeHamiltonian {
+    fn dim(&self) -> usize {
+        self.matrix.nrows()
+    }
+
+    fn compute(&self, _t: f64, out: &mut Array2<Complex64>) {
+        out.assign(&self.matrix);
+    }
+
+    fn compute_single(&self, _t: f64, out: &mut Array2<num_complex::Complex32>) {
+        out.zip_mut_with(&self.matrix, |o, &x| {
+            *o = crate::core::hamiltonian::narrow(x)
+        });
+    }
+
+    fn apply(&self, _t: f64, psi: &Array1<Complex64>, out: &mut Array1<Complex64>) {
+        out.fill(Complex64::new(0.0, 0.0));
+        for &(i, j, value) in &self.entries {
+            out[i] += value * psi[j];
+        }
+    }
+
+    fn is_time_independent(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BondConfig {
+    pub sites: [usize; 2],
+    pub j: f64,
+}
+
+fn default_distribution() -> String {
+    "uniform".to_string()
+}
+
+fn default_realizations() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DisorderConfig {
+    #[serde(default = "default_distribution")]
+    pub distribution: String,
+    #[serde(default)]
+    pub onsite: f64,
+    #[serde(default)]
+    pub coupling: f64,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default = "default_realizations")]
+    pub realizations: usize,
+}
+
+impl Default for DisorderConfig {
+    fn default() -> Self {
+        Self {
+            distribution: default_distribution(),
+            onsite: 0.0,
+            coupling: 0.0,
+            seed: None,
+            realizations: default_realizations(),
+        }
+    }
+}
+
+impl DisorderConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !matches!(self.distribution.as_str(), "uniform" | "gaussian") {
+            return Err(Error::Config(format!(
+                "Unknown disorder distribution '{}', expected uniform or gaussian",
+                self.distribution
+            )));
+        }
+        if !(self.onsite >= 0.0 && self.coupling >= 0.0) {
+            return Err(Error::Config(format!(
+                "Disorder strengths must be non-negative, got onsite = {} and coupling = {}",
+                self.onsite, self.coupling
+            )));
+        }
+        if self.realizations == 0 {
+            return Err(Error::Config(
+                "Disorder needs at least one realization".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn realization(&self, clean: &Lattice, index: usize) -> Result<Lattice> {
+        self.validate()?;
+        let mut rng = match resolve_seed(self.seed) {
+            Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(index as u64)),
+            None => StdRng::from_os_rng(),
+        };
+        let gaussian = self.distribution == "gaussian";
+        let mut sample = |strength: f64| {
+            if gaussian {
+                strength * rng.sample::<f64, _>(StandardNormal)
+            } else {
+                strength * (rng.random::<f64>() - 0.5)
+            }
+        };
+
+        let mut lattice = clean.clone();
+        for detuning in &mut lattice.onsite {
+            *detuning += sample(self.onsite);
+        }
+        for bond in &mut lattice.bonds {
+            bond.coupling += sample(self.coupling);
+        }
+        Ok(lattice)
+    }
+
+    pub fn realizations(&self, clean: &Lattice) -> Result<Vec<Lattice>> {
+        (0..self.realizations)
+            .map(|k| self.realization(clean, k))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LatticeConfig {
+    pub shape: String,
+    #[serde(default)]
+    pub sites: usize,
+    #[serde(default)]
+    pub width: usize,
+    #[serde(default)]
+    pub height: usize,
+    #[serde(default)]
+    pub periodic: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bonds: Vec<BondConfig>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub onsite: Vec<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disorder: Option<DisorderConfig>,
+}
+
+impl LatticeConfig {
+    pub fn build(&self, coupling: f64) -> Result<Lattice> {
+        let mut lattice = match self.shape.as_str() {
+            "chain" if self.periodic => Lattice::ring(self.sites, coupling)?,
+            "chain" => Lattice::chain(self.sites, coupling)?,
+            "ring" => Lattice::ring(self.sites, coupling)?,
+            "square" => Lattice::square(self.width, self.height, self.periodic, coupling)?,
+            "custom" => Lattice::new(self.sites)?,
+            other => {
+                return Err(Error::Config(format!(
+                    "Unknown lattice shape '{}', expected chain, ring, square or custom",
+                    other
+                )))
+            }
+        };
+        for bond in &self.bonds {
+            lattice.set_bond(bond.sites[0], bond.sites[1], bond.j)?;
+        }
+        if !self.onsite.is_empty() {
+            if self.onsite.len() != lattice.num_sites() {
+                return Err(Error::dimension_mismatch(
+                    lattice.num_sites(),
+                    self.onsite.len(),
+                ));
+            }
+            for (site, &detuning) in self.onsite.iter().enumerate() {
+                lattice.set_onsite(site, detuning)?;
+            }
+        }
+        Ok(lattice)
+    }
+
+    pub fn realizations(&self, coupling: f64) -> Result<Vec<Lattice>> {
+        let clean = self.build(coupling)?;
+        match &self.disorder {
+            Some(disorder) => disorder.realizations(&clean),
+            None => Ok(vec![clean]),
+        }
+    }
+
+    pub fn num_sites(&self) -> usize {
+        match self.shape.as_str() {
+            "square" => self.width * self.height,
+            _ => self.sites,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::math::hermitian_eigen;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_ring_spectrum() {
+        let n = 6;
+        let h = Lattice::ring(n, 0.5).unwrap().hamiltonian(5.0);
+        assert_eq!(h.dim(), n + 1);
+
+        let (values, _) = hermitian_eigen(&h.matrix().view());
+        let mut expected: Vec<f64> = (0..n)
+            .map(|k| 5.0 + 2.0 * 0.5 * (2.0 * std::f64::consts::PI * k as f64 / n as f64).cos())
+            .chain([0.0])
+            .collect();
+        expected.sort_by(f64::total_cmp);
+        for (value, expected) in values.iter().zip(&expected) {
+            assert_relative_eq!(value, expected, epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_config_builds_square_lattice_with_bond_overrides() {
+        let config: LatticeConfig = toml::from_str(
+            r#"
+            shape = "square"
+            width = 3
+            height = 3
+            periodic = true
+            bonds = [{ sites = [4, 0], j = 0.1 }]
+            "#,
+        )
+        .unwrap();
+        let lattice = config.build(1.0).unwrap();
+        assert_eq!(lattice.num_sites(), config.num_sites());
+        assert_eq!(lattice.bonds().len(), 19);
+        assert!(lattice
+            .bonds()
+            .iter()
+            .all(|b| b.sites.iter().all(|&s| s < 9)));
+
+        let open = Lattice::square(3, 3, false, 1.0).unwrap();
+        assert_eq!(open.bonds().len(), 12);
+        assert!(LatticeConfig {
+            shape: "hexagonal".to_string(),
+            ..config
+        }
+        .build(1.0)
+        .is_err());
+    }
+
+    #[test]
+    fn test_localization_tracks_spreading_on_square_lattice() {
+        use crate::core::QuantumState;
+        use crate::simulation::SimulationBuilder;
+
+        let lattice = Lattice::square(5, 5, false, 1.0).unwrap();
+        let center = 12;
+        assert_eq!(lattice.positions()[center], [2.0, 2.0]);
+
+        let results = SimulationBuilder::new()
+            .hamiltonian(lattice.hamiltonian(0.0))
+            .initial_state(QuantumState::fock(26, center + 1).unwrap())
+            .duration(1.0)
+            .timestep(0.01)
+            .vector_observable("loc", lattice.localization())
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+        let loc = results.vector_series("loc").unwrap();
+        let (first, last) = (loc.row(0), loc.row(loc.len() - 1));
+        assert_relative_eq!(first[0].re, 1.0, epsilon = 1e-12);
+        assert!(last[0].re < 0.5);
+        assert!(last[1].re > 0.5);
+        assert_relative_eq!(last[2].re, 2.0, epsilon = 1e-8);
+        assert_relative_eq!(last[3].re, 2.0, epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_correlations_spread_inside_light_cone() {
+        use crate::core::QuantumState;
+        use crate::simulation::SimulationBuilder;
+
+        let n = 21;
+        let lattice = Lattice::chain(n, 1.0).unwrap();
+        let results = SimulationBuilder::new()
+            .hamiltonian(lattice.hamiltonian(0.0))
+            .initial_state(QuantumState::fock(n + 1, n / 2 + 1).unwrap())
+            .duration(2.0)
+            .timestep(0.01)
+            .vector_observable("corr", lattice.correlations())
+            .vector_observable("conc", lattice.concurrence())
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+
+        let series = results.vector_series("corr").unwrap();
+        let corr = series.to_array3().unwrap();
+        assert_eq!(corr.shape(), [series.len(), n, n]);
+        let last = corr.shape()[0] - 1;
+        let center = n / 2;
+        assert!(corr[[last, center, center + 2]].norm() > 0.05);
+        assert!(corr[[last, center, n - 1]].norm() < 1e-4);
+
+        let conc = results.vector_series("conc").unwrap().to_array3().unwrap();
+        assert_relative_eq!(
+            conc[[last, center, center + 1]].re,
+            2.0 * corr[[last, center, center + 1]].norm(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_seeded_disorder_is_reproducible() {
+        let clean = Lattice::chain(5, 1.0).unwrap();
+        let disorder = DisorderConfig {
+            distribution: "gaussian".to_string(),
+            onsite: 0.5,
+            coupling: 0.1,
+            seed: Some(3),
+            realizations: 4,
+        };
+        let first = disorder.realizations(&clean).unwrap();
+        assert_eq!(first, disorder.realizations(&clean).unwrap());
+        assert_ne!(first[0], first[1]);
+        assert!(first[0].onsite().iter().all(|&d| d != 0.0));
+
+        let h = first[2].hamiltonian(5.0);
+        assert_relative_eq!(h.matrix()[[1, 1]].re, 5.0 + first[2].onsite()[0]);
+        assert_relative_eq!(h.matrix()[[1, 2]].re, first[2].bonds()[0].coupling);
+
+        let none = DisorderConfig {
+            seed: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(none.realization(&clean, 0).unwrap(), clean);
+        assert!(DisorderConfig {
+            distribution: "cauchy".to_string(),
+            ..disorder
+        }
+        .realization(&clean, 0)
+        .is_err());
+    }
+}
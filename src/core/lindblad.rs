@@ -1,16 +1,22 @@
 use crate::core::{DensityMatrix, Hamiltonian};
+use crate::utils::math::{identity, kron};
 use crate::utils::Result;
 use ndarray::Array2;
 use num_complex::Complex64;
+use std::sync::Arc;
 
 pub struct LindbladOperator {
-    pub operator: Array2<Complex64>,
+    pub operator: Arc<Array2<Complex64>>,
 
     pub rate: f64,
 }
 
 impl LindbladOperator {
     pub fn new(operator: Array2<Complex64>, rate: f64) -> Result<Self> {
+        Self::shared(Arc::new(operator), rate)
+    }
+
+    pub fn shared(operator: Arc<Array2<Complex64>>, rate: f64) -> Result<Self> {
         if rate < 0.0 {
             return Err(crate::utils::Error::InvalidParameter(
                 "Lindblad rate must be non-negative".to_string(),
@@ -65,11 +71,16 @@ impl LindbladSolver {
         })
     }
 
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
     pub fn compute_derivative(&self, rho: &DensityMatrix, t: f64) -> Result<Array2<Complex64>> {
+        let _span = tracing::trace_span!("lindblad_derivative").entered();
         let mut drho_dt;
 
         let mut h = Array2::zeros((self.dim, self.dim));
-        self.hamiltonian.compute(t, &mut h);
+        crate::core::integrator::compute_hamiltonian(self.hamiltonian.as_ref(), t, &mut h);
 
         let i = Complex64::new(0.0, 1.0);
         let rho_data = rho.data();
@@ -154,7 +165,27 @@ impl LindbladSolver {
         Ok(drho_dt)
     }
 
+    // Superoperator acting on the row-major vec(ρ), where vec(AρB) = (A ⊗ Bᵀ) vec(ρ).
+    pub fn liouvillian(&self, t: f64) -> Result<Array2<Complex64>> {
+        let dim = self.dim;
+        let mut h = Array2::zeros((dim, dim));
+        crate::core::integrator::compute_hamiltonian(self.hamiltonian.as_ref(), t, &mut h);
+
+        let id = identity(dim);
+        let minus_i = Complex64::new(0.0, -1.0);
+        let mut liouvillian = (kron(&h, &id) - kron(&id, &h.t().to_owned())).mapv(|x| x * minus_i);
+        for lindblad_op in &self.lindblad_ops {
+            let l = lindblad_op.operator.as_ref();
+            let ldag_l = l.t().mapv(|x| x.conj()).dot(l);
+            let dissipator = kron(l, &l.mapv(|x| x.conj()))
+                - (kron(&ldag_l, &id) + kron(&id, &ldag_l.t().to_owned())).mapv(|x| x * 0.5);
+            liouvillian.scaled_add(Complex64::new(lindblad_op.rate, 0.0), &dissipator);
+        }
+        Ok(liouvillian)
+    }
+
     pub fn step(&self, rho: &mut DensityMatrix, t: f64, dt: f64) -> Result<()> {
+        let _span = tracing::trace_span!("lindblad_step").entered();
         let k1 = self.compute_derivative(rho, t)?;
 
         let rho2_data = rho.data().clone() + &k1.mapv(|x| x * Complex64::new(dt / 2.0, 0.0));
@@ -199,4 +230,34 @@ mod tests {
         let solver = LindbladSolver::new(Box::new(ham), lindblad_ops);
         assert!(solver.is_ok());
     }
+
+    #[test]
+    fn test_liouvillian_matches_derivative() {
+        let mut h = Array2::zeros((3, 3));
+        h[[0, 1]] = Complex64::new(0.3, 0.2);
+        h[[1, 0]] = Complex64::new(0.3, -0.2);
+        h[[2, 2]] = Complex64::new(1.1, 0.0);
+        let solver = LindbladSolver::new(
+            Box::new(TimeIndependentHamiltonian::new(h)),
+            vec![
+                LindbladOperator::annihilation(3, 0.05).unwrap(),
+                LindbladOperator::dephasing(3, 0.02).unwrap(),
+            ],
+        )
+        .unwrap();
+
+        let rho = Array2::from_shape_fn((3, 3), |(i, j)| {
+            Complex64::new(0.1 * (i + j) as f64, 0.05 * i as f64 - 0.03 * j as f64)
+        });
+        let expected = solver
+            .compute_derivative(&DensityMatrix::new_unchecked(rho.clone()), 0.0)
+            .unwrap();
+        let vectorized = solver
+            .liouvillian(0.0)
+            .unwrap()
+            .dot(&ndarray::Array1::from_iter(rho.iter().copied()));
+        for (a, b) in vectorized.iter().zip(expected.iter()) {
+            assert!((a - b).norm() < 1e-12);
+        }
+    }
 }
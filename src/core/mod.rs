@@ -1,12 +1,21 @@
+pub mod bands;
 pub mod floquet;
 pub mod hamiltonian;
 pub mod integrator;
+pub mod lattice;
 pub mod lindblad;
+pub mod observable_expr;
 pub mod observables;
+pub mod operator_cache;
+pub mod quasiperiodic;
 pub mod state;
+pub mod symmetry;
 pub mod systems;
+pub mod wigner;
 
 pub use hamiltonian::Hamiltonian;
-pub use integrator::{Integrator, IntegratorType};
-pub use observables::{ExpectationValue, Observable};
-pub use state::{DensityMatrix, QuantumState};
+pub use integrator::{
+    AdaptiveStep, DormandPrinceIntegrator, ExactIntegrator, Integrator, IntegratorType, Precision,
+};
+pub use observables::{ExpectationValue, Observable, VectorObservable};
+pub use state::{DensityMatrix, DensityMatrixViewMut, QuantumState, StateViewMut};
@@ -0,0 +1,460 @@
+use crate::core::observables::{
+    BlochVector, Concurrence, Localization, MatrixObservable, PhotonNumberDistribution,
+    SiteCorrelations, VectorObservable,
+};
+use crate::utils::math::{hermitian_eigen, identity, is_hermitian};
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+
+pub fn named_operator(name: &str, dim: usize) -> Result<Array2<Complex64>> {
+    let mut parts = name.split(':');
+    let base = parts.next().unwrap_or_default();
+    let indices = parts
+        .map(|p| {
+            p.parse::<usize>()
+                .map_err(|_| Error::Config(format!("Invalid index '{}' in operator '{}'", p, name)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let check = |idx: usize| {
+        if idx >= dim {
+            Err(Error::InvalidParameter(format!(
+                "Level {} out of bounds for dimension {}",
+                idx, dim
+            )))
+        } else {
+            Ok(idx)
+        }
+    };
+
+    let mut m = Array2::zeros((dim, dim));
+    match (base, indices.as_slice()) {
+        ("population", []) => m[[0, 0]] = Complex64::new(1.0, 0.0),
+        ("population", &[k]) => m[[check(k)?, k]] = Complex64::new(1.0, 0.0),
+        ("coherence", &[i, j]) => m[[check(i)?, check(j)?]] = Complex64::new(1.0, 0.0),
+        ("identity" | "id", []) => m = identity(dim),
+        ("number" | "n", []) => {
+            for k in 0..dim {
+                m[[k, k]] = Complex64::new(k as f64, 0.0);
+            }
+        }
+        ("a", []) => {
+            for k in 1..dim {
+                m[[k - 1, k]] = Complex64::new((k as f64).sqrt(), 0.0);
+            }
+        }
+        ("adag", []) => {
+            for k in 1..dim {
+                m[[k, k - 1]] = Complex64::new((k as f64).sqrt(), 0.0);
+            }
+        }
+        ("sigma_x" | "sigma_y" | "sigma_z", []) => {
+            if dim < 2 {
+                return Err(Error::dimension_mismatch(2, dim));
+            }
+            match base {
+                "sigma_x" => {
+                    m[[0, 1]] = Complex64::new(1.0, 0.0);
+                    m[[1, 0]] = Complex64::new(1.0, 0.0);
+                }
+                "sigma_y" => {
+                    m[[0, 1]] = Complex64::new(0.0, -1.0);
+                    m[[1, 0]] = Complex64::new(0.0, 1.0);
+                }
+                _ => {
+                    m[[0, 0]] = Complex64::new(1.0, 0.0);
+                    m[[1, 1]] = Complex64::new(-1.0, 0.0);
+                }
+            }
+        }
+        _ => {
+            return Err(Error::Config(format!(
+                "Unknown operator '{}' (expected population[:k], coherence:i:j, number, a, \
+                 adag, identity, sigma_x, sigma_y or sigma_z)",
+                name
+            )))
+        }
+    }
+
+    Ok(m)
+}
+
+// Observable names end up in output file names and CSV headers, so they are
+// limited to letters, digits, '_' and ':'.
+pub fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() {
+        return Err(Error::Config(
+            "Observable names must not be empty".to_string(),
+        ));
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':')
+    {
+        return Err(Error::Config(format!(
+            "Observable name '{}' may only contain letters, digits, '_' and ':'",
+            name
+        )));
+    }
+    Ok(())
+}
+
+fn split_spec(spec: &str) -> Result<(String, &str)> {
+    match spec.split_once('=') {
+        Some((name, expr)) => {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(Error::Config(format!(
+                    "Observable '{}' has an empty name",
+                    spec
+                )));
+            }
+            validate_name(name)?;
+            Ok((name.to_string(), expr.trim()))
+        }
+        None => {
+            let expr = spec.trim();
+            if validate_name(expr).is_err() {
+                return Err(Error::Config(format!(
+                    "Observable '{}' needs a name, write it as 'name = {}'",
+                    expr, expr
+                )));
+            }
+            Ok((expr.to_string(), expr))
+        }
+    }
+}
+
+pub fn parse_observable(spec: &str, dim: usize) -> Result<(String, MatrixObservable)> {
+    let (name, expr) = split_spec(spec)?;
+    let matrix = evaluate(expr, dim)?;
+    Ok((name, MatrixObservable::new(matrix)))
+}
+
+// Whether every expectation value of the observable lies in [0, 1], i.e. it is
+// Hermitian with its spectrum in [0, 1], so it can be read as a click probability.
+pub fn is_probability(spec: &str, dim: usize) -> Result<bool> {
+    let expr = spec.split_once('=').map_or(spec, |(_, expr)| expr).trim();
+    let matrix = evaluate(expr, dim)?;
+    if !is_hermitian(&matrix.view(), 1e-12) {
+        return Ok(false);
+    }
+    let (eigenvalues, _) = hermitian_eigen(&matrix.view());
+    Ok(eigenvalues
+        .iter()
+        .all(|&e| (-1e-9..=1.0 + 1e-9).contains(&e)))
+}
+
+pub fn parse_vector_observable(
+    spec: &str,
+    dim: usize,
+) -> Result<Option<(String, Box<dyn VectorObservable>)>> {
+    let (name, expr) = split_spec(spec)?;
+
+    let observable: Box<dyn VectorObservable> = match expr {
+        "photon_distribution" | "fock_distribution" => Box::new(PhotonNumberDistribution::new(dim)),
+        "bloch" => Box::new(BlochVector::new(dim, 0, 1)?),
+        "localization" => Box::new(Localization::chain(dim)?),
+        "correlations" => Box::new(SiteCorrelations::chain(dim)?),
+        "concurrence" => Box::new(Concurrence::chain(dim)?),
+        _ => match expr
+            .strip_prefix("bloch:")
+            .and_then(|rest| rest.split_once(':'))
+        {
+            Some((i, j)) => {
+                let level = |p: &str| {
+                    p.parse::<usize>().map_err(|_| {
+                        Error::Config(format!("Invalid index '{}' in observable '{}'", p, expr))
+                    })
+                };
+                Box::new(BlochVector::new(dim, level(i)?, level(j)?)?)
+            }
+            None => return Ok(None),
+        },
+    };
+
+    Ok(Some((name, observable)))
+}
+
+pub fn evaluate(expr: &str, dim: usize) -> Result<Array2<Complex64>> {
+    let tokens = tokenize(expr)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        dim,
+    };
+
+    let value = parser.expr()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(Error::Config(format!(
+            "Unexpected trailing input in observable expression '{}'",
+            expr
+        )));
+    }
+
+    Ok(value.into_operator(dim))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(char),
+}
+
+fn tokenize(expr: &str) -> Result<Vec<Token>> {
+    let chars: Vec<char> = expr.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() || c == '.' {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text
+                .parse()
+                .map_err(|_| Error::Config(format!("Invalid number '{}'", text)))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == ':')
+            {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if "+-*/()".contains(c) {
+            tokens.push(Token::Op(c));
+            i += 1;
+        } else {
+            return Err(Error::Config(format!(
+                "Unexpected character '{}' in observable expression '{}'",
+                c, expr
+            )));
+        }
+    }
+
+    Ok(tokens)
+}
+
+enum Value {
+    Scalar(Complex64),
+    Operator(Array2<Complex64>),
+}
+
+impl Value {
+    fn into_operator(self, dim: usize) -> Array2<Complex64> {
+        match self {
+            Value::Scalar(s) => identity(dim).mapv(|x| x * s),
+            Value::Operator(m) => m,
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    dim: usize,
+}
+
+impl Parser {
+    fn peek_op(&self) -> Option<char> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Op(c)) => Some(*c),
+            _ => None,
+        }
+    }
+
+    fn expect_op(&mut self, op: char) -> Result<()> {
+        if self.peek_op() == Some(op) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(Error::Config(format!(
+                "Expected '{}' in observable expression",
+                op
+            )))
+        }
+    }
+
+    fn expr(&mut self) -> Result<Value> {
+        let mut lhs = self.term()?;
+        while let Some(op @ ('+' | '-')) = self.peek_op() {
+            self.pos += 1;
+            let rhs = self.term()?;
+            let sign = if op == '+' { 1.0 } else { -1.0 };
+            lhs = match (lhs, rhs) {
+                (Value::Scalar(a), Value::Scalar(b)) => Value::Scalar(a + b * sign),
+                (a, b) => {
+                    let b = b.into_operator(self.dim).mapv(|x| x * sign);
+                    Value::Operator(a.into_operator(self.dim) + b)
+                }
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn term(&mut self) -> Result<Value> {
+        let mut lhs = self.unary()?;
+        while let Some(op @ ('*' | '/')) = self.peek_op() {
+            self.pos += 1;
+            let rhs = self.unary()?;
+            lhs = match (op, lhs, rhs) {
+                ('*', Value::Scalar(a), Value::Scalar(b)) => Value::Scalar(a * b),
+                ('*', Value::Scalar(s), Value::Operator(m))
+                | ('*', Value::Operator(m), Value::Scalar(s)) => Value::Operator(m.mapv(|x| x * s)),
+                ('*', Value::Operator(a), Value::Operator(b)) => Value::Operator(a.dot(&b)),
+                ('/', Value::Scalar(a), Value::Scalar(b)) => Value::Scalar(a / b),
+                ('/', Value::Operator(m), Value::Scalar(s)) => Value::Operator(m.mapv(|x| x / s)),
+                _ => {
+                    return Err(Error::Config(
+                        "Division by an operator is not supported".to_string(),
+                    ))
+                }
+            };
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Value> {
+        match self.peek_op() {
+            Some('-') => {
+                self.pos += 1;
+                Ok(match self.unary()? {
+                    Value::Scalar(s) => Value::Scalar(-s),
+                    Value::Operator(m) => Value::Operator(m.mapv(|x| -x)),
+                })
+            }
+            Some('+') => {
+                self.pos += 1;
+                self.unary()
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Value> {
+        let token =
+            self.tokens.get(self.pos).cloned().ok_or_else(|| {
+                Error::Config("Unexpected end of observable expression".to_string())
+            })?;
+        self.pos += 1;
+
+        match token {
+            Token::Number(x) => Ok(Value::Scalar(Complex64::new(x, 0.0))),
+            Token::Op('(') => {
+                let value = self.expr()?;
+                self.expect_op(')')?;
+                Ok(value)
+            }
+            Token::Ident(name) if self.peek_op() == Some('(') => {
+                self.pos += 1;
+                let arg = self.expr()?;
+                self.expect_op(')')?;
+                match (name.as_str(), arg) {
+                    ("sqrt", Value::Scalar(s)) => Ok(Value::Scalar(s.sqrt())),
+                    ("dag", Value::Operator(m)) => Ok(Value::Operator(m.t().mapv(|x| x.conj()))),
+                    ("dag", Value::Scalar(s)) => Ok(Value::Scalar(s.conj())),
+                    _ => Err(Error::Config(format!(
+                        "Unknown function or invalid argument for '{}'",
+                        name
+                    ))),
+                }
+            }
+            Token::Ident(name) => match name.as_str() {
+                "i" => Ok(Value::Scalar(Complex64::new(0.0, 1.0))),
+                "pi" => Ok(Value::Scalar(Complex64::new(std::f64::consts::PI, 0.0))),
+                _ => Ok(Value::Operator(named_operator(&name, self.dim)?)),
+            },
+            Token::Op(c) => Err(Error::Config(format!(
+                "Unexpected '{}' in observable expression",
+                c
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observables::Observable;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_population_difference() {
+        let (name, obs) = parse_observable("sz = population:0 - population:1", 2).unwrap();
+        assert_eq!(name, "sz");
+        assert_relative_eq!(obs.matrix()[[0, 0]].re, 1.0);
+        assert_relative_eq!(obs.matrix()[[1, 1]].re, -1.0);
+    }
+
+    #[test]
+    fn test_names_are_safe_file_names() {
+        assert_eq!(
+            parse_observable("population:1", 2).unwrap().0,
+            "population:1"
+        );
+        assert_eq!(parse_observable(" half = n/2 ", 3).unwrap().0, "half");
+
+        let err = parse_observable("n/2+sigma_z", 2)
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("'name = n/2+sigma_z'"), "{}", err);
+        assert!(parse_observable("../n = number", 2).is_err());
+        assert!(parse_observable("a/b = number", 2).is_err());
+        assert!(parse_observable(" = number", 2).is_err());
+        assert!(parse_vector_observable("x y = bloch", 2).is_err());
+    }
+
+    #[test]
+    fn test_quadrature_and_products() {
+        let x = evaluate("(a + adag)/sqrt(2)", 4).unwrap();
+        assert_relative_eq!(x[[0, 1]].re, 1.0 / 2f64.sqrt(), epsilon = 1e-12);
+        assert_relative_eq!(x[[1, 0]].re, 1.0 / 2f64.sqrt(), epsilon = 1e-12);
+
+        let n = evaluate("adag * a", 4).unwrap();
+        let number = named_operator("number", 4).unwrap();
+        for (x, y) in n.iter().zip(number.iter()) {
+            assert_relative_eq!(x.re, y.re, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        assert!(evaluate("population:5", 2).is_err());
+        assert!(evaluate("a / adag", 3).is_err());
+        assert!(evaluate("(a + adag", 3).is_err());
+        assert!(evaluate("foo", 3).is_err());
+    }
+
+    #[test]
+    fn test_probability_observables() {
+        assert!(is_probability("population:1", 3).unwrap());
+        assert!(is_probability("p = population:0 + population:1", 3).unwrap());
+        assert!(is_probability("number / 2", 3).unwrap());
+        assert!(!is_probability("number", 3).unwrap());
+        assert!(!is_probability("sigma_z", 2).unwrap());
+        assert!(!is_probability("a", 3).unwrap());
+    }
+
+    #[test]
+    fn test_vector_observables() {
+        let (name, dist) = parse_vector_observable("pn = photon_distribution", 4)
+            .unwrap()
+            .unwrap();
+        assert_eq!(name, "pn");
+        assert_eq!(dist.width(), 4);
+
+        let (_, bloch) = parse_vector_observable("bloch:1:2", 3).unwrap().unwrap();
+        assert_eq!(bloch.width(), 3);
+        assert!(parse_vector_observable("number", 3).unwrap().is_none());
+    }
+}
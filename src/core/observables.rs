@@ -9,32 +9,52 @@ pub trait Observable: Send + Sync {
     fn matrix(&self) -> &Array2<Complex64>;
 
     fn expectation_pure(&self, state: &QuantumState) -> Complex64 {
-        let psi = state.data();
-        let op = self.matrix();
-        let dim = self.dim();
-
-        let mut sum = Complex64::new(0.0, 0.0);
-        for i in 0..dim {
-            for j in 0..dim {
-                sum += psi[i].conj() * op[[i, j]] * psi[j];
-            }
-        }
-        sum
+        pure_expectation(self.matrix(), state)
     }
 
     fn expectation_mixed(&self, state: &DensityMatrix) -> Complex64 {
-        let rho = state.data();
-        let op = self.matrix();
-        let dim = self.dim();
-
-        let mut sum = Complex64::new(0.0, 0.0);
-        for i in 0..dim {
-            for j in 0..dim {
-                sum += rho[[i, j]] * op[[j, i]];
-            }
+        mixed_expectation(self.matrix(), state)
+    }
+}
+
+pub trait VectorObservable: Send + Sync {
+    fn dim(&self) -> usize;
+
+    fn width(&self) -> usize;
+
+    fn expectation_pure(&self, state: &QuantumState) -> Vec<Complex64>;
+
+    fn expectation_mixed(&self, state: &DensityMatrix) -> Vec<Complex64>;
+
+    fn shape(&self) -> Option<(usize, usize)> {
+        None
+    }
+}
+
+fn pure_expectation(op: &Array2<Complex64>, state: &QuantumState) -> Complex64 {
+    let psi = state.data();
+    let dim = op.nrows();
+
+    let mut sum = Complex64::new(0.0, 0.0);
+    for i in 0..dim {
+        for j in 0..dim {
+            sum += psi[i].conj() * op[[i, j]] * psi[j];
+        }
+    }
+    sum
+}
+
+fn mixed_expectation(op: &Array2<Complex64>, state: &DensityMatrix) -> Complex64 {
+    let rho = state.data();
+    let dim = op.nrows();
+
+    let mut sum = Complex64::new(0.0, 0.0);
+    for i in 0..dim {
+        for j in 0..dim {
+            sum += rho[[i, j]] * op[[j, i]];
         }
-        sum
     }
+    sum
 }
 
 #[derive(Debug, Clone)]
@@ -148,6 +168,281 @@ impl Observable for CoherenceOperator {
     }
 }
 
+pub struct BlochVector {
+    components: [Array2<Complex64>; 3],
+}
+
+impl BlochVector {
+    pub fn new(dim: usize, lower: usize, upper: usize) -> Result<Self> {
+        if lower >= dim || upper >= dim || lower == upper {
+            return Err(crate::utils::Error::InvalidParameter(format!(
+                "Invalid levels ({}, {}) for Bloch vector in dimension {}",
+                lower, upper, dim
+            )));
+        }
+
+        let one = Complex64::new(1.0, 0.0);
+        let i = Complex64::new(0.0, 1.0);
+        let mut components = [
+            Array2::zeros((dim, dim)),
+            Array2::zeros((dim, dim)),
+            Array2::zeros((dim, dim)),
+        ];
+        components[0][[lower, upper]] = one;
+        components[0][[upper, lower]] = one;
+        components[1][[lower, upper]] = -i;
+        components[1][[upper, lower]] = i;
+        components[2][[lower, lower]] = one;
+        components[2][[upper, upper]] = -one;
+
+        Ok(Self { components })
+    }
+}
+
+impl VectorObservable for BlochVector {
+    fn dim(&self) -> usize {
+        self.components[0].nrows()
+    }
+
+    fn width(&self) -> usize {
+        3
+    }
+
+    fn expectation_pure(&self, state: &QuantumState) -> Vec<Complex64> {
+        self.components
+            .iter()
+            .map(|op| pure_expectation(op, state))
+            .collect()
+    }
+
+    fn expectation_mixed(&self, state: &DensityMatrix) -> Vec<Complex64> {
+        self.components
+            .iter()
+            .map(|op| mixed_expectation(op, state))
+            .collect()
+    }
+}
+
+pub struct PhotonNumberDistribution {
+    dim: usize,
+}
+
+impl PhotonNumberDistribution {
+    pub fn new(dim: usize) -> Self {
+        Self { dim }
+    }
+}
+
+impl VectorObservable for PhotonNumberDistribution {
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn width(&self) -> usize {
+        self.dim
+    }
+
+    fn expectation_pure(&self, state: &QuantumState) -> Vec<Complex64> {
+        state
+            .data()
+            .iter()
+            .map(|c| Complex64::new(c.norm_sqr(), 0.0))
+            .collect()
+    }
+
+    fn expectation_mixed(&self, state: &DensityMatrix) -> Vec<Complex64> {
+        state.data().diag().to_vec()
+    }
+}
+
+pub struct Localization {
+    dim: usize,
+    sites: Vec<usize>,
+    positions: Vec<[f64; 2]>,
+}
+
+impl Localization {
+    pub fn new(dim: usize, sites: Vec<usize>, positions: Vec<[f64; 2]>) -> Result<Self> {
+        if sites.len() != positions.len() {
+            return Err(crate::utils::Error::DimensionMismatch {
+                expected: sites.len(),
+                actual: positions.len(),
+            });
+        }
+        check_sites(dim, &sites)?;
+        Ok(Self {
+            dim,
+            sites,
+            positions,
+        })
+    }
+
+    pub fn chain(dim: usize) -> Result<Self> {
+        let sites = chain_sites(dim)?;
+        let positions = (0..sites.len()).map(|i| [i as f64, 0.0]).collect();
+        Self::new(dim, sites, positions)
+    }
+
+    fn measure(&self, populations: impl Fn(usize) -> f64) -> Vec<Complex64> {
+        let p: Vec<f64> = self.sites.iter().map(|&s| populations(s)).collect();
+        let total: f64 = p.iter().sum();
+        if total <= f64::EPSILON {
+            return vec![Complex64::new(0.0, 0.0); 4];
+        }
+
+        let ipr = p.iter().map(|p| p * p).sum::<f64>() / (total * total);
+        let mean = p
+            .iter()
+            .zip(&self.positions)
+            .fold([0.0, 0.0], |[x, y], (p, r)| [x + p * r[0], y + p * r[1]])
+            .map(|c| c / total);
+        let variance = p
+            .iter()
+            .zip(&self.positions)
+            .map(|(p, r)| p * ((r[0] - mean[0]).powi(2) + (r[1] - mean[1]).powi(2)))
+            .sum::<f64>()
+            / total;
+        [ipr, variance.sqrt(), mean[0], mean[1]]
+            .into_iter()
+            .map(|v| Complex64::new(v, 0.0))
+            .collect()
+    }
+}
+
+impl VectorObservable for Localization {
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn width(&self) -> usize {
+        4
+    }
+
+    fn expectation_pure(&self, state: &QuantumState) -> Vec<Complex64> {
+        self.measure(|i| state.data()[i].norm_sqr())
+    }
+
+    fn expectation_mixed(&self, state: &DensityMatrix) -> Vec<Complex64> {
+        self.measure(|i| state.data()[[i, i]].re)
+    }
+}
+
+fn check_sites(dim: usize, sites: &[usize]) -> Result<()> {
+    match sites.iter().find(|&&s| s >= dim) {
+        Some(&site) => Err(crate::utils::Error::InvalidParameter(format!(
+            "Site basis index {} is outside a Hilbert space of dimension {}",
+            site, dim
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn chain_sites(dim: usize) -> Result<Vec<usize>> {
+    if dim < 2 {
+        return Err(crate::utils::Error::InvalidParameter(
+            "A chain needs at least one site besides the vacuum".to_string(),
+        ));
+    }
+    Ok((1..dim).collect())
+}
+
+pub struct SiteCorrelations {
+    dim: usize,
+    sites: Vec<usize>,
+}
+
+impl SiteCorrelations {
+    pub fn new(dim: usize, sites: Vec<usize>) -> Result<Self> {
+        check_sites(dim, &sites)?;
+        Ok(Self { dim, sites })
+    }
+
+    pub fn chain(dim: usize) -> Result<Self> {
+        Self::new(dim, chain_sites(dim)?)
+    }
+
+    fn measure(&self, element: impl Fn(usize, usize) -> Complex64) -> Vec<Complex64> {
+        self.sites
+            .iter()
+            .flat_map(|&i| self.sites.iter().map(move |&j| (i, j)))
+            .map(|(i, j)| element(i, j))
+            .collect()
+    }
+}
+
+impl VectorObservable for SiteCorrelations {
+    fn dim(&self) -> usize {
+        self.dim
+    }
+
+    fn width(&self) -> usize {
+        self.sites.len() * self.sites.len()
+    }
+
+    fn expectation_pure(&self, state: &QuantumState) -> Vec<Complex64> {
+        let psi = state.data();
+        self.measure(|i, j| psi[i].conj() * psi[j])
+    }
+
+    fn expectation_mixed(&self, state: &DensityMatrix) -> Vec<Complex64> {
+        self.measure(|i, j| state.data()[[j, i]])
+    }
+
+    fn shape(&self) -> Option<(usize, usize)> {
+        Some((self.sites.len(), self.sites.len()))
+    }
+}
+
+pub struct Concurrence {
+    correlations: SiteCorrelations,
+}
+
+impl Concurrence {
+    pub fn new(dim: usize, sites: Vec<usize>) -> Result<Self> {
+        Ok(Self {
+            correlations: SiteCorrelations::new(dim, sites)?,
+        })
+    }
+
+    pub fn chain(dim: usize) -> Result<Self> {
+        Self::new(dim, chain_sites(dim)?)
+    }
+
+    fn pairwise(&self, correlations: Vec<Complex64>) -> Vec<Complex64> {
+        let n = self.correlations.sites.len();
+        correlations
+            .into_iter()
+            .enumerate()
+            .map(|(k, c)| {
+                let value = if k / n == k % n { 0.0 } else { 2.0 * c.norm() };
+                Complex64::new(value, 0.0)
+            })
+            .collect()
+    }
+}
+
+impl VectorObservable for Concurrence {
+    fn dim(&self) -> usize {
+        self.correlations.dim
+    }
+
+    fn width(&self) -> usize {
+        self.correlations.width()
+    }
+
+    fn expectation_pure(&self, state: &QuantumState) -> Vec<Complex64> {
+        self.pairwise(self.correlations.expectation_pure(state))
+    }
+
+    fn expectation_mixed(&self, state: &DensityMatrix) -> Vec<Complex64> {
+        self.pairwise(self.correlations.expectation_mixed(state))
+    }
+
+    fn shape(&self) -> Option<(usize, usize)> {
+        self.correlations.shape()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -171,4 +466,69 @@ mod tests {
         let exp_val = num_op.expectation_pure(&ground);
         assert_relative_eq!(exp_val.re, 0.0);
     }
+
+    #[test]
+    fn test_bloch_vector_of_ground_state() {
+        let bloch = BlochVector::new(2, 0, 1).unwrap();
+        let r = bloch.expectation_pure(&QuantumState::ground_state(2));
+
+        assert_eq!(r.len(), 3);
+        assert_relative_eq!(r[0].re, 0.0);
+        assert_relative_eq!(r[1].re, 0.0);
+        assert_relative_eq!(r[2].re, 1.0);
+    }
+
+    #[test]
+    fn test_photon_number_distribution() {
+        let dist = PhotonNumberDistribution::new(3);
+        let p = dist.expectation_mixed(&DensityMatrix::maximally_mixed(3));
+
+        assert_eq!(p.len(), 3);
+        for value in p {
+            assert_relative_eq!(value.re, 1.0 / 3.0, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_localization_of_site_and_uniform_states() {
+        let n = 5;
+        let localization = Localization::chain(n + 1).unwrap();
+        let r = localization.expectation_pure(&QuantumState::fock(n + 1, 3).unwrap());
+        assert_relative_eq!(r[0].re, 1.0);
+        assert_relative_eq!(r[1].re, 0.0);
+        assert_relative_eq!(r[2].re, 2.0);
+
+        let mut rho = Array2::zeros((n + 1, n + 1));
+        for i in 1..=n {
+            rho[[i, i]] = Complex64::new(1.0 / n as f64, 0.0);
+        }
+        let r = localization.expectation_mixed(&DensityMatrix::new(rho).unwrap());
+        assert_relative_eq!(r[0].re, 1.0 / n as f64, epsilon = 1e-12);
+        assert_relative_eq!(r[1].re, 2.0_f64.sqrt(), epsilon = 1e-12);
+        assert_relative_eq!(r[2].re, 2.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_correlations_and_concurrence_of_w_state() {
+        let amplitude = Complex64::new(1.0 / 3.0_f64.sqrt(), 0.0);
+        let mut psi = ndarray::Array1::zeros(4);
+        psi[1] = amplitude;
+        psi[2] = amplitude * Complex64::i();
+        psi[3] = amplitude;
+        let state = QuantumState::new(psi).unwrap();
+
+        let correlations = SiteCorrelations::chain(4).unwrap();
+        assert_eq!(correlations.shape(), Some((3, 3)));
+        let c = correlations.expectation_pure(&state);
+        assert_relative_eq!(c[0].re, 1.0 / 3.0, epsilon = 1e-12);
+        assert_relative_eq!(c[1].im, 1.0 / 3.0, epsilon = 1e-12);
+        let mixed = correlations.expectation_mixed(&state.to_density_matrix());
+        for (a, b) in c.iter().zip(&mixed) {
+            assert_relative_eq!((a - b).norm(), 0.0, epsilon = 1e-12);
+        }
+
+        let concurrence = Concurrence::chain(4).unwrap().expectation_pure(&state);
+        assert_relative_eq!(concurrence[0].re, 0.0);
+        assert_relative_eq!(concurrence[5].re, 2.0 / 3.0, epsilon = 1e-12);
+    }
 }
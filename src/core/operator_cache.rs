@@ -0,0 +1,228 @@
+use crate::core::lindblad::LindbladOperator;
+use crate::core::observable_expr;
+use crate::core::Observable;
+use crate::data::Config;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OperatorKey {
+    pub system: String,
+    pub dim: usize,
+    pub operator: String,
+}
+
+#[derive(Default)]
+pub struct OperatorCache {
+    entries: RwLock<HashMap<OperatorKey, Arc<Array2<Complex64>>>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl OperatorCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_or_build(
+        &self,
+        key: OperatorKey,
+        build: impl FnOnce() -> Result<Array2<Complex64>>,
+    ) -> Result<Arc<Array2<Complex64>>> {
+        let cached = self
+            .entries
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+            .cloned();
+        if let Some(matrix) = cached {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(matrix);
+        }
+
+        let matrix = Arc::new(build()?);
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        Ok(self
+            .entries
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(key)
+            .or_insert(matrix)
+            .clone())
+    }
+
+    pub fn observable(
+        &self,
+        system: &str,
+        spec: &str,
+        dim: usize,
+    ) -> Result<(String, SharedObservable)> {
+        let (name, expr) = match spec.split_once('=') {
+            Some((name, expr)) => (name.trim().to_string(), expr.trim()),
+            None => (spec.trim().to_string(), spec.trim()),
+        };
+        if name.is_empty() {
+            return Err(Error::Config(format!(
+                "Observable '{}' has an empty name",
+                spec
+            )));
+        }
+
+        let key = OperatorKey {
+            system: system.to_string(),
+            dim,
+            operator: expr.to_string(),
+        };
+        let matrix = self.get_or_build(key, || observable_expr::evaluate(expr, dim))?;
+        Ok((name, SharedObservable { matrix }))
+    }
+
+    pub fn collapse_operator(
+        &self,
+        system: &str,
+        kind: &str,
+        dim: usize,
+        rate: f64,
+        site: Option<usize>,
+    ) -> Result<LindbladOperator> {
+        let operator = match (kind, site) {
+            ("annihilation" | "decay", None) => "a".to_string(),
+            ("dephasing", None) => "number".to_string(),
+            ("site_decay", Some(site)) => format!("coherence:0:{}", site + 1),
+            ("site_decay", None) => {
+                return Err(Error::Config(
+                    "Lindblad operator 'site_decay' needs a site index".to_string(),
+                ))
+            }
+            (other, Some(_)) if other != "site_decay" => {
+                return Err(Error::Config(format!(
+                    "Lindblad operator type '{}' does not take a site index",
+                    other
+                )))
+            }
+            (other, _) => {
+                return Err(Error::Config(format!(
+                    "Unknown Lindblad operator type '{}', expected annihilation, decay, \
+                     dephasing or site_decay",
+                    other
+                )))
+            }
+        };
+        let key = OperatorKey {
+            system: system.to_string(),
+            dim,
+            operator: operator.clone(),
+        };
+        let matrix = self.get_or_build(key, || observable_expr::named_operator(&operator, dim))?;
+        LindbladOperator::shared(matrix, rate)
+    }
+
+    pub fn observables_for(&self, config: &Config) -> Result<Vec<(String, Box<dyn Observable>)>> {
+        let dim = config.system.hilbert_dim;
+        let mut observables: Vec<(String, Box<dyn Observable>)> = Vec::new();
+        for spec in &config.observables.list {
+            if observable_expr::parse_vector_observable(spec, dim)?.is_some() {
+                continue;
+            }
+            let (name, observable) = self.observable(&config.system.hamiltonian, spec, dim)?;
+            observables.push((name, Box::new(observable)));
+        }
+        Ok(observables)
+    }
+
+    pub fn collapse_operators_for(&self, config: &Config) -> Result<Vec<LindbladOperator>> {
+        if !config.lindblad.enabled {
+            return Ok(Vec::new());
+        }
+        config
+            .lindblad
+            .operators
+            .iter()
+            .map(|op| {
+                self.collapse_operator(
+                    &config.system.hamiltonian,
+                    &op.r#type,
+                    config.system.hilbert_dim,
+                    op.rate,
+                    op.site,
+                )
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+pub struct SharedObservable {
+    matrix: Arc<Array2<Complex64>>,
+}
+
+impl Observable for SharedObservable {
+    fn dim(&self) -> usize {
+        self.matrix.nrows()
+    }
+
+    fn matrix(&self) -> &Array2<Complex64> {
+        &self.matrix
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_operators_are_shared_across_points() {
+        let cache = OperatorCache::new();
+        let (name, first) = cache
+            .observable("driven_tls", "p1 = population:1", 2)
+            .unwrap();
+        let (_, second) = cache.observable("driven_tls", "population:1", 2).unwrap();
+        assert_eq!(name, "p1");
+        assert!(Arc::ptr_eq(&first.matrix, &second.matrix));
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        cache.observable("driven_tls", "population:1", 3).unwrap();
+        let a = cache
+            .collapse_operator("driven_tls", "decay", 2, 0.1, None)
+            .unwrap();
+        let b = cache
+            .collapse_operator("driven_tls", "decay", 2, 0.2, None)
+            .unwrap();
+        assert!(Arc::ptr_eq(&a.operator, &b.operator));
+        assert_eq!(b.rate, 0.2);
+        assert_eq!(cache.len(), 3);
+        assert!(cache
+            .collapse_operator("driven_tls", "squeezing", 2, 0.1, None)
+            .is_err());
+
+        let site = cache
+            .collapse_operator("lattice", "site_decay", 3, 0.1, Some(1))
+            .unwrap();
+        assert_eq!(site.operator[[0, 2]], Complex64::new(1.0, 0.0));
+        assert!(cache
+            .collapse_operator("lattice", "site_decay", 3, 0.1, None)
+            .is_err());
+        assert!(cache
+            .collapse_operator("lattice", "decay", 3, 0.1, Some(0))
+            .is_err());
+    }
+}
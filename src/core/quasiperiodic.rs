@@ -0,0 +1,360 @@
+use crate::core::hamiltonian::narrow;
+use crate::core::Hamiltonian;
+use crate::simulation::TimeGrid;
+use crate::utils::math::spectral_norm;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::{Complex32, Complex64};
+use std::f64::consts::PI;
+
+pub const GOLDEN_RATIO: f64 = 1.618_033_988_749_895;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interval {
+    Long,
+    Short,
+}
+
+pub fn fibonacci_word(length: usize) -> Vec<Interval> {
+    let mut word = vec![Interval::Long];
+    while word.len() < length {
+        word = word
+            .iter()
+            .flat_map(|letter| match letter {
+                Interval::Long => vec![Interval::Long, Interval::Short],
+                Interval::Short => vec![Interval::Long],
+            })
+            .collect();
+    }
+    word.truncate(length);
+    word
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PulseSequence {
+    intervals: Vec<Interval>,
+    pulse_times: Vec<f64>,
+    long: f64,
+    short: f64,
+}
+
+impl PulseSequence {
+    pub fn fibonacci(long: f64, short: f64, pulses: usize) -> Result<Self> {
+        Self::from_intervals(fibonacci_word(pulses), long, short)
+    }
+
+    pub fn periodic(interval: f64, pulses: usize) -> Result<Self> {
+        Self::from_intervals(vec![Interval::Long; pulses], interval, interval)
+    }
+
+    pub fn from_intervals(intervals: Vec<Interval>, long: f64, short: f64) -> Result<Self> {
+        if !(long > 0.0 && short > 0.0 && long.is_finite() && short.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "Pulse intervals must be positive, got long = {} and short = {}",
+                long, short
+            )));
+        }
+        if intervals.is_empty() {
+            return Err(Error::InvalidParameter(
+                "Pulse sequence needs at least one pulse".to_string(),
+            ));
+        }
+        let pulse_times = intervals
+            .iter()
+            .scan(0.0, |t, interval| {
+                *t += match interval {
+                    Interval::Long => long,
+                    Interval::Short => short,
+                };
+                Some(*t)
+            })
+            .collect();
+        Ok(Self {
+            intervals,
+            pulse_times,
+            long,
+            short,
+        })
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn pulse_times(&self) -> &[f64] {
+        &self.pulse_times
+    }
+
+    pub fn num_pulses(&self) -> usize {
+        self.pulse_times.len()
+    }
+
+    pub fn duration(&self) -> f64 {
+        *self.pulse_times.last().expect("sequence has pulses")
+    }
+
+    pub fn shortest_interval(&self) -> f64 {
+        if self.intervals.contains(&Interval::Short) {
+            self.long.min(self.short)
+        } else {
+            self.long
+        }
+    }
+
+    pub fn sample_grid(&self) -> TimeGrid {
+        let mut times = vec![0.0];
+        times.extend_from_slice(&self.pulse_times);
+        TimeGrid::explicit(times)
+    }
+
+    pub fn fibonacci_checkpoints(&self) -> Vec<usize> {
+        let (mut a, mut b) = (1, 2);
+        let mut checkpoints = Vec::new();
+        while a <= self.num_pulses() {
+            checkpoints.push(a);
+            (a, b) = (b, a + b);
+        }
+        checkpoints
+    }
+}
+
+pub struct PulsedHamiltonian {
+    static_part: Array2<Complex64>,
+    kick: Array2<Complex64>,
+    angle: f64,
+    width: f64,
+    sequence: PulseSequence,
+}
+
+impl PulsedHamiltonian {
+    pub fn new(
+        static_part: Array2<Complex64>,
+        kick: Array2<Complex64>,
+        angle: f64,
+        width: f64,
+        sequence: PulseSequence,
+    ) -> Result<Self> {
+        if kick.dim() != static_part.dim() {
+            return Err(Error::dimension_mismatch(static_part.nrows(), kick.nrows()));
+        }
+        if !(width > 0.0 && width <= sequence.shortest_interval()) {
+            return Err(Error::InvalidParameter(format!(
+                "Pulse width must be in (0, {}], got {}",
+                sequence.shortest_interval(),
+                width
+            )));
+        }
+        Ok(Self {
+            static_part,
+            kick,
+            angle,
+            width,
+            sequence,
+        })
+    }
+
+    pub fn sequence(&self) -> &PulseSequence {
+        &self.sequence
+    }
+
+    fn envelope(&self, t: f64) -> f64 {
+        let times = self.sequence.pulse_times();
+        let k = times.partition_point(|&end| end < t);
+        match times.get(k) {
+            Some(&end) if end - t <= self.width => {
+                let s = (end - t) / self.width;
+                2.0 * (PI * s).sin().powi(2)
+            }
+            _ => 0.0,
+        }
+    }
+}
+
+impl Hamiltonian for PulsedHamiltonian {
+    fn dim(&self) -> usize {
+        self.static_part.nrows()
+    }
+
+    fn compute(&self, t: f64, out: &mut Array2<Complex64>) {
+        let amplitude = self.envelope(t) * self.angle / (2.0 * self.width);
+        ndarray::Zip::from(out)
+            .and(&self.static_part)
+            .and(&self.kick)
+            .for_each(|o, &h, &k| *o = h + k * amplitude);
+    }
+
+    fn compute_single(&self, t: f64, out: &mut Array2<Complex32>) {
+        let amplitude = self.envelope(t) * self.angle / (2.0 * self.width);
+        ndarray::Zip::from(out)
+            .and(&self.static_part)
+            .and(&self.kick)
+            .for_each(|o, &h, &k| *o = narrow(h + k * amplitude));
+    }
+
+    fn spectral_norm_estimate(&self) -> f64 {
+        spectral_norm(&self.static_part.view(), 100, 1e-6)
+            + self.angle.abs() / self.width * spectral_norm(&self.kick.view(), 100, 1e-6)
+    }
+}
+
+pub struct TwoToneDrive {
+    static_part: Array2<Complex64>,
+    first: Array2<Complex64>,
+    second: Array2<Complex64>,
+    omega_1: f64,
+    omega_2: f64,
+}
+
+impl TwoToneDrive {
+    pub fn new(
+        static_part: Array2<Complex64>,
+        first: Array2<Complex64>,
+        second: Array2<Complex64>,
+        omega_1: f64,
+        omega_2: f64,
+    ) -> Result<Self> {
+        let dim = static_part.nrows();
+        for op in [&first, &second] {
+            if op.dim() != static_part.dim() {
+                return Err(Error::dimension_mismatch(dim, op.nrows()));
+            }
+        }
+        if !(omega_1 > 0.0 && omega_2 > 0.0) {
+            return Err(Error::InvalidParameter(format!(
+                "Drive frequencies must be positive, got {} and {}",
+                omega_1, omega_2
+            )));
+        }
+        Ok(Self {
+            static_part,
+            first,
+            second,
+            omega_1,
+            omega_2,
+        })
+    }
+
+    pub fn golden(
+        static_part: Array2<Complex64>,
+        first: Array2<Complex64>,
+        second: Array2<Complex64>,
+        omega_1: f64,
+    ) -> Result<Self> {
+        Self::new(static_part, first, second, omega_1, GOLDEN_RATIO * omega_1)
+    }
+
+    pub fn first_period(&self) -> f64 {
+        2.0 * PI / self.omega_1
+    }
+
+    pub fn stroboscopic_times(&self, samples: usize) -> Vec<f64> {
+        (0..=samples)
+            .map(|n| n as f64 * self.first_period())
+            .collect()
+    }
+
+    pub fn sample_grid(&self, samples: usize) -> TimeGrid {
+        TimeGrid::explicit(self.stroboscopic_times(samples))
+    }
+
+    pub fn second_phase(&self, t: f64) -> f64 {
+        (self.omega_2 * t).rem_euclid(2.0 * PI)
+    }
+
+    pub fn near_returns(&self, samples: usize, tolerance: f64) -> Vec<usize> {
+        (1..=samples)
+            .filter(|&n| {
+                let phase = self.second_phase(n as f64 * self.first_period());
+                phase.min(2.0 * PI - phase) <= tolerance
+            })
+            .collect()
+    }
+}
+
+impl Hamiltonian for TwoToneDrive {
+    fn dim(&self) -> usize {
+        self.static_part.nrows()
+    }
+
+    fn compute(&self, t: f64, out: &mut Array2<Complex64>) {
+        let (c1, c2) = ((self.omega_1 * t).cos(), (self.omega_2 * t).cos());
+        ndarray::Zip::from(out)
+            .and(&self.static_part)
+            .and(&self.first)
+            .and(&self.second)
+            .for_each(|o, &h, &a, &b| *o = h + a * c1 + b * c2);
+    }
+
+    fn compute_single(&self, t: f64, out: &mut Array2<Complex32>) {
+        let (c1, c2) = ((self.omega_1 * t).cos(), (self.omega_2 * t).cos());
+        ndarray::Zip::from(out)
+            .and(&self.static_part)
+            .and(&self.first)
+            .and(&self.second)
+            .for_each(|o, &h, &a, &b| *o = narrow(h + a * c1 + b * c2));
+    }
+
+    fn spectral_norm_estimate(&self) -> f64 {
+        [&self.static_part, &self.first, &self.second]
+            .iter()
+            .map(|m| spectral_norm(&m.view(), 100, 1e-6))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observables::MatrixObservable;
+    use crate::core::QuantumState;
+    use crate::simulation::SimulationBuilder;
+    use approx::assert_relative_eq;
+    use ndarray::array;
+
+    #[test]
+    fn test_fibonacci_sequence_bookkeeping() {
+        use Interval::{Long as A, Short as B};
+        assert_eq!(fibonacci_word(8), vec![A, B, A, A, B, A, B, A]);
+
+        let sequence = PulseSequence::fibonacci(1.0, 0.5, 8).unwrap();
+        assert_relative_eq!(sequence.duration(), 5.0 * 1.0 + 3.0 * 0.5);
+        assert_relative_eq!(sequence.pulse_times()[1], 1.5);
+        assert_eq!(sequence.fibonacci_checkpoints(), vec![1, 2, 3, 5, 8]);
+
+        let one = Array2::<Complex64>::eye(2);
+        let drive = TwoToneDrive::golden(one.clone(), one.clone(), one, 1.0).unwrap();
+        assert_eq!(drive.near_returns(30, 0.3), vec![13, 21]);
+    }
+
+    #[test]
+    fn test_pi_pulses_flip_the_spin_after_every_pulse() {
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+        let sigma_x = array![[zero, one], [one, zero]];
+        let sigma_z = array![[one, zero], [zero, -one]];
+        let sequence = PulseSequence::fibonacci(1.0, 0.6, 6).unwrap();
+        let grid = sequence.sample_grid();
+        let duration = sequence.duration();
+        let hamiltonian =
+            PulsedHamiltonian::new(Array2::zeros((2, 2)), sigma_x, PI, 0.2, sequence).unwrap();
+
+        let results = SimulationBuilder::new()
+            .hamiltonian(hamiltonian)
+            .initial_state(QuantumState::ground_state(2))
+            .duration(duration)
+            .timestep(0.002)
+            .time_grid(grid)
+            .observable("sz", MatrixObservable::new(sigma_z))
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+
+        let sz = &results.series("sz").unwrap().values;
+        assert_eq!(sz.len(), 7);
+        for (k, value) in sz.iter().enumerate() {
+            let expected = if k % 2 == 0 { 1.0 } else { -1.0 };
+            assert_relative_eq!(value.re, expected, epsilon = 1e-6);
+        }
+    }
+}
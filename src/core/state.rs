@@ -1,5 +1,5 @@
-use crate::utils::{Error, Result};
-use ndarray::{Array1, Array2};
+use crate::utils::{Error, Result, Tolerances};
+use ndarray::{Array1, Array2, ArrayView1, ArrayView2, ArrayViewMut1, ArrayViewMut2};
 use num_complex::Complex64;
 
 #[derive(Clone, Debug)]
@@ -9,25 +9,83 @@ pub struct QuantumState {
 
 impl QuantumState {
     pub fn new(data: Array1<Complex64>) -> Result<Self> {
-        let norm_sq: f64 = data.iter().map(|x| x.norm_sqr()).sum();
-        if (norm_sq - 1.0).abs() > 1e-10 {
-            return Err(Error::InvalidParameter(format!(
-                "State must be normalized, got norm^2 = {}",
-                norm_sq
-            )));
-        }
+        Self::with_tolerances(data, &Tolerances::global())
+    }
+
+    pub fn with_tolerances(data: Array1<Complex64>, tolerances: &Tolerances) -> Result<Self> {
+        check_normalized(&data.view(), tolerances)?;
+        let data = if data.is_standard_layout() {
+            data
+        } else {
+            data.as_standard_layout().into_owned()
+        };
         Ok(Self { data })
     }
 
+    pub fn from_slice(amplitudes: &[Complex64]) -> Result<Self> {
+        Self::new(Array1::from(amplitudes.to_vec()))
+    }
+
+    pub fn from_slice_mut(amplitudes: &mut [Complex64]) -> Result<StateViewMut<'_>> {
+        let data = ArrayViewMut1::from(amplitudes);
+        check_normalized(&data.view(), &Tolerances::global())?;
+        Ok(StateViewMut { data })
+    }
+
     pub fn ground_state(dim: usize) -> Self {
         let mut data = Array1::zeros(dim);
         data[0] = Complex64::new(1.0, 0.0);
         Self { data }
     }
 
+    pub fn fock(dim: usize, n: usize) -> Result<Self> {
+        if n >= dim {
+            return Err(Error::InvalidParameter(format!(
+                "Fock level {} is outside a Hilbert space of dimension {}",
+                n, dim
+            )));
+        }
+        let mut data = Array1::zeros(dim);
+        data[n] = Complex64::new(1.0, 0.0);
+        Ok(Self { data })
+    }
+
+    pub fn coherent(dim: usize, alpha: Complex64) -> Result<Self> {
+        if dim == 0 {
+            return Err(Error::InvalidParameter(
+                "Coherent state needs a positive dimension".to_string(),
+            ));
+        }
+        let mut data = Array1::zeros(dim);
+        let mut amplitude = Complex64::new(1.0, 0.0);
+        for n in 0..dim {
+            if n > 0 {
+                amplitude *= alpha / (n as f64).sqrt();
+            }
+            data[n] = amplitude;
+        }
+
+        let norm: f64 = data.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+        data.mapv_inplace(|x| x / norm);
+        Ok(Self { data })
+    }
+
+    pub fn bloch(dim: usize, theta: f64, phi: f64) -> Result<Self> {
+        if dim < 2 {
+            return Err(Error::InvalidParameter(format!(
+                "Bloch state needs at least two levels, got dimension {}",
+                dim
+            )));
+        }
+        let mut data = Array1::zeros(dim);
+        data[0] = Complex64::new((theta / 2.0).cos(), 0.0);
+        data[1] = Complex64::from_polar((theta / 2.0).sin(), phi);
+        Ok(Self { data })
+    }
+
     pub fn random(dim: usize) -> Self {
         use rand::Rng;
-        let mut rng = rand::rng();
+        let mut rng = crate::utils::performance::seeded_rng(None);
 
         let mut data = Array1::zeros(dim);
         for i in 0..dim {
@@ -48,6 +106,35 @@ impl QuantumState {
         &self.data
     }
 
+    pub fn as_slice(&self) -> &[Complex64] {
+        self.data
+            .as_slice()
+            .expect("state amplitudes are contiguous")
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [Complex64] {
+        self.data
+            .as_slice_mut()
+            .expect("state amplitudes are contiguous")
+    }
+
+    pub fn copy_from_slice(&mut self, amplitudes: &[Complex64]) -> Result<()> {
+        if amplitudes.len() != self.dim() {
+            return Err(Error::dimension_mismatch(self.dim(), amplitudes.len()));
+        }
+        check_normalized(&ArrayView1::from(amplitudes), &Tolerances::global())?;
+        self.as_mut_slice().copy_from_slice(amplitudes);
+        Ok(())
+    }
+
+    pub fn normalize(&mut self) -> f64 {
+        normalize_amplitudes(self.data.view_mut())
+    }
+
+    pub fn into_vec(self) -> Vec<Complex64> {
+        self.data.into_raw_vec_and_offset().0
+    }
+
     pub fn to_density_matrix(&self) -> DensityMatrix {
         let dim = self.dim();
         let mut rho = Array2::zeros((dim, dim));
@@ -69,6 +156,10 @@ pub struct DensityMatrix {
 
 impl DensityMatrix {
     pub fn new(data: Array2<Complex64>) -> Result<Self> {
+        Self::with_tolerances(data, &Tolerances::global())
+    }
+
+    pub fn with_tolerances(data: Array2<Complex64>, tolerances: &Tolerances) -> Result<Self> {
         use crate::utils::math::{is_hermitian, trace};
 
         if data.nrows() != data.ncols() {
@@ -78,14 +169,14 @@ impl DensityMatrix {
             });
         }
 
-        if !is_hermitian(&data.view(), 1e-10) {
+        if !is_hermitian(&data.view(), tolerances.hermiticity) {
             return Err(Error::InvalidParameter(
                 "Density matrix must be Hermitian".to_string(),
             ));
         }
 
         let tr = trace(&data.view());
-        if (tr.re - 1.0).abs() > 1e-10 || tr.im.abs() > 1e-10 {
+        if (tr.re - 1.0).abs() > tolerances.trace || tr.im.abs() > tolerances.trace {
             return Err(Error::InvalidParameter(format!(
                 "Density matrix must have trace 1, got {}",
                 tr
@@ -96,9 +187,31 @@ impl DensityMatrix {
     }
 
     pub fn new_unchecked(data: Array2<Complex64>) -> Self {
+        let data = if data.is_standard_layout() {
+            data
+        } else {
+            data.as_standard_layout().into_owned()
+        };
         Self { data }
     }
 
+    pub fn from_slice(entries: &[Complex64], dim: usize) -> Result<Self> {
+        let data = Array2::from_shape_vec((dim, dim), entries.to_vec())
+            .map_err(|_| Error::dimension_mismatch(dim * dim, entries.len()))?;
+        Self::new(data)
+    }
+
+    pub fn from_slice_mut(
+        entries: &mut [Complex64],
+        dim: usize,
+    ) -> Result<DensityMatrixViewMut<'_>> {
+        let len = entries.len();
+        let data = ArrayViewMut2::from_shape((dim, dim), entries)
+            .map_err(|_| Error::dimension_mismatch(dim * dim, len))?;
+        Self::with_tolerances(data.to_owned(), &Tolerances::global())?;
+        Ok(DensityMatrixViewMut { data })
+    }
+
     pub fn maximally_mixed(dim: usize) -> Self {
         let mut data = Array2::zeros((dim, dim));
         let weight = 1.0 / dim as f64;
@@ -122,6 +235,26 @@ impl DensityMatrix {
         &mut self.data
     }
 
+    pub fn as_slice(&self) -> &[Complex64] {
+        self.data
+            .as_slice()
+            .expect("density matrix entries are contiguous")
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [Complex64] {
+        self.data
+            .as_slice_mut()
+            .expect("density matrix entries are contiguous")
+    }
+
+    pub fn copy_from_slice(&mut self, entries: &[Complex64]) -> Result<()> {
+        if entries.len() != self.data.len() {
+            return Err(Error::dimension_mismatch(self.data.len(), entries.len()));
+        }
+        self.as_mut_slice().copy_from_slice(entries);
+        Ok(())
+    }
+
     pub fn purity(&self) -> f64 {
         use crate::utils::math::trace;
 
@@ -146,11 +279,135 @@ impl DensityMatrix {
     }
 }
 
+pub struct StateViewMut<'a> {
+    data: ArrayViewMut1<'a, Complex64>,
+}
+
+impl StateViewMut<'_> {
+    pub fn dim(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn data(&self) -> ArrayView1<'_, Complex64> {
+        self.data.view()
+    }
+
+    pub fn data_mut(&mut self) -> ArrayViewMut1<'_, Complex64> {
+        self.data.view_mut()
+    }
+
+    pub fn assign(&mut self, state: &QuantumState) -> Result<()> {
+        if state.dim() != self.dim() {
+            return Err(Error::dimension_mismatch(self.dim(), state.dim()));
+        }
+        self.data.assign(state.data());
+        Ok(())
+    }
+
+    pub fn normalize(&mut self) -> f64 {
+        normalize_amplitudes(self.data.view_mut())
+    }
+
+    pub fn to_owned(&self) -> QuantumState {
+        QuantumState {
+            data: self.data.to_owned(),
+        }
+    }
+}
+
+pub struct DensityMatrixViewMut<'a> {
+    data: ArrayViewMut2<'a, Complex64>,
+}
+
+impl DensityMatrixViewMut<'_> {
+    pub fn dim(&self) -> usize {
+        self.data.nrows()
+    }
+
+    pub fn data(&self) -> ArrayView2<'_, Complex64> {
+        self.data.view()
+    }
+
+    pub fn data_mut(&mut self) -> ArrayViewMut2<'_, Complex64> {
+        self.data.view_mut()
+    }
+
+    pub fn assign(&mut self, rho: &DensityMatrix) -> Result<()> {
+        if rho.dim() != self.dim() {
+            return Err(Error::dimension_mismatch(self.dim(), rho.dim()));
+        }
+        self.data.assign(rho.data());
+        Ok(())
+    }
+
+    pub fn to_owned(&self) -> DensityMatrix {
+        DensityMatrix::new_unchecked(self.data.to_owned())
+    }
+}
+
+fn check_normalized(data: &ArrayView1<Complex64>, tolerances: &Tolerances) -> Result<()> {
+    let norm_sq: f64 = data.iter().map(|x| x.norm_sqr()).sum();
+    if (norm_sq - 1.0).abs() > tolerances.normalization {
+        return Err(Error::InvalidParameter(format!(
+            "State must be normalized, got norm^2 = {}",
+            norm_sq
+        )));
+    }
+    Ok(())
+}
+
+fn normalize_amplitudes(mut data: ArrayViewMut1<Complex64>) -> f64 {
+    let norm = data.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        data.mapv_inplace(|x| x / norm);
+    }
+    norm
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use approx::assert_relative_eq;
 
+    #[test]
+    fn test_single_precision_tolerances() {
+        let amplitude = Complex64::new((0.5f64 + 1e-7).sqrt(), 0.0);
+        let data = Array1::from_vec(vec![amplitude, amplitude]);
+        assert!(QuantumState::with_tolerances(data.clone(), &Tolerances::DEFAULT).is_err());
+        assert!(QuantumState::with_tolerances(data, &Tolerances::single_precision()).is_ok());
+
+        let mut rho = DensityMatrix::maximally_mixed(2).data().clone();
+        rho[[0, 1]] = Complex64::new(1e-7, 0.0);
+        assert!(DensityMatrix::with_tolerances(rho.clone(), &Tolerances::DEFAULT).is_err());
+        assert!(DensityMatrix::with_tolerances(rho, &Tolerances::single_precision()).is_ok());
+        assert!(Tolerances::uniform(0.0).validate().is_err());
+    }
+
+    #[test]
+    fn test_borrowed_buffers_are_updated_in_place() {
+        let h = Complex64::new(0.5f64.sqrt(), 0.0);
+        let mut buffer = vec![h, h, Complex64::new(0.0, 0.0)];
+        let mut view = QuantumState::from_slice_mut(&mut buffer).unwrap();
+        view.data_mut()[1] = -h;
+        view.assign(&QuantumState::fock(3, 2).unwrap()).unwrap();
+        assert!(view.assign(&QuantumState::ground_state(2)).is_err());
+        assert_eq!(buffer[2], Complex64::new(1.0, 0.0));
+        assert!(QuantumState::from_slice_mut(&mut [h]).is_err());
+
+        let mut psi = QuantumState::ground_state(2);
+        let address = psi.as_slice().as_ptr();
+        psi.copy_from_slice(&[h, h]).unwrap();
+        psi.as_mut_slice()[1] = Complex64::new(1.0, 0.0);
+        assert_relative_eq!(psi.normalize(), 1.5f64.sqrt(), epsilon = 1e-12);
+        assert_eq!(psi.as_slice().as_ptr(), address);
+        assert!(psi.copy_from_slice(&[h, h, h]).is_err());
+
+        let mut entries = DensityMatrix::maximally_mixed(2).as_slice().to_vec();
+        let rho = DensityMatrix::from_slice_mut(&mut entries, 2).unwrap();
+        assert_relative_eq!(rho.to_owned().purity(), 0.5, epsilon = 1e-12);
+        assert!(DensityMatrix::from_slice(&entries[..3], 2).is_err());
+    }
+
     #[test]
     fn test_ground_state() {
         let psi = QuantumState::ground_state(3);
@@ -159,6 +416,24 @@ mod tests {
         assert_relative_eq!(psi.data()[1].norm_sqr(), 0.0);
     }
 
+    #[test]
+    fn test_coherent_and_bloch_states() {
+        let alpha = Complex64::new(1.0, 0.5);
+        let psi = QuantumState::coherent(30, alpha).unwrap();
+        let mean_n: f64 = psi
+            .data()
+            .iter()
+            .enumerate()
+            .map(|(n, c)| n as f64 * c.norm_sqr())
+            .sum();
+        assert_relative_eq!(mean_n, alpha.norm_sqr(), epsilon = 1e-10);
+
+        let psi = QuantumState::bloch(2, std::f64::consts::PI, 0.0).unwrap();
+        assert_relative_eq!(psi.data()[1].norm_sqr(), 1.0, epsilon = 1e-12);
+        assert!(QuantumState::bloch(1, 0.0, 0.0).is_err());
+        assert!(QuantumState::fock(3, 3).is_err());
+    }
+
     #[test]
     fn test_density_matrix_from_pure_state() {
         let psi = QuantumState::ground_state(2);
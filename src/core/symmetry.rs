@@ -0,0 +1,300 @@
+use crate::core::{Hamiltonian, QuantumState};
+use crate::utils::{Error, Result};
+use ndarray::{Array1, Array2};
+use num_complex::Complex64;
+
+const SAMPLE_TIMES: usize = 8;
+const COUPLING_TOLERANCE: f64 = 1e-12;
+const OCCUPATION_TOLERANCE: f64 = 1e-14;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sector {
+    pub charge: Option<i64>,
+    pub indices: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SectorDecomposition {
+    dim: usize,
+    sectors: Vec<Sector>,
+}
+
+impl SectorDecomposition {
+    pub fn detect(hamiltonian: &dyn Hamiltonian) -> Self {
+        let dim = hamiltonian.dim();
+        let mut parent: Vec<usize> = (0..dim).collect();
+        for (i, j) in couplings(hamiltonian) {
+            let (a, b) = (find(&mut parent, i), find(&mut parent, j));
+            parent[a.max(b)] = a.min(b);
+        }
+
+        let mut sectors: Vec<Sector> = Vec::new();
+        let mut roots: Vec<usize> = Vec::new();
+        for i in 0..dim {
+            let root = find(&mut parent, i);
+            match roots.iter().position(|&r| r == root) {
+                Some(k) => sectors[k].indices.push(i),
+                None => {
+                    roots.push(root);
+                    sectors.push(Sector {
+                        charge: None,
+                        indices: vec![i],
+                    });
+                }
+            }
+        }
+        Self { dim, sectors }
+    }
+
+    pub fn from_charges(hamiltonian: &dyn Hamiltonian, charges: &[i64]) -> Result<Self> {
+        let dim = hamiltonian.dim();
+        if charges.len() != dim {
+            return Err(Error::DimensionMismatch {
+                expected: dim,
+                actual: charges.len(),
+            });
+        }
+        if let Some((i, j)) = couplings(hamiltonian).find(|&(i, j)| charges[i] != charges[j]) {
+            return Err(Error::InvalidParameter(format!(
+                "Hamiltonian couples basis states {} and {} with charges {} and {}, \
+                 so the symmetry is not conserved",
+                i, j, charges[i], charges[j]
+            )));
+        }
+
+        let mut labels: Vec<i64> = charges.to_vec();
+        labels.sort_unstable();
+        labels.dedup();
+        let sectors = labels
+            .into_iter()
+            .map(|charge| Sector {
+                charge: Some(charge),
+                indices: (0..dim).filter(|&i| charges[i] == charge).collect(),
+            })
+            .collect();
+        Ok(Self { dim, sectors })
+    }
+
+    pub fn parity(hamiltonian: &dyn Hamiltonian, numbers: &[i64]) -> Result<Self> {
+        let parities: Vec<i64> = numbers.iter().map(|n| n.rem_euclid(2)).collect();
+        Self::from_charges(hamiltonian, &parities)
+    }
+
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    pub fn sectors(&self) -> &[Sector] {
+        &self.sectors
+    }
+
+    pub fn is_trivial(&self) -> bool {
+        self.sectors.len() <= 1
+    }
+
+    pub fn occupied(&self, state: &QuantumState) -> Vec<&Sector> {
+        self.sectors
+            .iter()
+            .filter(|sector| {
+                sector
+                    .indices
+                    .iter()
+                    .any(|&i| state.data()[i].norm_sqr() > OCCUPATION_TOLERANCE)
+            })
+            .collect()
+    }
+
+    pub fn subspace(&self, state: &QuantumState) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .occupied(state)
+            .into_iter()
+            .flat_map(|sector| sector.indices.iter().copied())
+            .collect();
+        indices.sort_unstable();
+        indices
+    }
+}
+
+fn couplings(hamiltonian: &dyn Hamiltonian) -> impl Iterator<Item = (usize, usize)> {
+    let dim = hamiltonian.dim();
+    let samples = if hamiltonian.is_time_independent() {
+        1
+    } else {
+        SAMPLE_TIMES
+    };
+    let span = hamiltonian.period().unwrap_or(1.0);
+
+    let mut coupled = Array2::from_elem((dim, dim), false);
+    let mut h = Array2::zeros((dim, dim));
+    for k in 0..samples {
+        let t = span * (k as f64 + 0.5) / samples as f64;
+        crate::core::integrator::compute_hamiltonian(hamiltonian, t, &mut h);
+        let scale = h.iter().map(|x| x.norm()).fold(0.0, f64::max);
+        coupled.zip_mut_with(&h, |c, x| *c |= x.norm() > COUPLING_TOLERANCE * scale);
+    }
+
+    (0..dim)
+        .flat_map(move |i| (0..dim).map(move |j| (i, j)))
+        .filter(move |&(i, j)| i != j && coupled[[i, j]])
+}
+
+fn find(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+pub struct SubspaceHamiltonian {
+    parent: Box<dyn Hamiltonian>,
+    indices: Vec<usize>,
+}
+
+impl SubspaceHamiltonian {
+    pub fn new(parent: Box<dyn Hamiltonian>, indices: Vec<usize>) -> Result<Self> {
+        if let Some(&i) = indices.iter().find(|&&i| i >= parent.dim()) {
+            return Err(Error::InvalidParameter(format!(
+                "Subspace index {} is outside a Hilbert space of dimension {}",
+                i,
+                parent.dim()
+            )));
+        }
+        Ok(Self { parent, indices })
+    }
+
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+impl Hamiltonian for SubspaceHamiltonian {
+    fn dim(&self) -> usize {
+        self.indices.len()
+    }
+
+    fn compute(&self, t: f64, out: &mut Array2<Complex64>) {
+        let dim = self.parent.dim();
+        let mut full = Array2::zeros((dim, dim));
+        self.parent.compute(t, &mut full);
+        *out = restrict_operator(&full, &self.indices);
+    }
+
+    fn is_time_independent(&self) -> bool {
+        self.parent.is_time_independent()
+    }
+
+    fn period(&self) -> Option<f64> {
+        self.parent.period()
+    }
+}
+
+pub fn restrict_operator(matrix: &Array2<Complex64>, indices: &[usize]) -> Array2<Complex64> {
+    Array2::from_shape_fn((indices.len(), indices.len()), |(a, b)| {
+        matrix[[indices[a], indices[b]]]
+    })
+}
+
+pub fn restrict_state(state: &QuantumState, indices: &[usize]) -> Result<QuantumState> {
+    let data: Array1<Complex64> = indices.iter().map(|&i| state.data()[i]).collect();
+    let norm = data.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+    QuantumState::new(data.mapv(|x| x / norm))
+}
+
+pub fn embed(amplitudes: &[Complex64], indices: &[usize], dim: usize) -> Vec<Complex64> {
+    let mut full = vec![Complex64::new(0.0, 0.0); dim];
+    for (&i, &amplitude) in indices.iter().zip(amplitudes) {
+        full[i] = amplitude;
+    }
+    full
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::hamiltonian::TimeIndependentHamiltonian;
+    use crate::core::observables::PopulationOperator;
+    use crate::simulation::SimulationBuilder;
+    use approx::assert_relative_eq;
+
+    const LEVELS: usize = 3;
+
+    fn two_mode_hopping(omega: f64, j: f64) -> TimeIndependentHamiltonian {
+        let dim = LEVELS * LEVELS;
+        let mut h = Array2::zeros((dim, dim));
+        for na in 0..LEVELS {
+            for nb in 0..LEVELS {
+                let k = na * LEVELS + nb;
+                h[[k, k]] = Complex64::new(omega * (na + nb) as f64, 0.0);
+                if na + 1 < LEVELS && nb > 0 {
+                    let target = (na + 1) * LEVELS + nb - 1;
+                    let amplitude = j * (((na + 1) * nb) as f64).sqrt();
+                    h[[target, k]] = Complex64::new(amplitude, 0.0);
+                    h[[k, target]] = Complex64::new(amplitude, 0.0);
+                }
+            }
+        }
+        TimeIndependentHamiltonian::new(h)
+    }
+
+    fn total_number() -> Vec<i64> {
+        (0..LEVELS * LEVELS)
+            .map(|k| (k / LEVELS + k % LEVELS) as i64)
+            .collect()
+    }
+
+    #[test]
+    fn test_detects_number_sectors() {
+        let h = two_mode_hopping(1.0, 0.4);
+        let detected = SectorDecomposition::detect(&h);
+        let sizes: Vec<usize> = detected.sectors().iter().map(|s| s.indices.len()).collect();
+        assert_eq!(sizes, [1, 2, 3, 2, 1]);
+
+        let number = SectorDecomposition::from_charges(&h, &total_number()).unwrap();
+        assert_eq!(number.sectors()[2].charge, Some(2));
+        assert_eq!(number.sectors()[2].indices, [2, 4, 6]);
+        assert_eq!(
+            SectorDecomposition::parity(&h, &total_number())
+                .unwrap()
+                .sectors()
+                .len(),
+            2
+        );
+
+        let mode_a: Vec<i64> = (0..LEVELS * LEVELS).map(|k| (k / LEVELS) as i64).collect();
+        assert!(SectorDecomposition::from_charges(&h, &mode_a).is_err());
+    }
+
+    #[test]
+    fn test_reduced_evolution_matches_full() {
+        let state = QuantumState::fock(LEVELS * LEVELS, LEVELS).unwrap();
+        let run = |reduce: bool| {
+            SimulationBuilder::new()
+                .hamiltonian(two_mode_hopping(1.0, 0.4))
+                .initial_state(state.clone())
+                .duration(5.0)
+                .timestep(0.01)
+                .observable(
+                    "p_10",
+                    PopulationOperator::new(LEVELS * LEVELS, LEVELS).unwrap(),
+                )
+                .symmetry_reduction(reduce)
+                .record_states(true)
+                .build()
+                .unwrap()
+                .run()
+                .unwrap()
+        };
+
+        let (full, reduced) = (run(false), run(true));
+        let (p_full, p_reduced) = (
+            full.series("p_10").unwrap().to_vec_real(),
+            reduced.series("p_10").unwrap().to_vec_real(),
+        );
+        for (a, b) in p_full.iter().zip(&p_reduced) {
+            assert_relative_eq!(a, b, epsilon = 1e-10);
+        }
+        assert_relative_eq!(p_reduced[393], 0.0, epsilon = 1e-3);
+        assert_eq!(reduced.snapshots()[0].amplitudes.len(), LEVELS * LEVELS);
+    }
+}
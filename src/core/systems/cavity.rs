@@ -1,6 +1,6 @@
 use crate::core::Hamiltonian;
 use ndarray::Array2;
-use num_complex::Complex64;
+use num_complex::{Complex32, Complex64};
 
 pub struct DrivenCavity {
     pub omega_c: f64,
@@ -41,6 +41,22 @@ impl Hamiltonian for DrivenCavity {
         }
     }
 
+    fn compute_single(&self, t: f64, out: &mut Array2<Complex32>) {
+        out.fill(Complex32::new(0.0, 0.0));
+
+        let drive = self.g * (self.omega_p * t).cos();
+
+        for n in 0..self.dim {
+            out[[n, n]] = Complex32::new((self.omega_c * n as f64) as f32, 0.0);
+        }
+
+        for n in 0..self.dim - 2 {
+            let amp = ((n + 1) * (n + 2)) as f64;
+            out[[n + 2, n]] += Complex32::new((drive * amp.sqrt()) as f32, 0.0);
+            out[[n, n + 2]] += Complex32::new((drive * amp.sqrt()) as f32, 0.0);
+        }
+    }
+
     fn period(&self) -> Option<f64> {
         Some(2.0 * std::f64::consts::PI / self.omega_p)
     }
@@ -1,5 +1,7 @@
+use crate::core::lattice::Lattice;
 use crate::core::Hamiltonian;
-use ndarray::Array2;
+use crate::utils::Result;
+use ndarray::{Array1, Array2};
 use num_complex::Complex64;
 
 pub struct CoupledCavities {
@@ -29,6 +31,14 @@ impl CoupledCavities {
             num_cavities,
         }
     }
+
+    pub fn lattice(&self) -> Result<Lattice> {
+        let mut lattice = Lattice::new(self.num_cavities)?;
+        for (i, &j) in self.couplings.iter().enumerate() {
+            lattice.set_bond(i, i + 1, j)?;
+        }
+        Ok(lattice)
+    }
 }
 
 impl Hamiltonian for CoupledCavities {
@@ -38,20 +48,20 @@ impl Hamiltonian for CoupledCavities {
 
     fn compute(&self, _t: f64, out: &mut Array2<Complex64>) {
         out.fill(Complex64::new(0.0, 0.0));
-
-        out[[0, 0]] = Complex64::new(0.0, 0.0);
-
-        for i in 1..=self.num_cavities {
-            out[[i, i]] = Complex64::new(self.omega_c, 0.0);
+        if let Ok(lattice) = self.lattice() {
+            out.assign(lattice.hamiltonian(self.omega_c).matrix());
         }
+    }
 
-        for (idx, &j) in self.couplings.iter().enumerate() {
-            let i = idx + 1;
-            let j_next = i + 1;
-            if j_next <= self.num_cavities {
-                out[[i, j_next]] = Complex64::new(j, 0.0);
-                out[[j_next, i]] = Complex64::new(j, 0.0);
-            }
+    fn apply(&self, _t: f64, psi: &Array1<Complex64>, out: &mut Array1<Complex64>) {
+        out.fill(Complex64::new(0.0, 0.0));
+        for site in 1..=self.num_cavities {
+            out[site] = psi[site] * self.omega_c;
+        }
+        for (i, &j) in self.couplings.iter().enumerate() {
+            let (a, b) = (i + 1, i + 2);
+            out[a] += psi[b] * j;
+            out[b] += psi[a] * j;
         }
     }
 
@@ -69,5 +79,11 @@ mod tests {
         let ssh = CoupledCavities::ssh(5.0, 1.0, 0.5, 4);
         assert_eq!(ssh.dim(), 5);
         assert_eq!(ssh.couplings.len(), 3);
+
+        let mut h = Array2::zeros((5, 5));
+        ssh.compute(0.0, &mut h);
+        assert_eq!(h[[1, 1]], Complex64::new(5.0, 0.0));
+        assert_eq!(h[[2, 3]], Complex64::new(0.5, 0.0));
+        assert_eq!(h[[1, 3]], Complex64::new(0.0, 0.0));
     }
 }
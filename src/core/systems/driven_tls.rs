@@ -1,6 +1,7 @@
 use crate::core::Hamiltonian;
+use crate::utils::{Error, Result};
 use ndarray::Array2;
-use num_complex::Complex64;
+use num_complex::{Complex32, Complex64};
 
 pub struct DrivenTLS {
     pub omega_0: f64,
@@ -31,6 +32,19 @@ impl DrivenTLS {
     pub fn detuning(&self) -> f64 {
         self.omega_0 - self.omega_d
     }
+
+    pub fn parameter_mut(&mut self, name: &str) -> Result<&mut f64> {
+        match name {
+            "omega_0" => Ok(&mut self.omega_0),
+            "omega_d" => Ok(&mut self.omega_d),
+            "rabi_freq" => Ok(&mut self.rabi_freq),
+            "phase" => Ok(&mut self.phase),
+            other => Err(Error::InvalidParameter(format!(
+                "DrivenTLS has no parameter '{}', expected omega_0, omega_d, rabi_freq or phase",
+                other
+            ))),
+        }
+    }
 }
 
 impl Hamiltonian for DrivenTLS {
@@ -47,9 +61,71 @@ impl Hamiltonian for DrivenTLS {
         out[[1, 0]] = Complex64::new(omega_eff, 0.0);
     }
 
+    fn compute_single(&self, t: f64, out: &mut Array2<Complex32>) {
+        let omega_eff = (self.rabi_freq * (self.omega_d * t + self.phase).cos()) as f32;
+
+        out[[0, 0]] = Complex32::new((self.omega_0 / 2.0) as f32, 0.0);
+        out[[1, 1]] = Complex32::new((-self.omega_0 / 2.0) as f32, 0.0);
+        out[[0, 1]] = Complex32::new(omega_eff, 0.0);
+        out[[1, 0]] = Complex32::new(omega_eff, 0.0);
+    }
+
     fn period(&self) -> Option<f64> {
         Some(2.0 * std::f64::consts::PI / self.omega_d)
     }
+
+    fn compute_dt(&self, t: f64, out: &mut Array2<Complex64>) {
+        let slope = -self.rabi_freq * self.omega_d * (self.omega_d * t + self.phase).sin();
+        out.fill(Complex64::new(0.0, 0.0));
+        out[[0, 1]] = Complex64::new(slope, 0.0);
+        out[[1, 0]] = Complex64::new(slope, 0.0);
+    }
+
+    fn parameter_names(&self) -> Vec<&'static str> {
+        vec!["omega_0", "omega_d", "rabi_freq", "phase"]
+    }
+
+    fn parameter(&self, name: &str) -> Option<f64> {
+        match name {
+            "omega_0" => Some(self.omega_0),
+            "omega_d" => Some(self.omega_d),
+            "rabi_freq" => Some(self.rabi_freq),
+            "phase" => Some(self.phase),
+            _ => None,
+        }
+    }
+
+    fn with_parameter(&self, name: &str, value: f64) -> Result<Box<dyn Hamiltonian>> {
+        let mut tls = Self::with_phase(self.omega_0, self.omega_d, self.rabi_freq, self.phase);
+        *tls.parameter_mut(name)? = value;
+        Ok(Box::new(tls))
+    }
+
+    fn compute_derivative_param(
+        &self,
+        t: f64,
+        param: &str,
+        out: &mut Array2<Complex64>,
+    ) -> Result<()> {
+        let angle = self.omega_d * t + self.phase;
+        let (diagonal, coupling) = match param {
+            "omega_0" => (0.5, 0.0),
+            "omega_d" => (0.0, -self.rabi_freq * t * angle.sin()),
+            "rabi_freq" => (0.0, angle.cos()),
+            "phase" => (0.0, -self.rabi_freq * angle.sin()),
+            other => {
+                return Err(Error::InvalidParameter(format!(
+                "DrivenTLS has no parameter '{}', expected omega_0, omega_d, rabi_freq or phase",
+                other
+            )))
+            }
+        };
+        out[[0, 0]] = Complex64::new(diagonal, 0.0);
+        out[[1, 1]] = Complex64::new(-diagonal, 0.0);
+        out[[0, 1]] = Complex64::new(coupling, 0.0);
+        out[[1, 0]] = Complex64::new(coupling, 0.0);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,91 @@
+use crate::core::DensityMatrix;
+use ndarray::Array2;
+use num_complex::Complex64;
+use std::f64::consts::PI;
+
+pub fn wigner(rho: &DensityMatrix, xvec: &[f64], pvec: &[f64]) -> Array2<f64> {
+    let data = rho.data();
+    let dim = rho.dim();
+    let mut w = Array2::zeros((pvec.len(), xvec.len()));
+
+    for (ip, &p) in pvec.iter().enumerate() {
+        for (ix, &x) in xvec.iter().enumerate() {
+            let alpha = Complex64::new(x, p) / 2f64.sqrt();
+            let b = 4.0 * alpha.norm_sqr();
+
+            let mut value = 0.0;
+            for m in 0..dim {
+                let sign = if m % 2 == 0 { 1.0 } else { -1.0 };
+                value += sign * (data[[m, m]].re * laguerre(m, 0, b));
+
+                let mut power = Complex64::new(1.0, 0.0);
+                let mut ratio = 1.0;
+                for n in (m + 1)..dim {
+                    power *= 2.0 * alpha;
+                    ratio /= n as f64;
+                    if data[[m, n]].norm_sqr() == 0.0 {
+                        continue;
+                    }
+                    value += 2.0
+                        * sign
+                        * (data[[m, n]] * power).re
+                        * ratio.sqrt()
+                        * laguerre(m, n - m, b);
+                }
+            }
+
+            w[[ip, ix]] = value * (-b / 2.0).exp() / PI;
+        }
+    }
+
+    w
+}
+
+fn laguerre(n: usize, alpha: usize, x: f64) -> f64 {
+    let alpha = alpha as f64;
+    let mut previous = 1.0;
+    if n == 0 {
+        return previous;
+    }
+    let mut current = 1.0 + alpha - x;
+    for k in 1..n {
+        let k = k as f64;
+        let next = ((2.0 * k + 1.0 + alpha - x) * current - (k + alpha) * previous) / (k + 1.0);
+        previous = current;
+        current = next;
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::QuantumState;
+    use approx::assert_relative_eq;
+    use ndarray::Array1;
+
+    #[test]
+    fn test_fock_state_wigner() {
+        let vacuum = QuantumState::ground_state(4).to_density_matrix();
+        let w = wigner(&vacuum, &[0.0, 1.0], &[0.0]);
+        assert_relative_eq!(w[[0, 0]], 1.0 / PI, epsilon = 1e-12);
+        assert_relative_eq!(w[[0, 1]], (-1.0f64).exp() / PI, epsilon = 1e-12);
+
+        let mut data = Array1::zeros(4);
+        data[1] = Complex64::new(1.0, 0.0);
+        let one = QuantumState::new(data).unwrap().to_density_matrix();
+        assert_relative_eq!(
+            wigner(&one, &[0.0], &[0.0])[[0, 0]],
+            -1.0 / PI,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn test_superposition_is_displaced_along_x() {
+        let amplitude = Complex64::new(0.5f64.sqrt(), 0.0);
+        let state = QuantumState::new(Array1::from(vec![amplitude, amplitude])).unwrap();
+        let w = wigner(&state.to_density_matrix(), &[-1.0, 1.0], &[0.0]);
+        assert!(w[[0, 1]] > w[[0, 0]]);
+    }
+}
@@ -0,0 +1,472 @@
+use crate::core::wigner::wigner;
+use crate::core::QuantumState;
+use crate::data::report::{plot_err, register_fonts};
+use crate::simulation::StateSnapshot;
+use crate::utils::{Error, Result};
+use ndarray::Array1;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+const WIGNER_POINTS: usize = 101;
+const BLOCH_CIRCLE_POINTS: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnimationKind {
+    Bloch,
+    Wigner,
+    PhotonNumber,
+}
+
+impl AnimationKind {
+    pub const ALL: [AnimationKind; 3] = [
+        AnimationKind::Bloch,
+        AnimationKind::Wigner,
+        AnimationKind::PhotonNumber,
+    ];
+
+    pub fn parse(kind: &str) -> Result<Self> {
+        match kind {
+            "bloch" => Ok(Self::Bloch),
+            "wigner" => Ok(Self::Wigner),
+            "photon_number" | "photon-number" => Ok(Self::PhotonNumber),
+            other => Err(Error::InvalidParameter(format!(
+                "Unknown animation kind '{}' (expected bloch, wigner or photon_number)",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnimationKind::Bloch => "bloch",
+            AnimationKind::Wigner => "wigner",
+            AnimationKind::PhotonNumber => "photon_number",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            AnimationKind::Bloch => "Bloch vector",
+            AnimationKind::Wigner => "Wigner function",
+            AnimationKind::PhotonNumber => "Photon-number distribution",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AnimationFormat {
+    Gif,
+    Apng,
+    Video,
+}
+
+impl AnimationFormat {
+    fn from_path(path: &Path) -> Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("gif") => Ok(Self::Gif),
+            Some("png") | Some("apng") => Ok(Self::Apng),
+            Some("mp4") | Some("webm") | Some("mkv") | Some("mov") => Ok(Self::Video),
+            _ => Err(Error::InvalidParameter(format!(
+                "Unsupported animation format for {} (expected .gif, .png, .apng or .mp4)",
+                path.display()
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct AnimationOptions {
+    pub kind: AnimationKind,
+    pub fps: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl AnimationOptions {
+    pub fn new(kind: AnimationKind) -> Self {
+        Self {
+            kind,
+            fps: 20,
+            width: 640,
+            height: 480,
+        }
+    }
+}
+
+pub struct Animation<'a> {
+    snapshots: &'a [StateSnapshot],
+    options: AnimationOptions,
+}
+
+impl<'a> Animation<'a> {
+    pub fn new(snapshots: &'a [StateSnapshot], options: AnimationOptions) -> Result<Self> {
+        let Some(first) = snapshots.first() else {
+            return Err(Error::InvalidParameter(
+                "No state snapshots to animate. Enable [output] states = true to record them"
+                    .to_string(),
+            ));
+        };
+        let dim = first.amplitudes.len();
+        if let Some(bad) = snapshots.iter().find(|s| s.amplitudes.len() != dim) {
+            return Err(Error::dimension_mismatch(dim, bad.amplitudes.len()));
+        }
+        if options.kind == AnimationKind::Bloch && dim < 2 {
+            return Err(Error::InvalidParameter(format!(
+                "Bloch animation needs at least two levels, got dimension {}",
+                dim
+            )));
+        }
+        if options.fps == 0 || options.width < 64 || options.height < 64 {
+            return Err(Error::InvalidParameter(format!(
+                "Invalid animation settings: {} fps at {}x{}",
+                options.fps, options.width, options.height
+            )));
+        }
+        Ok(Self { snapshots, options })
+    }
+
+    pub fn num_frames(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn render(&self, output: &Path) -> Result<usize> {
+        register_fonts()?;
+        match AnimationFormat::from_path(output)? {
+            AnimationFormat::Gif => self.render_gif(output)?,
+            AnimationFormat::Apng => self.render_apng(output)?,
+            AnimationFormat::Video => self.render_video(output)?,
+        }
+        Ok(self.num_frames())
+    }
+
+    fn render_gif(&self, output: &Path) -> Result<()> {
+        let delay_ms = (1000 / self.options.fps).max(1);
+        let root = BitMapBackend::gif(output, (self.options.width, self.options.height), delay_ms)
+            .map_err(plot_err)?
+            .into_drawing_area();
+        for index in 0..self.num_frames() {
+            self.draw_frame(&root, index)?;
+        }
+        Ok(())
+    }
+
+    fn render_apng(&self, output: &Path) -> Result<()> {
+        let file = std::io::BufWriter::new(std::fs::File::create(output)?);
+        let mut encoder = png::Encoder::new(file, self.options.width, self.options.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .set_animated(self.num_frames() as u32, 0)
+            .and_then(|_| encoder.set_frame_delay(1, self.options.fps.min(u16::MAX as u32) as u16))
+            .map_err(apng_err)?;
+
+        let mut writer = encoder.write_header().map_err(apng_err)?;
+        for index in 0..self.num_frames() {
+            writer
+                .write_image_data(&self.frame_rgb(index)?)
+                .map_err(apng_err)?;
+        }
+        writer.finish().map_err(apng_err)
+    }
+
+    fn render_video(&self, output: &Path) -> Result<()> {
+        let (width, height) = (self.options.width, self.options.height);
+        let mut ffmpeg = Command::new("ffmpeg")
+            .args(["-y", "-loglevel", "error", "-f", "rawvideo", "-pix_fmt", "rgb24"])
+            .args(["-s", &format!("{}x{}", width, height)])
+            .args(["-r", &self.options.fps.to_string()])
+            .args(["-i", "-", "-vf", "pad=ceil(iw/2)*2:ceil(ih/2)*2"])
+            .args(["-pix_fmt", "yuv420p"])
+            .arg(output)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|e| {
+                Error::Other(anyhow::anyhow!(
+                    "Failed to start ffmpeg ({}). Install ffmpeg or export a .gif or .png animation instead",
+                    e
+                ))
+            })?;
+
+        let mut stdin = ffmpeg.stdin.take().expect("ffmpeg stdin is piped");
+        for index in 0..self.num_frames() {
+            stdin.write_all(&self.frame_rgb(index)?)?;
+        }
+        drop(stdin);
+
+        let status = ffmpeg.wait()?;
+        if !status.success() {
+            return Err(Error::Other(anyhow::anyhow!(
+                "ffmpeg exited with {} while writing {}",
+                status,
+                output.display()
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn frame_rgb(&self, index: usize) -> Result<Vec<u8>> {
+        let (width, height) = (self.options.width, self.options.height);
+        let mut buffer = vec![0u8; width as usize * height as usize * 3];
+        {
+            let root = BitMapBackend::with_buffer(&mut buffer, (width, height)).into_drawing_area();
+            self.draw_frame(&root, index)?;
+        }
+        Ok(buffer)
+    }
+
+    fn draw_frame<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        index: usize,
+    ) -> Result<()>
+    where
+        DB::ErrorType: 'static,
+    {
+        root.fill(&WHITE).map_err(plot_err)?;
+        let snapshot = &self.snapshots[index];
+        let caption = format!("{}, t = {:.3}", self.options.kind.title(), snapshot.time);
+
+        match self.options.kind {
+            AnimationKind::Bloch => self.draw_bloch(root, &caption, index)?,
+            AnimationKind::Wigner => draw_wigner(root, &caption, snapshot)?,
+            AnimationKind::PhotonNumber => draw_photon_number(root, &caption, snapshot)?,
+        }
+
+        root.present().map_err(plot_err)?;
+        Ok(())
+    }
+
+    fn draw_bloch<DB: DrawingBackend>(
+        &self,
+        root: &DrawingArea<DB, Shift>,
+        caption: &str,
+        index: usize,
+    ) -> Result<()>
+    where
+        DB::ErrorType: 'static,
+    {
+        let mut chart = ChartBuilder::on(root)
+            .caption(caption, ("sans-serif", 22))
+            .margin(10)
+            .build_cartesian_3d(-1.1..1.1, -1.1..1.1, -1.1..1.1)
+            .map_err(plot_err)?;
+        chart.with_projection(|mut projection| {
+            projection.yaw = 0.6;
+            projection.pitch = 0.3;
+            projection.scale = 0.9;
+            projection.into_matrix()
+        });
+        chart
+            .configure_axes()
+            .light_grid_style(BLACK.mix(0.1))
+            .max_light_lines(2)
+            .draw()
+            .map_err(plot_err)?;
+
+        let circle = |plane: fn(f64, f64) -> (f64, f64, f64)| {
+            (0..=BLOCH_CIRCLE_POINTS).map(move |k| {
+                let phi = 2.0 * std::f64::consts::PI * k as f64 / BLOCH_CIRCLE_POINTS as f64;
+                plane(phi.cos(), phi.sin())
+            })
+        };
+        for plane in [
+            (|c, s| (c, 0.0, s)) as fn(f64, f64) -> (f64, f64, f64),
+            |c, s| (c, s, 0.0),
+            |c, s| (0.0, s, c),
+        ] {
+            chart
+                .draw_series(LineSeries::new(circle(plane), BLACK.mix(0.3)))
+                .map_err(plot_err)?;
+        }
+
+        let points: Vec<_> = self.snapshots[..=index]
+            .iter()
+            .map(|s| to_plot_coords(bloch_vector(&s.amplitudes)))
+            .collect();
+        chart
+            .draw_series(LineSeries::new(points.iter().copied(), BLUE.mix(0.6)))
+            .map_err(plot_err)?;
+
+        let tip = points[index];
+        chart
+            .draw_series(LineSeries::new([(0.0, 0.0, 0.0), tip], RED.stroke_width(3)))
+            .map_err(plot_err)?;
+        chart
+            .draw_series(std::iter::once(Circle::new(tip, 6, RED.filled())))
+            .map_err(plot_err)?;
+        Ok(())
+    }
+}
+
+fn bloch_vector(amplitudes: &[num_complex::Complex64]) -> (f64, f64, f64) {
+    let coherence = amplitudes[0].conj() * amplitudes[1];
+    (
+        2.0 * coherence.re,
+        2.0 * coherence.im,
+        amplitudes[0].norm_sqr() - amplitudes[1].norm_sqr(),
+    )
+}
+
+fn to_plot_coords((x, y, z): (f64, f64, f64)) -> (f64, f64, f64) {
+    (x, z, y)
+}
+
+fn draw_wigner<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    caption: &str,
+    snapshot: &StateSnapshot,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let dim = snapshot.amplitudes.len();
+    let extent = (2.0 * dim as f64 + 1.0).sqrt() + 1.0;
+    let grid: Vec<f64> = (0..WIGNER_POINTS)
+        .map(|k| -extent + 2.0 * extent * k as f64 / (WIGNER_POINTS - 1) as f64)
+        .collect();
+
+    let state = QuantumState::new(Array1::from(snapshot.amplitudes.clone()))?;
+    let w = wigner(&state.to_density_matrix(), &grid, &grid);
+    let scale = std::f64::consts::FRAC_1_PI;
+
+    let mut chart = ChartBuilder::on(root)
+        .caption(caption, ("sans-serif", 22))
+        .margin(15)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d(-extent..extent, -extent..extent)
+        .map_err(plot_err)?;
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .x_desc("x")
+        .y_desc("p")
+        .draw()
+        .map_err(plot_err)?;
+
+    let step = 2.0 * extent / (WIGNER_POINTS - 1) as f64;
+    chart
+        .draw_series(w.indexed_iter().map(|((ip, ix), &value)| {
+            let (x, p) = (grid[ix] - step / 2.0, grid[ip] - step / 2.0);
+            Rectangle::new(
+                [(x, p), (x + step, p + step)],
+                diverging_color(value / scale).filled(),
+            )
+        }))
+        .map_err(plot_err)?;
+    Ok(())
+}
+
+fn diverging_color(level: f64) -> RGBColor {
+    let level = level.clamp(-1.0, 1.0);
+    let fade = (255.0 * (1.0 - level.abs())) as u8;
+    if level >= 0.0 {
+        RGBColor(255, fade, fade)
+    } else {
+        RGBColor(fade, fade, 255)
+    }
+}
+
+fn draw_photon_number<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    caption: &str,
+    snapshot: &StateSnapshot,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    let dim = snapshot.amplitudes.len();
+    let mut chart = ChartBuilder::on(root)
+        .caption(caption, ("sans-serif", 22))
+        .margin(15)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d(-0.5..dim as f64 - 0.5, 0.0..1.0)
+        .map_err(plot_err)?;
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_desc("n")
+        .y_desc("P(n)")
+        .draw()
+        .map_err(plot_err)?;
+
+    chart
+        .draw_series(snapshot.amplitudes.iter().enumerate().map(|(n, c)| {
+            let n = n as f64;
+            Rectangle::new([(n - 0.4, 0.0), (n + 0.4, c.norm_sqr())], BLUE.filled())
+        }))
+        .map_err(plot_err)?;
+    Ok(())
+}
+
+fn apng_err(e: png::EncodingError) -> Error {
+    Error::Other(anyhow::anyhow!("Failed to encode animated PNG: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex64;
+
+    fn rabi_snapshots() -> Vec<StateSnapshot> {
+        (0..4)
+            .map(|k| {
+                let theta = k as f64 * 0.4;
+                StateSnapshot {
+                    time: k as f64,
+                    amplitudes: vec![
+                        Complex64::new(theta.cos(), 0.0),
+                        Complex64::new(0.0, -theta.sin()),
+                        Complex64::new(0.0, 0.0),
+                    ],
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_render_gif_and_apng() {
+        let dir = std::env::temp_dir().join("chronophoton_animation_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let snapshots = rabi_snapshots();
+
+        for (kind, file) in [
+            (AnimationKind::Bloch, "bloch.gif"),
+            (AnimationKind::Wigner, "wigner.png"),
+            (AnimationKind::PhotonNumber, "photon_number.gif"),
+        ] {
+            let mut options = AnimationOptions::new(kind);
+            options.width = 160;
+            options.height = 120;
+            let frames = Animation::new(&snapshots, options)
+                .unwrap()
+                .render(&dir.join(file))
+                .unwrap();
+            assert_eq!(frames, 4);
+            assert!(std::fs::metadata(dir.join(file)).unwrap().len() > 0);
+        }
+
+        let data = std::fs::read(dir.join("wigner.png")).unwrap();
+        assert!(data.windows(4).any(|w| w == b"acTL"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_rejects_bad_inputs() {
+        let options = AnimationOptions::new(AnimationKind::Bloch);
+        assert!(Animation::new(&[], options).is_err());
+        assert!(AnimationKind::parse("husimi").is_err());
+
+        let snapshots = rabi_snapshots();
+        let animation = Animation::new(&snapshots, options).unwrap();
+        assert!(animation.render(Path::new("bloch.avi")).is_err());
+    }
+}
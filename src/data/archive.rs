@@ -0,0 +1,178 @@
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+
+const FORMAT_VERSION: u32 = 1;
+const EXCLUDED_EXTENSIONS: [&str; 2] = ["bin", "ckpt"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub archive: ArchiveInfo,
+    pub environment: EnvironmentInfo,
+    pub files: Vec<ArchivedFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveInfo {
+    pub format_version: u32,
+    pub run: String,
+    pub created: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvironmentInfo {
+    pub chronophoton_version: String,
+    pub os: String,
+    pub arch: String,
+}
+
+impl EnvironmentInfo {
+    pub fn current() -> Self {
+        Self {
+            chronophoton_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedFile {
+    pub path: String,
+    pub kind: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+fn file_kind(path: &Path) -> &'static str {
+    match path.extension().and_then(|s| s.to_str()) {
+        _ if path.file_name().is_some_and(|n| n == "config.toml") => "config",
+        Some("csv" | "gz" | "zst" | "mat") => "data",
+        Some("svg" | "png" | "pdf") => "figure",
+        Some("md" | "tex") => "summary",
+        Some("log") => "log",
+        _ => "other",
+    }
+}
+
+pub struct Archive;
+
+impl Archive {
+    pub fn create(run_dir: &Path, output: &Path) -> Result<ArchiveManifest> {
+        if !run_dir.join("config.toml").is_file() {
+            return Err(Error::Config(format!(
+                "{} is not a run directory (config.toml is missing)",
+                run_dir.display()
+            )));
+        }
+        let run = run_dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or("run")
+            .to_string();
+
+        let mut sources = Vec::new();
+        collect_files(run_dir, &mut sources)?;
+        sources.sort();
+
+        let zip_error =
+            |e: zip::result::ZipError| Error::Serialization(format!("Zip error: {}", e));
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(output)?);
+
+        let mut files = Vec::new();
+        for source in sources {
+            let relative = source
+                .strip_prefix(run_dir)
+                .expect("collected below run_dir")
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let contents = std::fs::read(&source)?;
+
+            zip.start_file(format!("{}/{}", run, relative), options)
+                .map_err(zip_error)?;
+            zip.write_all(&contents)?;
+
+            files.push(ArchivedFile {
+                kind: file_kind(&source).to_string(),
+                path: relative,
+                size: contents.len() as u64,
+                sha256: Sha256::digest(&contents)
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect(),
+            });
+        }
+
+        let manifest = ArchiveManifest {
+            archive: ArchiveInfo {
+                format_version: FORMAT_VERSION,
+                run: run.clone(),
+                created: chrono::Utc::now().to_rfc3339(),
+            },
+            environment: EnvironmentInfo::current(),
+            files,
+        };
+        let text = toml::to_string_pretty(&manifest)
+            .map_err(|e| Error::Serialization(format!("TOML error: {}", e)))?;
+        zip.start_file(format!("{}/manifest.toml", run), options)
+            .map_err(zip_error)?;
+        zip.write_all(text.as_bytes())?;
+        zip.finish().map_err(zip_error)?;
+
+        Ok(manifest)
+    }
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(&path, out)?;
+        } else if !path
+            .extension()
+            .and_then(|s| s.to_str())
+            .is_some_and(|ext| EXCLUDED_EXTENSIONS.contains(&ext))
+        {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::output::CollisionPolicy;
+    use crate::data::{Config, RunDirectory};
+
+    #[test]
+    fn test_archive_bundle() {
+        let root = std::env::temp_dir().join("chronophoton_archive_test");
+        std::fs::remove_dir_all(&root).ok();
+        let config = Config::generate_template("driven_tls").unwrap();
+        let dir = RunDirectory::create_named(&root, "run", CollisionPolicy::Refuse).unwrap();
+        dir.write_config(&config).unwrap();
+        std::fs::write(dir.join("sz.csv"), "time,sz_re,sz_im\n0,1,0\n").unwrap();
+        std::fs::write(dir.results_path(), [0u8; 4]).unwrap();
+
+        let output = root.join("bundle.zip");
+        let manifest = Archive::create(dir.path(), &output).unwrap();
+        let paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, ["config.toml", "sz.csv"]);
+        assert_eq!(manifest.files[1].kind, "data");
+
+        let mut zip = zip::ZipArchive::new(std::fs::File::open(&output).unwrap()).unwrap();
+        assert_eq!(zip.len(), 3);
+        assert!(zip.by_name("run/manifest.toml").is_ok());
+
+        assert!(Archive::create(&root, &root.join("other.zip")).is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
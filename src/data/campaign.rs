@@ -0,0 +1,411 @@
+use crate::data::output::CollisionPolicy;
+use crate::data::{pipeline, Config, RunDirectory};
+use crate::simulation::{JobControl, Scheduler, SteadyStateEstimate};
+use crate::utils::error::ConfigLocation;
+use crate::utils::resources::ResourceUsage;
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignFile {
+    pub campaign: CampaignSettings,
+    pub simulations: Vec<CampaignEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignSettings {
+    pub name: String,
+    #[serde(default)]
+    pub base: Option<PathBuf>,
+    #[serde(default = "default_output_dir")]
+    pub output_dir: PathBuf,
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: usize,
+}
+
+fn default_output_dir() -> PathBuf {
+    PathBuf::from("campaigns")
+}
+
+fn default_max_concurrent() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CampaignEntry {
+    pub name: String,
+    #[serde(default)]
+    pub config: Option<PathBuf>,
+    #[serde(flatten)]
+    pub overrides: toml::Table,
+}
+
+pub struct Campaign {
+    pub settings: CampaignSettings,
+    pub simulations: Vec<Config>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CampaignRun {
+    pub name: String,
+    pub output_dir: PathBuf,
+    pub outcome: std::result::Result<Vec<(String, SteadyStateEstimate)>, String>,
+    pub resources: Option<ResourceUsage>,
+}
+
+pub struct CampaignSummary {
+    pub name: String,
+    pub output_dir: PathBuf,
+    pub runs: Vec<CampaignRun>,
+}
+
+impl Campaign {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        let file: CampaignFile = toml::from_str(&contents).map_err(|e| Error::ConfigAt {
+            location: ConfigLocation {
+                file: Some(path.to_path_buf()),
+                ..ConfigLocation::default()
+            },
+            message: e.message().to_string(),
+        })?;
+
+        let root = path.parent().unwrap_or(Path::new("."));
+        let base = match &file.campaign.base {
+            Some(base) => Some(load_table(&root.join(base))?),
+            None => None,
+        };
+
+        let mut simulations = Vec::with_capacity(file.simulations.len());
+        for entry in file.simulations {
+            if simulations
+                .iter()
+                .any(|c: &Config| c.simulation.name == entry.name)
+            {
+                return Err(Error::Config(format!(
+                    "Duplicate simulation name '{}' in campaign",
+                    entry.name
+                )));
+            }
+
+            let mut table = match (&entry.config, &base) {
+                (Some(config), _) => load_table(&root.join(config))?,
+                (None, Some(base)) => base.clone(),
+                (None, None) => toml::Table::new(),
+            };
+            merge_tables(&mut table, entry.overrides);
+
+            let mut config: Config = serde_path_to_error::deserialize(toml::Value::Table(table))
+                .map_err(|e| Error::ConfigAt {
+                    location: ConfigLocation {
+                        file: Some(path.to_path_buf()),
+                        key: Some(format!("simulations.{}.{}", entry.name, e.path())),
+                        ..ConfigLocation::default()
+                    },
+                    message: e.inner().to_string(),
+                })?;
+            config.simulation.name = entry.name;
+            config.validate()?;
+            simulations.push(config);
+        }
+
+        if simulations.is_empty() {
+            return Err(Error::Config(
+                "Campaign must list at least one simulation".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            settings: file.campaign,
+            simulations,
+        })
+    }
+
+    pub fn run(&self) -> Result<CampaignSummary> {
+        self.run_with_control(&JobControl::new())
+    }
+
+    pub fn run_with_control(&self, control: &JobControl) -> Result<CampaignSummary> {
+        let run_dir = RunDirectory::create_named(
+            &self.settings.output_dir,
+            &self.settings.name,
+            CollisionPolicy::Uniquify,
+        )?;
+        let output_dir = run_dir.path().to_path_buf();
+
+        let scheduler = Scheduler::new(self.settings.max_concurrent);
+        let completed = AtomicUsize::new(0);
+        let runs = scheduler.run(self.simulations.iter().collect(), |config| {
+            let dir = output_dir.join(&config.simulation.name);
+            let outcome = control
+                .check()
+                .and_then(|_| {
+                    tracing::info!("Running campaign simulation '{}'", config.simulation.name);
+                    run_simulation(config, &dir)
+                })
+                .map_err(|e| {
+                    tracing::warn!("Simulation '{}' failed: {}", config.simulation.name, e);
+                    e.to_string()
+                });
+            let done = completed.fetch_add(1, Ordering::Relaxed) + 1;
+            control.set_progress(done as f64 / self.simulations.len() as f64);
+            let (outcome, resources) = match outcome {
+                Ok((estimates, resources)) => (Ok(estimates), resources),
+                Err(e) => (Err(e), None),
+            };
+            CampaignRun {
+                name: config.simulation.name.clone(),
+                output_dir: dir,
+                outcome,
+                resources,
+            }
+        })?;
+
+        Ok(CampaignSummary {
+            name: self.settings.name.clone(),
+            output_dir,
+            runs,
+        })
+    }
+}
+
+impl CampaignSummary {
+    pub fn failed(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.runs.iter().filter_map(|run| match &run.outcome {
+            Ok(_) => None,
+            Err(e) => Some((run.name.as_str(), e.as_str())),
+        })
+    }
+
+    pub fn total_resources(&self) -> Option<ResourceUsage> {
+        self.runs
+            .iter()
+            .filter_map(|run| run.resources)
+            .reduce(|a, b| a.combine(&b))
+    }
+
+    pub fn write_markdown(&self, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(writer, "# Campaign: {}\n", self.name)?;
+        writeln!(
+            writer,
+            "| Simulation | Status | Observable | Steady state |"
+        )?;
+        writeln!(writer, "|---|---|---|---|")?;
+
+        for run in &self.runs {
+            match &run.outcome {
+                Ok(estimates) if estimates.is_empty() => {
+                    writeln!(writer, "| {} | ok | - | - |", run.name)?;
+                }
+                Ok(estimates) => {
+                    for (observable, est) in estimates {
+                        writeln!(
+                            writer,
+                            "| {} | ok | {} | {:.6} ± {:.6} |",
+                            run.name, observable, est.mean, est.std_error
+                        )?;
+                    }
+                }
+                Err(e) => {
+                    writeln!(
+                        writer,
+                        "| {} | failed: {} | - | - |",
+                        run.name,
+                        e.replace('\n', " ")
+                    )?;
+                }
+            }
+        }
+
+        if let Some(total) = self.total_resources() {
+            writeln!(writer, "\n## Resources\n")?;
+            writeln!(
+                writer,
+                "| Simulation | Wall time (s) | CPU time (s) | Peak RSS (MB) |"
+            )?;
+            writeln!(writer, "|---|---|---|---|")?;
+            let measured = self
+                .runs
+                .iter()
+                .filter_map(|run| Some((run.name.as_str(), run.resources?)));
+            for (name, usage) in measured.chain(std::iter::once(("total", total))) {
+                writeln!(
+                    writer,
+                    "| {} | {:.3} | {} | {} |",
+                    name,
+                    usage.wall_time,
+                    usage
+                        .cpu_time
+                        .map_or("-".to_string(), |t| format!("{:.3}", t)),
+                    usage.peak_rss_bytes.map_or("-".to_string(), |b| format!(
+                        "{:.1}",
+                        b as f64 / (1024.0 * 1024.0)
+                    ))
+                )?;
+            }
+        }
+
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+type RunOutcome = (Vec<(String, SteadyStateEstimate)>, Option<ResourceUsage>);
+
+fn run_simulation(config: &Config, dir: &Path) -> Result<RunOutcome> {
+    let dir = RunDirectory::open(dir)?;
+    dir.write_config(config)?;
+
+    // Each run gets its own control so that its progress does not overwrite the
+    // campaign's.
+    let mut results = pipeline::simulate(config, Arc::new(JobControl::new()), None, None)?;
+    pipeline::save_results(&mut results, config, &dir)?;
+    pipeline::write_outputs(&mut results, config, &dir)?;
+
+    let estimates = results
+        .steady_states()
+        .into_iter()
+        .map(|(name, est)| (name.to_string(), est))
+        .collect();
+    Ok((estimates, results.resources().copied()))
+}
+
+fn load_table(path: &Path) -> Result<toml::Table> {
+    let contents = std::fs::read_to_string(path)?;
+    if path.extension().and_then(|s| s.to_str()) == Some("toml") {
+        toml::from_str(&contents)
+            .map_err(|e| Error::Config(format!("{}: {}", path.display(), e.message())))
+    } else {
+        serde_yaml::from_str(&contents)
+            .map_err(|e| Error::Config(format!("{}: {}", path.display(), e)))
+    }
+}
+
+fn merge_tables(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(value)) => {
+                merge_tables(existing, value)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::SimulationResults;
+
+    #[test]
+    fn test_campaign_merges_overrides_into_base() {
+        let dir = std::env::temp_dir().join("chronophoton_campaign_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        Config::generate_template("driven_tls")
+            .unwrap()
+            .save(&dir.join("base.toml"))
+            .unwrap();
+        std::fs::write(
+            dir.join("campaign.toml"),
+            r#"
+[campaign]
+name = "detuning"
+base = "base.toml"
+
+[[simulations]]
+name = "resonant"
+
+[[simulations]]
+name = "detuned"
+simulation.duration = 20.0
+system.parameters.omega_d = 5.5
+"#,
+        )
+        .unwrap();
+
+        let campaign = Campaign::from_file(&dir.join("campaign.toml")).unwrap();
+        assert_eq!(campaign.simulations.len(), 2);
+
+        let detuned = &campaign.simulations[1];
+        assert_eq!(detuned.simulation.name, "detuned");
+        assert_eq!(detuned.simulation.duration, 20.0);
+        assert_eq!(detuned.system.parameters.get("omega_d"), Some(5.5));
+        assert_eq!(detuned.system.parameters.get("omega_0"), Some(5.0));
+        assert_eq!(campaign.simulations[0].simulation.duration, 50.0);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_campaign_runs_kicked_systems_and_saves_results() {
+        let dir = std::env::temp_dir().join("chronophoton_campaign_run_test");
+        std::fs::remove_dir_all(&dir).ok();
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut base = Config::generate_template("driven_tls").unwrap();
+        base.simulation.duration = 2.0;
+        base.save(&dir.join("base.toml")).unwrap();
+        std::fs::write(
+            dir.join("campaign.toml"),
+            r#"
+[campaign]
+name = "kicks"
+base = "base.toml"
+output_dir = "out"
+
+[[simulations]]
+name = "plain"
+
+[[simulations]]
+name = "kicked"
+kicks.enabled = true
+kicks.operator = "sigma_x"
+kicks.period = 0.5
+"#,
+        )
+        .unwrap();
+
+        let mut campaign = Campaign::from_file(&dir.join("campaign.toml")).unwrap();
+        campaign.settings.output_dir = dir.join("out");
+        let summary = campaign.run().unwrap();
+        for run in &summary.runs {
+            assert!(run.outcome.is_ok(), "{}: {:?}", run.name, run.outcome);
+            let results = SimulationResults::load(&run.output_dir.join("results.bin")).unwrap();
+            assert!(!results.times().is_empty());
+        }
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_campaign_reports_bad_override_key() {
+        let dir = std::env::temp_dir().join("chronophoton_campaign_bad_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        Config::generate_template("driven_tls")
+            .unwrap()
+            .save(&dir.join("base.toml"))
+            .unwrap();
+        std::fs::write(
+            dir.join("campaign.toml"),
+            "[campaign]\nname = \"c\"\nbase = \"base.toml\"\n\n[[simulations]]\nname = \"a\"\n\
+             system.parameters.omega_dd = 1.0\n",
+        )
+        .unwrap();
+
+        let err = Campaign::from_file(&dir.join("campaign.toml"))
+            .err()
+            .unwrap();
+        assert!(err
+            .location()
+            .unwrap()
+            .key
+            .as_deref()
+            .unwrap()
+            .starts_with("simulations.a."));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,28 +1,37 @@
-use crate::utils::Result;
-use bincode::{Decode, Encode};
+use crate::simulation::SimulationResults;
+use crate::utils::{Error, Result};
+use num_complex::Complex64;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
-#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Checkpoint {
     pub time: f64,
     pub step: usize,
+    #[serde(default)]
+    pub amplitudes: Vec<Complex64>,
+    #[serde(default)]
+    pub results: Option<SimulationResults>,
+    #[serde(default)]
+    pub complete: bool,
 }
 
 impl Checkpoint {
     pub fn save(&self, path: &Path) -> Result<()> {
         let config = bincode::config::standard();
-        let data = bincode::encode_to_vec(self, config)
-            .map_err(|e| crate::utils::Error::Serialization(e.to_string()))?;
-        std::fs::write(path, data)?;
+        let data = bincode::serde::encode_to_vec(self, config)
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, data)?;
+        std::fs::rename(&partial, path)?;
         Ok(())
     }
 
     pub fn load(path: &Path) -> Result<Self> {
         let data = std::fs::read(path)?;
         let config = bincode::config::standard();
-        let (checkpoint, _) = bincode::decode_from_slice(&data, config)
-            .map_err(|e| crate::utils::Error::Serialization(e.to_string()))?;
+        let (checkpoint, _) = bincode::serde::decode_from_slice(&data, config)
+            .map_err(|e| Error::Serialization(e.to_string()))?;
         Ok(checkpoint)
     }
 }
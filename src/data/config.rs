@@ -1,5 +1,10 @@
+use crate::core::lattice::{Lattice, LatticeConfig, LatticeHamiltonian};
+use crate::core::systems::CoupledCavities;
+use crate::core::QuantumState;
+use crate::utils::error::ConfigLocation;
 use crate::utils::{Error, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,7 +17,31 @@ pub struct Config {
     #[serde(default)]
     pub gpu: GpuConfig,
     #[serde(default)]
+    pub initial_state: InitialStateConfig,
+    #[serde(default)]
     pub parameter_sweep: ParameterSweepConfig,
+    #[serde(default)]
+    pub measurement: MeasurementConfig,
+    #[serde(default)]
+    pub tomography: TomographyConfig,
+    #[serde(default)]
+    pub report: ReportConfig,
+    #[serde(default)]
+    pub output: crate::data::output::OutputConfig,
+    #[serde(default)]
+    pub performance: crate::utils::performance::PerformanceConfig,
+    #[serde(default)]
+    pub preemption: crate::simulation::PreemptionConfig,
+    #[serde(default)]
+    pub cumulant: CumulantConfig,
+    #[serde(default)]
+    pub kicks: KickConfig,
+    #[serde(default)]
+    pub spectroscopy: crate::simulation::SpectroscopyConfig,
+    #[serde(default)]
+    pub detection: crate::simulation::DetectionConfig,
+    #[serde(default)]
+    pub tolerances: crate::utils::Tolerances,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -22,18 +51,310 @@ pub struct SimulationConfig {
     pub timestep: f64,
     #[serde(default = "default_integrator")]
     pub integrator: String,
+    #[serde(default = "default_precision")]
+    pub precision: String,
+    #[serde(default = "default_atol")]
+    pub atol: f64,
+    #[serde(default = "default_rtol")]
+    pub rtol: f64,
+    #[serde(default = "default_krylov_dim")]
+    pub krylov_dim: usize,
+    #[serde(default = "default_krylov_tol")]
+    pub krylov_tol: f64,
+    #[serde(default)]
+    pub max_retries: u32,
+    #[serde(default)]
+    pub warmup_duration: f64,
+    #[serde(default)]
+    pub stop_when: Vec<String>,
+    #[serde(default = "default_solver")]
+    pub solver: String,
+    #[serde(default = "default_trajectories")]
+    pub trajectories: usize,
+    #[serde(default)]
+    pub seed: Option<u64>,
 }
 
 fn default_integrator() -> String {
     "rk4".to_string()
 }
 
+fn default_atol() -> f64 {
+    crate::core::IntegratorType::DEFAULT_ATOL
+}
+
+fn default_rtol() -> f64 {
+    crate::core::IntegratorType::DEFAULT_RTOL
+}
+
+fn default_krylov_dim() -> usize {
+    crate::core::IntegratorType::DEFAULT_KRYLOV_DIM
+}
+
+fn default_krylov_tol() -> f64 {
+    crate::core::IntegratorType::DEFAULT_KRYLOV_TOL
+}
+
+impl SimulationConfig {
+    pub fn integrator_type(&self) -> Result<crate::core::IntegratorType> {
+        match crate::core::IntegratorType::parse(&self.integrator, self.atol, self.rtol)? {
+            crate::core::IntegratorType::Krylov { .. } => {
+                crate::core::IntegratorType::krylov(self.krylov_dim, self.krylov_tol)
+            }
+            other => Ok(other),
+        }
+    }
+}
+
+fn default_solver() -> String {
+    "dense".to_string()
+}
+
+fn default_trajectories() -> usize {
+    200
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawSystemConfig", into = "RawSystemConfig")]
 pub struct SystemConfig {
     pub hilbert_dim: usize,
     pub hamiltonian: String,
+    pub parameters: SystemParameters,
+    pub lattice: Option<LatticeConfig>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawSystemConfig {
+    hilbert_dim: usize,
+    hamiltonian: String,
+    #[serde(default)]
+    parameters: BTreeMap<String, f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lattice: Option<LatticeConfig>,
+}
+
+impl TryFrom<RawSystemConfig> for SystemConfig {
+    type Error = String;
+
+    fn try_from(raw: RawSystemConfig) -> std::result::Result<Self, Self::Error> {
+        let parameters =
+            SystemParameters::parse(&raw.hamiltonian, raw.parameters).map_err(|e| match e {
+                Error::Config(message) => message,
+                other => other.to_string(),
+            })?;
+        Ok(Self {
+            hilbert_dim: raw.hilbert_dim,
+            hamiltonian: raw.hamiltonian,
+            parameters,
+            lattice: raw.lattice,
+        })
+    }
+}
+
+impl SystemConfig {
+    fn lattice_model(&self) -> Result<Option<(&LatticeParameters, &LatticeConfig)>> {
+        let (parameters, lattice) = match (&self.parameters, &self.lattice) {
+            (SystemParameters::Lattice(parameters), Some(lattice)) => (parameters, lattice),
+            (SystemParameters::Lattice(_), None) => {
+                return Err(Error::Config(
+                    "System 'lattice' needs a [system.lattice] table".to_string(),
+                ))
+            }
+            (_, Some(_)) => {
+                return Err(Error::Config(format!(
+                    "[system.lattice] is only used with hamiltonian = \"lattice\", got \"{}\"",
+                    self.hamiltonian
+                )))
+            }
+            (_, None) => return Ok(None),
+        };
+
+        if self.hilbert_dim != lattice.num_sites() + 1 {
+            return Err(Error::Config(format!(
+                "A lattice of {} sites needs hilbert_dim = {} (vacuum plus one excitation per \
+                 site), got {}",
+                lattice.num_sites(),
+                lattice.num_sites() + 1,
+                self.hilbert_dim
+            )));
+        }
+        if let Some(disorder) = &lattice.disorder {
+            disorder.validate()?;
+        }
+        Ok(Some((parameters, lattice)))
+    }
+
+    pub fn lattice_hamiltonian(&self) -> Result<Option<LatticeHamiltonian>> {
+        let Some((parameters, lattice)) = self.lattice_model()? else {
+            return Ok(None);
+        };
+        Ok(Some(
+            lattice.build(parameters.j)?.hamiltonian(parameters.omega_c),
+        ))
+    }
+
+    pub fn build_lattice(&self) -> Result<Option<(Lattice, f64)>> {
+        if let SystemParameters::CoupledCavities(p) = &self.parameters {
+            if self.hilbert_dim < 2 {
+                return Err(Error::Config(
+                    "Coupled cavities need hilbert_dim of at least 2".to_string(),
+                ));
+            }
+            let cavities =
+                CoupledCavities::ssh(p.omega_c, p.j1, p.j2.unwrap_or(p.j1), self.hilbert_dim - 1);
+            return Ok(Some((cavities.lattice()?, p.omega_c)));
+        }
+        let Some((parameters, lattice)) = self.lattice_model()? else {
+            return Ok(None);
+        };
+        Ok(Some((lattice.build(parameters.j)?, parameters.omega_c)))
+    }
+
+    pub fn lattice_realizations(&self) -> Result<Vec<LatticeHamiltonian>> {
+        let Some((parameters, lattice)) = self.lattice_model()? else {
+            return Ok(Vec::new());
+        };
+        Ok(lattice
+            .realizations(parameters.j)?
+            .iter()
+            .map(|realization| realization.hamiltonian(parameters.omega_c))
+            .collect())
+    }
+}
+
+impl From<SystemConfig> for RawSystemConfig {
+    fn from(config: SystemConfig) -> Self {
+        Self {
+            hilbert_dim: config.hilbert_dim,
+            hamiltonian: config.hamiltonian,
+            parameters: config.parameters.to_map(),
+            lattice: config.lattice,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DrivenTlsParameters {
+    pub omega_0: f64,
+    pub omega_d: f64,
+    #[serde(alias = "Omega_R")]
+    pub rabi_freq: f64,
     #[serde(default)]
-    pub parameters: std::collections::HashMap<String, f64>,
+    pub phase: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DrivenCavityParameters {
+    pub omega_c: f64,
+    pub omega_p: f64,
+    pub g: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CoupledCavitiesParameters {
+    pub omega_c: f64,
+    #[serde(alias = "j")]
+    pub j1: f64,
+    #[serde(
+        default,
+        deserialize_with = "some_f64",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub j2: Option<f64>,
+}
+
+fn some_f64<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<f64>, D::Error> {
+    f64::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LatticeParameters {
+    pub omega_c: f64,
+    #[serde(default)]
+    pub j: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TavisCummingsParameters {
+    pub omega_c: f64,
+    pub omega_a: f64,
+    pub g: f64,
+    #[serde(default)]
+    pub drive: f64,
+    #[serde(default)]
+    pub omega_d: f64,
+}
+
+#[derive(Debug, Clone)]
+pub enum SystemParameters {
+    DrivenTls(DrivenTlsParameters),
+    DrivenCavity(DrivenCavityParameters),
+    CoupledCavities(CoupledCavitiesParameters),
+    Lattice(LatticeParameters),
+    TavisCummings(TavisCummingsParameters),
+    Custom(BTreeMap<String, f64>),
+}
+
+impl SystemParameters {
+    pub fn parse(hamiltonian: &str, parameters: BTreeMap<String, f64>) -> Result<Self> {
+        fn typed<T: serde::de::DeserializeOwned>(
+            hamiltonian: &str,
+            parameters: BTreeMap<String, f64>,
+        ) -> Result<T> {
+            use serde::de::value::{Error as ValueError, MapDeserializer};
+
+            T::deserialize(MapDeserializer::<_, ValueError>::new(
+                parameters.into_iter(),
+            ))
+            .map_err(|e| {
+                Error::Config(format!(
+                    "Invalid parameters for system '{}': {}",
+                    hamiltonian, e
+                ))
+            })
+        }
+
+        match hamiltonian {
+            "driven_tls" => Ok(Self::DrivenTls(typed(hamiltonian, parameters)?)),
+            "cavity" | "driven_cavity" => Ok(Self::DrivenCavity(typed(hamiltonian, parameters)?)),
+            "coupled_cavities" => Ok(Self::CoupledCavities(typed(hamiltonian, parameters)?)),
+            "lattice" => Ok(Self::Lattice(typed(hamiltonian, parameters)?)),
+            "tavis_cummings" => Ok(Self::TavisCummings(typed(hamiltonian, parameters)?)),
+            _ => Ok(Self::Custom(parameters)),
+        }
+    }
+
+    pub fn to_map(&self) -> BTreeMap<String, f64> {
+        fn untyped<T: Serialize>(parameters: &T) -> BTreeMap<String, f64> {
+            match toml::Value::try_from(parameters) {
+                Ok(toml::Value::Table(table)) => table
+                    .into_iter()
+                    .filter_map(|(k, v)| v.as_float().map(|v| (k, v)))
+                    .collect(),
+                _ => BTreeMap::new(),
+            }
+        }
+
+        match self {
+            Self::DrivenTls(p) => untyped(p),
+            Self::DrivenCavity(p) => untyped(p),
+            Self::CoupledCavities(p) => untyped(p),
+            Self::Lattice(p) => untyped(p),
+            Self::TavisCummings(p) => untyped(p),
+            Self::Custom(map) => map.clone(),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.to_map().get(name).copied()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -50,6 +371,8 @@ pub struct LindbladOperatorConfig {
     pub rate: f64,
     #[serde(default)]
     pub temperature: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,12 +380,60 @@ pub struct ObservablesConfig {
     pub list: Vec<String>,
     #[serde(default = "default_save_interval")]
     pub save_interval: f64,
+    #[serde(default)]
+    pub output: std::collections::HashMap<String, ObservableOutputConfig>,
+    #[serde(default)]
+    pub time_grid: Option<crate::simulation::TimeGrid>,
+    #[serde(default)]
+    pub steady_state: crate::simulation::SteadyStateOptions,
+    #[serde(default)]
+    pub sampling: crate::simulation::SamplingOptions,
+    #[serde(default)]
+    pub spectrogram: crate::simulation::SpectrogramOptions,
+    #[serde(default)]
+    pub dmd: crate::simulation::DmdOptions,
+    #[serde(default)]
+    pub events: Vec<crate::simulation::EventSpec>,
+    #[serde(default)]
+    pub fits: Vec<crate::simulation::FitSpec>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObservableOutputConfig {
+    #[serde(default)]
+    pub file: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default = "default_precision")]
+    pub precision: String,
+    #[serde(default)]
+    pub downsample: Option<crate::data::export::Downsampling>,
+    #[serde(default = "default_compression")]
+    pub compression: String,
+}
+
+fn default_compression() -> String {
+    "none".to_string()
+}
+
+fn default_precision() -> String {
+    "f64".to_string()
 }
 
 fn default_save_interval() -> f64 {
     1.0
 }
 
+impl ObservablesConfig {
+    pub fn time_grid(&self) -> crate::simulation::TimeGrid {
+        self.time_grid
+            .clone()
+            .unwrap_or(crate::simulation::TimeGrid::Uniform {
+                interval: self.save_interval,
+            })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GpuConfig {
     #[serde(default)]
@@ -91,7 +462,79 @@ fn default_batch_size() -> usize {
     256
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InitialStateConfig {
+    #[serde(default = "default_initial_state_kind")]
+    pub kind: String,
+    #[serde(default)]
+    pub level: usize,
+    #[serde(default)]
+    pub alpha: f64,
+    #[serde(default)]
+    pub alpha_phase: f64,
+    #[serde(default)]
+    pub theta: f64,
+    #[serde(default)]
+    pub phi: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub amplitudes: Vec<[f64; 2]>,
+}
+
+impl Default for InitialStateConfig {
+    fn default() -> Self {
+        Self {
+            kind: default_initial_state_kind(),
+            level: 0,
+            alpha: 0.0,
+            alpha_phase: 0.0,
+            theta: 0.0,
+            phi: 0.0,
+            amplitudes: Vec::new(),
+        }
+    }
+}
+
+fn default_initial_state_kind() -> String {
+    "ground".to_string()
+}
+
+impl InitialStateConfig {
+    pub fn build(&self, dim: usize) -> Result<QuantumState> {
+        match self.kind.as_str() {
+            "ground" => Ok(QuantumState::ground_state(dim)),
+            "fock" => QuantumState::fock(dim, self.level),
+            "coherent" => QuantumState::coherent(
+                dim,
+                num_complex::Complex64::from_polar(self.alpha, self.alpha_phase),
+            ),
+            "bloch" => QuantumState::bloch(dim, self.theta, self.phi),
+            "superposition" => {
+                if self.amplitudes.len() != dim {
+                    return Err(Error::dimension_mismatch(dim, self.amplitudes.len()));
+                }
+                let amplitudes: ndarray::Array1<num_complex::Complex64> = self
+                    .amplitudes
+                    .iter()
+                    .map(|&[re, im]| num_complex::Complex64::new(re, im))
+                    .collect();
+                let norm = amplitudes.iter().map(|z| z.norm_sqr()).sum::<f64>().sqrt();
+                if norm == 0.0 {
+                    return Err(Error::InvalidParameter(
+                        "Superposition amplitudes must not all vanish".to_string(),
+                    ));
+                }
+                QuantumState::new(amplitudes / norm)
+            }
+            other => Err(Error::Config(format!(
+                "Unknown initial state kind '{}', expected ground, fock, coherent, bloch or \
+                 superposition",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParameterSweepConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -101,23 +544,341 @@ pub struct ParameterSweepConfig {
     pub range: Vec<f64>,
     #[serde(default)]
     pub num_points: usize,
+    #[serde(default = "default_sweep_strategy")]
+    pub strategy: String,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub parameters: Vec<SweepParameterConfig>,
+    #[serde(default)]
+    pub reductions: Vec<crate::sweep::reduction::ReductionConfig>,
+    #[serde(default)]
+    pub derived: Vec<crate::sweep::hooks::DerivedMetricConfig>,
+    #[serde(default)]
+    pub refinement: Option<crate::sweep::refine::RefinementConfig>,
+    #[serde(default)]
+    pub objective: Option<crate::sweep::objective::ObjectiveConfig>,
+    #[serde(default)]
+    pub objectives: Vec<crate::sweep::objective::ObjectiveConfig>,
+    #[serde(default)]
+    pub keep_traces: bool,
+    #[serde(default)]
+    pub checkpoint_dir: Option<std::path::PathBuf>,
+    #[serde(default)]
+    pub checkpoint_interval: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SweepParameterConfig {
+    pub name: String,
+    pub range: Vec<f64>,
+}
+
+impl Default for ParameterSweepConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            parameter: String::new(),
+            range: Vec::new(),
+            num_points: 0,
+            strategy: default_sweep_strategy(),
+            seed: None,
+            parameters: Vec::new(),
+            reductions: Vec::new(),
+            derived: Vec::new(),
+            refinement: None,
+            objective: None,
+            objectives: Vec::new(),
+            keep_traces: false,
+            checkpoint_dir: None,
+            checkpoint_interval: None,
+        }
+    }
+}
+
+fn default_sweep_strategy() -> String {
+    "grid".to_string()
+}
+
+impl ParameterSweepConfig {
+    pub fn all_parameters(&self) -> Vec<SweepParameterConfig> {
+        let mut parameters = Vec::new();
+        if !self.parameter.is_empty() {
+            parameters.push(SweepParameterConfig {
+                name: self.parameter.clone(),
+                range: self.range.clone(),
+            });
+        }
+        parameters.extend(self.parameters.iter().cloned());
+        parameters
+    }
+
+    pub fn all_objectives(&self) -> Vec<crate::sweep::objective::ObjectiveConfig> {
+        self.objective
+            .iter()
+            .chain(&self.objectives)
+            .cloned()
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeasurementConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_efficiency")]
+    pub efficiency: f64,
+    #[serde(default)]
+    pub dark_counts: f64,
+    #[serde(default)]
+    pub amplifier_noise: f64,
+    #[serde(default)]
+    pub shots: usize,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+impl Default for MeasurementConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            efficiency: default_efficiency(),
+            dark_counts: 0.0,
+            amplifier_noise: 0.0,
+            shots: 0,
+            seed: None,
+        }
+    }
+}
+
+fn default_efficiency() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TomographyConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_tomography_measurements")]
+    pub measurements: String,
+    #[serde(default)]
+    pub shots: usize,
+    #[serde(default)]
+    pub seed: Option<u64>,
+    #[serde(default = "default_tomography_iterations")]
+    pub max_iterations: usize,
+    #[serde(default = "default_tomography_tolerance")]
+    pub tolerance: f64,
+}
+
+impl Default for TomographyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            measurements: default_tomography_measurements(),
+            shots: 0,
+            seed: None,
+            max_iterations: default_tomography_iterations(),
+            tolerance: default_tomography_tolerance(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CumulantConfig {
+    #[serde(default = "default_cumulant_emitters")]
+    pub emitters: usize,
+    #[serde(default = "default_cumulant_order")]
+    pub order: String,
+    #[serde(default)]
+    pub kappa: f64,
+    #[serde(default)]
+    pub gamma: f64,
+    #[serde(default)]
+    pub dephasing: f64,
+    #[serde(default)]
+    pub field: [f64; 2],
+    #[serde(default)]
+    pub emitter_angle: f64,
+}
+
+impl Default for CumulantConfig {
+    fn default() -> Self {
+        Self {
+            emitters: default_cumulant_emitters(),
+            order: default_cumulant_order(),
+            kappa: 0.0,
+            gamma: 0.0,
+            dephasing: 0.0,
+            field: [0.0, 0.0],
+            emitter_angle: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KickConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_kick_operator")]
+    pub operator: String,
+    #[serde(default)]
+    pub strength: f64,
+    #[serde(default = "default_kick_period")]
+    pub period: f64,
+}
+
+impl Default for KickConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            operator: default_kick_operator(),
+            strength: 0.0,
+            period: default_kick_period(),
+        }
+    }
+}
+
+fn default_kick_operator() -> String {
+    "quadrature".to_string()
+}
+
+fn default_kick_period() -> f64 {
+    1.0
+}
+
+fn default_cumulant_emitters() -> usize {
+    1
+}
+
+fn default_cumulant_order() -> String {
+    "second".to_string()
+}
+
+fn default_tomography_measurements() -> String {
+    "gell_mann".to_string()
+}
+
+fn default_tomography_iterations() -> usize {
+    2000
+}
+
+fn default_tomography_tolerance() -> f64 {
+    1e-10
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportConfig {
+    #[serde(default = "default_report_format")]
+    pub format: String,
+    #[serde(default = "default_figure_width")]
+    pub width: u32,
+    #[serde(default = "default_figure_height")]
+    pub height: u32,
+    #[serde(default = "default_line_width")]
+    pub line_width: u32,
+    #[serde(default = "default_font_size")]
+    pub font_size: u32,
+    #[serde(default = "default_summary_format")]
+    pub summary: String,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            format: default_report_format(),
+            width: default_figure_width(),
+            height: default_figure_height(),
+            line_width: default_line_width(),
+            font_size: default_font_size(),
+            summary: default_summary_format(),
+        }
+    }
+}
+
+fn default_report_format() -> String {
+    "png".to_string()
+}
+
+fn default_figure_width() -> u32 {
+    1024
+}
+
+fn default_figure_height() -> u32 {
+    768
+}
+
+fn default_line_width() -> u32 {
+    2
+}
+
+fn default_font_size() -> u32 {
+    24
+}
+
+fn default_summary_format() -> String {
+    "markdown".to_string()
+}
+
+fn toml_location(location: &mut ConfigLocation, contents: &str, error: &toml::de::Error) {
+    if let Some(span) = error.span() {
+        let before = &contents[..span.start.min(contents.len())];
+        location.line = Some(before.matches('\n').count() + 1);
+        location.column = Some(before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1);
+    }
 }
 
 impl Config {
     pub fn from_file(path: &Path) -> Result<Self> {
         let contents = std::fs::read_to_string(path)?;
 
+        let mut location = ConfigLocation {
+            file: Some(path.to_path_buf()),
+            ..ConfigLocation::default()
+        };
+
         let config = if path.extension().and_then(|s| s.to_str()) == Some("toml") {
-            toml::from_str(&contents)
-                .map_err(|e| Error::Config(format!("TOML parse error: {}", e)))?
+            Self::parse_toml(&contents, location)?
         } else {
-            serde_yaml::from_str(&contents)
-                .map_err(|e| Error::Config(format!("YAML parse error: {}", e)))?
+            let deserializer = serde_yaml::Deserializer::from_str(&contents);
+            serde_path_to_error::deserialize(deserializer).map_err(|e| {
+                location.key = Some(e.path().to_string());
+                if let Some(loc) = e.inner().location() {
+                    location.line = Some(loc.line());
+                    location.column = Some(loc.column());
+                }
+                Error::ConfigAt {
+                    location,
+                    message: format!("YAML parse error: {}", e.inner()),
+                }
+            })?
         };
 
         Ok(config)
     }
 
+    pub fn from_toml_str(contents: &str) -> Result<Self> {
+        Self::parse_toml(contents, ConfigLocation::default())
+    }
+
+    fn parse_toml(contents: &str, mut location: ConfigLocation) -> Result<Self> {
+        let deserializer = toml::Deserializer::parse(contents).map_err(|e| {
+            toml_location(&mut location, contents, &e);
+            Error::ConfigAt {
+                location: location.clone(),
+                message: format!("TOML parse error: {}", e.message()),
+            }
+        })?;
+        serde_path_to_error::deserialize(deserializer).map_err(|e| {
+            location.key = Some(e.path().to_string());
+            toml_location(&mut location, contents, e.inner());
+            Error::ConfigAt {
+                location,
+                message: e.inner().message().to_string(),
+            }
+        })
+    }
+
     pub fn save(&self, path: &Path) -> Result<()> {
         let contents = if path.extension().and_then(|s| s.to_str()) == Some("toml") {
             toml::to_string_pretty(self)
@@ -144,12 +905,260 @@ impl Config {
             ));
         }
 
+        if self.simulation.warmup_duration < 0.0 || !self.simulation.warmup_duration.is_finite() {
+            return Err(Error::InvalidParameter(format!(
+                "Warm-up duration must be non-negative, got {}",
+                self.simulation.warmup_duration
+            )));
+        }
+
         if self.system.hilbert_dim == 0 {
             return Err(Error::InvalidParameter(
                 "Hilbert dimension must be positive".to_string(),
             ));
         }
 
+        self.system.lattice_hamiltonian()?;
+
+        self.observables
+            .time_grid()
+            .validate(self.simulation.duration)?;
+
+        crate::data::output::CollisionPolicy::parse(&self.output.on_collision)?;
+        crate::data::matfile::MatVersion::parse(&self.output.mat_version)?;
+        crate::data::ReportStyle::from_config(&self.report)?;
+        self.performance.validate()?;
+        self.tolerances.validate()?;
+        let precision = crate::core::Precision::parse(&self.simulation.precision)?;
+        let integrator = self.simulation.integrator_type()?;
+        if !integrator.has_single_precision() && precision == crate::core::Precision::Single {
+            return Err(Error::Config(format!(
+                "The {} integrator runs in f64 only, set simulation.precision = \"f64\"",
+                self.simulation.integrator
+            )));
+        }
+        if self.lindblad.enabled && precision == crate::core::Precision::Single {
+            return Err(Error::Config(
+                "Open systems run in f64 only, set simulation.precision = \"f64\"".to_string(),
+            ));
+        }
+        let solver = crate::utils::performance::Solver::parse(&self.simulation.solver)?;
+        if solver == crate::utils::performance::Solver::Cumulant {
+            crate::simulation::CumulantSolver::from_config(self)?;
+        }
+        if self.kicks.enabled {
+            crate::simulation::KickedSystem::from_config(self)?;
+        }
+        if !self.detection.ports.is_empty() {
+            crate::simulation::OutputPorts::from_config(self)?;
+        }
+        if self.simulation.trajectories == 0 {
+            return Err(Error::InvalidParameter(
+                "simulation.trajectories must be positive".to_string(),
+            ));
+        }
+        self.preemption.validate()?;
+        if self.tomography.enabled {
+            crate::data::Tomography::from_config(&self.tomography)?;
+        }
+        self.initial_state.build(self.system.hilbert_dim)?;
+        if self.parameter_sweep.enabled {
+            crate::sweep::ParameterSweep::from_config(self)?.points()?;
+        }
+
+        let steady_state = &self.observables.steady_state;
+        if !(steady_state.window > 0.0 && steady_state.window <= 1.0) {
+            return Err(Error::InvalidParameter(format!(
+                "Steady-state window must be a fraction in (0, 1], got {}",
+                steady_state.window
+            )));
+        }
+
+        let mut names = Vec::new();
+        let mut vector_names = Vec::new();
+        for spec in &self.observables.list {
+            let dim = self.system.hilbert_dim;
+            if let Some((name, _)) =
+                crate::core::observable_expr::parse_vector_observable(spec, dim)?
+            {
+                vector_names.push(name);
+                continue;
+            }
+            let (name, _) = crate::core::observable_expr::parse_observable(spec, dim)?;
+            if self.measurement.enabled
+                && self.measurement.shots > 0
+                && !crate::core::observable_expr::is_probability(spec, dim)?
+            {
+                return Err(Error::Config(format!(
+                    "measurement.shots samples clicks, so observable '{}' must be bounded to \
+                     [0, 1] (e.g. population:k)",
+                    name
+                )));
+            }
+            names.push(name);
+        }
+
+        let dmd = &self.observables.dmd;
+        if let Some(source) = &dmd.source {
+            dmd.validate()?;
+            let known = [
+                crate::simulation::dmd::STATES_SOURCE,
+                crate::simulation::dmd::OBSERVABLES_SOURCE,
+            ];
+            if !known.contains(&source.as_str()) && !vector_names.contains(source) {
+                return Err(Error::Config(format!(
+                    "DMD source must be 'states', 'observables' or a vector observable, got '{}'",
+                    source
+                )));
+            }
+        }
+
+        // Open-system runs evolve a density matrix and record no state vectors.
+        if self.lindblad.enabled {
+            let needs_states = [
+                (self.output.states, "output.states"),
+                (self.tomography.enabled, "tomography"),
+                (
+                    dmd.source.as_deref() == Some(crate::simulation::dmd::STATES_SOURCE),
+                    "observables.dmd.source = \"states\"",
+                ),
+            ];
+            if let Some((_, option)) = needs_states.iter().find(|(enabled, _)| *enabled) {
+                return Err(Error::Config(format!(
+                    "{} needs recorded state vectors, which Lindblad runs do not produce",
+                    option
+                )));
+            }
+        }
+
+        let spectrogram = &self.observables.spectrogram;
+        if !spectrogram.observables.is_empty() {
+            spectrogram.validate()?;
+        }
+        for name in &spectrogram.observables {
+            if !names.contains(name) {
+                return Err(Error::Config(format!(
+                    "Spectrogram requested for unknown scalar observable '{}'",
+                    name
+                )));
+            }
+        }
+
+        for spec in &self.observables.events {
+            spec.validate()?;
+            if !names.contains(&spec.observable) {
+                return Err(Error::Config(format!(
+                    "Event detection requested for unknown scalar observable '{}'",
+                    spec.observable
+                )));
+            }
+        }
+
+        for spec in &self.observables.fits {
+            spec.validate()?;
+            if !names.contains(&spec.observable) {
+                return Err(Error::Config(format!(
+                    "Fit requested for unknown scalar observable '{}'",
+                    spec.observable
+                )));
+            }
+        }
+
+        for spec in &self.simulation.stop_when {
+            let condition = crate::simulation::StopCondition::parse(spec)?;
+            if let Some(name) = condition.observable() {
+                if !names.iter().any(|n| n == name) {
+                    return Err(Error::Config(format!(
+                        "Stop condition '{}' refers to unknown scalar observable '{}'",
+                        spec, name
+                    )));
+                }
+            }
+        }
+
+        for reduction in &self.parameter_sweep.reductions {
+            reduction.validate()?;
+            if !names.contains(&reduction.observable) {
+                return Err(Error::Config(format!(
+                    "Reduction '{}' requested for unknown scalar observable '{}'",
+                    reduction.metric_name(),
+                    reduction.observable
+                )));
+            }
+        }
+
+        let sweep = &self.parameter_sweep;
+        let parameters = sweep.all_parameters();
+        let mut metrics: Vec<String> = sweep.reductions.iter().map(|r| r.metric_name()).collect();
+        for derived in &sweep.derived {
+            if metrics.contains(&derived.name) {
+                return Err(Error::Config(format!(
+                    "Derived metric '{}' is defined twice",
+                    derived.name
+                )));
+            }
+            for variable in derived.expression()?.variables() {
+                let known = metrics.iter().any(|m| m == variable)
+                    || parameters
+                        .iter()
+                        .any(|p| p.name == variable || p.name.rsplit('.').next() == Some(variable));
+                if !known {
+                    return Err(Error::Config(format!(
+                        "Derived metric '{}' refers to '{}', which is neither a reduction, an \
+                         earlier derived metric nor a sweep parameter",
+                        derived.name, variable
+                    )));
+                }
+            }
+            metrics.push(derived.name.clone());
+        }
+        if let Some(refinement) = &sweep.refinement {
+            refinement.validate()?;
+            if !metrics.contains(&refinement.metric) {
+                return Err(Error::Config(format!(
+                    "Refinement metric '{}' must be a reduction or derived metric",
+                    refinement.metric
+                )));
+            }
+        }
+        let objectives = sweep.all_objectives();
+        for (k, objective) in objectives.iter().enumerate() {
+            objective.validate()?;
+            if objectives[..k].iter().any(|o| o.metric == objective.metric) {
+                return Err(Error::Config(format!(
+                    "Objective metric '{}' is listed twice",
+                    objective.metric
+                )));
+            }
+            if !metrics.contains(&objective.metric) {
+                return Err(Error::Config(format!(
+                    "Objective metric '{}' must be a reduction or derived metric",
+                    objective.metric
+                )));
+            }
+        }
+
+        crate::data::export::observable_output_files(&self.observables.output)?;
+        for (name, output) in &self.observables.output {
+            if vector_names.contains(name) {
+                return Err(Error::Config(format!(
+                    "Output options are only supported for scalar observables, '{}' is a vector",
+                    name
+                )));
+            }
+            if !names.contains(name) {
+                return Err(Error::Config(format!(
+                    "Output configured for unknown observable '{}'",
+                    name
+                )));
+            }
+            crate::data::export::Precision::parse(&output.precision)?;
+            crate::data::export::Compression::parse(&output.compression)?;
+            if let Some(downsample) = &output.downsample {
+                downsample.validate()?;
+            }
+        }
+
         Ok(())
     }
 
@@ -164,30 +1173,215 @@ impl Config {
     }
 
     fn driven_tls_template() -> Self {
-        let mut parameters = std::collections::HashMap::new();
-        parameters.insert("omega_0".to_string(), 5.0);
-        parameters.insert("omega_d".to_string(), 5.0);
-        parameters.insert("rabi_freq".to_string(), 0.5);
-
         Self {
             simulation: SimulationConfig {
                 name: "driven_tls".to_string(),
                 duration: 50.0,
                 timestep: 0.1,
                 integrator: "rk4".to_string(),
+                precision: default_precision(),
+                atol: default_atol(),
+                rtol: default_rtol(),
+                krylov_dim: default_krylov_dim(),
+                krylov_tol: default_krylov_tol(),
+                max_retries: 0,
+                warmup_duration: 0.0,
+                stop_when: Vec::new(),
+                solver: default_solver(),
+                trajectories: default_trajectories(),
+                seed: None,
             },
             system: SystemConfig {
                 hilbert_dim: 2,
                 hamiltonian: "driven_tls".to_string(),
-                parameters,
+                parameters: SystemParameters::DrivenTls(DrivenTlsParameters {
+                    omega_0: 5.0,
+                    omega_d: 5.0,
+                    rabi_freq: 0.5,
+                    phase: 0.0,
+                }),
+                lattice: None,
             },
             lindblad: LindbladConfig::default(),
             observables: ObservablesConfig {
                 list: vec!["population".to_string()],
                 save_interval: 1.0,
+                output: std::collections::HashMap::new(),
+                time_grid: None,
+                steady_state: Default::default(),
+                sampling: Default::default(),
+                spectrogram: Default::default(),
+                dmd: Default::default(),
+                events: Vec::new(),
+                fits: Vec::new(),
             },
             gpu: GpuConfig::default(),
+            initial_state: InitialStateConfig::default(),
             parameter_sweep: ParameterSweepConfig::default(),
+            measurement: MeasurementConfig::default(),
+            tomography: TomographyConfig::default(),
+            report: ReportConfig::default(),
+            output: crate::data::output::OutputConfig::default(),
+            performance: crate::utils::performance::PerformanceConfig::default(),
+            preemption: crate::simulation::PreemptionConfig::default(),
+            cumulant: CumulantConfig::default(),
+            kicks: KickConfig::default(),
+            spectroscopy: crate::simulation::SpectroscopyConfig::default(),
+            detection: crate::simulation::DetectionConfig::default(),
+            tolerances: crate::utils::Tolerances::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"
+[simulation]
+name = "test"
+duration = 1.0
+timestep = 0.1
+
+[observables]
+list = ["population"]
+"#;
+
+    #[test]
+    fn test_typed_parameters_reject_typos() {
+        let text = format!(
+            "{}\n[system]\nhilbert_dim = 2\nhamiltonian = \"driven_tls\"\n\n[system.parameters]\n\
+             omega_0 = 5.0\nomega_d = 5.0\nrabi_frequncy = 0.5\n",
+            BASE
+        );
+        let err = toml::from_str::<Config>(&text).unwrap_err().to_string();
+        assert!(err.contains("rabi_frequncy"));
+        assert!(err.contains("rabi_freq"));
+    }
+
+    #[test]
+    fn test_custom_system_parameters_pass_through() {
+        let text = format!(
+            "{}\n[system]\nhilbert_dim = 3\nhamiltonian = \"my_plugin\"\n\n[system.parameters]\n\
+             anything = 1.5\n",
+            BASE
+        );
+        let config: Config = toml::from_str(&text).unwrap();
+        assert_eq!(config.system.parameters.get("anything"), Some(1.5));
+    }
+
+    #[test]
+    fn test_lattice_system_builds_hamiltonian() {
+        let text = format!(
+            "{}\n[system]\nhilbert_dim = 5\nhamiltonian = \"lattice\"\n\n[system.parameters]\n\
+             omega_c = 5.0\nj = 0.2\n\n[system.lattice]\nshape = \"square\"\nwidth = 2\n\
+             height = 2\n",
+            BASE
+        );
+        let mut config: Config = toml::from_str(&text).unwrap();
+        config.validate().unwrap();
+        let h = config.system.lattice_hamiltonian().unwrap().unwrap();
+        assert_eq!(h.matrix()[[1, 2]], num_complex::Complex64::new(0.2, 0.0));
+        assert_eq!(config.system.lattice_realizations().unwrap().len(), 1);
+
+        if let Some(lattice) = config.system.lattice.as_mut() {
+            lattice.disorder = Some(crate::core::lattice::DisorderConfig {
+                onsite: 1.0,
+                seed: Some(7),
+                realizations: 3,
+                ..Default::default()
+            });
+        }
+        let realizations = config.system.lattice_realizations().unwrap();
+        assert_eq!(realizations.len(), 3);
+        assert_ne!(realizations[0].matrix(), realizations[1].matrix());
+
+        config.system.hilbert_dim = 4;
+        assert!(config.validate().is_err());
+        config.system.lattice = None;
+        assert!(config.system.lattice_hamiltonian().is_err());
+    }
+
+    #[test]
+    fn test_coupled_cavities_build_ssh_lattice() {
+        let text = format!(
+            "{}\n[system]\nhilbert_dim = 5\nhamiltonian = \"coupled_cavities\"\n\n\
+             [system.parameters]\nomega_c = 5.0\nj1 = 0.4\nj2 = 1.0\n",
+            BASE
+        );
+        let config: Config = toml::from_str(&text).unwrap();
+        let (lattice, omega_c) = config.system.build_lattice().unwrap().unwrap();
+        assert_eq!(omega_c, 5.0);
+        let couplings: Vec<f64> = lattice.bonds().iter().map(|b| b.coupling).collect();
+        assert_eq!(couplings, [0.4, 1.0, 0.4]);
+    }
+
+    #[test]
+    fn test_from_file_reports_key_and_line() {
+        let path = std::env::temp_dir().join("chronophoton_bad_config.toml");
+        std::fs::write(
+            &path,
+            format!(
+                "{}\n[system]\nhilbert_dim = \"two\"\nhamiltonian = \"driven_tls\"\n",
+                BASE
+            ),
+        )
+        .unwrap();
+
+        let err = Config::from_file(&path).unwrap_err();
+        let location = err.location().unwrap();
+        assert_eq!(location.key.as_deref(), Some("system.hilbert_dim"));
+        assert_eq!(location.line, Some(11));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_template_round_trip() {
+        let template = Config::driven_tls_template();
+        let text = toml::to_string_pretty(&template).unwrap();
+        let parsed: Config = toml::from_str(&text).unwrap();
+        assert!(matches!(
+            parsed.system.parameters,
+            SystemParameters::DrivenTls(DrivenTlsParameters { rabi_freq, .. }) if rabi_freq == 0.5
+        ));
+    }
+
+    #[test]
+    fn test_lindblad_rejects_outputs_that_need_state_vectors() {
+        let mut config = Config::driven_tls_template();
+        config.lindblad.enabled = true;
+        config.validate().unwrap();
+
+        config.output.states = true;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("output.states"), "{}", err);
+        config.output.states = false;
+        config.tomography.enabled = true;
+        assert!(config.validate().is_err());
+        config.tomography.enabled = false;
+        config.observables.dmd.source = Some("states".to_string());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_integrator_options() {
+        let mut config = Config::driven_tls_template();
+        config.simulation.integrator = "krylov".to_string();
+        config.simulation.krylov_dim = 12;
+        assert!(matches!(
+            config.simulation.integrator_type().unwrap(),
+            crate::core::IntegratorType::Krylov { dim: 12, .. }
+        ));
+        config.simulation.precision = "f32".to_string();
+        assert!(config.validate().is_err());
+        config.simulation.precision = "f64".to_string();
+        config.simulation.krylov_tol = 0.0;
+        assert!(config.simulation.integrator_type().is_err());
+
+        let mut config = Config::driven_tls_template();
+        config.simulation.precision = "f32".to_string();
+        assert!(config.validate().is_ok());
+        config.lindblad.enabled = true;
+        assert!(config.validate().is_err());
+    }
+}
@@ -1,5 +1,184 @@
-use crate::utils::Result;
-use std::path::Path;
+use crate::data::config::ObservableOutputConfig;
+use crate::simulation::{Event, SimulationResults, Spectrogram};
+use crate::utils::{Error, Result};
+use num_complex::Complex64;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    F32,
+    F64,
+}
+
+impl Precision {
+    pub fn parse(precision: &str) -> Result<Self> {
+        match precision {
+            "f32" => Ok(Self::F32),
+            "f64" => Ok(Self::F64),
+            other => Err(Error::InvalidParameter(format!(
+                "Unknown output precision '{}' (expected f32 or f64)",
+                other
+            ))),
+        }
+    }
+
+    fn format(&self, value: f64) -> String {
+        match self {
+            Self::F32 => format!("{}", value as f32),
+            Self::F64 => format!("{}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    pub fn parse(compression: &str) -> Result<Self> {
+        match compression {
+            "none" => Ok(Self::None),
+            "gzip" | "gz" => Ok(Self::Gzip),
+            "zstd" => Ok(Self::Zstd),
+            other => Err(Error::InvalidParameter(format!(
+                "Unknown compression '{}' (expected none, gzip or zstd)",
+                other
+            ))),
+        }
+    }
+
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Self::None => None,
+            Self::Gzip => Some("gz"),
+            Self::Zstd => Some("zst"),
+        }
+    }
+
+    pub fn create(&self, path: &Path) -> Result<CompressedWriter> {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        Ok(match self {
+            Self::None => CompressedWriter::Plain(file),
+            Self::Gzip => CompressedWriter::Gzip(flate2::write::GzEncoder::new(
+                file,
+                flate2::Compression::default(),
+            )),
+            Self::Zstd => CompressedWriter::Zstd(zstd::Encoder::new(file, 0)?),
+        })
+    }
+}
+
+// Dropping an encoder swallows errors from writing its trailer, so callers must
+// call `finish` to learn whether the file is complete.
+pub enum CompressedWriter {
+    Plain(std::io::BufWriter<std::fs::File>),
+    Gzip(flate2::write::GzEncoder<std::io::BufWriter<std::fs::File>>),
+    Zstd(zstd::Encoder<'static, std::io::BufWriter<std::fs::File>>),
+}
+
+impl CompressedWriter {
+    pub fn finish(self) -> Result<()> {
+        let file = match self {
+            Self::Plain(file) => file,
+            Self::Gzip(encoder) => encoder.finish()?,
+            Self::Zstd(encoder) => encoder.finish()?,
+        };
+        file.into_inner().map_err(|e| e.into_error())?;
+        Ok(())
+    }
+}
+
+impl Write for CompressedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Plain(w) => w.write(buf),
+            Self::Gzip(w) => w.write(buf),
+            Self::Zstd(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Plain(w) => w.flush(),
+            Self::Gzip(w) => w.flush(),
+            Self::Zstd(w) => w.flush(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Downsampling {
+    Stride { every: usize },
+    PeakPreserving { max_points: usize },
+}
+
+impl Downsampling {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Stride { every: 0 } => Err(Error::InvalidParameter(
+                "Downsampling stride must be at least 1".to_string(),
+            )),
+            Self::PeakPreserving { max_points } if *max_points < 4 => Err(Error::InvalidParameter(
+                "Peak-preserving decimation needs max_points >= 4".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn apply(&self, series: &[(f64, Complex64)]) -> Vec<(f64, Complex64)> {
+        match *self {
+            Self::Stride { every } => {
+                let mut kept: Vec<_> = series.iter().step_by(every.max(1)).copied().collect();
+                if let Some(&last) = series.last() {
+                    if kept.last() != Some(&last) {
+                        kept.push(last);
+                    }
+                }
+                kept
+            }
+            Self::PeakPreserving { max_points } => {
+                if series.len() <= max_points {
+                    return series.to_vec();
+                }
+
+                let inner = &series[1..series.len() - 1];
+                let buckets = (max_points - 2) / 2;
+                let size = inner.len().div_ceil(buckets);
+
+                let mut kept = vec![series[0]];
+                for bucket in inner.chunks(size) {
+                    let by_re =
+                        |a: &&(f64, Complex64), b: &&(f64, Complex64)| a.1.re.total_cmp(&b.1.re);
+                    let (lo, _) = bucket
+                        .iter()
+                        .enumerate()
+                        .min_by(|a, b| by_re(&a.1, &b.1))
+                        .unwrap();
+                    let (hi, _) = bucket
+                        .iter()
+                        .enumerate()
+                        .max_by(|a, b| by_re(&a.1, &b.1))
+                        .unwrap();
+                    kept.push(bucket[lo.min(hi)]);
+                    if lo != hi {
+                        kept.push(bucket[lo.max(hi)]);
+                    }
+                }
+                kept.push(series[series.len() - 1]);
+                kept
+            }
+        }
+    }
+}
+
+const NPY_PREAMBLE: usize = 10;
 
 pub struct Exporter;
 
@@ -15,4 +194,417 @@ impl Exporter {
             "CSV export".to_string(),
         ))
     }
+
+    pub fn write_observable_outputs(
+        results: &mut SimulationResults,
+        outputs: &HashMap<String, ObservableOutputConfig>,
+        dir: &Path,
+    ) -> Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+        for (key, file) in observable_output_files(outputs)? {
+            let output = &outputs[key];
+            let precision = Precision::parse(&output.precision)?;
+            let Some(series) = results.remove_observable(key) else {
+                return Err(Error::Config(format!(
+                    "Output configured for unknown observable '{}'",
+                    key
+                )));
+            };
+
+            let compression = Compression::parse(&output.compression)?;
+            let series = match &output.downsample {
+                Some(downsample) => {
+                    downsample.validate()?;
+                    downsample.apply(&series)
+                }
+                None => series,
+            };
+
+            let column = output.name.as_deref().unwrap_or(key);
+            let path = dir.join(file);
+
+            let mut writer = compression.create(&path)?;
+            writeln!(writer, "time,{}_re,{}_im", column, column)?;
+            for (t, v) in series {
+                writeln!(
+                    writer,
+                    "{},{},{}",
+                    precision.format(t),
+                    precision.format(v.re),
+                    precision.format(v.im)
+                )?;
+            }
+            writer.finish()?;
+
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+
+    pub fn write_vector_series(results: &SimulationResults, dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+        for series in results.iter_vector_series() {
+            let path = dir.join(format!("{}.csv", series.name));
+            let mut writer = std::io::BufWriter::new(std::fs::File::create(&path)?);
+
+            write!(writer, "time")?;
+            for i in 0..series.width {
+                write!(writer, ",{}_{}_re,{}_{}_im", series.name, i, series.name, i)?;
+            }
+            writeln!(writer)?;
+
+            for (t, row) in series.rows() {
+                write!(writer, "{}", t)?;
+                for v in row {
+                    write!(writer, ",{},{}", v.re, v.im)?;
+                }
+                writeln!(writer)?;
+            }
+            writer.flush()?;
+            written.push(path);
+
+            if let Some(array) = series.to_array3() {
+                let path = dir.join(format!("{}.npy", series.name));
+                let values: Vec<Complex64> = array.iter().copied().collect();
+                Self::write_npy(&values, array.shape(), &path)?;
+                written.push(path);
+            }
+        }
+        Ok(written)
+    }
+
+    pub fn write_npy(values: &[Complex64], shape: &[usize], path: &Path) -> Result<()> {
+        if shape.iter().product::<usize>() != values.len() {
+            return Err(Error::DimensionMismatch {
+                expected: shape.iter().product(),
+                actual: values.len(),
+            });
+        }
+        let dims: Vec<String> = shape.iter().map(usize::to_string).collect();
+        let mut header = format!(
+            "{{'descr': '<c16', 'fortran_order': False, 'shape': ({},), }}",
+            dims.join(", ")
+        );
+        let padding = (64 - (NPY_PREAMBLE + header.len() + 1) % 64) % 64;
+        header.push_str(&" ".repeat(padding));
+        header.push('\n');
+
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writer.write_all(b"\x93NUMPY\x01\x00")?;
+        writer.write_all(&(header.len() as u16).to_le_bytes())?;
+        writer.write_all(header.as_bytes())?;
+        for v in values {
+            writer.write_all(&v.re.to_le_bytes())?;
+            writer.write_all(&v.im.to_le_bytes())?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn write_spectrogram(spectrogram: &Spectrogram, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        write!(writer, "time")?;
+        for omega in &spectrogram.frequencies {
+            write!(writer, ",{}", omega)?;
+        }
+        writeln!(writer)?;
+
+        for (t, row) in spectrogram.times.iter().zip(spectrogram.power.rows()) {
+            write!(writer, "{}", t)?;
+            for p in row {
+                write!(writer, ",{}", p)?;
+            }
+            writeln!(writer)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn write_events(events: &[Event], path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(writer, "observable,event,time,value,interval")?;
+        for event in events {
+            writeln!(
+                writer,
+                "{},{},{},{},{}",
+                event.observable,
+                event.kind.name(),
+                event.time,
+                event.value,
+                event.interval.map(|t| t.to_string()).unwrap_or_default()
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn write_steady_state(results: &SimulationResults, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(
+            writer,
+            "observable,mean,std_error,autocorrelation_time,tail_start,converged_at"
+        )?;
+        for (name, est) in results.steady_states() {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{}",
+                name,
+                est.mean,
+                est.std_error,
+                est.autocorrelation_time,
+                est.tail_start,
+                est.converged_at.map(|t| t.to_string()).unwrap_or_default()
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+// The file each routed observable goes to, in key order. Files must be plain names
+// inside the output directory, and no two observables may share one.
+pub fn observable_output_files(
+    outputs: &HashMap<String, ObservableOutputConfig>,
+) -> Result<Vec<(&str, String)>> {
+    let mut keys: Vec<&String> = outputs.keys().collect();
+    keys.sort();
+
+    let mut files: Vec<(&str, String)> = Vec::with_capacity(keys.len());
+    for key in keys {
+        let output = &outputs[key];
+        let column = output.name.as_deref().unwrap_or(key);
+        crate::core::observable_expr::validate_name(column)?;
+        let mut file = output
+            .file
+            .clone()
+            .unwrap_or_else(|| format!("{}.csv", column));
+        if file.contains('\\') || Path::new(&file).file_name() != Some(file.as_ref()) {
+            return Err(Error::Config(format!(
+                "Output file '{}' for observable '{}' must be a plain file name",
+                file, key
+            )));
+        }
+        if let Some(extension) = Compression::parse(&output.compression)?.extension() {
+            if !file.ends_with(&format!(".{}", extension)) {
+                file = format!("{}.{}", file, extension);
+            }
+        }
+        if let Some((other, _)) = files.iter().find(|(_, f)| *f == file) {
+            return Err(Error::Config(format!(
+                "Observables '{}' and '{}' are both written to '{}'",
+                other, key, file
+            )));
+        }
+        files.push((key, file));
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_per_observable_output() {
+        let mut results = SimulationResults::new();
+        results.add_observable("sz", 0.0, Complex64::new(1.0 / 3.0, 0.0));
+        results.add_observable("population", 0.0, Complex64::new(1.0, 0.0));
+
+        let mut outputs = HashMap::new();
+        outputs.insert(
+            "sz".to_string(),
+            ObservableOutputConfig {
+                file: Some("sigma_z_test.csv".to_string()),
+                name: Some("sigma_z".to_string()),
+                precision: "f32".to_string(),
+                downsample: None,
+                compression: "none".to_string(),
+            },
+        );
+
+        let dir = std::env::temp_dir();
+        let written = Exporter::write_observable_outputs(&mut results, &outputs, &dir).unwrap();
+        let contents = std::fs::read_to_string(&written[0]).unwrap();
+
+        assert!(contents.starts_with("time,sigma_z_re,sigma_z_im"));
+        assert!(contents.contains("0.33333334"));
+        assert!(results.get_observable("sz").is_none());
+        assert!(results.get_observable("population").is_some());
+        std::fs::remove_file(&written[0]).ok();
+    }
+
+    #[test]
+    fn test_output_files_stay_in_the_output_directory() {
+        let output = |file: Option<&str>, name: Option<&str>| ObservableOutputConfig {
+            file: file.map(str::to_string),
+            name: name.map(str::to_string),
+            precision: "f64".to_string(),
+            downsample: None,
+            compression: "none".to_string(),
+        };
+        let files = |entries: Vec<(&str, ObservableOutputConfig)>| {
+            let outputs: HashMap<String, ObservableOutputConfig> = entries
+                .into_iter()
+                .map(|(key, output)| (key.to_string(), output))
+                .collect();
+            observable_output_files(&outputs)
+                .map(|files| files.into_iter().map(|(_, file)| file).collect::<Vec<_>>())
+        };
+
+        assert_eq!(
+            files(vec![
+                ("sz", output(None, None)),
+                ("n", output(None, Some("photons")))
+            ])
+            .unwrap(),
+            vec!["photons.csv", "sz.csv"]
+        );
+        for file in [
+            "../sz.csv",
+            "out/sz.csv",
+            "/tmp/sz.csv",
+            "..",
+            "",
+            "a\\b.csv",
+        ] {
+            assert!(
+                files(vec![("sz", output(Some(file), None))]).is_err(),
+                "{}",
+                file
+            );
+        }
+        assert!(files(vec![("sz", output(None, Some("../sz")))]).is_err());
+        let err = files(vec![
+            ("sz", output(Some("both.csv"), None)),
+            ("n", output(Some("both.csv"), None)),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("'n' and 'sz'"), "{}", err);
+    }
+
+    #[test]
+    fn test_shaped_vector_series_written_as_npy() {
+        let mut results = SimulationResults::new();
+        for t in [0.0, 1.0] {
+            let row: Vec<Complex64> = (0..4).map(|k| Complex64::new(t + k as f64, 0.5)).collect();
+            results.add_vector_observable("corr", t, &row).unwrap();
+        }
+        results.set_vector_shape("corr", 2, 2).unwrap();
+        assert!(results.set_vector_shape("corr", 3, 2).is_err());
+
+        let dir = std::env::temp_dir().join(format!("chronophoton_npy_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let written = Exporter::write_vector_series(&results, &dir).unwrap();
+        assert_eq!(written.len(), 2);
+
+        let bytes = std::fs::read(&written[1]).unwrap();
+        let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+        let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+        assert!(header.contains("'shape': (2, 2, 2,)"));
+        assert_eq!((10 + header_len) % 64, 0);
+        assert_eq!(bytes.len(), 10 + header_len + 8 * 16);
+        let last = f64::from_le_bytes(bytes[bytes.len() - 16..bytes.len() - 8].try_into().unwrap());
+        assert_eq!(last, 4.0);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_write_steady_state() {
+        let mut results = SimulationResults::new();
+        for i in 0..100 {
+            results.add_observable("p", i as f64, Complex64::new(0.5, 0.0));
+        }
+
+        let path = std::env::temp_dir().join("chronophoton_steady_state_test.csv");
+        Exporter::write_steady_state(&results, &path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+
+        assert!(contents.starts_with("observable,mean,std_error"));
+        assert!(contents.contains("\np,0.5,0,0.5,75,0\n"));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_write_events() {
+        let mut results = SimulationResults::new();
+        for i in 0..5 {
+            results.add_observable("p", i as f64, Complex64::new(i as f64 / 4.0, 0.0));
+        }
+        let spec = crate::simulation::EventSpec {
+            observable: "p".to_string(),
+            thresholds: vec![0.6],
+            direction: "both".to_string(),
+            extrema: false,
+        };
+
+        let path = std::env::temp_dir().join("chronophoton_events_test.csv");
+        Exporter::write_events(&results.events(&[spec]).unwrap(), &path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            contents,
+            "observable,event,time,value,interval\np,rising,2.4,0.6,\n"
+        );
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_downsampling() {
+        let series: Vec<_> = (0..101)
+            .map(|i| {
+                let t = i as f64 * 0.1;
+                (t, Complex64::new(if i == 37 { 5.0 } else { t.sin() }, 0.0))
+            })
+            .collect();
+
+        let strided = Downsampling::Stride { every: 10 }.apply(&series);
+        assert_eq!(strided.len(), 11);
+        assert_eq!(strided.last(), series.last());
+
+        let decimated = Downsampling::PeakPreserving { max_points: 20 }.apply(&series);
+        assert!(decimated.len() <= 20);
+        assert!(decimated.iter().any(|&(_, v)| v.re == 5.0));
+        assert!(decimated.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn test_compressed_output_round_trips() {
+        let mut results = SimulationResults::new();
+        for i in 0..50 {
+            results.add_observable("p", i as f64, Complex64::new(0.25, 0.0));
+        }
+
+        let mut outputs = HashMap::new();
+        outputs.insert(
+            "p".to_string(),
+            ObservableOutputConfig {
+                file: Some("chronophoton_compressed_test.csv".to_string()),
+                name: None,
+                precision: "f64".to_string(),
+                downsample: Some(Downsampling::Stride { every: 7 }),
+                compression: "gzip".to_string(),
+            },
+        );
+
+        let written =
+            Exporter::write_observable_outputs(&mut results, &outputs, &std::env::temp_dir())
+                .unwrap();
+        assert!(written[0].to_str().unwrap().ends_with(".csv.gz"));
+
+        let mut contents = String::new();
+        flate2::read::GzDecoder::new(std::fs::File::open(&written[0]).unwrap())
+            .read_to_string(&mut contents)
+            .unwrap();
+        assert_eq!(contents.lines().count(), 1 + 8);
+        std::fs::remove_file(&written[0]).ok();
+
+        let path = std::env::temp_dir().join("chronophoton_compressed_test.zst");
+        let mut writer = Compression::Zstd.create(&path).unwrap();
+        writeln!(writer, "time,p_re,p_im").unwrap();
+        writer.finish().unwrap();
+        let decoded = zstd::decode_all(std::fs::File::open(&path).unwrap()).unwrap();
+        assert_eq!(decoded, b"time,p_re,p_im\n");
+        std::fs::remove_file(&path).ok();
+    }
 }
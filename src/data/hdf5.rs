@@ -0,0 +1,501 @@
+// A minimal HDF5 writer: superblock version 0, version 1 object headers,
+// symbol-table groups and contiguous datasets. This is the subset MATLAB itself
+// writes for v7.3 MAT-files, and every HDF5 release since 1.0 can read it.
+use crate::utils::{Error, Result};
+use std::io::{Seek, SeekFrom, Write};
+
+const SIGNATURE: &[u8; 8] = b"\x89HDF\r\n\x1a\n";
+const UNDEFINED: u64 = u64::MAX;
+const SUPERBLOCK_SIZE: usize = 96;
+const SYMBOL_ENTRY_SIZE: usize = 40;
+const GROUP_INTERNAL_K: usize = 16;
+const MIN_GROUP_LEAF_K: usize = 4;
+// The local heap marks the end of its free list with offset 1, not the undefined address.
+const HEAP_FREE_NULL: u64 = 1;
+
+const MSG_DATASPACE: u16 = 0x0001;
+const MSG_DATATYPE: u16 = 0x0003;
+const MSG_LAYOUT: u16 = 0x0008;
+const MSG_ATTRIBUTE: u16 = 0x000C;
+const MSG_SYMBOL_TABLE: u16 = 0x0011;
+const MSG_FLAG_CONSTANT: u8 = 0x01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Datatype {
+    F64,
+    ComplexF64,
+    U8,
+    U16,
+    I32,
+    U64,
+    Ascii(usize),
+}
+
+impl Datatype {
+    pub fn size(&self) -> usize {
+        match self {
+            Self::F64 | Self::U64 => 8,
+            Self::ComplexF64 => 16,
+            Self::U8 => 1,
+            Self::U16 => 2,
+            Self::I32 => 4,
+            Self::Ascii(len) => *len,
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            Self::F64 => {
+                // IEEE little-endian double: implied mantissa MSB, sign bit 63.
+                out.extend_from_slice(&[0x11, 0x20, 63, 0]);
+                out.extend_from_slice(&8u32.to_le_bytes());
+                out.extend_from_slice(&0u16.to_le_bytes());
+                out.extend_from_slice(&64u16.to_le_bytes());
+                out.extend_from_slice(&[52, 11, 0, 52]);
+                out.extend_from_slice(&1023u32.to_le_bytes());
+            }
+            Self::ComplexF64 => {
+                out.extend_from_slice(&[0x16, 2, 0, 0]);
+                out.extend_from_slice(&16u32.to_le_bytes());
+                for (name, offset) in [("real", 0u32), ("imag", 8)] {
+                    out.extend_from_slice(&padded(name.as_bytes(), 1));
+                    out.extend_from_slice(&offset.to_le_bytes());
+                    // Dimensionality, permutation and four dimension sizes, all unused.
+                    out.extend_from_slice(&[0; 28]);
+                    out.extend(Self::F64.encode());
+                }
+            }
+            Self::U8 | Self::U16 | Self::I32 | Self::U64 => {
+                let signed = if matches!(self, Self::I32) { 0x08 } else { 0 };
+                out.extend_from_slice(&[0x10, signed, 0, 0]);
+                out.extend_from_slice(&(self.size() as u32).to_le_bytes());
+                out.extend_from_slice(&0u16.to_le_bytes());
+                out.extend_from_slice(&(8 * self.size() as u16).to_le_bytes());
+            }
+            Self::Ascii(len) => {
+                out.extend_from_slice(&[0x13, 0, 0, 0]);
+                out.extend_from_slice(&(*len as u32).to_le_bytes());
+            }
+        }
+        out
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    pub name: String,
+    pub datatype: Datatype,
+    pub dims: Vec<u64>,
+    pub data: Vec<u8>,
+}
+
+impl Attribute {
+    pub fn string(name: &str, value: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            datatype: Datatype::Ascii(value.len()),
+            dims: Vec::new(),
+            data: value.as_bytes().to_vec(),
+        }
+    }
+
+    pub fn scalar(name: &str, datatype: Datatype, data: &[u8]) -> Self {
+        Self {
+            name: name.to_string(),
+            datatype,
+            dims: Vec::new(),
+            data: data.to_vec(),
+        }
+    }
+
+    fn encode(&self) -> Result<Vec<u8>> {
+        let name = padded(self.name.as_bytes(), 1);
+        let datatype = self.datatype.encode();
+        let dataspace = dataspace(&self.dims);
+        let elements: u64 = self.dims.iter().product();
+        if self.data.len() as u64 != elements * self.datatype.size() as u64 {
+            return Err(Error::Serialization(format!(
+                "HDF5 attribute '{}' has {} bytes of data for {} elements",
+                self.name,
+                self.data.len(),
+                elements
+            )));
+        }
+
+        let mut out = vec![1, 0];
+        out.extend_from_slice(&(self.name.len() as u16 + 1).to_le_bytes());
+        out.extend_from_slice(&(datatype.len() as u16).to_le_bytes());
+        out.extend_from_slice(&(dataspace.len() as u16).to_le_bytes());
+        out.extend(name);
+        out.extend(padded(&datatype, 0));
+        out.extend(padded(&dataspace, 0));
+        out.extend_from_slice(&self.data);
+        Ok(out)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ObjectRef {
+    header: u64,
+    // B-tree and local heap of a group, cached in its symbol table entries.
+    group: Option<(u64, u64)>,
+}
+
+pub struct Hdf5Writer<W: Write + Seek> {
+    out: W,
+    base: u64,
+    pos: u64,
+    leaf_k: usize,
+}
+
+impl<W: Write + Seek> Hdf5Writer<W> {
+    // `userblock` precedes the HDF5 data and must be 512 bytes or a larger power of
+    // two. Every group written later may hold up to `group_capacity` members.
+    pub fn new(mut out: W, userblock: &[u8], group_capacity: usize) -> Result<Self> {
+        if !userblock.is_empty() && (userblock.len() < 512 || !userblock.len().is_power_of_two()) {
+            return Err(Error::Serialization(format!(
+                "HDF5 user block must be 0 or a power of two >= 512 bytes, got {}",
+                userblock.len()
+            )));
+        }
+        let leaf_k = MIN_GROUP_LEAF_K.max(group_capacity.div_ceil(2));
+        if leaf_k > u16::MAX as usize {
+            return Err(Error::Serialization(format!(
+                "HDF5 groups are limited to {} members here, got {}",
+                2 * u16::MAX as usize,
+                group_capacity
+            )));
+        }
+        out.write_all(userblock)?;
+        out.write_all(&[0; SUPERBLOCK_SIZE])?;
+        Ok(Self {
+            out,
+            base: userblock.len() as u64,
+            pos: SUPERBLOCK_SIZE as u64,
+            leaf_k,
+        })
+    }
+
+    // Writes `bytes` at the next 8-byte aligned address and returns that address.
+    fn block(&mut self, bytes: &[u8]) -> Result<u64> {
+        let padding = (8 - self.pos % 8) % 8;
+        self.out.write_all(&[0; 8][..padding as usize])?;
+        let address = self.pos + padding;
+        self.out.write_all(bytes)?;
+        self.pos = address + bytes.len() as u64;
+        Ok(address)
+    }
+
+    fn object_header(
+        &mut self,
+        messages: &[(u16, u8, Vec<u8>)],
+        attributes: &[Attribute],
+    ) -> Result<u64> {
+        let mut encoded: Vec<(u16, u8, Vec<u8>)> = messages.to_vec();
+        for attribute in attributes {
+            encoded.push((MSG_ATTRIBUTE, 0, attribute.encode()?));
+        }
+
+        let mut body = Vec::new();
+        for (kind, flags, data) in &encoded {
+            let data = padded(data, 0);
+            let size = u16::try_from(data.len()).map_err(|_| {
+                Error::Serialization(format!(
+                    "HDF5 header message of {} bytes is too large",
+                    data.len()
+                ))
+            })?;
+            body.extend_from_slice(&kind.to_le_bytes());
+            body.extend_from_slice(&size.to_le_bytes());
+            body.extend_from_slice(&[*flags, 0, 0, 0]);
+            body.extend(data);
+        }
+
+        let mut header = vec![1, 0];
+        header.extend_from_slice(&(encoded.len() as u16).to_le_bytes());
+        header.extend_from_slice(&1u32.to_le_bytes());
+        header.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        header.extend_from_slice(&[0; 4]);
+        header.extend(body);
+        self.block(&header)
+    }
+
+    pub fn dataset(
+        &mut self,
+        datatype: Datatype,
+        dims: &[u64],
+        data: &[u8],
+        attributes: &[Attribute],
+    ) -> Result<ObjectRef> {
+        let elements: u64 = dims.iter().product();
+        if data.len() as u64 != elements * datatype.size() as u64 || elements == 0 {
+            return Err(Error::Serialization(format!(
+                "HDF5 dataset of shape {:?} cannot hold {} bytes",
+                dims,
+                data.len()
+            )));
+        }
+        let address = self.block(data)?;
+
+        let mut layout = vec![3, 1];
+        layout.extend_from_slice(&address.to_le_bytes());
+        layout.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        let messages = [
+            (MSG_DATASPACE, 0, dataspace(dims)),
+            (MSG_DATATYPE, MSG_FLAG_CONSTANT, datatype.encode()),
+            (MSG_LAYOUT, 0, layout),
+        ];
+        Ok(ObjectRef {
+            header: self.object_header(&messages, attributes)?,
+            group: None,
+        })
+    }
+
+    pub fn group(
+        &mut self,
+        members: &[(String, ObjectRef)],
+        attributes: &[Attribute],
+    ) -> Result<ObjectRef> {
+        if members.len() > 2 * self.leaf_k {
+            return Err(Error::Serialization(format!(
+                "HDF5 group has {} members, more than the {} the file was sized for",
+                members.len(),
+                2 * self.leaf_k
+            )));
+        }
+        let mut members: Vec<&(String, ObjectRef)> = members.iter().collect();
+        members.sort_by(|a, b| a.0.as_bytes().cmp(b.0.as_bytes()));
+        if let Some(pair) = members.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(Error::Serialization(format!(
+                "HDF5 group has two members named '{}'",
+                pair[0].0
+            )));
+        }
+
+        // Local heap: the empty name at offset 0, then every member name.
+        let mut names = vec![0u8; 8];
+        let mut offsets = Vec::with_capacity(members.len());
+        for (name, _) in &members {
+            if name.is_empty() || name.contains(['/', '\0']) {
+                return Err(Error::Serialization(format!(
+                    "'{}' is not a valid HDF5 link name",
+                    name
+                )));
+            }
+            offsets.push(names.len() as u64);
+            names.extend(padded(name.as_bytes(), 1));
+        }
+        let heap_data = self.block(&names)?;
+        let mut heap = b"HEAP".to_vec();
+        heap.extend_from_slice(&[0; 4]);
+        heap.extend_from_slice(&(names.len() as u64).to_le_bytes());
+        heap.extend_from_slice(&HEAP_FREE_NULL.to_le_bytes());
+        heap.extend_from_slice(&heap_data.to_le_bytes());
+        let heap = self.block(&heap)?;
+
+        // One symbol node holds every member; the B-tree root points at it.
+        let node = if members.is_empty() {
+            None
+        } else {
+            let mut node = b"SNOD".to_vec();
+            node.extend_from_slice(&[1, 0]);
+            node.extend_from_slice(&(members.len() as u16).to_le_bytes());
+            for ((_, object), &offset) in members.iter().zip(&offsets) {
+                node.extend(symbol_entry(offset, object));
+            }
+            node.resize(8 + 2 * self.leaf_k * SYMBOL_ENTRY_SIZE, 0);
+            Some(self.block(&node)?)
+        };
+
+        let mut btree = b"TREE".to_vec();
+        btree.extend_from_slice(&[0, 0]);
+        btree.extend_from_slice(&(node.is_some() as u16).to_le_bytes());
+        btree.extend_from_slice(&UNDEFINED.to_le_bytes());
+        btree.extend_from_slice(&UNDEFINED.to_le_bytes());
+        if let Some(node) = node {
+            // Keys bracket the child's names: the empty name, then the last one.
+            let last = *offsets.last().expect("members is not empty");
+            btree.extend_from_slice(&0u64.to_le_bytes());
+            btree.extend_from_slice(&node.to_le_bytes());
+            btree.extend_from_slice(&last.to_le_bytes());
+        }
+        btree.resize(
+            24 + 2 * GROUP_INTERNAL_K * 8 + (2 * GROUP_INTERNAL_K + 1) * 8,
+            0,
+        );
+        let btree = self.block(&btree)?;
+
+        let mut table = btree.to_le_bytes().to_vec();
+        table.extend_from_slice(&heap.to_le_bytes());
+        Ok(ObjectRef {
+            header: self.object_header(&[(MSG_SYMBOL_TABLE, 0, table)], attributes)?,
+            group: Some((btree, heap)),
+        })
+    }
+
+    // Writes the root group and the superblock, and returns the underlying writer.
+    pub fn finish(mut self, members: &[(String, ObjectRef)]) -> Result<W> {
+        let root = self.group(members, &[])?;
+        let end = self.pos;
+
+        let mut superblock = SIGNATURE.to_vec();
+        superblock.extend_from_slice(&[0, 0, 0, 0, 0, 8, 8, 0]);
+        superblock.extend_from_slice(&(self.leaf_k as u16).to_le_bytes());
+        superblock.extend_from_slice(&(GROUP_INTERNAL_K as u16).to_le_bytes());
+        superblock.extend_from_slice(&0u32.to_le_bytes());
+        superblock.extend_from_slice(&self.base.to_le_bytes());
+        superblock.extend_from_slice(&UNDEFINED.to_le_bytes());
+        superblock.extend_from_slice(&end.to_le_bytes());
+        superblock.extend_from_slice(&UNDEFINED.to_le_bytes());
+        superblock.extend(symbol_entry(0, &root));
+        debug_assert_eq!(superblock.len(), SUPERBLOCK_SIZE);
+
+        self.out.seek(SeekFrom::Start(self.base))?;
+        self.out.write_all(&superblock)?;
+        self.out.seek(SeekFrom::Start(self.base + end))?;
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+fn symbol_entry(name_offset: u64, object: &ObjectRef) -> Vec<u8> {
+    let mut entry = name_offset.to_le_bytes().to_vec();
+    entry.extend_from_slice(&object.header.to_le_bytes());
+    match object.group {
+        Some((btree, heap)) => {
+            entry.extend_from_slice(&1u32.to_le_bytes());
+            entry.extend_from_slice(&[0; 4]);
+            entry.extend_from_slice(&btree.to_le_bytes());
+            entry.extend_from_slice(&heap.to_le_bytes());
+        }
+        None => entry.extend_from_slice(&[0; 24]),
+    }
+    entry
+}
+
+fn dataspace(dims: &[u64]) -> Vec<u8> {
+    let mut out = vec![1, dims.len() as u8, 0, 0, 0, 0, 0, 0];
+    for dim in dims {
+        out.extend_from_slice(&dim.to_le_bytes());
+    }
+    out
+}
+
+// Pads to a multiple of 8 bytes after appending `terminators` zero bytes.
+fn padded(bytes: &[u8], terminators: usize) -> Vec<u8> {
+    let mut out = bytes.to_vec();
+    out.resize((bytes.len() + terminators).div_ceil(8) * 8, 0);
+    out
+}
+
+#[cfg(test)]
+pub(crate) mod reader {
+    pub(crate) fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+    }
+
+    pub(crate) fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    pub(crate) fn u64_at(bytes: &[u8], offset: usize) -> usize {
+        u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap()) as usize
+    }
+
+    // Walks the HDF5 structures the way the library does, relative to the
+    // superblock that follows the user block.
+    pub(crate) struct Hdf5Reader<'a> {
+        file: &'a [u8],
+        base: usize,
+    }
+
+    impl<'a> Hdf5Reader<'a> {
+        pub(crate) fn new(file: &'a [u8], base: usize) -> Self {
+            Self { file, base }
+        }
+
+        // Members of the root group, whose entry closes the superblock.
+        pub(crate) fn root(&self) -> Vec<(String, usize)> {
+            self.members(u64_at(self.at(0), 64))
+        }
+
+        fn at(&self, address: usize) -> &'a [u8] {
+            &self.file[self.base + address..]
+        }
+
+        fn messages(&self, header: usize) -> Vec<(u16, &'a [u8])> {
+            let object = self.at(header);
+            assert_eq!(object[0], 1);
+            let mut offset = 16;
+            let mut messages = Vec::new();
+            for _ in 0..u16_at(object, 2) {
+                let size = u16_at(object, offset + 2) as usize;
+                messages.push((
+                    u16_at(object, offset),
+                    &object[offset + 8..offset + 8 + size],
+                ));
+                offset += 8 + size;
+            }
+            assert_eq!(offset - 16, u32_at(object, 8) as usize);
+            messages
+        }
+
+        pub(crate) fn members(&self, header: usize) -> Vec<(String, usize)> {
+            let (_, table) = self
+                .messages(header)
+                .into_iter()
+                .find(|(kind, _)| *kind == 0x0011)
+                .expect("groups have a symbol table");
+            let btree = self.at(u64_at(table, 0));
+            let heap = self.at(u64_at(table, 8));
+            assert_eq!(&btree[..4], b"TREE");
+            assert_eq!(&heap[..4], b"HEAP");
+            let names = self.at(u64_at(heap, 24));
+
+            let mut members = Vec::new();
+            for child in 0..u16_at(btree, 6) as usize {
+                let node = self.at(u64_at(btree, 24 + 8 + 16 * child));
+                assert_eq!(&node[..4], b"SNOD");
+                for k in 0..u16_at(node, 6) as usize {
+                    let entry = &node[8 + 40 * k..];
+                    let name = &names[u64_at(entry, 0)..];
+                    let end = name.iter().position(|&b| b == 0).unwrap();
+                    members.push((
+                        String::from_utf8(name[..end].to_vec()).unwrap(),
+                        u64_at(entry, 8),
+                    ));
+                }
+            }
+            members
+        }
+
+        // Attribute data, followed by the message padding.
+        pub(crate) fn attribute(&self, header: usize, name: &str) -> Option<&'a [u8]> {
+            self.messages(header).into_iter().find_map(|(kind, data)| {
+                let name_size = u16_at(data, 2) as usize;
+                (kind == 0x000C && &data[8..8 + name_size - 1] == name.as_bytes()).then(|| {
+                    let padded = |n: usize| n.div_ceil(8) * 8;
+                    let start = 8
+                        + padded(name_size)
+                        + padded(u16_at(data, 4) as usize)
+                        + padded(u16_at(data, 6) as usize);
+                    &data[start..]
+                })
+            })
+        }
+
+        pub(crate) fn dataset(&self, header: usize) -> (Vec<usize>, &'a [u8]) {
+            let messages = self.messages(header);
+            let find = |wanted| messages.iter().find(|(kind, _)| *kind == wanted).unwrap().1;
+            let space = find(0x0001);
+            let dims = (0..space[1] as usize)
+                .map(|k| u64_at(space, 8 + 8 * k))
+                .collect();
+            let layout = find(0x0008);
+            assert_eq!(&layout[..2], &[3, 1]);
+            let data = &self.at(u64_at(layout, 2))[..u64_at(layout, 10)];
+            (dims, data)
+        }
+    }
+}
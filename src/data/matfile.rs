@@ -0,0 +1,593 @@
+use crate::data::hdf5::{Attribute, Datatype, Hdf5Writer, ObjectRef};
+use crate::data::Config;
+use crate::simulation::SimulationResults;
+use crate::utils::{Error, Result};
+use num_complex::Complex64;
+use std::io::{Cursor, Seek, Write};
+use std::path::Path;
+
+const MI_INT8: u32 = 1;
+const MI_UINT16: u32 = 4;
+const MI_INT32: u32 = 5;
+const MI_UINT32: u32 = 6;
+const MI_DOUBLE: u32 = 9;
+const MI_MATRIX: u32 = 14;
+
+const MX_STRUCT_CLASS: u32 = 2;
+const MX_CHAR_CLASS: u32 = 4;
+const MX_DOUBLE_CLASS: u32 = 6;
+const COMPLEX_FLAG: u32 = 0x0800;
+
+const MAX_NAME_LENGTH: usize = 63;
+const USER_BLOCK_SIZE: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MatVersion {
+    // Level 5: readable by every MATLAB release and scipy.io.loadmat, but each
+    // variable is limited to 4 GiB.
+    V5,
+    // HDF5-based, no size limits. Needs MATLAB R2006b or later, or h5py.
+    #[default]
+    V73,
+}
+
+impl MatVersion {
+    pub fn parse(version: &str) -> Result<Self> {
+        match version {
+            "5" => Ok(Self::V5),
+            "7.3" => Ok(Self::V73),
+            other => Err(Error::Config(format!(
+                "Unknown MAT-file version '{}' (expected 5 or 7.3)",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum MatValue {
+    Double {
+        dims: Vec<usize>,
+        re: Vec<f64>,
+        im: Option<Vec<f64>>,
+    },
+    Char(String),
+    Struct(Vec<(String, MatValue)>),
+}
+
+impl MatValue {
+    pub fn column(values: &[f64]) -> Self {
+        Self::Double {
+            dims: vec![values.len(), 1],
+            re: values.to_vec(),
+            im: None,
+        }
+    }
+
+    pub fn complex_matrix(rows: usize, cols: usize, column_major: &[Complex64]) -> Self {
+        Self::complex_array(&[rows, cols], column_major)
+    }
+
+    pub fn complex_array(dims: &[usize], column_major: &[Complex64]) -> Self {
+        let im: Vec<f64> = column_major.iter().map(|v| v.im).collect();
+        Self::Double {
+            dims: dims.to_vec(),
+            re: column_major.iter().map(|v| v.re).collect(),
+            im: im.iter().any(|&v| v != 0.0).then_some(im),
+        }
+    }
+
+    fn encode(&self, name: &str) -> Result<Vec<u8>> {
+        let mut body = Vec::new();
+        match self {
+            Self::Double { dims, re, im } => {
+                let flags = MX_DOUBLE_CLASS | if im.is_some() { COMPLEX_FLAG } else { 0 };
+                write_header(&mut body, flags, dims, name)?;
+                write_element(&mut body, MI_DOUBLE, &f64_bytes(re))?;
+                if let Some(im) = im {
+                    write_element(&mut body, MI_DOUBLE, &f64_bytes(im))?;
+                }
+            }
+            Self::Char(text) => {
+                let units: Vec<u16> = text.encode_utf16().collect();
+                write_header(&mut body, MX_CHAR_CLASS, &[1, units.len()], name)?;
+                let bytes: Vec<u8> = units.iter().flat_map(|u| u.to_le_bytes()).collect();
+                write_element(&mut body, MI_UINT16, &bytes)?;
+            }
+            Self::Struct(fields) => {
+                write_header(&mut body, MX_STRUCT_CLASS, &[1, 1], name)?;
+
+                let field_length = fields
+                    .iter()
+                    .map(|(field, _)| field.len() + 1)
+                    .max()
+                    .unwrap_or(1);
+                body.extend_from_slice(&((4 << 16) | MI_INT32).to_le_bytes());
+                body.extend_from_slice(&(field_length as u32).to_le_bytes());
+
+                let mut names = vec![0u8; field_length * fields.len()];
+                for (k, (field, _)) in fields.iter().enumerate() {
+                    names[k * field_length..k * field_length + field.len()]
+                        .copy_from_slice(field.as_bytes());
+                }
+                write_element(&mut body, MI_INT8, &names)?;
+
+                for (_, value) in fields {
+                    body.extend(value.encode("")?);
+                }
+            }
+        }
+
+        let mut out = Vec::with_capacity(body.len() + 8);
+        out.extend_from_slice(&MI_MATRIX.to_le_bytes());
+        out.extend_from_slice(&element_length(body.len())?.to_le_bytes());
+        out.extend(body);
+        Ok(out)
+    }
+
+    // MATLAB stores arrays column-major, HDF5 row-major, so v7.3 files list the
+    // dimensions in reverse and the data keeps its order.
+    fn write_hdf5<W: Write + Seek>(&self, writer: &mut Hdf5Writer<W>) -> Result<ObjectRef> {
+        match self {
+            Self::Double { dims, re, im } => {
+                let class = Attribute::string("MATLAB_class", "double");
+                if re.is_empty() {
+                    return write_empty(writer, dims, vec![class]);
+                }
+                let (datatype, bytes) = match im {
+                    Some(im) => (
+                        Datatype::ComplexF64,
+                        re.iter()
+                            .zip(im)
+                            .flat_map(|(r, i)| r.to_le_bytes().into_iter().chain(i.to_le_bytes()))
+                            .collect(),
+                    ),
+                    None => (Datatype::F64, f64_bytes(re)),
+                };
+                writer.dataset(datatype, &hdf5_dims(dims), &bytes, &[class])
+            }
+            Self::Char(text) => {
+                let units: Vec<u16> = text.encode_utf16().collect();
+                let attributes = vec![
+                    Attribute::string("MATLAB_class", "char"),
+                    Attribute::scalar("MATLAB_int_decode", Datatype::I32, &2i32.to_le_bytes()),
+                ];
+                if units.is_empty() {
+                    return write_empty(writer, &[0, 0], attributes);
+                }
+                let bytes: Vec<u8> = units.iter().flat_map(|u| u.to_le_bytes()).collect();
+                writer.dataset(
+                    Datatype::U16,
+                    &hdf5_dims(&[1, units.len()]),
+                    &bytes,
+                    &attributes,
+                )
+            }
+            Self::Struct(fields) => {
+                let mut members = Vec::with_capacity(fields.len());
+                for (field, value) in fields {
+                    members.push((field.clone(), value.write_hdf5(writer)?));
+                }
+                writer.group(&members, &[Attribute::string("MATLAB_class", "struct")])
+            }
+        }
+    }
+
+    fn largest_struct(&self) -> usize {
+        match self {
+            Self::Struct(fields) => fields
+                .iter()
+                .map(|(_, value)| value.largest_struct())
+                .max()
+                .unwrap_or(0)
+                .max(fields.len()),
+            _ => 0,
+        }
+    }
+}
+
+// Empty arrays are stored as their MATLAB dimensions, flagged by MATLAB_empty.
+fn write_empty<W: Write + Seek>(
+    writer: &mut Hdf5Writer<W>,
+    dims: &[usize],
+    mut attributes: Vec<Attribute>,
+) -> Result<ObjectRef> {
+    attributes.push(Attribute::scalar("MATLAB_empty", Datatype::U8, &[1]));
+    let bytes: Vec<u8> = dims
+        .iter()
+        .flat_map(|&d| (d as u64).to_le_bytes())
+        .collect();
+    writer.dataset(Datatype::U64, &[dims.len() as u64], &bytes, &attributes)
+}
+
+fn hdf5_dims(dims: &[usize]) -> Vec<u64> {
+    dims.iter().rev().map(|&d| d as u64).collect()
+}
+
+fn element_length(len: usize) -> Result<u32> {
+    u32::try_from(len).map_err(|_| {
+        Error::InvalidParameter(format!(
+            "A MAT v5 element of {} bytes exceeds the 4 GiB limit of the format, \
+             set mat_version = \"7.3\" under [output]",
+            len
+        ))
+    })
+}
+
+fn write_header(out: &mut Vec<u8>, flags: u32, dims: &[usize], name: &str) -> Result<()> {
+    let mut flag_bytes = flags.to_le_bytes().to_vec();
+    flag_bytes.extend_from_slice(&[0; 4]);
+    write_element(out, MI_UINT32, &flag_bytes)?;
+
+    let mut dim_bytes = Vec::with_capacity(4 * dims.len());
+    for &d in dims {
+        let d = i32::try_from(d).map_err(|_| {
+            Error::InvalidParameter(format!(
+                "A MAT v5 dimension of {} exceeds the format's limit, \
+                 set mat_version = \"7.3\" under [output]",
+                d
+            ))
+        })?;
+        dim_bytes.extend_from_slice(&d.to_le_bytes());
+    }
+    write_element(out, MI_INT32, &dim_bytes)?;
+    write_element(out, MI_INT8, name.as_bytes())
+}
+
+fn write_element(out: &mut Vec<u8>, data_type: u32, data: &[u8]) -> Result<()> {
+    out.extend_from_slice(&data_type.to_le_bytes());
+    out.extend_from_slice(&element_length(data.len())?.to_le_bytes());
+    out.extend_from_slice(data);
+    out.resize(out.len() + (8 - data.len() % 8) % 8, 0);
+    Ok(())
+}
+
+fn f64_bytes(values: &[f64]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+pub fn matlab_identifier(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !ident.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        ident.insert(0, 'x');
+    }
+    ident.truncate(MAX_NAME_LENGTH);
+    ident
+}
+
+// Distinct names such as `<n>` and `[n]` map to the same identifier, so a name
+// already in `taken` gets a numeric suffix.
+pub fn unique_identifier<'a>(
+    name: &str,
+    taken: impl IntoIterator<Item = &'a String> + Clone,
+) -> String {
+    let ident = matlab_identifier(name);
+    let is_taken = |candidate: &str| taken.clone().into_iter().any(|t| t == candidate);
+    if !is_taken(&ident) {
+        return ident;
+    }
+    (2..)
+        .map(|k| {
+            let suffix = format!("_{}", k);
+            let mut base = ident.clone();
+            base.truncate(MAX_NAME_LENGTH - suffix.len());
+            base + &suffix
+        })
+        .find(|candidate| !is_taken(candidate))
+        .expect("suffixes are unbounded")
+}
+
+fn push_field(fields: &mut Vec<(String, MatValue)>, name: &str, value: MatValue) -> String {
+    let field = unique_identifier(name, fields.iter().map(|(f, _)| f));
+    fields.push((field.clone(), value));
+    field
+}
+
+#[derive(Debug, Default)]
+pub struct MatFile {
+    variables: Vec<(String, MatValue)>,
+    version: MatVersion,
+}
+
+impl MatFile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_version(mut self, version: MatVersion) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn add(&mut self, name: &str, value: MatValue) {
+        push_field(&mut self.variables, name, value);
+    }
+
+    pub fn from_results(results: &SimulationResults, config: &Config) -> Result<Self> {
+        let mut file = Self::new().with_version(MatVersion::parse(&config.output.mat_version)?);
+        file.add("t", MatValue::column(results.times()));
+
+        let mut names = results.observable_names();
+        names.sort();
+        let mut observables = Vec::new();
+        let mut observable_times = Vec::new();
+        for name in names {
+            let series = results.series(name).expect("name comes from results");
+            let field = push_field(
+                &mut observables,
+                name,
+                MatValue::complex_matrix(series.len(), 1, series.values),
+            );
+            observable_times.push((field, MatValue::column(series.times)));
+        }
+        file.add("observables", MatValue::Struct(observables));
+        file.add("observable_times", MatValue::Struct(observable_times));
+
+        let mut vectors = Vec::new();
+        for series in results.iter_vector_series() {
+            let value = match series.to_array3() {
+                Some(array) => {
+                    let column_major: Vec<Complex64> = array.t().iter().copied().collect();
+                    MatValue::complex_array(array.shape(), &column_major)
+                }
+                None => {
+                    let matrix = series.to_ndarray();
+                    let column_major: Vec<Complex64> = matrix.t().iter().copied().collect();
+                    MatValue::complex_matrix(series.len(), series.width, &column_major)
+                }
+            };
+            push_field(&mut vectors, series.name, value);
+        }
+        file.add("vectors", MatValue::Struct(vectors));
+
+        let config_text = toml::to_string_pretty(config)
+            .map_err(|e| Error::Serialization(format!("TOML error: {}", e)))?;
+        let mut metadata = vec![
+            (
+                "name".to_string(),
+                MatValue::Char(config.simulation.name.clone()),
+            ),
+            (
+                "chronophoton_version".to_string(),
+                MatValue::Char(env!("CARGO_PKG_VERSION").to_string()),
+            ),
+            ("config".to_string(), MatValue::Char(config_text)),
+        ];
+        if let Some(resources) = results.resources() {
+            let fields = resources
+                .metrics()
+                .into_iter()
+                .map(|(name, value)| (name, MatValue::column(&[value])))
+                .collect();
+            metadata.push(("resources".to_string(), MatValue::Struct(fields)));
+        }
+        file.add("metadata", MatValue::Struct(metadata));
+
+        Ok(file)
+    }
+
+    fn header(&self) -> Vec<u8> {
+        let created = chrono::Utc::now().format("%a %b %e %H:%M:%S %Y");
+        let (mut text, version) = match self.version {
+            MatVersion::V5 => (
+                format!(
+                    "MATLAB 5.0 MAT-file, Platform: {}, Created on: {}, by ChronoPhoton",
+                    std::env::consts::OS,
+                    created
+                ),
+                0x0100u16,
+            ),
+            MatVersion::V73 => (
+                format!(
+                    "MATLAB 7.3 MAT-file, Platform: {}, Created on: {} HDF5 schema 1.00 .",
+                    std::env::consts::OS,
+                    created
+                ),
+                0x0200u16,
+            ),
+        };
+        text.truncate(116);
+        let mut out = text.into_bytes();
+        out.resize(116, b' ');
+        out.extend_from_slice(&[0; 8]);
+        out.extend_from_slice(&version.to_le_bytes());
+        out.extend_from_slice(b"IM");
+        out
+    }
+
+    fn to_v5_bytes(&self) -> Result<Vec<u8>> {
+        let mut out = self.header();
+        for (name, value) in &self.variables {
+            out.extend(value.encode(name)?);
+        }
+        Ok(out)
+    }
+
+    // v7.3 files are HDF5 files whose 512-byte user block holds the MAT header.
+    fn write_v73<W: Write + Seek>(&self, out: W) -> Result<W> {
+        let mut user_block = self.header();
+        user_block.resize(USER_BLOCK_SIZE, 0);
+        let capacity = self
+            .variables
+            .iter()
+            .map(|(_, value)| value.largest_struct())
+            .max()
+            .unwrap_or(0)
+            .max(self.variables.len());
+
+        let mut writer = Hdf5Writer::new(out, &user_block, capacity)?;
+        let mut members = Vec::with_capacity(self.variables.len());
+        for (name, value) in &self.variables {
+            members.push((name.clone(), value.write_hdf5(&mut writer)?));
+        }
+        writer.finish(&members)
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        match self.version {
+            MatVersion::V5 => self.to_v5_bytes(),
+            MatVersion::V73 => Ok(self.write_v73(Cursor::new(Vec::new()))?.into_inner()),
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        match self.version {
+            MatVersion::V5 => file.write_all(&self.to_v5_bytes()?)?,
+            MatVersion::V73 => file = self.write_v73(file)?,
+        }
+        file.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::hdf5::reader::{u16_at, u32_at, u64_at, Hdf5Reader};
+
+    #[test]
+    fn test_matrix_layout() {
+        let mut file = MatFile::new().with_version(MatVersion::V5);
+        file.add("t", MatValue::column(&[0.0, 0.5, 1.0]));
+        let bytes = file.to_bytes().unwrap();
+
+        assert!(bytes.starts_with(b"MATLAB 5.0 MAT-file"));
+        assert_eq!(&bytes[126..128], b"IM");
+
+        let element = &bytes[128..];
+        assert_eq!(u32_at(element, 0), MI_MATRIX);
+        assert_eq!(u32_at(element, 4) as usize, element.len() - 8);
+        assert_eq!(u32_at(element, 16), MX_DOUBLE_CLASS);
+        assert_eq!((u32_at(element, 32), u32_at(element, 36)), (3, 1));
+        assert_eq!(u32_at(element, 56), MI_DOUBLE);
+        assert_eq!(u32_at(element, 60), 24);
+        assert_eq!(element.len() % 8, 0);
+    }
+
+    #[test]
+    fn test_v5_refuses_elements_over_4_gib() {
+        assert_eq!(element_length(24).unwrap(), 24);
+        assert!(element_length(u32::MAX as usize + 1).is_err());
+    }
+
+    #[test]
+    fn test_v73_layout() {
+        let mut file = MatFile::new();
+        file.add("t", MatValue::column(&[0.0, 0.5, 1.0]));
+        file.add(
+            "s",
+            MatValue::Struct(vec![
+                ("name".to_string(), MatValue::Char("tls".to_string())),
+                (
+                    "z".to_string(),
+                    MatValue::complex_matrix(
+                        1,
+                        2,
+                        &[Complex64::new(1.0, 2.0), Complex64::new(3.0, 4.0)],
+                    ),
+                ),
+                ("empty".to_string(), MatValue::column(&[])),
+            ]),
+        );
+        let bytes = file.to_bytes().unwrap();
+
+        assert!(bytes.starts_with(b"MATLAB 7.3 MAT-file"));
+        assert_eq!(u16_at(&bytes, 124), 0x0200);
+        assert_eq!(&bytes[126..128], b"IM");
+        assert_eq!(&bytes[512..520], b"\x89HDF\r\n\x1a\n");
+
+        let superblock = &bytes[512..];
+        assert_eq!(u64_at(superblock, 24), 512);
+        assert_eq!(u64_at(superblock, 40) + 512, bytes.len());
+        let reader = Hdf5Reader::new(&bytes, USER_BLOCK_SIZE);
+        let root = reader.members(u64_at(superblock, 64));
+        let names: Vec<&str> = root.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["s", "t"]);
+
+        let (dims, data) = reader.dataset(root[1].1);
+        assert_eq!(dims, [1, 3]);
+        assert_eq!(data, f64_bytes(&[0.0, 0.5, 1.0]));
+        assert!(reader
+            .attribute(root[1].1, "MATLAB_class")
+            .unwrap()
+            .starts_with(b"double"));
+
+        let s = root[0].1;
+        assert!(reader
+            .attribute(s, "MATLAB_class")
+            .unwrap()
+            .starts_with(b"struct"));
+        let fields = reader.members(s);
+        let names: Vec<&str> = fields.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, ["empty", "name", "z"]);
+
+        assert!(reader
+            .attribute(fields[0].1, "MATLAB_empty")
+            .unwrap()
+            .starts_with(&[1]));
+        let (dims, data) = reader.dataset(fields[0].1);
+        assert_eq!(dims, [2]);
+        assert_eq!(u64_at(data, 0), 0);
+
+        let (dims, data) = reader.dataset(fields[1].1);
+        assert_eq!(dims, [3, 1]);
+        assert_eq!(data, [b't', 0, b'l', 0, b's', 0]);
+        assert!(reader
+            .attribute(fields[1].1, "MATLAB_class")
+            .unwrap()
+            .starts_with(b"char"));
+
+        let (dims, data) = reader.dataset(fields[2].1);
+        assert_eq!(dims, [2, 1]);
+        assert_eq!(data, f64_bytes(&[1.0, 2.0, 3.0, 4.0]));
+    }
+
+    #[test]
+    fn test_results_export() {
+        let mut results = SimulationResults::new();
+        results.add_observable("<n>", 0.0, Complex64::new(1.0, 0.0));
+        results.add_observable("<n>", 1.0, Complex64::new(0.5, 0.1));
+        results.add_observable("[n]", 0.0, Complex64::new(2.0, 0.0));
+        let mut config = Config::generate_template("driven_tls").unwrap();
+
+        let file = MatFile::from_results(&results, &config).unwrap();
+        assert_eq!(file.version, MatVersion::V73);
+        let names: Vec<&str> = file.variables.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "t",
+                "observables",
+                "observable_times",
+                "vectors",
+                "metadata"
+            ]
+        );
+        assert_eq!(matlab_identifier("<n>"), "x_n_");
+        let MatValue::Struct(observables) = &file.variables[1].1 else {
+            panic!("observables is a struct");
+        };
+        let fields: Vec<&str> = observables.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(fields, ["x_n_", "x_n__2"]);
+        assert!(file.to_bytes().is_ok());
+
+        config.output.mat_version = "5".to_string();
+        let file = MatFile::from_results(&results, &config).unwrap();
+        assert_eq!(file.to_bytes().unwrap().len() % 8, 0);
+        config.output.mat_version = "7".to_string();
+        assert!(MatFile::from_results(&results, &config).is_err());
+    }
+
+    #[test]
+    fn test_unique_identifier_respects_length_limit() {
+        let long = "a".repeat(80);
+        let taken = vec![matlab_identifier(&long)];
+        let unique = unique_identifier(&long, &taken);
+        assert_eq!(unique.len(), MAX_NAME_LENGTH);
+        assert!(unique.ends_with("_2"));
+    }
+}
@@ -0,0 +1,174 @@
+use crate::data::config::MeasurementConfig;
+use crate::simulation::SimulationResults;
+use crate::utils::{Error, Result};
+use num_complex::Complex64;
+use rand_distr::{Binomial, Distribution, Normal};
+
+// Round-off allowed on a probability before shot sampling rejects it.
+const PROBABILITY_TOLERANCE: f64 = 1e-9;
+
+pub struct MeasurementModel {
+    efficiency: f64,
+    dark_counts: f64,
+    amplifier_noise: f64,
+    shots: usize,
+    seed: Option<u64>,
+}
+
+impl MeasurementModel {
+    pub fn new(
+        efficiency: f64,
+        dark_counts: f64,
+        amplifier_noise: f64,
+        shots: usize,
+        seed: Option<u64>,
+    ) -> Result<Self> {
+        if !(0.0..=1.0).contains(&efficiency) {
+            return Err(Error::InvalidParameter(format!(
+                "Detector efficiency must be in [0, 1], got {}",
+                efficiency
+            )));
+        }
+
+        if dark_counts < 0.0 {
+            return Err(Error::InvalidParameter(
+                "Dark count level must be non-negative".to_string(),
+            ));
+        }
+
+        if amplifier_noise < 0.0 {
+            return Err(Error::InvalidParameter(
+                "Amplifier noise must be non-negative".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            efficiency,
+            dark_counts,
+            amplifier_noise,
+            shots,
+            seed,
+        })
+    }
+
+    pub fn from_config(config: &MeasurementConfig) -> Result<Self> {
+        Self::new(
+            config.efficiency,
+            config.dark_counts,
+            config.amplifier_noise,
+            config.shots,
+            config.seed,
+        )
+    }
+
+    pub fn ideal() -> Self {
+        Self {
+            efficiency: 1.0,
+            dark_counts: 0.0,
+            amplifier_noise: 0.0,
+            shots: 0,
+            seed: None,
+        }
+    }
+
+    // Replaces each scalar series with what the detector reports and keeps
+    // everything else: snapshots, vector series, termination and step history.
+    pub fn apply(&self, results: &SimulationResults) -> Result<SimulationResults> {
+        let mut rng = crate::utils::performance::seeded_rng(self.seed);
+
+        let noise = Normal::new(0.0, self.amplifier_noise)
+            .map_err(|e| Error::InvalidParameter(e.to_string()))?;
+
+        let mut measured = results.clone();
+        for series in results.iter_series() {
+            let mut values = Vec::with_capacity(series.len());
+            for (_, value) in series.iter() {
+                let mut signal = self.efficiency * value.re + self.dark_counts;
+
+                if self.shots > 0 {
+                    if !(-PROBABILITY_TOLERANCE..=1.0 + PROBABILITY_TOLERANCE).contains(&value.re) {
+                        return Err(Error::InvalidParameter(format!(
+                            "Shot sampling needs observables bounded to [0, 1], but '{}' reaches {}",
+                            series.name, value.re
+                        )));
+                    }
+                    let p = signal.clamp(0.0, 1.0);
+                    let clicks = Binomial::new(self.shots as u64, p)
+                        .map_err(|e| Error::InvalidParameter(e.to_string()))?
+                        .sample(&mut rng);
+                    signal = clicks as f64 / self.shots as f64;
+                }
+
+                if self.amplifier_noise > 0.0 {
+                    signal += noise.sample(&mut rng);
+                }
+
+                values.push(Complex64::new(signal, 0.0));
+            }
+            measured.set_series_values(series.name, values)?;
+        }
+
+        Ok(measured)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn sample_results() -> SimulationResults {
+        let mut results = SimulationResults::new();
+        for i in 0..10 {
+            results.add_observable("population", i as f64, Complex64::new(0.5, 0.0));
+        }
+        results
+    }
+
+    #[test]
+    fn test_efficiency_and_dark_counts() {
+        let model = MeasurementModel::new(0.8, 0.01, 0.0, 0, None).unwrap();
+        let measured = model.apply(&sample_results()).unwrap();
+
+        for &value in measured.series("population").unwrap().values {
+            assert_relative_eq!(value.re, 0.41, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_seeded_sampling_is_reproducible() {
+        let model = MeasurementModel::new(1.0, 0.0, 0.05, 100, Some(7)).unwrap();
+        let a = model.apply(&sample_results()).unwrap();
+        let b = model.apply(&sample_results()).unwrap();
+
+        assert_eq!(
+            a.series("population").unwrap().values[3],
+            b.series("population").unwrap().values[3]
+        );
+    }
+
+    #[test]
+    fn test_keeps_times_and_states_and_rejects_sampling_unbounded_series() {
+        let mut results = sample_results();
+        results.add_snapshot(9.0, &[Complex64::new(1.0, 0.0)]);
+        results.add_observable("number", 0.0, Complex64::new(2.0, 0.0));
+
+        let model = MeasurementModel::new(0.5, 0.0, 0.0, 0, None).unwrap();
+        let measured = model.apply(&results).unwrap();
+        assert_eq!(measured.snapshots().len(), 1);
+        assert_eq!(measured.series("number").unwrap().values[0].re, 1.0);
+        assert_eq!(
+            measured.series("population").unwrap().times,
+            results.series("population").unwrap().times
+        );
+
+        let sampled = MeasurementModel::new(1.0, 0.0, 0.0, 100, Some(1)).unwrap();
+        let err = sampled.apply(&results).unwrap_err().to_string();
+        assert!(err.contains("'number'"), "{}", err);
+    }
+
+    #[test]
+    fn test_invalid_efficiency() {
+        assert!(MeasurementModel::new(1.5, 0.0, 0.0, 0, None).is_err());
+    }
+}
@@ -1,7 +1,30 @@
+pub mod animation;
+pub mod archive;
+pub mod campaign;
 pub mod checkpoint;
 pub mod config;
 pub mod export;
+pub(crate) mod hdf5;
+pub mod matfile;
+pub mod measurement;
+pub mod operator_export;
+pub mod operator_import;
+pub mod output;
+pub mod pipeline;
+pub mod presets;
+pub mod report;
+pub mod snapshots;
+pub mod tomography;
 
+pub use animation::{Animation, AnimationKind, AnimationOptions};
+pub use archive::{Archive, ArchiveManifest};
+pub use campaign::Campaign;
 pub use checkpoint::Checkpoint;
 pub use config::Config;
 pub use export::Exporter;
+pub use matfile::MatFile;
+pub use measurement::MeasurementModel;
+pub use output::RunDirectory;
+pub use report::{ReportGenerator, ReportStyle};
+pub use snapshots::SnapshotFile;
+pub use tomography::Tomography;
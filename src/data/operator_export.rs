@@ -0,0 +1,219 @@
+use crate::core::hamiltonian::TimeIndependentHamiltonian;
+use crate::core::lindblad::{LindbladOperator, LindbladSolver};
+use crate::core::operator_cache::OperatorCache;
+use crate::core::systems::{DrivenCavity, DrivenTLS};
+use crate::core::Hamiltonian;
+use crate::data::config::{Config, SystemParameters};
+use crate::data::Exporter;
+use crate::simulation::CumulantSolver;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperatorKind {
+    Hamiltonian,
+    Liouvillian,
+}
+
+impl OperatorKind {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "hamiltonian" => Ok(Self::Hamiltonian),
+            "liouvillian" => Ok(Self::Liouvillian),
+            other => Err(Error::InvalidParameter(format!(
+                "Unknown operator '{}' (expected hamiltonian or liouvillian)",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Hamiltonian => "hamiltonian",
+            Self::Liouvillian => "liouvillian",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixFormat {
+    Npy,
+    Mtx,
+}
+
+impl MatrixFormat {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "npy" => Ok(Self::Npy),
+            "mtx" => Ok(Self::Mtx),
+            other => Err(Error::InvalidParameter(format!(
+                "Unknown matrix format '{}' (expected npy or mtx)",
+                other
+            ))),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Npy => "npy",
+            Self::Mtx => "mtx",
+        }
+    }
+}
+
+pub fn system_hamiltonian(config: &Config) -> Result<Box<dyn Hamiltonian>> {
+    let system = &config.system;
+    let dim = system.hilbert_dim;
+    match &system.parameters {
+        SystemParameters::DrivenTls(p) => {
+            if dim != 2 {
+                return Err(Error::Config(format!(
+                    "driven_tls needs hilbert_dim = 2, got {}",
+                    dim
+                )));
+            }
+            Ok(Box::new(DrivenTLS::with_phase(
+                p.omega_0,
+                p.omega_d,
+                p.rabi_freq,
+                p.phase,
+            )))
+        }
+        SystemParameters::DrivenCavity(p) => {
+            if dim < 3 {
+                return Err(Error::Config(format!(
+                    "A driven cavity needs hilbert_dim of at least 3, got {}",
+                    dim
+                )));
+            }
+            Ok(Box::new(DrivenCavity::new(p.omega_c, p.omega_p, p.g, dim)))
+        }
+        SystemParameters::CoupledCavities(_) | SystemParameters::Lattice(_) => {
+            let hamiltonian = match system.lattice_hamiltonian()? {
+                Some(hamiltonian) => hamiltonian,
+                None => {
+                    let (lattice, omega_c) = system
+                        .build_lattice()?
+                        .expect("coupled cavities always build a lattice");
+                    lattice.hamiltonian(omega_c)
+                }
+            };
+            Ok(Box::new(hamiltonian))
+        }
+        SystemParameters::TavisCummings(_) => {
+            let solver = CumulantSolver::from_config(config)?;
+            let dense = solver.model().dense(dim)?;
+            Ok(Box::new(TimeIndependentHamiltonian::new(dense.hamiltonian)))
+        }
+        SystemParameters::Custom(_) => Err(Error::NotImplemented(format!(
+            "Building the '{}' Hamiltonian from a config",
+            system.hamiltonian
+        ))),
+    }
+}
+
+pub fn system_collapse_operators(config: &Config) -> Result<Vec<LindbladOperator>> {
+    if let SystemParameters::TavisCummings(_) = config.system.parameters {
+        let solver = CumulantSolver::from_config(config)?;
+        return Ok(solver
+            .model()
+            .dense(config.system.hilbert_dim)?
+            .collapse_ops);
+    }
+    OperatorCache::new().collapse_operators_for(config)
+}
+
+pub fn build_operator(config: &Config, kind: OperatorKind, t: f64) -> Result<Array2<Complex64>> {
+    let hamiltonian = system_hamiltonian(config)?;
+    match kind {
+        OperatorKind::Hamiltonian => {
+            let dim = hamiltonian.dim();
+            let mut matrix = Array2::zeros((dim, dim));
+            crate::core::integrator::compute_hamiltonian(hamiltonian.as_ref(), t, &mut matrix);
+            Ok(matrix)
+        }
+        OperatorKind::Liouvillian => {
+            LindbladSolver::new(hamiltonian, system_collapse_operators(config)?)?.liouvillian(t)
+        }
+    }
+}
+
+pub fn write_matrix(matrix: &Array2<Complex64>, format: MatrixFormat, path: &Path) -> Result<()> {
+    match format {
+        MatrixFormat::Npy => {
+            let values: Vec<Complex64> = matrix.iter().copied().collect();
+            Exporter::write_npy(&values, matrix.shape(), path)
+        }
+        MatrixFormat::Mtx => {
+            let entries: Vec<((usize, usize), &Complex64)> = matrix
+                .indexed_iter()
+                .filter(|(_, value)| value.norm_sqr() > 0.0)
+                .collect();
+            let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+            writeln!(writer, "%%MatrixMarket matrix coordinate complex general")?;
+            writeln!(
+                writer,
+                "{} {} {}",
+                matrix.nrows(),
+                matrix.ncols(),
+                entries.len()
+            )?;
+            for ((row, col), value) in entries {
+                writeln!(
+                    writer,
+                    "{} {} {:e} {:e}",
+                    row + 1,
+                    col + 1,
+                    value.re,
+                    value.im
+                )?;
+            }
+            writer.flush()?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::math::is_hermitian;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_exported_operators_match_the_model() {
+        let mut config = Config::generate_template("driven_tls").unwrap();
+        let h = build_operator(&config, OperatorKind::Hamiltonian, 0.3).unwrap();
+        let mut expected = Array2::zeros((2, 2));
+        DrivenTLS::new(5.0, 5.0, 0.5).compute(0.3, &mut expected);
+        assert_eq!(h, expected);
+        assert!(is_hermitian(&h.view(), 1e-12));
+
+        config.lindblad.enabled = true;
+        config.lindblad.operators = vec![crate::data::config::LindbladOperatorConfig {
+            r#type: "decay".to_string(),
+            rate: 0.2,
+            temperature: 0.0,
+            site: None,
+        }];
+        let l = build_operator(&config, OperatorKind::Liouvillian, 0.0).unwrap();
+        assert_eq!(l.dim(), (4, 4));
+        for col in 0..4 {
+            let trace_change = l[[0, col]] + l[[3, col]];
+            assert_relative_eq!(trace_change.norm(), 0.0, epsilon = 1e-12);
+        }
+        assert_relative_eq!(l[[0, 3]].re, 0.2, epsilon = 1e-12);
+
+        let path = std::env::temp_dir().join(format!("chronophoton_op_{}.mtx", std::process::id()));
+        write_matrix(&l, MatrixFormat::Mtx, &path).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let nonzero = l.iter().filter(|x| x.norm_sqr() > 0.0).count();
+        assert_eq!(text.lines().nth(1).unwrap(), format!("4 4 {}", nonzero));
+        assert_eq!(text.lines().count(), nonzero + 2);
+        std::fs::remove_file(&path).ok();
+        assert!(MatrixFormat::parse("csv").is_err());
+    }
+}
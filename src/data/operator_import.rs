@@ -0,0 +1,609 @@
+use crate::core::hamiltonian::TimeIndependentHamiltonian;
+use crate::core::lindblad::{LindbladOperator, LindbladSolver};
+use crate::utils::error::{Diagnostics, ErrorCode};
+use crate::utils::math::{frobenius_norm, hermitian_eigen};
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_TOLERANCE: f64 = 1e-10;
+const MAX_CHOI_DIM: usize = 16;
+
+pub fn read_matrix(path: &Path) -> Result<Array2<Complex64>> {
+    let bytes = std::fs::read(path)?;
+    let matrix = match path.extension().and_then(|e| e.to_str()) {
+        Some("npy") => parse_npy(&bytes),
+        Some("mtx") => parse_mtx(&String::from_utf8_lossy(&bytes)),
+        _ => Err("unknown extension (expected .npy or .mtx)".to_string()),
+    };
+    matrix.map_err(|message| Error::Serialization(format!("{}: {}", path.display(), message)))
+}
+
+fn parse_npy(bytes: &[u8]) -> std::result::Result<Array2<Complex64>, String> {
+    if bytes.len() < 10 || &bytes[..6] != b"\x93NUMPY" {
+        return Err("not an npy file".to_string());
+    }
+    let (header_len, offset) = match bytes[6] {
+        1 => (u16::from_le_bytes([bytes[8], bytes[9]]) as usize, 10),
+        2 | 3 if bytes.len() >= 12 => (
+            u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]) as usize,
+            12,
+        ),
+        version => return Err(format!("unsupported npy version {}", version)),
+    };
+    let header = bytes
+        .get(offset..offset + header_len)
+        .map(String::from_utf8_lossy)
+        .ok_or("truncated npy header")?;
+    let field = |key: &str| {
+        let start = header
+            .find(&format!("'{}':", key))
+            .ok_or(format!("npy header has no '{}'", key))?
+            + key.len()
+            + 3;
+        Ok::<_, String>(header[start..].trim_start().to_string())
+    };
+
+    let descr = field("descr")?;
+    let width = if descr.starts_with("'<c16'") {
+        16
+    } else if descr.starts_with("'<f8'") {
+        8
+    } else {
+        return Err(format!(
+            "unsupported dtype {} (expected complex128 or float64)",
+            descr.split(',').next().unwrap_or("")
+        ));
+    };
+    let fortran = field("fortran_order")?.starts_with("True");
+    let shape = field("shape")?;
+    let dims: Vec<usize> = shape[1..shape.find(')').ok_or("malformed npy shape")?]
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.parse().map_err(|_| format!("malformed npy shape {}", s)))
+        .collect::<std::result::Result<_, _>>()?;
+    let [rows, cols] = dims[..] else {
+        return Err(format!("expected a 2-D array, got shape {:?}", dims));
+    };
+
+    let data = &bytes[offset + header_len..];
+    if data.len() != rows * cols * width {
+        return Err(format!(
+            "expected {} bytes of data for shape ({}, {}), found {}",
+            rows * cols * width,
+            rows,
+            cols,
+            data.len()
+        ));
+    }
+    let float = |i: usize| f64::from_le_bytes(data[8 * i..8 * i + 8].try_into().unwrap());
+    let value = |i: usize| match width {
+        16 => Complex64::new(float(2 * i), float(2 * i + 1)),
+        _ => Complex64::new(float(i), 0.0),
+    };
+    Ok(Array2::from_shape_fn((rows, cols), |(r, c)| {
+        value(if fortran { c * rows + r } else { r * cols + c })
+    }))
+}
+
+fn parse_mtx(text: &str) -> std::result::Result<Array2<Complex64>, String> {
+    let mut lines = text.lines().enumerate();
+    let banner: Vec<String> = lines
+        .next()
+        .map(|(_, l)| l.split_whitespace().map(str::to_lowercase).collect())
+        .unwrap_or_default();
+    if banner.len() != 5 || banner[0] != "%%matrixmarket" || banner[1] != "matrix" {
+        return Err("missing %%MatrixMarket matrix banner".to_string());
+    }
+    if banner[2] != "coordinate" {
+        return Err(format!(
+            "unsupported layout '{}', expected coordinate",
+            banner[2]
+        ));
+    }
+    let complex = match banner[3].as_str() {
+        "complex" => true,
+        "real" | "integer" => false,
+        other => return Err(format!("unsupported field '{}'", other)),
+    };
+    let symmetry = banner[4].clone();
+    if !["general", "hermitian", "symmetric"].contains(&symmetry.as_str()) {
+        return Err(format!("unsupported symmetry '{}'", symmetry));
+    }
+
+    let mut lines = lines.filter(|(_, l)| !l.trim().is_empty() && !l.starts_with('%'));
+    let number = |line: usize, token: Option<&str>| -> std::result::Result<f64, String> {
+        token
+            .and_then(|s| s.parse().ok())
+            .ok_or(format!("line {}: malformed entry", line + 1))
+    };
+    let (line, size) = lines.next().ok_or("missing size line")?;
+    let mut tokens = size.split_whitespace();
+    let rows = number(line, tokens.next())? as usize;
+    let cols = number(line, tokens.next())? as usize;
+    let count = number(line, tokens.next())? as usize;
+
+    let mut matrix = Array2::zeros((rows, cols));
+    let mut read = 0;
+    for (line, entry) in lines {
+        let mut tokens = entry.split_whitespace();
+        let row = number(line, tokens.next())? as usize;
+        let col = number(line, tokens.next())? as usize;
+        let re = number(line, tokens.next())?;
+        let im = if complex {
+            number(line, tokens.next())?
+        } else {
+            0.0
+        };
+        if row == 0 || col == 0 || row > rows || col > cols {
+            return Err(format!(
+                "line {}: index ({}, {}) outside a {}x{} matrix",
+                line + 1,
+                row,
+                col,
+                rows,
+                cols
+            ));
+        }
+        let value = Complex64::new(re, im);
+        matrix[[row - 1, col - 1]] = value;
+        if row != col {
+            match symmetry.as_str() {
+                "hermitian" => matrix[[col - 1, row - 1]] = value.conj(),
+                "symmetric" => matrix[[col - 1, row - 1]] = value,
+                _ => {}
+            }
+        }
+        read += 1;
+    }
+    if read != count {
+        return Err(format!("header declares {} entries, found {}", count, read));
+    }
+    Ok(matrix)
+}
+
+pub fn parse_collapse_spec(spec: &str) -> Result<(PathBuf, f64)> {
+    match spec.rsplit_once(':') {
+        Some((path, rate)) if !path.is_empty() => {
+            let rate = rate.parse().map_err(|_| {
+                Error::InvalidParameter(format!(
+                    "Collapse operator '{}' should be given as PATH or PATH:RATE",
+                    spec
+                ))
+            })?;
+            Ok((PathBuf::from(path), rate))
+        }
+        _ => Ok((PathBuf::from(spec), 1.0)),
+    }
+}
+
+struct Imported {
+    name: String,
+    matrix: Array2<Complex64>,
+}
+
+pub struct OperatorImport {
+    hamiltonian: Option<Imported>,
+    collapse_ops: Vec<(Imported, f64)>,
+    liouvillian: Option<Imported>,
+    tolerance: f64,
+}
+
+impl Default for OperatorImport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OperatorImport {
+    pub fn new() -> Self {
+        Self {
+            hamiltonian: None,
+            collapse_ops: Vec::new(),
+            liouvillian: None,
+            tolerance: DEFAULT_TOLERANCE,
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    pub fn with_hamiltonian(mut self, name: impl Into<String>, matrix: Array2<Complex64>) -> Self {
+        self.hamiltonian = Some(Imported {
+            name: name.into(),
+            matrix,
+        });
+        self
+    }
+
+    pub fn with_collapse(
+        mut self,
+        name: impl Into<String>,
+        matrix: Array2<Complex64>,
+        rate: f64,
+    ) -> Self {
+        self.collapse_ops.push((
+            Imported {
+                name: name.into(),
+                matrix,
+            },
+            rate,
+        ));
+        self
+    }
+
+    pub fn with_liouvillian(mut self, name: impl Into<String>, matrix: Array2<Complex64>) -> Self {
+        self.liouvillian = Some(Imported {
+            name: name.into(),
+            matrix,
+        });
+        self
+    }
+
+    pub fn with_hamiltonian_file(self, path: &Path) -> Result<Self> {
+        let matrix = read_matrix(path)?;
+        Ok(self.with_hamiltonian(path.display().to_string(), matrix))
+    }
+
+    pub fn with_collapse_file(self, path: &Path, rate: f64) -> Result<Self> {
+        let matrix = read_matrix(path)?;
+        Ok(self.with_collapse(path.display().to_string(), matrix, rate))
+    }
+
+    pub fn with_liouvillian_file(self, path: &Path) -> Result<Self> {
+        let matrix = read_matrix(path)?;
+        Ok(self.with_liouvillian(path.display().to_string(), matrix))
+    }
+
+    pub fn check(&self) -> Diagnostics {
+        let mut problems = Diagnostics::new();
+        let mut dim = None;
+
+        if let Some(h) = &self.hamiltonian {
+            if self.check_square(&h.name, &h.matrix, &mut problems) {
+                dim = Some(h.matrix.nrows());
+                self.check_hermitian(h, &mut problems);
+            }
+        }
+
+        for (op, rate) in &self.collapse_ops {
+            if !self.check_square(&op.name, &op.matrix, &mut problems) {
+                continue;
+            }
+            match dim {
+                Some(d) if d != op.matrix.nrows() => problems.error(
+                    ErrorCode::DimensionMismatch,
+                    format!(
+                        "{}: collapse operator is {}x{} but the Hamiltonian is {}x{}",
+                        op.name,
+                        op.matrix.nrows(),
+                        op.matrix.nrows(),
+                        d,
+                        d
+                    ),
+                ),
+                Some(_) => {}
+                None => dim = Some(op.matrix.nrows()),
+            }
+            self.check_collapse(op, *rate, &mut problems);
+        }
+
+        if let Some(l) = &self.liouvillian {
+            if self.check_square(&l.name, &l.matrix, &mut problems) {
+                let n = l.matrix.nrows();
+                let d = (n as f64).sqrt().round() as usize;
+                if d * d != n {
+                    problems.error(
+                        ErrorCode::DimensionMismatch,
+                        format!(
+                            "{}: a Liouvillian acts on vec(rho) and must be d²xd², got {}x{}",
+                            l.name, n, n
+                        ),
+                    );
+                } else if dim.is_some_and(|dim| dim != d) {
+                    problems.error(
+                        ErrorCode::DimensionMismatch,
+                        format!(
+                            "{}: Liouvillian acts on dimension {} but the operators have dimension {}",
+                            l.name,
+                            d,
+                            dim.unwrap()
+                        ),
+                    );
+                } else {
+                    self.check_liouvillian(l, d, &mut problems);
+                }
+            }
+        }
+
+        if self.hamiltonian.is_none() && self.liouvillian.is_none() {
+            problems.error(
+                ErrorCode::InvalidParameter,
+                "Nothing to import: provide a Hamiltonian or a Liouvillian",
+            );
+        }
+        problems
+    }
+
+    pub fn into_solver(self) -> Result<LindbladSolver> {
+        self.check().into_result()?;
+        let hamiltonian = self.hamiltonian.ok_or_else(|| {
+            Error::InvalidParameter("Building a solver needs an imported Hamiltonian".to_string())
+        })?;
+        let collapse_ops = self
+            .collapse_ops
+            .into_iter()
+            .map(|(op, rate)| LindbladOperator::new(op.matrix, rate))
+            .collect::<Result<Vec<_>>>()?;
+        LindbladSolver::new(
+            Box::new(TimeIndependentHamiltonian::new(hamiltonian.matrix)),
+            collapse_ops,
+        )
+    }
+
+    fn check_square(
+        &self,
+        name: &str,
+        matrix: &Array2<Complex64>,
+        problems: &mut Diagnostics,
+    ) -> bool {
+        let (rows, cols) = matrix.dim();
+        if rows != cols || rows == 0 {
+            problems.error(
+                ErrorCode::DimensionMismatch,
+                format!(
+                    "{}: operator must be square and non-empty, got {}x{}",
+                    name, rows, cols
+                ),
+            );
+            return false;
+        }
+        if let Some(((r, c), value)) = matrix.indexed_iter().find(|(_, v)| !v.is_finite()) {
+            problems.error(
+                ErrorCode::Numerical,
+                format!("{}: entry ({}, {}) is {}", name, r, c, value),
+            );
+            return false;
+        }
+        true
+    }
+
+    fn scaled_tolerance(&self, matrix: &Array2<Complex64>) -> f64 {
+        self.tolerance * frobenius_norm(&matrix.view()).max(1.0)
+    }
+
+    fn check_hermitian(&self, h: &Imported, problems: &mut Diagnostics) {
+        let ((r, c), deviation) = h
+            .matrix
+            .indexed_iter()
+            .map(|((r, c), v)| ((r, c), (v - h.matrix[[c, r]].conj()).norm()))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .expect("square matrices are non-empty");
+        if deviation > self.scaled_tolerance(&h.matrix) {
+            problems.error(
+                ErrorCode::Hamiltonian,
+                format!(
+                    "{}: Hamiltonian is not Hermitian, H[{},{}] - conj(H[{},{}]) = {:.3e}; \
+                     losses belong in collapse operators rather than an anti-Hermitian part",
+                    h.name, r, c, c, r, deviation
+                ),
+            );
+        }
+    }
+
+    fn check_collapse(&self, op: &Imported, rate: f64, problems: &mut Diagnostics) {
+        if !rate.is_finite() || rate < 0.0 {
+            problems.error(
+                ErrorCode::InvalidParameter,
+                format!(
+                    "{}: rate {} breaks complete positivity; collapse rates must be finite and \
+                     non-negative",
+                    op.name, rate
+                ),
+            );
+            return;
+        }
+
+        let norm = frobenius_norm(&op.matrix.view());
+        if norm == 0.0 || rate == 0.0 {
+            problems.warning(
+                ErrorCode::InvalidParameter,
+                format!(
+                    "{}: collapse operator has no effect (zero operator or rate)",
+                    op.name
+                ),
+            );
+            return;
+        }
+
+        let dim = op.matrix.nrows();
+        let mean = op.matrix.diag().sum() / dim as f64;
+        let traceless = &op.matrix - &(crate::utils::math::identity(dim) * mean);
+        if frobenius_norm(&traceless.view()) <= self.tolerance * norm {
+            problems.warning(
+                ErrorCode::InvalidParameter,
+                format!(
+                    "{}: collapse operator is proportional to the identity and generates no \
+                     dissipation",
+                    op.name
+                ),
+            );
+        }
+
+        let largest = op.matrix.iter().map(|v| v.norm()).fold(0.0, f64::max);
+        if rate != 1.0 && (largest - rate.sqrt()).abs() <= self.tolerance.max(1e-12) * largest {
+            problems.warning(
+                ErrorCode::InvalidParameter,
+                format!(
+                    "{}: operator already looks scaled by sqrt(rate) = {:.4}; rates are applied \
+                     separately, so store the bare jump operator or set the rate to 1",
+                    op.name,
+                    rate.sqrt()
+                ),
+            );
+        }
+    }
+
+    fn check_liouvillian(&self, l: &Imported, d: usize, problems: &mut Diagnostics) {
+        let m = &l.matrix;
+        let tolerance = self.scaled_tolerance(m);
+
+        let leak = (0..d * d)
+            .map(|col| {
+                (0..d)
+                    .map(|i| m[[i * d + i, col]])
+                    .sum::<Complex64>()
+                    .norm()
+            })
+            .fold(0.0, f64::max);
+        if leak > tolerance {
+            problems.error(
+                ErrorCode::Numerical,
+                format!(
+                    "{}: Liouvillian does not preserve the trace, |d/dt Tr rho| reaches {:.3e}",
+                    l.name, leak
+                ),
+            );
+        }
+
+        let choi = Array2::from_shape_fn((d * d, d * d), |(a, b)| {
+            let (k, i, l_, j) = (a / d, a % d, b / d, b % d);
+            m[[i * d + j, k * d + l_]]
+        });
+        let asymmetry = choi
+            .indexed_iter()
+            .map(|((a, b), v)| (v - choi[[b, a]].conj()).norm())
+            .fold(0.0, f64::max);
+        if asymmetry > tolerance {
+            problems.error(
+                ErrorCode::Numerical,
+                format!(
+                    "{}: Liouvillian does not preserve Hermiticity (deviation {:.3e})",
+                    l.name, asymmetry
+                ),
+            );
+            return;
+        }
+
+        if d > MAX_CHOI_DIM {
+            problems.warning(
+                ErrorCode::Numerical,
+                format!(
+                    "{}: skipping the complete-positivity check above dimension {}",
+                    l.name, MAX_CHOI_DIM
+                ),
+            );
+            return;
+        }
+        let projector = Array2::from_shape_fn((d * d, d * d), |(a, b)| {
+            let identity = if a == b { 1.0 } else { 0.0 };
+            let entangled = if a % (d + 1) == 0 && b % (d + 1) == 0 {
+                1.0 / d as f64
+            } else {
+                0.0
+            };
+            Complex64::new(identity - entangled, 0.0)
+        });
+        let (eigenvalues, _) = hermitian_eigen(&projector.dot(&choi).dot(&projector).view());
+        let lowest = eigenvalues.first().copied().unwrap_or(0.0);
+        if lowest < -tolerance {
+            problems.error(
+                ErrorCode::Numerical,
+                format!(
+                    "{}: Liouvillian is not completely positive, its projected Choi matrix has \
+                     eigenvalue {:.3e}; check for negative rates",
+                    l.name, lowest
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::operator_export::{write_matrix, MatrixFormat};
+    use crate::utils::error::Severity;
+    use approx::assert_relative_eq;
+    use ndarray::array;
+
+    fn c(re: f64, im: f64) -> Complex64 {
+        Complex64::new(re, im)
+    }
+
+    #[test]
+    fn test_round_trip_and_valid_lindbladian() {
+        let h = array![[c(1.0, 0.0), c(0.2, -0.3)], [c(0.2, 0.3), c(-1.0, 0.0)]];
+        let lowering = array![[c(0.0, 0.0), c(1.0, 0.0)], [c(0.0, 0.0), c(0.0, 0.0)]];
+        let dir = std::env::temp_dir();
+        for format in [MatrixFormat::Npy, MatrixFormat::Mtx] {
+            let path = dir.join(format!(
+                "chronophoton_import_{}.{}",
+                std::process::id(),
+                format.extension()
+            ));
+            write_matrix(&h, format, &path).unwrap();
+            let read = read_matrix(&path).unwrap();
+            std::fs::remove_file(&path).ok();
+            for (a, b) in read.iter().zip(h.iter()) {
+                assert_relative_eq!((a - b).norm(), 0.0, epsilon = 1e-14);
+            }
+        }
+
+        let solver = OperatorImport::new()
+            .with_hamiltonian("h", h.clone())
+            .with_collapse("a", lowering.clone(), 0.3)
+            .into_solver()
+            .unwrap();
+        let liouvillian = solver.liouvillian(0.0).unwrap();
+        let import = OperatorImport::new()
+            .with_hamiltonian("h", h)
+            .with_collapse("a", lowering, 0.3)
+            .with_liouvillian("l", liouvillian);
+        assert!(import.check().is_empty());
+    }
+
+    #[test]
+    fn test_reports_actionable_problems() {
+        let h = array![[c(1.0, 0.0), c(0.5, 0.0)], [c(0.0, 0.0), c(-1.0, 0.0)]];
+        let lowering = array![[c(0.0, 0.0), c(1.0, 0.0)], [c(0.0, 0.0), c(0.0, 0.0)]];
+        let problems = OperatorImport::new()
+            .with_hamiltonian("h", h)
+            .with_collapse("a", lowering.clone(), -0.1)
+            .with_collapse("b", Array2::zeros((3, 3)), 0.1)
+            .check();
+        let errors: Vec<_> = problems
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+            .map(|d| d.code)
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                ErrorCode::Hamiltonian,
+                ErrorCode::InvalidParameter,
+                ErrorCode::DimensionMismatch
+            ]
+        );
+
+        let mut amplified = LindbladSolver::new(
+            Box::new(TimeIndependentHamiltonian::new(Array2::zeros((2, 2)))),
+            vec![LindbladOperator::new(lowering.t().to_owned(), 0.2).unwrap()],
+        )
+        .unwrap()
+        .liouvillian(0.0)
+        .unwrap();
+        amplified.mapv_inplace(|x| -x);
+        let problems = OperatorImport::new()
+            .with_liouvillian("l", amplified)
+            .check();
+        assert!(problems.has_errors());
+        assert!(problems
+            .iter()
+            .any(|d| d.message.contains("not completely positive")));
+    }
+}
@@ -0,0 +1,201 @@
+use crate::data::Config;
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputConfig {
+    #[serde(default = "default_root")]
+    pub root: PathBuf,
+    #[serde(default = "default_on_collision")]
+    pub on_collision: String,
+    #[serde(default)]
+    pub mat: bool,
+    #[serde(default = "default_mat_version")]
+    pub mat_version: String,
+    #[serde(default)]
+    pub states: bool,
+}
+
+fn default_root() -> PathBuf {
+    PathBuf::from("runs")
+}
+
+fn default_on_collision() -> String {
+    "uniquify".to_string()
+}
+
+fn default_mat_version() -> String {
+    "7.3".to_string()
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            root: default_root(),
+            on_collision: default_on_collision(),
+            mat: false,
+            mat_version: default_mat_version(),
+            states: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionPolicy {
+    Refuse,
+    Uniquify,
+}
+
+impl CollisionPolicy {
+    pub fn parse(policy: &str) -> Result<Self> {
+        match policy {
+            "refuse" => Ok(Self::Refuse),
+            "uniquify" => Ok(Self::Uniquify),
+            other => Err(Error::Config(format!(
+                "Unknown output collision policy '{}' (expected refuse or uniquify)",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RunDirectory {
+    path: PathBuf,
+}
+
+impl RunDirectory {
+    pub fn create(root: &Path, config: &Config, policy: CollisionPolicy) -> Result<Self> {
+        let timestamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ");
+        let name = format!(
+            "{}_{}_{}",
+            config.simulation.name,
+            timestamp,
+            config_hash(config)?
+        );
+        Self::create_named(root, &name, policy)
+    }
+
+    pub fn create_named(root: &Path, name: &str, policy: CollisionPolicy) -> Result<Self> {
+        std::fs::create_dir_all(root)?;
+
+        let mut attempt = 0;
+        loop {
+            let candidate = match attempt {
+                0 => root.join(name),
+                n => root.join(format!("{}-{}", name, n)),
+            };
+
+            match std::fs::create_dir(&candidate) {
+                Ok(()) => {
+                    std::fs::create_dir(candidate.join("checkpoints"))?;
+                    return Ok(Self { path: candidate });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if policy == CollisionPolicy::Refuse {
+                        return Err(Error::Config(format!(
+                            "Run directory {} already exists",
+                            candidate.display()
+                        )));
+                    }
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    // An existing or nested directory, such as one campaign entry's outputs.
+    pub fn open(path: &Path) -> Result<Self> {
+        std::fs::create_dir_all(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn join(&self, file: impl AsRef<Path>) -> PathBuf {
+        self.path.join(file)
+    }
+
+    pub fn config_path(&self) -> PathBuf {
+        self.join("config.toml")
+    }
+
+    pub fn results_path(&self) -> PathBuf {
+        self.join("results.bin")
+    }
+
+    pub fn mat_path(&self) -> PathBuf {
+        self.join("results.mat")
+    }
+
+    pub fn states_path(&self) -> PathBuf {
+        self.join("states.bin")
+    }
+
+    pub fn log_path(&self) -> PathBuf {
+        self.join("run.log")
+    }
+
+    pub fn checkpoint_path(&self, step: usize) -> PathBuf {
+        self.join("checkpoints")
+            .join(format!("step_{:08}.ckpt", step))
+    }
+
+    pub fn latest_checkpoint_path(&self) -> PathBuf {
+        self.join("checkpoints").join("latest.ckpt")
+    }
+
+    pub fn write_config(&self, config: &Config) -> Result<()> {
+        config.save(&self.config_path())
+    }
+}
+
+pub fn config_hash(config: &Config) -> Result<String> {
+    let text =
+        toml::to_string(config).map_err(|e| Error::Serialization(format!("TOML error: {}", e)))?;
+    let digest = Sha256::digest(text.as_bytes());
+    Ok(digest[..4].iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collision_policies() {
+        let root = std::env::temp_dir().join("chronophoton_output_test");
+        std::fs::remove_dir_all(&root).ok();
+
+        let first = RunDirectory::create_named(&root, "run", CollisionPolicy::Uniquify).unwrap();
+        let second = RunDirectory::create_named(&root, "run", CollisionPolicy::Uniquify).unwrap();
+        assert_eq!(first.path(), root.join("run"));
+        assert_eq!(second.path(), root.join("run-1"));
+        assert!(second.checkpoint_path(3).parent().unwrap().is_dir());
+
+        assert!(RunDirectory::create_named(&root, "run", CollisionPolicy::Refuse).is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_run_directory_name_includes_config_hash() {
+        let root = std::env::temp_dir().join("chronophoton_output_hash_test");
+        let config = Config::generate_template("driven_tls").unwrap();
+        let hash = config_hash(&config).unwrap();
+        assert_eq!(hash.len(), 8);
+
+        let dir = RunDirectory::create(&root, &config, CollisionPolicy::Refuse).unwrap();
+        dir.write_config(&config).unwrap();
+        let name = dir.path().file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("driven_tls_"));
+        assert!(name.ends_with(&hash));
+        assert!(dir.config_path().is_file());
+        std::fs::remove_dir_all(&root).ok();
+    }
+}
@@ -0,0 +1,195 @@
+// The steps shared by `chronophoton run` and campaign runs: pick the solver the
+// config selects, run it, and write every output the config enables.
+use crate::core::QuantumState;
+use crate::data::tomography::Reconstruction;
+use crate::data::{
+    Checkpoint, Config, Exporter, MatFile, MeasurementModel, RunDirectory, SnapshotFile, Tomography,
+};
+use crate::simulation::{
+    dmd, CumulantSolver, Dmd, JobControl, KickedSystem, SimulationBuilder, SimulationResults,
+};
+use crate::utils::{performance, Error, Result};
+use ndarray::Array1;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub struct TomographyOutcome {
+    pub reconstruction: Reconstruction,
+    pub fidelity: f64,
+}
+
+// `checkpoint` is where a preempted run leaves its state; `None` disables preemption
+// checkpoints.
+pub fn simulate(
+    config: &Config,
+    control: Arc<JobControl>,
+    checkpoint: Option<PathBuf>,
+    resume: Option<Checkpoint>,
+) -> Result<SimulationResults> {
+    if performance::select_solver(config)? == performance::Solver::Cumulant {
+        let solver = CumulantSolver::from_config(config)?;
+        tracing::info!(
+            "Running {} order cumulant equations for {} emitters",
+            solver.order().name(),
+            config.cumulant.emitters
+        );
+        return solver.run(
+            config.simulation.duration,
+            config.simulation.timestep,
+            &config.observables.time_grid(),
+        );
+    }
+
+    if config.kicks.enabled {
+        let system = KickedSystem::from_config(config)?;
+        tracing::info!(
+            "Running kicked evolution with {} kicks of strength {} every {}",
+            config.kicks.operator,
+            system.strength(),
+            system.period()
+        );
+        return system.run(
+            &config.initial_state.build(config.system.hilbert_dim)?,
+            config.simulation.duration,
+            &config.observables.time_grid(),
+        );
+    }
+
+    tracing::info!("Building simulation");
+    let mut sim = SimulationBuilder::from_config(config)?
+        .with_record_states(
+            config.output.states
+                || config.tomography.enabled
+                || config.observables.dmd.source.as_deref() == Some(dmd::STATES_SOURCE),
+        )
+        .with_control(control);
+    if let Some(path) = checkpoint {
+        sim = match config.preemption.checkpoint_interval {
+            Some(interval) if !sim.is_open() => sim.with_checkpoints(path, interval)?,
+            _ => sim.with_preemption_checkpoint(path),
+        };
+    }
+    if let Some(checkpoint) = resume {
+        sim = sim.with_resume(checkpoint)?;
+    }
+
+    tracing::info!("Running simulation");
+    sim.run()
+}
+
+// Applies the detector model and steady-state options and saves results.bin, so
+// the results survive a failure in any later post-processing step.
+pub fn save_results(
+    results: &mut SimulationResults,
+    config: &Config,
+    dir: &RunDirectory,
+) -> Result<()> {
+    if config.measurement.enabled {
+        tracing::info!("Applying detector model to observables");
+        *results = MeasurementModel::from_config(&config.measurement)?.apply(results)?;
+    }
+    results.set_steady_state_options(config.observables.steady_state.clone());
+
+    tracing::info!("Saving results to {:?}", dir.results_path());
+    results.save(&dir.results_path())
+}
+
+// Writes the outputs derived from saved results into `dir`.
+pub fn write_outputs(
+    results: &mut SimulationResults,
+    config: &Config,
+    dir: &RunDirectory,
+) -> Result<Option<TomographyOutcome>> {
+    for name in &config.observables.spectrogram.observables {
+        let spectrogram = results.spectrogram(name, &config.observables.spectrogram)?;
+        let path = dir.join(format!("spectrogram_{}.csv", name));
+        Exporter::write_spectrogram(&spectrogram, &path)?;
+        tracing::info!("Wrote spectrogram {:?}", path);
+    }
+
+    if config.observables.dmd.source.is_some() {
+        let dmd = Dmd::from_results(results, &config.observables.dmd)?;
+        dmd.write_csv(&dir.join("dmd_modes.csv"))?;
+        tracing::info!("Extracted {} DMD modes", dmd.rank());
+    }
+
+    if !config.observables.events.is_empty() {
+        let events = results.events(&config.observables.events)?;
+        Exporter::write_events(&events, &dir.join("events.csv"))?;
+        tracing::info!("Detected {} events", events.len());
+    }
+
+    if config.output.mat {
+        MatFile::from_results(results, config)?.write(&dir.mat_path())?;
+        tracing::info!("Wrote MATLAB file {:?}", dir.mat_path());
+    }
+
+    if config.output.states {
+        SnapshotFile::save(results.snapshots(), &dir.states_path())?;
+        tracing::info!("Wrote state snapshots {:?}", dir.states_path());
+    }
+
+    for path in Exporter::write_observable_outputs(results, &config.observables.output, dir.path())?
+    {
+        tracing::info!("Wrote observable output {:?}", path);
+    }
+    for path in Exporter::write_vector_series(results, dir.path())? {
+        tracing::info!("Wrote vector observable {:?}", path);
+    }
+    Exporter::write_steady_state(results, &dir.join("steady_state.csv"))?;
+
+    let tomography = if config.tomography.enabled {
+        let last = results
+            .snapshots()
+            .last()
+            .ok_or_else(|| Error::Config("Tomography needs a recorded final state".to_string()))?;
+        let state = QuantumState::new(Array1::from(last.amplitudes.clone()))?;
+        let reconstruction =
+            Tomography::from_config(&config.tomography)?.run(&state.to_density_matrix())?;
+        reconstruction.write_csv(&dir.join("tomography.csv"))?;
+        Some(TomographyOutcome {
+            fidelity: reconstruction.fidelity(&state)?,
+            reconstruction,
+        })
+    } else {
+        None
+    };
+
+    Ok(tomography)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detector_model_keeps_states_for_tomography() {
+        let dir = std::env::temp_dir().join("chronophoton_pipeline_measurement_test");
+        std::fs::remove_dir_all(&dir).ok();
+        let mut config = Config::generate_template("driven_tls").unwrap();
+        config.simulation.duration = 2.0;
+        config.observables.list = vec!["population:1".to_string()];
+        config.measurement.enabled = true;
+        config.measurement.efficiency = 0.9;
+        config.measurement.shots = 200;
+        config.measurement.seed = Some(3);
+        config.tomography.enabled = true;
+        config.validate().unwrap();
+
+        let mut results = simulate(&config, Arc::new(JobControl::new()), None, None).unwrap();
+        let termination = results.termination().cloned();
+        let dir = RunDirectory::open(&dir).unwrap();
+        save_results(&mut results, &config, &dir).unwrap();
+        assert!(!results.snapshots().is_empty());
+        assert_eq!(results.termination().cloned(), termination);
+
+        let tomography = write_outputs(&mut results, &config, &dir).unwrap().unwrap();
+        assert!(tomography.fidelity > 0.99, "{}", tomography.fidelity);
+        let saved = SimulationResults::load(&dir.results_path()).unwrap();
+        assert_eq!(saved.snapshots().len(), results.snapshots().len());
+
+        config.observables.list = vec!["sigma_z".to_string()];
+        assert!(config.validate().is_err());
+        std::fs::remove_dir_all(dir.path()).ok();
+    }
+}
@@ -0,0 +1,107 @@
+use crate::data::Config;
+use crate::utils::{Error, Result};
+
+pub struct Preset {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub source: &'static str,
+}
+
+const PRESETS: &[Preset] = &[
+    Preset {
+        name: "rabi_oscillation",
+        description: "Resonantly driven two-level system",
+        source: include_str!("../../examples/presets/rabi_oscillation.toml"),
+    },
+    Preset {
+        name: "vacuum_rabi_splitting",
+        description: "Emitter exchanging a single photon with a cavity mode",
+        source: include_str!("../../examples/presets/vacuum_rabi_splitting.toml"),
+    },
+    Preset {
+        name: "parametric_threshold",
+        description: "Sweep of a lossy parametric oscillator across its threshold",
+        source: include_str!("../../examples/presets/parametric_threshold.toml"),
+    },
+    Preset {
+        name: "ssh_edge_state",
+        description: "Photon trapped on the edge of a topological SSH chain",
+        source: include_str!("../../examples/presets/ssh_edge_state.toml"),
+    },
+    Preset {
+        name: "time_crystal",
+        description: "Period-doubled response of a parametrically pumped cavity",
+        source: include_str!("../../examples/presets/time_crystal.toml"),
+    },
+];
+
+pub fn presets() -> &'static [Preset] {
+    PRESETS
+}
+
+pub fn find(name: &str) -> Result<&'static Preset> {
+    PRESETS
+        .iter()
+        .find(|preset| preset.name == name)
+        .ok_or_else(|| {
+            let names: Vec<&str> = PRESETS.iter().map(|preset| preset.name).collect();
+            Error::Config(format!(
+                "Unknown example '{}', expected one of: {}",
+                name,
+                names.join(", ")
+            ))
+        })
+}
+
+impl Preset {
+    pub fn config(&self) -> Result<Config> {
+        let config = Config::from_toml_str(self.source)?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observables::PopulationOperator;
+    use crate::data::config::SystemParameters;
+    use crate::simulation::SimulationBuilder;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_presets_are_valid() {
+        for preset in presets() {
+            let config = preset
+                .config()
+                .unwrap_or_else(|e| panic!("preset '{}': {}", preset.name, e));
+            assert_eq!(config.simulation.name, preset.name);
+        }
+        assert!(find("rabi_oscillation").is_ok());
+        assert!(find("rabi").is_err());
+    }
+
+    #[test]
+    fn test_vacuum_rabi_preset_swaps_excitation() {
+        let config = find("vacuum_rabi_splitting").unwrap().config().unwrap();
+        let SystemParameters::Lattice(parameters) = &config.system.parameters else {
+            panic!("vacuum Rabi preset should use the lattice Hamiltonian");
+        };
+        let g = parameters.j;
+        let dim = config.system.hilbert_dim;
+        let results = SimulationBuilder::new()
+            .hamiltonian(config.system.lattice_hamiltonian().unwrap().unwrap())
+            .initial_state(config.initial_state.build(dim).unwrap())
+            .duration(std::f64::consts::PI / (2.0 * g))
+            .timestep(config.simulation.timestep)
+            .observable("cavity", PopulationOperator::new(dim, 2).unwrap())
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+
+        let cavity = results.series("cavity").unwrap().to_vec_real();
+        assert_relative_eq!(cavity[0], 0.0);
+        assert_relative_eq!(*cavity.last().unwrap(), 1.0, epsilon = 1e-6);
+    }
+}
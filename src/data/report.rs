@@ -0,0 +1,828 @@
+use crate::data::config::{Config, ReportConfig};
+use crate::simulation::{Estimate, FitResult, SimulationResults, Spectrogram};
+use crate::utils::math::power_spectrum;
+use crate::utils::resources::ResourceUsage;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FigureFormat {
+    Png,
+    Svg,
+    Pdf,
+}
+
+impl FigureFormat {
+    pub fn parse(format: &str) -> Result<Self> {
+        match format.to_ascii_lowercase().as_str() {
+            "png" => Ok(Self::Png),
+            "svg" => Ok(Self::Svg),
+            "pdf" => Ok(Self::Pdf),
+            other => Err(Error::InvalidParameter(format!(
+                "Unknown figure format '{}', expected png, svg or pdf",
+                other
+            ))),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Svg => "svg",
+            Self::Pdf => "pdf",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportStyle {
+    pub format: FigureFormat,
+    pub width: u32,
+    pub height: u32,
+    pub line_width: u32,
+    pub font_size: u32,
+}
+
+impl ReportStyle {
+    pub fn from_config(config: &ReportConfig) -> Result<Self> {
+        Ok(Self {
+            format: FigureFormat::parse(&config.format)?,
+            width: config.width,
+            height: config.height,
+            line_width: config.line_width,
+            font_size: config.font_size,
+        })
+    }
+}
+
+impl Default for ReportStyle {
+    fn default() -> Self {
+        Self::from_config(&ReportConfig::default()).expect("default report config is valid")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryFormat {
+    Markdown,
+    Latex,
+}
+
+impl SummaryFormat {
+    pub fn parse(format: &str) -> Result<Option<Self>> {
+        match format.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Ok(Some(Self::Markdown)),
+            "latex" | "tex" => Ok(Some(Self::Latex)),
+            "none" => Ok(None),
+            other => Err(Error::InvalidParameter(format!(
+                "Unknown summary format: {}",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ObservableMetrics {
+    pub name: String,
+    pub initial: f64,
+    pub final_value: f64,
+    pub steady_state: f64,
+    pub steady_state_error: f64,
+    pub converged_at: Option<f64>,
+    pub peak_frequency: Option<f64>,
+}
+
+pub fn observable_metrics(results: &SimulationResults) -> Vec<ObservableMetrics> {
+    let uniform = results.is_uniformly_sampled();
+
+    results
+        .iter_series()
+        .filter_map(|series| {
+            let values = series.values;
+            let first = values.first()?;
+            let last = values.last()?;
+
+            let steady_state = results.steady_state(series.name)?;
+
+            let peak_frequency = if uniform && values.len() > 2 {
+                let dt = series.times[1] - series.times[0];
+                let (freqs, power) = power_spectrum(values, dt);
+                power
+                    .iter()
+                    .enumerate()
+                    .skip(1)
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .map(|(k, _)| freqs[k])
+            } else {
+                None
+            };
+
+            Some(ObservableMetrics {
+                name: series.name.to_string(),
+                initial: first.re,
+                final_value: last.re,
+                steady_state: steady_state.mean,
+                steady_state_error: steady_state.std_error,
+                converged_at: steady_state.converged_at,
+                peak_frequency,
+            })
+        })
+        .collect()
+}
+
+pub enum Figure {
+    Lines {
+        title: String,
+        x_label: String,
+        y_label: String,
+        series: Vec<(String, Vec<(f64, f64)>)>,
+    },
+    Heatmap {
+        title: String,
+        x_label: String,
+        y_label: String,
+        x_range: (f64, f64),
+        y_range: (f64, f64),
+        values: Array2<f64>,
+    },
+}
+
+pub struct ReportGenerator {
+    output_dir: PathBuf,
+    style: ReportStyle,
+}
+
+impl ReportGenerator {
+    pub fn new(output_dir: impl Into<PathBuf>, style: ReportStyle) -> Result<Self> {
+        let output_dir = output_dir.into();
+        std::fs::create_dir_all(&output_dir)?;
+        register_fonts()?;
+        Ok(Self { output_dir, style })
+    }
+
+    pub fn output_dir(&self) -> &Path {
+        &self.output_dir
+    }
+
+    pub fn render(&self, results: &SimulationResults) -> Result<Vec<PathBuf>> {
+        let mut written = Vec::new();
+        let mut traces = Vec::new();
+
+        let uniform = results.is_uniformly_sampled();
+
+        for series in results.iter_series() {
+            if series.len() < 2 {
+                continue;
+            }
+            let name = series.name.to_string();
+
+            traces.push((
+                name.clone(),
+                series.iter().map(|(t, v)| (t, v.re)).collect::<Vec<_>>(),
+            ));
+
+            if !uniform {
+                tracing::warn!(
+                    "Skipping spectrum of {}: output times are non-uniform",
+                    name
+                );
+            }
+
+            let dt = series.times[1] - series.times[0];
+            let (freqs, power) = power_spectrum(series.values, dt);
+            if uniform {
+                written.push(self.save_figure(
+                    &format!("spectrum_{}", name),
+                    &Figure::Lines {
+                        title: format!("Spectrum of {}", name),
+                        x_label: "ω".to_string(),
+                        y_label: "|S(ω)|²".to_string(),
+                        series: vec![(name.clone(), freqs.into_iter().zip(power).collect())],
+                    },
+                )?);
+            }
+
+            written.push(self.save_figure(
+                &format!("phase_space_{}", name),
+                &Figure::Lines {
+                    title: format!("Phase space of {}", name),
+                    x_label: format!("Re ⟨{}⟩", name),
+                    y_label: format!("Im ⟨{}⟩", name),
+                    series: vec![(
+                        name.clone(),
+                        series.values.iter().map(|v| (v.re, v.im)).collect(),
+                    )],
+                },
+            )?);
+        }
+
+        for series in results.iter_vector_series() {
+            if series.len() < 2 {
+                continue;
+            }
+
+            let values = series.to_ndarray().t().mapv(|v| v.re);
+            written.push(self.save_figure(
+                &format!("heatmap_{}", series.name),
+                &Figure::Heatmap {
+                    title: series.name.to_string(),
+                    x_label: "t".to_string(),
+                    y_label: "component".to_string(),
+                    x_range: (series.times[0], series.times[series.len() - 1]),
+                    y_range: (0.0, series.width as f64),
+                    values,
+                },
+            )?);
+        }
+
+        if !traces.is_empty() {
+            written.insert(
+                0,
+                self.save_figure(
+                    "observables",
+                    &Figure::Lines {
+                        title: "Observables".to_string(),
+                        x_label: "t".to_string(),
+                        y_label: "Re ⟨O⟩".to_string(),
+                        series: traces,
+                    },
+                )?,
+            );
+        }
+
+        Ok(written)
+    }
+
+    pub fn write_summary(
+        &self,
+        config: &Config,
+        results: &SimulationResults,
+        figures: &[PathBuf],
+        format: SummaryFormat,
+    ) -> Result<PathBuf> {
+        let config_text = toml::to_string_pretty(config)
+            .map_err(|e| Error::Serialization(format!("TOML error: {}", e)))?;
+        let metrics = observable_metrics(results);
+        let fits = fit_results(config, results);
+        let figure_names: Vec<String> = figures
+            .iter()
+            .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .collect();
+
+        let (file_name, contents) = match format {
+            SummaryFormat::Markdown => (
+                "summary.md",
+                markdown_summary(
+                    config,
+                    &config_text,
+                    &metrics,
+                    &fits,
+                    &figure_names,
+                    results.resources(),
+                ),
+            ),
+            SummaryFormat::Latex => (
+                "summary.tex",
+                latex_summary(
+                    config,
+                    &config_text,
+                    &metrics,
+                    &fits,
+                    &figure_names,
+                    results.resources(),
+                ),
+            ),
+        };
+
+        let path = self.output_dir.join(file_name);
+        std::fs::write(&path, contents)?;
+        Ok(path)
+    }
+
+    pub fn render_heatmap(
+        &self,
+        name: &str,
+        (x_label, y_label): (&str, &str),
+        values: Array2<f64>,
+        x_range: (f64, f64),
+        y_range: (f64, f64),
+    ) -> Result<PathBuf> {
+        self.save_figure(
+            &format!("heatmap_{}", name),
+            &Figure::Heatmap {
+                title: name.to_string(),
+                x_label: x_label.to_string(),
+                y_label: y_label.to_string(),
+                x_range,
+                y_range,
+                values,
+            },
+        )
+    }
+
+    pub fn render_spectrogram(&self, name: &str, spectrogram: &Spectrogram) -> Result<PathBuf> {
+        let last = |v: &[f64]| v.last().copied().unwrap_or(0.0);
+        self.save_figure(
+            &format!("spectrogram_{}", name),
+            &Figure::Heatmap {
+                title: format!("Spectrogram of {}", name),
+                x_label: "t".to_string(),
+                y_label: "ω".to_string(),
+                x_range: (spectrogram.times[0], last(&spectrogram.times)),
+                y_range: (0.0, last(&spectrogram.frequencies)),
+                values: spectrogram.power.t().to_owned(),
+            },
+        )
+    }
+
+    pub fn save_figure(&self, stem: &str, figure: &Figure) -> Result<PathBuf> {
+        let path = self
+            .output_dir
+            .join(format!("{}.{}", stem, self.style.format.extension()));
+        let size = (self.style.width, self.style.height);
+
+        match self.style.format {
+            FigureFormat::Png => {
+                let root = BitMapBackend::new(&path, size).into_drawing_area();
+                draw_figure(&root, figure, &self.style)?;
+            }
+            FigureFormat::Svg => {
+                let root = SVGBackend::new(&path, size).into_drawing_area();
+                draw_figure(&root, figure, &self.style)?;
+            }
+            FigureFormat::Pdf => {
+                // plotters has no PDF backend, so the figure is drawn as SVG and converted.
+                let mut svg = String::new();
+                draw_figure(
+                    &SVGBackend::with_string(&mut svg, size).into_drawing_area(),
+                    figure,
+                    &self.style,
+                )?;
+                std::fs::write(&path, svg_to_pdf(&svg)?)?;
+            }
+        }
+
+        Ok(path)
+    }
+}
+
+fn format_frequency(freq: Option<f64>) -> String {
+    freq.map(|f| format!("{:.4}", f))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn format_time(time: Option<f64>) -> String {
+    time.map(|t| format!("{:.3}", t))
+        .unwrap_or_else(|| "not reached".to_string())
+}
+
+pub fn fit_results(config: &Config, results: &SimulationResults) -> Vec<FitResult> {
+    config
+        .observables
+        .fits
+        .iter()
+        .filter_map(|spec| match results.fit(spec) {
+            Ok(fit) => Some(fit),
+            Err(e) => {
+                tracing::warn!("Fit of '{}' failed: {}", spec.observable, e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn format_estimate(estimate: Option<&Estimate>) -> String {
+    estimate
+        .map(|e| e.to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn markdown_summary(
+    config: &Config,
+    config_text: &str,
+    metrics: &[ObservableMetrics],
+    fits: &[FitResult],
+    figures: &[String],
+    resources: Option<&ResourceUsage>,
+) -> String {
+    let mut out = format!("# Simulation report: {}\n\n", config.simulation.name);
+
+    out.push_str("## Key metrics\n\n");
+    out.push_str("| Observable | Initial | Final | Steady state | Converged at | Peak ω |\n");
+    out.push_str("|---|---|---|---|---|---|\n");
+    for m in metrics {
+        out.push_str(&format!(
+            "| {} | {:.6} | {:.6} | {:.6} ± {:.6} | {} | {} |\n",
+            m.name,
+            m.initial,
+            m.final_value,
+            m.steady_state,
+            m.steady_state_error,
+            format_time(m.converged_at),
+            format_frequency(m.peak_frequency)
+        ));
+    }
+
+    if !fits.is_empty() {
+        out.push_str("\n## Fits\n\n");
+        out.push_str("| Observable | Model | Decay time | Frequency | RMS residual |\n");
+        out.push_str("|---|---|---|---|---|\n");
+        for fit in fits {
+            out.push_str(&format!(
+                "| {} | {} | {} | {} | {:.3e} |\n",
+                fit.observable,
+                fit.model.name(),
+                fit.decay_time,
+                format_estimate(fit.frequency.as_ref()),
+                fit.rms_residual
+            ));
+        }
+    }
+
+    if let Some(resources) = resources {
+        out.push_str(&format!("\n## Resources\n\n{}\n", resources.describe()));
+    }
+
+    out.push_str("\n## Figures\n\n");
+    for figure in figures {
+        out.push_str(&format!("![{}]({})\n\n", figure, figure));
+    }
+
+    out.push_str("## Configuration\n\n```toml\n");
+    out.push_str(config_text);
+    out.push_str("```\n");
+    out
+}
+
+fn latex_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '_' | '%' | '&' | '#' | '$' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\\' => out.push_str("\\textbackslash{}"),
+            '^' => out.push_str("\\^{}"),
+            '~' => out.push_str("\\~{}"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn latex_summary(
+    config: &Config,
+    config_text: &str,
+    metrics: &[ObservableMetrics],
+    fits: &[FitResult],
+    figures: &[String],
+    resources: Option<&ResourceUsage>,
+) -> String {
+    // pdflatex cannot include SVG, the svg package converts it through Inkscape.
+    let mut out = String::from("\\documentclass{article}\n\\usepackage{graphicx}\n");
+    if figures.iter().any(|figure| figure.ends_with(".svg")) {
+        out.push_str("\\usepackage{svg}\n");
+    }
+    out.push_str("\\begin{document}\n\n");
+    out.push_str(&format!(
+        "\\section*{{Simulation report: {}}}\n\n",
+        latex_escape(&config.simulation.name)
+    ));
+
+    out.push_str("\\subsection*{Key metrics}\n\\begin{tabular}{lrrrrr}\n");
+    out.push_str(
+        "Observable & Initial & Final & Steady state & Converged at & Peak $\\omega$ \\\\\n\\hline\n",
+    );
+    for m in metrics {
+        out.push_str(&format!(
+            "{} & {:.6} & {:.6} & ${:.6} \\pm {:.6}$ & {} & {} \\\\\n",
+            latex_escape(&m.name),
+            m.initial,
+            m.final_value,
+            m.steady_state,
+            m.steady_state_error,
+            format_time(m.converged_at),
+            format_frequency(m.peak_frequency)
+        ));
+    }
+    out.push_str("\\end{tabular}\n\n");
+
+    if !fits.is_empty() {
+        out.push_str("\\subsection*{Fits}\n\\begin{tabular}{llrrr}\n");
+        out.push_str("Observable & Model & Decay time & Frequency & RMS residual \\\\\n\\hline\n");
+        for fit in fits {
+            out.push_str(&format!(
+                "{} & {} & ${}$ & ${}$ & {:.3e} \\\\\n",
+                latex_escape(&fit.observable),
+                latex_escape(fit.model.name()),
+                fit.decay_time.to_string().replace('±', "\\pm"),
+                format_estimate(fit.frequency.as_ref()).replace('±', "\\pm"),
+                fit.rms_residual
+            ));
+        }
+        out.push_str("\\end{tabular}\n\n");
+    }
+
+    if let Some(resources) = resources {
+        out.push_str(&format!(
+            "\\subsection*{{Resources}}\n{}\n\n",
+            latex_escape(&resources.describe())
+        ));
+    }
+
+    out.push_str("\\subsection*{Figures}\n");
+    for figure in figures {
+        let include = match figure.strip_suffix(".svg") {
+            Some(stem) => format!("\\includesvg[width=0.8\\linewidth]{{{}}}", stem),
+            None => format!("\\includegraphics[width=0.8\\linewidth]{{{}}}", figure),
+        };
+        out.push_str(&format!(
+            "\\begin{{figure}}[h]\n\\centering\n{}\n\\end{{figure}}\n",
+            include
+        ));
+    }
+
+    out.push_str("\n\\subsection*{Configuration}\n\\begin{verbatim}\n");
+    out.push_str(config_text);
+    out.push_str("\\end{verbatim}\n\n\\end{document}\n");
+    out
+}
+
+fn report_font() -> Option<&'static [u8]> {
+    let fonts = egui::FontDefinitions::default();
+    match fonts.font_data.get("Ubuntu-Light").map(|data| &data.font) {
+        Some(std::borrow::Cow::Borrowed(bytes)) => Some(bytes),
+        _ => None,
+    }
+}
+
+pub(crate) fn register_fonts() -> Result<()> {
+    static REGISTERED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+
+    let ok = *REGISTERED.get_or_init(|| {
+        report_font().is_some_and(|bytes| {
+            plotters::style::register_font("sans-serif", FontStyle::Normal, bytes).is_ok()
+        })
+    });
+
+    if ok {
+        Ok(())
+    } else {
+        Err(Error::Other(anyhow::anyhow!(
+            "Failed to register report font"
+        )))
+    }
+}
+
+// Text is turned into paths with the report font, so the PDF needs no fonts of its own.
+fn svg_to_pdf(svg: &str) -> Result<Vec<u8>> {
+    use svg2pdf::usvg::{fontdb, PostProcessingSteps, TreeParsing, TreePostProc};
+
+    let mut tree =
+        svg2pdf::usvg::Tree::from_str(svg, &svg2pdf::usvg::Options::default()).map_err(plot_err)?;
+    let mut fonts = fontdb::Database::new();
+    if let Some(bytes) = report_font() {
+        fonts.load_font_data(bytes.to_vec());
+    }
+    let family = fonts.faces().next().map(|face| face.families[0].0.clone());
+    if let Some(family) = family {
+        fonts.set_sans_serif_family(family);
+    }
+    tree.postprocess(
+        PostProcessingSteps {
+            convert_text_into_paths: true,
+        },
+        &fonts,
+    );
+    Ok(svg2pdf::convert_tree(&tree, svg2pdf::Options::default()))
+}
+
+pub(crate) fn plot_err<E: std::fmt::Display>(e: E) -> Error {
+    Error::Other(anyhow::anyhow!("Plotting error: {}", e))
+}
+
+fn padded_range(min: f64, max: f64) -> std::ops::Range<f64> {
+    if !min.is_finite() || !max.is_finite() {
+        return 0.0..1.0;
+    }
+    let pad = if max > min { 0.05 * (max - min) } else { 0.5 };
+    (min - pad)..(max + pad)
+}
+
+fn draw_figure<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    figure: &Figure,
+    style: &ReportStyle,
+) -> Result<()>
+where
+    DB::ErrorType: 'static,
+{
+    root.fill(&WHITE).map_err(plot_err)?;
+    let label_font = ("sans-serif", style.font_size * 2 / 3);
+
+    match figure {
+        Figure::Lines {
+            title,
+            x_label,
+            y_label,
+            series,
+        } => {
+            let points = series.iter().flat_map(|(_, p)| p.iter());
+            let (mut x_min, mut x_max) = (f64::INFINITY, f64::NEG_INFINITY);
+            let (mut y_min, mut y_max) = (f64::INFINITY, f64::NEG_INFINITY);
+            for &(x, y) in points {
+                x_min = x_min.min(x);
+                x_max = x_max.max(x);
+                y_min = y_min.min(y);
+                y_max = y_max.max(y);
+            }
+
+            let mut chart = ChartBuilder::on(root)
+                .caption(title, ("sans-serif", style.font_size))
+                .margin(15)
+                .x_label_area_size(50)
+                .y_label_area_size(70)
+                .build_cartesian_2d(padded_range(x_min, x_max), padded_range(y_min, y_max))
+                .map_err(plot_err)?;
+
+            chart
+                .configure_mesh()
+                .x_desc(x_label.as_str())
+                .y_desc(y_label.as_str())
+                .label_style(label_font)
+                .draw()
+                .map_err(plot_err)?;
+
+            for (idx, (name, points)) in series.iter().enumerate() {
+                let color = Palette99::pick(idx).to_rgba();
+                chart
+                    .draw_series(LineSeries::new(
+                        points.iter().copied(),
+                        color.stroke_width(style.line_width),
+                    ))
+                    .map_err(plot_err)?
+                    .label(name.as_str())
+                    .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+            }
+
+            chart
+                .configure_series_labels()
+                .label_font(label_font)
+                .background_style(WHITE.mix(0.8))
+                .border_style(BLACK)
+                .draw()
+                .map_err(plot_err)?;
+        }
+        Figure::Heatmap {
+            title,
+            x_label,
+            y_label,
+            x_range,
+            y_range,
+            values,
+        } => {
+            let (ny, nx) = values.dim();
+            let v_min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+            let v_max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let span = if v_max > v_min { v_max - v_min } else { 1.0 };
+
+            let mut chart = ChartBuilder::on(root)
+                .caption(title, ("sans-serif", style.font_size))
+                .margin(15)
+                .x_label_area_size(50)
+                .y_label_area_size(70)
+                .build_cartesian_2d(x_range.0..x_range.1, y_range.0..y_range.1)
+                .map_err(plot_err)?;
+
+            chart
+                .configure_mesh()
+                .disable_mesh()
+                .x_desc(x_label.as_str())
+                .y_desc(y_label.as_str())
+                .label_style(label_font)
+                .draw()
+                .map_err(plot_err)?;
+
+            let dx = (x_range.1 - x_range.0) / nx.max(1) as f64;
+            let dy = (y_range.1 - y_range.0) / ny.max(1) as f64;
+            chart
+                .draw_series(values.indexed_iter().filter(|(_, v)| v.is_finite()).map(
+                    |((iy, ix), &v)| {
+                        let x0 = x_range.0 + ix as f64 * dx;
+                        let y0 = y_range.0 + iy as f64 * dy;
+                        let level = (v - v_min) / span;
+                        Rectangle::new(
+                            [(x0, y0), (x0 + dx, y0 + dy)],
+                            HSLColor(0.7 * (1.0 - level), 0.9, 0.5).filled(),
+                        )
+                    },
+                ))
+                .map_err(plot_err)?;
+        }
+    }
+
+    root.present().map_err(plot_err)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex64;
+
+    #[test]
+    fn test_figure_format_parse() {
+        assert_eq!(FigureFormat::parse("SVG").unwrap(), FigureFormat::Svg);
+        assert!(FigureFormat::parse("bmp").is_err());
+        assert_eq!(FigureFormat::parse("pdf").unwrap(), FigureFormat::Pdf);
+    }
+
+    #[test]
+    fn test_render_pdf_heatmap() {
+        let dir = std::env::temp_dir().join("chronophoton_report_pdf_test");
+        let style = ReportStyle {
+            format: FigureFormat::Pdf,
+            ..ReportStyle::default()
+        };
+        let generator = ReportGenerator::new(&dir, style).unwrap();
+        let mut values = Array2::from_shape_fn((3, 4), |(i, j)| (i * j) as f64);
+        values[[1, 2]] = f64::NAN;
+        let path = generator
+            .render_heatmap("fidelity", ("g", "κ"), values, (0.0, 1.0), (0.0, 2.0))
+            .unwrap();
+
+        assert_eq!(path, dir.join("heatmap_fidelity.pdf"));
+        assert!(std::fs::read(&path).unwrap().starts_with(b"%PDF"));
+        std::fs::remove_dir_all(&dir).ok();
+
+        // Labels are drawn as glyph outlines with the report font.
+        let svg = |text: &str| {
+            format!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="50"><text x="10" y="30" font-family="sans-serif" font-size="20">{}</text></svg>"#,
+                text
+            )
+        };
+        assert!(
+            svg_to_pdf(&svg("Spectrum")).unwrap().len() > svg_to_pdf(&svg("")).unwrap().len() + 200
+        );
+    }
+
+    #[test]
+    fn test_observable_metrics() {
+        let mut results = SimulationResults::new();
+        for i in 0..100 {
+            let t = i as f64 * 0.1;
+            results.add_observable("purity", t, Complex64::new(1.0 - 0.004 * i as f64, 0.0));
+        }
+
+        let metrics = observable_metrics(&results);
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].initial, 1.0);
+        assert!(metrics[0].steady_state < 0.66);
+        assert!(latex_escape("rabi_freq").contains("\\_"));
+        assert_eq!(latex_escape("a\\b^2~c"), "a\\textbackslash{}b\\^{}2\\~{}c");
+    }
+
+    #[test]
+    fn test_latex_summary_includes_svg_through_the_svg_package() {
+        let config = Config::generate_template("driven_tls").unwrap();
+        let summary = |figures: &[&str]| {
+            let figures: Vec<String> = figures.iter().map(|f| f.to_string()).collect();
+            latex_summary(&config, "", &[], &[], &figures, None)
+        };
+
+        let svg = summary(&["observables.svg"]);
+        assert!(svg.contains("\\usepackage{svg}"));
+        assert!(svg.contains("\\includesvg[width=0.8\\linewidth]{observables}"));
+        assert!(!svg.contains("\\includegraphics"));
+
+        let pdf = summary(&["observables.pdf"]);
+        assert!(!pdf.contains("\\usepackage{svg}"));
+        assert!(pdf.contains("\\includegraphics[width=0.8\\linewidth]{observables.pdf}"));
+    }
+
+    #[test]
+    fn test_render_svg_report() {
+        let mut results = SimulationResults::new();
+        for i in 0..32 {
+            let t = i as f64 * 0.1;
+            results.add_observable("population", t, Complex64::new(t.cos().powi(2), 0.0));
+        }
+
+        let dir = std::env::temp_dir().join("chronophoton_report_test");
+        let style = ReportStyle {
+            format: FigureFormat::Svg,
+            ..ReportStyle::default()
+        };
+        let generator = ReportGenerator::new(&dir, style).unwrap();
+        let written = generator.render(&results).unwrap();
+
+        assert_eq!(written.len(), 3);
+        assert!(written.iter().all(|p| p.exists()));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,81 @@
+use crate::simulation::StateSnapshot;
+use crate::utils::{Error, Result};
+use bincode::{Decode, Encode};
+use num_complex::Complex64;
+use std::path::Path;
+
+#[derive(Encode, Decode)]
+struct SnapshotRecord {
+    time: f64,
+    amplitudes: Vec<(f64, f64)>,
+}
+
+pub struct SnapshotFile;
+
+impl SnapshotFile {
+    pub fn save(snapshots: &[StateSnapshot], path: &Path) -> Result<()> {
+        let records: Vec<SnapshotRecord> = snapshots
+            .iter()
+            .map(|s| SnapshotRecord {
+                time: s.time,
+                amplitudes: s.amplitudes.iter().map(|c| (c.re, c.im)).collect(),
+            })
+            .collect();
+
+        let data = bincode::encode_to_vec(&records, bincode::config::standard())
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Vec<StateSnapshot>> {
+        let data = std::fs::read(path)?;
+        let (records, _): (Vec<SnapshotRecord>, _) =
+            bincode::decode_from_slice(&data, bincode::config::standard()).map_err(|e| {
+                Error::Serialization(format!(
+                    "Failed to read state snapshots from {}: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+
+        Ok(records
+            .into_iter()
+            .map(|r| StateSnapshot {
+                time: r.time,
+                amplitudes: r
+                    .amplitudes
+                    .into_iter()
+                    .map(|(re, im)| Complex64::new(re, im))
+                    .collect(),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_roundtrip() {
+        let dir = std::env::temp_dir().join("chronophoton_snapshot_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("states.bin");
+
+        let snapshots = vec![
+            StateSnapshot {
+                time: 0.0,
+                amplitudes: vec![Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
+            },
+            StateSnapshot {
+                time: 0.5,
+                amplitudes: vec![Complex64::new(0.6, 0.0), Complex64::new(0.0, -0.8)],
+            },
+        ];
+        SnapshotFile::save(&snapshots, &path).unwrap();
+        assert_eq!(SnapshotFile::load(&path).unwrap(), snapshots);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,366 @@
+use crate::core::{DensityMatrix, QuantumState};
+use crate::data::config::TomographyConfig;
+use crate::simulation::sampling::sample_multinomial;
+use crate::utils::math::{frobenius_norm, trace};
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+use std::io::Write;
+use std::path::Path;
+
+const PROBABILITY_FLOOR: f64 = 1e-15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeasurementSet {
+    GellMann,
+    Diagonal,
+}
+
+impl MeasurementSet {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "gell_mann" | "pauli" => Ok(Self::GellMann),
+            "diagonal" | "fock" => Ok(Self::Diagonal),
+            other => Err(Error::Config(format!(
+                "Unknown tomography measurement set '{}', expected gell_mann or diagonal",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::GellMann => "gell_mann",
+            Self::Diagonal => "diagonal",
+        }
+    }
+
+    fn settings(&self, dim: usize) -> Vec<Setting> {
+        let mut settings = vec![Setting {
+            label: "z".to_string(),
+            outcomes: (0..dim)
+                .map(|i| Projector::Ket(vec![(i, Complex64::new(1.0, 0.0))]))
+                .collect(),
+        }];
+        if *self == Self::Diagonal {
+            return settings;
+        }
+
+        let amplitude = std::f64::consts::FRAC_1_SQRT_2;
+        for j in 0..dim {
+            for k in j + 1..dim {
+                for (label, phase) in [("x", Complex64::new(1.0, 0.0)), ("y", Complex64::i())] {
+                    let mut outcomes = vec![
+                        Projector::Ket(vec![
+                            (j, Complex64::new(amplitude, 0.0)),
+                            (k, phase * amplitude),
+                        ]),
+                        Projector::Ket(vec![
+                            (j, Complex64::new(amplitude, 0.0)),
+                            (k, -phase * amplitude),
+                        ]),
+                    ];
+                    if dim > 2 {
+                        outcomes.push(Projector::Complement(vec![j, k]));
+                    }
+                    settings.push(Setting {
+                        label: format!("{}_{}_{}", label, j, k),
+                        outcomes,
+                    });
+                }
+            }
+        }
+        settings
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Projector {
+    Ket(Vec<(usize, Complex64)>),
+    Complement(Vec<usize>),
+}
+
+impl Projector {
+    fn probability(&self, rho: &Array2<Complex64>) -> f64 {
+        match self {
+            Self::Ket(ket) => {
+                ket.iter()
+                    .flat_map(|&(i, a)| ket.iter().map(move |&(j, b)| (i, j, a, b)))
+                    .map(|(i, j, a, b)| a.conj() * rho[[i, j]] * b)
+                    .sum::<Complex64>()
+                    .re
+            }
+            Self::Complement(excluded) => {
+                1.0 - excluded.iter().map(|&i| rho[[i, i]].re).sum::<f64>()
+            }
+        }
+    }
+
+    fn accumulate(&self, weight: f64, target: &mut Array2<Complex64>) {
+        match self {
+            Self::Ket(ket) => {
+                for &(i, a) in ket {
+                    for &(j, b) in ket {
+                        target[[i, j]] += a * b.conj() * weight;
+                    }
+                }
+            }
+            Self::Complement(excluded) => {
+                for i in (0..target.nrows()).filter(|i| !excluded.contains(i)) {
+                    target[[i, i]] += weight;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Setting {
+    label: String,
+    outcomes: Vec<Projector>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TomographyData {
+    pub dim: usize,
+    pub labels: Vec<String>,
+    pub frequencies: Vec<Vec<f64>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Reconstruction {
+    pub rho: DensityMatrix,
+    pub iterations: usize,
+    pub converged: bool,
+    pub log_likelihood: f64,
+}
+
+impl Reconstruction {
+    pub fn fidelity(&self, target: &QuantumState) -> Result<f64> {
+        if target.dim() != self.rho.dim() {
+            return Err(Error::dimension_mismatch(self.rho.dim(), target.dim()));
+        }
+        let psi = target.data();
+        let rho = self.rho.data();
+        let mut overlap = Complex64::new(0.0, 0.0);
+        for i in 0..psi.len() {
+            for j in 0..psi.len() {
+                overlap += psi[i].conj() * rho[[i, j]] * psi[j];
+            }
+        }
+        Ok(overlap.re)
+    }
+
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(writer, "row,col,re,im")?;
+        for ((i, j), value) in self.rho.data().indexed_iter() {
+            writeln!(writer, "{},{},{},{}", i, j, value.re, value.im)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+pub struct Tomography {
+    measurements: MeasurementSet,
+    shots: usize,
+    seed: Option<u64>,
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+impl Tomography {
+    pub fn new(
+        measurements: MeasurementSet,
+        shots: usize,
+        seed: Option<u64>,
+        max_iterations: usize,
+        tolerance: f64,
+    ) -> Result<Self> {
+        if max_iterations == 0 {
+            return Err(Error::InvalidParameter(
+                "Tomography needs at least one likelihood iteration".to_string(),
+            ));
+        }
+
+        if tolerance <= 0.0 || !tolerance.is_finite() {
+            return Err(Error::InvalidParameter(format!(
+                "Tomography tolerance must be positive, got {}",
+                tolerance
+            )));
+        }
+
+        Ok(Self {
+            measurements,
+            shots,
+            seed,
+            max_iterations,
+            tolerance,
+        })
+    }
+
+    pub fn from_config(config: &TomographyConfig) -> Result<Self> {
+        Self::new(
+            MeasurementSet::parse(&config.measurements)?,
+            config.shots,
+            config.seed,
+            config.max_iterations,
+            config.tolerance,
+        )
+    }
+
+    pub fn measure(&self, rho: &DensityMatrix) -> Result<TomographyData> {
+        let mut rng = crate::utils::performance::seeded_rng(self.seed);
+        let settings = self.measurements.settings(rho.dim());
+
+        let mut frequencies = Vec::with_capacity(settings.len());
+        for setting in &settings {
+            let mut probabilities: Vec<f64> = setting
+                .outcomes
+                .iter()
+                .map(|outcome| outcome.probability(rho.data()).max(0.0))
+                .collect();
+            let total: f64 = probabilities.iter().sum();
+            if total <= 0.0 {
+                return Err(Error::Numerical(format!(
+                    "Measurement setting '{}' has vanishing outcome probabilities",
+                    setting.label
+                )));
+            }
+            probabilities.iter_mut().for_each(|p| *p /= total);
+
+            if self.shots > 0 {
+                let counts = sample_multinomial(&mut rng, self.shots, &probabilities)?;
+                for (p, clicks) in probabilities.iter_mut().zip(counts) {
+                    *p = clicks as f64 / self.shots as f64;
+                }
+            }
+            frequencies.push(probabilities);
+        }
+
+        Ok(TomographyData {
+            dim: rho.dim(),
+            labels: settings.into_iter().map(|s| s.label).collect(),
+            frequencies,
+        })
+    }
+
+    pub fn reconstruct(&self, data: &TomographyData) -> Result<Reconstruction> {
+        let dim = data.dim;
+        let settings = self.measurements.settings(dim);
+        if settings.len() != data.frequencies.len() {
+            return Err(Error::dimension_mismatch(
+                settings.len(),
+                data.frequencies.len(),
+            ));
+        }
+        for (setting, frequencies) in settings.iter().zip(&data.frequencies) {
+            if setting.outcomes.len() != frequencies.len() {
+                return Err(Error::dimension_mismatch(
+                    setting.outcomes.len(),
+                    frequencies.len(),
+                ));
+            }
+        }
+
+        let weight = 1.0 / settings.len() as f64;
+        let mut rho = DensityMatrix::maximally_mixed(dim).data().clone();
+        let mut iterations = 0;
+        let mut converged = false;
+        while iterations < self.max_iterations {
+            iterations += 1;
+            let mut r = Array2::<Complex64>::zeros((dim, dim));
+            for (setting, frequencies) in settings.iter().zip(&data.frequencies) {
+                for (outcome, &f) in setting.outcomes.iter().zip(frequencies) {
+                    if f > 0.0 {
+                        let p = outcome.probability(&rho).max(PROBABILITY_FLOOR);
+                        outcome.accumulate(weight * f / p, &mut r);
+                    }
+                }
+            }
+
+            let next = r.dot(&rho).dot(&r);
+            let next = (&next + &next.t().mapv(|z| z.conj())) / (2.0 * trace(&next.view()).re);
+            let change = frobenius_norm(&(&next - &rho).view());
+            rho = next;
+            if change < self.tolerance {
+                converged = true;
+                break;
+            }
+        }
+
+        let scale = if self.shots > 0 {
+            self.shots as f64
+        } else {
+            1.0
+        };
+        let log_likelihood = settings
+            .iter()
+            .zip(&data.frequencies)
+            .flat_map(|(setting, frequencies)| setting.outcomes.iter().zip(frequencies))
+            .filter(|(_, &f)| f > 0.0)
+            .map(|(outcome, &f)| scale * f * outcome.probability(&rho).max(PROBABILITY_FLOOR).ln())
+            .sum();
+
+        Ok(Reconstruction {
+            rho: DensityMatrix::new_unchecked(rho),
+            iterations,
+            converged,
+            log_likelihood,
+        })
+    }
+
+    pub fn run(&self, rho: &DensityMatrix) -> Result<Reconstruction> {
+        self.reconstruct(&self.measure(rho)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use ndarray::array;
+
+    #[test]
+    fn test_exact_tomography_recovers_mixed_state() {
+        let c = |re: f64, im: f64| Complex64::new(re, im);
+        let rho = DensityMatrix::new(array![
+            [c(0.5, 0.0), c(0.1, 0.1), c(0.0, 0.05)],
+            [c(0.1, -0.1), c(0.3, 0.0), c(0.05, 0.0)],
+            [c(0.0, -0.05), c(0.05, 0.0), c(0.2, 0.0)],
+        ])
+        .unwrap();
+        let tomography = Tomography::new(MeasurementSet::GellMann, 0, None, 5000, 1e-12).unwrap();
+        let reconstruction = tomography.run(&rho).unwrap();
+
+        let error = frobenius_norm(&(reconstruction.rho.data() - rho.data()).view());
+        assert!(error < 1e-4, "reconstruction error {}", error);
+        assert_relative_eq!(reconstruction.rho.purity(), rho.purity(), epsilon = 1e-4);
+        assert!(MeasurementSet::parse("sic").is_err());
+    }
+
+    #[test]
+    fn test_shot_noise_and_measurement_set_limit_fidelity() {
+        let plus = QuantumState::bloch(2, std::f64::consts::FRAC_PI_2, 0.0).unwrap();
+        let rho = plus.to_density_matrix();
+
+        let noisy = Tomography::new(MeasurementSet::GellMann, 2000, Some(7), 2000, 1e-10)
+            .unwrap()
+            .run(&rho)
+            .unwrap();
+        let fidelity = noisy.fidelity(&plus).unwrap();
+        assert!(
+            fidelity > 0.97 && fidelity <= 1.0 + 1e-9,
+            "fidelity {}",
+            fidelity
+        );
+        assert!(noisy.log_likelihood < 0.0);
+
+        let diagonal = Tomography::new(MeasurementSet::Diagonal, 0, None, 2000, 1e-10)
+            .unwrap()
+            .run(&rho)
+            .unwrap();
+        assert_relative_eq!(diagonal.fidelity(&plus).unwrap(), 0.5, epsilon = 1e-9);
+    }
+}
@@ -1,4 +1,7 @@
-use crate::utils::Result;
+use crate::utils::{Error, Result};
+use std::future::Future;
+use std::sync::OnceLock;
+use std::task::{Context, Poll, Waker};
 
 #[derive(Debug, Clone)]
 pub struct GpuDevice {
@@ -19,7 +22,6 @@ pub enum BackendType {
 #[derive(Default)]
 pub struct GpuBackend {
     device: Option<wgpu::Device>,
-    #[allow(dead_code)]
     queue: Option<wgpu::Queue>,
     enabled: bool,
 }
@@ -34,19 +36,79 @@ impl GpuBackend {
             });
         }
 
-        tracing::info!("GPU backend requested but not yet implemented");
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = match instance
+            .request_adapter(&wgpu::RequestAdapterOptions::default())
+            .await
+        {
+            Ok(adapter) => adapter,
+            Err(e) => {
+                tracing::info!("No GPU adapter available: {}", e);
+                return Ok(Self::default());
+            }
+        };
+        let info = adapter.get_info();
+        if !adapter
+            .get_downlevel_capabilities()
+            .flags
+            .contains(wgpu::DownlevelFlags::COMPUTE_SHADERS)
+        {
+            tracing::info!("GPU adapter {} has no compute shaders", info.name);
+            return Ok(Self::default());
+        }
+
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: Some("chronophoton"),
+                required_limits: adapter.limits(),
+                ..Default::default()
+            })
+            .await
+            .map_err(|e| Error::gpu(format!("Failed to open GPU device: {}", e)))?;
+        tracing::info!("Using GPU adapter {} ({:?})", info.name, info.backend);
 
         Ok(Self {
-            device: None,
-            queue: None,
-            enabled: false,
+            device: Some(device),
+            queue: Some(queue),
+            enabled: true,
         })
     }
 
+    // The device the batch kernels share, opened on first use. `None` when no
+    // adapter with compute shaders is available.
+    pub fn shared() -> Option<&'static GpuBackend> {
+        static SHARED: OnceLock<Option<GpuBackend>> = OnceLock::new();
+        SHARED
+            .get_or_init(|| match block_on(Self::new(true)) {
+                Ok(backend) if backend.is_enabled() => Some(backend),
+                Ok(_) => None,
+                Err(e) => {
+                    tracing::warn!("{}", e);
+                    None
+                }
+            })
+            .as_ref()
+    }
+
     pub fn is_enabled(&self) -> bool {
         self.enabled && self.device.is_some()
     }
 
+    pub(crate) fn device(&self) -> Result<(&wgpu::Device, &wgpu::Queue)> {
+        match (&self.device, &self.queue) {
+            (Some(device), Some(queue)) => Ok((device, queue)),
+            _ => Err(Error::gpu("GPU backend has no device")),
+        }
+    }
+
+    // Largest buffer a kernel may bind as one storage array.
+    pub fn max_buffer_bytes(&self) -> u64 {
+        self.device.as_ref().map_or(0, |device| {
+            let limits = device.limits();
+            (limits.max_storage_buffer_binding_size as u64).min(limits.max_buffer_size)
+        })
+    }
+
     pub async fn available_devices() -> Result<Vec<GpuDevice>> {
         Ok(vec![GpuDevice {
             name: "CPU Fallback".to_string(),
@@ -55,3 +117,16 @@ impl GpuBackend {
         }])
     }
 }
+
+// wgpu's native futures complete once the device has been polled, so a spinning
+// executor is enough and keeps the kernels callable from synchronous code.
+pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut context = Context::from_waker(Waker::noop());
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+        std::thread::yield_now();
+    }
+}
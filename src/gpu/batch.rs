@@ -1,8 +1,18 @@
-use crate::utils::Result;
+use crate::core::floquet::{one_period_propagator, step_propagator, FloquetSpectrum};
+use crate::core::Hamiltonian;
+use crate::gpu::kernels::{DensityExpectationKernel, PropagatorProductKernel};
+use crate::gpu::GpuBackend;
+use crate::simulation::JobControl;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+use rayon::prelude::*;
+use std::sync::Arc;
 
 pub struct BatchExecutor {
     batch_size: usize,
     gpu_enabled: bool,
+    control: Option<Arc<JobControl>>,
 }
 
 impl BatchExecutor {
@@ -10,6 +20,21 @@ impl BatchExecutor {
         Self {
             batch_size,
             gpu_enabled,
+            control: None,
+        }
+    }
+
+    // Cancellation is checked before every dispatch, so an abort lands between
+    // batches whether they run on the device or fall back to the CPU.
+    pub fn with_control(mut self, control: Arc<JobControl>) -> Self {
+        self.control = Some(control);
+        self
+    }
+
+    fn check(&self) -> Result<()> {
+        match &self.control {
+            Some(control) => control.check(),
+            None => Ok(()),
         }
     }
 
@@ -21,13 +46,274 @@ impl BatchExecutor {
         self.gpu_enabled
     }
 
+    // The shared device when GPU batches were requested and one is available.
+    // Deterministic mode always runs on the CPU.
+    fn device(&self) -> Option<&'static GpuBackend> {
+        if !self.gpu_enabled || crate::utils::performance::is_deterministic() {
+            return None;
+        }
+        let device = GpuBackend::shared();
+        if device.is_none() {
+            tracing::warn!("No GPU adapter with compute shaders available, using CPU");
+        }
+        device
+    }
+
     pub fn execute<F, T>(&self, operations: Vec<F>) -> Result<Vec<T>>
     where
         F: Fn() -> Result<T> + Send,
         T: Send,
     {
-        use rayon::prelude::*;
+        let _span = tracing::trace_span!("batch_execute", jobs = operations.len()).entered();
+        operations
+            .into_par_iter()
+            .map(|op| {
+                self.check()?;
+                op()
+            })
+            .collect()
+    }
+
+    // The step propagators are built on the CPU thread pool and multiplied into
+    // one-period propagators on the device. The spectra are diagonalized on the CPU.
+    pub fn floquet_spectra(
+        &self,
+        hamiltonians: &[&dyn Hamiltonian],
+        period: f64,
+        num_steps: usize,
+    ) -> Result<Vec<FloquetSpectrum>> {
+        let mut spectra = Vec::with_capacity(hamiltonians.len());
+        let mut device = self.device();
+
+        for batch in hamiltonians.chunks(self.batch_size.max(1)) {
+            self.check()?;
+            let _span = tracing::trace_span!("gpu_dispatch", size = batch.len()).entered();
+            let propagators = match device.map(|gpu| gpu_propagators(gpu, batch, period, num_steps))
+            {
+                Some(Ok(propagators)) => propagators,
+                None => cpu_propagators(batch, period, num_steps),
+                Some(Err(Error::Gpu(e))) => {
+                    tracing::warn!("{}, using CPU", e);
+                    device = None;
+                    cpu_propagators(batch, period, num_steps)
+                }
+                Some(Err(e)) => return Err(e),
+            };
 
-        operations.into_par_iter().map(|op| op()).collect()
+            for propagator in &propagators {
+                spectra.push(FloquetSpectrum::from_propagator(propagator, period)?);
+            }
+        }
+        Ok(spectra)
+    }
+
+    // Tr(Oρ) for every state and observable. On the device only these scalars
+    // are copied back, never the density matrices.
+    pub fn density_expectations(
+        &self,
+        states: &[Array2<Complex64>],
+        observables: &[&Array2<Complex64>],
+    ) -> Result<Vec<Vec<Complex64>>> {
+        for rho in states {
+            if let Some(o) = observables.iter().find(|o| o.dim() != rho.dim()) {
+                return Err(Error::dimension_mismatch(rho.nrows(), o.nrows()));
+            }
+        }
+        let mut values = Vec::with_capacity(states.len());
+        let mut device = self.device();
+
+        for batch in states.chunks(self.batch_size.max(1)) {
+            self.check()?;
+            let _span = tracing::trace_span!("gpu_dispatch", size = batch.len()).entered();
+            match device.map(|gpu| DensityExpectationKernel::execute_batch(gpu, batch, observables))
+            {
+                Some(Ok(batch_values)) => values.extend(batch_values),
+                None => values.extend(cpu_expectations(batch, observables)),
+                Some(Err(Error::Gpu(e))) => {
+                    tracing::warn!("{}, using CPU", e);
+                    device = None;
+                    values.extend(cpu_expectations(batch, observables));
+                }
+                Some(Err(e)) => return Err(e),
+            }
+        }
+        Ok(values)
+    }
+}
+
+fn cpu_propagators(
+    batch: &[&dyn Hamiltonian],
+    period: f64,
+    num_steps: usize,
+) -> Vec<Array2<Complex64>> {
+    batch
+        .par_iter()
+        .map(|h| one_period_propagator(*h, period, num_steps))
+        .collect()
+}
+
+// Splits the batch so each dispatch's step propagators fit in one device buffer.
+fn gpu_propagators(
+    gpu: &GpuBackend,
+    batch: &[&dyn Hamiltonian],
+    period: f64,
+    num_steps: usize,
+) -> Result<Vec<Array2<Complex64>>> {
+    let dt = period / num_steps as f64;
+    let Some(dim) = batch.first().map(|h| h.dim()) else {
+        return Ok(Vec::new());
+    };
+    if let Some(h) = batch.iter().find(|h| h.dim() != dim) {
+        return Err(Error::dimension_mismatch(dim, h.dim()));
+    }
+    if num_steps == 0 {
+        return Ok(cpu_propagators(batch, period, num_steps));
+    }
+    let bytes_per_hamiltonian = (num_steps.max(1) * dim * dim * 8) as u64;
+    let per_dispatch = (gpu.max_buffer_bytes() / bytes_per_hamiltonian) as usize;
+    if per_dispatch == 0 {
+        return Err(Error::gpu(format!(
+            "{} step propagators of dimension {} do not fit in one device buffer",
+            num_steps, dim
+        )));
+    }
+
+    let mut propagators = Vec::with_capacity(batch.len());
+    for chunk in batch.chunks(per_dispatch) {
+        let steps: Vec<Vec<Array2<Complex64>>> = chunk
+            .par_iter()
+            .map(|h| {
+                (0..num_steps)
+                    .map(|step| step_propagator(*h, step as f64 * dt, dt))
+                    .collect()
+            })
+            .collect();
+        propagators.extend(PropagatorProductKernel::execute_batch(gpu, &steps)?);
+    }
+    Ok(propagators)
+}
+
+fn cpu_expectations(
+    batch: &[Array2<Complex64>],
+    observables: &[&Array2<Complex64>],
+) -> Vec<Vec<Complex64>> {
+    batch
+        .par_iter()
+        .map(|rho| {
+            observables
+                .iter()
+                .map(|o| o.iter().zip(rho.t().iter()).map(|(a, b)| a * b).sum())
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observables::{MatrixObservable, Observable};
+    use crate::core::DensityMatrix;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_cancelled_control_stops_before_dispatch() {
+        let control = Arc::new(JobControl::new());
+        control.cancel();
+        let result = BatchExecutor::new(2, false)
+            .with_control(control)
+            .execute(vec![|| Ok(1)]);
+        assert!(matches!(result, Err(Error::Cancelled(_))));
+    }
+
+    fn mixed_state(dim: usize, seed: usize) -> Array2<Complex64> {
+        // A normalized ρ = AA†/Tr(AA†) from a fixed pseudo-random A.
+        let a = Array2::from_shape_fn((dim, dim), |(i, j)| {
+            let x = ((i * 7 + j * 13 + seed * 29) % 17) as f64 / 17.0;
+            Complex64::new(x - 0.4, 0.5 - x * x)
+        });
+        let rho = a.dot(&a.t().mapv(|z| z.conj()));
+        let trace: Complex64 = rho.diag().sum();
+        rho.mapv(|z| z / trace.re)
+    }
+
+    #[test]
+    fn test_density_expectations_match_cpu_expectation_mixed() {
+        let dim = 4;
+        let states: Vec<_> = (0..5).map(|seed| mixed_state(dim, seed)).collect();
+        let number = Array2::from_shape_fn((dim, dim), |(i, j)| {
+            Complex64::new(if i == j { i as f64 } else { 0.0 }, 0.0)
+        });
+        let hopping = Array2::from_shape_fn((dim, dim), |(i, j)| match i.abs_diff(j) {
+            1 => Complex64::new(0.5, if i < j { 0.25 } else { -0.25 }),
+            _ => Complex64::new(0.0, 0.0),
+        });
+        let observables = [&number, &hopping];
+
+        let cpu = BatchExecutor::new(2, false)
+            .density_expectations(&states, &observables)
+            .unwrap();
+        let gpu = GpuBackend::shared().map(|gpu| {
+            DensityExpectationKernel::execute_batch(gpu, &states, &observables).unwrap()
+        });
+        for (k, rho) in states.iter().enumerate() {
+            let rho = DensityMatrix::new(rho.clone()).unwrap();
+            for (m, op) in observables.iter().enumerate() {
+                let expected = MatrixObservable::new((*op).clone()).expectation_mixed(&rho);
+                assert_relative_eq!(cpu[k][m].re, expected.re, epsilon = 1e-12);
+                assert_relative_eq!(cpu[k][m].im, expected.im, epsilon = 1e-12);
+                // The device computes in f32.
+                if let Some(gpu) = &gpu {
+                    assert_relative_eq!(gpu[k][m].re, expected.re, epsilon = 1e-5);
+                    assert_relative_eq!(gpu[k][m].im, expected.im, epsilon = 1e-5);
+                }
+            }
+        }
+
+        let qutrit = Array2::<Complex64>::zeros((3, 3));
+        assert!(BatchExecutor::new(2, true)
+            .density_expectations(&states, &[&qutrit])
+            .is_err());
+        let control = Arc::new(JobControl::new());
+        control.cancel();
+        let cancelled = BatchExecutor::new(2, true)
+            .with_control(control)
+            .density_expectations(&states, &observables);
+        assert!(matches!(cancelled, Err(Error::Cancelled(_))));
+    }
+
+    #[test]
+    fn test_device_floquet_propagators_match_cpu() {
+        use crate::core::systems::DrivenTLS;
+
+        let Some(gpu) = GpuBackend::shared() else {
+            return;
+        };
+        let systems: Vec<DrivenTLS> = (0..5)
+            .map(|k| DrivenTLS::new(5.0, 5.0, 0.1 * k as f64))
+            .collect();
+        let refs: Vec<&dyn Hamiltonian> = systems.iter().map(|h| h as &dyn Hamiltonian).collect();
+        let period = 2.0 * std::f64::consts::PI / 5.0;
+
+        // 37 steps exercise the odd carry at several reduction rounds.
+        let cpu = cpu_propagators(&refs, period, 37);
+        let device = gpu_propagators(gpu, &refs, period, 37).unwrap();
+        assert_eq!(device.len(), cpu.len());
+        for (d, c) in device.iter().zip(&cpu) {
+            for (a, b) in d.iter().zip(c) {
+                assert!((a - b).norm() < 1e-5, "{} vs {}", a, b);
+            }
+        }
+
+        let spectra = BatchExecutor::new(2, true)
+            .floquet_spectra(&refs, period, 400)
+            .unwrap();
+        let reference = BatchExecutor::new(2, false)
+            .floquet_spectra(&refs, period, 400)
+            .unwrap();
+        for (s, r) in spectra.iter().zip(&reference) {
+            for (a, b) in s.quasi_energies.iter().zip(&r.quasi_energies) {
+                assert_relative_eq!(*a, *b, epsilon = 1e-4);
+            }
+        }
     }
 }
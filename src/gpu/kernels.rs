@@ -1,6 +1,9 @@
-use crate::utils::Result;
+use crate::gpu::backend::{block_on, GpuBackend};
+use crate::gpu::memory::GpuMemoryPool;
+use crate::utils::{Error, Result};
 use ndarray::Array2;
 use num_complex::Complex64;
+use wgpu::util::DeviceExt;
 
 pub struct MatMulKernel;
 
@@ -25,3 +28,364 @@ impl EvolveKernel {
         ))
     }
 }
+
+// Tr(Oρ) for every state and observable. Each invocation reduces one (state,
+// observable) pair, so only the scalars come back from the device.
+const DENSITY_EXPECTATION_SHADER: &str = r#"
+struct Params { states: u32, observables: u32, dim: u32, unused: u32 }
+
+@group(0) @binding(0) var<storage, read> rho: array<vec2<f32>>;
+@group(0) @binding(1) var<storage, read> ops: array<vec2<f32>>;
+@group(0) @binding(2) var<storage, read_write> values: array<vec2<f32>>;
+@group(0) @binding(3) var<uniform> params: Params;
+
+fn cmul(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let index = id.x + id.y * groups.x * 64u;
+    if (index >= params.states * params.observables) {
+        return;
+    }
+    let n = params.dim;
+    let r = (index / params.observables) * n * n;
+    let o = (index % params.observables) * n * n;
+    var sum = vec2<f32>(0.0, 0.0);
+    for (var i = 0u; i < n; i++) {
+        for (var j = 0u; j < n; j++) {
+            sum += cmul(rho[r + i * n + j], ops[o + j * n + i]);
+        }
+    }
+    values[index] = sum;
+}
+"#;
+
+pub struct DensityExpectationKernel;
+
+impl DensityExpectationKernel {
+    pub fn execute_batch(
+        gpu: &GpuBackend,
+        states: &[Array2<Complex64>],
+        observables: &[&Array2<Complex64>],
+    ) -> Result<Vec<Vec<Complex64>>> {
+        let (Some(first), false) = (states.first(), observables.is_empty()) else {
+            return Ok(vec![Vec::new(); states.len()]);
+        };
+        let dim = first.nrows();
+        let pairs = states.len() * observables.len();
+        let mut session = KernelSession::new(gpu, "density_expectation")?;
+        session.check_size(states.len() * dim * dim)?;
+
+        let rho = session.input(states.iter().flat_map(|rho| rho.iter()))?;
+        let ops = session.input(observables.iter().flat_map(|o| o.iter()))?;
+        let values = session.output(pairs)?;
+        let params = session.params([states.len() as u32, observables.len() as u32, dim as u32, 0]);
+        let kernel = session.pipeline(DENSITY_EXPECTATION_SHADER)?;
+        let mut encoder = session.encoder();
+        session.dispatch(
+            &mut encoder,
+            &kernel,
+            &[&rho, &ops, &values, &params],
+            pairs,
+        )?;
+        let values = session.finish(encoder, &values, pairs)?;
+
+        Ok(values
+            .chunks(observables.len())
+            .map(<[Complex64]>::to_vec)
+            .collect())
+    }
+}
+
+// Multiplies each Hamiltonian's step propagators U = S(n-1)···S(1)S(0) by a
+// pairwise tree reduction: every round halves the number of factors, and a
+// trailing odd factor is carried over unchanged.
+const PROPAGATOR_PRODUCT_SHADER: &str = r#"
+struct Params { batch: u32, factors: u32, dim: u32, products: u32 }
+
+@group(0) @binding(0) var<storage, read> src: array<vec2<f32>>;
+@group(0) @binding(1) var<storage, read_write> dst: array<vec2<f32>>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+fn cmul(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let index = id.x + id.y * groups.x * 64u;
+    let n = params.dim;
+    let size = n * n;
+    if (index >= params.batch * params.products * size) {
+        return;
+    }
+    let entry = index % size;
+    let product = (index / size) % params.products;
+    let b = index / (size * params.products);
+    let right = (b * params.factors + 2u * product) * size;
+    if (2u * product + 1u >= params.factors) {
+        dst[index] = src[right + entry];
+        return;
+    }
+    let left = right + size;
+    let i = entry / n;
+    let j = entry % n;
+    var sum = vec2<f32>(0.0, 0.0);
+    for (var k = 0u; k < n; k++) {
+        sum += cmul(src[left + i * n + k], src[right + k * n + j]);
+    }
+    dst[index] = sum;
+}
+"#;
+
+pub struct PropagatorProductKernel;
+
+impl PropagatorProductKernel {
+    // `steps[b]` holds the step propagators of Hamiltonian `b` in time order; all
+    // share one dimension and step count.
+    pub fn execute_batch(
+        gpu: &GpuBackend,
+        steps: &[Vec<Array2<Complex64>>],
+    ) -> Result<Vec<Array2<Complex64>>> {
+        let Some(first) = steps.first().and_then(|s| s.first()) else {
+            return Ok(Vec::new());
+        };
+        let dim = first.nrows();
+        let size = dim * dim;
+        let factors = steps[0].len();
+        if let Some(other) = steps.iter().find(|s| s.len() != factors) {
+            return Err(Error::dimension_mismatch(factors, other.len()));
+        }
+        if let Some(other) = steps.iter().flatten().find(|s| s.dim() != (dim, dim)) {
+            return Err(Error::dimension_mismatch(dim, other.nrows()));
+        }
+        let mut session = KernelSession::new(gpu, "propagator_product")?;
+        session.check_size(steps.len() * factors * size)?;
+
+        let kernel = session.pipeline(PROPAGATOR_PRODUCT_SHADER)?;
+        let mut current = session.input(steps.iter().flatten().flat_map(|s| s.iter()))?;
+        let mut encoder = session.encoder();
+        let mut remaining = factors;
+        // Each round gets its own uniform buffer, so all rounds share one submission.
+        while remaining > 1 {
+            let products = remaining.div_ceil(2);
+            let next = session.output(steps.len() * products * size)?;
+            let params = session.params([
+                steps.len() as u32,
+                remaining as u32,
+                dim as u32,
+                products as u32,
+            ]);
+            session.dispatch(
+                &mut encoder,
+                &kernel,
+                &[&current, &next, &params],
+                steps.len() * products * size,
+            )?;
+            current = next;
+            remaining = products;
+        }
+        let values = session.finish(encoder, &current, steps.len() * size)?;
+
+        Ok(values
+            .chunks(size)
+            .map(|entries| {
+                Array2::from_shape_vec((dim, dim), entries.to_vec())
+                    .expect("device returned whole matrices")
+            })
+            .collect())
+    }
+}
+
+// Buffers, dispatches and readback for one kernel call. Device validation errors
+// surface as `Error::Gpu` instead of wgpu's default panic, and every buffer is
+// counted in a memory pool so the run's resource report includes the device.
+struct KernelSession<'a> {
+    device: &'a wgpu::Device,
+    queue: &'a wgpu::Queue,
+    max_bytes: u64,
+    label: &'static str,
+    pool: GpuMemoryPool,
+    scope_open: bool,
+}
+
+const WORKGROUP_SIZE: usize = 64;
+const COMPLEX_BYTES: u64 = 2 * std::mem::size_of::<f32>() as u64;
+
+impl<'a> KernelSession<'a> {
+    fn new(gpu: &'a GpuBackend, label: &'static str) -> Result<Self> {
+        let (device, queue) = gpu.device()?;
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        Ok(Self {
+            device,
+            queue,
+            max_bytes: gpu.max_buffer_bytes(),
+            label,
+            pool: GpuMemoryPool::new(),
+            scope_open: true,
+        })
+    }
+
+    fn check_size(&self, entries: usize) -> Result<()> {
+        let bytes = entries as u64 * COMPLEX_BYTES;
+        if bytes > self.max_bytes {
+            return Err(Error::gpu(format!(
+                "{} batch needs a {} byte buffer, the device allows {}",
+                self.label, bytes, self.max_bytes
+            )));
+        }
+        Ok(())
+    }
+
+    fn input<'b>(&mut self, values: impl Iterator<Item = &'b Complex64>) -> Result<wgpu::Buffer> {
+        let data: Vec<f32> = values.flat_map(|z| [z.re as f32, z.im as f32]).collect();
+        self.pool
+            .allocate(data.len() * std::mem::size_of::<f32>())?;
+        Ok(self
+            .device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(self.label),
+                contents: bytemuck::cast_slice(&data),
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            }))
+    }
+
+    fn output(&mut self, entries: usize) -> Result<wgpu::Buffer> {
+        let size = entries as u64 * COMPLEX_BYTES;
+        self.pool.allocate(size as usize)?;
+        Ok(self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(self.label),
+            size,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        }))
+    }
+
+    fn params(&self, params: [u32; 4]) -> wgpu::Buffer {
+        self.device
+            .create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(self.label),
+                contents: bytemuck::cast_slice(&params),
+                usage: wgpu::BufferUsages::UNIFORM,
+            })
+    }
+
+    fn pipeline(&self, source: &str) -> Result<wgpu::ComputePipeline> {
+        let module = self
+            .device
+            .create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(self.label),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+        Ok(self
+            .device
+            .create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(self.label),
+                layout: None,
+                module: &module,
+                entry_point: Some("main"),
+                compilation_options: Default::default(),
+                cache: None,
+            }))
+    }
+
+    fn encoder(&self) -> wgpu::CommandEncoder {
+        self.device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some(self.label),
+            })
+    }
+
+    // Spreads `invocations` over a 2D grid when one dimension would exceed the
+    // device's workgroup limit; the shaders flatten the grid back to an index.
+    fn dispatch(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::ComputePipeline,
+        buffers: &[&wgpu::Buffer],
+        invocations: usize,
+    ) -> Result<()> {
+        let groups = invocations.div_ceil(WORKGROUP_SIZE);
+        let max_groups = self.device.limits().max_compute_workgroups_per_dimension as usize;
+        let x = groups.clamp(1, max_groups);
+        let y = groups.div_ceil(x);
+        if y > max_groups {
+            return Err(Error::gpu(format!(
+                "{} dispatch of {} invocations exceeds the device's workgroup limit",
+                self.label, invocations
+            )));
+        }
+
+        let entries: Vec<wgpu::BindGroupEntry> = buffers
+            .iter()
+            .enumerate()
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect();
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some(self.label),
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &entries,
+        });
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some(self.label),
+            timestamp_writes: None,
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(x as u32, y as u32, 1);
+        Ok(())
+    }
+
+    // Submits the recorded work and copies `entries` complex values out of `result`.
+    fn finish(
+        mut self,
+        mut encoder: wgpu::CommandEncoder,
+        result: &wgpu::Buffer,
+        entries: usize,
+    ) -> Result<Vec<Complex64>> {
+        let size = entries as u64 * COMPLEX_BYTES;
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some(self.label),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        encoder.copy_buffer_to_buffer(result, 0, &staging, 0, size);
+        self.queue.submit([encoder.finish()]);
+
+        let slice = staging.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |mapped| {
+            tx.send(mapped).ok();
+        });
+        self.device
+            .poll(wgpu::PollType::wait_indefinitely())
+            .map_err(|e| Error::gpu(format!("{} failed: {}", self.label, e)))?;
+        self.scope_open = false;
+        if let Some(e) = block_on(self.device.pop_error_scope()) {
+            return Err(Error::gpu(format!("{} failed: {}", self.label, e)));
+        }
+        rx.recv()
+            .map_err(|_| Error::gpu(format!("{} readback was dropped", self.label)))?
+            .map_err(|e| Error::gpu(format!("{} readback failed: {}", self.label, e)))?;
+
+        let data: Vec<f32> = bytemuck::pod_collect_to_vec(&slice.get_mapped_range());
+        Ok(data
+            .chunks_exact(2)
+            .map(|z| Complex64::new(z[0] as f64, z[1] as f64))
+            .collect())
+    }
+}
+
+impl Drop for KernelSession<'_> {
+    fn drop(&mut self) {
+        if self.scope_open {
+            block_on(self.device.pop_error_scope());
+        }
+    }
+}
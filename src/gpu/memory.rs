@@ -1,4 +1,7 @@
 use crate::utils::Result;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static PEAK_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
 
 pub struct GpuBuffer {
     size_bytes: usize,
@@ -25,14 +28,23 @@ impl GpuMemoryPool {
 
     pub fn allocate(&mut self, size_bytes: usize) -> Result<GpuBuffer> {
         self.total_allocated += size_bytes;
+        PEAK_ALLOCATED.fetch_max(self.total_allocated, Ordering::Relaxed);
         GpuBuffer::new(size_bytes)
     }
 
+    pub fn release(&mut self, buffer: GpuBuffer) {
+        self.total_allocated = self.total_allocated.saturating_sub(buffer.size());
+    }
+
     pub fn total_allocated(&self) -> usize {
         self.total_allocated
     }
 }
 
+pub fn peak_allocated() -> usize {
+    PEAK_ALLOCATED.load(Ordering::Relaxed)
+}
+
 impl Default for GpuMemoryPool {
     fn default() -> Self {
         Self::new()
@@ -3,5 +3,5 @@ pub mod batch;
 pub mod kernels;
 pub mod memory;
 
-pub use backend::{GpuBackend, GpuDevice};
+pub use backend::{BackendType, GpuBackend, GpuDevice};
 pub use batch::BatchExecutor;
@@ -1,6 +1,11 @@
+pub mod bench;
+#[cfg(feature = "capi")]
+pub mod capi;
 pub mod core;
 pub mod data;
 pub mod gpu;
+pub mod plugin;
+pub mod quick;
 pub mod simulation;
 pub mod sweep;
 pub mod ui;
@@ -10,10 +15,12 @@ pub mod prelude {
     pub use crate::core::{
         hamiltonian::Hamiltonian,
         integrator::{Integrator, IntegratorType},
+        lindblad::LindbladOperator,
         observables::{ExpectationValue, Observable},
         state::{DensityMatrix, QuantumState},
     };
     pub use crate::data::config::Config;
+    pub use crate::quick::{evolve, lindblad_evolve, Trajectory};
     pub use crate::simulation::{SimulationBuilder, SimulationResults};
     pub use crate::utils::error::Result;
 }
@@ -1,8 +1,32 @@
+use chronophoton::plugin::PluginRegistry;
 use chronophoton::{
-    data::config::Config, simulation::SimulationBuilder, ui::gui::App, utils::logger,
+    bench::{self, BenchReport},
+    core::bands::{
+        chain_edges, edge_modes, winding_number, write_edge_modes_csv, BandStructure, UnitCell,
+    },
+    core::Precision,
+    data::{
+        config::Config,
+        operator_export::{self, MatrixFormat, OperatorKind},
+        operator_import::{self, OperatorImport},
+        output::{config_hash, CollisionPolicy},
+        pipeline, presets,
+        report::{fit_results, SummaryFormat},
+        Animation, AnimationKind, AnimationOptions, Archive, Campaign, Checkpoint,
+        MeasurementModel, ReportGenerator, ReportStyle, RunDirectory, SnapshotFile,
+    },
+    simulation::{
+        convergence, run_preemptible, ConvergenceTest, CostEstimate, HeatingAnalysis,
+        HeatingOptions, JobControl, OutputPorts, RandomEnsemble, RandomFloquetStudy, ReducedModel,
+        RunDifference, SimulationBuilder, SimulationResults, SolverHierarchy, TransmissionSpectrum,
+    },
+    sweep::{self, MergedSweep, ParameterSweep, SweepPoint},
+    ui::gui::App,
+    utils::{exit, logger, performance},
 };
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser};
 use std::path::PathBuf;
+use std::sync::Arc;
 
 #[derive(Parser, Debug)]
 #[command(name = "chronophoton")]
@@ -10,6 +34,9 @@ use std::path::PathBuf;
 struct Args {
     #[command(subcommand)]
     command: Commands,
+
+    #[arg(long, global = true)]
+    profile: Option<PathBuf>,
 }
 
 #[derive(Parser, Debug)]
@@ -23,16 +50,282 @@ enum Commands {
 
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        #[arg(long)]
+        resume: Option<PathBuf>,
+
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    DiffRun {
+        a: PathBuf,
+
+        b: PathBuf,
+
+        #[arg(long, value_delimiter = ',')]
+        observables: Vec<String>,
+
+        #[arg(short, long, default_value = "diff")]
+        output: PathBuf,
     },
 
     Gui {
         config: Option<PathBuf>,
+
+        #[arg(long)]
+        session: Option<PathBuf>,
+    },
+
+    Report {
+        #[arg(short, long)]
+        config: PathBuf,
+
+        #[arg(short, long, default_value = "reports")]
+        output: PathBuf,
+
+        #[arg(short, long)]
+        format: Option<String>,
+    },
+
+    Campaign {
+        file: PathBuf,
+    },
+
+    Sweep {
+        #[arg(short, long)]
+        config: PathBuf,
+
+        #[arg(short, long, default_value = "sweeps")]
+        output: PathBuf,
+
+        #[arg(long, requires = "array_size")]
+        array_index: Option<usize>,
+
+        #[arg(long, requires = "array_index")]
+        array_size: Option<usize>,
+    },
+
+    Merge {
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+
+        #[arg(short, long)]
+        output: Option<PathBuf>,
     },
 
     Validate {
         config: PathBuf,
     },
 
+    Bands {
+        #[arg(short, long)]
+        config: PathBuf,
+
+        #[arg(short, long, default_value = "bands")]
+        output: PathBuf,
+
+        #[arg(long, default_value_t = 101)]
+        k_points: usize,
+
+        #[arg(long, default_value_t = 2)]
+        edge_depth: usize,
+    },
+
+    Bench {
+        #[arg(long, value_delimiter = ',')]
+        dims: Option<Vec<usize>>,
+
+        #[arg(long, value_delimiter = ',')]
+        solvers: Option<Vec<String>>,
+
+        #[arg(long, default_value_t = 5)]
+        samples: usize,
+
+        #[arg(long)]
+        json: bool,
+
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+
+        #[arg(long, default_value_t = 0.2)]
+        tolerance: f64,
+    },
+
+    Archive {
+        run_dir: PathBuf,
+
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    Animate {
+        states: PathBuf,
+
+        #[arg(short, long, default_value = "bloch")]
+        kind: String,
+
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        #[arg(long, default_value_t = 20)]
+        fps: u32,
+    },
+
+    ExitCodes {
+        #[arg(long)]
+        json: bool,
+    },
+
+    ExportOperator {
+        #[arg(short, long)]
+        config: PathBuf,
+
+        #[arg(long, default_value = "hamiltonian")]
+        what: String,
+
+        #[arg(long, default_value_t = 0.0)]
+        time: f64,
+
+        #[arg(long, default_value = "npy")]
+        format: String,
+
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    CheckOperators {
+        #[arg(long)]
+        hamiltonian: Option<PathBuf>,
+
+        #[arg(long)]
+        collapse: Vec<String>,
+
+        #[arg(long)]
+        liouvillian: Option<PathBuf>,
+
+        #[arg(long, default_value_t = 1e-10)]
+        tolerance: f64,
+    },
+
+    VerifyIntegrators {
+        #[arg(long, value_delimiter = ',')]
+        timesteps: Vec<f64>,
+
+        #[arg(long, default_value = "f64")]
+        precision: String,
+    },
+
+    RandomFloquet {
+        #[arg(long, default_value = "goe")]
+        ensemble: String,
+
+        #[arg(long, default_value_t = 64)]
+        dim: usize,
+
+        #[arg(long, default_value_t = 100)]
+        realizations: usize,
+
+        #[arg(long, default_value_t = 1.0)]
+        drive: f64,
+
+        #[arg(long, default_value_t = 2.0)]
+        frequency: f64,
+
+        #[arg(long, default_value_t = 200)]
+        steps: usize,
+
+        #[arg(long, default_value_t = 2)]
+        bandwidth: usize,
+
+        #[arg(long, default_value_t = 1.0)]
+        j1: f64,
+
+        #[arg(long, default_value_t = 0.5)]
+        j2: f64,
+
+        #[arg(long, default_value_t = 1.0)]
+        disorder: f64,
+
+        #[arg(long)]
+        seed: Option<u64>,
+
+        #[arg(long, default_value_t = 256)]
+        batch_size: usize,
+
+        #[arg(long)]
+        gpu: bool,
+
+        #[arg(short, long, default_value = "level_statistics.csv")]
+        output: PathBuf,
+    },
+
+    Heating {
+        #[arg(short, long)]
+        config: PathBuf,
+
+        #[arg(long, value_delimiter = ',', required = true)]
+        frequencies: Vec<f64>,
+
+        #[arg(long, default_value = "system.parameters.omega_d")]
+        parameter: String,
+
+        #[arg(long, default_value_t = 200)]
+        periods: usize,
+
+        #[arg(long, default_value_t = 100)]
+        steps: usize,
+
+        #[arg(long, default_value_t = 0.02)]
+        tolerance: f64,
+
+        #[arg(short, long, default_value = "heating.csv")]
+        output: PathBuf,
+    },
+
+    CompareSolvers {
+        #[arg(short, long)]
+        config: PathBuf,
+
+        #[arg(long, default_value_t = 0.05)]
+        tolerance: f64,
+
+        #[arg(short, long, default_value = "solver_comparison")]
+        output: PathBuf,
+    },
+
+    Transmission {
+        #[arg(short, long)]
+        config: PathBuf,
+
+        #[arg(short, long, default_value = "transmission.csv")]
+        output: PathBuf,
+    },
+
+    Ports {
+        #[arg(short, long)]
+        config: PathBuf,
+
+        #[arg(short, long, default_value = "ports")]
+        output: PathBuf,
+    },
+
+    Reduce {
+        #[arg(short, long)]
+        config: PathBuf,
+
+        #[arg(long, default_value_t = 4)]
+        modes: usize,
+
+        #[arg(short, long, default_value = "reduced.toml")]
+        output: PathBuf,
+    },
+
+    Example {
+        #[command(subcommand)]
+        command: ExampleCommand,
+    },
+
     Template {
         #[arg(short, long, default_value = "config.toml")]
         output: PathBuf,
@@ -42,73 +335,852 @@ enum Commands {
     },
 }
 
+#[derive(Parser, Debug)]
+enum ExampleCommand {
+    List,
+
+    Show {
+        name: String,
+    },
+
+    Run {
+        name: String,
+
+        #[arg(long)]
+        gpu: Option<bool>,
+
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    logger::init()?;
+async fn main() {
+    if let Err(e) = start().await {
+        eprintln!("Error: {:?}", e);
+        let code = e
+            .downcast_ref::<chronophoton::utils::Error>()
+            .map_or(exit::FAILURE, exit::code_for);
+        std::process::exit(code);
+    }
+}
+
+async fn start() -> anyhow::Result<()> {
+    let registry = plugins()?;
+    let matches = registry.augment(Args::command())?.get_matches();
+    if let Some(result) = registry.dispatch(&matches) {
+        logger::init()?;
+        return Ok(result?);
+    }
+    run(Args::from_arg_matches(&matches)?, registry).await
+}
+
+#[cfg(feature = "plugins")]
+fn plugins() -> anyhow::Result<PluginRegistry> {
+    let mut registry = PluginRegistry::new();
+    registry.discover()?;
+    Ok(registry)
+}
+
+#[cfg(not(feature = "plugins"))]
+fn plugins() -> anyhow::Result<PluginRegistry> {
+    Ok(PluginRegistry::new())
+}
 
-    let args = Args::parse();
+async fn run(args: Args, registry: PluginRegistry) -> anyhow::Result<()> {
+    let _profile = match &args.profile {
+        Some(path) => Some(logger::init_profiled(path)?),
+        None => {
+            logger::init()?;
+            None
+        }
+    };
 
     match args.command {
         Commands::Run {
             config,
             gpu,
             output,
+            resume,
+            dry_run,
+        } => {
+            tracing::info!("Loading configuration from {:?}", config);
+            registry.validate_config_file(&config)?;
+            let mut cfg = Config::from_file(&config)?;
+            if dry_run {
+                performance::apply(&mut cfg).await?;
+                let estimate = CostEstimate::dry_run(&cfg)?;
+                println!(
+                    "Dry run of '{}': {}",
+                    cfg.simulation.name,
+                    estimate.describe()
+                );
+            } else {
+                run_config(cfg, gpu, output, resume).await?;
+            }
+        }
+
+        Commands::Report {
+            config,
+            output,
+            format,
         } => {
             tracing::info!("Loading configuration from {:?}", config);
             let mut cfg = Config::from_file(&config)?;
+            performance::apply(&mut cfg).await?;
 
-            if let Some(gpu_enabled) = gpu {
-                cfg.gpu.enabled = gpu_enabled;
+            if let Some(format) = format {
+                cfg.report.format = format;
             }
 
-            tracing::info!("Building simulation");
+            let style = ReportStyle::from_config(&cfg.report)?;
             let sim = SimulationBuilder::from_config(&cfg)?;
+            let mut results = sim.run()?;
 
-            tracing::info!("Running simulation");
-            let results = sim.run()?;
+            if cfg.measurement.enabled {
+                results = MeasurementModel::from_config(&cfg.measurement)?.apply(&results)?;
+            }
+            results.set_steady_state_options(cfg.observables.steady_state.clone());
 
-            if let Some(output_path) = output {
-                tracing::info!("Saving results to {:?}", output_path);
-                results.save(&output_path)?;
-            } else {
-                results.print_summary();
+            let report_dir = output.join(&cfg.simulation.name);
+            let generator = ReportGenerator::new(&report_dir, style)?;
+            let mut figures = generator.render(&results)?;
+            for name in &cfg.observables.spectrogram.observables {
+                let spectrogram = results.spectrogram(name, &cfg.observables.spectrogram)?;
+                figures.push(generator.render_spectrogram(name, &spectrogram)?);
+            }
+
+            if let Some(summary_format) = SummaryFormat::parse(&cfg.report.summary)? {
+                let summary = generator.write_summary(&cfg, &results, &figures, summary_format)?;
+                tracing::info!("Wrote run summary to {:?}", summary);
             }
 
-            tracing::info!("Simulation complete");
+            println!("Wrote {} figures to {:?}", figures.len(), report_dir);
         }
 
-        Commands::Gui { config } => {
-            tracing::info!("Launching GUI");
-            let app = if let Some(config_path) = config {
-                let cfg = Config::from_file(&config_path)?;
-                App::with_config(cfg)
-            } else {
-                App::new()
+        Commands::Campaign { file } => {
+            tracing::info!("Loading campaign from {:?}", file);
+            let campaign = Campaign::from_file(&file)?;
+            let summary = campaign.run()?;
+
+            let summary_path = summary.output_dir.join("summary.md");
+            summary.write_markdown(&summary_path)?;
+
+            let failed: Vec<_> = summary.failed().collect();
+            for (name, error) in &failed {
+                eprintln!("✗ {}: {}", name, error);
+            }
+            println!(
+                "Campaign '{}': {}/{} simulations succeeded, summary written to {:?}",
+                summary.name,
+                summary.runs.len() - failed.len(),
+                summary.runs.len(),
+                summary_path
+            );
+        }
+
+        Commands::Sweep {
+            config,
+            output,
+            array_index,
+            array_size,
+        } => {
+            tracing::info!("Loading configuration from {:?}", config);
+            let mut cfg = Config::from_file(&config)?;
+            performance::apply(&mut cfg).await?;
+
+            let mut sweep = ParameterSweep::from_config(&cfg)?;
+            if let (Some(index), Some(size)) = (array_index, array_size) {
+                sweep = sweep.array_task(index, size);
+            }
+            let points = sweep.assigned_points()?;
+            tracing::info!("Running {} sweep points", points.len());
+            let runs = sweep.run(&cfg)?;
+
+            let sweep_dir = output.join(&cfg.simulation.name);
+            std::fs::create_dir_all(&sweep_dir)?;
+            let path = match array_index {
+                Some(index) => {
+                    let path = sweep_dir.join(format!("metrics_task_{:04}.csv", index));
+                    sweep::write_array_task_csv(&runs, &config_hash(&cfg)?, &path)?;
+                    path
+                }
+                None => {
+                    let path = sweep_dir.join("metrics.csv");
+                    sweep::write_metrics_csv(&runs, &path)?;
+                    path
+                }
             };
 
+            let failed = runs.iter().filter(|run| run.outcome.is_err()).count();
+            println!(
+                "{}/{} sweep points succeeded, metrics written to {:?}",
+                runs.len() - failed,
+                runs.len(),
+                path
+            );
+            if array_index.is_none() {
+                let generator =
+                    ReportGenerator::new(&sweep_dir, ReportStyle::from_config(&cfg.report)?)?;
+                let heatmaps = sweep::write_metric_heatmaps(&runs, &generator)?;
+                if !heatmaps.is_empty() {
+                    println!(
+                        "{} metric heatmaps written to {:?}",
+                        heatmaps.len(),
+                        sweep_dir
+                    );
+                }
+            }
+            match sweep.objectives() {
+                [] => {}
+                [objective] => {
+                    if let Some(best) = objective.best(&runs)? {
+                        println!(
+                            "Best {} = {} at {}",
+                            objective.metric,
+                            objective.value(best).unwrap_or(f64::NAN),
+                            best.point.label()
+                        );
+                    }
+                }
+                objectives => {
+                    let front = sweep::pareto_front(objectives, &runs)?;
+                    let pareto_dir = sweep_dir.join("pareto");
+                    std::fs::create_dir_all(&pareto_dir)?;
+                    for run in &front {
+                        sweep
+                            .config_for(&cfg, &run.point)?
+                            .save(&pareto_dir.join(format!("point_{:04}.toml", run.index)))?;
+                    }
+                    if !front.is_empty() {
+                        sweep::write_pareto_csv(&front, &sweep_dir.join("pareto.csv"))?;
+                    }
+                    println!(
+                        "{} points on the Pareto front, written to {:?}",
+                        front.len(),
+                        sweep_dir.join("pareto.csv")
+                    );
+                }
+            }
+        }
+
+        Commands::Merge { inputs, output } => {
+            let mut files = Vec::new();
+            for input in &inputs {
+                if input.is_dir() {
+                    let mut tasks: Vec<PathBuf> = std::fs::read_dir(input)?
+                        .filter_map(|entry| entry.ok().map(|e| e.path()))
+                        .filter(|path| {
+                            path.file_name()
+                                .and_then(|name| name.to_str())
+                                .is_some_and(|name| {
+                                    name.starts_with("metrics_task_") && name.ends_with(".csv")
+                                })
+                        })
+                        .collect();
+                    tasks.sort();
+                    files.extend(tasks);
+                } else {
+                    files.push(input.clone());
+                }
+            }
+            let output = output.unwrap_or_else(|| match &inputs[..] {
+                [dir] if dir.is_dir() => dir.join("metrics.csv"),
+                _ => PathBuf::from("metrics.csv"),
+            });
+
+            let merged = MergedSweep::load(&files)?;
+            merged.write(&output)?;
+            let stem = output
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("metrics");
+            let stats_path = output.with_file_name(format!("{}_stats.csv", stem));
+            merged.write_statistics(&stats_path)?;
+
+            if merged.missing() > 0 {
+                eprintln!("✗ {} sweep points are missing", merged.missing());
+            }
+            println!(
+                "✓ Merged {} sweep points ({} failed, config {}) from {} files into {}",
+                merged.len(),
+                merged.failed(),
+                merged.config_hash,
+                files.len(),
+                output.display()
+            );
+            println!("  Aggregate statistics written to {}", stats_path.display());
+        }
+
+        Commands::Gui { config, session } => {
+            tracing::info!("Launching GUI");
+            let mut app = App::new();
+            if let Some(config_path) = config {
+                app.load_config(&config_path)?;
+            }
+
             let native_options = eframe::NativeOptions::default();
             eframe::run_native(
                 "ChronoPhoton",
                 native_options,
-                Box::new(|_cc| Ok(Box::new(app))),
+                Box::new(move |cc| {
+                    let mut app = app.restore(cc.storage);
+                    if let Some(session_path) = session {
+                        app.load_session(&session_path)?;
+                    }
+                    Ok(Box::new(app))
+                }),
             )
             .map_err(|e| anyhow::anyhow!("GUI error: {}", e))?;
         }
 
+        Commands::DiffRun {
+            a,
+            b,
+            observables,
+            output,
+        } => {
+            let mut cfg_a = Config::from_file(&a)?;
+            let mut cfg_b = Config::from_file(&b)?;
+            performance::apply(&mut cfg_a).await?;
+            performance::check_memory(&cfg_b)?;
+
+            let grid_differs = cfg_a.simulation.duration != cfg_b.simulation.duration
+                || cfg_a.simulation.timestep != cfg_b.simulation.timestep
+                || cfg_a.observables.save_interval != cfg_b.observables.save_interval
+                || cfg_a.observables.time_grid != cfg_b.observables.time_grid;
+            if grid_differs {
+                tracing::warn!(
+                    "{:?} uses a different time grid, running it on the grid of {:?}",
+                    b,
+                    a
+                );
+                cfg_b.simulation.duration = cfg_a.simulation.duration;
+                cfg_b.simulation.timestep = cfg_a.simulation.timestep;
+                cfg_b.observables.save_interval = cfg_a.observables.save_interval;
+                cfg_b.observables.time_grid = cfg_a.observables.time_grid.clone();
+            }
+
+            let mut runs = Vec::with_capacity(2);
+            for cfg in [&cfg_a, &cfg_b] {
+                tracing::info!("Running '{}'", cfg.simulation.name);
+                runs.push(
+                    SimulationBuilder::from_config(cfg)?
+                        .with_record_states(true)
+                        .run()?,
+                );
+            }
+            let diff = RunDifference::compute(&runs[0], &runs[1], &observables)?;
+
+            std::fs::create_dir_all(&output)?;
+            let path = output.join("diff.csv");
+            diff.write_csv(&path)?;
+            for (name, _) in &diff.differences {
+                println!(
+                    "{}: max |b - a| = {:.6e}",
+                    name,
+                    diff.max_abs_difference(name).unwrap_or(0.0)
+                );
+            }
+            if let Some(fidelity) = diff.min_fidelity() {
+                println!("Minimum state fidelity: {:.6}", fidelity);
+            }
+            println!("Differences written to {:?}", path);
+        }
+
         Commands::Validate { config } => {
             tracing::info!("Validating configuration {:?}", config);
             match Config::from_file(&config) {
                 Ok(cfg) => {
                     cfg.validate()?;
+                    registry.validate_config_file(&config)?;
                     println!("✓ Configuration is valid");
                 }
                 Err(e) => {
-                    eprintln!("✗ Configuration error: {}", e);
+                    for diagnostic in e.diagnostics().iter() {
+                        eprintln!("✗ {}", diagnostic);
+                    }
                     std::process::exit(1);
                 }
             }
         }
 
+        Commands::Bands {
+            config,
+            output,
+            k_points,
+            edge_depth,
+        } => {
+            let cfg = Config::from_file(&config)?;
+            let Some((lattice, omega_c)) = cfg.system.build_lattice()? else {
+                anyhow::bail!(
+                    "Band structures need a lattice or coupled_cavities system, got \"{}\"",
+                    cfg.system.hamiltonian
+                );
+            };
+            let cell = UnitCell::detect(&lattice)?;
+            let bands = BandStructure::compute(&cell, k_points)?;
+
+            let bands_dir = output.join(&cfg.simulation.name);
+            std::fs::create_dir_all(&bands_dir)?;
+            bands.write_csv(&bands_dir.join("bands.csv"))?;
+            let edges = chain_edges(lattice.num_sites(), edge_depth);
+            let modes = edge_modes(&lattice, &edges, 0.5)?;
+            write_edge_modes_csv(&modes, &bands_dir.join("edge_modes.csv"))?;
+
+            println!(
+                "{} bands from a {}-site unit cell, energies relative to omega_c = {}",
+                bands.num_bands(),
+                cell.sites(),
+                omega_c
+            );
+            if cell.is_chiral() {
+                match winding_number(&cell, k_points) {
+                    Ok(winding) => println!("Winding number: {}", winding),
+                    Err(e) => println!("Winding number: {}", e),
+                }
+            }
+            println!(
+                "{} edge modes, results written to {:?}",
+                modes.len(),
+                bands_dir
+            );
+        }
+
+        Commands::Bench {
+            dims,
+            solvers,
+            samples,
+            json,
+            baseline,
+            tolerance,
+        } => {
+            let dims = dims.unwrap_or_else(|| bench::DIMS.to_vec());
+            let solvers = match solvers {
+                Some(names) => names
+                    .iter()
+                    .map(|name| bench::Solver::parse(name))
+                    .collect::<Result<Vec<_>, _>>()?,
+                None => bench::Solver::ALL.to_vec(),
+            };
+            let report = BenchReport::run(&bench::cases(&dims, &solvers), samples)?;
+
+            if json {
+                println!("{}", report.to_json()?);
+            } else {
+                println!(
+                    "{:<10} {:>6} {:>8} {:>12} {:>12} {:>14}",
+                    "solver", "dim", "steps", "mean (s)", "min (s)", "steps/s"
+                );
+                for m in &report.measurements {
+                    println!(
+                        "{:<10} {:>6} {:>8} {:>12.4e} {:>12.4e} {:>14.1}",
+                        m.solver, m.dim, m.steps, m.mean_seconds, m.min_seconds, m.steps_per_second
+                    );
+                }
+            }
+
+            if let Some(path) = baseline {
+                let regressions = report.regressions(&BenchReport::load(&path)?, tolerance);
+                for r in &regressions {
+                    eprintln!(
+                        "Regression: {} dim {} took {:.4e} s (baseline {:.4e} s, {:.2}x)",
+                        r.solver, r.dim, r.current_seconds, r.baseline_seconds, r.slowdown
+                    );
+                }
+                if !regressions.is_empty() {
+                    anyhow::bail!(
+                        "{} benchmark(s) regressed by more than {:.0}%",
+                        regressions.len(),
+                        tolerance * 100.0
+                    );
+                }
+            }
+        }
+
+        Commands::Archive { run_dir, output } => {
+            let output = output.unwrap_or_else(|| run_dir.with_extension("zip"));
+            tracing::info!("Archiving {:?} to {:?}", run_dir, output);
+
+            let manifest = Archive::create(&run_dir, &output)?;
+            println!(
+                "✓ Archived {} files from {} to {}",
+                manifest.files.len(),
+                manifest.archive.run,
+                output.display()
+            );
+        }
+
+        Commands::Animate {
+            states,
+            kind,
+            output,
+            fps,
+        } => {
+            let states = if states.is_dir() {
+                states.join("states.bin")
+            } else {
+                states
+            };
+            let kind = AnimationKind::parse(&kind)?;
+            let output =
+                output.unwrap_or_else(|| states.with_file_name(kind.name()).with_extension("gif"));
+
+            tracing::info!("Loading state snapshots from {:?}", states);
+            let snapshots = SnapshotFile::load(&states)?;
+            let options = AnimationOptions {
+                fps,
+                ..AnimationOptions::new(kind)
+            };
+            let frames = Animation::new(&snapshots, options)?.render(&output)?;
+            println!("✓ Wrote {} frames to {}", frames, output.display());
+        }
+
+        Commands::ExitCodes { json } => {
+            let codes = exit::exit_codes();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&codes)?);
+            } else {
+                for info in codes {
+                    println!(
+                        "{:>4}  {:<20} requeue={:<5}  {}",
+                        info.code, info.name, info.requeue, info.description
+                    );
+                }
+            }
+        }
+
+        Commands::ExportOperator {
+            config,
+            what,
+            time,
+            format,
+            output,
+        } => {
+            let kind = OperatorKind::parse(&what)?;
+            let format = MatrixFormat::parse(&format)?;
+            let cfg = Config::from_file(&config)?;
+            cfg.validate()?;
+
+            let matrix = operator_export::build_operator(&cfg, kind, time)?;
+            let path = output.unwrap_or_else(|| {
+                PathBuf::from(format!("{}.{}", kind.name(), format.extension()))
+            });
+            operator_export::write_matrix(&matrix, format, &path)?;
+            println!(
+                "✓ Wrote {}x{} {} at t = {} to {:?}",
+                matrix.nrows(),
+                matrix.ncols(),
+                kind.name(),
+                time,
+                path
+            );
+        }
+
+        Commands::CheckOperators {
+            hamiltonian,
+            collapse,
+            liouvillian,
+            tolerance,
+        } => {
+            let mut import = OperatorImport::new().with_tolerance(tolerance);
+            if let Some(path) = &hamiltonian {
+                import = import.with_hamiltonian_file(path)?;
+            }
+            for spec in &collapse {
+                let (path, rate) = operator_import::parse_collapse_spec(spec)?;
+                import = import.with_collapse_file(&path, rate)?;
+            }
+            if let Some(path) = &liouvillian {
+                import = import.with_liouvillian_file(path)?;
+            }
+
+            let problems = import.check();
+            for diagnostic in problems.iter() {
+                eprintln!("✗ {}", diagnostic);
+            }
+            if problems.has_errors() {
+                std::process::exit(1);
+            }
+            println!("✓ Operators are consistent");
+        }
+
+        Commands::VerifyIntegrators {
+            timesteps,
+            precision,
+        } => {
+            let precision = Precision::parse(&precision)?;
+            let mut test = ConvergenceTest::new();
+            if !timesteps.is_empty() {
+                test = test.with_timesteps(timesteps)?;
+            } else if precision == Precision::Single {
+                test = test.with_timesteps(convergence::SINGLE_PRECISION_TIMESTEPS.to_vec())?;
+            }
+            let reports = test.run_all(precision)?;
+            let mut failed = false;
+            for report in &reports {
+                let errors: Vec<String> = report
+                    .timesteps
+                    .iter()
+                    .zip(&report.errors)
+                    .map(|(dt, error)| format!("dt={}: {:.2e}", dt, error))
+                    .collect();
+                println!("  {}", errors.join("  "));
+                if report.passed() {
+                    println!("✓ {}", report.describe());
+                } else {
+                    eprintln!("✗ {}", report.describe());
+                    failed = true;
+                }
+            }
+            for (alias, original) in convergence::find_aliases(&reports) {
+                eprintln!(
+                    "✗ {:?} gives exactly the same errors as {:?}, it is not a separate scheme",
+                    alias, original
+                );
+                failed = true;
+            }
+            if failed {
+                std::process::exit(1);
+            }
+        }
+
+        Commands::RandomFloquet {
+            ensemble,
+            dim,
+            realizations,
+            drive,
+            frequency,
+            steps,
+            bandwidth,
+            j1,
+            j2,
+            disorder,
+            seed,
+            batch_size,
+            gpu,
+            output,
+        } => {
+            let ensemble = match ensemble.as_str() {
+                "goe" => RandomEnsemble::Goe,
+                "gue" => RandomEnsemble::Gue,
+                "banded" => RandomEnsemble::Banded { bandwidth },
+                "ssh" => RandomEnsemble::Ssh { j1, j2, disorder },
+                other => anyhow::bail!(
+                    "Unknown ensemble '{}', expected goe, gue, banded or ssh",
+                    other
+                ),
+            };
+            let mut study = RandomFloquetStudy::new(ensemble, dim, realizations)
+                .with_drive(drive, frequency)
+                .with_steps(steps)
+                .with_batch(batch_size, gpu);
+            if let Some(seed) = seed {
+                study = study.with_seed(seed);
+            }
+
+            let statistics = study.run()?;
+            statistics.write_csv(&output)?;
+            println!(
+                "{} ensemble, {} realizations of dimension {}: <r> = {:.4} ± {:.4} (closest to {})",
+                ensemble.name(),
+                realizations,
+                dim,
+                statistics.mean(),
+                statistics.standard_error(),
+                statistics.closest_class()
+            );
+            println!("✓ Per-realization r-ratios written to {:?}", output);
+        }
+
+        Commands::Heating {
+            config,
+            frequencies,
+            parameter,
+            periods,
+            steps,
+            tolerance,
+            output,
+        } => {
+            let cfg = Config::from_file(&config)?;
+            cfg.validate()?;
+            let analysis = HeatingAnalysis::new(cfg.initial_state.build(cfg.system.hilbert_dim)?)
+                .with_options(HeatingOptions {
+                    periods,
+                    steps_per_period: steps,
+                    plateau_tolerance: tolerance,
+                    ..HeatingOptions::default()
+                });
+            let sweep = ParameterSweep::new();
+            let scan = analysis.scan(&frequencies, |omega| {
+                let point = SweepPoint {
+                    values: vec![(parameter.clone(), omega)],
+                };
+                operator_export::system_hamiltonian(&sweep.config_for(&cfg, &point)?)
+            })?;
+            scan.write_csv(&output)?;
+
+            println!(
+                "{:>12} {:>14} {:>14} {:>14}",
+                "frequency", "rate", "plateau end", "final energy"
+            );
+            for curve in &scan.curves {
+                println!(
+                    "{:>12.4} {:>14.4e} {:>14} {:>14.6}",
+                    curve.frequency,
+                    curve.rate.value,
+                    curve
+                        .heating_time()
+                        .map_or("-".to_string(), |t| format!("{:.3}", t)),
+                    curve.energy.last().copied().unwrap_or(f64::NAN)
+                );
+            }
+            if let Some(scale) = scan.exponential_scale() {
+                println!("rate ~ exp(-ω/ω₀) with ω₀ = {}", scale);
+            }
+            if let Some(exponent) = scan.power_law_exponent() {
+                println!("rate ~ ω^p with p = {}", exponent);
+            }
+            println!("✓ Heating rates written to {:?}", output);
+        }
+
+        Commands::CompareSolvers {
+            config,
+            tolerance,
+            output,
+        } => {
+            tracing::info!("Loading configuration from {:?}", config);
+            let mut cfg = Config::from_file(&config)?;
+            performance::apply(&mut cfg).await?;
+
+            let hierarchy = SolverHierarchy::run(&cfg, tolerance)?;
+            println!(
+                "{:<14} {:>10} {:<16} {:>14} {:>14}",
+                "method", "time (s)", "observable", "max deviation", "diverges at"
+            );
+            for run in &hierarchy.runs {
+                for d in hierarchy
+                    .divergences
+                    .iter()
+                    .filter(|d| d.method == run.method)
+                {
+                    println!(
+                        "{:<14} {:>10.3} {:<16} {:>14.3e} {:>14}",
+                        run.method.name(),
+                        run.wall_time(),
+                        d.observable,
+                        d.max_deviation,
+                        d.diverges_at
+                            .map_or("-".to_string(), |t| format!("t = {}", t))
+                    );
+                }
+            }
+            for (method, reason) in &hierarchy.skipped {
+                println!("{:<14} skipped: {}", method.name(), reason);
+            }
+
+            std::fs::create_dir_all(&output)?;
+            let path = output.join("solver_comparison.csv");
+            hierarchy.write_csv(&path)?;
+            println!(
+                "✓ Reference: {}; cheapest method within tolerance {}: {}",
+                hierarchy.reference.name(),
+                tolerance,
+                hierarchy.recommendation().name()
+            );
+            println!("Observables written to {:?}", path);
+        }
+
+        Commands::Transmission { config, output } => {
+            tracing::info!("Loading configuration from {:?}", config);
+            let mut cfg = Config::from_file(&config)?;
+            performance::apply(&mut cfg).await?;
+
+            let spectrum = TransmissionSpectrum::from_config(&cfg)?;
+            spectrum.write_csv(&output)?;
+            println!("Transmission spectrum written to {:?}", output);
+            let modes = spectrum.normal_modes()?;
+            let coupling = cfg.system.parameters.get("g").unwrap_or_default();
+            println!(
+                "✓ Normal modes at detunings {:.6} and {:.6}, splitting {:.6} (2g√N = {:.6})",
+                modes.lower,
+                modes.upper,
+                modes.splitting(),
+                2.0 * coupling * (cfg.cumulant.emitters as f64).sqrt()
+            );
+        }
+
+        Commands::Ports { config, output } => {
+            tracing::info!("Loading configuration from {:?}", config);
+            let mut cfg = Config::from_file(&config)?;
+            performance::apply(&mut cfg).await?;
+
+            let analysis = OutputPorts::from_config(&cfg)?.analyze()?;
+            println!("{:<16} {:>12}", "port", "emitted");
+            for (k, name) in analysis.ports.iter().enumerate() {
+                println!("{:<16} {:>12.6}", name, analysis.emission(k));
+            }
+            if analysis.residual > 1e-3 {
+                tracing::warn!(
+                    "{:.3e} of the excitation is still in the lattice at the end of the run; \
+                     increase simulation.duration for converged correlations",
+                    analysis.residual
+                );
+            }
+
+            std::fs::create_dir_all(&output)?;
+            analysis.write_spectra_csv(&output.join("port_spectra.csv"))?;
+            analysis.write_correlations_csv(&output.join("port_correlations.csv"))?;
+            println!("✓ Port spectra and correlations written to {:?}", output);
+        }
+
+        Commands::Reduce {
+            config,
+            modes,
+            output,
+        } => {
+            tracing::info!("Loading configuration from {:?}", config);
+            let mut cfg = Config::from_file(&config)?;
+            performance::apply(&mut cfg).await?;
+
+            let results = SimulationBuilder::from_config(&cfg)?
+                .with_record_states(true)
+                .run()?;
+            let model = ReducedModel::fit(&results, modes)?;
+            for (k, mode) in model.modes.iter().enumerate() {
+                println!(
+                    "  mode {}: energy {:.6}, decay {:.3e}, weight {:.4}",
+                    k,
+                    mode.energy,
+                    mode.decay_rate,
+                    mode.amplitude.norm()
+                );
+            }
+            model.to_config(&cfg)?.save(&output)?;
+            println!(
+                "✓ Wrote {}-mode surrogate to {:?} (max relative fit error {:.3e})",
+                model.modes.len(),
+                output,
+                model.fit_error
+            );
+        }
+
+        Commands::Example { command } => match command {
+            ExampleCommand::List => {
+                for preset in presets::presets() {
+                    println!("{:<24} {}", preset.name, preset.description);
+                }
+            }
+            ExampleCommand::Show { name } => {
+                print!("{}", presets::find(&name)?.source);
+            }
+            ExampleCommand::Run { name, gpu, output } => {
+                let preset = presets::find(&name)?;
+                tracing::info!("Running example '{}': {}", preset.name, preset.description);
+                run_config(preset.config()?, gpu, output, None).await?;
+            }
+        },
+
         Commands::Template {
             output,
             template_type,
@@ -122,3 +1194,80 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+async fn run_config(
+    mut cfg: Config,
+    gpu: Option<bool>,
+    output: Option<PathBuf>,
+    resume: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    performance::apply(&mut cfg).await?;
+    if let Some(gpu_enabled) = gpu {
+        cfg.gpu.enabled = gpu_enabled;
+    }
+
+    // `-o results.csv` names a results file, anything else is the run root.
+    let results_file = output
+        .as_ref()
+        .filter(|path| SimulationResults::is_results_path(path))
+        .cloned();
+    if let Some(root) = output.filter(|_| results_file.is_none()) {
+        cfg.output.root = root;
+    }
+
+    let run_dir = RunDirectory::create(
+        &cfg.output.root,
+        &cfg,
+        CollisionPolicy::parse(&cfg.output.on_collision)?,
+    )?;
+    logger::log_to_file(&run_dir.log_path())?;
+    run_dir.write_config(&cfg)?;
+    tracing::info!("Writing run outputs to {:?}", run_dir.path());
+
+    let control = Arc::new(JobControl::new());
+    let checkpoint = cfg
+        .preemption
+        .enabled
+        .then(|| run_dir.latest_checkpoint_path());
+    let resume = resume.map(|path| Checkpoint::load(&path)).transpose()?;
+    let job_cfg = cfg.clone();
+    let mut results = run_preemptible(&cfg.preemption, control.clone(), move || {
+        pipeline::simulate(&job_cfg, control, checkpoint, resume)
+    })
+    .await?;
+
+    pipeline::save_results(&mut results, &cfg, &run_dir)?;
+    if let Some(path) = &results_file {
+        results.save(path)?;
+        println!("Saved results to {}", path.display());
+    }
+
+    let tomography = pipeline::write_outputs(&mut results, &cfg, &run_dir)?;
+    results.print_summary();
+    if let Some(outcome) = tomography {
+        let reconstruction = &outcome.reconstruction;
+        println!(
+            "  Tomography ({}): fidelity {:.6}, purity {:.6}, {} iterations{}",
+            cfg.tomography.measurements,
+            outcome.fidelity,
+            reconstruction.rho.purity(),
+            reconstruction.iterations,
+            if reconstruction.converged {
+                ""
+            } else {
+                " (not converged)"
+            }
+        );
+    }
+    for fit in fit_results(&cfg, &results) {
+        println!(
+            "  {} ({}): {}",
+            fit.observable,
+            fit.model.name(),
+            fit.describe()
+        );
+    }
+
+    tracing::info!("Simulation complete");
+    Ok(())
+}
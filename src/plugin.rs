@@ -0,0 +1,269 @@
+use crate::utils::{Error, Result};
+use std::path::Path;
+
+pub const PLUGIN_API_VERSION: u32 = 1;
+pub const PLUGIN_PATH_VAR: &str = "CHRONOPHOTON_PLUGIN_PATH";
+
+pub trait Plugin: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn commands(&self) -> Vec<clap::Command> {
+        Vec::new()
+    }
+
+    fn run_command(&self, name: &str, _matches: &clap::ArgMatches) -> Result<()> {
+        Err(Error::NotImplemented(format!(
+            "Plugin '{}' command '{}'",
+            self.name(),
+            name
+        )))
+    }
+
+    fn config_sections(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn validate_section(&self, _section: &str, _value: &toml::Value) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[macro_export]
+macro_rules! declare_plugin {
+    ($constructor:expr) => {
+        #[no_mangle]
+        pub extern "C" fn chronophoton_plugin_api_version() -> u32 {
+            $crate::plugin::PLUGIN_API_VERSION
+        }
+
+        #[no_mangle]
+        pub extern "C" fn chronophoton_plugin_create() -> *mut ::std::ffi::c_void {
+            let plugin: Box<dyn $crate::plugin::Plugin> = Box::new($constructor);
+            Box::into_raw(Box::new(plugin)) as *mut ::std::ffi::c_void
+        }
+    };
+}
+
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: Vec<Box<dyn Plugin>>,
+    #[cfg(feature = "plugins")]
+    libraries: Vec<libloading::Library>,
+}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, plugin: Box<dyn Plugin>) -> Result<()> {
+        if let Some(existing) = self.plugins.iter().find(|p| p.name() == plugin.name()) {
+            return Err(Error::Config(format!(
+                "Plugin '{}' is registered twice",
+                existing.name()
+            )));
+        }
+        for section in plugin.config_sections() {
+            if let Some(owner) = self.section_owner(&section) {
+                return Err(Error::Config(format!(
+                    "Config section [{}] of plugin '{}' is already claimed by '{}'",
+                    section,
+                    plugin.name(),
+                    owner.name()
+                )));
+            }
+        }
+        tracing::debug!("Registered plugin '{}'", plugin.name());
+        self.plugins.push(plugin);
+        Ok(())
+    }
+
+    pub fn plugins(&self) -> impl Iterator<Item = &dyn Plugin> {
+        self.plugins.iter().map(|p| p.as_ref())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+
+    pub fn augment(&self, mut command: clap::Command) -> Result<clap::Command> {
+        for plugin in &self.plugins {
+            for subcommand in plugin.commands() {
+                let name = subcommand.get_name().to_string();
+                if command.find_subcommand(&name).is_some() {
+                    return Err(Error::Config(format!(
+                        "Plugin '{}' command '{}' clashes with an existing command",
+                        plugin.name(),
+                        name
+                    )));
+                }
+                command = command.subcommand(subcommand);
+            }
+        }
+        Ok(command)
+    }
+
+    pub fn command_owner(&self, name: &str) -> Option<&dyn Plugin> {
+        self.plugins()
+            .find(|p| p.commands().iter().any(|c| c.get_name() == name))
+    }
+
+    pub fn dispatch(&self, matches: &clap::ArgMatches) -> Option<Result<()>> {
+        let (name, sub_matches) = matches.subcommand()?;
+        let plugin = self.command_owner(name)?;
+        let _span = tracing::info_span!("plugin", name = plugin.name()).entered();
+        Some(plugin.run_command(name, sub_matches))
+    }
+
+    pub fn section_owner(&self, section: &str) -> Option<&dyn Plugin> {
+        self.plugins()
+            .find(|p| p.config_sections().iter().any(|s| s == section))
+    }
+
+    pub fn validate_sections(&self, sections: &toml::Table) -> Result<()> {
+        for (section, value) in sections {
+            if let Some(plugin) = self.section_owner(section) {
+                plugin.validate_section(section, value).map_err(|e| {
+                    Error::Config(format!("[{}] (plugin '{}'): {}", section, plugin.name(), e))
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn validate_config_file(&self, path: &Path) -> Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        self.validate_sections(&read_sections(path)?)
+    }
+
+    #[cfg(feature = "plugins")]
+    pub fn load(&mut self, path: &Path) -> Result<()> {
+        let failed = |message: String| Error::Config(format!("{}: {}", path.display(), message));
+        // SAFETY: plugins are trusted native code built against the same
+        // chronophoton version and compiler, checked through the API version.
+        unsafe {
+            let library = libloading::Library::new(path).map_err(|e| failed(e.to_string()))?;
+            let version = library
+                .get::<extern "C" fn() -> u32>(b"chronophoton_plugin_api_version")
+                .map_err(|e| failed(e.to_string()))?;
+            if version() != PLUGIN_API_VERSION {
+                return Err(failed(format!(
+                    "plugin API version {} does not match {}",
+                    version(),
+                    PLUGIN_API_VERSION
+                )));
+            }
+            let create = library
+                .get::<extern "C" fn() -> *mut std::ffi::c_void>(b"chronophoton_plugin_create")
+                .map_err(|e| failed(e.to_string()))?;
+            let plugin = *Box::from_raw(create() as *mut Box<dyn Plugin>);
+            self.register(plugin)?;
+            self.libraries.push(library);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "plugins")]
+    pub fn discover(&mut self) -> Result<usize> {
+        let Some(paths) = std::env::var_os(PLUGIN_PATH_VAR) else {
+            return Ok(0);
+        };
+        let before = self.plugins.len();
+        for dir in std::env::split_paths(&paths) {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                tracing::warn!("Skipping missing plugin directory {:?}", dir);
+                continue;
+            };
+            let mut libraries: Vec<_> = entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| {
+                    p.extension().and_then(|e| e.to_str()) == Some(std::env::consts::DLL_EXTENSION)
+                })
+                .collect();
+            libraries.sort();
+            for library in libraries {
+                self.load(&library)?;
+            }
+        }
+        Ok(self.plugins.len() - before)
+    }
+}
+
+pub fn read_sections(path: &Path) -> Result<toml::Table> {
+    let contents = std::fs::read_to_string(path)?;
+    if path.extension().and_then(|s| s.to_str()) == Some("toml") {
+        toml::from_str(&contents).map_err(|e| Error::Config(format!("TOML parse error: {}", e)))
+    } else {
+        serde_yaml::from_str(&contents)
+            .map_err(|e| Error::Config(format!("YAML parse error: {}", e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct DevicePlugin;
+
+    impl Plugin for DevicePlugin {
+        fn name(&self) -> &str {
+            "device"
+        }
+
+        fn commands(&self) -> Vec<clap::Command> {
+            vec![clap::Command::new("device-info").arg(clap::Arg::new("serial").required(true))]
+        }
+
+        fn run_command(&self, _name: &str, matches: &clap::ArgMatches) -> Result<()> {
+            match matches.get_one::<String>("serial").map(String::as_str) {
+                Some("ok") => Ok(()),
+                _ => Err(Error::InvalidParameter("unknown device".to_string())),
+            }
+        }
+
+        fn config_sections(&self) -> Vec<String> {
+            vec!["device".to_string()]
+        }
+
+        fn validate_section(&self, _section: &str, value: &toml::Value) -> Result<()> {
+            match value.get("qubits").and_then(toml::Value::as_integer) {
+                Some(n) if n > 0 => Ok(()),
+                _ => Err(Error::InvalidParameter(
+                    "qubits must be positive".to_string(),
+                )),
+            }
+        }
+    }
+
+    #[test]
+    fn test_plugin_commands_and_sections() {
+        let mut registry = PluginRegistry::new();
+        registry.register(Box::new(DevicePlugin)).unwrap();
+        assert!(registry.register(Box::new(DevicePlugin)).is_err());
+
+        let command = registry
+            .augment(clap::Command::new("chronophoton").subcommand(clap::Command::new("run")))
+            .unwrap();
+        let matches = command
+            .clone()
+            .try_get_matches_from(["chronophoton", "device-info", "ok"])
+            .unwrap();
+        assert!(registry.dispatch(&matches).unwrap().is_ok());
+        let builtin = command
+            .try_get_matches_from(["chronophoton", "run"])
+            .unwrap();
+        assert!(registry.dispatch(&builtin).is_none());
+
+        let clashing =
+            clap::Command::new("chronophoton").subcommand(clap::Command::new("device-info"));
+        assert!(registry.augment(clashing).is_err());
+
+        let good: toml::Table = toml::from_str("[device]\nqubits = 3\n[other]\nx = 1\n").unwrap();
+        assert!(registry.validate_sections(&good).is_ok());
+        let bad: toml::Table = toml::from_str("[device]\nqubits = 0\n").unwrap();
+        let err = registry.validate_sections(&bad).unwrap_err().to_string();
+        assert!(err.contains("qubits must be positive"), "{}", err);
+    }
+}
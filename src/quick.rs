@@ -0,0 +1,184 @@
+use crate::core::integrator::{self, IntegratorType};
+use crate::core::lindblad::{LindbladOperator, LindbladSolver};
+use crate::core::{DensityMatrix, Hamiltonian, Observable, QuantumState};
+use crate::simulation::guard::{self, StepContext};
+use crate::utils::{Error, Result};
+use num_complex::Complex64;
+
+#[derive(Debug, Clone)]
+pub struct Trajectory<S> {
+    pub times: Vec<f64>,
+    pub states: Vec<S>,
+}
+
+impl<S> Trajectory<S> {
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn final_state(&self) -> &S {
+        self.states
+            .last()
+            .expect("trajectory always holds the initial state")
+    }
+}
+
+impl Trajectory<QuantumState> {
+    pub fn expectation(&self, observable: &dyn Observable) -> Vec<Complex64> {
+        self.states
+            .iter()
+            .map(|state| observable.expectation_pure(state))
+            .collect()
+    }
+}
+
+impl Trajectory<DensityMatrix> {
+    pub fn expectation(&self, observable: &dyn Observable) -> Vec<Complex64> {
+        self.states
+            .iter()
+            .map(|rho| observable.expectation_mixed(rho))
+            .collect()
+    }
+}
+
+pub fn evolve(
+    hamiltonian: impl Hamiltonian,
+    psi0: QuantumState,
+    tspan: (f64, f64),
+    dt: f64,
+) -> Result<Trajectory<QuantumState>> {
+    check_inputs(hamiltonian.dim(), psi0.dim(), tspan, dt)?;
+
+    let integrator = integrator::create_integrator(IntegratorType::RK4);
+    let mut state = psi0;
+    step_through(tspan, dt, &mut state, |state, t, h| {
+        integrator.step(&hamiltonian, state, t, h)
+    })
+}
+
+pub fn lindblad_evolve(
+    hamiltonian: impl Hamiltonian + 'static,
+    rho0: DensityMatrix,
+    collapse_ops: Vec<LindbladOperator>,
+    tspan: (f64, f64),
+    dt: f64,
+) -> Result<Trajectory<DensityMatrix>> {
+    check_inputs(hamiltonian.dim(), rho0.dim(), tspan, dt)?;
+
+    let solver = LindbladSolver::new(Box::new(hamiltonian), collapse_ops)?;
+    let mut rho = rho0;
+    step_through(tspan, dt, &mut rho, |rho, t, h| solver.step(rho, t, h))
+}
+
+fn check_inputs(dim: usize, state_dim: usize, tspan: (f64, f64), dt: f64) -> Result<()> {
+    if state_dim != dim {
+        return Err(Error::DimensionMismatch {
+            expected: dim,
+            actual: state_dim,
+        });
+    }
+    if tspan.1 <= tspan.0 || !tspan.0.is_finite() || !tspan.1.is_finite() {
+        return Err(Error::InvalidParameter(format!(
+            "Time span must be increasing, got ({}, {})",
+            tspan.0, tspan.1
+        )));
+    }
+    if dt <= 0.0 || !dt.is_finite() {
+        return Err(Error::InvalidParameter(format!(
+            "Timestep must be positive, got {}",
+            dt
+        )));
+    }
+    Ok(())
+}
+
+fn step_through<S: Clone + Checked>(
+    (t0, t1): (f64, f64),
+    dt: f64,
+    state: &mut S,
+    mut step: impl FnMut(&mut S, f64, f64) -> Result<()>,
+) -> Result<Trajectory<S>> {
+    let tolerance = 1e-9 * dt;
+    let mut times = vec![t0];
+    let mut states = vec![state.clone()];
+    let mut t = t0;
+
+    while t1 - t > tolerance {
+        let h = dt.min(t1 - t);
+        step(state, t, h)?;
+        t += h;
+        state.check(&StepContext {
+            step: times.len(),
+            time: t,
+            timestep: dt,
+        })?;
+
+        times.push(t);
+        states.push(state.clone());
+    }
+
+    Ok(Trajectory { times, states })
+}
+
+trait Checked {
+    fn check(&self, context: &StepContext) -> Result<()>;
+}
+
+impl Checked for QuantumState {
+    fn check(&self, context: &StepContext) -> Result<()> {
+        guard::check_state(self, context)
+    }
+}
+
+impl Checked for DensityMatrix {
+    fn check(&self, context: &StepContext) -> Result<()> {
+        guard::check_density_matrix(self, context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observables::PopulationOperator;
+    use crate::core::systems::DrivenTLS;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_evolve_rabi() {
+        let trajectory = evolve(
+            DrivenTLS::new(0.0, 0.0, 0.5),
+            QuantumState::ground_state(2),
+            (0.0, std::f64::consts::PI),
+            0.01,
+        )
+        .unwrap();
+
+        let p1 = trajectory.expectation(&PopulationOperator::new(2, 1).unwrap());
+        assert_eq!(trajectory.times.len(), trajectory.len());
+        assert_relative_eq!(*trajectory.times.last().unwrap(), std::f64::consts::PI);
+        assert_relative_eq!(p1.last().unwrap().re, 1.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_lindblad_decay() {
+        let mut excited = ndarray::Array2::zeros((2, 2));
+        excited[[1, 1]] = Complex64::new(1.0, 0.0);
+        let rho0 = DensityMatrix::new_unchecked(excited);
+
+        let trajectory = lindblad_evolve(
+            DrivenTLS::new(0.0, 0.0, 0.0),
+            rho0,
+            vec![LindbladOperator::annihilation(2, 1.0).unwrap()],
+            (0.0, 2.0),
+            0.01,
+        )
+        .unwrap();
+
+        let p1 = trajectory.expectation(&PopulationOperator::new(2, 1).unwrap());
+        assert_relative_eq!(p1.last().unwrap().re, (-2.0f64).exp(), epsilon = 1e-6);
+    }
+}
@@ -1,7 +1,20 @@
-use crate::core::{Hamiltonian, IntegratorType, Observable, QuantumState};
+use crate::core::lindblad::LindbladOperator;
+use crate::core::observable_expr;
+use crate::core::observables::MatrixObservable;
+use crate::core::operator_cache::OperatorCache;
+use crate::core::symmetry::{self, SectorDecomposition, SubspaceHamiltonian};
+use crate::core::{
+    Hamiltonian, IntegratorType, Observable, Precision, QuantumState, VectorObservable,
+};
+use crate::data::operator_export;
 use crate::data::Config;
-use crate::simulation::SimulationRunner;
-use crate::utils::{Error, Result};
+use crate::simulation::transport::{Transport, ARRIVAL_OBSERVABLE};
+use crate::simulation::{
+    SamplingOptions, SimulationRunner, SteadyStateOptions, StopCondition, TimeGrid,
+};
+use crate::utils::error::Severity;
+use crate::utils::performance::{self, Solver};
+use crate::utils::{Diagnostics, Error, ErrorCode, Result};
 
 pub struct SimulationBuilder {
     hamiltonian: Option<Box<dyn Hamiltonian>>,
@@ -9,8 +22,24 @@ pub struct SimulationBuilder {
     duration: Option<f64>,
     timestep: Option<f64>,
     integrator_type: IntegratorType,
+    precision: Precision,
     observables: Vec<(String, Box<dyn Observable>)>,
+    vector_observables: Vec<(String, Box<dyn VectorObservable>)>,
+    floquet_populations: Option<(String, usize)>,
+    prethermal_populations: Option<(String, usize)>,
+    symmetry_reduction: bool,
+    transport: Option<Transport>,
+    time_grid: TimeGrid,
+    max_retries: u32,
+    warmup_duration: f64,
+    record_states: bool,
+    stop_conditions: Vec<StopCondition>,
+    steady_state: SteadyStateOptions,
+    sampling: SamplingOptions,
     gpu_enabled: bool,
+    collapse_operators: Vec<LindbladOperator>,
+    trajectories: Option<(usize, Option<u64>)>,
+    profile_terms: bool,
 }
 
 impl SimulationBuilder {
@@ -21,8 +50,24 @@ impl SimulationBuilder {
             duration: None,
             timestep: None,
             integrator_type: IntegratorType::RK4,
+            precision: Precision::Double,
             observables: Vec::new(),
+            vector_observables: Vec::new(),
+            floquet_populations: None,
+            prethermal_populations: None,
+            symmetry_reduction: false,
+            transport: None,
+            time_grid: TimeGrid::default(),
+            max_retries: 0,
+            warmup_duration: 0.0,
+            record_states: false,
+            stop_conditions: Vec::new(),
+            steady_state: SteadyStateOptions::default(),
+            sampling: SamplingOptions::default(),
             gpu_enabled: false,
+            collapse_operators: Vec::new(),
+            trajectories: None,
+            profile_terms: false,
         }
     }
 
@@ -51,6 +96,11 @@ impl SimulationBuilder {
         self
     }
 
+    pub fn precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
     pub fn observable(
         mut self,
         name: impl Into<String>,
@@ -60,41 +110,498 @@ impl SimulationBuilder {
         self
     }
 
+    pub fn vector_observable(
+        mut self,
+        name: impl Into<String>,
+        observable: impl VectorObservable + 'static,
+    ) -> Self {
+        self.vector_observables
+            .push((name.into(), Box::new(observable)));
+        self
+    }
+
+    pub fn floquet_populations(mut self, name: impl Into<String>, num_steps: usize) -> Self {
+        self.floquet_populations = Some((name.into(), num_steps));
+        self
+    }
+
+    pub fn prethermal_populations(mut self, name: impl Into<String>, samples: usize) -> Self {
+        self.prethermal_populations = Some((name.into(), samples));
+        self
+    }
+
+    pub fn symmetry_reduction(mut self, enabled: bool) -> Self {
+        self.symmetry_reduction = enabled;
+        self
+    }
+
+    pub fn transport(mut self, transport: Transport) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    pub fn time_grid(mut self, time_grid: TimeGrid) -> Self {
+        self.time_grid = time_grid;
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn warmup_duration(mut self, warmup_duration: f64) -> Self {
+        self.warmup_duration = warmup_duration;
+        self
+    }
+
+    pub fn record_states(mut self, record_states: bool) -> Self {
+        self.record_states = record_states;
+        self
+    }
+
+    pub fn stop_when(mut self, condition: StopCondition) -> Self {
+        self.stop_conditions.push(condition);
+        self
+    }
+
+    pub fn steady_state_options(mut self, options: SteadyStateOptions) -> Self {
+        self.steady_state = options;
+        self
+    }
+
+    pub fn sampling(mut self, options: SamplingOptions) -> Self {
+        self.sampling = options;
+        self
+    }
+
     pub fn gpu(mut self, enabled: bool) -> Self {
         self.gpu_enabled = enabled;
         self
     }
 
+    pub fn collapse_operator(mut self, operator: LindbladOperator) -> Self {
+        self.collapse_operators.push(operator);
+        self
+    }
+
+    pub fn collapse_operators(mut self, operators: Vec<LindbladOperator>) -> Self {
+        self.collapse_operators.extend(operators);
+        self
+    }
+
+    pub fn trajectories(mut self, count: usize, seed: Option<u64>) -> Self {
+        self.trajectories = Some((count, seed));
+        self
+    }
+
+    // Times every Hamiltonian term while building and warns about a dominant one.
+    // Off by default, since the timing evaluates the Hamiltonian repeatedly.
+    pub fn profile_terms(mut self, enabled: bool) -> Self {
+        self.profile_terms = enabled;
+        self
+    }
+
     pub fn build(self) -> Result<SimulationRunner> {
-        let hamiltonian = self
+        let mut hamiltonian = self
             .hamiltonian
             .ok_or_else(|| Error::Config("Hamiltonian not specified".to_string()))?;
 
-        let initial_state = self
-            .initial_state
-            .ok_or_else(|| Error::Config("Initial state not specified".to_string()))?;
+        let mut observables = self.observables;
+        let mut initial_state = match (self.transport, self.initial_state) {
+            (Some(transport), state) => {
+                let dim = hamiltonian.dim();
+                hamiltonian = transport.hamiltonian(hamiltonian)?;
+                observables.push((
+                    ARRIVAL_OBSERVABLE.to_string(),
+                    Box::new(transport.arrival(dim)?),
+                ));
+                match state {
+                    Some(state) => state,
+                    None => transport.initial_state(dim)?,
+                }
+            }
+            (None, state) => {
+                state.ok_or_else(|| Error::Config("Initial state not specified".to_string()))?
+            }
+        };
 
         let duration = self
             .duration
             .ok_or_else(|| Error::Config("Duration not specified".to_string()))?;
 
-        let timestep = self
-            .timestep
-            .ok_or_else(|| Error::Config("Timestep not specified".to_string()))?;
+        let timestep = match self.timestep {
+            Some(timestep) => timestep,
+            None => {
+                let timestep = suggest_timestep(hamiltonian.as_ref())
+                    .unwrap_or(duration)
+                    .min(duration);
+                tracing::info!("No timestep given, using dt = {:.3e}", timestep);
+                timestep
+            }
+        };
+
+        if self.warmup_duration < 0.0 || !self.warmup_duration.is_finite() {
+            return Err(Error::InvalidParameter(format!(
+                "Warm-up duration must be non-negative, got {}",
+                self.warmup_duration
+            )));
+        }
+
+        // Open systems are always stepped with fixed-step RK4.
+        let open = !self.collapse_operators.is_empty();
+        let stepping = if open {
+            IntegratorType::RK4
+        } else {
+            self.integrator_type
+        };
+        let mut problems = validate_setup(
+            hamiltonian.as_ref(),
+            &initial_state,
+            duration,
+            timestep,
+            stepping,
+            &observables,
+            &self.vector_observables,
+        );
+        if self.profile_terms {
+            check_term_costs(hamiltonian.as_ref(), &mut problems);
+        }
+        for warning in problems.iter().filter(|d| d.severity == Severity::Warning) {
+            tracing::warn!("{}", warning.message);
+        }
+        problems.into_result()?;
+
+        for condition in &self.stop_conditions {
+            if let Some(name) = condition.observable() {
+                if !observables.iter().any(|(n, _)| n == name) {
+                    return Err(Error::Config(format!(
+                        "Stop condition '{}' refers to unknown scalar observable '{}'",
+                        condition.describe(),
+                        name
+                    )));
+                }
+            }
+        }
+
+        if open {
+            if self.floquet_populations.is_some() || self.prethermal_populations.is_some() {
+                return Err(Error::Config(
+                    "Floquet and prethermal populations need a closed system".to_string(),
+                ));
+            }
+            if self.sampling.shots > 0 {
+                return Err(Error::Config(
+                    "Shot sampling needs a closed system".to_string(),
+                ));
+            }
+            if let (Some(_), Some((name, _))) = (self.trajectories, self.vector_observables.first())
+            {
+                return Err(Error::Config(format!(
+                    "Quantum trajectories record scalar observables only, vector observable '{}' \
+                     needs the dense solver",
+                    name
+                )));
+            }
+            if !matches!(self.integrator_type, IntegratorType::RK4) {
+                tracing::warn!(
+                    "Open systems are stepped with fixed-step RK4, ignoring the {:?} integrator",
+                    self.integrator_type
+                );
+            }
+        }
+
+        let mut embedding = None;
+        if self.symmetry_reduction && open {
+            tracing::warn!("Skipping symmetry reduction, collapse operators act on the full space");
+        } else if self.symmetry_reduction {
+            if !self.vector_observables.is_empty()
+                || self.floquet_populations.is_some()
+                || self.prethermal_populations.is_some()
+            {
+                tracing::warn!(
+                    "Skipping symmetry reduction, vector observables need the full Hilbert space"
+                );
+            } else {
+                let dim = hamiltonian.dim();
+                let indices =
+                    SectorDecomposition::detect(hamiltonian.as_ref()).subspace(&initial_state);
+                if indices.len() < dim {
+                    tracing::info!(
+                        "Symmetry reduction: evolving {} of {} basis states",
+                        indices.len(),
+                        dim
+                    );
+                    initial_state = symmetry::restrict_state(&initial_state, &indices)?;
+                    observables = observables
+                        .into_iter()
+                        .map(|(name, o)| {
+                            let reduced = symmetry::restrict_operator(o.matrix(), &indices);
+                            (
+                                name,
+                                Box::new(MatrixObservable::new(reduced)) as Box<dyn Observable>,
+                            )
+                        })
+                        .collect();
+                    hamiltonian = Box::new(SubspaceHamiltonian::new(hamiltonian, indices.clone())?);
+                    embedding = Some((indices, dim));
+                }
+            }
+        }
+
+        let floquet = match self.floquet_populations {
+            Some((name, num_steps)) => {
+                let period = hamiltonian.period().ok_or_else(|| {
+                    Error::Config(format!(
+                        "Floquet populations '{}' need a time-periodic Hamiltonian",
+                        name
+                    ))
+                })?;
+                Some((name, period, num_steps))
+            }
+            None => None,
+        };
 
-        SimulationRunner::new(
+        let prethermal = match self.prethermal_populations {
+            Some((name, samples)) => {
+                let period = hamiltonian.period().ok_or_else(|| {
+                    Error::Config(format!(
+                        "Prethermal populations '{}' need a time-periodic Hamiltonian",
+                        name
+                    ))
+                })?;
+                Some((name, period, samples))
+            }
+            None => None,
+        };
+
+        let runner = SimulationRunner::new(
             hamiltonian,
             initial_state,
             duration,
             timestep,
             self.integrator_type,
-            self.observables,
+            observables,
             self.gpu_enabled,
-        )
+        )?
+        .with_time_grid(self.time_grid)
+        .and_then(|runner| runner.with_precision(self.precision))
+        .map(|runner| {
+            runner
+                .with_vector_observables(self.vector_observables)
+                .with_max_retries(self.max_retries)
+                .with_warmup_duration(self.warmup_duration)
+                .with_record_states(self.record_states)
+                .with_stop_conditions(self.stop_conditions)
+                .with_steady_state_options(self.steady_state)
+                .with_sampling(self.sampling)
+        })?;
+        let runner = match (open, self.trajectories) {
+            (false, _) => runner,
+            (true, Some((count, seed))) => {
+                runner.with_trajectories(self.collapse_operators, count, seed)?
+            }
+            (true, None) => runner.with_master_equation(self.collapse_operators)?,
+        };
+        let runner = match embedding {
+            Some((indices, dim)) => runner.with_embedding(indices, dim),
+            None => runner,
+        };
+        let runner = match floquet {
+            Some((name, period, num_steps)) => {
+                runner.with_floquet_populations(name, period, num_steps)
+            }
+            None => runner,
+        };
+        Ok(match prethermal {
+            Some((name, period, samples)) => {
+                runner.with_prethermal_populations(name, period, samples)
+            }
+            None => runner,
+        })
+    }
+
+    pub fn from_config(config: &Config) -> Result<SimulationRunner> {
+        if config.kicks.enabled {
+            return Err(Error::Config(
+                "Kicked systems run through KickedSystem, not the simulation builder".to_string(),
+            ));
+        }
+        let dim = config.system.hilbert_dim;
+        let simulation = &config.simulation;
+        let mut builder = Self::new()
+            .initial_state(config.initial_state.build(dim)?)
+            .duration(simulation.duration)
+            .timestep(simulation.timestep)
+            .integrator(simulation.integrator_type()?)
+            .precision(Precision::parse(&simulation.precision)?)
+            .time_grid(config.observables.time_grid())
+            .max_retries(simulation.max_retries)
+            .warmup_duration(simulation.warmup_duration)
+            .steady_state_options(config.observables.steady_state.clone())
+            .sampling(config.observables.sampling.clone())
+            .gpu(config.gpu.enabled);
+        builder.hamiltonian = Some(operator_export::system_hamiltonian(config)?);
+        builder.observables = OperatorCache::new().observables_for(config)?;
+        for spec in &config.observables.list {
+            if let Some(observable) = observable_expr::parse_vector_observable(spec, dim)? {
+                builder.vector_observables.push(observable);
+            }
+        }
+        for condition in &simulation.stop_when {
+            builder = builder.stop_when(StopCondition::parse(condition)?);
+        }
+
+        builder.collapse_operators = operator_export::system_collapse_operators(config)?;
+        let open = !builder.collapse_operators.is_empty();
+        match performance::select_solver(config)? {
+            Solver::Cumulant => {
+                return Err(Error::Config(
+                    "The cumulant solver runs through CumulantSolver, not the simulation builder"
+                        .to_string(),
+                ))
+            }
+            Solver::Superoperator if open => {
+                return Err(Error::NotImplemented(
+                    "The superoperator solver for open systems".to_string(),
+                ))
+            }
+            Solver::Trajectories if open => {
+                builder = builder.trajectories(simulation.trajectories, simulation.seed);
+            }
+            _ => {}
+        }
+        builder.build()
+    }
+}
+
+const RK4_STABILITY_LIMIT: f64 = 2.8;
+const TIMESTEP_ACCURACY_FACTOR: f64 = 0.1;
+const COST_SAMPLES: usize = 16;
+const DOMINANT_TERM_FRACTION: f64 = 0.8;
+
+pub fn suggest_timestep(hamiltonian: &dyn Hamiltonian) -> Option<f64> {
+    let norm = hamiltonian.spectral_norm_estimate();
+    (norm > 0.0 && norm.is_finite()).then(|| TIMESTEP_ACCURACY_FACTOR / norm)
+}
+
+fn validate_setup(
+    hamiltonian: &dyn Hamiltonian,
+    initial_state: &QuantumState,
+    duration: f64,
+    timestep: f64,
+    stepping: IntegratorType,
+    observables: &[(String, Box<dyn Observable>)],
+    vector_observables: &[(String, Box<dyn VectorObservable>)],
+) -> Diagnostics {
+    let mut problems = Diagnostics::new();
+    let dim = hamiltonian.dim();
+
+    if duration <= 0.0 {
+        problems.error(
+            ErrorCode::InvalidParameter,
+            format!("Duration must be positive, got {}", duration),
+        );
+    }
+
+    if timestep <= 0.0 {
+        problems.error(
+            ErrorCode::InvalidParameter,
+            format!("Timestep must be positive, got {}", timestep),
+        );
+    } else if timestep > duration && !stepping.is_exact() {
+        problems.error(
+            ErrorCode::InvalidParameter,
+            format!(
+                "Timestep {} exceeds simulation duration {}",
+                timestep, duration
+            ),
+        );
     }
 
-    pub fn from_config(_config: &Config) -> Result<SimulationRunner> {
-        Err(Error::NotImplemented("from_config".to_string()))
+    if initial_state.dim() != dim {
+        problems.error(
+            ErrorCode::DimensionMismatch,
+            format!(
+                "Initial state dimension {} does not match Hamiltonian dimension {}",
+                initial_state.dim(),
+                dim
+            ),
+        );
+    }
+
+    let observable_dims = observables
+        .iter()
+        .map(|(name, o)| (name, o.dim()))
+        .chain(vector_observables.iter().map(|(name, o)| (name, o.dim())));
+    for (name, observable_dim) in observable_dims {
+        if observable_dim != dim {
+            problems.error(
+                ErrorCode::DimensionMismatch,
+                format!(
+                    "Observable '{}' has dimension {} but Hamiltonian has dimension {}",
+                    name, observable_dim, dim
+                ),
+            );
+        }
+    }
+
+    // Only fixed-step RK4 is bound by its stability region. Exact and Krylov
+    // propagators are unconditionally stable, and RK45 adapts its first step.
+    if timestep > 0.0 && matches!(stepping, IntegratorType::RK4) {
+        let norm = hamiltonian.spectral_norm_estimate();
+
+        if timestep * norm > RK4_STABILITY_LIMIT {
+            problems.error(
+                ErrorCode::Numerical,
+                format!(
+                    "Timestep {} is too large for ||H|| ~ {:.3}; use dt <= {:.3e}",
+                    timestep,
+                    norm,
+                    TIMESTEP_ACCURACY_FACTOR / norm
+                ),
+            );
+        } else if timestep * norm > TIMESTEP_ACCURACY_FACTOR {
+            problems.warning(
+                ErrorCode::Numerical,
+                format!(
+                    "Timestep {} may be inaccurate for ||H|| ~ {:.3}; dt <= {:.3e} is recommended",
+                    timestep,
+                    norm,
+                    TIMESTEP_ACCURACY_FACTOR / norm
+                ),
+            );
+        }
+    }
+
+    problems
+}
+
+fn check_term_costs(hamiltonian: &dyn Hamiltonian, problems: &mut Diagnostics) {
+    let costs = hamiltonian.term_costs(COST_SAMPLES);
+    if costs.len() > 1 {
+        for cost in &costs {
+            tracing::debug!(
+                "Hamiltonian term {}: {:.3e} s per evaluation ({:.0}%)",
+                cost.label,
+                cost.seconds_per_eval,
+                100.0 * cost.fraction
+            );
+        }
+        if let Some(dominant) = costs
+            .iter()
+            .find(|c| c.time_dependent && c.fraction > DOMINANT_TERM_FRACTION)
+        {
+            problems.warning(
+                ErrorCode::Hamiltonian,
+                format!(
+                    "Hamiltonian term '{}' takes {:.0}% of evaluation time; \
+                     consider precomputing or tabulating it",
+                    dominant.label,
+                    100.0 * dominant.fraction
+                ),
+            );
+        }
     }
 }
 
@@ -103,3 +610,238 @@ impl Default for SimulationBuilder {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observables::NumberOperator;
+    use crate::core::systems::DrivenTLS;
+
+    #[test]
+    fn test_build_reports_all_problems() {
+        let result = SimulationBuilder::new()
+            .hamiltonian(DrivenTLS::new(5.0, 5.0, 0.5))
+            .initial_state(QuantumState::ground_state(3))
+            .duration(1.0)
+            .timestep(2.0)
+            .observable("n", NumberOperator::new(4))
+            .build();
+
+        match result {
+            Err(Error::Validation(problems)) => assert_eq!(problems.len(), 4),
+            _ => panic!("expected validation error"),
+        }
+    }
+
+    #[test]
+    fn test_stability_limit_applies_to_fixed_step_rk4_only() {
+        use crate::core::hamiltonian::TimeIndependentHamiltonian;
+        use num_complex::Complex64;
+
+        let h = ndarray::Array2::from_shape_fn((2, 2), |(i, j)| {
+            Complex64::new(if i == j { 50.0 * i as f64 } else { 5.0 }, 0.0)
+        });
+        let build = |integrator| {
+            SimulationBuilder::new()
+                .hamiltonian(TimeIndependentHamiltonian::new(h.clone()))
+                .initial_state(QuantumState::ground_state(2))
+                .duration(1.0)
+                .timestep(0.5)
+                .integrator(integrator)
+                .build()
+        };
+        assert!(build(IntegratorType::RK4).is_err());
+        assert!(build(IntegratorType::Exact).is_ok());
+        assert!(build(IntegratorType::Krylov { dim: 2, tol: 1e-10 }).is_ok());
+        assert!(build(IntegratorType::AdaptiveRK45 {
+            atol: IntegratorType::DEFAULT_ATOL,
+            rtol: IntegratorType::DEFAULT_RTOL,
+        })
+        .is_ok());
+    }
+
+    #[test]
+    fn test_timestep_is_suggested_from_norm() {
+        let tls = DrivenTLS::new(5.0, 5.0, 0.5);
+        let dt = suggest_timestep(&tls).unwrap();
+        assert!((dt - 0.1 / tls.spectral_norm_estimate()).abs() < 1e-12);
+
+        let result = SimulationBuilder::new()
+            .hamiltonian(tls)
+            .initial_state(QuantumState::ground_state(2))
+            .duration(1.0)
+            .build();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_build_valid_setup() {
+        let result = SimulationBuilder::new()
+            .hamiltonian(DrivenTLS::new(5.0, 5.0, 0.5))
+            .initial_state(QuantumState::ground_state(2))
+            .duration(1.0)
+            .timestep(0.01)
+            .observable("n", NumberOperator::new(2))
+            .build();
+
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_term_costs_are_only_timed_on_request() {
+        use crate::core::hamiltonian::TermCost;
+        use ndarray::Array2;
+        use num_complex::Complex64;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        struct Counted(Arc<AtomicUsize>);
+        impl Hamiltonian for Counted {
+            fn dim(&self) -> usize {
+                2
+            }
+            fn compute(&self, _t: f64, out: &mut Array2<Complex64>) {
+                out.fill(Complex64::new(0.0, 0.0));
+            }
+            fn is_time_independent(&self) -> bool {
+                true
+            }
+            fn term_costs(&self, _samples: usize) -> Vec<TermCost> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Vec::new()
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        for profile in [false, true] {
+            SimulationBuilder::new()
+                .hamiltonian(Counted(calls.clone()))
+                .initial_state(QuantumState::ground_state(2))
+                .duration(1.0)
+                .timestep(0.01)
+                .profile_terms(profile)
+                .build()
+                .unwrap();
+            assert_eq!(calls.load(Ordering::SeqCst), profile as usize);
+        }
+    }
+
+    #[test]
+    fn test_from_config_matches_manual_setup() {
+        let mut config = Config::generate_template("driven_tls").unwrap();
+        config.simulation.duration = 5.0;
+        config.simulation.timestep = 0.01;
+        config.observables.list.push("bloch".to_string());
+        let results = SimulationBuilder::from_config(&config)
+            .unwrap()
+            .run()
+            .unwrap();
+
+        let (name, population) = observable_expr::parse_observable("population", 2).unwrap();
+        let expected = SimulationBuilder::new()
+            .hamiltonian(DrivenTLS::new(5.0, 5.0, 0.5))
+            .initial_state(QuantumState::ground_state(2))
+            .duration(5.0)
+            .timestep(0.01)
+            .time_grid(TimeGrid::uniform(1.0))
+            .observable(name, population)
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!(results.times(), expected.times());
+        assert_eq!(
+            results.series("population").unwrap().values,
+            expected.series("population").unwrap().values
+        );
+        assert_eq!(results.vector_series("bloch").unwrap().len(), 6);
+    }
+
+    fn decaying_cavity(solver: &str) -> Config {
+        let text = format!(
+            r#"
+            [simulation]
+            name = "decay"
+            duration = 2.0
+            timestep = 0.01
+            solver = "{}"
+            trajectories = 400
+            seed = 7
+
+            [system]
+            hilbert_dim = 4
+            hamiltonian = "cavity"
+
+            [system.parameters]
+            omega_c = 1.0
+            omega_p = 2.0
+            g = 0.0
+
+            [initial_state]
+            kind = "fock"
+            level = 1
+
+            [lindblad]
+            enabled = true
+            operators = [{{ type = "annihilation", rate = 0.5 }}]
+
+            [observables]
+            list = ["number"]
+            save_interval = 0.5
+            "#,
+            solver
+        );
+        toml::from_str(&text).unwrap()
+    }
+
+    #[test]
+    fn test_from_config_evolves_open_systems() {
+        for (solver, tolerance) in [("dense", 1e-6), ("trajectories", 0.1)] {
+            let config = decaying_cavity(solver);
+            let runner = SimulationBuilder::from_config(&config).unwrap();
+            assert!(runner.is_open());
+            let results = runner.run().unwrap();
+            let number = results.series("number").unwrap();
+            assert_eq!(results.times(), &[0.0, 0.5, 1.0, 1.5, 2.0]);
+            for (t, n) in results.times().iter().zip(number.values) {
+                assert!(
+                    (n.re - (-0.5 * t).exp()).abs() < tolerance,
+                    "{} solver: <n>({}) = {}",
+                    solver,
+                    t,
+                    n.re
+                );
+            }
+            assert_eq!(
+                results.series("number_std").is_some(),
+                solver == "trajectories"
+            );
+        }
+
+        let mut config = decaying_cavity("dense");
+        assert!(SimulationBuilder::from_config(&config)
+            .unwrap()
+            .with_checkpoints(std::env::temp_dir().join("unused.ckpt"), 1.0)
+            .is_err());
+        config.observables.sampling.shots = 10;
+        assert!(SimulationBuilder::from_config(&config).is_err());
+        let mut config = decaying_cavity("superoperator");
+        assert!(matches!(
+            SimulationBuilder::from_config(&config),
+            Err(Error::NotImplemented(_))
+        ));
+        config.lindblad.enabled = false;
+        assert!(!SimulationBuilder::from_config(&config).unwrap().is_open());
+        config.kicks.enabled = true;
+        assert!(SimulationBuilder::from_config(&config).is_err());
+
+        for solver in ["dense", "trajectories"] {
+            let mut config = decaying_cavity(solver);
+            config.simulation.precision = "f32".to_string();
+            assert!(matches!(
+                SimulationBuilder::from_config(&config),
+                Err(Error::Config(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use crate::simulation::ensemble::check_times;
+use crate::simulation::SimulationResults;
+use crate::utils::{Error, Result};
+use num_complex::Complex64;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Debug, Clone)]
+pub struct RunDifference {
+    pub times: Vec<f64>,
+    pub differences: Vec<(String, Vec<Complex64>)>,
+    pub fidelity: Option<Vec<f64>>,
+}
+
+impl RunDifference {
+    pub fn compute(
+        a: &SimulationResults,
+        b: &SimulationResults,
+        observables: &[String],
+    ) -> Result<Self> {
+        let names: Vec<String> = if observables.is_empty() {
+            a.iter_series()
+                .filter(|series| b.series(series.name).is_some())
+                .map(|series| series.name.to_string())
+                .collect()
+        } else {
+            observables.to_vec()
+        };
+        if names.is_empty() {
+            return Err(Error::InvalidParameter(
+                "The two runs have no observables in common".to_string(),
+            ));
+        }
+
+        let mut times: Option<Vec<f64>> = None;
+        let mut differences = Vec::with_capacity(names.len());
+        for name in names {
+            let (Some(sa), Some(sb)) = (a.series(&name), b.series(&name)) else {
+                return Err(Error::InvalidParameter(format!(
+                    "Observable '{}' is missing from one of the runs",
+                    name
+                )));
+            };
+            check_times(&name, sa.times, sb.times)?;
+            if let Some(times) = &times {
+                check_times(&name, times, sa.times)?;
+            } else {
+                times = Some(sa.times.to_vec());
+            }
+            let delta = sa
+                .values
+                .iter()
+                .zip(sb.values)
+                .map(|(x, y)| y - x)
+                .collect();
+            differences.push((name, delta));
+        }
+
+        let times = times.unwrap_or_default();
+        let fidelity = state_fidelity(a, b, &times)?;
+        Ok(Self {
+            times,
+            differences,
+            fidelity,
+        })
+    }
+
+    pub fn max_abs_difference(&self, name: &str) -> Option<f64> {
+        self.differences
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, delta)| delta.iter().map(|d| d.norm()).fold(0.0, f64::max))
+    }
+
+    pub fn min_fidelity(&self) -> Option<f64> {
+        self.fidelity
+            .as_ref()
+            .map(|f| f.iter().copied().fold(1.0, f64::min))
+    }
+
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let mut header = vec!["time".to_string()];
+        for (name, _) in &self.differences {
+            header.push(format!("{}_diff_re", name));
+            header.push(format!("{}_diff_im", name));
+        }
+        if self.fidelity.is_some() {
+            header.push("fidelity".to_string());
+        }
+        writeln!(writer, "{}", header.join(","))?;
+
+        for (k, t) in self.times.iter().enumerate() {
+            let mut row = vec![t.to_string()];
+            for (_, delta) in &self.differences {
+                row.push(delta[k].re.to_string());
+                row.push(delta[k].im.to_string());
+            }
+            if let Some(fidelity) = &self.fidelity {
+                row.push(fidelity[k].to_string());
+            }
+            writeln!(writer, "{}", row.join(","))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+fn state_fidelity(
+    a: &SimulationResults,
+    b: &SimulationResults,
+    times: &[f64],
+) -> Result<Option<Vec<f64>>> {
+    let (sa, sb) = (a.snapshots(), b.snapshots());
+    if sa.is_empty() || sb.is_empty() {
+        return Ok(None);
+    }
+    let (ta, tb): (Vec<f64>, Vec<f64>) = (
+        sa.iter().map(|s| s.time).collect(),
+        sb.iter().map(|s| s.time).collect(),
+    );
+    check_times("states", times, &ta)?;
+    check_times("states", &ta, &tb)?;
+
+    sa.iter()
+        .zip(sb)
+        .map(|(x, y)| {
+            if x.amplitudes.len() != y.amplitudes.len() {
+                return Err(Error::dimension_mismatch(
+                    x.amplitudes.len(),
+                    y.amplitudes.len(),
+                ));
+            }
+            let overlap: Complex64 = x
+                .amplitudes
+                .iter()
+                .zip(&y.amplitudes)
+                .map(|(p, q)| p.conj() * q)
+                .sum();
+            Ok(overlap.norm_sqr())
+        })
+        .collect::<Result<Vec<_>>>()
+        .map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observables::PopulationOperator;
+    use crate::core::systems::DrivenTLS;
+    use crate::core::QuantumState;
+    use crate::simulation::SimulationBuilder;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_difference_of_detuned_runs() {
+        let run = |detuning: f64| {
+            SimulationBuilder::new()
+                .hamiltonian(DrivenTLS::new(5.0 + detuning, 5.0, 0.5))
+                .initial_state(QuantumState::ground_state(2))
+                .duration(4.0)
+                .timestep(0.01)
+                .observable("p1", PopulationOperator::new(2, 1).unwrap())
+                .record_states(true)
+                .build()
+                .unwrap()
+                .run()
+                .unwrap()
+        };
+        let (a, b) = (run(0.0), run(1.0));
+
+        let same = RunDifference::compute(&a, &a, &[]).unwrap();
+        assert_relative_eq!(same.max_abs_difference("p1").unwrap(), 0.0);
+        assert_relative_eq!(same.min_fidelity().unwrap(), 1.0, epsilon = 1e-12);
+
+        let diff = RunDifference::compute(&a, &b, &["p1".to_string()]).unwrap();
+        assert_eq!(diff.times.len(), diff.differences[0].1.len());
+        assert!(diff.max_abs_difference("p1").unwrap() > 0.1);
+        assert!(diff.min_fidelity().unwrap() < 0.99);
+        assert!(RunDifference::compute(&a, &b, &["p0".to_string()]).is_err());
+
+        let path = std::env::temp_dir().join("chronophoton_run_difference.csv");
+        diff.write_csv(&path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(contents.starts_with("time,p1_diff_re,p1_diff_im,fidelity"));
+    }
+}
@@ -0,0 +1,331 @@
+use crate::core::{integrator, Hamiltonian, IntegratorType, Precision, QuantumState};
+use crate::simulation::fit::Estimate;
+use crate::simulation::heating::linear_slope;
+use crate::utils::{Error, Result};
+use ndarray::{array, Array1, Array2};
+use num_complex::Complex64;
+
+pub const DEFAULT_TIMESTEPS: [f64; 4] = [0.1, 0.05, 0.025, 0.0125];
+pub const SINGLE_PRECISION_TIMESTEPS: [f64; 3] = [0.2, 0.1, 0.05];
+const ORDER_TOLERANCE: f64 = 0.3;
+// Below these errors rounding dominates the truncation error being measured.
+fn error_floor(precision: Precision) -> f64 {
+    match precision {
+        Precision::Double => 1e-12,
+        Precision::Single => 5e-7,
+    }
+}
+
+// The energy offset keeps the spectrum asymmetric. Otherwise a scheme whose leading
+// error only rescales the norm looks an order better once the state is normalized.
+pub struct RotatingFieldQubit {
+    pub omega_0: f64,
+    pub omega: f64,
+    pub g: f64,
+    pub offset: f64,
+}
+
+impl Default for RotatingFieldQubit {
+    fn default() -> Self {
+        Self {
+            omega_0: 1.0,
+            omega: 3.0,
+            g: 1.5,
+            offset: 0.7,
+        }
+    }
+}
+
+impl RotatingFieldQubit {
+    pub fn exact(&self, psi0: &QuantumState, t: f64) -> Array1<Complex64> {
+        let delta = (self.omega_0 - self.omega) / 2.0;
+        let rabi = (delta * delta + self.g * self.g).sqrt();
+        let (c, s) = ((rabi * t).cos(), (rabi * t).sin() / rabi);
+        let i = Complex64::i();
+        let rotating: Array2<Complex64> = array![
+            [c - i * delta * s, -i * self.g * s],
+            [-i * self.g * s, c + i * delta * s]
+        ];
+        let frame = Complex64::from_polar(1.0, -self.omega * t / 2.0);
+        let psi = rotating.dot(psi0.data()) * Complex64::from_polar(1.0, -self.offset * t);
+        array![psi[0] * frame, psi[1] * frame.conj()]
+    }
+}
+
+impl Hamiltonian for RotatingFieldQubit {
+    fn dim(&self) -> usize {
+        2
+    }
+
+    fn is_time_independent(&self) -> bool {
+        self.omega == 0.0
+    }
+
+    fn compute(&self, t: f64, out: &mut Array2<Complex64>) {
+        let field = Complex64::from_polar(self.g, -self.omega * t);
+        out[[0, 0]] = Complex64::new(self.offset + self.omega_0 / 2.0, 0.0);
+        out[[1, 1]] = Complex64::new(self.offset - self.omega_0 / 2.0, 0.0);
+        out[[0, 1]] = field;
+        out[[1, 0]] = field.conj();
+    }
+}
+
+pub fn expected_order(integrator_type: IntegratorType) -> f64 {
+    match integrator_type {
+        IntegratorType::RK4 => 4.0,
+        IntegratorType::AdaptiveRK45 { .. } => 5.0,
+        IntegratorType::Exact => f64::INFINITY,
+        IntegratorType::Krylov { .. } => 2.0,
+    }
+}
+
+pub struct ConvergenceReport {
+    pub integrator: IntegratorType,
+    pub precision: Precision,
+    pub timesteps: Vec<f64>,
+    pub errors: Vec<f64>,
+    pub expected_order: f64,
+    pub observed_order: Estimate,
+}
+
+impl ConvergenceReport {
+    pub fn passed(&self) -> bool {
+        self.observed_order.value == self.expected_order
+            || (self.observed_order.value - self.expected_order).abs() <= ORDER_TOLERANCE
+    }
+
+    pub fn describe(&self) -> String {
+        let verdict = if self.passed() {
+            "ok".to_string()
+        } else if self.observed_order.value > self.expected_order {
+            "converges faster than its order, is it an alias of another scheme?".to_string()
+        } else {
+            "converges slower than its order, check the time ordering".to_string()
+        };
+        format!(
+            "{:?} ({}): order {} expected, {} observed, {}",
+            self.integrator,
+            self.precision.name(),
+            self.expected_order,
+            self.observed_order,
+            verdict
+        )
+    }
+}
+
+pub struct ConvergenceTest {
+    problem: RotatingFieldQubit,
+    duration: f64,
+    timesteps: Vec<f64>,
+}
+
+impl Default for ConvergenceTest {
+    fn default() -> Self {
+        Self {
+            problem: RotatingFieldQubit::default(),
+            duration: 2.0,
+            timesteps: DEFAULT_TIMESTEPS.to_vec(),
+        }
+    }
+}
+
+impl ConvergenceTest {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_timesteps(mut self, timesteps: Vec<f64>) -> Result<Self> {
+        if timesteps.len() < 2 || timesteps.iter().any(|&dt| !(dt > 0.0 && dt.is_finite())) {
+            return Err(Error::InvalidParameter(format!(
+                "Convergence test needs at least two positive timesteps, got {:?}",
+                timesteps
+            )));
+        }
+        self.timesteps = timesteps;
+        Ok(self)
+    }
+
+    pub fn with_duration(mut self, duration: f64) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    // The exact integrator only takes time-independent Hamiltonians, so it sees the
+    // same qubit without the rotating field.
+    fn problem(&self, integrator_type: IntegratorType) -> RotatingFieldQubit {
+        RotatingFieldQubit {
+            omega_0: self.problem.omega_0,
+            omega: match integrator_type {
+                IntegratorType::Exact => 0.0,
+                _ => self.problem.omega,
+            },
+            g: self.problem.g,
+            offset: self.problem.offset,
+        }
+    }
+
+    pub fn final_error(
+        &self,
+        integrator_type: IntegratorType,
+        precision: Precision,
+        dt: f64,
+    ) -> Result<f64> {
+        let integrator = integrator::create_integrator_with_precision(integrator_type, precision)?;
+        let problem = self.problem(integrator_type);
+        let psi0 = QuantumState::ground_state(2);
+        let mut state = psi0.clone();
+        let steps = (self.duration / dt).round().max(1.0) as usize;
+        let dt = self.duration / steps as f64;
+        for step in 0..steps {
+            integrator.step(&problem, &mut state, step as f64 * dt, dt)?;
+        }
+        let exact = problem.exact(&psi0, self.duration);
+        Ok((state.data() - &exact)
+            .iter()
+            .map(|x| x.norm_sqr())
+            .sum::<f64>()
+            .sqrt())
+    }
+
+    pub fn run(
+        &self,
+        integrator_type: IntegratorType,
+        precision: Precision,
+    ) -> Result<ConvergenceReport> {
+        let errors = self
+            .timesteps
+            .iter()
+            .map(|&dt| self.final_error(integrator_type, precision, dt))
+            .collect::<Result<Vec<_>>>()?;
+        let (log_dt, log_error): (Vec<f64>, Vec<f64>) = self
+            .timesteps
+            .iter()
+            .zip(&errors)
+            .filter(|(_, &error)| error > error_floor(precision))
+            .map(|(dt, error)| (dt.ln(), error.ln()))
+            .unzip();
+        let expected_order = expected_order(integrator_type);
+        let observed_order = if log_dt.len() >= 2 {
+            linear_slope(&log_dt, &log_error)
+        } else if log_dt.is_empty() && expected_order.is_infinite() {
+            // An exact scheme stays at the rounding floor for every timestep.
+            Estimate {
+                value: f64::INFINITY,
+                uncertainty: 0.0,
+            }
+        } else {
+            return Err(Error::Numerical(format!(
+                "{:?} errors are below {:e} at every timestep, use larger timesteps",
+                integrator_type,
+                error_floor(precision)
+            )));
+        };
+        Ok(ConvergenceReport {
+            integrator: integrator_type,
+            precision,
+            timesteps: self.timesteps.clone(),
+            errors,
+            expected_order,
+            observed_order,
+        })
+    }
+
+    pub fn run_all(&self, precision: Precision) -> Result<Vec<ConvergenceReport>> {
+        [
+            IntegratorType::RK4,
+            IntegratorType::AdaptiveRK45 {
+                atol: IntegratorType::DEFAULT_ATOL,
+                rtol: IntegratorType::DEFAULT_RTOL,
+            },
+            IntegratorType::Exact,
+            IntegratorType::Krylov {
+                dim: IntegratorType::DEFAULT_KRYLOV_DIM,
+                tol: IntegratorType::DEFAULT_KRYLOV_TOL,
+            },
+        ]
+        .into_iter()
+        .filter(|integrator_type| {
+            precision == Precision::Double || integrator_type.has_single_precision()
+        })
+        .map(|integrator_type| self.run(integrator_type, precision))
+        .collect()
+    }
+}
+
+pub fn find_aliases(reports: &[ConvergenceReport]) -> Vec<(IntegratorType, IntegratorType)> {
+    let mut aliases = Vec::new();
+    for (k, report) in reports.iter().enumerate() {
+        if let Some(original) = reports[..k].iter().find(|r| r.errors == report.errors) {
+            aliases.push((report.integrator, original.integrator));
+        }
+    }
+    aliases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_reference_solution_solves_the_schrodinger_equation() {
+        let problem = RotatingFieldQubit::default();
+        let psi0 = QuantumState::ground_state(2);
+        let (t, dt) = (0.7, 1e-6);
+        let derivative = (problem.exact(&psi0, t + dt) - problem.exact(&psi0, t - dt)) / (2.0 * dt);
+        let mut h = Array2::zeros((2, 2));
+        problem.compute(t, &mut h);
+        let rhs = h
+            .dot(&problem.exact(&psi0, t))
+            .mapv(|x| -Complex64::i() * x);
+        for (a, b) in derivative.iter().zip(rhs.iter()) {
+            assert_relative_eq!((a - b).norm(), 0.0, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_every_integrator_reaches_its_order() {
+        let reports = ConvergenceTest::new().run_all(Precision::Double).unwrap();
+        assert_eq!(reports.len(), 4);
+        for report in &reports {
+            assert!(report.passed(), "{}", report.describe());
+        }
+        assert!(find_aliases(&reports).is_empty());
+
+        let single = ConvergenceTest::new().run_all(Precision::Single).unwrap();
+        assert!(matches!(
+            single[..],
+            [ConvergenceReport {
+                integrator: IntegratorType::RK4,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn test_rk4_order_and_alias_detection() {
+        let test = ConvergenceTest::new();
+
+        // A scheme that secretly runs another one gives identical errors.
+        let rk4 = test.run(IntegratorType::RK4, Precision::Double).unwrap();
+        let alias = ConvergenceReport {
+            integrator: IntegratorType::Exact,
+            expected_order: 2.0,
+            ..test.run(IntegratorType::RK4, Precision::Double).unwrap()
+        };
+        assert!(!alias.passed());
+        assert!(alias.describe().contains("alias"));
+        let single = ConvergenceTest::new()
+            .with_timesteps(SINGLE_PRECISION_TIMESTEPS.to_vec())
+            .unwrap()
+            .run(IntegratorType::RK4, Precision::Single)
+            .unwrap();
+        assert!(single.passed(), "{}", single.describe());
+
+        let aliases = find_aliases(&[rk4, alias]);
+        assert!(matches!(
+            aliases[..],
+            [(IntegratorType::Exact, IntegratorType::RK4)]
+        ));
+    }
+}
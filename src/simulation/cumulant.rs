@@ -0,0 +1,513 @@
+use crate::core::lindblad::LindbladOperator;
+use crate::core::QuantumState;
+use crate::data::config::{Config, SystemParameters};
+use crate::simulation::{SimulationResults, TimeGrid};
+use crate::utils::math::{identity, kron};
+use crate::utils::resources::ResourceMeter;
+use crate::utils::{Error, Result};
+use ndarray::{Array1, Array2};
+use num_complex::Complex64;
+
+pub const CAVITY_FIELD: &str = "cavity_field";
+pub const PHOTON_NUMBER: &str = "photon_number";
+pub const SIGMA_MINUS: &str = "sigma_minus";
+pub const SIGMA_Z: &str = "sigma_z";
+pub const CAVITY_EMITTER: &str = "cavity_emitter";
+pub const EMITTER_CORRELATION: &str = "emitter_correlation";
+
+const ALPHA: usize = 0;
+const S: usize = 1;
+const Z: usize = 2;
+const N: usize = 3;
+const M: usize = 4;
+const P: usize = 5;
+const Q: usize = 6;
+const R: usize = 7;
+const U: usize = 8;
+const V: usize = 9;
+const W: usize = 10;
+const ZZ: usize = 11;
+const MOMENTS: usize = 12;
+const MAX_DENSE_DIM: usize = 1 << 14;
+
+type Moments = [Complex64; MOMENTS];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CumulantOrder {
+    MeanField,
+    Second,
+}
+
+impl CumulantOrder {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "mean_field" | "first" => Ok(Self::MeanField),
+            "second" => Ok(Self::Second),
+            other => Err(Error::Config(format!(
+                "Unknown cumulant order '{}', expected mean_field or second",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MeanField => "mean_field",
+            Self::Second => "second",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TavisCummings {
+    pub emitters: usize,
+    pub omega_c: f64,
+    pub omega_a: f64,
+    pub g: f64,
+    pub drive: f64,
+    pub omega_d: f64,
+    pub kappa: f64,
+    pub gamma: f64,
+    pub dephasing: f64,
+}
+
+impl TavisCummings {
+    pub fn new(emitters: usize, omega_c: f64, omega_a: f64, g: f64) -> Self {
+        Self {
+            emitters,
+            omega_c,
+            omega_a,
+            g,
+            drive: 0.0,
+            omega_d: 0.0,
+            kappa: 0.0,
+            gamma: 0.0,
+            dephasing: 0.0,
+        }
+    }
+
+    pub fn with_drive(mut self, strength: f64, frequency: f64) -> Self {
+        self.drive = strength;
+        self.omega_d = frequency;
+        self
+    }
+
+    pub fn with_losses(mut self, kappa: f64, gamma: f64, dephasing: f64) -> Self {
+        self.kappa = kappa;
+        self.gamma = gamma;
+        self.dephasing = dephasing;
+        self
+    }
+
+    pub fn dense(&self, cavity_dim: usize) -> Result<DenseTavisCummings> {
+        self.validate()?;
+        let dim = dense_dim(cavity_dim, self.emitters)?;
+        let zero = Complex64::new(0.0, 0.0);
+        let one = Complex64::new(1.0, 0.0);
+        let mut a = Array2::zeros((cavity_dim, cavity_dim));
+        for n in 1..cavity_dim {
+            a[[n - 1, n]] = Complex64::new((n as f64).sqrt(), 0.0);
+        }
+        let sigma = Array2::from_shape_vec((2, 2), vec![zero, one, zero, zero])
+            .expect("2x2 shape matches four entries");
+        let sz = Array2::from_diag(&Array1::from(vec![-one, one]));
+        let id2 = identity(2);
+        let embed = |op: &Array2<Complex64>, site: usize| {
+            (0..self.emitters).fold(identity(cavity_dim), |out, k| {
+                kron(&out, if k == site { op } else { &id2 })
+            })
+        };
+        let dagger = |op: &Array2<Complex64>| op.t().mapv(|x| x.conj());
+
+        let a = kron(&a, &identity(dim / cavity_dim));
+        let adag = dagger(&a);
+        let photon_number = adag.dot(&a);
+        let mut hamiltonian = photon_number.mapv(|x| x * (self.omega_c - self.omega_d))
+            + (&a + &adag).mapv(|x| x * self.drive);
+        let mut sigma_z = Array2::zeros((dim, dim));
+        let mut collapse_ops = Vec::new();
+        if self.kappa > 0.0 {
+            collapse_ops.push(LindbladOperator::new(a.clone(), self.kappa)?);
+        }
+        for site in 0..self.emitters {
+            let s = embed(&sigma, site);
+            let z = embed(&sz, site);
+            hamiltonian = hamiltonian
+                + z.mapv(|x| x * (self.omega_a - self.omega_d) / 2.0)
+                + (adag.dot(&s) + a.dot(&dagger(&s))).mapv(|x| x * self.g);
+            sigma_z = sigma_z + z.mapv(|x| x / self.emitters as f64);
+            if self.gamma > 0.0 {
+                collapse_ops.push(LindbladOperator::new(s, self.gamma)?);
+            }
+            if self.dephasing > 0.0 {
+                collapse_ops.push(LindbladOperator::new(z, self.dephasing)?);
+            }
+        }
+
+        Ok(DenseTavisCummings {
+            hamiltonian,
+            collapse_ops,
+            photon_number,
+            sigma_z,
+        })
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.emitters == 0 {
+            return Err(Error::InvalidParameter(
+                "Cumulant solver needs at least one emitter".to_string(),
+            ));
+        }
+        for (name, rate) in [
+            ("kappa", self.kappa),
+            ("gamma", self.gamma),
+            ("dephasing", self.dephasing),
+        ] {
+            if rate < 0.0 || !rate.is_finite() {
+                return Err(Error::InvalidParameter(format!(
+                    "Cumulant rate {} must be non-negative, got {}",
+                    name, rate
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct DenseTavisCummings {
+    pub hamiltonian: Array2<Complex64>,
+    pub collapse_ops: Vec<LindbladOperator>,
+    pub photon_number: Array2<Complex64>,
+    pub sigma_z: Array2<Complex64>,
+}
+
+pub fn dense_dim(cavity_dim: usize, emitters: usize) -> Result<usize> {
+    let dim = u32::try_from(emitters)
+        .ok()
+        .and_then(|n| 1usize.checked_shl(n))
+        .and_then(|states| states.checked_mul(cavity_dim))
+        .filter(|&dim| dim > 0 && dim <= MAX_DENSE_DIM);
+    dim.ok_or_else(|| {
+        Error::InvalidParameter(format!(
+            "A dense model of {} emitters with {} cavity levels exceeds {} states",
+            emitters, cavity_dim, MAX_DENSE_DIM
+        ))
+    })
+}
+
+pub struct CumulantSolver {
+    model: TavisCummings,
+    order: CumulantOrder,
+    field: Complex64,
+    emitter_angle: f64,
+}
+
+impl CumulantSolver {
+    pub fn new(model: TavisCummings, order: CumulantOrder) -> Result<Self> {
+        model.validate()?;
+        Ok(Self {
+            model,
+            order,
+            field: Complex64::new(0.0, 0.0),
+            emitter_angle: 0.0,
+        })
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let SystemParameters::TavisCummings(parameters) = &config.system.parameters else {
+            return Err(Error::Config(format!(
+                "The cumulant solver needs hamiltonian = \"tavis_cummings\", got \"{}\"",
+                config.system.hamiltonian
+            )));
+        };
+        let cumulant = &config.cumulant;
+        let model = TavisCummings::new(
+            cumulant.emitters,
+            parameters.omega_c,
+            parameters.omega_a,
+            parameters.g,
+        )
+        .with_drive(parameters.drive, parameters.omega_d)
+        .with_losses(cumulant.kappa, cumulant.gamma, cumulant.dephasing);
+        Ok(
+            Self::new(model, CumulantOrder::parse(&cumulant.order)?)?.with_initial_state(
+                Complex64::new(cumulant.field[0], cumulant.field[1]),
+                cumulant.emitter_angle,
+            ),
+        )
+    }
+
+    pub fn with_initial_state(mut self, field: Complex64, emitter_angle: f64) -> Self {
+        self.field = field;
+        self.emitter_angle = emitter_angle;
+        self
+    }
+
+    pub fn with_order(mut self, order: CumulantOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    pub fn order(&self) -> CumulantOrder {
+        self.order
+    }
+
+    pub fn model(&self) -> &TavisCummings {
+        &self.model
+    }
+
+    pub fn dense_initial_state(&self, cavity_dim: usize) -> Result<QuantumState> {
+        let dim = dense_dim(cavity_dim, self.model.emitters)?;
+        let field = QuantumState::coherent(cavity_dim, self.field)?;
+        let (ground, excited) = (
+            (self.emitter_angle / 2.0).cos(),
+            (self.emitter_angle / 2.0).sin(),
+        );
+        let data = Array1::from_shape_fn(dim, |index| {
+            let emitters = index % (dim / cavity_dim);
+            let excitations = emitters.count_ones() as i32;
+            let weight =
+                excited.powi(excitations) * ground.powi(self.model.emitters as i32 - excitations);
+            field.data()[index / (dim / cavity_dim)] * weight
+        });
+        QuantumState::new(data)
+    }
+
+    pub fn run(
+        &self,
+        duration: f64,
+        timestep: f64,
+        time_grid: &TimeGrid,
+    ) -> Result<SimulationResults> {
+        if duration <= 0.0 || timestep <= 0.0 {
+            return Err(Error::InvalidParameter(format!(
+                "Duration and timestep must be positive, got {} and {}",
+                duration, timestep
+            )));
+        }
+        time_grid.validate(duration)?;
+
+        let meter = ResourceMeter::start();
+        let mut results = SimulationResults::new();
+        let mut moments = self.initial_moments();
+        let mut t = 0.0;
+        let tolerance = 1e-9 * timestep;
+        for t_out in time_grid.output_times(duration, timestep) {
+            while t_out - t > tolerance {
+                let dt = timestep.min(t_out - t);
+                self.step(&mut moments, dt);
+                t += dt;
+            }
+            if moments.iter().any(|x| !x.is_finite()) {
+                return Err(Error::Numerical(format!(
+                    "Cumulant equations diverged at t = {}, try a smaller timestep",
+                    t
+                )));
+            }
+            self.record(&mut results, t_out, &moments);
+        }
+
+        results.set_resources(meter.finish());
+        Ok(results)
+    }
+
+    fn initial_moments(&self) -> Moments {
+        let alpha = self.field;
+        let s = Complex64::new(self.emitter_angle.sin() / 2.0, 0.0);
+        let z = Complex64::new(-self.emitter_angle.cos(), 0.0);
+        let mut moments = [Complex64::new(0.0, 0.0); MOMENTS];
+        moments[ALPHA] = alpha;
+        moments[S] = s;
+        moments[Z] = z;
+        factorize(&mut moments);
+        moments
+    }
+
+    fn step(&self, moments: &mut Moments, dt: f64) {
+        let shifted = |base: &Moments, k: &Moments, h: f64| {
+            let mut out = *base;
+            for (x, dx) in out.iter_mut().zip(k) {
+                *x += dx * h;
+            }
+            out
+        };
+        let k1 = self.derivative(moments);
+        let k2 = self.derivative(&shifted(moments, &k1, dt / 2.0));
+        let k3 = self.derivative(&shifted(moments, &k2, dt / 2.0));
+        let k4 = self.derivative(&shifted(moments, &k3, dt));
+        for i in 0..MOMENTS {
+            moments[i] += (k1[i] + 2.0 * k2[i] + 2.0 * k3[i] + k4[i]) * (dt / 6.0);
+        }
+        if self.order == CumulantOrder::MeanField {
+            factorize(moments);
+        }
+    }
+
+    fn derivative(&self, x: &Moments) -> Moments {
+        let model = &self.model;
+        let i = Complex64::i();
+        let emitters = model.emitters as f64;
+        let pairs = emitters - 1.0;
+        let delta_c = model.omega_c - model.omega_d;
+        let delta_a = model.omega_a - model.omega_d;
+        let g = model.g;
+        let eta = model.drive;
+        let kappa = model.kappa;
+        let gamma = model.gamma;
+        let transverse = gamma / 2.0 + 2.0 * model.dephasing;
+
+        let (alpha, s, z) = (x[ALPHA], x[S], x[Z]);
+        let mut dx = [Complex64::new(0.0, 0.0); MOMENTS];
+
+        if self.order == CumulantOrder::MeanField {
+            let q = alpha.conj() * s;
+            dx[ALPHA] = -(i * delta_c + kappa / 2.0) * alpha - i * g * emitters * s - i * eta;
+            dx[S] = -(i * delta_a + transverse) * s + i * g * alpha * z;
+            dx[Z] = 2.0 * i * g * (q - q.conj()) - gamma * (1.0 + z);
+            return dx;
+        }
+
+        let (n, m, p, q, r) = (x[N], x[M], x[P], x[Q], x[R]);
+        let (u, v, w, zz) = (x[U], x[V], x[W], x[ZZ]);
+        let one = Complex64::new(1.0, 0.0);
+
+        let adag_a_z = n * z + r.conj() * alpha + r * alpha.conj() - 2.0 * alpha.norm_sqr() * z;
+        let a_a_z = m * z + 2.0 * r * alpha - 2.0 * alpha * alpha * z;
+        let adag_a_s = n * s + q * alpha + p * alpha.conj() - 2.0 * alpha.norm_sqr() * s;
+        let a_a_sdag = m * s.conj() + 2.0 * q.conj() * alpha - 2.0 * alpha * alpha * s.conj();
+        let adag_s_z = r.conj() * s + q * z + w * alpha.conj() - 2.0 * alpha.conj() * z * s;
+        let a_s_z = r * s + p * z + w * alpha - 2.0 * alpha * z * s;
+        let a_z_z = 2.0 * r * z + zz * alpha - 2.0 * alpha * z * z;
+        let adag_s_s = 2.0 * q * s + v * alpha.conj() - 2.0 * alpha.conj() * s * s;
+        let a_s_sdag = p * s.conj() + q.conj() * s + u * alpha - 2.0 * alpha * s.norm_sqr();
+
+        dx[ALPHA] = -(i * delta_c + kappa / 2.0) * alpha - i * g * emitters * s - i * eta;
+        dx[S] = -(i * delta_a + transverse) * s + i * g * r;
+        dx[Z] = 2.0 * i * g * (q - q.conj()) - gamma * (one + z);
+        dx[N] = -kappa * n + i * g * emitters * (q.conj() - q) + i * eta * (alpha - alpha.conj());
+        dx[M] =
+            -(2.0 * i * delta_c + kappa) * m - 2.0 * i * g * emitters * p - 2.0 * i * eta * alpha;
+        dx[P] = -(i * (delta_c + delta_a) + kappa / 2.0 + transverse) * p
+            - i * g * pairs * v
+            - i * eta * s
+            + i * g * a_a_z;
+        dx[Q] = (i * (delta_c - delta_a) - kappa / 2.0 - transverse) * q
+            + i * g * ((one + z) / 2.0 + pairs * u)
+            + i * eta * s
+            + i * g * adag_a_z;
+        dx[R] =
+            -(i * delta_c + kappa / 2.0 + gamma) * r + i * g * s - i * g * pairs * w - i * eta * z
+                + 2.0 * i * g * adag_a_s
+                - 2.0 * i * g * a_a_sdag
+                - gamma * alpha;
+        dx[U] = -2.0 * transverse * u - i * g * (adag_s_z - adag_s_z.conj());
+        dx[V] = -2.0 * (i * delta_a + transverse) * v + 2.0 * i * g * a_s_z;
+        dx[W] = -(i * delta_a + transverse + gamma) * w - gamma * s
+            + i * g * a_z_z
+            + 2.0 * i * g * adag_s_s
+            - 2.0 * i * g * a_s_sdag;
+        dx[ZZ] = 4.0 * i * g * (adag_s_z - adag_s_z.conj()) - 2.0 * gamma * (z + zz);
+        dx
+    }
+
+    fn record(&self, results: &mut SimulationResults, t: f64, x: &Moments) {
+        let real = |z: Complex64| Complex64::new(z.re, 0.0);
+        results.add_observable(CAVITY_FIELD, t, x[ALPHA]);
+        results.add_observable(PHOTON_NUMBER, t, real(x[N]));
+        results.add_observable(SIGMA_MINUS, t, x[S]);
+        results.add_observable(SIGMA_Z, t, real(x[Z]));
+        results.add_observable(CAVITY_EMITTER, t, x[Q]);
+        results.add_observable(EMITTER_CORRELATION, t, real(x[U]));
+    }
+}
+
+fn factorize(x: &mut Moments) {
+    let (alpha, s, z) = (x[ALPHA], x[S], x[Z]);
+    x[N] = Complex64::new(alpha.norm_sqr(), 0.0);
+    x[M] = alpha * alpha;
+    x[P] = alpha * s;
+    x[Q] = alpha.conj() * s;
+    x[R] = alpha * z;
+    x[U] = Complex64::new(s.norm_sqr(), 0.0);
+    x[V] = s * s;
+    x[W] = s * z;
+    x[ZZ] = z * z;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::hamiltonian::TimeIndependentHamiltonian;
+    use crate::core::lindblad::LindbladSolver;
+    use crate::core::DensityMatrix;
+    use crate::utils::math::trace;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_lossless_dynamics_conserve_excitations() {
+        let model = TavisCummings::new(50, 1.0, 1.0, 0.05);
+        for order in [CumulantOrder::MeanField, CumulantOrder::Second] {
+            let results = CumulantSolver::new(model.clone(), order)
+                .unwrap()
+                .with_initial_state(Complex64::new(0.5, 0.0), 2.5)
+                .run(20.0, 0.01, &TimeGrid::uniform(1.0))
+                .unwrap();
+            let photons = &results.series(PHOTON_NUMBER).unwrap().values;
+            let sz = &results.series(SIGMA_Z).unwrap().values;
+            let excitations: Vec<f64> = photons
+                .iter()
+                .zip(sz.iter())
+                .map(|(n, z)| n.re + 50.0 * (1.0 + z.re) / 2.0)
+                .collect();
+            for e in &excitations {
+                assert_relative_eq!(*e, excitations[0], epsilon = 1e-6);
+            }
+            assert!((sz[0].re - sz.last().unwrap().re).abs() > 1e-2);
+        }
+        assert!(CumulantOrder::parse("third").is_err());
+    }
+
+    #[test]
+    fn test_second_order_matches_exact_weak_drive() {
+        let (emitters, cavity) = (2, 5);
+        let model = TavisCummings::new(emitters, 1.0, 1.1, 0.3)
+            .with_drive(0.02, 1.0)
+            .with_losses(0.4, 0.2, 0.05);
+        let final_photons = |order| {
+            let results = CumulantSolver::new(model.clone(), order)
+                .unwrap()
+                .run(15.0, 0.01, &TimeGrid::uniform(15.0))
+                .unwrap();
+            let photons = results
+                .series(PHOTON_NUMBER)
+                .unwrap()
+                .values
+                .last()
+                .unwrap()
+                .re;
+            let z = results.series(SIGMA_Z).unwrap().values.last().unwrap().re;
+            (photons, z)
+        };
+
+        let dense = model.dense(cavity).unwrap();
+        let solver = LindbladSolver::new(
+            Box::new(TimeIndependentHamiltonian::new(dense.hamiltonian)),
+            dense.collapse_ops,
+        )
+        .unwrap();
+        let mut rho = QuantumState::ground_state(cavity << emitters).to_density_matrix();
+        for k in 0..1500 {
+            solver.step(&mut rho, k as f64 * 0.01, 0.01).unwrap();
+        }
+        let expect =
+            |op: &Array2<Complex64>, rho: &DensityMatrix| trace(&op.dot(rho.data()).view());
+
+        let exact = expect(&dense.photon_number, &rho).re;
+        let (photons, z) = final_photons(CumulantOrder::Second);
+        assert_relative_eq!(photons, exact, max_relative = 5e-3);
+        assert_relative_eq!(z, expect(&dense.sigma_z, &rho).re, epsilon = 1e-5);
+
+        let (mean_field, _) = final_photons(CumulantOrder::MeanField);
+        assert!((mean_field - exact).abs() > 10.0 * (photons - exact).abs());
+    }
+}
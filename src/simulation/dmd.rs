@@ -0,0 +1,308 @@
+use crate::simulation::SimulationResults;
+use crate::utils::math::{complex_eigenvalues, eigenvector, hermitian_eigen, solve};
+use crate::utils::{Error, Result};
+use ndarray::{Array1, Array2};
+use num_complex::Complex64;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+
+pub const STATES_SOURCE: &str = "states";
+pub const OBSERVABLES_SOURCE: &str = "observables";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DmdOptions {
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub rank: usize,
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+}
+
+fn default_tolerance() -> f64 {
+    1e-6
+}
+
+impl Default for DmdOptions {
+    fn default() -> Self {
+        Self {
+            source: None,
+            rank: 0,
+            tolerance: default_tolerance(),
+        }
+    }
+}
+
+impl DmdOptions {
+    pub fn validate(&self) -> Result<()> {
+        if !(self.tolerance > 0.0 && self.tolerance < 1.0) {
+            return Err(Error::InvalidParameter(format!(
+                "DMD tolerance must be in (0, 1), got {}",
+                self.tolerance
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DmdMode {
+    pub eigenvalue: Complex64,
+    pub frequency: f64,
+    pub decay_rate: f64,
+    pub amplitude: Complex64,
+    pub shape: Vec<Complex64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Dmd {
+    pub t0: f64,
+    pub dt: f64,
+    pub modes: Vec<DmdMode>,
+}
+
+impl Dmd {
+    pub fn compute(
+        times: &[f64],
+        snapshots: &[Vec<Complex64>],
+        options: &DmdOptions,
+    ) -> Result<Self> {
+        options.validate()?;
+        if snapshots.len() < 3 || times.len() != snapshots.len() {
+            return Err(Error::InvalidParameter(format!(
+                "DMD needs at least three snapshots with matching times, got {} snapshots and {} \
+                 times",
+                snapshots.len(),
+                times.len()
+            )));
+        }
+        let dt = times[1] - times[0];
+        if times
+            .windows(2)
+            .any(|w| ((w[1] - w[0]) - dt).abs() > 1e-9 * dt.abs().max(1.0))
+        {
+            return Err(Error::InvalidParameter(
+                "DMD requires uniformly sampled snapshots".to_string(),
+            ));
+        }
+        let n = snapshots[0].len();
+        if let Some(other) = snapshots.iter().find(|s| s.len() != n) {
+            return Err(Error::dimension_mismatch(n, other.len()));
+        }
+
+        let m = snapshots.len() - 1;
+        let x1 = Array2::from_shape_fn((n, m), |(i, k)| snapshots[k][i]);
+        let x2 = Array2::from_shape_fn((n, m), |(i, k)| snapshots[k + 1][i]);
+        let adjoint = |a: &Array2<Complex64>| a.t().mapv(|z| z.conj());
+
+        let (sigma_sq, u) = if n <= m {
+            let (values, vectors) = hermitian_eigen(&x1.dot(&adjoint(&x1)).view());
+            (values, vectors)
+        } else {
+            let (values, v) = hermitian_eigen(&adjoint(&x1).dot(&x1).view());
+            let mut u = x1.dot(&v);
+            for (k, &value) in values.iter().enumerate() {
+                let scale = value.max(0.0).sqrt();
+                u.column_mut(k)
+                    .mapv_inplace(|z| if scale > 0.0 { z / scale } else { z });
+            }
+            (values, u)
+        };
+
+        let largest = sigma_sq.iter().copied().fold(0.0, f64::max);
+        if largest <= 0.0 {
+            return Err(Error::Numerical("DMD snapshots are all zero".to_string()));
+        }
+        let mut kept: Vec<usize> = (0..sigma_sq.len())
+            .rev()
+            .filter(|&k| sigma_sq[k] > options.tolerance.powi(2) * largest)
+            .collect();
+        if options.rank > 0 {
+            kept.truncate(options.rank);
+        }
+        let r = kept.len();
+        let u = Array2::from_shape_fn((n, r), |(i, k)| u[[i, kept[k]]]);
+        let inverse_sigma_sq = Array2::from_shape_fn((r, r), |(i, k)| {
+            if i == k {
+                Complex64::new(1.0 / sigma_sq[kept[k]], 0.0)
+            } else {
+                Complex64::new(0.0, 0.0)
+            }
+        });
+
+        let u_adjoint = adjoint(&u);
+        let reduced = u_adjoint
+            .dot(&x2)
+            .dot(&adjoint(&x1))
+            .dot(&u)
+            .dot(&inverse_sigma_sq);
+        let eigenvalues = complex_eigenvalues(&reduced.view())?;
+        let eigenvectors: Vec<Array1<Complex64>> = eigenvalues
+            .iter()
+            .map(|&mu| eigenvector(&reduced.view(), mu))
+            .collect();
+        let w = Array2::from_shape_fn((r, r), |(i, k)| eigenvectors[k][i]);
+        let x0 = Array1::from(snapshots[0].clone());
+        let amplitudes = solve(&w, &u_adjoint.dot(&x0));
+        let shapes = u.dot(&w);
+
+        let mut modes: Vec<DmdMode> = eigenvalues
+            .iter()
+            .enumerate()
+            .map(|(k, &mu)| {
+                let omega = mu.ln() / dt;
+                DmdMode {
+                    eigenvalue: mu,
+                    frequency: omega.im,
+                    decay_rate: -omega.re,
+                    amplitude: amplitudes[k],
+                    shape: shapes.column(k).to_vec(),
+                }
+            })
+            .collect();
+        modes.sort_by(|a, b| b.amplitude.norm().total_cmp(&a.amplitude.norm()));
+
+        Ok(Self {
+            t0: times[0],
+            dt,
+            modes,
+        })
+    }
+
+    pub fn from_results(results: &SimulationResults, options: &DmdOptions) -> Result<Self> {
+        let Some(source) = options.source.as_deref() else {
+            return Err(Error::Config(
+                "DMD source must be 'states', 'observables' or a vector observable".to_string(),
+            ));
+        };
+        match source {
+            STATES_SOURCE => {
+                let snapshots = results.snapshots();
+                let times: Vec<f64> = snapshots.iter().map(|s| s.time).collect();
+                let columns: Vec<Vec<Complex64>> =
+                    snapshots.iter().map(|s| s.amplitudes.clone()).collect();
+                Self::compute(&times, &columns, options)
+            }
+            OBSERVABLES_SOURCE => {
+                let series: Vec<_> = results.iter_series().collect();
+                let Some(first) = series.first() else {
+                    return Err(Error::InvalidParameter(
+                        "No scalar observables recorded for DMD".to_string(),
+                    ));
+                };
+                for other in &series {
+                    super::ensemble::check_times(other.name, first.times, other.times)?;
+                }
+                let columns: Vec<Vec<Complex64>> = (0..first.len())
+                    .map(|k| series.iter().map(|s| s.values[k]).collect())
+                    .collect();
+                Self::compute(first.times, &columns, options)
+            }
+            name => {
+                let series = results.vector_series(name).ok_or_else(|| {
+                    Error::InvalidParameter(format!("Unknown vector observable '{}'", name))
+                })?;
+                let columns: Vec<Vec<Complex64>> =
+                    (0..series.len()).map(|k| series.row(k).to_vec()).collect();
+                Self::compute(series.times, &columns, options)
+            }
+        }
+    }
+
+    pub fn rank(&self) -> usize {
+        self.modes.len()
+    }
+
+    pub fn predict(&self, t: f64) -> Vec<Complex64> {
+        let steps = (t - self.t0) / self.dt;
+        let n = self.modes.first().map_or(0, |mode| mode.shape.len());
+        let mut state = vec![Complex64::new(0.0, 0.0); n];
+        for mode in &self.modes {
+            let weight = mode.amplitude * (mode.eigenvalue.ln() * steps).exp();
+            for (x, phi) in state.iter_mut().zip(&mode.shape) {
+                *x += phi * weight;
+            }
+        }
+        state
+    }
+
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(
+            writer,
+            "mode,frequency,decay_rate,amplitude,eigenvalue_re,eigenvalue_im"
+        )?;
+        for (k, mode) in self.modes.iter().enumerate() {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{}",
+                k,
+                mode.frequency,
+                mode.decay_rate,
+                mode.amplitude.norm(),
+                mode.eigenvalue.re,
+                mode.eigenvalue.im
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_dmd_recovers_damped_oscillations() {
+        let rates = [(0.05, 1.3), (0.2, -0.4)];
+        let shapes = [[1.0, 0.5, -0.2, 0.1], [0.3, -1.0, 0.4, 0.8]];
+        let dt = 0.1;
+        let times: Vec<f64> = (0..120).map(|k| k as f64 * dt).collect();
+        let snapshots: Vec<Vec<Complex64>> = times
+            .iter()
+            .map(|&t| {
+                (0..4)
+                    .map(|i| {
+                        rates
+                            .iter()
+                            .zip(&shapes)
+                            .map(|(&(gamma, omega), shape)| {
+                                Complex64::new(-gamma * t, omega * t).exp() * shape[i]
+                            })
+                            .sum()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let dmd = Dmd::compute(&times, &snapshots, &DmdOptions::default()).unwrap();
+        assert_eq!(dmd.rank(), 2);
+        for &(gamma, omega) in &rates {
+            let mode = dmd
+                .modes
+                .iter()
+                .find(|mode| (mode.frequency - omega).abs() < 1e-6)
+                .unwrap();
+            assert_relative_eq!(mode.decay_rate, gamma, epsilon = 1e-6);
+        }
+
+        let future = 20.0;
+        let predicted = dmd.predict(future);
+        for (i, value) in predicted.iter().enumerate() {
+            let exact: Complex64 = rates
+                .iter()
+                .zip(&shapes)
+                .map(|(&(gamma, omega), shape)| {
+                    Complex64::new(-gamma * future, omega * future).exp() * shape[i]
+                })
+                .sum();
+            assert_relative_eq!((value - exact).norm(), 0.0, epsilon = 1e-6);
+        }
+
+        let uneven = [0.0, 0.1, 0.3];
+        assert!(Dmd::compute(&uneven, &snapshots[..3], &DmdOptions::default()).is_err());
+    }
+}
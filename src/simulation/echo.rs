@@ -0,0 +1,314 @@
+use crate::core::{integrator, Hamiltonian, Integrator, IntegratorType, Observable, QuantumState};
+use crate::simulation::{SimulationResults, TimeGrid};
+use crate::utils::resources::ResourceMeter;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+
+pub const ECHO_FIDELITY: &str = "echo_fidelity";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reversal {
+    Negate,
+    Conjugate,
+}
+
+impl Reversal {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "negate" => Ok(Self::Negate),
+            "conjugate" | "phase_conjugate" => Ok(Self::Conjugate),
+            other => Err(Error::Config(format!(
+                "Unknown echo reversal '{}', expected negate or conjugate",
+                other
+            ))),
+        }
+    }
+}
+
+struct ReversedHamiltonian<'a> {
+    base: &'a dyn Hamiltonian,
+    pivot: f64,
+    scale: f64,
+    conjugate: bool,
+}
+
+impl Hamiltonian for ReversedHamiltonian<'_> {
+    fn dim(&self) -> usize {
+        self.base.dim()
+    }
+
+    fn compute(&self, t: f64, out: &mut Array2<Complex64>) {
+        self.base.compute(self.pivot - t, out);
+        if self.conjugate {
+            out.mapv_inplace(|z| z.conj());
+        }
+        out.mapv_inplace(|z| z * self.scale);
+    }
+
+    fn is_time_independent(&self) -> bool {
+        self.base.is_time_independent()
+    }
+}
+
+struct Kick<'a> {
+    operator: &'a Array2<Complex64>,
+}
+
+impl Hamiltonian for Kick<'_> {
+    fn dim(&self) -> usize {
+        self.operator.nrows()
+    }
+
+    fn compute(&self, _t: f64, out: &mut Array2<Complex64>) {
+        out.assign(self.operator);
+    }
+
+    fn is_time_independent(&self) -> bool {
+        true
+    }
+}
+
+pub struct EchoProtocol {
+    hamiltonian: Box<dyn Hamiltonian>,
+    forward_time: f64,
+    timestep: f64,
+    integrator: Box<dyn Integrator>,
+    time_grid: TimeGrid,
+    reversal: Reversal,
+    reversal_error: f64,
+    kick: Option<(Box<dyn Observable>, f64)>,
+    observables: Vec<(String, Box<dyn Observable>)>,
+}
+
+impl EchoProtocol {
+    pub fn new(
+        hamiltonian: Box<dyn Hamiltonian>,
+        forward_time: f64,
+        timestep: f64,
+        integrator_type: IntegratorType,
+    ) -> Result<Self> {
+        if forward_time <= 0.0 || timestep <= 0.0 {
+            return Err(Error::InvalidParameter(format!(
+                "Forward time and timestep must be positive, got {} and {}",
+                forward_time, timestep
+            )));
+        }
+
+        Ok(Self {
+            hamiltonian,
+            forward_time,
+            timestep,
+            integrator: integrator::create_integrator(integrator_type),
+            time_grid: TimeGrid::uniform(timestep),
+            reversal: Reversal::Negate,
+            reversal_error: 0.0,
+            kick: None,
+            observables: Vec::new(),
+        })
+    }
+
+    pub fn with_time_grid(mut self, time_grid: TimeGrid) -> Result<Self> {
+        time_grid.validate(2.0 * self.forward_time)?;
+        self.time_grid = time_grid;
+        Ok(self)
+    }
+
+    pub fn with_reversal(mut self, reversal: Reversal) -> Self {
+        self.reversal = reversal;
+        self
+    }
+
+    pub fn with_reversal_error(mut self, reversal_error: f64) -> Self {
+        self.reversal_error = reversal_error;
+        self
+    }
+
+    pub fn with_kick(mut self, operator: impl Observable + 'static, strength: f64) -> Result<Self> {
+        if operator.dim() != self.hamiltonian.dim() {
+            return Err(Error::dimension_mismatch(
+                self.hamiltonian.dim(),
+                operator.dim(),
+            ));
+        }
+        self.kick = Some((Box::new(operator), strength));
+        Ok(self)
+    }
+
+    pub fn with_observable(
+        mut self,
+        name: impl Into<String>,
+        observable: impl Observable + 'static,
+    ) -> Self {
+        self.observables.push((name.into(), Box::new(observable)));
+        self
+    }
+
+    pub fn run(&self, psi0: &QuantumState) -> Result<SimulationResults> {
+        let dim = self.hamiltonian.dim();
+        if psi0.dim() != dim {
+            return Err(Error::dimension_mismatch(dim, psi0.dim()));
+        }
+        if let Some((name, _)) = self.observables.iter().find(|(_, o)| o.dim() != dim) {
+            return Err(Error::InvalidParameter(format!(
+                "Observable '{}' does not match the Hamiltonian dimension {}",
+                name, dim
+            )));
+        }
+
+        let meter = ResourceMeter::start();
+        let pivot = 2.0 * self.forward_time;
+        let reversed = ReversedHamiltonian {
+            base: self.hamiltonian.as_ref(),
+            pivot,
+            scale: match self.reversal {
+                Reversal::Negate => -(1.0 + self.reversal_error),
+                Reversal::Conjugate => 1.0 + self.reversal_error,
+            },
+            conjugate: self.reversal == Reversal::Conjugate,
+        };
+        let target = match self.reversal {
+            Reversal::Negate => psi0.data().clone(),
+            Reversal::Conjugate => psi0.data().mapv(|z| z.conj()),
+        };
+
+        let mut results = SimulationResults::new();
+        let mut state = psi0.clone();
+        let mut t = 0.0;
+        let mut reversed_yet = false;
+        for t_out in self.time_grid.output_times(pivot, self.timestep) {
+            if !reversed_yet && t_out > self.forward_time {
+                self.evolve(self.hamiltonian.as_ref(), &mut state, t, self.forward_time)?;
+                t = self.forward_time;
+                state = self.reverse(state)?;
+                reversed_yet = true;
+            }
+            if reversed_yet {
+                self.evolve(&reversed, &mut state, t, t_out)?;
+            } else {
+                self.evolve(self.hamiltonian.as_ref(), &mut state, t, t_out)?;
+            }
+            t = t_out;
+
+            let reference = if reversed_yet { &target } else { psi0.data() };
+            let overlap: Complex64 = reference
+                .iter()
+                .zip(state.data())
+                .map(|(a, b)| a.conj() * b)
+                .sum();
+            results.add_observable(
+                ECHO_FIDELITY,
+                t_out,
+                Complex64::new(overlap.norm_sqr(), 0.0),
+            );
+            for (name, observable) in &self.observables {
+                results.add_observable(name, t_out, observable.expectation_pure(&state));
+            }
+        }
+
+        results.set_resources(meter.finish());
+        Ok(results)
+    }
+
+    pub fn final_fidelity(results: &SimulationResults) -> Option<f64> {
+        results
+            .series(ECHO_FIDELITY)
+            .and_then(|series| series.values.last().map(|v| v.re))
+    }
+
+    fn reverse(&self, mut state: QuantumState) -> Result<QuantumState> {
+        if let Some((operator, strength)) = &self.kick {
+            let kick = Kick {
+                operator: operator.matrix(),
+            };
+            self.evolve(&kick, &mut state, 0.0, *strength)?;
+        }
+        Ok(match self.reversal {
+            Reversal::Negate => state,
+            Reversal::Conjugate => QuantumState::new(state.data().mapv(|z| z.conj()))?,
+        })
+    }
+
+    fn evolve(
+        &self,
+        hamiltonian: &dyn Hamiltonian,
+        state: &mut QuantumState,
+        from: f64,
+        to: f64,
+    ) -> Result<()> {
+        self.integrator.clear_cache();
+        let tolerance = 1e-9 * self.timestep;
+        let direction = if to >= from { 1.0 } else { -1.0 };
+        let mut t = from;
+        while (to - t) * direction > tolerance {
+            let dt = self.timestep.min((to - t) * direction) * direction;
+            self.integrator.step(hamiltonian, state, t, dt)?;
+            t += dt;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observables::MatrixObservable;
+    use crate::core::systems::DrivenTLS;
+    use approx::assert_relative_eq;
+    use ndarray::array;
+
+    fn sigma_z() -> MatrixObservable {
+        MatrixObservable::new(array![
+            [Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
+            [Complex64::new(0.0, 0.0), Complex64::new(-1.0, 0.0)],
+        ])
+    }
+
+    #[test]
+    fn test_perfect_reversal_returns_initial_state() {
+        let psi0 = QuantumState::bloch(2, 0.7, 0.3).unwrap();
+        for reversal in [Reversal::Negate, Reversal::Conjugate] {
+            let protocol = EchoProtocol::new(
+                Box::new(DrivenTLS::new(5.0, 4.8, 0.6)),
+                3.0,
+                0.005,
+                IntegratorType::RK4,
+            )
+            .unwrap()
+            .with_reversal(reversal);
+            let results = protocol.run(&psi0).unwrap();
+            let fidelity = EchoProtocol::final_fidelity(&results).unwrap();
+            assert_relative_eq!(fidelity, 1.0, epsilon = 1e-8);
+        }
+        assert!(Reversal::parse("mirror").is_err());
+    }
+
+    #[test]
+    fn test_kick_reduces_echo_fidelity() {
+        let psi0 = QuantumState::ground_state(2);
+        let epsilon = 0.3;
+        let protocol = EchoProtocol::new(
+            Box::new(DrivenTLS::new(5.0, 5.0, 0.4)),
+            2.0,
+            0.005,
+            IntegratorType::RK4,
+        )
+        .unwrap()
+        .with_observable("sz", sigma_z())
+        .with_kick(sigma_z(), epsilon)
+        .unwrap();
+        let results = protocol.run(&psi0).unwrap();
+
+        let sz = results.series("sz").unwrap();
+        let turn = sz
+            .times
+            .iter()
+            .position(|&t| (t - 2.0).abs() < 1e-9)
+            .unwrap();
+        let z = sz.values[turn].re;
+        let expected = 1.0 - epsilon.sin().powi(2) * (1.0 - z * z);
+        let fidelity = EchoProtocol::final_fidelity(&results).unwrap();
+        assert_relative_eq!(fidelity, expected, epsilon = 1e-6);
+        assert!(fidelity < 0.999);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::simulation::{SimulationResults, SimulationRunner};
+use crate::utils::resources::ResourceMeter;
+use crate::utils::{Error, Result};
+use num_complex::Complex64;
+use rayon::prelude::*;
+
+const TIME_TOLERANCE: f64 = 1e-9;
+
+pub fn run_ensemble<F>(realizations: usize, build: F) -> Result<SimulationResults>
+where
+    F: Fn(usize) -> Result<SimulationRunner> + Sync,
+{
+    let meter = ResourceMeter::start();
+    let members = (0..realizations)
+        .into_par_iter()
+        .map(|k| {
+            let _span = tracing::debug_span!("realization", index = k).entered();
+            build(k)?.run()
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut averaged = average(&members)?;
+    averaged.set_resources(meter.finish());
+    Ok(averaged)
+}
+
+pub fn average(members: &[SimulationResults]) -> Result<SimulationResults> {
+    let Some(first) = members.first() else {
+        return Err(Error::InvalidParameter(
+            "Ensemble average needs at least one realization".to_string(),
+        ));
+    };
+    let count = members.len() as f64;
+    let mut averaged = SimulationResults::new();
+
+    for series in first.iter_series() {
+        let all = members
+            .iter()
+            .map(|member| {
+                let other = member.series(series.name).ok_or_else(|| {
+                    Error::InvalidParameter(format!(
+                        "Observable '{}' is missing from a realization",
+                        series.name
+                    ))
+                })?;
+                check_times(series.name, series.times, other.times)?;
+                Ok(other.values)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let std_name = format!("{}_std", series.name);
+        for (k, &t) in series.times.iter().enumerate() {
+            let mean = all.iter().map(|values| values[k]).sum::<Complex64>() / count;
+            let variance = all
+                .iter()
+                .map(|values| (values[k] - mean).norm_sqr())
+                .sum::<f64>()
+                / (count - 1.0).max(1.0);
+            averaged.add_observable(series.name, t, mean);
+            averaged.add_observable(&std_name, t, Complex64::new(variance.sqrt(), 0.0));
+        }
+    }
+
+    for series in first.iter_vector_series() {
+        let all = members
+            .iter()
+            .map(|member| {
+                let other = member
+                    .vector_series(series.name)
+                    .filter(|other| other.width == series.width)
+                    .ok_or_else(|| {
+                        Error::InvalidParameter(format!(
+                            "Vector observable '{}' is missing or has a different width in a \
+                             realization",
+                            series.name
+                        ))
+                    })?;
+                check_times(series.name, series.times, other.times)?;
+                Ok(other)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for (k, &t) in series.times.iter().enumerate() {
+            let mut mean = vec![Complex64::new(0.0, 0.0); series.width];
+            for other in &all {
+                for (m, v) in mean.iter_mut().zip(other.row(k)) {
+                    *m += v / count;
+                }
+            }
+            averaged.add_vector_observable(series.name, t, &mean)?;
+        }
+        if let Some((rows, cols)) = series.shape {
+            averaged.set_vector_shape(series.name, rows, cols)?;
+        }
+    }
+
+    Ok(averaged)
+}
+
+pub(crate) fn check_times(name: &str, expected: &[f64], actual: &[f64]) -> Result<()> {
+    if expected.len() != actual.len() {
+        return Err(Error::DimensionMismatch {
+            expected: expected.len(),
+            actual: actual.len(),
+        });
+    }
+    if expected
+        .iter()
+        .zip(actual)
+        .any(|(a, b)| (a - b).abs() > TIME_TOLERANCE * a.abs().max(1.0))
+    {
+        return Err(Error::InvalidParameter(format!(
+            "Realizations sample '{}' on different time grids",
+            name
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::lattice::{DisorderConfig, Lattice};
+    use crate::core::observables::PopulationOperator;
+    use crate::core::QuantumState;
+    use crate::simulation::SimulationBuilder;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_average_reports_mean_and_spread() {
+        let mut a = SimulationResults::new();
+        let mut b = SimulationResults::new();
+        for (k, t) in [0.0, 0.5].into_iter().enumerate() {
+            a.add_observable("x", t, Complex64::new(k as f64, 0.0));
+            b.add_observable("x", t, Complex64::new(k as f64 + 2.0, 0.0));
+        }
+        let averaged = average(&[a.clone(), b]).unwrap();
+        assert_eq!(averaged.series("x").unwrap().to_vec_real(), [1.0, 2.0]);
+        assert_relative_eq!(
+            averaged.series("x_std").unwrap().values[1].re,
+            2.0_f64.sqrt()
+        );
+
+        let mut shifted = SimulationResults::new();
+        shifted.add_observable("x", 0.0, Complex64::new(0.0, 0.0));
+        shifted.add_observable("x", 0.7, Complex64::new(0.0, 0.0));
+        assert!(average(&[a, shifted]).is_err());
+        assert!(average(&[]).is_err());
+    }
+
+    #[test]
+    fn test_disorder_localizes_excitation_on_chain() {
+        let sites = 9;
+        let center = sites / 2 + 1;
+        let clean = Lattice::chain(sites, 1.0).unwrap();
+        let return_probability = |strength: f64| {
+            let disorder = DisorderConfig {
+                onsite: strength,
+                seed: Some(11),
+                realizations: 16,
+                ..Default::default()
+            };
+            let averaged = run_ensemble(disorder.realizations, |k| {
+                SimulationBuilder::new()
+                    .hamiltonian(disorder.realization(&clean, k)?.hamiltonian(0.0))
+                    .initial_state(QuantumState::fock(sites + 1, center)?)
+                    .duration(20.0)
+                    .timestep(0.05)
+                    .observable("p_center", PopulationOperator::new(sites + 1, center)?)
+                    .build()
+            })
+            .unwrap();
+            let p = averaged.series("p_center").unwrap().to_vec_real();
+            p[p.len() / 2..].iter().sum::<f64>() / (p.len() - p.len() / 2) as f64
+        };
+
+        let (ordered, disordered) = (return_probability(0.0), return_probability(10.0));
+        assert!(ordered < 0.3, "ordered return probability {}", ordered);
+        assert!(
+            disordered > 0.6,
+            "disordered return probability {}",
+            disordered
+        );
+    }
+}
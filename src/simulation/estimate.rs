@@ -0,0 +1,96 @@
+use crate::data::Config;
+use crate::simulation::SimulationBuilder;
+use crate::utils::performance;
+use crate::utils::Result;
+
+const PROBE_STEPS: usize = 20;
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CostEstimate {
+    pub seconds: f64,
+    pub memory_bytes: u64,
+}
+
+impl CostEstimate {
+    // Builds the run and times a short probe of it, so the estimate reflects this
+    // machine. Memory comes from the solver's working-set estimate.
+    pub fn dry_run(config: &Config) -> Result<Self> {
+        let runner = SimulationBuilder::from_config(config)?;
+        Ok(Self {
+            seconds: runner.estimate_seconds(PROBE_STEPS)?,
+            memory_bytes: performance::estimated_memory_bytes(config),
+        })
+    }
+
+    // Runs may overlap, so the memory of a batch is that of its largest run.
+    pub fn dry_run_all<'a>(configs: impl IntoIterator<Item = &'a Config>) -> Result<Self> {
+        configs.into_iter().try_fold(
+            Self {
+                seconds: 0.0,
+                memory_bytes: 0,
+            },
+            |total, config| {
+                let estimate = Self::dry_run(config)?;
+                Ok(Self {
+                    seconds: total.seconds + estimate.seconds,
+                    memory_bytes: total.memory_bytes.max(estimate.memory_bytes),
+                })
+            },
+        )
+    }
+
+    pub fn memory_mb(&self) -> f64 {
+        megabytes(self.memory_bytes)
+    }
+
+    pub fn describe(&self) -> String {
+        format!(
+            "about {} and {:.1} MB",
+            format_seconds(self.seconds),
+            self.memory_mb()
+        )
+    }
+}
+
+pub fn megabytes(bytes: u64) -> f64 {
+    bytes as f64 / BYTES_PER_MB
+}
+
+pub fn format_seconds(seconds: f64) -> String {
+    if seconds < 120.0 {
+        format!("{:.1} s", seconds)
+    } else if seconds < 7200.0 {
+        format!("{:.1} min", seconds / 60.0)
+    } else {
+        format!("{:.1} h", seconds / 3600.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dry_run_scales_with_duration() {
+        let mut config = Config::generate_template("driven_tls").unwrap();
+        config.simulation.timestep = 0.01;
+        config.simulation.duration = 1.0;
+        let short = CostEstimate::dry_run(&config).unwrap();
+        config.simulation.duration = 1e6;
+        let long = CostEstimate::dry_run(&config).unwrap();
+        assert!(short.seconds > 0.0);
+        assert!(long.seconds > 100.0 * short.seconds);
+        assert!(long.memory_bytes > short.memory_bytes);
+
+        let both = CostEstimate::dry_run_all([&config, &config]).unwrap();
+        assert_eq!(both.memory_bytes, long.memory_bytes);
+        assert!(both.seconds > short.seconds);
+
+        assert_eq!(format_seconds(30.0), "30.0 s");
+        assert_eq!(format_seconds(600.0), "10.0 min");
+        assert_eq!(format_seconds(36000.0), "10.0 h");
+        config.kicks.enabled = true;
+        assert!(CostEstimate::dry_run(&config).is_err());
+    }
+}
@@ -0,0 +1,233 @@
+use crate::simulation::TimeSeries;
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventSpec {
+    pub observable: String,
+    #[serde(default)]
+    pub thresholds: Vec<f64>,
+    #[serde(default = "default_direction")]
+    pub direction: String,
+    #[serde(default)]
+    pub extrema: bool,
+}
+
+fn default_direction() -> String {
+    "both".to_string()
+}
+
+impl EventSpec {
+    pub fn validate(&self) -> Result<()> {
+        Direction::parse(&self.direction)?;
+        if self.thresholds.is_empty() && !self.extrema {
+            return Err(Error::Config(format!(
+                "Event detector for '{}' needs thresholds or extrema = true",
+                self.observable
+            )));
+        }
+        if let Some(t) = self.thresholds.iter().find(|t| !t.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "Event threshold for '{}' must be finite, got {}",
+                self.observable, t
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Rising,
+    Falling,
+    Both,
+}
+
+impl Direction {
+    fn parse(name: &str) -> Result<Self> {
+        match name {
+            "rising" => Ok(Direction::Rising),
+            "falling" => Ok(Direction::Falling),
+            "both" => Ok(Direction::Both),
+            other => Err(Error::Config(format!(
+                "Unknown crossing direction '{}', expected rising, falling or both",
+                other
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Rising,
+    Falling,
+    Maximum,
+    Minimum,
+}
+
+impl EventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::Rising => "rising",
+            EventKind::Falling => "falling",
+            EventKind::Maximum => "maximum",
+            EventKind::Minimum => "minimum",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub observable: String,
+    pub kind: EventKind,
+    pub time: f64,
+    pub value: f64,
+    pub interval: Option<f64>,
+}
+
+pub fn detect(series: &TimeSeries, spec: &EventSpec) -> Result<Vec<Event>> {
+    let direction = Direction::parse(&spec.direction)?;
+    let times = series.times;
+    let values = series.to_vec_real();
+    let mut events = Vec::new();
+
+    for k in 1..values.len() {
+        let (t0, t1, v0, v1) = (times[k - 1], times[k], values[k - 1], values[k]);
+        for &threshold in &spec.thresholds {
+            let kind = if v0 < threshold && v1 >= threshold {
+                EventKind::Rising
+            } else if v0 > threshold && v1 <= threshold {
+                EventKind::Falling
+            } else {
+                continue;
+            };
+            let wanted = match direction {
+                Direction::Both => true,
+                Direction::Rising => kind == EventKind::Rising,
+                Direction::Falling => kind == EventKind::Falling,
+            };
+            if wanted {
+                let time = t0 + (threshold - v0) * (t1 - t0) / (v1 - v0);
+                events.push(Event {
+                    observable: series.name.to_string(),
+                    kind,
+                    time,
+                    value: threshold,
+                    interval: interval_since(&events, kind, time, Some(threshold)),
+                });
+            }
+        }
+
+        if spec.extrema && k + 1 < values.len() {
+            let v2 = values[k + 1];
+            let kind = if v1 > v0 && v1 >= v2 {
+                EventKind::Maximum
+            } else if v1 < v0 && v1 <= v2 {
+                EventKind::Minimum
+            } else {
+                continue;
+            };
+            let (time, value) = parabola_vertex([t0, t1, times[k + 1]], [v0, v1, v2]);
+            events.push(Event {
+                observable: series.name.to_string(),
+                kind,
+                time,
+                value,
+                interval: interval_since(&events, kind, time, None),
+            });
+        }
+    }
+
+    Ok(events)
+}
+
+fn interval_since(
+    events: &[Event],
+    kind: EventKind,
+    time: f64,
+    threshold: Option<f64>,
+) -> Option<f64> {
+    events
+        .iter()
+        .rev()
+        .find(|e| e.kind == kind && threshold.is_none_or(|t| e.value == t))
+        .map(|e| time - e.time)
+}
+
+fn parabola_vertex(t: [f64; 3], v: [f64; 3]) -> (f64, f64) {
+    let d01 = (v[1] - v[0]) / (t[1] - t[0]);
+    let d12 = (v[2] - v[1]) / (t[2] - t[1]);
+    let a = (d12 - d01) / (t[2] - t[0]);
+    if a.abs() < f64::EPSILON * (d01.abs() + d12.abs()).max(1.0) {
+        return (t[1], v[1]);
+    }
+    let b = d01 - a * (t[0] + t[1]);
+    let c = v[0] - a * t[0] * t[0] - b * t[0];
+    let vertex = (-b / (2.0 * a)).clamp(t[0], t[2]);
+    (vertex, a * vertex * vertex + b * vertex + c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use num_complex::Complex64;
+    use std::f64::consts::PI;
+
+    fn rabi_series(times: &[f64]) -> Vec<Complex64> {
+        times
+            .iter()
+            .map(|t| Complex64::new((0.5 * t).sin().powi(2), 0.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_crossings_are_interpolated() {
+        let times: Vec<f64> = (0..=200).map(|k| k as f64 * 0.1).collect();
+        let values = rabi_series(&times);
+        let series = TimeSeries {
+            name: "p1",
+            times: &times,
+            values: &values,
+        };
+        let spec = EventSpec {
+            observable: "p1".to_string(),
+            thresholds: vec![0.5],
+            direction: "rising".to_string(),
+            extrema: false,
+        };
+
+        let events = detect(&series, &spec).unwrap();
+        assert_eq!(events.len(), 3);
+        assert!(events.iter().all(|e| e.kind == EventKind::Rising));
+        assert_relative_eq!(events[0].time, PI / 2.0, epsilon = 1e-2);
+        assert_relative_eq!(events[1].interval.unwrap(), 2.0 * PI, epsilon = 1e-2);
+    }
+
+    #[test]
+    fn test_extrema_give_rabi_period() {
+        let times: Vec<f64> = (0..=300).map(|k| k as f64 * 0.07).collect();
+        let values = rabi_series(&times);
+        let series = TimeSeries {
+            name: "p1",
+            times: &times,
+            values: &values,
+        };
+        let spec = EventSpec {
+            observable: "p1".to_string(),
+            thresholds: Vec::new(),
+            direction: "both".to_string(),
+            extrema: true,
+        };
+
+        let events = detect(&series, &spec).unwrap();
+        let maxima: Vec<&Event> = events
+            .iter()
+            .filter(|e| e.kind == EventKind::Maximum)
+            .collect();
+        assert_relative_eq!(maxima[0].time, PI, epsilon = 1e-3);
+        assert_relative_eq!(maxima[0].value, 1.0, epsilon = 1e-3);
+        assert_relative_eq!(maxima[1].interval.unwrap(), 2.0 * PI, epsilon = 1e-3);
+        assert!(events.iter().any(|e| e.kind == EventKind::Minimum));
+    }
+}
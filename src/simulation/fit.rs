@@ -0,0 +1,373 @@
+use crate::simulation::TimeSeries;
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+const MAX_ITERATIONS: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FitSpec {
+    pub observable: String,
+    pub model: String,
+    #[serde(default)]
+    pub start: f64,
+}
+
+impl FitSpec {
+    pub fn validate(&self) -> Result<()> {
+        FitModel::parse(&self.model)?;
+        if !self.start.is_finite() || self.start < 0.0 {
+            return Err(Error::InvalidParameter(format!(
+                "Fit start time for '{}' must be non-negative, got {}",
+                self.observable, self.start
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitModel {
+    Exponential,
+    DampedSine,
+}
+
+impl FitModel {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "exponential" => Ok(FitModel::Exponential),
+            "damped_sine" => Ok(FitModel::DampedSine),
+            other => Err(Error::Config(format!(
+                "Unknown fit model '{}', expected exponential or damped_sine",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FitModel::Exponential => "exponential",
+            FitModel::DampedSine => "damped_sine",
+        }
+    }
+
+    fn evaluate(&self, p: &[f64], t: f64) -> f64 {
+        match self {
+            FitModel::Exponential => p[0] * (-p[1] * t).exp() + p[2],
+            FitModel::DampedSine => p[0] * (-p[1] * t).exp() * (p[3] * t + p[4]).cos() + p[2],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub value: f64,
+    pub uncertainty: f64,
+}
+
+impl std::fmt::Display for Estimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.6} ± {:.6}", self.value, self.uncertainty)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitResult {
+    pub observable: String,
+    pub model: FitModel,
+    pub amplitude: Estimate,
+    pub decay_time: Estimate,
+    pub offset: Estimate,
+    pub frequency: Option<Estimate>,
+    pub phase: Option<Estimate>,
+    pub rms_residual: f64,
+}
+
+impl FitResult {
+    pub fn describe(&self) -> String {
+        match (self.model, &self.frequency) {
+            (FitModel::DampedSine, Some(omega)) => {
+                format!("T2 = {}, Ω = {}", self.decay_time, omega)
+            }
+            _ => format!("T1 = {}", self.decay_time),
+        }
+    }
+}
+
+pub fn fit(series: &TimeSeries, spec: &FitSpec) -> Result<FitResult> {
+    let model = FitModel::parse(&spec.model)?;
+    let first = series.times.partition_point(|&t| t < spec.start);
+    let t0 = series.times.get(first).copied().unwrap_or(spec.start);
+    let times: Vec<f64> = series.times[first..].iter().map(|t| t - t0).collect();
+    let values: Vec<f64> = series.values[first..].iter().map(|v| v.re).collect();
+
+    let num_params = match model {
+        FitModel::Exponential => 3,
+        FitModel::DampedSine => 5,
+    };
+    if times.len() <= num_params {
+        return Err(Error::InvalidParameter(format!(
+            "Fitting '{}' needs more than {} samples after t = {}",
+            series.name, num_params, spec.start
+        )));
+    }
+
+    let guess = initial_guess(model, &times, &values);
+    let (params, covariance, rms_residual) = levenberg_marquardt(model, guess, &times, &values)?;
+    let estimate = |i: usize| Estimate {
+        value: params[i],
+        uncertainty: covariance[i][i].max(0.0).sqrt(),
+    };
+
+    let rate = estimate(1);
+    let decay_time = Estimate {
+        value: 1.0 / rate.value,
+        uncertainty: rate.uncertainty / (rate.value * rate.value),
+    };
+    let (frequency, phase) = match model {
+        FitModel::Exponential => (None, None),
+        FitModel::DampedSine => {
+            let omega = estimate(3);
+            let phase = estimate(4);
+            let absolute_phase = phase.value - omega.value * t0;
+            (
+                Some(Estimate {
+                    value: omega.value.abs(),
+                    uncertainty: omega.uncertainty,
+                }),
+                Some(Estimate {
+                    value: (absolute_phase * omega.value.signum())
+                        .rem_euclid(std::f64::consts::TAU),
+                    ..phase
+                }),
+            )
+        }
+    };
+
+    Ok(FitResult {
+        observable: series.name.to_string(),
+        model,
+        amplitude: estimate(0),
+        decay_time,
+        offset: estimate(2),
+        frequency,
+        phase,
+        rms_residual,
+    })
+}
+
+fn initial_guess(model: FitModel, times: &[f64], values: &[f64]) -> Vec<f64> {
+    let n = values.len();
+    let span = times[n - 1].max(f64::EPSILON);
+    let tail = &values[n - (n / 10).max(1)..];
+    let tail_mean = tail.iter().sum::<f64>() / tail.len() as f64;
+
+    match model {
+        FitModel::Exponential => {
+            let amplitude = values[0] - tail_mean;
+            let decay_time = times
+                .iter()
+                .zip(values)
+                .find(|(_, v)| (*v - tail_mean).abs() < amplitude.abs() / std::f64::consts::E)
+                .map_or(span, |(t, _)| t.max(f64::EPSILON));
+            vec![amplitude, 1.0 / decay_time, tail_mean]
+        }
+        FitModel::DampedSine => {
+            let mean = values.iter().sum::<f64>() / n as f64;
+            let crossings = values
+                .windows(2)
+                .filter(|w| (w[0] - mean) * (w[1] - mean) < 0.0)
+                .count()
+                .max(1);
+            let omega = std::f64::consts::PI * crossings as f64 / span;
+            let amplitude = values
+                .iter()
+                .map(|v| (v - mean).abs())
+                .fold(0.0, f64::max)
+                .max(f64::EPSILON);
+            let mut phase = ((values[0] - mean) / amplitude).clamp(-1.0, 1.0).acos();
+            if values[1] > values[0] {
+                phase = -phase;
+            }
+            vec![amplitude, 1.0 / span, mean, omega, phase]
+        }
+    }
+}
+
+type FitOutput = (Vec<f64>, Vec<Vec<f64>>, f64);
+
+fn levenberg_marquardt(
+    model: FitModel,
+    mut params: Vec<f64>,
+    times: &[f64],
+    values: &[f64],
+) -> Result<FitOutput> {
+    let n = params.len();
+    let residuals = |p: &[f64]| -> Vec<f64> {
+        times
+            .iter()
+            .zip(values)
+            .map(|(&t, &y)| y - model.evaluate(p, t))
+            .collect()
+    };
+    let cost = |r: &[f64]| r.iter().map(|x| x * x).sum::<f64>();
+    let jacobian = |p: &[f64]| -> Vec<Vec<f64>> {
+        times
+            .iter()
+            .map(|&t| {
+                (0..n)
+                    .map(|j| {
+                        let h = 1e-7 * p[j].abs().max(1e-3);
+                        let mut shifted = p.to_vec();
+                        shifted[j] += h;
+                        (model.evaluate(&shifted, t) - model.evaluate(p, t)) / h
+                    })
+                    .collect()
+            })
+            .collect()
+    };
+    let normal_matrix = |jac: &[Vec<f64>]| -> Vec<Vec<f64>> {
+        (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| jac.iter().map(|row| row[i] * row[j]).sum())
+                    .collect()
+            })
+            .collect()
+    };
+
+    let mut r = residuals(&params);
+    let mut current = cost(&r);
+    let mut lambda = 1e-3;
+
+    for _ in 0..MAX_ITERATIONS {
+        let jac = jacobian(&params);
+        let a = normal_matrix(&jac);
+        let g: Vec<f64> = (0..n)
+            .map(|i| jac.iter().zip(&r).map(|(row, ri)| row[i] * ri).sum())
+            .collect();
+
+        let mut improved = false;
+        while lambda < 1e12 {
+            let mut damped = a.clone();
+            for (i, row) in damped.iter_mut().enumerate() {
+                row[i] += lambda * a[i][i].max(1e-12);
+            }
+            let Some(step) = solve(damped, g.clone()) else {
+                lambda *= 10.0;
+                continue;
+            };
+            let candidate: Vec<f64> = params.iter().zip(&step).map(|(p, s)| p + s).collect();
+            let r_new = residuals(&candidate);
+            let c_new = cost(&r_new);
+            if c_new.is_finite() && c_new <= current {
+                let converged = current - c_new <= 1e-14 * current.max(f64::MIN_POSITIVE);
+                params = candidate;
+                r = r_new;
+                current = c_new;
+                lambda = (lambda / 10.0).max(1e-12);
+                improved = !converged;
+                break;
+            }
+            lambda *= 10.0;
+        }
+        if !improved {
+            break;
+        }
+    }
+
+    let dof = (times.len() - n) as f64;
+    let variance = current / dof;
+    let a = normal_matrix(&jacobian(&params));
+    let covariance: Vec<Vec<f64>> = (0..n)
+        .map(|i| {
+            let mut unit = vec![0.0; n];
+            unit[i] = 1.0;
+            solve(a.clone(), unit)
+                .map(|column| column.iter().map(|c| c * variance).collect())
+                .unwrap_or_else(|| vec![f64::INFINITY; n])
+        })
+        .collect();
+    let covariance = (0..n)
+        .map(|i| (0..n).map(|j| covariance[j][i]).collect())
+        .collect();
+
+    if params.iter().any(|p| !p.is_finite()) {
+        return Err(Error::Numerical("Fit did not converge".to_string()));
+    }
+    Ok((params, covariance, (current / times.len() as f64).sqrt()))
+}
+
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-300 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        for row in col + 1..n {
+            let factor = a[row][col] / a[col][col];
+            let (upper, lower) = a.split_at_mut(row);
+            for (x, p) in lower[0][col..].iter_mut().zip(&upper[col][col..]) {
+                *x -= factor * p;
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let sum: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use num_complex::Complex64;
+
+    fn fit_samples(model: &str, f: impl Fn(f64) -> f64) -> FitResult {
+        let times: Vec<f64> = (0..400).map(|k| k as f64 * 0.05).collect();
+        let values: Vec<Complex64> = times
+            .iter()
+            .enumerate()
+            .map(|(k, &t)| Complex64::new(f(t) + 1e-4 * ((k * 7919) % 13) as f64, 0.0))
+            .collect();
+        let series = TimeSeries {
+            name: "p",
+            times: &times,
+            values: &values,
+        };
+        let spec = FitSpec {
+            observable: "p".to_string(),
+            model: model.to_string(),
+            start: 0.0,
+        };
+        fit(&series, &spec).unwrap()
+    }
+
+    #[test]
+    fn test_exponential_fit_recovers_t1() {
+        let result = fit_samples("exponential", |t| 0.8 * (-t / 4.0).exp() + 0.1);
+        assert_relative_eq!(result.decay_time.value, 4.0, epsilon = 1e-2);
+        assert_relative_eq!(result.amplitude.value, 0.8, epsilon = 1e-2);
+        assert!(result.decay_time.uncertainty < 1e-2);
+        assert!(result.frequency.is_none());
+        assert!(result.describe().starts_with("T1 = "));
+    }
+
+    #[test]
+    fn test_damped_sine_fit_recovers_t2_and_frequency() {
+        let result = fit_samples("damped_sine", |t| {
+            0.5 * (-t / 6.0).exp() * (2.0 * t + 0.3).cos() + 0.5
+        });
+        assert_relative_eq!(result.frequency.unwrap().value, 2.0, epsilon = 1e-3);
+        assert_relative_eq!(result.decay_time.value, 6.0, epsilon = 5e-2);
+        assert_relative_eq!(result.offset.value, 0.5, epsilon = 1e-3);
+        assert!(result.frequency.unwrap().uncertainty < 1e-3);
+    }
+}
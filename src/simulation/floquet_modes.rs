@@ -0,0 +1,128 @@
+use crate::core::floquet::{high_frequency_hamiltonian, FloquetSpectrum};
+use crate::core::{Hamiltonian, QuantumState};
+use crate::utils::math::hermitian_eigen;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+pub struct FloquetModes {
+    period: f64,
+    num_steps: usize,
+    spectrum: OnceLock<FloquetSpectrum>,
+    computations: AtomicUsize,
+}
+
+impl FloquetModes {
+    pub fn new(period: f64, num_steps: usize) -> Self {
+        Self {
+            period,
+            num_steps,
+            spectrum: OnceLock::new(),
+            computations: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn period(&self) -> f64 {
+        self.period
+    }
+
+    pub fn spectrum(&self, hamiltonian: &dyn Hamiltonian) -> Result<&FloquetSpectrum> {
+        if let Some(spectrum) = self.spectrum.get() {
+            return Ok(spectrum);
+        }
+        let _span = tracing::debug_span!("floquet_decomposition").entered();
+        let spectrum = FloquetSpectrum::compute(hamiltonian, self.period, self.num_steps)?;
+        self.computations.fetch_add(1, Ordering::Relaxed);
+        Ok(self.spectrum.get_or_init(|| spectrum))
+    }
+
+    pub fn populations(
+        &self,
+        hamiltonian: &dyn Hamiltonian,
+        state: &QuantumState,
+    ) -> Result<Vec<Complex64>> {
+        project(&self.spectrum(hamiltonian)?.modes, state)
+    }
+
+    pub fn computations(&self) -> usize {
+        self.computations.load(Ordering::Relaxed)
+    }
+}
+
+pub struct EffectiveSpectrum {
+    pub hamiltonian: Array2<Complex64>,
+    pub energies: Vec<f64>,
+    pub states: Array2<Complex64>,
+}
+
+pub struct PrethermalModes {
+    period: f64,
+    samples: usize,
+    spectrum: OnceLock<EffectiveSpectrum>,
+    computations: AtomicUsize,
+}
+
+impl PrethermalModes {
+    pub fn new(period: f64, samples: usize) -> Self {
+        Self {
+            period,
+            samples,
+            spectrum: OnceLock::new(),
+            computations: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn period(&self) -> f64 {
+        self.period
+    }
+
+    pub fn spectrum(&self, hamiltonian: &dyn Hamiltonian) -> Result<&EffectiveSpectrum> {
+        if let Some(spectrum) = self.spectrum.get() {
+            return Ok(spectrum);
+        }
+        let _span = tracing::debug_span!("effective_hamiltonian").entered();
+        let effective = high_frequency_hamiltonian(hamiltonian, self.period, self.samples)?;
+        let (energies, states) = hermitian_eigen(&effective.view());
+        self.computations.fetch_add(1, Ordering::Relaxed);
+        Ok(self.spectrum.get_or_init(|| EffectiveSpectrum {
+            hamiltonian: effective,
+            energies,
+            states,
+        }))
+    }
+
+    pub fn populations(
+        &self,
+        hamiltonian: &dyn Hamiltonian,
+        state: &QuantumState,
+    ) -> Result<Vec<Complex64>> {
+        project(&self.spectrum(hamiltonian)?.states, state)
+    }
+
+    pub fn computations(&self) -> usize {
+        self.computations.load(Ordering::Relaxed)
+    }
+}
+
+fn project(basis: &Array2<Complex64>, state: &QuantumState) -> Result<Vec<Complex64>> {
+    if basis.nrows() != state.dim() {
+        return Err(Error::DimensionMismatch {
+            expected: basis.nrows(),
+            actual: state.dim(),
+        });
+    }
+    Ok(basis
+        .columns()
+        .into_iter()
+        .map(|mode| {
+            let overlap: Complex64 = mode
+                .iter()
+                .zip(state.data().iter())
+                .map(|(m, psi)| m.conj() * psi)
+                .sum();
+            Complex64::new(overlap.norm_sqr(), 0.0)
+        })
+        .collect())
+}
@@ -0,0 +1,139 @@
+use crate::core::{DensityMatrix, QuantumState};
+use crate::utils::{Error, Result};
+
+const NORM_TOLERANCE: f64 = 1e-6;
+const POPULATION_TOLERANCE: f64 = 1e-8;
+const TRUNCATION_WARNING: f64 = 1e-3;
+
+pub struct StepContext {
+    pub step: usize,
+    pub time: f64,
+    pub timestep: f64,
+}
+
+impl StepContext {
+    fn failure(&self, problem: String, top_population: f64) -> Error {
+        let mut hints = vec![
+            format!(
+                "reduce the timestep (e.g. dt = {:.3e})",
+                self.timestep / 2.0
+            ),
+            "switch to an adaptive integrator".to_string(),
+        ];
+        if top_population > TRUNCATION_WARNING {
+            hints.push(format!(
+                "increase the Hilbert-space truncation (highest level holds {:.2e} of the population)",
+                top_population
+            ));
+        } else {
+            hints.push("increase the Hilbert-space truncation".to_string());
+        }
+
+        Error::Numerical(format!(
+            "{} at step {} (t = {:.6}, dt = {:.3e}). Try to: {}",
+            problem,
+            self.step,
+            self.time,
+            self.timestep,
+            hints.join("; ")
+        ))
+    }
+}
+
+pub fn check_state(state: &QuantumState, context: &StepContext) -> Result<()> {
+    let data = state.data();
+    let top_population = data.last().map_or(0.0, |c| c.norm_sqr());
+
+    if data.iter().any(|c| !c.re.is_finite() || !c.im.is_finite()) {
+        return Err(context.failure("State contains NaN or Inf".to_string(), top_population));
+    }
+
+    let norm_sq: f64 = data.iter().map(|c| c.norm_sqr()).sum();
+    if (norm_sq - 1.0).abs() > NORM_TOLERANCE {
+        return Err(context.failure(
+            format!("State norm drifted to {:.6e}", norm_sq.sqrt()),
+            top_population,
+        ));
+    }
+
+    Ok(())
+}
+
+pub fn check_density_matrix(rho: &DensityMatrix, context: &StepContext) -> Result<()> {
+    let data = rho.data();
+    let dim = rho.dim();
+    let top_population = if dim > 0 {
+        data[[dim - 1, dim - 1]].re
+    } else {
+        0.0
+    };
+
+    if data.iter().any(|c| !c.re.is_finite() || !c.im.is_finite()) {
+        return Err(context.failure(
+            "Density matrix contains NaN or Inf".to_string(),
+            top_population,
+        ));
+    }
+
+    let trace: f64 = (0..dim).map(|i| data[[i, i]].re).sum();
+    if (trace - 1.0).abs() > NORM_TOLERANCE {
+        return Err(context.failure(
+            format!("Density matrix trace drifted to {:.6e}", trace),
+            top_population,
+        ));
+    }
+
+    if let Some(level) = (0..dim).find(|&i| data[[i, i]].re < -POPULATION_TOLERANCE) {
+        return Err(context.failure(
+            format!(
+                "Negative population {:.3e} in level {}",
+                data[[level, level]].re,
+                level
+            ),
+            top_population,
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::{Array1, Array2};
+    use num_complex::Complex64;
+
+    fn context() -> StepContext {
+        StepContext {
+            step: 42,
+            time: 4.2,
+            timestep: 0.1,
+        }
+    }
+
+    #[test]
+    fn test_nan_state_is_reported_with_hints() {
+        let data = Array1::from(vec![
+            Complex64::new(f64::NAN, 0.0),
+            Complex64::new(0.0, 0.0),
+        ]);
+        let state = QuantumState::new(data).unwrap();
+
+        let err = check_state(&state, &context()).unwrap_err();
+        let message = err.to_string();
+        assert!(matches!(err, Error::Numerical(_)));
+        assert!(message.contains("step 42"));
+        assert!(message.contains("dt = 5.000e-2"));
+        assert!(check_state(&QuantumState::ground_state(2), &context()).is_ok());
+    }
+
+    #[test]
+    fn test_negative_population() {
+        let mut data = Array2::zeros((2, 2));
+        data[[0, 0]] = Complex64::new(1.1, 0.0);
+        data[[1, 1]] = Complex64::new(-0.1, 0.0);
+
+        let err = check_density_matrix(&DensityMatrix::new_unchecked(data), &context());
+        assert!(err.unwrap_err().to_string().contains("Negative population"));
+    }
+}
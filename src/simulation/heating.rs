@@ -0,0 +1,388 @@
+use crate::core::{integrator, Hamiltonian, IntegratorType, QuantumState};
+use crate::simulation::fit::Estimate;
+use crate::utils::math::trace;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+use rayon::prelude::*;
+use std::io::Write;
+use std::path::Path;
+
+const SATURATED: f64 = 0.9;
+const RATE_WINDOW: f64 = 0.5;
+
+#[derive(Debug, Clone)]
+pub struct HeatingOptions {
+    pub periods: usize,
+    pub steps_per_period: usize,
+    pub average_samples: usize,
+    pub plateau_tolerance: f64,
+    pub min_plateau_periods: usize,
+}
+
+impl Default for HeatingOptions {
+    fn default() -> Self {
+        Self {
+            periods: 200,
+            steps_per_period: 100,
+            average_samples: 32,
+            plateau_tolerance: 0.02,
+            min_plateau_periods: 10,
+        }
+    }
+}
+
+pub fn period_average(
+    hamiltonian: &dyn Hamiltonian,
+    period: f64,
+    samples: usize,
+) -> Array2<Complex64> {
+    let dim = hamiltonian.dim();
+    let samples = samples.max(1);
+    let mut h = Array2::zeros((dim, dim));
+    let mut average = Array2::zeros((dim, dim));
+    for k in 0..samples {
+        hamiltonian.compute((k as f64 + 0.5) * period / samples as f64, &mut h);
+        average += &h;
+    }
+    average / Complex64::new(samples as f64, 0.0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plateau {
+    pub start: f64,
+    pub end: f64,
+    pub energy: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct HeatingCurve {
+    pub frequency: f64,
+    pub times: Vec<f64>,
+    pub energy: Vec<f64>,
+    pub infinite_temperature: f64,
+    pub rate: Estimate,
+    pub plateau: Option<Plateau>,
+}
+
+impl HeatingCurve {
+    pub fn from_series(
+        frequency: f64,
+        times: Vec<f64>,
+        energy: Vec<f64>,
+        infinite_temperature: f64,
+        options: &HeatingOptions,
+    ) -> Result<Self> {
+        if times.len() != energy.len() {
+            return Err(Error::dimension_mismatch(times.len(), energy.len()));
+        }
+        if times.len() < 3 {
+            return Err(Error::InvalidParameter(format!(
+                "Heating analysis needs at least 3 stroboscopic samples, got {}",
+                times.len()
+            )));
+        }
+
+        let mut curve = Self {
+            frequency,
+            times,
+            energy,
+            infinite_temperature,
+            rate: Estimate {
+                value: 0.0,
+                uncertainty: 0.0,
+            },
+            plateau: None,
+        };
+        let normalized = curve.normalized();
+        let window = normalized
+            .iter()
+            .position(|&e| e >= RATE_WINDOW)
+            .unwrap_or(normalized.len())
+            .max(3);
+        curve.rate = linear_slope(&curve.times[..window], &curve.energy[..window]);
+        curve.plateau = find_plateau(&curve.times, &curve.energy, &normalized, options);
+        Ok(curve)
+    }
+
+    pub fn normalized(&self) -> Vec<f64> {
+        let e0 = self.energy[0];
+        let span = self.infinite_temperature - e0;
+        if span.abs() < 1e-12 {
+            return vec![0.0; self.energy.len()];
+        }
+        self.energy.iter().map(|e| (e - e0) / span).collect()
+    }
+
+    pub fn heating_time(&self) -> Option<f64> {
+        self.plateau.map(|p| p.end)
+    }
+}
+
+pub(crate) fn linear_slope(times: &[f64], values: &[f64]) -> Estimate {
+    let n = times.len() as f64;
+    let t_mean = times.iter().sum::<f64>() / n;
+    let v_mean = values.iter().sum::<f64>() / n;
+    let sxx: f64 = times.iter().map(|t| (t - t_mean).powi(2)).sum();
+    let sxy: f64 = times
+        .iter()
+        .zip(values)
+        .map(|(t, v)| (t - t_mean) * (v - v_mean))
+        .sum();
+    if sxx <= 0.0 {
+        return Estimate {
+            value: 0.0,
+            uncertainty: f64::INFINITY,
+        };
+    }
+    let slope = sxy / sxx;
+    let residual: f64 = times
+        .iter()
+        .zip(values)
+        .map(|(t, v)| (v - v_mean - slope * (t - t_mean)).powi(2))
+        .sum();
+    Estimate {
+        value: slope,
+        uncertainty: (residual / (n - 2.0).max(1.0) / sxx).sqrt(),
+    }
+}
+
+fn find_plateau(
+    times: &[f64],
+    energy: &[f64],
+    normalized: &[f64],
+    options: &HeatingOptions,
+) -> Option<Plateau> {
+    let mut best: Option<(usize, usize)> = None;
+    let mut start = 0;
+    for end in 1..=normalized.len() {
+        let closes = end == normalized.len()
+            || (normalized[end] - normalized[start]).abs() > options.plateau_tolerance;
+        if !closes {
+            continue;
+        }
+        let length = end - start;
+        let saturated = normalized[start..end].iter().sum::<f64>() / length as f64 > SATURATED;
+        if length > options.min_plateau_periods
+            && !saturated
+            && best.is_none_or(|(a, b)| length > b - a)
+        {
+            best = Some((start, end));
+        }
+        start = end;
+    }
+
+    best.map(|(a, b)| Plateau {
+        start: times[a],
+        end: times[b - 1],
+        energy: energy[a..b].iter().sum::<f64>() / (b - a) as f64,
+    })
+}
+
+pub struct HeatingAnalysis {
+    initial_state: QuantumState,
+    options: HeatingOptions,
+}
+
+impl HeatingAnalysis {
+    pub fn new(initial_state: QuantumState) -> Self {
+        Self {
+            initial_state,
+            options: HeatingOptions::default(),
+        }
+    }
+
+    pub fn with_options(mut self, options: HeatingOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn run(&self, frequency: f64, hamiltonian: &dyn Hamiltonian) -> Result<HeatingCurve> {
+        if !(frequency > 0.0 && frequency.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "Drive frequency must be positive, got {}",
+                frequency
+            )));
+        }
+        let dim = hamiltonian.dim();
+        if self.initial_state.dim() != dim {
+            return Err(Error::dimension_mismatch(dim, self.initial_state.dim()));
+        }
+        if self.options.periods < 2 || self.options.steps_per_period == 0 {
+            return Err(Error::InvalidParameter(format!(
+                "Heating analysis needs at least 2 periods and 1 step per period, got {} and {}",
+                self.options.periods, self.options.steps_per_period
+            )));
+        }
+
+        let period = 2.0 * std::f64::consts::PI / frequency;
+        let average = period_average(hamiltonian, period, self.options.average_samples);
+        let infinite_temperature = trace(&average.view()).re / dim as f64;
+        let energy_of = |state: &QuantumState| {
+            let psi = state.data();
+            psi.iter()
+                .zip(average.dot(psi).iter())
+                .map(|(a, b)| a.conj() * b)
+                .sum::<Complex64>()
+                .re
+        };
+
+        let integrator = integrator::create_integrator(IntegratorType::RK4);
+        let dt = period / self.options.steps_per_period as f64;
+        let mut state = self.initial_state.clone();
+        let mut times = vec![0.0];
+        let mut energy = vec![energy_of(&state)];
+        for n in 0..self.options.periods {
+            let t0 = n as f64 * period;
+            for k in 0..self.options.steps_per_period {
+                integrator.step(hamiltonian, &mut state, t0 + k as f64 * dt, dt)?;
+            }
+            let e = energy_of(&state);
+            if !e.is_finite() {
+                return Err(Error::Numerical(format!(
+                    "Energy diverged after {} periods at ω = {}, increase steps_per_period",
+                    n + 1,
+                    frequency
+                )));
+            }
+            times.push((n + 1) as f64 * period);
+            energy.push(e);
+        }
+
+        HeatingCurve::from_series(
+            frequency,
+            times,
+            energy,
+            infinite_temperature,
+            &self.options,
+        )
+    }
+
+    pub fn scan<F>(&self, frequencies: &[f64], build: F) -> Result<HeatingScan>
+    where
+        F: Fn(f64) -> Result<Box<dyn Hamiltonian>> + Sync,
+    {
+        let curves = frequencies
+            .par_iter()
+            .map(|&omega| self.run(omega, build(omega)?.as_ref()))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(HeatingScan { curves })
+    }
+}
+
+pub struct HeatingScan {
+    pub curves: Vec<HeatingCurve>,
+}
+
+impl HeatingScan {
+    fn log_rates(&self, x: impl Fn(f64) -> f64) -> Option<Estimate> {
+        let (xs, ys): (Vec<f64>, Vec<f64>) = self
+            .curves
+            .iter()
+            .filter(|c| c.rate.value != 0.0)
+            .map(|c| (x(c.frequency), c.rate.value.abs().ln()))
+            .unzip();
+        (xs.len() >= 2).then(|| linear_slope(&xs, &ys))
+    }
+
+    pub fn exponential_scale(&self) -> Option<Estimate> {
+        let slope = self.log_rates(|omega| omega)?;
+        (slope.value < 0.0).then(|| Estimate {
+            value: -1.0 / slope.value,
+            uncertainty: slope.uncertainty / (slope.value * slope.value),
+        })
+    }
+
+    pub fn power_law_exponent(&self) -> Option<Estimate> {
+        self.log_rates(f64::ln)
+    }
+
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(
+            writer,
+            "frequency,rate,rate_err,plateau_start,plateau_end,plateau_energy,final_energy"
+        )?;
+        for curve in &self.curves {
+            let (start, end, energy) = match curve.plateau {
+                Some(p) => (p.start.to_string(), p.end.to_string(), p.energy.to_string()),
+                None => (String::new(), String::new(), String::new()),
+            };
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                curve.frequency,
+                curve.rate.value,
+                curve.rate.uncertainty,
+                start,
+                end,
+                energy,
+                curve.energy.last().copied().unwrap_or(f64::NAN)
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::systems::DrivenTLS;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_plateau_and_scaling_from_series() {
+        let options = HeatingOptions::default();
+        let times: Vec<f64> = (0..=100).map(|n| n as f64).collect();
+        let energy: Vec<f64> = times
+            .iter()
+            .map(|&t| {
+                if t < 60.0 {
+                    0.1
+                } else {
+                    0.1 + 0.02 * (t - 60.0)
+                }
+            })
+            .collect();
+        let curve = HeatingCurve::from_series(4.0, times, energy, 1.0, &options).unwrap();
+        let plateau = curve.plateau.unwrap();
+        assert_eq!(plateau.start, 0.0);
+        assert!((60.0..=62.0).contains(&plateau.end), "{}", plateau.end);
+        assert_relative_eq!(plateau.energy, 0.1, epsilon = 1e-3);
+
+        let curves = [2.0, 4.0, 6.0]
+            .into_iter()
+            .map(|omega: f64| {
+                let times: Vec<f64> = (0..10).map(|n| n as f64).collect();
+                let energy = times.iter().map(|t| t * (-omega / 1.5).exp()).collect();
+                HeatingCurve::from_series(omega, times, energy, 100.0, &options).unwrap()
+            })
+            .collect();
+        let scan = HeatingScan { curves };
+        assert_relative_eq!(scan.exponential_scale().unwrap().value, 1.5, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_resonant_drive_heats_faster_than_off_resonant() {
+        let options = HeatingOptions {
+            periods: 60,
+            steps_per_period: 60,
+            ..HeatingOptions::default()
+        };
+        let analysis =
+            HeatingAnalysis::new(QuantumState::fock(2, 1).unwrap()).with_options(options);
+        let scan = analysis
+            .scan(&[5.0, 40.0], |omega| {
+                Ok(Box::new(DrivenTLS::new(5.0, omega, 0.3)) as Box<dyn Hamiltonian>)
+            })
+            .unwrap();
+
+        let (resonant, fast) = (&scan.curves[0], &scan.curves[1]);
+        assert_relative_eq!(resonant.infinite_temperature, 0.0, epsilon = 1e-12);
+        assert!(resonant.rate.value > 10.0 * fast.rate.value.abs());
+        let plateau = fast.plateau.unwrap();
+        assert_relative_eq!(plateau.end, *fast.times.last().unwrap(), epsilon = 1e-9);
+        assert_relative_eq!(plateau.energy, -2.5, epsilon = 0.05);
+    }
+}
@@ -0,0 +1,414 @@
+use crate::core::hamiltonian::TimeIndependentHamiltonian;
+use crate::core::lindblad::{LindbladOperator, LindbladSolver};
+use crate::core::observables::MatrixObservable;
+use crate::core::{Observable, QuantumState};
+use crate::data::config::Config;
+use crate::simulation::cumulant::{
+    dense_dim, CumulantOrder, CumulantSolver, DenseTavisCummings, PHOTON_NUMBER, SIGMA_Z,
+};
+use crate::simulation::{QuantumTrajectories, SimulationResults};
+use crate::utils::resources::ResourceMeter;
+use crate::utils::{Error, Result};
+use num_complex::Complex64;
+use std::io::Write;
+use std::path::Path;
+
+const MAX_EXACT_DIM: usize = 128;
+const MAX_TRAJECTORY_DIM: usize = 2048;
+const TRAJECTORY_CONFIDENCE: f64 = 3.0;
+const STANDARD_ERROR_SUFFIX: &str = "_stderr";
+
+pub const COMPARED_OBSERVABLES: [&str; 2] = [PHOTON_NUMBER, SIGMA_Z];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Method {
+    MeanField,
+    Cumulant,
+    Trajectories,
+    Exact,
+}
+
+impl Method {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Method::MeanField => "mean_field",
+            Method::Cumulant => "cumulant",
+            Method::Trajectories => "trajectories",
+            Method::Exact => "exact",
+        }
+    }
+}
+
+pub struct MethodRun {
+    pub method: Method,
+    pub results: SimulationResults,
+}
+
+impl MethodRun {
+    pub fn wall_time(&self) -> f64 {
+        self.results.resources().map_or(0.0, |r| r.wall_time)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub method: Method,
+    pub observable: String,
+    pub max_deviation: f64,
+    pub diverges_at: Option<f64>,
+}
+
+pub struct SolverHierarchy {
+    pub tolerance: f64,
+    pub reference: Method,
+    pub runs: Vec<MethodRun>,
+    pub skipped: Vec<(Method, String)>,
+    pub divergences: Vec<Divergence>,
+}
+
+impl SolverHierarchy {
+    pub fn run(config: &Config, tolerance: f64) -> Result<Self> {
+        if tolerance <= 0.0 || !tolerance.is_finite() {
+            return Err(Error::InvalidParameter(format!(
+                "Comparison tolerance must be positive, got {}",
+                tolerance
+            )));
+        }
+        let solver = CumulantSolver::from_config(config)?;
+        let (duration, timestep) = (config.simulation.duration, config.simulation.timestep);
+        let grid = config.observables.time_grid();
+        let times = grid.output_times(duration, timestep);
+        let cavity_dim = config.system.hilbert_dim;
+
+        let mut runs = Vec::new();
+        let mut skipped = Vec::new();
+        let dense = match dense_dim(cavity_dim, solver.model().emitters) {
+            Ok(dim) if dim <= MAX_TRAJECTORY_DIM => Some((dim, solver.model().dense(cavity_dim)?)),
+            Ok(dim) => {
+                let reason = format!("{} states is too large for a state-based solver", dim);
+                skipped.push((Method::Exact, reason.clone()));
+                skipped.push((Method::Trajectories, reason));
+                None
+            }
+            Err(e) => {
+                skipped.push((Method::Exact, e.to_string()));
+                skipped.push((Method::Trajectories, e.to_string()));
+                None
+            }
+        };
+
+        if let Some((dim, model)) = &dense {
+            let psi0 = solver.dense_initial_state(cavity_dim)?;
+            if *dim <= MAX_EXACT_DIM {
+                tracing::info!("Running exact master equation with {} states", dim);
+                runs.push(MethodRun {
+                    method: Method::Exact,
+                    results: exact(model, &psi0, &times, timestep)?,
+                });
+            } else {
+                skipped.push((
+                    Method::Exact,
+                    format!(
+                        "{} states exceeds the dense density-matrix limit of {}",
+                        dim, MAX_EXACT_DIM
+                    ),
+                ));
+            }
+            if model.collapse_ops.is_empty() {
+                skipped.push((
+                    Method::Trajectories,
+                    "closed system, trajectories reduce to the exact solution".to_string(),
+                ));
+            } else {
+                tracing::info!(
+                    "Running {} quantum trajectories with {} states",
+                    config.simulation.trajectories,
+                    dim
+                );
+                runs.push(MethodRun {
+                    method: Method::Trajectories,
+                    results: trajectories(model, &psi0, config, &times)?,
+                });
+            }
+        }
+
+        for (method, order) in [
+            (Method::Cumulant, CumulantOrder::Second),
+            (Method::MeanField, CumulantOrder::MeanField),
+        ] {
+            let results = CumulantSolver::from_config(config)?
+                .with_order(order)
+                .run(duration, timestep, &grid)?;
+            runs.push(MethodRun { method, results });
+        }
+
+        let reference = runs[0].method;
+        let divergences = runs[1..]
+            .iter()
+            .flat_map(|run| {
+                COMPARED_OBSERVABLES
+                    .iter()
+                    .map(|name| divergence(&runs[0].results, run, name, tolerance))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            tolerance,
+            reference,
+            runs,
+            skipped,
+            divergences,
+        })
+    }
+
+    pub fn recommendation(&self) -> Method {
+        self.runs
+            .iter()
+            .map(|run| run.method)
+            .filter(|&method| {
+                self.divergences
+                    .iter()
+                    .filter(|d| d.method == method)
+                    .all(|d| d.diverges_at.is_none())
+            })
+            .min()
+            .unwrap_or(self.reference)
+    }
+
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let mut header = vec!["time".to_string()];
+        let mut columns = Vec::new();
+        for run in &self.runs {
+            for name in COMPARED_OBSERVABLES {
+                header.push(format!("{}_{}", run.method.name(), name));
+                columns.push(run.results.series(name).map(|s| s.values).unwrap_or(&[]));
+            }
+        }
+        writeln!(writer, "{}", header.join(","))?;
+
+        let times = self.runs[0]
+            .results
+            .series(PHOTON_NUMBER)
+            .map(|s| s.times)
+            .unwrap_or(&[]);
+        for (k, t) in times.iter().enumerate() {
+            let mut row = vec![t.to_string()];
+            row.extend(
+                columns
+                    .iter()
+                    .map(|values| values.get(k).map_or(String::new(), |v| v.re.to_string())),
+            );
+            writeln!(writer, "{}", row.join(","))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+fn exact(
+    model: &DenseTavisCummings,
+    psi0: &QuantumState,
+    times: &[f64],
+    timestep: f64,
+) -> Result<SimulationResults> {
+    let meter = ResourceMeter::start();
+    let solver = LindbladSolver::new(
+        Box::new(TimeIndependentHamiltonian::new(model.hamiltonian.clone())),
+        shared_collapse_ops(model)?,
+    )?;
+    let observables = [
+        (
+            PHOTON_NUMBER,
+            MatrixObservable::new(model.photon_number.clone()),
+        ),
+        (SIGMA_Z, MatrixObservable::new(model.sigma_z.clone())),
+    ];
+
+    let mut results = SimulationResults::new();
+    let mut rho = psi0.to_density_matrix();
+    let mut t = 0.0;
+    for &t_out in times {
+        while t_out - t > 1e-9 * timestep {
+            let dt = timestep.min(t_out - t);
+            solver.step(&mut rho, t, dt)?;
+            t += dt;
+        }
+        for (name, observable) in &observables {
+            let value = observable.expectation_mixed(&rho);
+            results.add_observable(name, t_out, Complex64::new(value.re, 0.0));
+        }
+    }
+    results.set_resources(meter.finish());
+    Ok(results)
+}
+
+fn trajectories(
+    model: &DenseTavisCummings,
+    psi0: &QuantumState,
+    config: &Config,
+    times: &[f64],
+) -> Result<SimulationResults> {
+    let count = config.simulation.trajectories;
+    let mut solver = QuantumTrajectories::new(
+        Box::new(TimeIndependentHamiltonian::new(model.hamiltonian.clone())),
+        shared_collapse_ops(model)?,
+        count,
+    )?;
+    if let Some(seed) = config.simulation.seed {
+        solver = solver.seed(seed);
+    }
+    let observables: Vec<(String, Box<dyn Observable>)> = vec![
+        (
+            PHOTON_NUMBER.to_string(),
+            Box::new(MatrixObservable::new(model.photon_number.clone())),
+        ),
+        (
+            SIGMA_Z.to_string(),
+            Box::new(MatrixObservable::new(model.sigma_z.clone())),
+        ),
+    ];
+    let timestep = config.simulation.timestep;
+    let full = solver.run(
+        psi0,
+        &observables,
+        (0.0, config.simulation.duration),
+        timestep,
+    )?;
+
+    let mut results = SimulationResults::new();
+    let standard_error = (count as f64).sqrt();
+    for name in COMPARED_OBSERVABLES {
+        let (Some(mean), Some(std)) = (full.series(name), full.series(&format!("{}_std", name)))
+        else {
+            continue;
+        };
+        let error_name = format!("{}{}", name, STANDARD_ERROR_SUFFIX);
+        for &t in times {
+            let k = ((t / timestep).round() as usize).min(mean.values.len() - 1);
+            results.add_observable(name, t, Complex64::new(mean.values[k].re, 0.0));
+            results.add_observable(
+                &error_name,
+                t,
+                Complex64::new(std.values[k].re / standard_error, 0.0),
+            );
+        }
+    }
+    if let Some(resources) = full.resources() {
+        results.set_resources(*resources);
+    }
+    Ok(results)
+}
+
+fn shared_collapse_ops(model: &DenseTavisCummings) -> Result<Vec<LindbladOperator>> {
+    model
+        .collapse_ops
+        .iter()
+        .map(|op| LindbladOperator::shared(op.operator.clone(), op.rate))
+        .collect()
+}
+
+fn divergence(
+    reference: &SimulationResults,
+    run: &MethodRun,
+    name: &str,
+    tolerance: f64,
+) -> Result<Divergence> {
+    let missing = || {
+        Error::InvalidParameter(format!(
+            "Observable '{}' is missing from the {} run",
+            name,
+            run.method.name()
+        ))
+    };
+    let expected = reference.series(name).ok_or_else(missing)?;
+    let actual = run.results.series(name).ok_or_else(missing)?;
+    let noise = run
+        .results
+        .series(&format!("{}{}", name, STANDARD_ERROR_SUFFIX));
+
+    let mut max_deviation: f64 = 0.0;
+    let mut diverges_at = None;
+    for (k, (&t, (x, y))) in expected
+        .times
+        .iter()
+        .zip(expected.values.iter().zip(actual.values))
+        .enumerate()
+    {
+        let scale = x.re.abs().max(1.0);
+        let deviation = (y.re - x.re).abs() / scale;
+        let allowed = tolerance
+            + noise
+                .as_ref()
+                .map_or(0.0, |n| TRAJECTORY_CONFIDENCE * n.values[k].re / scale);
+        max_deviation = max_deviation.max(deviation);
+        if diverges_at.is_none() && deviation > allowed {
+            diverges_at = Some(t);
+        }
+    }
+    Ok(Divergence {
+        method: run.method,
+        observable: name.to_string(),
+        max_deviation,
+        diverges_at,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[simulation]
+name = "superradiance"
+duration = 4.0
+timestep = 0.02
+solver = "cumulant"
+trajectories = 200
+seed = 11
+
+[system]
+hilbert_dim = 3
+hamiltonian = "tavis_cummings"
+
+[system.parameters]
+omega_c = 1.0
+omega_a = 1.0
+g = 0.4
+
+[cumulant]
+emitters = 2
+kappa = 1.0
+gamma = 0.05
+emitter_angle = 3.141592653589793
+
+[observables]
+list = ["population"]
+save_interval = 0.5
+"#;
+
+    #[test]
+    fn test_mean_field_misses_spontaneous_emission() {
+        let config = Config::from_toml_str(CONFIG).unwrap();
+        config.validate().unwrap();
+        let hierarchy = SolverHierarchy::run(&config, 0.05).unwrap();
+        assert_eq!(hierarchy.reference, Method::Exact);
+        assert_eq!(hierarchy.runs.len(), 4);
+
+        let find = |method, observable| {
+            hierarchy
+                .divergences
+                .iter()
+                .find(|d| d.method == method && d.observable == observable)
+                .unwrap()
+        };
+        assert!(find(Method::MeanField, PHOTON_NUMBER).diverges_at.is_some());
+        assert!(find(Method::Trajectories, SIGMA_Z).diverges_at.is_none());
+        assert!(
+            find(Method::Cumulant, SIGMA_Z).max_deviation
+                < find(Method::MeanField, SIGMA_Z).max_deviation
+        );
+        assert_eq!(hierarchy.recommendation(), Method::Trajectories);
+        assert!(SolverHierarchy::run(&config, 0.0).is_err());
+    }
+}
@@ -0,0 +1,313 @@
+use crate::core::floquet::{one_period_propagator, FloquetSpectrum};
+use crate::core::operator_cache::OperatorCache;
+use crate::core::{
+    integrator, Hamiltonian, Integrator, IntegratorType, Observable, Precision, QuantumState,
+};
+use crate::data::config::Config;
+use crate::data::operator_export::system_hamiltonian;
+use crate::simulation::{SimulationResults, TimeGrid};
+use crate::utils::math::{hermitian_eigen, is_hermitian};
+use crate::utils::resources::ResourceMeter;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+
+pub const KICK_COUNT: &str = "kicks";
+
+pub fn kick_generator(name: &str, dim: usize) -> Result<Array2<Complex64>> {
+    let c = |x: f64| Complex64::new(x, 0.0);
+    let spin = |entries: [Complex64; 4]| {
+        if dim != 2 {
+            return Err(Error::Config(format!(
+                "Kick operator '{}' needs hilbert_dim = 2, got {}",
+                name, dim
+            )));
+        }
+        Ok(Array2::from_shape_vec((2, 2), entries.to_vec()).expect("2x2 entries"))
+    };
+    let number = Array2::from_diag(&ndarray::Array1::from_iter((0..dim).map(|n| c(n as f64))));
+    let mut quadrature = Array2::zeros((dim, dim));
+    for n in 1..dim {
+        let amplitude = c((n as f64 / 2.0).sqrt());
+        quadrature[[n - 1, n]] = amplitude;
+        quadrature[[n, n - 1]] = amplitude;
+    }
+
+    match name {
+        "sigma_x" => spin([c(0.0), c(1.0), c(1.0), c(0.0)]),
+        "sigma_y" => spin([c(0.0), -Complex64::i(), Complex64::i(), c(0.0)]),
+        "sigma_z" => spin([c(1.0), c(0.0), c(0.0), c(-1.0)]),
+        "number" => Ok(number),
+        "number_squared" => Ok(number.dot(&number)),
+        "quadrature" => Ok(quadrature),
+        "cos_quadrature" => Ok(matrix_function(&quadrature, |x| c(x.cos()))),
+        other => Err(Error::Config(format!(
+            "Unknown kick operator '{}', expected sigma_x, sigma_y, sigma_z, number, \
+             number_squared, quadrature or cos_quadrature",
+            other
+        ))),
+    }
+}
+
+fn matrix_function(matrix: &Array2<Complex64>, f: impl Fn(f64) -> Complex64) -> Array2<Complex64> {
+    let (values, vectors) = hermitian_eigen(&matrix.view());
+    let weighted = Array2::from_shape_fn(vectors.dim(), |(row, col)| {
+        vectors[[row, col]] * f(values[col])
+    });
+    weighted.dot(&vectors.t().mapv(|x| x.conj()))
+}
+
+pub struct KickedSystem {
+    drift: Box<dyn Hamiltonian>,
+    kick: Array2<Complex64>,
+    strength: f64,
+    period: f64,
+    timestep: f64,
+    integrator: Box<dyn Integrator>,
+    observables: Vec<(String, Box<dyn Observable>)>,
+}
+
+impl KickedSystem {
+    pub fn new(
+        drift: Box<dyn Hamiltonian>,
+        generator: Array2<Complex64>,
+        strength: f64,
+        period: f64,
+    ) -> Result<Self> {
+        let dim = drift.dim();
+        if generator.dim() != (dim, dim) {
+            return Err(Error::dimension_mismatch(dim, generator.nrows()));
+        }
+        if !is_hermitian(&generator.view(), 1e-10) {
+            return Err(Error::InvalidParameter(
+                "Kick generator must be Hermitian for the kick to be unitary".to_string(),
+            ));
+        }
+        if !(period > 0.0 && period.is_finite() && strength.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "Kicks need a positive period and finite strength, got T = {} and K = {}",
+                period, strength
+            )));
+        }
+
+        Ok(Self {
+            drift,
+            kick: matrix_function(&generator, |x| Complex64::from_polar(1.0, -strength * x)),
+            strength,
+            period,
+            timestep: period / 100.0,
+            integrator: integrator::create_integrator(IntegratorType::RK4),
+            observables: Vec::new(),
+        })
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let kicks = &config.kicks;
+        if config.lindblad.enabled {
+            return Err(Error::NotImplemented(
+                "Kicked systems with Lindblad dissipation".to_string(),
+            ));
+        }
+        let drift = system_hamiltonian(config)?;
+        let generator = kick_generator(&kicks.operator, drift.dim())?;
+        let mut system = Self::new(drift, generator, kicks.strength, kicks.period)?
+            .with_timestep(config.simulation.timestep)?
+            .with_precision(Precision::parse(&config.simulation.precision)?)?;
+        system.observables = OperatorCache::new().observables_for(config)?;
+        Ok(system)
+    }
+
+    pub fn with_timestep(mut self, timestep: f64) -> Result<Self> {
+        if !(timestep > 0.0 && timestep.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "Timestep must be positive, got {}",
+                timestep
+            )));
+        }
+        self.timestep = timestep;
+        Ok(self)
+    }
+
+    pub fn with_integrator(mut self, integrator_type: IntegratorType) -> Self {
+        self.integrator = integrator::create_integrator(integrator_type);
+        self
+    }
+
+    pub fn with_precision(mut self, precision: Precision) -> Result<Self> {
+        self.integrator = integrator::create_integrator_with_precision(
+            self.integrator.integrator_type(),
+            precision,
+        )?;
+        Ok(self)
+    }
+
+    pub fn with_observable(
+        mut self,
+        name: impl Into<String>,
+        observable: impl Observable + 'static,
+    ) -> Self {
+        self.observables.push((name.into(), Box::new(observable)));
+        self
+    }
+
+    pub fn kick_unitary(&self) -> &Array2<Complex64> {
+        &self.kick
+    }
+
+    pub fn strength(&self) -> f64 {
+        self.strength
+    }
+
+    pub fn period(&self) -> f64 {
+        self.period
+    }
+
+    pub fn floquet_operator(&self) -> Array2<Complex64> {
+        let steps = (self.period / self.timestep).ceil().max(1.0) as usize;
+        self.kick.dot(&one_period_propagator(
+            self.drift.as_ref(),
+            self.period,
+            steps,
+        ))
+    }
+
+    pub fn floquet_spectrum(&self) -> Result<FloquetSpectrum> {
+        FloquetSpectrum::from_propagator(&self.floquet_operator(), self.period)
+    }
+
+    pub fn run(
+        &self,
+        psi0: &QuantumState,
+        duration: f64,
+        time_grid: &TimeGrid,
+    ) -> Result<SimulationResults> {
+        let dim = self.kick.nrows();
+        if psi0.dim() != dim {
+            return Err(Error::dimension_mismatch(dim, psi0.dim()));
+        }
+        if let Some((name, _)) = self.observables.iter().find(|(_, o)| o.dim() != dim) {
+            return Err(Error::InvalidParameter(format!(
+                "Observable '{}' does not match the Hilbert dimension {}",
+                name, dim
+            )));
+        }
+        if duration <= 0.0 {
+            return Err(Error::InvalidParameter(format!(
+                "Duration must be positive, got {}",
+                duration
+            )));
+        }
+        time_grid.validate(duration)?;
+        self.integrator.clear_cache();
+
+        let meter = ResourceMeter::start();
+        let tolerance = 1e-9 * self.timestep;
+        let mut results = SimulationResults::new();
+        let mut state = psi0.clone();
+        let mut t = 0.0;
+        let mut kicks = 0usize;
+        for t_out in time_grid.output_times(duration, self.timestep) {
+            loop {
+                let next_kick = (kicks + 1) as f64 * self.period;
+                if next_kick > t_out + tolerance {
+                    break;
+                }
+                self.drift_to(&mut state, t, next_kick)?;
+                state = QuantumState::new(self.kick.dot(state.data()))?;
+                t = next_kick;
+                kicks += 1;
+            }
+            self.drift_to(&mut state, t, t_out)?;
+            t = t_out.max(t);
+
+            results.add_observable(KICK_COUNT, t_out, Complex64::new(kicks as f64, 0.0));
+            for (name, observable) in &self.observables {
+                results.add_observable(name, t_out, observable.expectation_pure(&state));
+            }
+        }
+
+        results.set_resources(meter.finish());
+        Ok(results)
+    }
+
+    fn drift_to(&self, state: &mut QuantumState, from: f64, to: f64) -> Result<()> {
+        let tolerance = 1e-9 * self.timestep;
+        let mut t = from;
+        while to - t > tolerance {
+            let dt = self.timestep.min(to - t);
+            self.integrator.step(self.drift.as_ref(), state, t, dt)?;
+            t += dt;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::hamiltonian::TimeIndependentHamiltonian;
+    use crate::core::observables::{MatrixObservable, NumberOperator};
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_kicked_top_matches_stroboscopic_floquet_operator() {
+        let dim = 2;
+        let drift = kick_generator("sigma_z", dim).unwrap().mapv(|x| x * 0.7);
+        let system = KickedSystem::new(
+            Box::new(TimeIndependentHamiltonian::new(drift)),
+            kick_generator("sigma_x", dim).unwrap(),
+            0.4,
+            1.0,
+        )
+        .unwrap()
+        .with_timestep(0.01)
+        .unwrap()
+        .with_observable(
+            "sz",
+            MatrixObservable::new(kick_generator("sigma_z", 2).unwrap()),
+        );
+
+        let psi0 = QuantumState::ground_state(dim);
+        let results = system.run(&psi0, 5.0, &TimeGrid::uniform(1.0)).unwrap();
+        let kicks = &results.series(KICK_COUNT).unwrap().values;
+        assert_eq!(kicks.last().unwrap().re, 5.0);
+
+        let floquet = system.floquet_operator();
+        let mut expected = psi0.data().clone();
+        for _ in 0..5 {
+            expected = floquet.dot(&expected);
+        }
+        let sz = MatrixObservable::new(kick_generator("sigma_z", 2).unwrap())
+            .expectation_pure(&QuantumState::new(expected).unwrap());
+        let last = results.series("sz").unwrap().values.last().unwrap().re;
+        assert_relative_eq!(last, sz.re, epsilon = 1e-8);
+    }
+
+    #[test]
+    fn test_single_kick_is_exact() {
+        let dim = 12;
+        let system = KickedSystem::new(
+            Box::new(TimeIndependentHamiltonian::new(Array2::zeros((dim, dim)))),
+            kick_generator("quadrature", dim).unwrap(),
+            0.5,
+            1.0,
+        )
+        .unwrap()
+        .with_observable("n", NumberOperator::new(dim));
+        let results = system
+            .run(
+                &QuantumState::ground_state(dim),
+                1.0,
+                &TimeGrid::uniform(1.0),
+            )
+            .unwrap();
+
+        let n = results.series("n").unwrap().values[1].re;
+        assert_relative_eq!(n, 0.5 * 0.5 / 2.0, epsilon = 1e-6);
+        assert!(kick_generator("sigma_x", 3).is_err());
+        assert!(is_hermitian(
+            &kick_generator("cos_quadrature", dim).unwrap().view(),
+            1e-10
+        ));
+    }
+}
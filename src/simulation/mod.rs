@@ -1,8 +1,64 @@
 pub mod builder;
+pub mod comparison;
+pub mod convergence;
+pub mod cumulant;
+pub mod dmd;
+pub mod echo;
+pub mod ensemble;
+pub mod estimate;
+pub mod events;
+pub mod fit;
+pub mod floquet_modes;
+pub mod guard;
+pub mod heating;
+pub mod hierarchy;
+pub mod kicked;
+pub mod ports;
+pub mod preemption;
+pub mod random_floquet;
+pub mod reduced_model;
 pub mod results;
 pub mod runner;
+pub mod sampling;
 pub mod scheduler;
+pub mod scrambling;
+pub mod semiclassical;
+pub mod spectrogram;
+pub mod spectroscopy;
+pub mod steady_state;
+pub mod stop;
+pub mod time_grid;
+pub mod trajectories;
+pub mod transport;
 
-pub use builder::SimulationBuilder;
-pub use results::SimulationResults;
-pub use runner::SimulationRunner;
+pub use builder::{suggest_timestep, SimulationBuilder};
+pub use comparison::RunDifference;
+pub use convergence::{ConvergenceReport, ConvergenceTest};
+pub use cumulant::{CumulantOrder, CumulantSolver, TavisCummings};
+pub use dmd::{Dmd, DmdMode, DmdOptions};
+pub use echo::{EchoProtocol, Reversal};
+pub use ensemble::run_ensemble;
+pub use estimate::CostEstimate;
+pub use events::{Event, EventKind, EventSpec};
+pub use fit::{Estimate, FitModel, FitResult, FitSpec};
+pub use floquet_modes::{EffectiveSpectrum, FloquetModes, PrethermalModes};
+pub use heating::{HeatingAnalysis, HeatingCurve, HeatingOptions, HeatingScan};
+pub use hierarchy::{Method, SolverHierarchy};
+pub use kicked::KickedSystem;
+pub use ports::{DetectionConfig, OutputPort, OutputPorts, PortAnalysis, PortConfig};
+pub use preemption::{run_preemptible, PreemptionConfig};
+pub use random_floquet::{LevelStatistics, RandomEnsemble, RandomFloquetStudy};
+pub use reduced_model::{EffectiveMode, ReducedModel};
+pub use results::{AcceptedStep, SimulationResults, StateSnapshot, TimeSeries, VectorSeries};
+pub use runner::{SimulationRunner, StateIter};
+pub use sampling::{SamplingOptions, ShotSampler};
+pub use scheduler::{Job, JobControl, JobQueue, JobStatus, Scheduler};
+pub use scrambling::ScramblingAnalysis;
+pub use semiclassical::{ClassicalMode, SemiclassicalSolver};
+pub use spectrogram::{Spectrogram, SpectrogramOptions};
+pub use spectroscopy::{NormalModes, SpectroscopyConfig, TransmissionSpectrum};
+pub use steady_state::{SteadyStateEstimate, SteadyStateOptions};
+pub use stop::{StopCondition, Termination};
+pub use time_grid::TimeGrid;
+pub use trajectories::QuantumTrajectories;
+pub use transport::{Injection, Transport, TransportMetrics};
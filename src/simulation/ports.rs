@@ -0,0 +1,435 @@
+use crate::data::config::Config;
+use crate::utils::math::{hermitian_eigen, solve};
+use crate::utils::{Error, Result};
+use ndarray::{s, Array1, Array2};
+use num_complex::Complex64;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortConfig {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<usize>,
+    #[serde(default = "default_efficiency")]
+    pub efficiency: f64,
+}
+
+fn default_efficiency() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DetectionConfig {
+    #[serde(default)]
+    pub ports: Vec<PortConfig>,
+    #[serde(default)]
+    pub span: Option<f64>,
+    #[serde(default = "default_points")]
+    pub points: usize,
+}
+
+impl Default for DetectionConfig {
+    fn default() -> Self {
+        Self {
+            ports: Vec::new(),
+            span: None,
+            points: default_points(),
+        }
+    }
+}
+
+fn default_points() -> usize {
+    401
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputPort {
+    pub name: String,
+    pub site: usize,
+    pub rate: f64,
+    pub efficiency: f64,
+}
+
+impl OutputPort {
+    fn coupling(&self) -> f64 {
+        (self.efficiency * self.rate).sqrt()
+    }
+}
+
+pub struct OutputPorts {
+    ports: Vec<OutputPort>,
+    effective: Array2<Complex64>,
+    initial: Array1<Complex64>,
+    omega_c: f64,
+    detunings: Vec<f64>,
+    duration: f64,
+    timestep: f64,
+}
+
+impl OutputPorts {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let detection = &config.detection;
+        if detection.ports.is_empty() {
+            return Err(Error::Config(
+                "Output-port analysis needs at least one [[detection.ports]] entry".to_string(),
+            ));
+        }
+        let Some((lattice, omega_c)) = config.system.build_lattice()? else {
+            return Err(Error::Config(format!(
+                "Output ports need a lattice or coupled_cavities system, got '{}'",
+                config.system.hamiltonian
+            )));
+        };
+        let sites = lattice.num_sites();
+        let channels = if config.lindblad.enabled {
+            config.lindblad.operators.as_slice()
+        } else {
+            &[]
+        };
+        let mut losses = vec![0.0; sites];
+        for (index, channel) in channels.iter().enumerate() {
+            match (channel.r#type.as_str(), channel.site) {
+                ("site_decay", Some(site)) if site < sites => losses[site] += channel.rate,
+                ("site_decay", _) => {
+                    return Err(Error::Config(format!(
+                        "Decay channel {} needs a site index below {}",
+                        index, sites
+                    )))
+                }
+                (other, _) => return Err(Error::Config(format!(
+                    "Output-port analysis only supports site_decay channels, channel {} is '{}'",
+                    index, other
+                ))),
+            }
+        }
+
+        let mut ports: Vec<OutputPort> = Vec::with_capacity(detection.ports.len());
+        for port in &detection.ports {
+            if ports.iter().any(|p| p.name == port.name) {
+                return Err(Error::Config(format!(
+                    "Output port '{}' is defined twice",
+                    port.name
+                )));
+            }
+            if !(port.efficiency > 0.0 && port.efficiency <= 1.0) {
+                return Err(Error::InvalidParameter(format!(
+                    "Output port '{}' needs an efficiency in (0, 1], got {}",
+                    port.name, port.efficiency
+                )));
+            }
+            let (site, rate) = match (port.site, port.channel) {
+                (Some(site), None) => (site, losses.get(site).copied().unwrap_or(0.0)),
+                (None, Some(channel)) => {
+                    let op = channels.get(channel).ok_or_else(|| {
+                        Error::Config(format!(
+                            "Output port '{}' refers to decay channel {}, but only {} are enabled",
+                            port.name,
+                            channel,
+                            channels.len()
+                        ))
+                    })?;
+                    (op.site.unwrap_or_default(), op.rate)
+                }
+                _ => {
+                    return Err(Error::Config(format!(
+                        "Output port '{}' needs exactly one of site or channel",
+                        port.name
+                    )))
+                }
+            };
+            if rate <= 0.0 {
+                return Err(Error::Config(format!(
+                    "Output port '{}' at site {} has no site_decay channel to emit through",
+                    port.name, site
+                )));
+            }
+            ports.push(OutputPort {
+                name: port.name.clone(),
+                site,
+                rate,
+                efficiency: port.efficiency,
+            });
+        }
+
+        let hamiltonian = lattice.hamiltonian(omega_c);
+        let block = hamiltonian.matrix().slice(s![1.., 1..]).to_owned();
+        let (energies, _) = hermitian_eigen(&block.view());
+        let mut effective = block;
+        for (site, loss) in losses.iter().enumerate() {
+            effective[[site, site]] -= Complex64::new(0.0, 0.5 * loss);
+        }
+
+        let state = config.initial_state.build(config.system.hilbert_dim)?;
+        let initial = state.data().slice(s![1..]).to_owned();
+        if initial.iter().all(|x| x.norm_sqr() == 0.0) {
+            return Err(Error::InvalidParameter(
+                "Output-port analysis needs an initial state with a photon in the lattice"
+                    .to_string(),
+            ));
+        }
+
+        let span = detection.span.unwrap_or_else(|| {
+            let bandwidth = energies
+                .iter()
+                .map(|e| (e - omega_c).abs())
+                .fold(0.0, f64::max);
+            bandwidth + 5.0 * losses.iter().cloned().fold(0.0, f64::max)
+        });
+        if !(span > 0.0 && span.is_finite()) || detection.points < 3 {
+            return Err(Error::InvalidParameter(format!(
+                "Port spectra need a positive span and at least 3 points, got span {} and {} points",
+                span, detection.points
+            )));
+        }
+        let step = 2.0 * span / (detection.points - 1) as f64;
+        let detunings = (0..detection.points)
+            .map(|k| -span + k as f64 * step)
+            .collect();
+
+        Ok(Self {
+            ports,
+            effective,
+            initial,
+            omega_c,
+            detunings,
+            duration: config.simulation.duration,
+            timestep: config.simulation.timestep,
+        })
+    }
+
+    pub fn ports(&self) -> &[OutputPort] {
+        &self.ports
+    }
+
+    pub fn analyze(&self) -> Result<PortAnalysis> {
+        let n = self.initial.len();
+        let mut spectra = vec![Vec::with_capacity(self.detunings.len()); self.ports.len()];
+        for &detuning in &self.detunings {
+            let mut resolvent = self.effective.mapv(|x| -x);
+            for k in 0..n {
+                resolvent[[k, k]] += Complex64::new(self.omega_c + detuning, 0.0);
+            }
+            let amplitudes = solve(&resolvent, &self.initial);
+            for (spectrum, port) in spectra.iter_mut().zip(&self.ports) {
+                let field = port.coupling() * amplitudes[port.site];
+                spectrum.push(field.norm_sqr() / (2.0 * std::f64::consts::PI));
+            }
+        }
+        if spectra.iter().flatten().any(|x| !x.is_finite()) {
+            return Err(Error::Numerical(
+                "Port spectrum solve produced non-finite values".to_string(),
+            ));
+        }
+
+        let steps = (self.duration / self.timestep).round().max(1.0) as usize;
+        let dt = self.duration / steps as f64;
+        let mut psi = self.initial.clone();
+        let mut coherence = Array2::<Complex64>::zeros((self.ports.len(), self.ports.len()));
+        let mut accumulate = |psi: &Array1<Complex64>, weight: f64| {
+            let fields: Vec<Complex64> = self
+                .ports
+                .iter()
+                .map(|port| port.coupling() * psi[port.site])
+                .collect();
+            for (a, fa) in fields.iter().enumerate() {
+                for (b, fb) in fields.iter().enumerate() {
+                    coherence[[a, b]] += fa.conj() * fb * weight;
+                }
+            }
+        };
+        accumulate(&psi, 0.5 * dt);
+        for step in 1..=steps {
+            self.step(&mut psi, dt);
+            accumulate(&psi, if step == steps { 0.5 * dt } else { dt });
+        }
+        let residual = psi.iter().map(|x| x.norm_sqr()).sum();
+
+        Ok(PortAnalysis {
+            ports: self.ports.iter().map(|port| port.name.clone()).collect(),
+            detunings: self.detunings.clone(),
+            spectra,
+            coherence,
+            residual,
+        })
+    }
+
+    fn step(&self, psi: &mut Array1<Complex64>, dt: f64) {
+        let minus_i = Complex64::new(0.0, -1.0);
+        let derivative = |x: &Array1<Complex64>| self.effective.dot(x).mapv(|v| minus_i * v);
+        let k1 = derivative(psi);
+        let k2 = derivative(&(&*psi + &k1.mapv(|v| v * (0.5 * dt))));
+        let k3 = derivative(&(&*psi + &k2.mapv(|v| v * (0.5 * dt))));
+        let k4 = derivative(&(&*psi + &k3.mapv(|v| v * dt)));
+        *psi = &*psi + &((k1 + k2 * 2.0 + k3 * 2.0 + k4) * (dt / 6.0));
+    }
+}
+
+pub struct PortAnalysis {
+    pub ports: Vec<String>,
+    pub detunings: Vec<f64>,
+    pub spectra: Vec<Vec<f64>>,
+    pub coherence: Array2<Complex64>,
+    pub residual: f64,
+}
+
+impl PortAnalysis {
+    pub fn emission(&self, port: usize) -> f64 {
+        self.coherence[[port, port]].re
+    }
+
+    pub fn g1(&self, a: usize, b: usize) -> f64 {
+        let norm = (self.emission(a) * self.emission(b)).sqrt();
+        if norm > 0.0 {
+            self.coherence[[a, b]].norm() / norm
+        } else {
+            0.0
+        }
+    }
+
+    pub fn write_spectra_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(writer, "detuning,{}", self.ports.join(","))?;
+        for (k, detuning) in self.detunings.iter().enumerate() {
+            let row: Vec<String> = self.spectra.iter().map(|s| s[k].to_string()).collect();
+            writeln!(writer, "{},{}", detuning, row.join(","))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn write_correlations_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(writer, "port_a,port_b,re,im,g1")?;
+        for (a, name_a) in self.ports.iter().enumerate() {
+            for (b, name_b) in self.ports.iter().enumerate() {
+                let c = self.coherence[[a, b]];
+                writeln!(
+                    writer,
+                    "{},{},{},{},{}",
+                    name_a,
+                    name_b,
+                    c.re,
+                    c.im,
+                    self.g1(a, b)
+                )?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::config::LindbladOperatorConfig;
+    use approx::assert_relative_eq;
+
+    fn chain(sites: usize, rates: &[(usize, f64)], ports: &[(&str, usize)]) -> Config {
+        let mut config = Config::from_toml_str(&format!(
+            r#"
+            [simulation]
+            name = "ports"
+            duration = 200.0
+            timestep = 0.02
+
+            [system]
+            hilbert_dim = {}
+            hamiltonian = "coupled_cavities"
+            parameters = {{ omega_c = 5.0, j1 = 0.5 }}
+
+            [observables]
+            list = ["population"]
+            "#,
+            sites + 1
+        ))
+        .unwrap();
+        config.lindblad.enabled = true;
+        config.lindblad.operators = rates
+            .iter()
+            .map(|&(site, rate)| LindbladOperatorConfig {
+                r#type: "site_decay".to_string(),
+                rate,
+                temperature: 0.0,
+                site: Some(site),
+            })
+            .collect();
+        config.detection.ports = ports
+            .iter()
+            .map(|&(name, site)| PortConfig {
+                name: name.to_string(),
+                site: Some(site),
+                channel: None,
+                efficiency: 1.0,
+            })
+            .collect();
+        config.initial_state.kind = "fock".to_string();
+        config.initial_state.level = 1;
+        config
+    }
+
+    #[test]
+    fn test_single_port_spectrum_is_normalized_lorentzian() {
+        let mut config = chain(1, &[(0, 0.2)], &[("out", 0)]);
+        config.detection.span = Some(20.0);
+        config.detection.points = 4001;
+        let analysis = OutputPorts::from_config(&config)
+            .unwrap()
+            .analyze()
+            .unwrap();
+        let spectrum = &analysis.spectra[0];
+        let step = analysis.detunings[1] - analysis.detunings[0];
+        let area: f64 = spectrum.iter().sum::<f64>() * step;
+        assert_relative_eq!(area, 1.0, max_relative = 1e-2);
+        assert_relative_eq!(
+            spectrum[2000],
+            1.0 / (0.1 * std::f64::consts::PI),
+            max_relative = 1e-9
+        );
+        assert_relative_eq!(analysis.emission(0), 1.0, max_relative = 1e-4);
+        assert!(analysis.residual < 1e-8);
+    }
+
+    #[test]
+    fn test_symmetric_mode_splits_evenly_and_coherently() {
+        let mut config = chain(2, &[(0, 0.1), (1, 0.1)], &[("left", 0), ("right", 1)]);
+        let j = config.system.parameters.get("j1").unwrap();
+        config.initial_state.kind = "superposition".to_string();
+        config.initial_state.amplitudes = vec![[0.0, 0.0], [1.0, 0.0], [1.0, 0.0]];
+        let analysis = OutputPorts::from_config(&config)
+            .unwrap()
+            .analyze()
+            .unwrap();
+        assert_relative_eq!(analysis.emission(0), 0.5, max_relative = 1e-4);
+        assert_relative_eq!(analysis.emission(1), 0.5, max_relative = 1e-4);
+        assert_relative_eq!(analysis.g1(0, 1), 1.0, max_relative = 1e-6);
+
+        let peak = analysis.spectra[0]
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(k, _)| analysis.detunings[k])
+            .unwrap();
+        let step = analysis.detunings[1] - analysis.detunings[0];
+        assert!((peak - j).abs() <= step, "peak at {}, expected {}", peak, j);
+    }
+
+    #[test]
+    fn test_ports_need_an_emitting_channel() {
+        let config = chain(3, &[(0, 0.1)], &[("dark", 2)]);
+        assert!(OutputPorts::from_config(&config).is_err());
+
+        let mut config = chain(3, &[(0, 0.1)], &[("out", 0)]);
+        config.detection.ports[0].channel = Some(0);
+        assert!(OutputPorts::from_config(&config).is_err());
+        config.detection.ports[0].site = None;
+        assert_eq!(
+            OutputPorts::from_config(&config).unwrap().ports()[0].site,
+            0
+        );
+    }
+}
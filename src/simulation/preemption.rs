@@ -0,0 +1,207 @@
+use crate::simulation::JobControl;
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreemptionConfig {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_signals")]
+    pub signals: Vec<String>,
+    #[serde(default = "default_grace_period")]
+    pub grace_period: f64,
+    #[serde(default)]
+    pub checkpoint_interval: Option<f64>,
+}
+
+impl Default for PreemptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            signals: default_signals(),
+            grace_period: default_grace_period(),
+            checkpoint_interval: None,
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_signals() -> Vec<String> {
+    vec!["SIGTERM".to_string(), "SIGUSR1".to_string()]
+}
+
+fn default_grace_period() -> f64 {
+    30.0
+}
+
+impl PreemptionConfig {
+    pub fn validate(&self) -> Result<()> {
+        for signal in &self.signals {
+            parse_signal(signal)?;
+        }
+        if !(self.grace_period > 0.0 && self.grace_period.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "preemption.grace_period must be positive, got {}",
+                self.grace_period
+            )));
+        }
+        if let Some(interval) = self.checkpoint_interval {
+            if !(interval > 0.0 && interval.is_finite()) {
+                return Err(Error::InvalidParameter(format!(
+                    "preemption.checkpoint_interval must be positive, got {}",
+                    interval
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Signal {
+    Terminate,
+    Interrupt,
+    User1,
+    User2,
+}
+
+fn parse_signal(name: &str) -> Result<Signal> {
+    match name {
+        "SIGTERM" => Ok(Signal::Terminate),
+        "SIGINT" => Ok(Signal::Interrupt),
+        "SIGUSR1" => Ok(Signal::User1),
+        "SIGUSR2" => Ok(Signal::User2),
+        other => Err(Error::Config(format!(
+            "Unsupported preemption signal '{}', expected SIGTERM, SIGINT, SIGUSR1 or SIGUSR2",
+            other
+        ))),
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal(names: &[String]) -> Result<String> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+    for name in names {
+        let kind = match parse_signal(name)? {
+            Signal::Terminate => SignalKind::terminate(),
+            Signal::Interrupt => SignalKind::interrupt(),
+            Signal::User1 => SignalKind::user_defined1(),
+            Signal::User2 => SignalKind::user_defined2(),
+        };
+        let mut stream = signal(kind)?;
+        let sender = sender.clone();
+        let name = name.clone();
+        tokio::spawn(async move {
+            if stream.recv().await.is_some() {
+                sender.send(name).ok();
+            }
+        });
+    }
+    drop(sender);
+    match receiver.recv().await {
+        Some(name) => Ok(name),
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal(names: &[String]) -> Result<String> {
+    for name in names {
+        parse_signal(name)?;
+    }
+    tokio::signal::ctrl_c().await?;
+    Ok("CTRL_C".to_string())
+}
+
+pub async fn run_preemptible<T, F>(
+    config: &PreemptionConfig,
+    control: Arc<JobControl>,
+    work: F,
+) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    let mut handle = tokio::task::spawn_blocking(work);
+    let joined = |joined: std::result::Result<Result<T>, tokio::task::JoinError>| {
+        joined.map_err(|e| Error::Other(e.into()))?
+    };
+    if !config.enabled || config.signals.is_empty() {
+        return joined(handle.await);
+    }
+
+    let signal = tokio::select! {
+        result = &mut handle => return joined(result),
+        signal = wait_for_signal(&config.signals) => signal?,
+    };
+    tracing::warn!(
+        "Received {}, stopping and flushing checkpoint (grace period {} s)",
+        signal,
+        config.grace_period
+    );
+    control.cancel();
+
+    let grace = Duration::from_secs_f64(config.grace_period);
+    let flushed = match tokio::time::timeout(grace, handle).await {
+        Ok(result) => match joined(result) {
+            Ok(value) => return Ok(value),
+            Err(Error::Cancelled(_)) => true,
+            Err(e) => return Err(e),
+        },
+        Err(_) => {
+            tracing::error!("Grace period expired before the checkpoint was flushed");
+            false
+        }
+    };
+    Err(Error::Preempted { signal, flushed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_signal_cancels_work_and_reports_preemption() {
+        let config = PreemptionConfig {
+            signals: vec!["SIGUSR2".to_string()],
+            grace_period: 5.0,
+            ..PreemptionConfig::default()
+        };
+        let control = Arc::new(JobControl::new());
+        let worker = control.clone();
+        let work = move || loop {
+            worker.check()?;
+            std::thread::sleep(Duration::from_millis(1));
+        };
+
+        let run =
+            tokio::spawn(async move { run_preemptible::<(), _>(&config, control, work).await });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        std::process::Command::new("kill")
+            .args(["-USR2", &std::process::id().to_string()])
+            .status()
+            .unwrap();
+
+        match run.await.unwrap() {
+            Err(Error::Preempted { signal, flushed }) => {
+                assert_eq!(signal, "SIGUSR2");
+                assert!(flushed);
+            }
+            other => panic!("expected preemption, got {:?}", other.map(|_| ())),
+        }
+        assert!(PreemptionConfig {
+            signals: vec!["SIGKILL".to_string()],
+            ..PreemptionConfig::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -0,0 +1,338 @@
+use crate::core::lattice::{DisorderConfig, Lattice};
+use crate::core::Hamiltonian;
+use crate::gpu::batch::BatchExecutor;
+use crate::utils::performance::resolve_seed;
+use crate::utils::{Error, Result};
+use ndarray::{s, Array2};
+use num_complex::Complex64;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rand_distr::StandardNormal;
+use std::io::Write;
+use std::path::Path;
+
+pub const R_POISSON: f64 = 0.386_294_361_119_890_6;
+pub const R_GOE: f64 = 0.5307;
+pub const R_GUE: f64 = 0.5996;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RandomEnsemble {
+    Goe,
+    Gue,
+    Banded { bandwidth: usize },
+    Ssh { j1: f64, j2: f64, disorder: f64 },
+}
+
+impl RandomEnsemble {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Goe => "goe",
+            Self::Gue => "gue",
+            Self::Banded { .. } => "banded",
+            Self::Ssh { .. } => "ssh",
+        }
+    }
+
+    pub fn sample(&self, dim: usize, seed: Option<u64>, index: usize) -> Result<Array2<Complex64>> {
+        if dim < 3 {
+            return Err(Error::InvalidParameter(format!(
+                "Level statistics need at least 3 levels, got dimension {}",
+                dim
+            )));
+        }
+        if let Self::Ssh { j1, j2, disorder } = *self {
+            let mut clean = Lattice::new(dim)?;
+            for i in 1..dim {
+                clean.set_bond(i - 1, i, if i % 2 == 1 { j1 } else { j2 })?;
+            }
+            let disorder = DisorderConfig {
+                onsite: disorder,
+                coupling: disorder,
+                seed: resolve_seed(seed),
+                ..DisorderConfig::default()
+            };
+            let lattice = disorder.realization(&clean, index)?;
+            return Ok(lattice
+                .hamiltonian(0.0)
+                .matrix()
+                .slice(s![1.., 1..])
+                .to_owned());
+        }
+
+        let mut rng = match resolve_seed(seed) {
+            Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(index as u64)),
+            None => StdRng::from_os_rng(),
+        };
+        let (bandwidth, complex) = match *self {
+            Self::Goe => (dim, false),
+            Self::Gue => (dim, true),
+            Self::Banded { bandwidth } => (bandwidth, false),
+            Self::Ssh { .. } => unreachable!(),
+        };
+        let width = (2 * bandwidth + 1).min(dim) as f64;
+        let sigma = (1.0 / width).sqrt();
+        let mut matrix = Array2::zeros((dim, dim));
+        for i in 0..dim {
+            let diagonal: f64 = rng.sample(StandardNormal);
+            let scale = if complex { sigma } else { sigma * 2f64.sqrt() };
+            matrix[[i, i]] = Complex64::new(scale * diagonal, 0.0);
+            for j in i + 1..dim.min(i + bandwidth + 1) {
+                let re: f64 = rng.sample(StandardNormal);
+                let value = if complex {
+                    let im: f64 = rng.sample(StandardNormal);
+                    Complex64::new(re, im) * (sigma / 2f64.sqrt())
+                } else {
+                    Complex64::new(re * sigma, 0.0)
+                };
+                matrix[[i, j]] = value;
+                matrix[[j, i]] = value.conj();
+            }
+        }
+        Ok(matrix)
+    }
+}
+
+pub struct DrivenRandomHamiltonian {
+    static_part: Array2<Complex64>,
+    drive: Array2<Complex64>,
+    frequency: f64,
+}
+
+impl DrivenRandomHamiltonian {
+    pub fn new(static_part: Array2<Complex64>, drive: Array2<Complex64>, frequency: f64) -> Self {
+        Self {
+            static_part,
+            drive,
+            frequency,
+        }
+    }
+}
+
+impl Hamiltonian for DrivenRandomHamiltonian {
+    fn dim(&self) -> usize {
+        self.static_part.nrows()
+    }
+
+    fn compute(&self, t: f64, out: &mut Array2<Complex64>) {
+        let c = (self.frequency * t).cos();
+        ndarray::Zip::from(out)
+            .and(&self.static_part)
+            .and(&self.drive)
+            .for_each(|o, &h, &v| *o = h + v * c);
+    }
+
+    fn period(&self) -> Option<f64> {
+        Some(2.0 * std::f64::consts::PI / self.frequency)
+    }
+}
+
+pub fn r_ratios(quasi_energies: &[f64], period: f64) -> Vec<f64> {
+    let n = quasi_energies.len();
+    if n < 3 {
+        return Vec::new();
+    }
+    let mut sorted = quasi_energies.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let zone = 2.0 * std::f64::consts::PI / period;
+    let spacings: Vec<f64> = (0..n)
+        .map(|k| {
+            if k + 1 < n {
+                sorted[k + 1] - sorted[k]
+            } else {
+                sorted[0] + zone - sorted[k]
+            }
+        })
+        .collect();
+    (0..n)
+        .filter_map(|k| {
+            let (a, b) = (spacings[k], spacings[(k + 1) % n]);
+            (a.max(b) > 0.0).then(|| a.min(b) / a.max(b))
+        })
+        .collect()
+}
+
+pub struct LevelStatistics {
+    pub ensemble: RandomEnsemble,
+    pub dim: usize,
+    pub mean_r: Vec<f64>,
+    pub r_values: Vec<f64>,
+}
+
+impl LevelStatistics {
+    pub fn mean(&self) -> f64 {
+        self.r_values.iter().sum::<f64>() / self.r_values.len().max(1) as f64
+    }
+
+    pub fn standard_error(&self) -> f64 {
+        let n = self.mean_r.len() as f64;
+        if n < 2.0 {
+            return f64::NAN;
+        }
+        let mean = self.mean_r.iter().sum::<f64>() / n;
+        let variance = self.mean_r.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (variance / n).sqrt()
+    }
+
+    pub fn closest_class(&self) -> &'static str {
+        let mean = self.mean();
+        [("poisson", R_POISSON), ("goe", R_GOE), ("gue", R_GUE)]
+            .into_iter()
+            .min_by(|a, b| (a.1 - mean).abs().total_cmp(&(b.1 - mean).abs()))
+            .map(|(name, _)| name)
+            .unwrap()
+    }
+
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(writer, "realization,mean_r")?;
+        for (k, r) in self.mean_r.iter().enumerate() {
+            writeln!(writer, "{},{}", k, r)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+pub struct RandomFloquetStudy {
+    ensemble: RandomEnsemble,
+    dim: usize,
+    realizations: usize,
+    drive: f64,
+    frequency: f64,
+    num_steps: usize,
+    seed: Option<u64>,
+    batch_size: usize,
+    gpu_enabled: bool,
+}
+
+impl RandomFloquetStudy {
+    pub fn new(ensemble: RandomEnsemble, dim: usize, realizations: usize) -> Self {
+        Self {
+            ensemble,
+            dim,
+            realizations,
+            drive: 1.0,
+            frequency: 2.0,
+            num_steps: 200,
+            seed: None,
+            batch_size: 256,
+            gpu_enabled: false,
+        }
+    }
+
+    pub fn with_drive(mut self, amplitude: f64, frequency: f64) -> Self {
+        self.drive = amplitude;
+        self.frequency = frequency;
+        self
+    }
+
+    pub fn with_steps(mut self, num_steps: usize) -> Self {
+        self.num_steps = num_steps;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn with_batch(mut self, batch_size: usize, gpu_enabled: bool) -> Self {
+        self.batch_size = batch_size;
+        self.gpu_enabled = gpu_enabled;
+        self
+    }
+
+    pub fn run(&self) -> Result<LevelStatistics> {
+        if self.realizations == 0 {
+            return Err(Error::InvalidParameter(
+                "Random Floquet study needs at least one realization".to_string(),
+            ));
+        }
+        if !(self.frequency > 0.0 && self.frequency.is_finite()) || self.num_steps == 0 {
+            return Err(Error::InvalidParameter(format!(
+                "Drive frequency and step count must be positive, got {} and {}",
+                self.frequency, self.num_steps
+            )));
+        }
+
+        let hamiltonians = (0..self.realizations)
+            .map(|k| {
+                let static_part = self.ensemble.sample(self.dim, self.seed, 2 * k)?;
+                let drive = self.ensemble.sample(self.dim, self.seed, 2 * k + 1)?;
+                Ok(DrivenRandomHamiltonian::new(
+                    static_part,
+                    drive.mapv(|x| x * self.drive),
+                    self.frequency,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let refs: Vec<&dyn Hamiltonian> =
+            hamiltonians.iter().map(|h| h as &dyn Hamiltonian).collect();
+
+        let period = 2.0 * std::f64::consts::PI / self.frequency;
+        let spectra = BatchExecutor::new(self.batch_size, self.gpu_enabled).floquet_spectra(
+            &refs,
+            period,
+            self.num_steps,
+        )?;
+
+        let mut statistics = LevelStatistics {
+            ensemble: self.ensemble,
+            dim: self.dim,
+            mean_r: Vec::with_capacity(spectra.len()),
+            r_values: Vec::new(),
+        };
+        for spectrum in &spectra {
+            let ratios = r_ratios(&spectrum.quasi_energies, period);
+            statistics
+                .mean_r
+                .push(ratios.iter().sum::<f64>() / ratios.len().max(1) as f64);
+            statistics.r_values.extend(ratios);
+        }
+        Ok(statistics)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_r_ratio_of_equally_spaced_levels() {
+        let period = std::f64::consts::PI;
+        let levels: Vec<f64> = (0..8).map(|k| -0.5 + 0.125 * k as f64).collect();
+        let ratios = r_ratios(&levels, period);
+        assert_eq!(ratios.len(), 8);
+        assert_relative_eq!(ratios[0], 1.0, epsilon = 1e-12);
+        assert_relative_eq!(ratios[6], 0.125 / 1.125, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_chaotic_and_localized_ensembles() {
+        let chaotic = RandomFloquetStudy::new(RandomEnsemble::Goe, 24, 16)
+            .with_seed(7)
+            .with_steps(100)
+            .run()
+            .unwrap();
+        assert_eq!(chaotic.mean_r.len(), 16);
+        assert_eq!(chaotic.closest_class(), "goe");
+        assert!((chaotic.mean() - R_GOE).abs() < 0.04, "{}", chaotic.mean());
+
+        let localized = RandomFloquetStudy::new(
+            RandomEnsemble::Ssh {
+                j1: 0.1,
+                j2: 0.1,
+                disorder: 8.0,
+            },
+            24,
+            16,
+        )
+        .with_seed(7)
+        .with_steps(100)
+        .run()
+        .unwrap();
+        assert_eq!(localized.closest_class(), "poisson");
+        assert!(localized.standard_error() < 0.05);
+    }
+}
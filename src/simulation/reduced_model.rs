@@ -0,0 +1,250 @@
+use crate::core::lattice::LatticeConfig;
+use crate::core::QuantumState;
+use crate::data::config::{
+    Config, InitialStateConfig, LindbladConfig, LindbladOperatorConfig, ObservablesConfig,
+    SystemConfig, SystemParameters,
+};
+use crate::simulation::{Dmd, DmdOptions, SimulationResults};
+use crate::utils::{Error, Result};
+use num_complex::Complex64;
+use std::collections::BTreeMap;
+
+const NEGLIGIBLE_DECAY: f64 = 1e-8;
+
+#[derive(Debug, Clone)]
+pub struct EffectiveMode {
+    pub energy: f64,
+    pub decay_rate: f64,
+    pub amplitude: Complex64,
+    pub shape: Vec<Complex64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReducedModel {
+    pub modes: Vec<EffectiveMode>,
+    pub fit_error: f64,
+}
+
+impl ReducedModel {
+    pub fn fit(results: &SimulationResults, modes: usize) -> Result<Self> {
+        if modes == 0 {
+            return Err(Error::InvalidParameter(
+                "Reduced model needs at least one mode".to_string(),
+            ));
+        }
+        let snapshots = results.snapshots();
+        if snapshots.is_empty() {
+            return Err(Error::InvalidParameter(
+                "Reduced model fitting needs recorded states (output.states = true)".to_string(),
+            ));
+        }
+        let times: Vec<f64> = snapshots.iter().map(|s| s.time).collect();
+        let columns: Vec<Vec<Complex64>> = snapshots.iter().map(|s| s.amplitudes.clone()).collect();
+        let options = DmdOptions {
+            rank: modes,
+            ..DmdOptions::default()
+        };
+        let dmd = Dmd::compute(&times, &columns, &options)?;
+
+        let fit_error = times
+            .iter()
+            .zip(&columns)
+            .map(|(&t, exact)| {
+                let predicted = dmd.predict(t);
+                let error: f64 = predicted
+                    .iter()
+                    .zip(exact)
+                    .map(|(p, x)| (p - x).norm_sqr())
+                    .sum();
+                let norm: f64 = exact.iter().map(|x| x.norm_sqr()).sum();
+                (error / norm.max(f64::MIN_POSITIVE)).sqrt()
+            })
+            .fold(0.0, f64::max);
+
+        let modes = dmd
+            .modes
+            .into_iter()
+            .map(|mode| {
+                if mode.decay_rate < -NEGLIGIBLE_DECAY {
+                    tracing::warn!(
+                        "DMD mode at frequency {:.4} grows at rate {:.3e}; clamping its decay to zero",
+                        mode.frequency,
+                        -mode.decay_rate
+                    );
+                }
+                EffectiveMode {
+                    energy: -mode.frequency,
+                    decay_rate: if mode.decay_rate > NEGLIGIBLE_DECAY {
+                        2.0 * mode.decay_rate
+                    } else {
+                        0.0
+                    },
+                    amplitude: mode.amplitude,
+                    shape: mode.shape,
+                }
+            })
+            .collect();
+
+        Ok(Self { modes, fit_error })
+    }
+
+    fn norm(&self) -> f64 {
+        self.modes
+            .iter()
+            .map(|mode| mode.amplitude.norm_sqr())
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    pub fn to_config(&self, base: &Config) -> Result<Config> {
+        let n = self.modes.len();
+        let mut config = base.clone();
+        config.simulation.name = format!("{}_reduced", base.simulation.name);
+
+        let parameters = BTreeMap::from([("omega_c".to_string(), 0.0), ("j".to_string(), 0.0)]);
+        config.system = SystemConfig {
+            hilbert_dim: n + 1,
+            hamiltonian: "lattice".to_string(),
+            parameters: SystemParameters::parse("lattice", parameters)?,
+            lattice: Some(LatticeConfig {
+                shape: "custom".to_string(),
+                sites: n,
+                width: 0,
+                height: 0,
+                periodic: false,
+                bonds: Vec::new(),
+                onsite: self.modes.iter().map(|mode| mode.energy).collect(),
+                disorder: None,
+            }),
+        };
+
+        let operators: Vec<LindbladOperatorConfig> = self
+            .modes
+            .iter()
+            .enumerate()
+            .filter(|(_, mode)| mode.decay_rate > 0.0)
+            .map(|(site, mode)| LindbladOperatorConfig {
+                r#type: "site_decay".to_string(),
+                rate: mode.decay_rate,
+                temperature: 0.0,
+                site: Some(site),
+            })
+            .collect();
+        config.lindblad = LindbladConfig {
+            enabled: !operators.is_empty(),
+            operators,
+        };
+
+        let norm = self.norm();
+        config.initial_state = InitialStateConfig {
+            kind: "superposition".to_string(),
+            amplitudes: std::iter::once([0.0, 0.0])
+                .chain(
+                    self.modes
+                        .iter()
+                        .map(|mode| [mode.amplitude.re / norm, mode.amplitude.im / norm]),
+                )
+                .collect(),
+            ..InitialStateConfig::default()
+        };
+
+        config.observables = ObservablesConfig {
+            list: (0..n)
+                .map(|k| format!("mode_{} = population:{}", k, k + 1))
+                .collect(),
+            save_interval: base.observables.save_interval,
+            output: Default::default(),
+            time_grid: base.observables.time_grid.clone(),
+            steady_state: base.observables.steady_state.clone(),
+            sampling: base.observables.sampling.clone(),
+            spectrogram: Default::default(),
+            dmd: Default::default(),
+            events: Vec::new(),
+            fits: Vec::new(),
+        };
+        config.parameter_sweep.enabled = false;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn lift(&self, reduced: &QuantumState) -> Result<Vec<Complex64>> {
+        let n = self.modes.len();
+        if reduced.dim() != n + 1 {
+            return Err(Error::dimension_mismatch(n + 1, reduced.dim()));
+        }
+        let dim = self.modes.first().map_or(0, |mode| mode.shape.len());
+        let norm = self.norm();
+        let mut full = vec![Complex64::new(0.0, 0.0); dim];
+        for (mode, c) in self.modes.iter().zip(reduced.data().iter().skip(1)) {
+            for (x, phi) in full.iter_mut().zip(&mode.shape) {
+                *x += phi * c * norm;
+            }
+        }
+        Ok(full)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::lattice::Lattice;
+    use crate::core::observables::PopulationOperator;
+    use crate::simulation::SimulationBuilder;
+    use crate::utils::math::hermitian_eigen;
+    use ndarray::Array1;
+
+    #[test]
+    fn test_reduced_model_reproduces_two_mode_dynamics() {
+        let sites = 8;
+        let hamiltonian = Lattice::chain(sites, 1.0).unwrap().hamiltonian(0.0);
+        let (_, vectors) = hermitian_eigen(&hamiltonian.matrix().view());
+        let psi0: Array1<Complex64> = (&vectors.column(2) * Complex64::new(0.6, 0.0))
+            + (&vectors.column(5) * Complex64::new(0.0, 0.8));
+        let full = SimulationBuilder::new()
+            .hamiltonian(hamiltonian)
+            .initial_state(QuantumState::new(psi0).unwrap())
+            .duration(10.0)
+            .timestep(0.01)
+            .observable("p1", PopulationOperator::new(sites + 1, 1).unwrap())
+            .record_states(true)
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+
+        let model = ReducedModel::fit(&full, 2).unwrap();
+        assert!(model.fit_error < 1e-6, "fit error {}", model.fit_error);
+
+        let base = crate::data::presets::find("vacuum_rabi_splitting")
+            .unwrap()
+            .config()
+            .unwrap();
+        let config = model.to_config(&base).unwrap();
+        assert_eq!(config.system.hilbert_dim, 3);
+        assert!(!config.lindblad.enabled);
+
+        let reduced = SimulationBuilder::new()
+            .hamiltonian(config.system.lattice_hamiltonian().unwrap().unwrap())
+            .initial_state(config.initial_state.build(3).unwrap())
+            .duration(10.0)
+            .timestep(0.01)
+            .observable("mode_0", PopulationOperator::new(3, 1).unwrap())
+            .record_states(true)
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+
+        let last = reduced.snapshots().last().unwrap();
+        let lifted = model
+            .lift(&QuantumState::new(Array1::from(last.amplitudes.clone())).unwrap())
+            .unwrap();
+        let exact = &full.snapshots().last().unwrap().amplitudes;
+        let error: f64 = lifted
+            .iter()
+            .zip(exact)
+            .map(|(a, b)| (a - b).norm_sqr())
+            .sum();
+        assert!(error.sqrt() < 1e-4, "surrogate error {}", error.sqrt());
+    }
+}
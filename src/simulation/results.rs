@@ -1,46 +1,711 @@
-use crate::utils::Result;
+use crate::simulation::events::{self, Event, EventSpec};
+use crate::simulation::fit::{self, FitResult, FitSpec};
+use crate::simulation::spectrogram::{Spectrogram, SpectrogramOptions};
+use crate::simulation::steady_state::{self, SteadyStateEstimate, SteadyStateOptions};
+use crate::simulation::stop::Termination;
+use crate::utils::resources::ResourceUsage;
+use crate::utils::{Error, Result};
+use ndarray::{Array1, Array2, Array3};
 use num_complex::Complex64;
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::Write;
 use std::path::Path;
 
-#[derive(Debug, Clone)]
+// Binary results start with this magic and a little-endian format version ahead
+// of the bincode payload. Bump the version whenever `SimulationResults` changes shape.
+const RESULTS_MAGIC: &[u8; 8] = b"CPRESLT\0";
+const RESULTS_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResultsFormat {
+    Csv,
+    Json,
+    Binary,
+}
+
+impl ResultsFormat {
+    fn of(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => Ok(Self::Csv),
+            Some("json") => Ok(Self::Json),
+            Some("bin") => Ok(Self::Binary),
+            _ => Err(Error::Config(format!(
+                "Cannot tell the results format of {}, expected a .csv, .json or .bin extension",
+                path.display()
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonSeries {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    times: Option<Vec<f64>>,
+    re: Vec<f64>,
+    im: Vec<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct JsonResults {
+    times: Vec<f64>,
+    observables: BTreeMap<String, JsonSeries>,
+}
+
+#[derive(Debug, Default)]
+struct CsvColumns {
+    time: Option<usize>,
+    re: Option<usize>,
+    im: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Series {
+    values: Vec<Complex64>,
+    times: Option<Vec<f64>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TimeSeries<'a> {
+    pub name: &'a str,
+    pub times: &'a [f64],
+    pub values: &'a [Complex64],
+}
+
+impl TimeSeries<'_> {
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (f64, Complex64)> + '_ {
+        self.times.iter().copied().zip(self.values.iter().copied())
+    }
+
+    pub fn to_ndarray(&self) -> Array1<Complex64> {
+        Array1::from(self.values.to_vec())
+    }
+
+    pub fn to_vec_real(&self) -> Vec<f64> {
+        self.values.iter().map(|v| v.re).collect()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct VectorData {
+    width: usize,
+    times: Vec<f64>,
+    values: Vec<Complex64>,
+    #[serde(default)]
+    shape: Option<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct VectorSeries<'a> {
+    pub name: &'a str,
+    pub times: &'a [f64],
+    pub width: usize,
+    pub values: &'a [Complex64],
+    pub shape: Option<(usize, usize)>,
+}
+
+impl<'a> VectorSeries<'a> {
+    pub fn len(&self) -> usize {
+        self.times.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.times.is_empty()
+    }
+
+    pub fn row(&self, k: usize) -> &'a [Complex64] {
+        &self.values[k * self.width..(k + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = (f64, &'a [Complex64])> + '_ {
+        self.times
+            .iter()
+            .copied()
+            .zip(self.values.chunks_exact(self.width.max(1)))
+    }
+
+    pub fn component(&self, i: usize) -> Vec<Complex64> {
+        self.values
+            .iter()
+            .skip(i)
+            .step_by(self.width.max(1))
+            .copied()
+            .collect()
+    }
+
+    pub fn to_ndarray(&self) -> Array2<Complex64> {
+        Array2::from_shape_vec((self.len(), self.width), self.values.to_vec())
+            .expect("vector series rows have a fixed width")
+    }
+
+    pub fn to_array3(&self) -> Option<Array3<Complex64>> {
+        let (rows, cols) = self.shape?;
+        Array3::from_shape_vec((self.len(), rows, cols), self.values.to_vec()).ok()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub time: f64,
+    pub amplitudes: Vec<Complex64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AcceptedStep {
+    pub time: f64,
+    pub dt: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationResults {
-    observables: HashMap<String, Vec<(f64, Complex64)>>,
+    times: Vec<f64>,
+    observables: BTreeMap<String, Series>,
+    vectors: BTreeMap<String, VectorData>,
+    snapshots: Vec<StateSnapshot>,
+    steady_state: SteadyStateOptions,
+    #[serde(default)]
+    termination: Option<Termination>,
+    #[serde(default)]
+    resources: Option<Box<ResourceUsage>>,
+    #[serde(default)]
+    step_history: Vec<AcceptedStep>,
 }
 
 impl SimulationResults {
     pub fn new() -> Self {
         Self {
-            observables: HashMap::new(),
+            times: Vec::new(),
+            observables: BTreeMap::new(),
+            vectors: BTreeMap::new(),
+            snapshots: Vec::new(),
+            steady_state: SteadyStateOptions::default(),
+            termination: None,
+            resources: None,
+            step_history: Vec::new(),
         }
     }
 
     pub fn add_observable(&mut self, name: &str, time: f64, value: Complex64) {
-        self.observables
+        let series = self.observables.entry(name.to_string()).or_default();
+        let idx = series.values.len();
+
+        match &mut series.times {
+            Some(times) => times.push(time),
+            None if idx == self.times.len() => self.times.push(time),
+            None if self.times[idx] == time => {}
+            None => {
+                let mut times = self.times[..idx].to_vec();
+                times.push(time);
+                series.times = Some(times);
+            }
+        }
+
+        series.values.push(value);
+    }
+
+    pub fn add_vector_observable(
+        &mut self,
+        name: &str,
+        time: f64,
+        values: &[Complex64],
+    ) -> Result<()> {
+        let data = self
+            .vectors
             .entry(name.to_string())
-            .or_default()
-            .push((time, value));
+            .or_insert_with(|| VectorData {
+                width: values.len(),
+                ..VectorData::default()
+            });
+
+        if values.len() != data.width {
+            return Err(Error::dimension_mismatch(data.width, values.len()));
+        }
+
+        data.times.push(time);
+        data.values.extend_from_slice(values);
+        Ok(())
+    }
+
+    pub fn set_vector_shape(&mut self, name: &str, rows: usize, cols: usize) -> Result<()> {
+        let data = self.vectors.get_mut(name).ok_or_else(|| {
+            Error::InvalidParameter(format!("Unknown vector observable '{}'", name))
+        })?;
+        if rows * cols != data.width {
+            return Err(Error::dimension_mismatch(data.width, rows * cols));
+        }
+        data.shape = Some((rows, cols));
+        Ok(())
+    }
+
+    pub fn vector_series(&self, name: &str) -> Option<VectorSeries<'_>> {
+        let (name, data) = self.vectors.get_key_value(name)?;
+        Some(VectorSeries {
+            name,
+            times: &data.times,
+            width: data.width,
+            values: &data.values,
+            shape: data.shape,
+        })
     }
 
-    pub fn get_observable(&self, name: &str) -> Option<&Vec<(f64, Complex64)>> {
-        self.observables.get(name)
+    pub fn iter_vector_series(&self) -> impl Iterator<Item = VectorSeries<'_>> {
+        let mut names: Vec<&String> = self.vectors.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .filter_map(|name| self.vector_series(name))
+    }
+
+    pub fn add_snapshot(&mut self, time: f64, amplitudes: &[Complex64]) {
+        self.snapshots.push(StateSnapshot {
+            time,
+            amplitudes: amplitudes.to_vec(),
+        });
+    }
+
+    pub fn snapshots(&self) -> &[StateSnapshot] {
+        &self.snapshots
+    }
+
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    pub fn is_uniformly_sampled(&self) -> bool {
+        if self.times.len() < 3 {
+            return true;
+        }
+        let dt = self.times[1] - self.times[0];
+        self.times
+            .windows(2)
+            .all(|w| ((w[1] - w[0]) - dt).abs() <= 1e-9 * dt.abs().max(1e-300))
+    }
+
+    pub fn series(&self, name: &str) -> Option<TimeSeries<'_>> {
+        let (name, series) = self.observables.get_key_value(name)?;
+        let times = match &series.times {
+            Some(times) => times.as_slice(),
+            None => &self.times[..series.values.len()],
+        };
+
+        Some(TimeSeries {
+            name,
+            times,
+            values: &series.values,
+        })
+    }
+
+    pub fn iter_series(&self) -> impl Iterator<Item = TimeSeries<'_>> {
+        let mut names = self.observable_names();
+        names.sort();
+        names.into_iter().filter_map(|name| self.series(name))
+    }
+
+    pub fn get_observable(&self, name: &str) -> Option<Vec<(f64, Complex64)>> {
+        self.series(name).map(|s| s.iter().collect())
+    }
+
+    pub fn remove_observable(&mut self, name: &str) -> Option<Vec<(f64, Complex64)>> {
+        let data = self.get_observable(name)?;
+        self.observables.remove(name);
+        Some(data)
+    }
+
+    // Swaps in new values for an existing series, keeping its time axis.
+    pub fn set_series_values(&mut self, name: &str, values: Vec<Complex64>) -> Result<()> {
+        let series = self
+            .observables
+            .get_mut(name)
+            .ok_or_else(|| Error::InvalidParameter(format!("Unknown observable '{}'", name)))?;
+        if values.len() != series.values.len() {
+            return Err(Error::dimension_mismatch(series.values.len(), values.len()));
+        }
+        series.values = values;
+        Ok(())
     }
 
     pub fn observable_names(&self) -> Vec<&String> {
         self.observables.keys().collect()
     }
 
-    pub fn save(&self, _path: &Path) -> Result<()> {
-        Err(crate::utils::Error::NotImplemented(
-            "SimulationResults::save".to_string(),
-        ))
+    pub fn set_termination(&mut self, termination: Termination) {
+        self.termination = Some(termination);
+    }
+
+    pub fn termination(&self) -> Option<&Termination> {
+        self.termination.as_ref()
+    }
+
+    pub fn set_resources(&mut self, resources: ResourceUsage) {
+        self.resources = Some(Box::new(resources));
+    }
+
+    pub fn resources(&self) -> Option<&ResourceUsage> {
+        self.resources.as_deref()
+    }
+
+    pub fn extend_step_history(&mut self, steps: impl IntoIterator<Item = AcceptedStep>) {
+        self.step_history.extend(steps);
+    }
+
+    pub fn step_history(&self) -> &[AcceptedStep] {
+        &self.step_history
+    }
+
+    pub fn set_steady_state_options(&mut self, options: SteadyStateOptions) {
+        self.steady_state = options;
+    }
+
+    pub fn steady_state(&self, name: &str) -> Option<SteadyStateEstimate> {
+        steady_state::estimate(&self.series(name)?, &self.steady_state)
+    }
+
+    pub fn steady_states(&self) -> Vec<(&str, SteadyStateEstimate)> {
+        self.iter_series()
+            .filter_map(|series| {
+                Some((
+                    series.name,
+                    steady_state::estimate(&series, &self.steady_state)?,
+                ))
+            })
+            .collect()
+    }
+
+    pub fn spectrogram(&self, name: &str, options: &SpectrogramOptions) -> Result<Spectrogram> {
+        let series = self
+            .series(name)
+            .ok_or_else(|| Error::InvalidParameter(format!("Unknown observable '{}'", name)))?;
+        Spectrogram::compute(&series, options)
+    }
+
+    pub fn events(&self, specs: &[EventSpec]) -> Result<Vec<Event>> {
+        let mut all = Vec::new();
+        for spec in specs {
+            let series = self.series(&spec.observable).ok_or_else(|| {
+                Error::InvalidParameter(format!("Unknown observable '{}'", spec.observable))
+            })?;
+            all.extend(events::detect(&series, spec)?);
+        }
+        all.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Ok(all)
+    }
+
+    pub fn fit(&self, spec: &FitSpec) -> Result<FitResult> {
+        let series = self.series(&spec.observable).ok_or_else(|| {
+            Error::InvalidParameter(format!("Unknown observable '{}'", spec.observable))
+        })?;
+        fit::fit(&series, spec)
+    }
+
+    pub fn is_results_path(path: &Path) -> bool {
+        ResultsFormat::of(path).is_ok()
+    }
+
+    // CSV and JSON hold the scalar observables. The binary format keeps everything,
+    // including vector observables, snapshots and run metadata.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        match ResultsFormat::of(path)? {
+            ResultsFormat::Csv => self.write_csv(path),
+            ResultsFormat::Json => {
+                let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+                serde_json::to_writer_pretty(&mut writer, &self.to_json())
+                    .map_err(|e| Error::Serialization(e.to_string()))?;
+                writer.flush()?;
+                Ok(())
+            }
+            ResultsFormat::Binary => {
+                let mut data = RESULTS_MAGIC.to_vec();
+                data.extend_from_slice(&RESULTS_FORMAT_VERSION.to_le_bytes());
+                bincode::serde::encode_into_std_write(self, &mut data, bincode::config::standard())
+                    .map_err(|e| Error::Serialization(e.to_string()))?;
+                std::fs::write(path, data)?;
+                Ok(())
+            }
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let invalid = |e: &dyn std::fmt::Display| {
+            Error::Serialization(format!("{} is not a results file: {}", path.display(), e))
+        };
+        match ResultsFormat::of(path)? {
+            ResultsFormat::Csv => {
+                Self::read_csv(&std::fs::read_to_string(path)?).map_err(|e| invalid(&e))
+            }
+            ResultsFormat::Json => {
+                let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+                let json: JsonResults = serde_json::from_reader(reader).map_err(|e| invalid(&e))?;
+                Self::from_json(json).map_err(|e| invalid(&e))
+            }
+            ResultsFormat::Binary => {
+                let data = std::fs::read(path)?;
+                let payload = data
+                    .strip_prefix(RESULTS_MAGIC.as_slice())
+                    .ok_or_else(|| invalid(&"missing results header"))?;
+                let (version, payload) = payload
+                    .split_first_chunk::<4>()
+                    .ok_or_else(|| invalid(&"truncated results header"))?;
+                let version = u32::from_le_bytes(*version);
+                if version != RESULTS_FORMAT_VERSION {
+                    return Err(Error::Serialization(format!(
+                        "Results {} use format version {}, expected {}",
+                        path.display(),
+                        version,
+                        RESULTS_FORMAT_VERSION
+                    )));
+                }
+                let (results, _): (Self, _) =
+                    bincode::serde::decode_from_slice(payload, bincode::config::standard())
+                        .map_err(|e| invalid(&e))?;
+                Ok(results)
+            }
+        }
+    }
+
+    // One row per output time. Series sampled off the shared time axis get their
+    // own `<name>_time` column, and cells past the end of a shorter series stay empty.
+    fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let mut names: Vec<&String> = self.observables.keys().collect();
+        names.sort();
+
+        let mut header = vec!["time".to_string()];
+        let mut rows = self.times.len();
+        for name in &names {
+            let series = &self.observables[*name];
+            if let Some(times) = &series.times {
+                header.push(format!("{}_time", name));
+                rows = rows.max(times.len());
+            }
+            header.push(format!("{}_re", name));
+            header.push(format!("{}_im", name));
+            rows = rows.max(series.values.len());
+        }
+        writeln!(writer, "{}", header.join(","))?;
+
+        let cell = |value: Option<f64>| value.map(|v| v.to_string()).unwrap_or_default();
+        for k in 0..rows {
+            let mut row = vec![cell(self.times.get(k).copied())];
+            for name in &names {
+                let series = &self.observables[*name];
+                if let Some(times) = &series.times {
+                    row.push(cell(times.get(k).copied()));
+                }
+                let value = series.values.get(k);
+                row.push(cell(value.map(|v| v.re)));
+                row.push(cell(value.map(|v| v.im)));
+            }
+            writeln!(writer, "{}", row.join(","))?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    fn read_csv(contents: &str) -> Result<Self> {
+        let mut lines = contents.lines();
+        let header: Vec<&str> = lines.next().unwrap_or_default().split(',').collect();
+        if header[0] != "time" {
+            return Err(Error::Serialization(
+                "the first column must be 'time'".to_string(),
+            ));
+        }
+
+        let mut columns: BTreeMap<&str, CsvColumns> = BTreeMap::new();
+        for (idx, column) in header.iter().enumerate().skip(1) {
+            let (name, slot) = if let Some(name) = column.strip_suffix("_time") {
+                (name, &mut columns.entry(name).or_default().time)
+            } else if let Some(name) = column.strip_suffix("_re") {
+                (name, &mut columns.entry(name).or_default().re)
+            } else if let Some(name) = column.strip_suffix("_im") {
+                (name, &mut columns.entry(name).or_default().im)
+            } else {
+                return Err(Error::Serialization(format!(
+                    "column '{}' is not <name>_re, <name>_im or <name>_time",
+                    column
+                )));
+            };
+            if slot.replace(idx).is_some() {
+                return Err(Error::Serialization(format!(
+                    "column '{}' appears twice for observable '{}'",
+                    column, name
+                )));
+            }
+        }
+
+        let mut observables = BTreeMap::new();
+        let mut layout = Vec::new();
+        for (name, cols) in columns {
+            let (Some(re), Some(im)) = (cols.re, cols.im) else {
+                return Err(Error::Serialization(format!(
+                    "observable '{}' needs both {}_re and {}_im columns",
+                    name, name, name
+                )));
+            };
+            let series = Series {
+                values: Vec::new(),
+                times: cols.time.map(|_| Vec::new()),
+            };
+            observables.insert(name.to_string(), series);
+            layout.push((name, cols.time, re, im));
+        }
+
+        let mut times = Vec::new();
+
+        for (line, row) in lines.enumerate() {
+            let cells: Vec<&str> = row.split(',').collect();
+            let number = |idx: usize| -> Result<Option<f64>> {
+                match cells.get(idx).map(|c| c.trim()) {
+                    None | Some("") => Ok(None),
+                    Some(cell) => cell.parse().map(Some).map_err(|_| {
+                        Error::Serialization(format!(
+                            "row {}, column '{}': '{}' is not a number",
+                            line + 2,
+                            header[idx],
+                            cell
+                        ))
+                    }),
+                }
+            };
+
+            if let Some(t) = number(0)? {
+                times.push(t);
+            }
+            for &(name, time, re, im) in &layout {
+                let series = observables
+                    .get_mut(name)
+                    .expect("one series per observable");
+                if let (Some(own), Some(idx)) = (&mut series.times, time) {
+                    own.extend(number(idx)?);
+                }
+                match (number(re)?, number(im)?) {
+                    (Some(re), Some(im)) => series.values.push(Complex64::new(re, im)),
+                    (None, None) => {}
+                    _ => {
+                        return Err(Error::Serialization(format!(
+                            "row {}: observable '{}' has only half of a complex value",
+                            line + 2,
+                            name
+                        )))
+                    }
+                }
+            }
+        }
+
+        Self::from_series(times, observables)
+    }
+
+    fn to_json(&self) -> JsonResults {
+        JsonResults {
+            times: self.times.clone(),
+            observables: self
+                .observables
+                .iter()
+                .map(|(name, series)| {
+                    let json = JsonSeries {
+                        times: series.times.clone(),
+                        re: series.values.iter().map(|v| v.re).collect(),
+                        im: series.values.iter().map(|v| v.im).collect(),
+                    };
+                    (name.clone(), json)
+                })
+                .collect(),
+        }
+    }
+
+    fn from_json(json: JsonResults) -> Result<Self> {
+        let mut observables = BTreeMap::new();
+        for (name, series) in json.observables {
+            if series.re.len() != series.im.len() {
+                return Err(Error::Serialization(format!(
+                    "observable '{}' has {} real and {} imaginary parts",
+                    name,
+                    series.re.len(),
+                    series.im.len()
+                )));
+            }
+            let values = series
+                .re
+                .into_iter()
+                .zip(series.im)
+                .map(|(re, im)| Complex64::new(re, im))
+                .collect();
+            observables.insert(
+                name,
+                Series {
+                    values,
+                    times: series.times,
+                },
+            );
+        }
+        Self::from_series(json.times, observables)
+    }
+
+    fn from_series(times: Vec<f64>, observables: BTreeMap<String, Series>) -> Result<Self> {
+        for (name, series) in &observables {
+            // Series on the shared axis may stop early, own time axes must match.
+            let consistent = match &series.times {
+                Some(own) => own.len() == series.values.len(),
+                None => series.values.len() <= times.len(),
+            };
+            if !consistent {
+                return Err(Error::Serialization(format!(
+                    "observable '{}' has {} values for {} times",
+                    name,
+                    series.values.len(),
+                    series.times.as_ref().map_or(times.len(), Vec::len)
+                )));
+            }
+        }
+        Ok(Self {
+            times,
+            observables: observables.into_iter().collect(),
+            ..Self::new()
+        })
     }
 
     pub fn print_summary(&self) {
         println!("Simulation Results:");
         println!("  Observables: {:?}", self.observable_names());
-        for (name, data) in &self.observables {
-            println!("  {}: {} data points", name, data.len());
+        for series in self.iter_series() {
+            println!("  {}: {} data points", series.name, series.len());
+        }
+        for series in self.iter_vector_series() {
+            println!(
+                "  {}: {} data points x {} components",
+                series.name,
+                series.len(),
+                series.width
+            );
+        }
+
+        if let Some(termination) = &self.termination {
+            println!(
+                "  Stopped early at t = {:.3}: {}",
+                termination.time, termination.reason
+            );
+        }
+
+        if let Some(resources) = &self.resources {
+            println!("  Resources: {}", resources.describe());
+        }
+
+        for (name, est) in self.steady_states() {
+            print!(
+                "  {} (t >= {:.3}): {:.6} ± {:.6}, tau_int = {:.1} samples",
+                name, est.tail_start, est.mean, est.std_error, est.autocorrelation_time
+            );
+            match est.converged_at {
+                Some(t) => println!(", steady state reached at t≈{:.3}", t),
+                None => println!(", not converged"),
+            }
         }
     }
 }
@@ -50,3 +715,105 @@ impl Default for SimulationResults {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_series_share_time_axis() {
+        let mut results = SimulationResults::new();
+        for i in 0..5 {
+            let t = i as f64 * 0.5;
+            results.add_observable("a", t, Complex64::new(t, 0.0));
+            results.add_observable("b", t, Complex64::new(0.0, t));
+        }
+
+        assert_eq!(results.times().len(), 5);
+        let series: Vec<_> = results.iter_series().collect();
+        assert_eq!(series[0].name, "a");
+        assert_eq!(series[1].times, results.times());
+        assert_eq!(series[0].to_vec_real(), vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+        assert_eq!(series[1].to_ndarray()[2], Complex64::new(0.0, 1.0));
+    }
+
+    #[test]
+    fn test_irregular_series_keeps_own_times() {
+        let mut results = SimulationResults::new();
+        results.add_observable("a", 0.0, Complex64::new(1.0, 0.0));
+        results.add_observable("a", 1.0, Complex64::new(1.0, 0.0));
+        results.add_observable("b", 0.0, Complex64::new(2.0, 0.0));
+        results.add_observable("b", 2.0, Complex64::new(2.0, 0.0));
+
+        assert_eq!(results.series("a").unwrap().times, &[0.0, 1.0]);
+        assert_eq!(results.series("b").unwrap().times, &[0.0, 2.0]);
+    }
+
+    #[test]
+    fn test_vector_observables() {
+        let mut results = SimulationResults::new();
+        for i in 0..3 {
+            let t = i as f64;
+            let row = [Complex64::new(t, 0.0), Complex64::new(1.0 - t, 0.0)];
+            results.add_vector_observable("bloch", t, &row).unwrap();
+        }
+
+        let series = results.vector_series("bloch").unwrap();
+        assert_eq!((series.len(), series.width), (3, 2));
+        assert_eq!(series.row(2)[1], Complex64::new(-1.0, 0.0));
+        assert_eq!(series.component(0)[1], Complex64::new(1.0, 0.0));
+        assert_eq!(series.to_ndarray().dim(), (3, 2));
+        assert!(results
+            .add_vector_observable("bloch", 3.0, &[Complex64::new(0.0, 0.0)])
+            .is_err());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let mut results = SimulationResults::new();
+        for i in 0..4 {
+            let t = i as f64 * 0.1;
+            results.add_observable("population", t, Complex64::new(t.cos(), 0.0));
+            results.add_observable("coherence", t, Complex64::new(0.5, -t / 3.0));
+        }
+        results.add_observable("late", 0.0, Complex64::new(1.0, 0.0));
+        results.add_observable("late", 5.0, Complex64::new(2.0, 1.0));
+        results
+            .add_vector_observable("bloch", 0.0, &[Complex64::new(1.0, 0.0)])
+            .unwrap();
+
+        let dir = std::env::temp_dir().join(format!("chronophoton_results_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for file in ["results.csv", "results.json", "results.bin"] {
+            let path = dir.join(file);
+            results.save(&path).unwrap();
+            let loaded = SimulationResults::load(&path).unwrap();
+            assert_eq!(loaded.times(), results.times(), "{}", file);
+            for series in results.iter_series() {
+                let other = loaded.series(series.name).unwrap();
+                assert_eq!(other.times, series.times, "{} in {}", series.name, file);
+                assert_eq!(other.values, series.values, "{} in {}", series.name, file);
+            }
+            assert_eq!(
+                loaded.vector_series("bloch").is_some(),
+                file.ends_with(".bin")
+            );
+        }
+
+        let csv = std::fs::read_to_string(dir.join("results.csv")).unwrap();
+        assert!(csv.starts_with("time,coherence_re,coherence_im,late_time,late_re,late_im,"));
+
+        let bin = std::fs::read(dir.join("results.bin")).unwrap();
+        assert!(bin.starts_with(RESULTS_MAGIC));
+        results.save(&dir.join("again.bin")).unwrap();
+        assert_eq!(std::fs::read(dir.join("again.bin")).unwrap(), bin);
+        let mut stale = bin.clone();
+        stale[RESULTS_MAGIC.len()..RESULTS_MAGIC.len() + 4].copy_from_slice(&0u32.to_le_bytes());
+        std::fs::write(dir.join("stale.bin"), stale).unwrap();
+        assert!(SimulationResults::load(&dir.join("stale.bin")).is_err());
+        std::fs::write(dir.join("broken.csv"), "time,population_re\n0,1\n").unwrap();
+        assert!(SimulationResults::load(&dir.join("broken.csv")).is_err());
+        assert!(results.save(&dir.join("results.txt")).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,6 +1,34 @@
-use crate::core::{integrator, Hamiltonian, IntegratorType, Observable, QuantumState};
-use crate::simulation::SimulationResults;
-use crate::utils::Result;
+use crate::core::floquet::FloquetSpectrum;
+use crate::core::hamiltonian::TermCost;
+use crate::core::lindblad::{LindbladOperator, LindbladSolver};
+use crate::core::symmetry;
+use crate::core::{
+    integrator, DensityMatrix, Hamiltonian, IntegratorType, Observable, Precision, QuantumState,
+    VectorObservable,
+};
+use crate::data::Checkpoint;
+use crate::simulation::guard::{self, StepContext};
+use crate::simulation::{
+    AcceptedStep, EffectiveSpectrum, FloquetModes, JobControl, PrethermalModes,
+    QuantumTrajectories, SamplingOptions, ShotSampler, SimulationResults, StateSnapshot,
+    SteadyStateOptions, StopCondition, Termination, TimeGrid,
+};
+use crate::utils::resources::ResourceMeter;
+use crate::utils::{Error, Result};
+use ndarray::Array1;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const CONTROL_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+struct CancelOnDrop(Arc<JobControl>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
 
 pub struct SimulationRunner {
     hamiltonian: Box<dyn Hamiltonian>,
@@ -9,10 +37,30 @@ pub struct SimulationRunner {
     timestep: f64,
     integrator: Box<dyn crate::core::Integrator>,
     observables: Vec<(String, Box<dyn Observable>)>,
+    vector_observables: Vec<(String, Box<dyn VectorObservable>)>,
+    floquet: Option<(String, FloquetModes)>,
+    prethermal: Option<(String, PrethermalModes)>,
+    embedding: Option<(Vec<usize>, usize)>,
+    time_grid: TimeGrid,
+    max_retries: u32,
+    warmup_duration: f64,
+    record_states: bool,
+    stop_conditions: Vec<StopCondition>,
+    steady_state: SteadyStateOptions,
+    sampler: Option<ShotSampler>,
+    control: Option<Arc<JobControl>>,
+    checkpoints: Option<(PathBuf, f64)>,
+    resume: Option<Checkpoint>,
+    dissipation: Option<Dissipation>,
     #[allow(dead_code)]
     gpu_enabled: bool,
 }
 
+enum Dissipation {
+    MasterEquation(LindbladSolver),
+    Trajectories(QuantumTrajectories),
+}
+
 impl SimulationRunner {
     pub fn new(
         hamiltonian: Box<dyn Hamiltonian>,
@@ -23,6 +71,13 @@ impl SimulationRunner {
         observables: Vec<(String, Box<dyn Observable>)>,
         gpu_enabled: bool,
     ) -> Result<Self> {
+        if integrator_type.is_exact() && !hamiltonian.is_time_independent() {
+            return Err(Error::Config(
+                "The exact integrator needs a time-independent Hamiltonian, use rk4 or rk45 for \
+                 time-dependent systems"
+                    .to_string(),
+            ));
+        }
         let integrator = integrator::create_integrator(integrator_type);
 
         Ok(Self {
@@ -32,34 +87,1153 @@ impl SimulationRunner {
             timestep,
             integrator,
             observables,
+            vector_observables: Vec::new(),
+            floquet: None,
+            prethermal: None,
+            embedding: None,
+            time_grid: TimeGrid::default(),
+            max_retries: 0,
+            warmup_duration: 0.0,
+            record_states: false,
+            stop_conditions: Vec::new(),
+            steady_state: SteadyStateOptions::default(),
+            sampler: None,
+            control: None,
+            checkpoints: None,
+            resume: None,
+            dissipation: None,
             gpu_enabled,
         })
     }
 
+    pub fn with_time_grid(mut self, time_grid: TimeGrid) -> Result<Self> {
+        time_grid.validate(self.duration)?;
+        self.time_grid = time_grid;
+        Ok(self)
+    }
+
+    pub fn with_precision(mut self, precision: Precision) -> Result<Self> {
+        if precision != self.integrator.precision() {
+            self.integrator = integrator::create_integrator_with_precision(
+                self.integrator.integrator_type(),
+                precision,
+            )?;
+        }
+        self.check_open_precision()?;
+        Ok(self)
+    }
+
+    pub fn with_vector_observables(
+        mut self,
+        vector_observables: Vec<(String, Box<dyn VectorObservable>)>,
+    ) -> Self {
+        self.vector_observables = vector_observables;
+        self
+    }
+
+    pub fn with_floquet_populations(
+        mut self,
+        name: impl Into<String>,
+        period: f64,
+        num_steps: usize,
+    ) -> Self {
+        self.floquet = Some((name.into(), FloquetModes::new(period, num_steps)));
+        self
+    }
+
+    pub fn with_prethermal_populations(
+        mut self,
+        name: impl Into<String>,
+        period: f64,
+        samples: usize,
+    ) -> Self {
+        self.prethermal = Some((name.into(), PrethermalModes::new(period, samples)));
+        self
+    }
+
+    pub fn with_embedding(mut self, indices: Vec<usize>, dim: usize) -> Self {
+        self.embedding = Some((indices, dim));
+        self
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn with_warmup_duration(mut self, warmup_duration: f64) -> Self {
+        self.warmup_duration = warmup_duration;
+        self
+    }
+
+    pub fn with_record_states(mut self, record_states: bool) -> Self {
+        self.record_states = record_states;
+        self
+    }
+
+    pub fn with_stop_conditions(mut self, stop_conditions: Vec<StopCondition>) -> Self {
+        self.stop_conditions = stop_conditions;
+        self
+    }
+
+    pub fn with_steady_state_options(mut self, options: SteadyStateOptions) -> Self {
+        self.steady_state = options;
+        self
+    }
+
+    pub fn with_sampling(mut self, options: SamplingOptions) -> Self {
+        self.sampler = (options.shots > 0).then(|| ShotSampler::new(&options, &self.observables));
+        self
+    }
+
+    pub fn with_control(mut self, control: Arc<JobControl>) -> Self {
+        self.control = Some(control);
+        self
+    }
+
+    // The open-system solvers share the Hamiltonian with the runner, which keeps it for
+    // cost estimates.
+    pub fn with_master_equation(
+        mut self,
+        collapse_operators: Vec<LindbladOperator>,
+    ) -> Result<Self> {
+        let hamiltonian: Arc<dyn Hamiltonian> = Arc::from(self.hamiltonian);
+        self.hamiltonian = Box::new(hamiltonian.clone());
+        self.dissipation = Some(Dissipation::MasterEquation(LindbladSolver::new(
+            Box::new(hamiltonian),
+            collapse_operators,
+        )?));
+        self.check_open_precision()?;
+        Ok(self)
+    }
+
+    pub fn with_trajectories(
+        mut self,
+        collapse_operators: Vec<LindbladOperator>,
+        count: usize,
+        seed: Option<u64>,
+    ) -> Result<Self> {
+        let hamiltonian: Arc<dyn Hamiltonian> = Arc::from(self.hamiltonian);
+        self.hamiltonian = Box::new(hamiltonian.clone());
+        let solver = QuantumTrajectories::new(Box::new(hamiltonian), collapse_operators, count)?;
+        self.dissipation = Some(Dissipation::Trajectories(match seed {
+            Some(seed) => solver.seed(seed),
+            None => solver,
+        }));
+        self.check_open_precision()?;
+        Ok(self)
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.dissipation.is_some()
+    }
+
+    fn check_open_precision(&self) -> Result<()> {
+        if self.is_open() && self.integrator.precision() == Precision::Single {
+            return Err(Error::Config(
+                "Open systems run in f64 only, the master equation and trajectory solvers have no f32 path"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn with_checkpoints(mut self, path: PathBuf, interval: f64) -> Result<Self> {
+        if self.is_open() {
+            return Err(Error::Config(
+                "Checkpoints store state vectors and are not supported for open systems"
+                    .to_string(),
+            ));
+        }
+        if !(interval > 0.0 && interval.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "Checkpoint interval must be positive, got {}",
+                interval
+            )));
+        }
+        self.checkpoints = Some((path, interval));
+        Ok(self)
+    }
+
+    pub fn with_preemption_checkpoint(mut self, path: PathBuf) -> Self {
+        if self.is_open() {
+            tracing::warn!("Open systems are not checkpointed, a preempted run starts over");
+            return self;
+        }
+        self.checkpoints = Some((path, f64::INFINITY));
+        self
+    }
+
+    pub fn with_resume(mut self, checkpoint: Checkpoint) -> Result<Self> {
+        if self.is_open() {
+            return Err(Error::Config(
+                "Open systems cannot resume from a state-vector checkpoint".to_string(),
+            ));
+        }
+        if checkpoint.amplitudes.len() != self.initial_state.dim() {
+            return Err(Error::DimensionMismatch {
+                expected: self.initial_state.dim(),
+                actual: checkpoint.amplitudes.len(),
+            });
+        }
+        if checkpoint.time > self.warmup_duration + self.duration {
+            return Err(Error::InvalidParameter(format!(
+                "Checkpoint at t = {} is past the end of the simulation",
+                checkpoint.time
+            )));
+        }
+        QuantumState::new(Array1::from_vec(checkpoint.amplitudes.clone()))?;
+        tracing::info!("Resuming from checkpoint at t = {:.4}", checkpoint.time);
+        self.resume = Some(checkpoint);
+        Ok(self)
+    }
+
     pub fn run(&self) -> Result<SimulationResults> {
+        let mut timestep = self.timestep;
+        let mut retries = 0;
+        let meter = ResourceMeter::start();
+
+        loop {
+            match self.run_with_timestep(timestep) {
+                Ok(mut results) => {
+                    results.set_resources(meter.finish());
+                    return Ok(results);
+                }
+                Err(Error::Numerical(message)) if retries < self.max_retries => {
+                    retries += 1;
+                    timestep /= 2.0;
+                    tracing::warn!(
+                        "{}. Retrying with dt = {:.3e} ({}/{})",
+                        message,
+                        timestep,
+                        retries,
+                        self.max_retries
+                    );
+                }
+                result => return result,
+            }
+        }
+    }
+
+    pub fn floquet_spectrum(&self) -> Result<Option<&FloquetSpectrum>> {
+        self.floquet
+            .as_ref()
+            .map(|(_, modes)| modes.spectrum(self.hamiltonian.as_ref()))
+            .transpose()
+    }
+
+    pub fn floquet_modes(&self) -> Option<&FloquetModes> {
+        self.floquet.as_ref().map(|(_, modes)| modes)
+    }
+
+    pub fn effective_spectrum(&self) -> Result<Option<&EffectiveSpectrum>> {
+        self.prethermal
+            .as_ref()
+            .map(|(_, modes)| modes.spectrum(self.hamiltonian.as_ref()))
+            .transpose()
+    }
+
+    pub fn prethermal_modes(&self) -> Option<&PrethermalModes> {
+        self.prethermal.as_ref().map(|(_, modes)| modes)
+    }
+
+    pub fn hamiltonian_costs(&self, samples: usize) -> Vec<TermCost> {
+        self.hamiltonian.term_costs(samples)
+    }
+
+    // Times a few steps from the initial state and scales them up to the whole run.
+    // Integrators that jump straight to each output are scaled by the output count.
+    pub fn estimate_seconds(&self, probe_steps: usize) -> Result<f64> {
+        let total = self.warmup_duration + self.duration;
+        let probe_end = (probe_steps.max(1) as f64 * self.timestep).min(total);
+        let start = Instant::now();
+        match &self.dissipation {
+            Some(Dissipation::MasterEquation(solver)) => {
+                let mut rho = self.initial_state.to_density_matrix();
+                let (mut t, mut step) = (0.0, 0);
+                self.advance_density(
+                    solver,
+                    &mut rho,
+                    (&mut t, &mut step),
+                    self.timestep,
+                    probe_end,
+                )?;
+            }
+            Some(Dissipation::Trajectories(solver)) => {
+                solver.run(
+                    &self.initial_state,
+                    &self.observables,
+                    (0.0, probe_end),
+                    self.timestep,
+                )?;
+            }
+            None => {
+                self.integrator.clear_cache();
+                let mut states = StateIter {
+                    runner: self,
+                    timestep: self.timestep,
+                    output_times: Vec::new().into_iter(),
+                    state: self.initial_state.clone(),
+                    t: 0.0,
+                    step: 0,
+                    history: Vec::new(),
+                    warmed_up: true,
+                    finished: false,
+                };
+                self.advance(&mut states, probe_end)?;
+            }
+        }
+        let elapsed = start.elapsed().as_secs_f64();
+
+        let integrator_type = self.integrator.integrator_type();
+        let jumps_to_outputs = !self.is_open()
+            && (integrator_type.is_exact()
+                || (integrator_type.is_krylov() && self.hamiltonian.is_time_independent()));
+        Ok(if jumps_to_outputs {
+            let outputs = self
+                .time_grid
+                .output_times(self.duration, self.timestep)
+                .len();
+            elapsed * outputs.max(1) as f64
+        } else {
+            elapsed * total / probe_end
+        })
+    }
+
+    pub fn iter(&self) -> StateIter<'_> {
+        self.iter_with_timestep(self.timestep)
+    }
+
+    fn iter_with_timestep(&self, timestep: f64) -> StateIter<'_> {
+        self.integrator.clear_cache();
+        let mut output_times = self.time_grid.output_times(self.duration, self.timestep);
+        let (state, t, step) = match &self.resume {
+            Some(checkpoint) => {
+                let resumed = checkpoint.time - self.warmup_duration + 1e-9 * timestep;
+                output_times.retain(|&t_out| t_out > resumed);
+                (
+                    QuantumState::new(Array1::from_vec(checkpoint.amplitudes.clone()))
+                        .unwrap_or_else(|_| self.initial_state.clone()),
+                    checkpoint.time,
+                    checkpoint.step,
+                )
+            }
+            None => (self.initial_state.clone(), 0.0, 0),
+        };
+
+        StateIter {
+            runner: self,
+            timestep,
+            output_times: output_times.into_iter(),
+            state,
+            warmed_up: t >= self.warmup_duration,
+            t,
+            step,
+            history: Vec::new(),
+            finished: false,
+        }
+    }
+
+    fn initial_results(&self) -> SimulationResults {
+        let mut results = self
+            .resume
+            .as_ref()
+            .and_then(|checkpoint| checkpoint.results.clone())
+            .unwrap_or_default();
+        results.set_steady_state_options(self.steady_state.clone());
+        results
+    }
+
+    fn save_checkpoint(
+        &self,
+        states: &StateIter,
+        results: &SimulationResults,
+        next: &mut f64,
+        complete: bool,
+    ) -> Result<()> {
+        let Some((path, interval)) = &self.checkpoints else {
+            return Ok(());
+        };
+        if !complete && states.t < *next - 1e-9 * states.timestep {
+            return Ok(());
+        }
+        while *next <= states.t {
+            *next += interval;
+        }
+        tracing::debug!("Writing checkpoint at t = {:.4} to {:?}", states.t, path);
+        Checkpoint {
+            time: states.t,
+            step: states.step,
+            amplitudes: states.state.data().to_vec(),
+            results: Some(results.clone()),
+            complete,
+        }
+        .save(path)
+    }
+
+    fn flush_on_cancel(
+        &self,
+        states: &StateIter,
+        results: &SimulationResults,
+        error: Error,
+    ) -> Error {
+        if !matches!(error, Error::Cancelled(_)) {
+            return error;
+        }
+        let mut next = states.t;
+        match self.save_checkpoint(states, results, &mut next, false) {
+            Ok(()) => error,
+            Err(e) => {
+                tracing::error!("Failed to write checkpoint after cancellation: {}", e);
+                e
+            }
+        }
+    }
+
+    fn first_checkpoint_time(&self, states: &StateIter) -> f64 {
+        states.t
+            + self
+                .checkpoints
+                .as_ref()
+                .map_or(0.0, |(_, interval)| *interval)
+    }
+
+    fn run_with_timestep(&self, timestep: f64) -> Result<SimulationResults> {
+        if let Some(dissipation) = &self.dissipation {
+            return self.run_open(dissipation, timestep);
+        }
         tracing::info!("Starting simulation");
 
-        let num_steps = (self.duration / self.timestep).ceil() as usize;
-        let mut state = self.initial_state.clone();
-        let mut results = SimulationResults::new();
+        let mut results = self.initial_results();
+        let mut states = self.iter_with_timestep(timestep);
+        let mut next_checkpoint = self.first_checkpoint_time(&states);
+
+        loop {
+            let (t_out, state) = match states.next_state() {
+                Ok(Some(output)) => output,
+                Ok(None) => break,
+                Err(e) => return Err(self.flush_on_cancel(&states, &results, e)),
+            };
+            if self.record_output(&mut results, t_out, state)? {
+                break;
+            }
+            results.extend_step_history(states.history.drain(..));
+            self.save_checkpoint(&states, &results, &mut next_checkpoint, false)?;
+        }
+        results.extend_step_history(states.history.drain(..));
+        self.save_checkpoint(&states, &results, &mut next_checkpoint, true)?;
+
+        tracing::info!("Simulation complete");
+        Ok(results)
+    }
+
+    // Runs the blocking loop on tokio's blocking pool. The run gets its own
+    // control, so a timeout or dropping the future stops this run only, while
+    // cancelling a control passed to `with_control` still reaches it.
+    pub async fn run_async(self) -> Result<SimulationResults> {
+        self.run_blocking(None).await
+    }
+
+    pub async fn run_with_timeout(self, timeout: Duration) -> Result<SimulationResults> {
+        self.run_blocking(Some(timeout)).await
+    }
 
-        for step in 0..num_steps {
-            let t = step as f64 * self.timestep;
+    async fn run_blocking(mut self, timeout: Option<Duration>) -> Result<SimulationResults> {
+        let outer = self.control.take();
+        let control = Arc::new(JobControl::new());
+        self.control = Some(control.clone());
+        let _cancel_on_drop = CancelOnDrop(control.clone());
 
-            for (name, observable) in &self.observables {
-                let value = observable.expectation_pure(&state);
-                results.add_observable(name, t, value);
+        let deadline = timeout.map(|timeout| tokio::time::Instant::now() + timeout);
+        let mut poll = tokio::time::interval(CONTROL_POLL_INTERVAL);
+        let mut handle = tokio::task::spawn_blocking(move || self.run());
+        let mut timed_out = false;
+        loop {
+            tokio::select! {
+                joined = &mut handle => {
+                    return match joined.map_err(|e| Error::Other(e.into()))? {
+                        Err(Error::Cancelled(_)) if timed_out => Err(Error::Cancelled(format!(
+                            "simulation timed out after {:?}",
+                            timeout.unwrap_or_default()
+                        ))),
+                        result => result,
+                    };
+                }
+                _ = poll.tick() => {
+                    if let Some(outer) = &outer {
+                        outer.set_progress(control.progress());
+                        if outer.is_cancelled() {
+                            control.cancel();
+                        }
+                    }
+                    if deadline.is_some_and(|deadline| tokio::time::Instant::now() >= deadline) {
+                        timed_out = true;
+                        control.cancel();
+                    }
+                }
             }
+        }
+    }
 
-            self.integrator
-                .step(self.hamiltonian.as_ref(), &mut state, t, self.timestep)?;
+    fn record_output(
+        &self,
+        results: &mut SimulationResults,
+        t_out: f64,
+        state: &QuantumState,
+    ) -> Result<bool> {
+        let _span = tracing::trace_span!("observables").entered();
+        for (name, observable) in &self.observables {
+            let value = observable.expectation_pure(state);
+            results.add_observable(name, t_out, value);
+        }
+        if let Some(sampler) = &self.sampler {
+            sampler.record(results, t_out, state)?;
+        }
 
-            if step % 100 == 0 {
-                tracing::debug!("Step {}/{}", step, num_steps);
+        for (name, observable) in &self.vector_observables {
+            let values = observable.expectation_pure(state);
+            results.add_vector_observable(name, t_out, &values)?;
+            if let Some((rows, cols)) = observable.shape() {
+                results.set_vector_shape(name, rows, cols)?;
+            }
+        }
+
+        if let Some((name, modes)) = &self.floquet {
+            let populations = modes.populations(self.hamiltonian.as_ref(), state)?;
+            results.add_vector_observable(name, t_out, &populations)?;
+        }
+
+        if let Some((name, modes)) = &self.prethermal {
+            let populations = modes.populations(self.hamiltonian.as_ref(), state)?;
+            results.add_vector_observable(name, t_out, &populations)?;
+        }
+
+        if self.record_states {
+            let amplitudes = state.data().to_vec();
+            match &self.embedding {
+                Some((indices, dim)) => {
+                    results.add_snapshot(t_out, &symmetry::embed(&amplitudes, indices, *dim))
+                }
+                None => results.add_snapshot(t_out, &amplitudes),
+            }
+        }
+
+        Ok(self.finish_output(results, t_out))
+    }
+
+    fn finish_output(&self, results: &mut SimulationResults, t_out: f64) -> bool {
+        if let Some(control) = &self.control {
+            control.set_progress(t_out / (self.warmup_duration + self.duration));
+        }
+
+        if let Some(condition) = self.stop_conditions.iter().find(|c| c.is_met(results)) {
+            let reason = condition.describe();
+            tracing::info!("Stopping at t = {:.4}: {}", t_out, reason);
+            results.set_termination(Termination {
+                time: t_out,
+                reason,
+            });
+            return true;
+        }
+        false
+    }
+
+    fn run_open(&self, dissipation: &Dissipation, timestep: f64) -> Result<SimulationResults> {
+        tracing::info!("Starting open-system simulation");
+        if self.record_states {
+            tracing::info!("Open systems evolve a density matrix, state snapshots are skipped");
+        }
+
+        let mut results = self.initial_results();
+        let output_times = self.time_grid.output_times(self.duration, self.timestep);
+        match dissipation {
+            Dissipation::MasterEquation(solver) => {
+                let mut rho = self.initial_state.to_density_matrix();
+                let (mut t, mut step) = (0.0, 0);
+                self.advance_density(
+                    solver,
+                    &mut rho,
+                    (&mut t, &mut step),
+                    timestep,
+                    self.warmup_duration,
+                )?;
+                for t_out in output_times {
+                    let t_out = self.warmup_duration + t_out;
+                    self.advance_density(solver, &mut rho, (&mut t, &mut step), timestep, t_out)?;
+                    for (name, observable) in &self.observables {
+                        results.add_observable(name, t_out, observable.expectation_mixed(&rho));
+                    }
+                    for (name, observable) in &self.vector_observables {
+                        results.add_vector_observable(
+                            name,
+                            t_out,
+                            &observable.expectation_mixed(&rho),
+                        )?;
+                        if let Some((rows, cols)) = observable.shape() {
+                            results.set_vector_shape(name, rows, cols)?;
+                        }
+                    }
+                    if self.finish_output(&mut results, t_out) {
+                        break;
+                    }
+                }
+            }
+            // Trajectories record every step, so each output time takes the nearest one.
+            Dissipation::Trajectories(solver) => {
+                let span = (0.0, self.warmup_duration + self.duration);
+                let averaged = solver.run_with_control(
+                    &self.initial_state,
+                    &self.observables,
+                    span,
+                    timestep,
+                    self.control.as_deref(),
+                )?;
+                for t_out in output_times {
+                    let t_out = self.warmup_duration + t_out;
+                    let k = (t_out / timestep).round() as usize;
+                    for (name, _) in &self.observables {
+                        for name in [name.clone(), format!("{}_std", name)] {
+                            let series = averaged.series(&name).ok_or_else(|| {
+                                Error::Numerical(format!("Trajectories did not record '{}'", name))
+                            })?;
+                            let value = series.values[k.min(series.values.len() - 1)];
+                            results.add_observable(&name, t_out, value);
+                        }
+                    }
+                    if self.finish_output(&mut results, t_out) {
+                        break;
+                    }
+                }
             }
         }
 
         tracing::info!("Simulation complete");
         Ok(results)
     }
+
+    fn advance_density(
+        &self,
+        solver: &LindbladSolver,
+        rho: &mut DensityMatrix,
+        (t, step): (&mut f64, &mut usize),
+        timestep: f64,
+        t_end: f64,
+    ) -> Result<()> {
+        while t_end - *t > 1e-9 * timestep {
+            if let Some(control) = &self.control {
+                control.check()?;
+            }
+            let dt = timestep.min(t_end - *t);
+            solver.step(rho, *t, dt)?;
+            *t += dt;
+            *step += 1;
+            guard::check_density_matrix(
+                rho,
+                &StepContext {
+                    step: *step,
+                    time: *t,
+                    timestep: dt,
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    fn advance(&self, states: &mut StateIter<'_>, t_end: f64) -> Result<()> {
+        let integrator_type = self.integrator.integrator_type();
+        let adaptive = integrator_type.is_adaptive();
+        while t_end - states.t > 1e-9 * states.timestep {
+            if let Some(control) = &self.control {
+                control.check()?;
+            }
+            let dt = if integrator_type.is_exact()
+                || (integrator_type.is_krylov() && self.hamiltonian.is_time_independent())
+            {
+                t_end - states.t
+            } else {
+                states.timestep.min(t_end - states.t)
+            };
+            let outcome = self.integrator.step_adaptive(
+                self.hamiltonian.as_ref(),
+                &mut states.state,
+                states.t,
+                dt,
+            )?;
+            states.t += outcome.taken;
+            states.step += 1;
+            if adaptive {
+                states.history.push(AcceptedStep {
+                    time: states.t,
+                    dt: outcome.taken,
+                });
+                states.timestep = if outcome.taken < dt || dt >= states.timestep {
+                    outcome.next
+                } else {
+                    states.timestep.max(outcome.next)
+                };
+            }
+
+            guard::check_state(
+                &states.state,
+                &StepContext {
+                    step: states.step,
+                    time: states.t,
+                    timestep: outcome.taken,
+                },
+            )?;
+
+            if states.step.is_multiple_of(100) {
+                tracing::debug!("Step {} (t = {:.4})", states.step, states.t);
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct StateIter<'a> {
+    runner: &'a SimulationRunner,
+    timestep: f64,
+    output_times: std::vec::IntoIter<f64>,
+    state: QuantumState,
+    t: f64,
+    step: usize,
+    history: Vec<AcceptedStep>,
+    warmed_up: bool,
+    finished: bool,
+}
+
+impl StateIter<'_> {
+    pub fn next_state(&mut self) -> Result<Option<(f64, &QuantumState)>> {
+        if self.finished {
+            return Ok(None);
+        }
+        let result = self.advance_to_next_output();
+        if !matches!(result, Ok(Some(_))) {
+            self.finished = true;
+        }
+        Ok(result?.map(|t| (t, &self.state)))
+    }
+
+    fn advance_to_next_output(&mut self) -> Result<Option<f64>> {
+        let runner = self.runner;
+        if runner.is_open() {
+            return Err(Error::Config(
+                "Open systems evolve a density matrix and cannot be iterated as state vectors"
+                    .to_string(),
+            ));
+        }
+        if !self.warmed_up {
+            tracing::info!("Warming up for t = {}", runner.warmup_duration);
+            runner.advance(self, runner.warmup_duration)?;
+            self.warmed_up = true;
+        }
+
+        let Some(t_out) = self.output_times.next() else {
+            return Ok(None);
+        };
+        let t_out = runner.warmup_duration + t_out;
+        runner.advance(self, t_out)?;
+        Ok(Some(t_out))
+    }
+}
+
+impl Iterator for StateIter<'_> {
+    type Item = Result<(f64, StateSnapshot)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_state() {
+            Ok(Some((time, state))) => Some(Ok((
+                time,
+                StateSnapshot {
+                    time,
+                    amplitudes: state.data().to_vec(),
+                },
+            ))),
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+impl std::iter::FusedIterator for StateIter<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::observables::{BlochVector, PopulationOperator};
+    use crate::core::systems::DrivenTLS;
+    use crate::core::QuantumState;
+    use crate::simulation::{SimulationBuilder, TimeGrid};
+    use approx::assert_relative_eq;
+    use std::time::Duration;
+
+    #[test]
+    fn test_adaptive_integrator_grows_steps_for_slow_dynamics() {
+        use crate::core::hamiltonian::TimeIndependentHamiltonian;
+        use crate::core::IntegratorType;
+        use ndarray::Array2;
+        use num_complex::Complex64;
+
+        let mut h = Array2::zeros((2, 2));
+        h[[0, 1]] = Complex64::new(0.01, 0.0);
+        h[[1, 0]] = Complex64::new(0.01, 0.0);
+        let sim = SimulationBuilder::new()
+            .hamiltonian(TimeIndependentHamiltonian::new(h))
+            .initial_state(QuantumState::ground_state(2))
+            .duration(100.0)
+            .timestep(0.01)
+            .integrator(IntegratorType::AdaptiveRK45 {
+                atol: 1e-10,
+                rtol: 1e-8,
+            })
+            .observable("p0", PopulationOperator::new(2, 0).unwrap())
+            .time_grid(TimeGrid::explicit(vec![0.0, 50.0, 100.0]))
+            .build()
+            .unwrap();
+
+        let results = sim.run().unwrap();
+        let history = results.step_history();
+        assert!(history.len() < 100, "took {} steps", history.len());
+        assert!(history.iter().any(|s| s.dt > 1.0));
+        assert_relative_eq!(history.last().unwrap().time, 100.0, epsilon = 1e-9);
+        assert_relative_eq!(
+            history.iter().map(|s| s.dt).sum::<f64>(),
+            100.0,
+            epsilon = 1e-9
+        );
+        assert_relative_eq!(
+            results.series("p0").unwrap().values[2].re,
+            1.0f64.cos().powi(2),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_exact_integrator_matches_rk4_in_one_step_per_output() {
+        use crate::core::hamiltonian::TimeIndependentHamiltonian;
+        use crate::core::systems::DrivenTLS;
+        use crate::core::IntegratorType;
+        use ndarray::Array2;
+        use num_complex::Complex64;
+
+        let h = Array2::from_shape_fn((3, 3), |(i, j)| match (i as i32 - j as i32).abs() {
+            0 => Complex64::new(i as f64, 0.0),
+            1 => Complex64::new(0.3, 0.0),
+            _ => Complex64::new(0.0, 0.1 * (j as f64 - i as f64)),
+        });
+        let run = |integrator, timestep| {
+            SimulationBuilder::new()
+                .hamiltonian(TimeIndependentHamiltonian::new(h.clone()))
+                .initial_state(QuantumState::ground_state(3))
+                .duration(5.0)
+                .timestep(timestep)
+                .integrator(integrator)
+                .observable("p1", PopulationOperator::new(3, 1).unwrap())
+                .time_grid(TimeGrid::explicit(vec![0.0, 2.5, 5.0]))
+                .build()
+                .unwrap()
+                .run()
+                .unwrap()
+        };
+        // The exact propagator ignores dt, even one longer than the whole run.
+        let exact = run(IntegratorType::Exact, 10.0);
+        let rk4 = run(IntegratorType::RK4, 0.001);
+        let (a, b) = (exact.series("p1").unwrap(), rk4.series("p1").unwrap());
+        for (x, y) in a.values.iter().zip(b.values) {
+            assert_relative_eq!(x.re, y.re, epsilon = 1e-9);
+        }
+
+        let err = SimulationBuilder::new()
+            .hamiltonian(DrivenTLS::new(1.0, 1.0, 0.5))
+            .initial_state(QuantumState::ground_state(2))
+            .duration(1.0)
+            .timestep(0.01)
+            .integrator(IntegratorType::Exact)
+            .build()
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("time-independent"), "{}", err);
+    }
+
+    #[test]
+    fn test_non_uniform_output_times() {
+        let sim = SimulationBuilder::new()
+            .hamiltonian(DrivenTLS::new(5.0, 5.0, 0.5))
+            .initial_state(QuantumState::ground_state(2))
+            .duration(10.0)
+            .timestep(0.05)
+            .observable("p0", PopulationOperator::new(2, 0).unwrap())
+            .vector_observable("bloch", BlochVector::new(2, 0, 1).unwrap())
+            .time_grid(TimeGrid::explicit(vec![0.0, 0.013, 1.0, 9.999]))
+            .record_states(true)
+            .build()
+            .unwrap();
+
+        let results = sim.run().unwrap();
+        assert_eq!(results.times(), &[0.0, 0.013, 1.0, 9.999]);
+        assert_relative_eq!(results.series("p0").unwrap().values[0].re, 1.0);
+        let bloch = results.vector_series("bloch").unwrap();
+        assert_eq!((bloch.len(), bloch.width), (4, 3));
+        assert_relative_eq!(bloch.row(0)[2].re, 1.0);
+        assert_eq!(results.snapshots().len(), 4);
+        assert_eq!(results.snapshots()[2].time, 1.0);
+        assert!(results.resources().is_some_and(|r| r.wall_time > 0.0));
+    }
+
+    #[test]
+    fn test_warmup_is_not_recorded() {
+        let build = |warmup: f64| {
+            SimulationBuilder::new()
+                .hamiltonian(DrivenTLS::new(5.0, 5.0, 0.5))
+                .initial_state(QuantumState::ground_state(2))
+                .duration(1.0)
+                .timestep(0.01)
+                .warmup_duration(warmup)
+                .observable("p0", PopulationOperator::new(2, 0).unwrap())
+                .time_grid(TimeGrid::explicit(vec![0.0, 1.0]))
+                .build()
+                .unwrap()
+        };
+
+        let reference = build(0.0).run().unwrap();
+        let warmed = build(1.0).run().unwrap();
+        assert_eq!(warmed.times(), &[1.0, 2.0]);
+        assert_relative_eq!(
+            warmed.series("p0").unwrap().values[0].re,
+            reference.series("p0").unwrap().values[1].re,
+            epsilon = 1e-10
+        );
+    }
+
+    #[test]
+    fn test_cancelled_run_stops() {
+        let control = std::sync::Arc::new(crate::simulation::JobControl::new());
+        let sim = SimulationBuilder::new()
+            .hamiltonian(DrivenTLS::new(5.0, 5.0, 0.5))
+            .initial_state(QuantumState::ground_state(2))
+            .duration(1.0)
+            .timestep(0.01)
+            .build()
+            .unwrap()
+            .with_control(control.clone());
+
+        control.cancel();
+        assert!(matches!(
+            sim.run().unwrap_err(),
+            crate::utils::Error::Cancelled(_)
+        ));
+    }
+
+    #[test]
+    fn test_iter_matches_run() {
+        let sim = SimulationBuilder::new()
+            .hamiltonian(DrivenTLS::new(5.0, 5.0, 0.5))
+            .initial_state(QuantumState::ground_state(2))
+            .duration(2.0)
+            .timestep(0.01)
+            .warmup_duration(0.5)
+            .observable("p1", PopulationOperator::new(2, 1).unwrap())
+            .time_grid(TimeGrid::uniform(0.5))
+            .build()
+            .unwrap();
+
+        let results = sim.run().unwrap();
+        let snapshots: Vec<_> = sim.iter().collect::<crate::utils::Result<_>>().unwrap();
+        assert_eq!(snapshots.len(), results.times().len());
+        for ((t, snapshot), (&time, p1)) in snapshots.iter().zip(
+            results
+                .times()
+                .iter()
+                .zip(results.series("p1").unwrap().values),
+        ) {
+            assert_eq!(*t, time);
+            assert_eq!(snapshot.time, time);
+            assert_relative_eq!(snapshot.amplitudes[1].norm_sqr(), p1.re, epsilon = 1e-12);
+        }
+
+        let first_two: Vec<f64> = sim.iter().take(2).map(|s| s.unwrap().0).collect();
+        assert_eq!(first_two, vec![0.5, 1.0]);
+    }
+
+    #[tokio::test]
+    async fn test_run_async_matches_run_and_times_out() {
+        let build = |duration: f64| {
+            SimulationBuilder::new()
+                .hamiltonian(DrivenTLS::new(5.0, 5.0, 0.5))
+                .initial_state(QuantumState::ground_state(2))
+                .duration(duration)
+                .timestep(0.01)
+                .observable("p1", PopulationOperator::new(2, 1).unwrap())
+                .build()
+                .unwrap()
+        };
+
+        let expected = build(2.0).run().unwrap();
+        let results = build(2.0).run_async().await.unwrap();
+        assert_eq!(results.times(), expected.times());
+        assert_eq!(
+            results.series("p1").unwrap().values,
+            expected.series("p1").unwrap().values
+        );
+
+        let result = build(1e4).run_with_timeout(Duration::from_millis(20)).await;
+        assert!(matches!(result, Err(crate::utils::Error::Cancelled(_))));
+
+        // Cancelling the caller's control stops the blocking loop.
+        let control = std::sync::Arc::new(crate::simulation::JobControl::new());
+        let run = tokio::spawn(build(1e4).with_control(control.clone()).run_async());
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        control.cancel();
+        let result = tokio::time::timeout(Duration::from_secs(5), run)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(result, Err(crate::utils::Error::Cancelled(_))));
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint_matches_full_run() {
+        let build = |duration: f64| {
+            SimulationBuilder::new()
+                .hamiltonian(DrivenTLS::new(5.0, 5.0, 0.5))
+                .initial_state(QuantumState::ground_state(2))
+                .duration(duration)
+                .timestep(0.01)
+                .observable("p1", PopulationOperator::new(2, 1).unwrap())
+                .time_grid(TimeGrid::uniform(0.25))
+                .build()
+                .unwrap()
+        };
+        let path =
+            std::env::temp_dir().join(format!("chronophoton_resume_{}.ckpt", std::process::id()));
+
+        let expected = build(3.0).run().unwrap();
+        build(1.5)
+            .with_checkpoints(path.clone(), 0.5)
+            .unwrap()
+            .run()
+            .unwrap();
+        let checkpoint = crate::data::Checkpoint::load(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(checkpoint.complete);
+        assert_relative_eq!(checkpoint.time, 1.5, epsilon = 1e-12);
+
+        let resumed = build(3.0).with_resume(checkpoint).unwrap().run().unwrap();
+        assert_eq!(resumed.times().len(), expected.times().len());
+        for (a, b) in resumed
+            .series("p1")
+            .unwrap()
+            .values
+            .iter()
+            .zip(expected.series("p1").unwrap().values)
+        {
+            assert_relative_eq!(a.re, b.re, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_floquet_decomposition_is_computed_once() {
+        let period = 2.0 * std::f64::consts::PI / 5.0;
+        let sim = SimulationBuilder::new()
+            .hamiltonian(DrivenTLS::new(5.0, 5.0, 0.3))
+            .initial_state(QuantumState::ground_state(2))
+            .duration(20.0 * period)
+            .timestep(period / 40.0)
+            .time_grid(TimeGrid::Uniform { interval: period })
+            .floquet_populations("floquet", 200)
+            .build()
+            .unwrap();
+
+        let results = sim.run().unwrap();
+        let spectrum = sim.floquet_spectrum().unwrap().unwrap();
+        assert_eq!(spectrum.num_levels(), 2);
+        assert_eq!(sim.floquet_modes().unwrap().computations(), 1);
+
+        let populations = results.vector_series("floquet").unwrap();
+        assert_eq!(populations.len(), 21);
+        for (_, row) in populations.rows() {
+            assert_relative_eq!(row[0].re + row[1].re, 1.0, epsilon = 1e-6);
+            assert_relative_eq!(row[0].re, populations.row(0)[0].re, epsilon = 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_prethermal_populations_at_high_frequency() {
+        let period = 2.0 * std::f64::consts::PI / 30.0;
+        let sim = SimulationBuilder::new()
+            .hamiltonian(DrivenTLS::new(1.0, 30.0, 3.0))
+            .initial_state(QuantumState::ground_state(2))
+            .duration(50.0 * period)
+            .timestep(period / 40.0)
+            .time_grid(TimeGrid::Uniform { interval: period })
+            .prethermal_populations("prethermal", 32)
+            .build()
+            .unwrap();
+
+        let results = sim.run().unwrap();
+        let spectrum = sim.effective_spectrum().unwrap().unwrap();
+        assert_relative_eq!(spectrum.energies[0], -0.5, epsilon = 1e-10);
+        assert_eq!(sim.prethermal_modes().unwrap().computations(), 1);
+
+        let populations = results.vector_series("prethermal").unwrap();
+        assert_eq!(populations.len(), 51);
+        for (_, row) in populations.rows() {
+            assert_relative_eq!(row[0].re + row[1].re, 1.0, epsilon = 1e-6);
+            assert!(row[1].re > 0.95, "{}", row[1].re);
+        }
+    }
+
+    #[test]
+    fn test_stop_condition_ends_run_early() {
+        let results = SimulationBuilder::new()
+            .hamiltonian(DrivenTLS::new(5.0, 5.0, 0.5))
+            .initial_state(QuantumState::ground_state(2))
+            .duration(50.0)
+            .timestep(0.01)
+            .observable("p1", PopulationOperator::new(2, 1).unwrap())
+            .time_grid(TimeGrid::uniform(0.1))
+            .stop_when(crate::simulation::StopCondition::parse("p1 >= 0.5").unwrap())
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+
+        let termination = results.termination().unwrap();
+        assert_eq!(termination.reason, "p1 >= 0.5");
+        assert!(termination.time < 50.0);
+        assert_eq!(*results.times().last().unwrap(), termination.time);
+        let p1 = results.series("p1").unwrap().to_vec_real();
+        assert!(p1[p1.len() - 1] >= 0.5 && p1[p1.len() - 2] < 0.5);
+    }
+
+    struct BlowsUp;
+
+    impl crate::core::Hamiltonian for BlowsUp {
+        fn dim(&self) -> usize {
+            2
+        }
+
+        fn compute(&self, t: f64, out: &mut ndarray::Array2<num_complex::Complex64>) {
+            let value = if t > 0.5 { f64::NAN } else { 1.0 };
+            out.fill(num_complex::Complex64::new(0.0, 0.0));
+            out[[0, 1]] = num_complex::Complex64::new(value, 0.0);
+            out[[1, 0]] = num_complex::Complex64::new(value, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_blow_up_is_reported() {
+        let sim = SimulationBuilder::new()
+            .hamiltonian(BlowsUp)
+            .initial_state(QuantumState::ground_state(2))
+            .duration(1.0)
+            .timestep(0.1)
+            .max_retries(1)
+            .build()
+            .unwrap();
+
+        let err = sim.run().unwrap_err();
+        assert!(matches!(err, crate::utils::Error::Numerical(_)));
+        assert!(err.to_string().contains("NaN"));
+        assert!(err.to_string().contains("dt = 5.000e-2"));
+    }
 }
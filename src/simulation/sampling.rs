@@ -0,0 +1,188 @@
+use crate::core::{Observable, QuantumState};
+use crate::simulation::SimulationResults;
+use crate::utils::math::{hermitian_eigen, is_hermitian};
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Binomial, Distribution};
+use serde::{Deserialize, Serialize};
+
+const HERMITIAN_TOLERANCE: f64 = 1e-10;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SamplingOptions {
+    #[serde(default)]
+    pub shots: usize,
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+struct Spectrum {
+    name: String,
+    eigenvalues: Vec<f64>,
+    eigenvectors: Array2<Complex64>,
+}
+
+pub struct ShotSampler {
+    shots: usize,
+    seed: u64,
+    spectra: Vec<Spectrum>,
+}
+
+impl ShotSampler {
+    pub fn new(options: &SamplingOptions, observables: &[(String, Box<dyn Observable>)]) -> Self {
+        let spectra = observables
+            .iter()
+            .filter_map(|(name, observable)| {
+                let matrix = observable.matrix();
+                if !is_hermitian(&matrix.view(), HERMITIAN_TOLERANCE) {
+                    tracing::warn!("Observable '{}' is not Hermitian and is not sampled", name);
+                    return None;
+                }
+                let (eigenvalues, eigenvectors) = hermitian_eigen(&matrix.view());
+                Some(Spectrum {
+                    name: name.clone(),
+                    eigenvalues,
+                    eigenvectors,
+                })
+            })
+            .collect();
+
+        Self {
+            shots: options.shots,
+            seed: crate::utils::performance::resolve_seed(options.seed)
+                .unwrap_or_else(rand::random),
+            spectra,
+        }
+    }
+
+    pub fn record(
+        &self,
+        results: &mut SimulationResults,
+        t: f64,
+        state: &QuantumState,
+    ) -> Result<()> {
+        let mut rng = StdRng::seed_from_u64(self.seed ^ t.to_bits());
+        let psi = state.data();
+        for spectrum in &self.spectra {
+            let probabilities: Vec<f64> = spectrum
+                .eigenvectors
+                .columns()
+                .into_iter()
+                .map(|v| {
+                    v.iter()
+                        .zip(psi)
+                        .map(|(a, b)| a.conj() * b)
+                        .sum::<Complex64>()
+                        .norm_sqr()
+                })
+                .collect();
+            let counts = sample_multinomial(&mut rng, self.shots, &probabilities)?;
+
+            let n = self.shots as f64;
+            let mean = counts
+                .iter()
+                .zip(&spectrum.eigenvalues)
+                .map(|(&c, &lambda)| c as f64 * lambda)
+                .sum::<f64>()
+                / n;
+            let variance = counts
+                .iter()
+                .zip(&spectrum.eigenvalues)
+                .map(|(&c, &lambda)| c as f64 * (lambda - mean).powi(2))
+                .sum::<f64>()
+                / (n - 1.0).max(1.0);
+
+            results.add_observable(
+                &format!("{}_sampled", spectrum.name),
+                t,
+                Complex64::new(mean, 0.0),
+            );
+            results.add_observable(
+                &format!("{}_sampled_var", spectrum.name),
+                t,
+                Complex64::new(variance, 0.0),
+            );
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn sample_multinomial(
+    rng: &mut StdRng,
+    shots: usize,
+    probabilities: &[f64],
+) -> Result<Vec<u64>> {
+    let total: f64 = probabilities.iter().map(|p| p.max(0.0)).sum();
+    if total <= 0.0 {
+        return Err(Error::Numerical(
+            "Cannot sample from vanishing outcome probabilities".to_string(),
+        ));
+    }
+
+    let mut remaining = shots as u64;
+    let mut mass = 1.0;
+    let mut counts = Vec::with_capacity(probabilities.len());
+    for p in probabilities {
+        let p = p.max(0.0) / total;
+        let clicks = if remaining == 0 || mass <= 0.0 {
+            0
+        } else {
+            Binomial::new(remaining, (p / mass).clamp(0.0, 1.0))
+                .map_err(|e| Error::InvalidParameter(e.to_string()))?
+                .sample(rng)
+        };
+        mass -= p;
+        remaining -= clicks;
+        counts.push(clicks);
+    }
+    Ok(counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observables::{MatrixObservable, PopulationOperator};
+    use approx::assert_relative_eq;
+    use ndarray::array;
+
+    #[test]
+    fn test_sampled_statistics_match_distribution() {
+        let sigma_x = MatrixObservable::new(array![
+            [Complex64::new(0.0, 0.0), Complex64::new(1.0, 0.0)],
+            [Complex64::new(1.0, 0.0), Complex64::new(0.0, 0.0)],
+        ]);
+        let observables: Vec<(String, Box<dyn Observable>)> = vec![
+            ("x".to_string(), Box::new(sigma_x)),
+            (
+                "p1".to_string(),
+                Box::new(PopulationOperator::new(2, 1).unwrap()),
+            ),
+        ];
+        let options = SamplingOptions {
+            shots: 20000,
+            seed: Some(3),
+        };
+        let sampler = ShotSampler::new(&options, &observables);
+
+        let theta = 1.0_f64;
+        let state = QuantumState::bloch(2, theta, 0.0).unwrap();
+        let mut results = SimulationResults::new();
+        sampler.record(&mut results, 0.5, &state).unwrap();
+        sampler.record(&mut results, 1.0, &state).unwrap();
+
+        let mean = results.series("x_sampled").unwrap().values[0].re;
+        let variance = results.series("x_sampled_var").unwrap().values[0].re;
+        assert_relative_eq!(mean, theta.sin(), epsilon = 0.03);
+        assert_relative_eq!(variance, 1.0 - theta.sin().powi(2), epsilon = 0.03);
+        let p1 = results.series("p1_sampled").unwrap().to_vec_real();
+        assert_relative_eq!(p1[0], (theta / 2.0).sin().powi(2), epsilon = 0.03);
+        assert_ne!(p1[0], p1[1]);
+
+        let mut again = SimulationResults::new();
+        sampler.record(&mut again, 0.5, &state).unwrap();
+        assert_eq!(again.series("x_sampled").unwrap().values[0].re, mean);
+    }
+}
@@ -1,3 +1,8 @@
+use crate::utils::{Error, Result};
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
 pub struct Scheduler {
     max_concurrent: usize,
 }
@@ -10,4 +15,230 @@ impl Scheduler {
     pub fn max_concurrent(&self) -> usize {
         self.max_concurrent
     }
+
+    pub fn run<J, T, F>(&self, jobs: Vec<J>, job: F) -> Result<Vec<T>>
+    where
+        J: Send,
+        T: Send,
+        F: Fn(J) -> T + Send + Sync,
+    {
+        Ok(self
+            .pool()?
+            .install(|| jobs.into_par_iter().map(job).collect()))
+    }
+
+    pub fn queue(&self) -> Result<JobQueue> {
+        Ok(JobQueue {
+            pool: self.pool()?,
+            next_id: AtomicUsize::new(0),
+        })
+    }
+
+    fn pool(&self) -> Result<rayon::ThreadPool> {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(self.max_concurrent.max(1))
+            .build()
+            .map_err(|e| Error::Other(e.into()))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct JobControl {
+    progress: AtomicU64,
+    cancelled: AtomicBool,
+}
+
+impl JobControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_progress(&self, fraction: f64) {
+        self.progress
+            .store(fraction.clamp(0.0, 1.0).to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn progress(&self) -> f64 {
+        f64::from_bits(self.progress.load(Ordering::Relaxed))
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn check(&self) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Error::Cancelled("job was cancelled".to_string()));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_done(&self) -> bool {
+        matches!(
+            self,
+            JobStatus::Finished | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+enum JobState<T> {
+    Queued,
+    Running,
+    Finished(T),
+    Failed(String),
+    Cancelled,
+}
+
+pub struct Job<T> {
+    id: usize,
+    name: String,
+    control: Arc<JobControl>,
+    state: Mutex<JobState<T>>,
+}
+
+impl<T> Job<T> {
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn progress(&self) -> f64 {
+        self.control.progress()
+    }
+
+    pub fn cancel(&self) {
+        self.control.cancel();
+    }
+
+    pub fn status(&self) -> JobStatus {
+        match &*self.lock() {
+            JobState::Queued => JobStatus::Queued,
+            JobState::Running => JobStatus::Running,
+            JobState::Finished(_) => JobStatus::Finished,
+            JobState::Failed(_) => JobStatus::Failed,
+            JobState::Cancelled => JobStatus::Cancelled,
+        }
+    }
+
+    pub fn error(&self) -> Option<String> {
+        match &*self.lock() {
+            JobState::Failed(e) => Some(e.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn with_output<R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        match &*self.lock() {
+            JobState::Finished(output) => Some(f(output)),
+            _ => None,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, JobState<T>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn set(&self, state: JobState<T>) {
+        *self.lock() = state;
+    }
+}
+
+pub struct JobQueue {
+    pool: rayon::ThreadPool,
+    next_id: AtomicUsize,
+}
+
+impl JobQueue {
+    pub fn submit<T, F>(&self, name: impl Into<String>, work: F) -> Arc<Job<T>>
+    where
+        T: Send + 'static,
+        F: FnOnce(Arc<JobControl>) -> Result<T> + Send + 'static,
+    {
+        let job = Arc::new(Job {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            name: name.into(),
+            control: Arc::new(JobControl::new()),
+            state: Mutex::new(JobState::Queued),
+        });
+
+        let handle = job.clone();
+        self.pool.spawn(move || {
+            if handle.control.is_cancelled() {
+                handle.set(JobState::Cancelled);
+                return;
+            }
+            handle.set(JobState::Running);
+            let state = match work(handle.control.clone()) {
+                Ok(output) => {
+                    handle.control.set_progress(1.0);
+                    JobState::Finished(output)
+                }
+                Err(_) if handle.control.is_cancelled() => JobState::Cancelled,
+                Err(e) => JobState::Failed(e.to_string()),
+            };
+            handle.set(state);
+        });
+        job
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_run_preserves_job_order() {
+        let scheduler = Scheduler::new(2);
+        let results = scheduler.run((0..10).collect(), |i: i32| i * i).unwrap();
+        assert_eq!(results, (0..10).map(|i| i * i).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_queue_runs_and_cancels_jobs() {
+        let queue = Scheduler::new(1).queue().unwrap();
+        let blocking = queue.submit("blocking", |control| {
+            control.set_progress(0.5);
+            while !control.is_cancelled() {
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            control.check().map(|_| 0)
+        });
+        let queued = queue.submit("queued", |_| Ok(1));
+        queued.cancel();
+        blocking.cancel();
+        let finished = queue.submit("finished", |_| Ok(42));
+
+        let start = Instant::now();
+        let jobs = [&blocking, &queued, &finished];
+        while !jobs.iter().all(|job| job.status().is_done())
+            && start.elapsed() < Duration::from_secs(10)
+        {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(blocking.status(), JobStatus::Cancelled);
+        assert_eq!(queued.status(), JobStatus::Cancelled);
+        assert_eq!(finished.status(), JobStatus::Finished);
+        assert_eq!(finished.with_output(|v| *v), Some(42));
+        assert_eq!(finished.progress(), 1.0);
+        assert_ne!(blocking.id(), finished.id());
+    }
 }
@@ -0,0 +1,254 @@
+use crate::core::{integrator, Hamiltonian, Integrator, IntegratorType, Observable, QuantumState};
+use crate::simulation::{SimulationResults, TimeGrid};
+use crate::utils::{Error, Result};
+use ndarray::{Array1, Array2};
+use num_complex::Complex64;
+use rayon::prelude::*;
+
+struct PerturbedHamiltonian<'a> {
+    base: &'a dyn Hamiltonian,
+    perturbation: &'a dyn Hamiltonian,
+    strength: f64,
+}
+
+impl Hamiltonian for PerturbedHamiltonian<'_> {
+    fn dim(&self) -> usize {
+        self.base.dim()
+    }
+
+    fn compute(&self, t: f64, out: &mut Array2<Complex64>) {
+        let mut v = Array2::zeros(out.raw_dim());
+        self.base.compute(t, out);
+        self.perturbation.compute(t, &mut v);
+        out.scaled_add(Complex64::new(self.strength, 0.0), &v);
+    }
+}
+
+pub struct ScramblingAnalysis {
+    hamiltonian: Box<dyn Hamiltonian>,
+    duration: f64,
+    timestep: f64,
+    integrator: Box<dyn Integrator>,
+    time_grid: TimeGrid,
+}
+
+impl ScramblingAnalysis {
+    pub fn new(
+        hamiltonian: Box<dyn Hamiltonian>,
+        duration: f64,
+        timestep: f64,
+        integrator_type: IntegratorType,
+    ) -> Result<Self> {
+        if duration <= 0.0 || timestep <= 0.0 {
+            return Err(Error::InvalidParameter(format!(
+                "Duration and timestep must be positive, got {} and {}",
+                duration, timestep
+            )));
+        }
+
+        Ok(Self {
+            hamiltonian,
+            duration,
+            timestep,
+            integrator: integrator::create_integrator(integrator_type),
+            time_grid: TimeGrid::uniform(timestep),
+        })
+    }
+
+    pub fn with_time_grid(mut self, time_grid: TimeGrid) -> Result<Self> {
+        time_grid.validate(self.duration)?;
+        self.time_grid = time_grid;
+        Ok(self)
+    }
+
+    pub fn loschmidt_echo(
+        &self,
+        perturbation: &dyn Hamiltonian,
+        strength: f64,
+        ensemble: &[QuantumState],
+    ) -> Result<SimulationResults> {
+        self.check_ensemble(ensemble, perturbation.dim())?;
+
+        let perturbed = PerturbedHamiltonian {
+            base: self.hamiltonian.as_ref(),
+            perturbation,
+            strength,
+        };
+        let times = self.output_times();
+
+        let echoes = ensemble
+            .par_iter()
+            .map(|psi0| {
+                let mut forward = psi0.clone();
+                let mut t = 0.0;
+                let mut echo = Vec::with_capacity(times.len());
+                for &t_out in &times {
+                    self.evolve(self.hamiltonian.as_ref(), &mut forward, t, t_out)?;
+                    t = t_out;
+
+                    let mut returned = forward.clone();
+                    self.evolve(&perturbed, &mut returned, t_out, 0.0)?;
+                    echo.push(overlap(psi0.data(), returned.data()).norm_sqr());
+                }
+                Ok(echo)
+            })
+            .collect::<Result<Vec<Vec<f64>>>>()?;
+
+        let mut results = SimulationResults::new();
+        for (k, &t) in times.iter().enumerate() {
+            let mean = echoes.iter().map(|e| e[k]).sum::<f64>() / echoes.len() as f64;
+            results.add_observable("loschmidt_echo", t, Complex64::new(mean, 0.0));
+        }
+        Ok(results)
+    }
+
+    pub fn otoc(
+        &self,
+        w: &dyn Observable,
+        v: &dyn Observable,
+        ensemble: &[QuantumState],
+    ) -> Result<SimulationResults> {
+        self.check_ensemble(ensemble, w.dim())?;
+        self.check_ensemble(ensemble, v.dim())?;
+
+        let times = self.output_times();
+        let h = self.hamiltonian.as_ref();
+
+        let correlators = ensemble
+            .par_iter()
+            .map(|psi0| {
+                let (mut v_psi, v_norm) = apply_operator(v.matrix(), psi0)?;
+                let mut psi = psi0.clone();
+                let mut t = 0.0;
+                let mut values = Vec::with_capacity(times.len());
+                for &t_out in &times {
+                    self.evolve(h, &mut v_psi, t, t_out)?;
+                    self.evolve(h, &mut psi, t, t_out)?;
+                    t = t_out;
+
+                    let (mut a, a_norm) = apply_operator(w.matrix(), &v_psi)?;
+                    self.evolve(h, &mut a, t_out, 0.0)?;
+
+                    let (mut b, b_norm) = apply_operator(w.matrix(), &psi)?;
+                    self.evolve(h, &mut b, t_out, 0.0)?;
+                    let (b, b_v_norm) = apply_operator(v.matrix(), &b)?;
+
+                    let scale = v_norm * a_norm * b_norm * b_v_norm;
+                    values.push(overlap(b.data(), a.data()) * scale);
+                }
+                Ok(values)
+            })
+            .collect::<Result<Vec<Vec<Complex64>>>>()?;
+
+        let mut results = SimulationResults::new();
+        for (k, &t) in times.iter().enumerate() {
+            let mean =
+                correlators.iter().map(|c| c[k]).sum::<Complex64>() / correlators.len() as f64;
+            results.add_observable("otoc", t, mean);
+        }
+        Ok(results)
+    }
+
+    fn output_times(&self) -> Vec<f64> {
+        self.time_grid.output_times(self.duration, self.timestep)
+    }
+
+    fn check_ensemble(&self, ensemble: &[QuantumState], operator_dim: usize) -> Result<()> {
+        if ensemble.is_empty() {
+            return Err(Error::InvalidParameter(
+                "Ensemble of initial states must not be empty".to_string(),
+            ));
+        }
+
+        let dim = self.hamiltonian.dim();
+        if operator_dim != dim {
+            return Err(Error::dimension_mismatch(dim, operator_dim));
+        }
+        if let Some(state) = ensemble.iter().find(|s| s.dim() != dim) {
+            return Err(Error::dimension_mismatch(dim, state.dim()));
+        }
+        Ok(())
+    }
+
+    fn evolve(
+        &self,
+        hamiltonian: &dyn Hamiltonian,
+        state: &mut QuantumState,
+        from: f64,
+        to: f64,
+    ) -> Result<()> {
+        self.integrator.clear_cache();
+        let tolerance = 1e-9 * self.timestep;
+        let direction = if to >= from { 1.0 } else { -1.0 };
+        let mut t = from;
+        while (to - t) * direction > tolerance {
+            let dt = self.timestep.min((to - t) * direction) * direction;
+            self.integrator.step(hamiltonian, state, t, dt)?;
+            t += dt;
+        }
+        Ok(())
+    }
+}
+
+fn overlap(bra: &Array1<Complex64>, ket: &Array1<Complex64>) -> Complex64 {
+    bra.iter().zip(ket.iter()).map(|(b, k)| b.conj() * k).sum()
+}
+
+fn apply_operator(op: &Array2<Complex64>, state: &QuantumState) -> Result<(QuantumState, f64)> {
+    let data = op.dot(state.data());
+    let norm = data.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+    if norm == 0.0 {
+        return Ok((state.clone(), 0.0));
+    }
+    Ok((QuantumState::new(data.mapv(|x| x / norm))?, norm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::hamiltonian::TimeIndependentHamiltonian;
+    use crate::core::observables::MatrixObservable;
+    use approx::assert_relative_eq;
+
+    fn pauli(x: [f64; 4]) -> Array2<Complex64> {
+        Array2::from_shape_vec((2, 2), x.iter().map(|&v| Complex64::new(v, 0.0)).collect()).unwrap()
+    }
+
+    #[test]
+    fn test_loschmidt_echo_decays_with_perturbation() {
+        let h = TimeIndependentHamiltonian::new(pauli([1.0, 0.0, 0.0, -1.0]));
+        let v = TimeIndependentHamiltonian::new(pauli([0.0, 1.0, 1.0, 0.0]));
+        let analysis =
+            ScramblingAnalysis::new(Box::new(h), 2.0, 0.01, IntegratorType::RK4).unwrap();
+        let ensemble = vec![QuantumState::ground_state(2)];
+
+        let unperturbed = analysis.loschmidt_echo(&v, 0.0, &ensemble).unwrap();
+        for &value in unperturbed.series("loschmidt_echo").unwrap().values {
+            assert_relative_eq!(value.re, 1.0, epsilon = 1e-8);
+        }
+
+        let perturbed = analysis.loschmidt_echo(&v, 0.5, &ensemble).unwrap();
+        let echo = perturbed.series("loschmidt_echo").unwrap();
+        assert_relative_eq!(echo.values[0].re, 1.0, epsilon = 1e-12);
+        assert!(echo.values.last().unwrap().re < 0.99);
+    }
+
+    #[test]
+    fn test_otoc_of_sigma_z_under_sigma_x() {
+        let h = TimeIndependentHamiltonian::new(pauli([0.0, 1.0, 1.0, 0.0]));
+        let sz = MatrixObservable::new(pauli([1.0, 0.0, 0.0, -1.0]));
+        let analysis = ScramblingAnalysis::new(Box::new(h), 1.0, 0.001, IntegratorType::RK4)
+            .unwrap()
+            .with_time_grid(TimeGrid::explicit(vec![0.0, 0.5, 1.0]))
+            .unwrap();
+
+        let results = analysis
+            .otoc(&sz, &sz, &[QuantumState::ground_state(2)])
+            .unwrap();
+        let otoc = results.series("otoc").unwrap();
+
+        for (t, value) in otoc.iter() {
+            assert_relative_eq!(value.re, (4.0 * t).cos(), epsilon = 1e-6);
+        }
+    }
+}
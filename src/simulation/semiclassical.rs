@@ -0,0 +1,266 @@
+use crate::core::lindblad::{LindbladOperator, LindbladSolver};
+use crate::core::{DensityMatrix, Hamiltonian, Observable};
+use crate::simulation::cumulant::{CAVITY_FIELD, PHOTON_NUMBER};
+use crate::simulation::{SimulationResults, TimeGrid};
+use crate::utils::resources::ResourceMeter;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+
+#[derive(Debug, Clone)]
+pub struct ClassicalMode {
+    pub omega_c: f64,
+    pub kappa: f64,
+    pub drive: f64,
+    pub omega_d: f64,
+}
+
+impl ClassicalMode {
+    pub fn new(omega_c: f64, kappa: f64) -> Self {
+        Self {
+            omega_c,
+            kappa,
+            drive: 0.0,
+            omega_d: 0.0,
+        }
+    }
+
+    pub fn with_drive(mut self, strength: f64, frequency: f64) -> Self {
+        self.drive = strength;
+        self.omega_d = frequency;
+        self
+    }
+
+    fn derivative(&self, alpha: Complex64, source: Complex64, t: f64) -> Complex64 {
+        let i = Complex64::i();
+        -(i * self.omega_c + self.kappa / 2.0) * alpha
+            - i * source
+            - i * self.drive * Complex64::from_polar(1.0, -self.omega_d * t)
+    }
+}
+
+pub struct SemiclassicalSolver {
+    lindblad: LindbladSolver,
+    coupling: Array2<Complex64>,
+    g: f64,
+    mode: ClassicalMode,
+    observables: Vec<(String, Box<dyn Observable>)>,
+}
+
+impl SemiclassicalSolver {
+    pub fn new(
+        hamiltonian: Box<dyn Hamiltonian>,
+        collapse_ops: Vec<LindbladOperator>,
+        coupling: Array2<Complex64>,
+        g: f64,
+        mode: ClassicalMode,
+    ) -> Result<Self> {
+        let dim = hamiltonian.dim();
+        if coupling.dim() != (dim, dim) {
+            return Err(Error::dimension_mismatch(dim, coupling.nrows()));
+        }
+        if mode.kappa < 0.0 || !mode.kappa.is_finite() {
+            return Err(Error::InvalidParameter(format!(
+                "Cavity decay rate must be non-negative, got {}",
+                mode.kappa
+            )));
+        }
+        Ok(Self {
+            lindblad: LindbladSolver::new(hamiltonian, collapse_ops)?,
+            coupling,
+            g,
+            mode,
+            observables: Vec::new(),
+        })
+    }
+
+    pub fn with_observable(
+        mut self,
+        name: impl Into<String>,
+        observable: impl Observable + 'static,
+    ) -> Self {
+        self.observables.push((name.into(), Box::new(observable)));
+        self
+    }
+
+    pub fn run(
+        &self,
+        rho0: &DensityMatrix,
+        alpha0: Complex64,
+        duration: f64,
+        timestep: f64,
+        time_grid: &TimeGrid,
+    ) -> Result<SimulationResults> {
+        let dim = self.lindblad.dim();
+        if rho0.dim() != dim {
+            return Err(Error::dimension_mismatch(dim, rho0.dim()));
+        }
+        if let Some((name, _)) = self.observables.iter().find(|(_, o)| o.dim() != dim) {
+            return Err(Error::InvalidParameter(format!(
+                "Observable '{}' does not match the Hilbert dimension {}",
+                name, dim
+            )));
+        }
+        if duration <= 0.0 || timestep <= 0.0 {
+            return Err(Error::InvalidParameter(format!(
+                "Duration and timestep must be positive, got {} and {}",
+                duration, timestep
+            )));
+        }
+        time_grid.validate(duration)?;
+
+        let meter = ResourceMeter::start();
+        let mut results = SimulationResults::new();
+        let mut rho = rho0.clone();
+        let mut alpha = alpha0;
+        let mut t = 0.0;
+        let tolerance = 1e-9 * timestep;
+        for t_out in time_grid.output_times(duration, timestep) {
+            while t_out - t > tolerance {
+                let dt = timestep.min(t_out - t);
+                self.step(&mut rho, &mut alpha, t, dt)?;
+                t += dt;
+            }
+            if !alpha.is_finite() || rho.data().iter().any(|x| !x.is_finite()) {
+                return Err(Error::Numerical(format!(
+                    "Semiclassical evolution diverged at t = {}, try a smaller timestep",
+                    t
+                )));
+            }
+
+            results.add_observable(CAVITY_FIELD, t_out, alpha);
+            results.add_observable(PHOTON_NUMBER, t_out, Complex64::new(alpha.norm_sqr(), 0.0));
+            for (name, observable) in &self.observables {
+                results.add_observable(name, t_out, observable.expectation_mixed(&rho));
+            }
+        }
+
+        results.set_resources(meter.finish());
+        Ok(results)
+    }
+
+    fn derivative(
+        &self,
+        rho: &Array2<Complex64>,
+        alpha: Complex64,
+        t: f64,
+    ) -> Result<(Array2<Complex64>, Complex64)> {
+        let state = DensityMatrix::new_unchecked(rho.clone());
+        let i = Complex64::i();
+        let interaction = self.coupling.mapv(|c| c * alpha.conj() * self.g)
+            + self.coupling.t().mapv(|c| c.conj() * alpha * self.g);
+        let commutator = interaction.dot(rho) - rho.dot(&interaction);
+        let drho = self.lindblad.compute_derivative(&state, t)? - commutator.mapv(|x| x * i);
+
+        let source: Complex64 = self
+            .coupling
+            .t()
+            .iter()
+            .zip(rho.iter())
+            .map(|(c, r)| c * r)
+            .sum();
+        let dalpha = self.mode.derivative(alpha, self.g * source, t);
+        Ok((drho, dalpha))
+    }
+
+    fn step(&self, rho: &mut DensityMatrix, alpha: &mut Complex64, t: f64, dt: f64) -> Result<()> {
+        let (r0, a0) = (rho.data().clone(), *alpha);
+        let (k1, l1) = self.derivative(&r0, a0, t)?;
+        let (k2, l2) = self.derivative(
+            &(&r0 + &k1.mapv(|x| x * (dt / 2.0))),
+            a0 + l1 * (dt / 2.0),
+            t + dt / 2.0,
+        )?;
+        let (k3, l3) = self.derivative(
+            &(&r0 + &k2.mapv(|x| x * (dt / 2.0))),
+            a0 + l2 * (dt / 2.0),
+            t + dt / 2.0,
+        )?;
+        let (k4, l4) = self.derivative(&(&r0 + &k3.mapv(|x| x * dt)), a0 + l3 * dt, t + dt)?;
+
+        let increment = k1 + k2.mapv(|x| x * 2.0) + k3.mapv(|x| x * 2.0) + k4;
+        *rho = DensityMatrix::new_unchecked(r0 + increment.mapv(|x| x * (dt / 6.0)));
+        *alpha = a0 + (l1 + 2.0 * l2 + 2.0 * l3 + l4) * (dt / 6.0);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::hamiltonian::TimeIndependentHamiltonian;
+    use crate::core::observables::MatrixObservable;
+    use crate::core::QuantumState;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_coupled_oscillators_exchange_energy() {
+        let (dim, omega, g) = (12, 1.0, 0.5);
+        let mut b = Array2::zeros((dim, dim));
+        for n in 1..dim {
+            b[[n - 1, n]] = Complex64::new((n as f64).sqrt(), 0.0);
+        }
+        let number = b.t().mapv(|x: Complex64| x.conj()).dot(&b);
+        let solver = SemiclassicalSolver::new(
+            Box::new(TimeIndependentHamiltonian::new(number.mapv(|x| x * omega))),
+            Vec::new(),
+            b,
+            g,
+            ClassicalMode::new(omega, 0.0),
+        )
+        .unwrap()
+        .with_observable("nb", MatrixObservable::new(number));
+
+        let duration = std::f64::consts::PI / (2.0 * g);
+        let rho0 = QuantumState::ground_state(dim).to_density_matrix();
+        let results = solver
+            .run(
+                &rho0,
+                Complex64::new(1.0, 0.0),
+                duration,
+                0.005,
+                &TimeGrid::uniform(duration / 2.0),
+            )
+            .unwrap();
+
+        let photons = &results.series(PHOTON_NUMBER).unwrap().values;
+        let nb = &results.series("nb").unwrap().values;
+        assert_relative_eq!(photons[1].re, 0.5, epsilon = 1e-6);
+        assert_relative_eq!(nb[1].re, 0.5, epsilon = 1e-6);
+        assert_relative_eq!(photons[2].re, 0.0, epsilon = 1e-6);
+        assert_relative_eq!(nb[2].re, 1.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_driven_empty_cavity_reaches_steady_field() {
+        let (kappa, eta, detuning) = (1.0, 0.3, 0.5);
+        let solver = SemiclassicalSolver::new(
+            Box::new(TimeIndependentHamiltonian::new(Array2::zeros((2, 2)))),
+            Vec::new(),
+            Array2::zeros((2, 2)),
+            0.0,
+            ClassicalMode::new(2.0 + detuning, kappa).with_drive(eta, 2.0),
+        )
+        .unwrap();
+        let rho0 = QuantumState::ground_state(2).to_density_matrix();
+        let results = solver
+            .run(
+                &rho0,
+                Complex64::new(0.0, 0.0),
+                30.0,
+                0.01,
+                &TimeGrid::uniform(30.0),
+            )
+            .unwrap();
+
+        let expected = eta * eta / (detuning * detuning + kappa * kappa / 4.0);
+        let photons = results
+            .series(PHOTON_NUMBER)
+            .unwrap()
+            .values
+            .last()
+            .unwrap()
+            .re;
+        assert_relative_eq!(photons, expected, epsilon = 1e-6);
+    }
+}
@@ -0,0 +1,219 @@
+use crate::simulation::TimeSeries;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+use rustfft::FftPlanner;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SpectrogramOptions {
+    #[serde(default)]
+    pub observables: Vec<String>,
+    #[serde(default = "default_window_length")]
+    pub window_length: usize,
+    #[serde(default = "default_overlap")]
+    pub overlap: f64,
+    #[serde(default = "default_window")]
+    pub window: String,
+}
+
+fn default_window_length() -> usize {
+    128
+}
+
+fn default_overlap() -> f64 {
+    0.5
+}
+
+fn default_window() -> String {
+    "hann".to_string()
+}
+
+impl Default for SpectrogramOptions {
+    fn default() -> Self {
+        Self {
+            observables: Vec::new(),
+            window_length: default_window_length(),
+            overlap: default_overlap(),
+            window: default_window(),
+        }
+    }
+}
+
+impl SpectrogramOptions {
+    pub fn validate(&self) -> Result<()> {
+        if self.window_length < 2 {
+            return Err(Error::InvalidParameter(
+                "Spectrogram window length must be at least 2 samples".to_string(),
+            ));
+        }
+        if !(0.0..1.0).contains(&self.overlap) {
+            return Err(Error::InvalidParameter(format!(
+                "Spectrogram overlap must be in [0, 1), got {}",
+                self.overlap
+            )));
+        }
+        WindowFunction::parse(&self.window)?;
+        Ok(())
+    }
+
+    fn hop(&self) -> usize {
+        ((self.window_length as f64 * (1.0 - self.overlap)).round() as usize).max(1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowFunction {
+    Rectangular,
+    Hann,
+    Hamming,
+}
+
+impl WindowFunction {
+    pub fn parse(window: &str) -> Result<Self> {
+        match window {
+            "rectangular" | "boxcar" => Ok(Self::Rectangular),
+            "hann" => Ok(Self::Hann),
+            "hamming" => Ok(Self::Hamming),
+            other => Err(Error::InvalidParameter(format!(
+                "Unknown window function '{}' (expected rectangular, hann or hamming)",
+                other
+            ))),
+        }
+    }
+
+    fn coefficients(&self, n: usize) -> Vec<f64> {
+        let phase = |k: usize| 2.0 * std::f64::consts::PI * k as f64 / (n - 1) as f64;
+        (0..n)
+            .map(|k| match self {
+                Self::Rectangular => 1.0,
+                Self::Hann => 0.5 - 0.5 * phase(k).cos(),
+                Self::Hamming => 0.54 - 0.46 * phase(k).cos(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Spectrogram {
+    pub times: Vec<f64>,
+    pub frequencies: Vec<f64>,
+    pub power: Array2<f64>,
+}
+
+impl Spectrogram {
+    pub fn compute(series: &TimeSeries, options: &SpectrogramOptions) -> Result<Self> {
+        options.validate()?;
+
+        let n = options.window_length;
+        if series.len() < n {
+            return Err(Error::InvalidParameter(format!(
+                "Observable '{}' has {} samples, fewer than the spectrogram window {}",
+                series.name,
+                series.len(),
+                n
+            )));
+        }
+
+        let dt = series.times[1] - series.times[0];
+        if series
+            .times
+            .windows(2)
+            .any(|w| ((w[1] - w[0]) - dt).abs() > 1e-9 * dt.abs())
+        {
+            return Err(Error::InvalidParameter(format!(
+                "Spectrogram of '{}' requires uniformly sampled output times",
+                series.name
+            )));
+        }
+
+        let window = WindowFunction::parse(&options.window)?.coefficients(n);
+        let norm = window.iter().map(|w| w * w).sum::<f64>();
+        let fft = FftPlanner::new().plan_fft_forward(n);
+        let num_freqs = n / 2 + 1;
+
+        let starts: Vec<usize> = (0..=series.len() - n).step_by(options.hop()).collect();
+        let mut power = Array2::zeros((starts.len(), num_freqs));
+        let mut times = Vec::with_capacity(starts.len());
+        let mut buffer = vec![Complex64::new(0.0, 0.0); n];
+
+        for (row, &start) in starts.iter().enumerate() {
+            let frame = &series.values[start..start + n];
+            let mean = frame.iter().sum::<Complex64>() / n as f64;
+            for (k, (x, w)) in frame.iter().zip(&window).enumerate() {
+                buffer[k] = (x - mean) * *w;
+            }
+            fft.process(&mut buffer);
+
+            for k in 0..num_freqs {
+                power[[row, k]] = buffer[k].norm_sqr() / norm;
+            }
+            times.push(0.5 * (series.times[start] + series.times[start + n - 1]));
+        }
+
+        let frequencies = (0..num_freqs)
+            .map(|k| 2.0 * std::f64::consts::PI * k as f64 / (n as f64 * dt))
+            .collect();
+
+        Ok(Self {
+            times,
+            frequencies,
+            power,
+        })
+    }
+
+    pub fn peak_frequencies(&self) -> Vec<f64> {
+        self.power
+            .rows()
+            .into_iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .skip(1)
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .map(|(k, _)| self.frequencies[k])
+                    .unwrap_or(0.0)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::SimulationResults;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_spectrogram_tracks_chirp() {
+        let mut results = SimulationResults::new();
+        let dt = 0.01;
+        for i in 0..4096 {
+            let t = i as f64 * dt;
+            let omega = if t < 20.0 { 10.0 } else { 30.0 };
+            results.add_observable("x", t, Complex64::new((omega * t).cos(), 0.0));
+        }
+
+        let options = SpectrogramOptions {
+            window_length: 256,
+            ..SpectrogramOptions::default()
+        };
+        let spec = Spectrogram::compute(&results.series("x").unwrap(), &options).unwrap();
+        let peaks = spec.peak_frequencies();
+        let resolution = spec.frequencies[1];
+
+        assert_eq!(spec.power.dim(), (spec.times.len(), 129));
+        assert_relative_eq!(peaks[0], 10.0, epsilon = resolution);
+        assert_relative_eq!(*peaks.last().unwrap(), 30.0, epsilon = resolution);
+    }
+
+    #[test]
+    fn test_invalid_options() {
+        let options = SpectrogramOptions {
+            overlap: 1.0,
+            ..SpectrogramOptions::default()
+        };
+        assert!(options.validate().is_err());
+        assert!(WindowFunction::parse("kaiser").is_err());
+    }
+}
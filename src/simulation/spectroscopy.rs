@@ -0,0 +1,227 @@
+use crate::core::hamiltonian::TimeIndependentHamiltonian;
+use crate::core::lindblad::{LindbladOperator, LindbladSolver};
+use crate::data::config::Config;
+use crate::simulation::{CumulantSolver, TavisCummings};
+use crate::utils::math::solve;
+use crate::utils::{Error, Result};
+use ndarray::{Array1, Array2};
+use num_complex::Complex64;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpectroscopyConfig {
+    #[serde(default)]
+    pub span: Option<f64>,
+    #[serde(default = "default_points")]
+    pub points: usize,
+}
+
+impl Default for SpectroscopyConfig {
+    fn default() -> Self {
+        Self {
+            span: None,
+            points: default_points(),
+        }
+    }
+}
+
+fn default_points() -> usize {
+    401
+}
+
+impl SpectroscopyConfig {
+    pub fn detunings(&self, model: &TavisCummings) -> Result<Vec<f64>> {
+        let span = self.span.unwrap_or_else(|| {
+            3.0 * model.g * (model.emitters as f64).sqrt() + 5.0 * (model.kappa + model.gamma)
+        });
+        if !(span > 0.0 && span.is_finite()) || self.points < 3 {
+            return Err(Error::InvalidParameter(format!(
+                "spectroscopy needs a positive span and at least 3 points, got span {} and {} points",
+                span, self.points
+            )));
+        }
+        let step = 2.0 * span / (self.points - 1) as f64;
+        Ok((0..self.points)
+            .map(|k| -span + k as f64 * step)
+            .collect())
+    }
+}
+
+pub struct NormalModes {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl NormalModes {
+    pub fn splitting(&self) -> f64 {
+        self.upper - self.lower
+    }
+}
+
+pub struct TransmissionSpectrum {
+    pub detunings: Vec<f64>,
+    pub photon_number: Vec<f64>,
+    pub kappa: f64,
+}
+
+impl TransmissionSpectrum {
+    pub fn compute(model: &TavisCummings, cavity_dim: usize, detunings: &[f64]) -> Result<Self> {
+        if model.drive <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "A transmission spectrum needs a weak probe, set system.parameters.drive > 0"
+                    .to_string(),
+            ));
+        }
+        if model.kappa <= 0.0 {
+            return Err(Error::InvalidParameter(
+                "A transmission spectrum needs cavity decay, set cumulant.kappa > 0".to_string(),
+            ));
+        }
+        let photon_number = detunings
+            .iter()
+            .map(|&detuning| {
+                let probed = model
+                    .clone()
+                    .with_drive(model.drive, model.omega_c + detuning);
+                let dense = probed.dense(cavity_dim)?;
+                let rho = steady_state(dense.hamiltonian, dense.collapse_ops)?;
+                Ok(expectation(&dense.photon_number, &rho))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            detunings: detunings.to_vec(),
+            photon_number,
+            kappa: model.kappa,
+        })
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let solver = CumulantSolver::from_config(config)?;
+        let detunings = config.spectroscopy.detunings(solver.model())?;
+        Self::compute(solver.model(), config.system.hilbert_dim, &detunings)
+    }
+
+    pub fn transmission(&self) -> Vec<f64> {
+        self.photon_number.iter().map(|n| self.kappa * n).collect()
+    }
+
+    pub fn peaks(&self) -> Vec<(f64, f64)> {
+        let y = &self.photon_number;
+        let mut peaks: Vec<(f64, f64)> = (1..y.len().saturating_sub(1))
+            .filter(|&k| y[k] > y[k - 1] && y[k] >= y[k + 1])
+            .map(|k| {
+                let curvature = y[k - 1] - 2.0 * y[k] + y[k + 1];
+                let offset = if curvature < 0.0 {
+                    0.5 * (y[k - 1] - y[k + 1]) / curvature
+                } else {
+                    0.0
+                };
+                let step = self.detunings[k + 1] - self.detunings[k];
+                (self.detunings[k] - offset * step, y[k])
+            })
+            .collect();
+        peaks.sort_by(|a, b| b.1.total_cmp(&a.1));
+        peaks
+    }
+
+    pub fn normal_modes(&self) -> Result<NormalModes> {
+        let peaks = self.peaks();
+        if peaks.len() < 2 {
+            return Err(Error::Numerical(format!(
+                "Found {} transmission peak(s), the normal modes are not resolved. Widen the span or check that g exceeds the linewidths",
+                peaks.len()
+            )));
+        }
+        let (a, b) = (peaks[0].0, peaks[1].0);
+        Ok(NormalModes {
+            lower: a.min(b),
+            upper: a.max(b),
+        })
+    }
+
+    pub fn write_csv(&self, path: &Path) -> Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(writer, "detuning,photon_number,transmission")?;
+        for (detuning, n) in self.detunings.iter().zip(&self.photon_number) {
+            writeln!(writer, "{},{},{}", detuning, n, self.kappa * n)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+pub fn steady_state(
+    hamiltonian: Array2<Complex64>,
+    collapse_ops: Vec<LindbladOperator>,
+) -> Result<Array2<Complex64>> {
+    let dim = hamiltonian.nrows();
+    let solver = LindbladSolver::new(
+        Box::new(TimeIndependentHamiltonian::new(hamiltonian)),
+        collapse_ops,
+    )?;
+    let mut liouvillian = solver.liouvillian(0.0)?;
+    liouvillian.row_mut(0).fill(Complex64::new(0.0, 0.0));
+    for i in 0..dim {
+        liouvillian[[0, i * dim + i]] = Complex64::new(1.0, 0.0);
+    }
+    let mut rhs = Array1::zeros(dim * dim);
+    rhs[0] = Complex64::new(1.0, 0.0);
+    let rho = solve(&liouvillian, &rhs);
+    if rho.iter().any(|x| !x.is_finite()) {
+        return Err(Error::Numerical(
+            "Steady-state solve produced non-finite values".to_string(),
+        ));
+    }
+    Ok(Array2::from_shape_vec((dim, dim), rho.to_vec()).expect("d² entries fill a d×d matrix"))
+}
+
+fn expectation(operator: &Array2<Complex64>, rho: &Array2<Complex64>) -> f64 {
+    operator.t().iter().zip(rho.iter()).map(|(o, r)| o * r).sum::<Complex64>().re
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn probe(emitters: usize) -> TavisCummings {
+        TavisCummings::new(emitters, 1.0, 1.0, 0.05)
+            .with_drive(1e-4, 1.0)
+            .with_losses(0.01, 0.002, 0.0)
+    }
+
+    #[test]
+    fn test_vacuum_rabi_splitting_is_twice_the_coupling() {
+        let model = probe(1);
+        let detunings = SpectroscopyConfig {
+            span: Some(0.1),
+            points: 101,
+        }
+        .detunings(&model)
+        .unwrap();
+        let spectrum = TransmissionSpectrum::compute(&model, 3, &detunings).unwrap();
+        let modes = spectrum.normal_modes().unwrap();
+        assert_relative_eq!(modes.splitting(), 2.0 * model.g, max_relative = 1e-2);
+        assert_relative_eq!(modes.lower, -modes.upper, epsilon = 1e-3);
+        assert!(spectrum.photon_number.iter().all(|&n| n < 1e-2));
+    }
+
+    #[test]
+    fn test_collective_splitting_grows_with_sqrt_n() {
+        let model = probe(2);
+        // Two narrow windows around ±g√2, the peak interpolation does the rest.
+        let expected = model.g * 2f64.sqrt();
+        let detunings: Vec<f64> = [-expected, expected]
+            .iter()
+            .flat_map(|&centre| (-5..=5).map(move |k| centre + 0.002 * k as f64))
+            .collect();
+        let spectrum = TransmissionSpectrum::compute(&model, 3, &detunings).unwrap();
+        assert_relative_eq!(
+            spectrum.normal_modes().unwrap().splitting(),
+            2.0 * expected,
+            max_relative = 2e-2
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use crate::simulation::TimeSeries;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SteadyStateOptions {
+    #[serde(default = "default_window")]
+    pub window: f64,
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+}
+
+fn default_window() -> f64 {
+    0.25
+}
+
+fn default_tolerance() -> f64 {
+    1e-3
+}
+
+impl Default for SteadyStateOptions {
+    fn default() -> Self {
+        Self {
+            window: default_window(),
+            tolerance: default_tolerance(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SteadyStateEstimate {
+    pub mean: f64,
+    pub std_error: f64,
+    pub autocorrelation_time: f64,
+    pub tail_start: f64,
+    pub converged_at: Option<f64>,
+}
+
+pub fn estimate(series: &TimeSeries, options: &SteadyStateOptions) -> Option<SteadyStateEstimate> {
+    let n = series.len();
+    if n == 0 {
+        return None;
+    }
+
+    let values = series.to_vec_real();
+    let t0 = series.times[0];
+    let t_end = series.times[n - 1];
+    let tail_start = t_end - options.window.clamp(0.0, 1.0) * (t_end - t0);
+    let first = series.times.partition_point(|&t| t < tail_start).min(n - 1);
+    let tail = &values[first..];
+    let len = tail.len();
+
+    let mean = tail.iter().sum::<f64>() / len as f64;
+    let variance = tail.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / len as f64;
+    let autocorrelation_time = integrated_autocorrelation_time(tail, mean, variance);
+    let std_error = (variance * 2.0 * autocorrelation_time / len as f64).sqrt();
+
+    Some(SteadyStateEstimate {
+        mean,
+        std_error,
+        autocorrelation_time,
+        tail_start: series.times[first],
+        converged_at: convergence_time(series.times, &values, len, mean, options, std_error),
+    })
+}
+
+fn integrated_autocorrelation_time(tail: &[f64], mean: f64, variance: f64) -> f64 {
+    if variance <= f64::EPSILON * mean.abs().max(1.0) {
+        return 0.5;
+    }
+
+    let n = tail.len();
+    let mut tau = 0.5;
+    for lag in 1..n {
+        let rho = tail[..n - lag]
+            .iter()
+            .zip(&tail[lag..])
+            .map(|(a, b)| (a - mean) * (b - mean))
+            .sum::<f64>()
+            / ((n - lag) as f64 * variance);
+        tau += rho;
+
+        if lag as f64 >= 5.0 * tau {
+            break;
+        }
+    }
+
+    tau.max(0.5)
+}
+
+fn convergence_time(
+    times: &[f64],
+    values: &[f64],
+    window: usize,
+    mean: f64,
+    options: &SteadyStateOptions,
+    std_error: f64,
+) -> Option<f64> {
+    let n = values.len();
+    if window >= n {
+        return None;
+    }
+
+    let band = options.tolerance.max(3.0 * std_error);
+    let mut prefix = vec![0.0; n + 1];
+    for (k, x) in values.iter().enumerate() {
+        prefix[k + 1] = prefix[k] + x;
+    }
+
+    let window_mean = |start: usize| (prefix[start + window] - prefix[start]) / window as f64;
+    let last_outside = (0..=n - window)
+        .rev()
+        .find(|&start| (window_mean(start) - mean).abs() > band);
+
+    match last_outside {
+        None => Some(times[0]),
+        Some(start) if start + 1 < n - window => Some(times[start + 1]),
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulation::SimulationResults;
+    use approx::assert_relative_eq;
+    use num_complex::Complex64;
+
+    #[test]
+    fn test_relaxing_observable_converges() {
+        let mut results = SimulationResults::new();
+        for i in 0..2000 {
+            let t = i as f64 * 0.01;
+            let value = 0.3 + 0.7 * (-t).exp() * (5.0 * t).cos();
+            results.add_observable("p", t, Complex64::new(value, 0.0));
+        }
+
+        let est = estimate(
+            &results.series("p").unwrap(),
+            &SteadyStateOptions::default(),
+        )
+        .unwrap();
+        assert_relative_eq!(est.mean, 0.3, epsilon = 1e-4);
+        assert_relative_eq!(est.tail_start, 15.0, epsilon = 0.011);
+        let converged = est.converged_at.unwrap();
+        assert!(converged > 1.0 && converged < 15.0);
+    }
+
+    #[test]
+    fn test_error_bar_accounts_for_correlation() {
+        let mut results = SimulationResults::new();
+        for i in 0..4000 {
+            let t = i as f64 * 0.01;
+            results.add_observable("x", t, Complex64::new((0.3 * t).sin(), 0.0));
+        }
+
+        let options = SteadyStateOptions {
+            window: 1.0,
+            tolerance: 1e-3,
+        };
+        let est = estimate(&results.series("x").unwrap(), &options).unwrap();
+        let naive = (0.5_f64 / 4000.0).sqrt();
+        assert!(est.autocorrelation_time > 10.0);
+        assert!(est.std_error > 5.0 * naive);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::simulation::SimulationResults;
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+const MIN_STEADY_STATE_SAMPLES: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    Below,
+    AtMost,
+    Above,
+    AtLeast,
+}
+
+impl Comparison {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::Below => "<",
+            Comparison::AtMost => "<=",
+            Comparison::Above => ">",
+            Comparison::AtLeast => ">=",
+        }
+    }
+
+    fn holds(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::Below => value < threshold,
+            Comparison::AtMost => value <= threshold,
+            Comparison::Above => value > threshold,
+            Comparison::AtLeast => value >= threshold,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StopCondition {
+    Threshold {
+        observable: String,
+        comparison: Comparison,
+        value: f64,
+    },
+    SteadyState {
+        observable: Option<String>,
+    },
+}
+
+impl StopCondition {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        if spec == "steady_state" {
+            return Ok(StopCondition::SteadyState { observable: None });
+        }
+        if let Some(name) = spec
+            .strip_prefix("steady_state(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            return Ok(StopCondition::SteadyState {
+                observable: Some(name.trim().to_string()),
+            });
+        }
+
+        let operators = [
+            ("<=", Comparison::AtMost),
+            (">=", Comparison::AtLeast),
+            ("<", Comparison::Below),
+            (">", Comparison::Above),
+        ];
+        for (symbol, comparison) in operators {
+            if let Some((observable, value)) = spec.split_once(symbol) {
+                let observable = observable.trim();
+                let value: f64 = value.trim().parse().map_err(|_| {
+                    Error::Config(format!(
+                        "Invalid threshold '{}' in stop condition '{}'",
+                        value.trim(),
+                        spec
+                    ))
+                })?;
+                if observable.is_empty() {
+                    break;
+                }
+                return Ok(StopCondition::Threshold {
+                    observable: observable.to_string(),
+                    comparison,
+                    value,
+                });
+            }
+        }
+
+        Err(Error::Config(format!(
+            "Invalid stop condition '{}', expected '<observable> <op> <value>', \
+             'steady_state' or 'steady_state(<observable>)'",
+            spec
+        )))
+    }
+
+    pub fn observable(&self) -> Option<&str> {
+        match self {
+            StopCondition::Threshold { observable, .. } => Some(observable),
+            StopCondition::SteadyState { observable } => observable.as_deref(),
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            StopCondition::Threshold {
+                observable,
+                comparison,
+                value,
+            } => format!("{} {} {}", observable, comparison.symbol(), value),
+            StopCondition::SteadyState { observable: None } => "steady_state".to_string(),
+            StopCondition::SteadyState {
+                observable: Some(name),
+            } => format!("steady_state({})", name),
+        }
+    }
+
+    pub fn is_met(&self, results: &SimulationResults) -> bool {
+        match self {
+            StopCondition::Threshold {
+                observable,
+                comparison,
+                value,
+            } => results
+                .series(observable)
+                .and_then(|series| series.values.last().map(|v| v.re))
+                .is_some_and(|current| comparison.holds(current, *value)),
+            StopCondition::SteadyState { observable } => {
+                let names: Vec<&str> = match observable {
+                    Some(name) => vec![name.as_str()],
+                    None => results
+                        .observable_names()
+                        .into_iter()
+                        .map(|s| s.as_str())
+                        .collect(),
+                };
+                !names.is_empty() && names.iter().all(|name| is_steady(results, name))
+            }
+        }
+    }
+}
+
+fn is_steady(results: &SimulationResults, name: &str) -> bool {
+    let long_enough = results
+        .series(name)
+        .is_some_and(|series| series.len() >= MIN_STEADY_STATE_SAMPLES);
+    long_enough
+        && results
+            .steady_state(name)
+            .and_then(|est| est.converged_at.map(|t| t <= est.tail_start))
+            .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Termination {
+    pub time: f64,
+    pub reason: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_complex::Complex64;
+
+    #[test]
+    fn test_parse_stop_conditions() {
+        assert_eq!(
+            StopCondition::parse("population:1 >= 0.99").unwrap(),
+            StopCondition::Threshold {
+                observable: "population:1".to_string(),
+                comparison: Comparison::AtLeast,
+                value: 0.99,
+            }
+        );
+        assert_eq!(
+            StopCondition::parse("steady_state(n)")
+                .unwrap()
+                .observable(),
+            Some("n")
+        );
+        assert_eq!(
+            StopCondition::parse("purity<0.01").unwrap().describe(),
+            "purity < 0.01"
+        );
+        assert!(StopCondition::parse("purity is small").is_err());
+        assert!(StopCondition::parse("< 1").is_err());
+    }
+
+    #[test]
+    fn test_conditions_evaluate_on_results() {
+        let mut results = SimulationResults::new();
+        for k in 0..30 {
+            let t = k as f64;
+            results.add_observable("p", t, Complex64::new((t / 10.0).min(1.0), 0.0));
+            results.add_observable("flat", t, Complex64::new(0.5, 0.0));
+        }
+        assert!(StopCondition::parse("p >= 0.99").unwrap().is_met(&results));
+        assert!(!StopCondition::parse("p < 0.5").unwrap().is_met(&results));
+        assert!(StopCondition::parse("steady_state(flat)")
+            .unwrap()
+            .is_met(&results));
+        assert!(!StopCondition::parse("missing > 0")
+            .unwrap()
+            .is_met(&results));
+    }
+}
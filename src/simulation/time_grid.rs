@@ -0,0 +1,122 @@
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TimeGrid {
+    #[default]
+    EveryStep,
+
+    Uniform {
+        interval: f64,
+    },
+
+    Log {
+        start: f64,
+        num_points: usize,
+    },
+
+    Explicit {
+        times: Vec<f64>,
+    },
+}
+
+impl TimeGrid {
+    pub fn uniform(interval: f64) -> Self {
+        Self::Uniform { interval }
+    }
+
+    pub fn log(start: f64, num_points: usize) -> Self {
+        Self::Log { start, num_points }
+    }
+
+    pub fn explicit(times: Vec<f64>) -> Self {
+        Self::Explicit { times }
+    }
+
+    pub fn validate(&self, duration: f64) -> Result<()> {
+        match self {
+            Self::EveryStep => Ok(()),
+            Self::Uniform { interval } if *interval <= 0.0 => Err(Error::InvalidParameter(
+                format!("Output interval must be positive, got {}", interval),
+            )),
+            Self::Log { start, num_points } if *start <= 0.0 || *start >= duration => {
+                Err(Error::InvalidParameter(format!(
+                    "Log grid start must be in (0, {}), got {} ({} points)",
+                    duration, start, num_points
+                )))
+            }
+            Self::Log { num_points, .. } if *num_points < 2 => Err(Error::InvalidParameter(
+                "Log grid needs at least 2 points".to_string(),
+            )),
+            Self::Explicit { times } if times.iter().any(|&t| t < 0.0 || t > duration) => {
+                Err(Error::InvalidParameter(format!(
+                    "Explicit output times must lie in [0, {}]",
+                    duration
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn output_times(&self, duration: f64, timestep: f64) -> Vec<f64> {
+        let mut times = match self {
+            Self::EveryStep => {
+                let num_steps = (duration / timestep).ceil() as usize;
+                (0..num_steps).map(|k| k as f64 * timestep).collect()
+            }
+            Self::Uniform { interval } => {
+                let n = (duration / interval + 1e-9).floor() as usize;
+                (0..=n).map(|k| k as f64 * interval).collect()
+            }
+            Self::Log { start, num_points } => {
+                let ratio = (duration / start).ln() / (*num_points - 1) as f64;
+                let mut times = vec![0.0];
+                times.extend((0..*num_points - 1).map(|k| start * (ratio * k as f64).exp()));
+                times.push(duration);
+                times
+            }
+            Self::Explicit { times } => times.clone(),
+        };
+
+        times.retain(|&t| (0.0..=duration * (1.0 + 1e-12)).contains(&t));
+        times.sort_by(|a, b| a.total_cmp(b));
+        times.dedup();
+        times
+    }
+
+    pub fn is_uniform(&self) -> bool {
+        matches!(self, Self::EveryStep | Self::Uniform { .. })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_uniform_and_every_step() {
+        assert_eq!(TimeGrid::EveryStep.output_times(1.0, 0.25).len(), 4);
+
+        let times = TimeGrid::uniform(0.5).output_times(2.0, 0.1);
+        assert_eq!(times, vec![0.0, 0.5, 1.0, 1.5, 2.0]);
+    }
+
+    #[test]
+    fn test_log_grid() {
+        let times = TimeGrid::log(0.01, 5).output_times(100.0, 0.01);
+        assert_eq!(times.len(), 6);
+        assert_eq!(times[0], 0.0);
+        assert_relative_eq!(times[1], 0.01);
+        assert_relative_eq!(times[3], 1.0, epsilon = 1e-12);
+        assert_relative_eq!(times[5], 100.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_explicit_grid_validation() {
+        assert!(TimeGrid::explicit(vec![0.0, 5.0]).validate(1.0).is_err());
+        let times = TimeGrid::explicit(vec![0.7, 0.1, 0.1]).output_times(1.0, 0.1);
+        assert_eq!(times, vec![0.1, 0.7]);
+    }
+}
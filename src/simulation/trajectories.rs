@@ -0,0 +1,399 @@
+use crate::core::lindblad::LindbladOperator;
+use crate::core::{Hamiltonian, Observable, QuantumState};
+use crate::simulation::{JobControl, SimulationResults};
+use crate::utils::performance::{is_deterministic, resolve_seed};
+use crate::utils::resources::ResourceMeter;
+use crate::utils::{Error, Result};
+use ndarray::{Array1, Array2};
+use num_complex::Complex64;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+
+const DETERMINISTIC_CHUNK: usize = 16;
+
+pub struct QuantumTrajectories {
+    hamiltonian: Box<dyn Hamiltonian>,
+    lindblad_ops: Vec<LindbladOperator>,
+    decay: Array2<Complex64>,
+    trajectories: usize,
+    seed: Option<u64>,
+    deterministic: bool,
+}
+
+struct Moments {
+    sum: Vec<Vec<Complex64>>,
+    sum_sq: Vec<Vec<f64>>,
+}
+
+impl Moments {
+    fn zeros(outputs: usize, observables: usize) -> Self {
+        Self {
+            sum: vec![vec![Complex64::new(0.0, 0.0); observables]; outputs],
+            sum_sq: vec![vec![0.0; observables]; outputs],
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        for (row, other) in self.sum.iter_mut().zip(other.sum) {
+            row.iter_mut().zip(other).for_each(|(a, b)| *a += b);
+        }
+        for (row, other) in self.sum_sq.iter_mut().zip(other.sum_sq) {
+            row.iter_mut().zip(other).for_each(|(a, b)| *a += b);
+        }
+        self
+    }
+}
+
+impl QuantumTrajectories {
+    pub fn new(
+        hamiltonian: Box<dyn Hamiltonian>,
+        lindblad_ops: Vec<LindbladOperator>,
+        trajectories: usize,
+    ) -> Result<Self> {
+        let dim = hamiltonian.dim();
+        if let Some(op) = lindblad_ops
+            .iter()
+            .find(|op| op.operator.nrows() != dim || op.operator.ncols() != dim)
+        {
+            return Err(Error::dimension_mismatch(dim, op.operator.nrows()));
+        }
+        if trajectories == 0 {
+            return Err(Error::InvalidParameter(
+                "Quantum trajectories need at least one trajectory".to_string(),
+            ));
+        }
+
+        let mut decay = Array2::zeros((dim, dim));
+        for op in &lindblad_ops {
+            let l = op.operator.as_ref();
+            decay = decay + l.t().mapv(|x| x.conj()).dot(l).mapv(|x| x * op.rate);
+        }
+        Ok(Self {
+            hamiltonian,
+            lindblad_ops,
+            decay,
+            trajectories,
+            seed: None,
+            deterministic: is_deterministic(),
+        })
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    pub fn dim(&self) -> usize {
+        self.hamiltonian.dim()
+    }
+
+    pub fn trajectories(&self) -> usize {
+        self.trajectories
+    }
+
+    pub fn run(
+        &self,
+        psi0: &QuantumState,
+        observables: &[(String, Box<dyn Observable>)],
+        span: (f64, f64),
+        dt: f64,
+    ) -> Result<SimulationResults> {
+        self.run_with_control(psi0, observables, span, dt, None)
+    }
+
+    // Every trajectory checks `control` at each step, so cancelling stops the whole
+    // ensemble within one step.
+    pub fn run_with_control(
+        &self,
+        psi0: &QuantumState,
+        observables: &[(String, Box<dyn Observable>)],
+        (t0, t1): (f64, f64),
+        dt: f64,
+        control: Option<&JobControl>,
+    ) -> Result<SimulationResults> {
+        if psi0.dim() != self.dim() {
+            return Err(Error::dimension_mismatch(self.dim(), psi0.dim()));
+        }
+        if let Some((name, _)) = observables.iter().find(|(_, o)| o.dim() != self.dim()) {
+            return Err(Error::InvalidParameter(format!(
+                "Observable '{}' does not match the Hilbert dimension {}",
+                name,
+                self.dim()
+            )));
+        }
+        if !(dt > 0.0 && t1 > t0) {
+            return Err(Error::InvalidParameter(format!(
+                "Trajectories need a positive timestep and an increasing time span, got dt = {} \
+                 over ({}, {})",
+                dt, t0, t1
+            )));
+        }
+
+        let steps = ((t1 - t0) / dt - 1e-9).ceil() as usize;
+        let times: Vec<f64> = (0..=steps).map(|k| (t0 + k as f64 * dt).min(t1)).collect();
+        let meter = ResourceMeter::start();
+        let base = resolve_seed(self.seed).unwrap_or_else(|| StdRng::from_os_rng().random());
+
+        let run = |k: usize| {
+            self.trajectory(
+                psi0,
+                observables,
+                &times,
+                base.wrapping_add(k as u64),
+                control,
+            )
+        };
+        let zeros = || Moments::zeros(times.len(), observables.len());
+        let moments = if self.deterministic {
+            let indices: Vec<usize> = (0..self.trajectories).collect();
+            indices
+                .par_chunks(DETERMINISTIC_CHUNK)
+                .map(|chunk| {
+                    chunk
+                        .iter()
+                        .try_fold(zeros(), |moments, &k| Ok(moments.merge(run(k)?)))
+                })
+                .collect::<Result<Vec<_>>>()?
+                .into_iter()
+                .fold(zeros(), Moments::merge)
+        } else {
+            (0..self.trajectories)
+                .into_par_iter()
+                .map(run)
+                .try_reduce(zeros, |a, b| Ok(a.merge(b)))?
+        };
+
+        let count = self.trajectories as f64;
+        let mut results = SimulationResults::new();
+        for (j, (name, _)) in observables.iter().enumerate() {
+            let std_name = format!("{}_std", name);
+            for (k, &t) in times.iter().enumerate() {
+                let mean = moments.sum[k][j] / count;
+                let variance = ((moments.sum_sq[k][j] - count * mean.norm_sqr())
+                    / (count - 1.0).max(1.0))
+                .max(0.0);
+                results.add_observable(name, t, mean);
+                results.add_observable(&std_name, t, Complex64::new(variance.sqrt(), 0.0));
+            }
+        }
+        results.set_resources(meter.finish());
+        Ok(results)
+    }
+
+    fn trajectory(
+        &self,
+        psi0: &QuantumState,
+        observables: &[(String, Box<dyn Observable>)],
+        times: &[f64],
+        seed: u64,
+        control: Option<&JobControl>,
+    ) -> Result<Moments> {
+        let _span = tracing::trace_span!("trajectory", seed).entered();
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut moments = Moments::zeros(times.len(), observables.len());
+        let mut h = Array2::zeros((self.dim(), self.dim()));
+        let mut psi = psi0.data().clone();
+        let mut threshold: f64 = rng.random();
+
+        for (k, &t) in times.iter().enumerate() {
+            if let Some(control) = control {
+                control.check()?;
+            }
+            if k > 0 {
+                self.step(&mut psi, &mut h, times[k - 1], t - times[k - 1]);
+                if norm_sqr(&psi) <= threshold {
+                    self.jump(&mut psi, &mut rng);
+                    threshold = rng.random();
+                }
+            }
+            let norm = norm_sqr(&psi);
+            for (j, (_, observable)) in observables.iter().enumerate() {
+                let value = psi
+                    .iter()
+                    .zip(observable.matrix().dot(&psi))
+                    .map(|(a, b)| a.conj() * b)
+                    .sum::<Complex64>()
+                    / norm;
+                moments.sum[k][j] += value;
+                moments.sum_sq[k][j] += value.norm_sqr();
+            }
+        }
+        Ok(moments)
+    }
+
+    fn derivative(
+        &self,
+        psi: &Array1<Complex64>,
+        h: &mut Array2<Complex64>,
+        t: f64,
+    ) -> Array1<Complex64> {
+        crate::core::integrator::compute_hamiltonian(self.hamiltonian.as_ref(), t, h);
+        h.dot(psi).mapv(|x| Complex64::new(0.0, -1.0) * x) - self.decay.dot(psi).mapv(|x| x * 0.5)
+    }
+
+    fn step(&self, psi: &mut Array1<Complex64>, h: &mut Array2<Complex64>, t: f64, dt: f64) {
+        let k1 = self.derivative(psi, h, t);
+        let k2 = self.derivative(&(&*psi + &k1.mapv(|x| x * (dt / 2.0))), h, t + dt / 2.0);
+        let k3 = self.derivative(&(&*psi + &k2.mapv(|x| x * (dt / 2.0))), h, t + dt / 2.0);
+        let k4 = self.derivative(&(&*psi + &k3.mapv(|x| x * dt)), h, t + dt);
+        *psi = &*psi
+            + &(k1 + k2.mapv(|x| x * 2.0) + k3.mapv(|x| x * 2.0) + k4).mapv(|x| x * (dt / 6.0));
+    }
+
+    fn jump(&self, psi: &mut Array1<Complex64>, rng: &mut StdRng) {
+        let mut candidates: Vec<(Array1<Complex64>, f64)> = self
+            .lindblad_ops
+            .iter()
+            .map(|op| {
+                let jumped = op.operator.dot(psi);
+                let weight = op.rate * norm_sqr(&jumped);
+                (jumped, weight)
+            })
+            .collect();
+        let total: f64 = candidates.iter().map(|(_, w)| w).sum();
+        if total <= 0.0 {
+            let norm = norm_sqr(psi).sqrt();
+            psi.mapv_inplace(|x| x / norm);
+            return;
+        }
+
+        let mut pick = rng.random::<f64>() * total;
+        let index = candidates
+            .iter()
+            .position(|&(_, weight)| {
+                pick -= weight;
+                pick <= 0.0
+            })
+            .or_else(|| candidates.iter().rposition(|&(_, weight)| weight > 0.0))
+            .expect("total jump weight is positive");
+        let jumped = candidates.swap_remove(index).0;
+        let norm = norm_sqr(&jumped).sqrt();
+        *psi = jumped.mapv(|x| x / norm);
+    }
+}
+
+fn norm_sqr(psi: &Array1<Complex64>) -> f64 {
+    psi.iter().map(|x| x.norm_sqr()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::observables::PopulationOperator;
+    use crate::core::systems::DrivenTLS;
+    use crate::core::DensityMatrix;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_trajectories_reproduce_lindblad_decay() {
+        let observables: Vec<(String, Box<dyn Observable>)> = vec![(
+            "p1".to_string(),
+            Box::new(PopulationOperator::new(2, 1).unwrap()),
+        )];
+        let solver = QuantumTrajectories::new(
+            Box::new(DrivenTLS::new(0.0, 0.0, 0.3)),
+            vec![LindbladOperator::annihilation(2, 1.0).unwrap()],
+            800,
+        )
+        .unwrap()
+        .seed(7);
+        let excited = QuantumState::fock(2, 1).unwrap();
+        let results = solver
+            .run(&excited, &observables, (0.0, 2.0), 0.01)
+            .unwrap();
+
+        let mut rho = ndarray::Array2::zeros((2, 2));
+        rho[[1, 1]] = Complex64::new(1.0, 0.0);
+        let exact = crate::quick::lindblad_evolve(
+            DrivenTLS::new(0.0, 0.0, 0.3),
+            DensityMatrix::new_unchecked(rho),
+            vec![LindbladOperator::annihilation(2, 1.0).unwrap()],
+            (0.0, 2.0),
+            0.01,
+        )
+        .unwrap()
+        .expectation(observables[0].1.as_ref());
+
+        let p1 = results.series("p1").unwrap().to_vec_real();
+        assert_eq!(p1.len(), exact.len());
+        assert_relative_eq!(p1[0], 1.0);
+        for k in [50, 100, 200] {
+            assert!(
+                (p1[k] - exact[k].re).abs() < 0.06,
+                "t = {}: {} vs {}",
+                k as f64 * 0.01,
+                p1[k],
+                exact[k].re
+            );
+        }
+        assert!(results.series("p1_std").unwrap().values[100].re > 0.0);
+
+        let again = solver
+            .run(&excited, &observables, (0.0, 2.0), 0.01)
+            .unwrap();
+        assert_eq!(again.series("p1").unwrap().to_vec_real(), p1);
+    }
+
+    #[test]
+    fn test_deterministic_mode_is_independent_of_thread_count() {
+        let observables: Vec<(String, Box<dyn Observable>)> = vec![(
+            "p1".to_string(),
+            Box::new(PopulationOperator::new(2, 1).unwrap()),
+        )];
+        let solver = QuantumTrajectories::new(
+            Box::new(DrivenTLS::new(0.0, 0.0, 0.7)),
+            vec![LindbladOperator::annihilation(2, 0.5).unwrap()],
+            100,
+        )
+        .unwrap()
+        .seed(3)
+        .deterministic(true);
+        let excited = QuantumState::fock(2, 1).unwrap();
+        let run = |threads: usize| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap()
+                .install(|| solver.run(&excited, &observables, (0.0, 1.0), 0.01))
+                .unwrap()
+        };
+
+        let (serial, parallel) = (run(1), run(4));
+        for name in ["p1", "p1_std"] {
+            let bits = |results: &SimulationResults| -> Vec<u64> {
+                let values = results.series(name).unwrap().to_vec_real();
+                values.into_iter().map(f64::to_bits).collect()
+            };
+            assert_eq!(bits(&serial), bits(&parallel));
+        }
+    }
+
+    #[test]
+    fn test_cancelled_control_stops_every_trajectory() {
+        let observables: Vec<(String, Box<dyn Observable>)> = vec![(
+            "p1".to_string(),
+            Box::new(PopulationOperator::new(2, 1).unwrap()),
+        )];
+        let excited = QuantumState::fock(2, 1).unwrap();
+        let control = JobControl::new();
+        control.cancel();
+        for deterministic in [false, true] {
+            let solver = QuantumTrajectories::new(
+                Box::new(DrivenTLS::new(0.0, 0.0, 0.7)),
+                vec![LindbladOperator::annihilation(2, 0.5).unwrap()],
+                50,
+            )
+            .unwrap()
+            .deterministic(deterministic);
+            let result =
+                solver.run_with_control(&excited, &observables, (0.0, 1.0), 0.01, Some(&control));
+            assert!(matches!(result, Err(Error::Cancelled(_))));
+        }
+    }
+}
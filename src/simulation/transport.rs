@@ -0,0 +1,259 @@
+use crate::core::observables::PopulationOperator;
+use crate::core::{Hamiltonian, QuantumState};
+use crate::simulation::SimulationResults;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+
+pub const ARRIVAL_OBSERVABLE: &str = "arrival";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Injection {
+    Excitation,
+    Drive { amplitude: f64, frequency: f64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transport {
+    source: usize,
+    target: usize,
+    injection: Injection,
+}
+
+impl Transport {
+    pub fn new(source: usize, target: usize) -> Self {
+        Self {
+            source,
+            target,
+            injection: Injection::Excitation,
+        }
+    }
+
+    pub fn drive(mut self, amplitude: f64, frequency: f64) -> Self {
+        self.injection = Injection::Drive {
+            amplitude,
+            frequency,
+        };
+        self
+    }
+
+    pub fn source(&self) -> usize {
+        self.source
+    }
+
+    pub fn target(&self) -> usize {
+        self.target
+    }
+
+    pub fn injection(&self) -> Injection {
+        self.injection
+    }
+
+    pub fn validate(&self, dim: usize) -> Result<()> {
+        if let Some(site) = [self.source, self.target]
+            .into_iter()
+            .find(|&site| site + 1 >= dim)
+        {
+            return Err(Error::InvalidParameter(format!(
+                "Transport site {} is outside a lattice with {} sites",
+                site,
+                dim.saturating_sub(1)
+            )));
+        }
+        if let Injection::Drive { frequency, .. } = self.injection {
+            if !(frequency > 0.0 && frequency.is_finite()) {
+                return Err(Error::InvalidParameter(format!(
+                    "Source drive frequency must be positive, got {}",
+                    frequency
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn initial_state(&self, dim: usize) -> Result<QuantumState> {
+        match self.injection {
+            Injection::Excitation => QuantumState::fock(dim, self.source + 1),
+            Injection::Drive { .. } => QuantumState::fock(dim, 0),
+        }
+    }
+
+    pub fn hamiltonian(&self, base: Box<dyn Hamiltonian>) -> Result<Box<dyn Hamiltonian>> {
+        self.validate(base.dim())?;
+        Ok(match self.injection {
+            Injection::Excitation => base,
+            Injection::Drive {
+                amplitude,
+                frequency,
+            } => Box::new(SourceDrive {
+                base,
+                site: self.source + 1,
+                amplitude,
+                frequency,
+            }),
+        })
+    }
+
+    pub fn arrival(&self, dim: usize) -> Result<PopulationOperator> {
+        PopulationOperator::new(dim, self.target + 1)
+    }
+}
+
+pub struct SourceDrive {
+    base: Box<dyn Hamiltonian>,
+    site: usize,
+    amplitude: f64,
+    frequency: f64,
+}
+
+impl Hamiltonian for SourceDrive {
+    fn dim(&self) -> usize {
+        self.base.dim()
+    }
+
+    fn compute(&self, t: f64, out: &mut Array2<Complex64>) {
+        self.base.compute(t, out);
+        let coupling = Complex64::new(self.amplitude * (self.frequency * t).cos(), 0.0);
+        out[[0, self.site]] += coupling;
+        out[[self.site, 0]] += coupling;
+    }
+
+    fn period(&self) -> Option<f64> {
+        let period = 2.0 * std::f64::consts::PI / self.frequency;
+        match self.base.period() {
+            Some(base) if (base / period - (base / period).round()).abs() > 1e-9 => None,
+            Some(base) => Some(base.max(period)),
+            None if self.base.is_time_independent() => Some(period),
+            None => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransportMetrics {
+    pub peak_arrival: f64,
+    pub peak_time: f64,
+    pub arrival_time: Option<f64>,
+    pub mean_arrival: f64,
+    pub peak_flux: f64,
+}
+
+impl TransportMetrics {
+    pub fn from_results(results: &SimulationResults, threshold: f64) -> Result<Self> {
+        let series = results.series(ARRIVAL_OBSERVABLE).ok_or_else(|| {
+            Error::InvalidParameter(format!(
+                "Transport metrics need the '{}' observable",
+                ARRIVAL_OBSERVABLE
+            ))
+        })?;
+        let (times, p) = (series.times, series.to_vec_real());
+        let (Some(&t0), Some(&t1)) = (times.first(), times.last()) else {
+            return Err(Error::InvalidParameter(
+                "Transport metrics need at least one sample".to_string(),
+            ));
+        };
+
+        let (peak_index, &peak_arrival) = p
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .expect("series is not empty");
+        let arrival_time = p.iter().position(|&v| v >= threshold).map(|k| times[k]);
+        let mean_arrival = if t1 > t0 {
+            (1..times.len())
+                .map(|k| 0.5 * (p[k] + p[k - 1]) * (times[k] - times[k - 1]))
+                .sum::<f64>()
+                / (t1 - t0)
+        } else {
+            p[0]
+        };
+        let peak_flux = flux(times, &p)
+            .into_iter()
+            .fold(0.0, |max: f64, (_, j)| max.max(j));
+
+        Ok(Self {
+            peak_arrival,
+            peak_time: times[peak_index],
+            arrival_time,
+            mean_arrival,
+            peak_flux,
+        })
+    }
+
+    pub fn metrics(&self) -> Vec<(String, f64)> {
+        [
+            ("peak_arrival", self.peak_arrival),
+            ("peak_time", self.peak_time),
+            ("arrival_time", self.arrival_time.unwrap_or(f64::NAN)),
+            ("mean_arrival", self.mean_arrival),
+            ("peak_flux", self.peak_flux),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+    }
+}
+
+pub fn flux(times: &[f64], arrival: &[f64]) -> Vec<(f64, f64)> {
+    times
+        .windows(2)
+        .zip(arrival.windows(2))
+        .filter(|(t, _)| t[1] > t[0])
+        .map(|(t, p)| (0.5 * (t[0] + t[1]), (p[1] - p[0]) / (t[1] - t[0])))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::lattice::Lattice;
+    use crate::simulation::SimulationBuilder;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_excitation_crosses_perfect_state_transfer_chain() {
+        let n = 4;
+        let mut lattice = Lattice::new(n).unwrap();
+        for i in 1..n {
+            let j = 0.5 * ((i * (n - i)) as f64).sqrt();
+            lattice.set_bond(i - 1, i, j).unwrap();
+        }
+
+        let results = SimulationBuilder::new()
+            .hamiltonian(lattice.hamiltonian(0.0))
+            .transport(Transport::new(0, n - 1))
+            .duration(4.0)
+            .timestep(0.01)
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+
+        let metrics = TransportMetrics::from_results(&results, 0.5).unwrap();
+        assert_relative_eq!(metrics.peak_arrival, 1.0, epsilon = 1e-4);
+        assert_relative_eq!(metrics.peak_time, std::f64::consts::PI, epsilon = 0.01);
+        assert!(metrics.arrival_time.is_some_and(|t| t > 1.5 && t < 3.0));
+        assert!(metrics.peak_flux > 0.0);
+    }
+
+    #[test]
+    fn test_source_drive_populates_lattice_from_vacuum() {
+        let lattice = Lattice::chain(3, 1.0).unwrap();
+        let transport = Transport::new(0, 2).drive(0.05, 5.0);
+        let results = SimulationBuilder::new()
+            .hamiltonian(lattice.hamiltonian(5.0))
+            .transport(transport)
+            .duration(20.0)
+            .timestep(0.01)
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+
+        let arrival = results.series(ARRIVAL_OBSERVABLE).unwrap().to_vec_real();
+        assert_relative_eq!(arrival[0], 0.0);
+        assert!(arrival.iter().copied().fold(0.0, f64::max) > 0.01);
+        assert!(Transport::new(0, 3).validate(4).is_err());
+        assert!(Transport::new(0, 2).drive(1.0, 0.0).validate(4).is_err());
+    }
+}
@@ -0,0 +1,529 @@
+use crate::sweep::objective::ObjectiveConfig;
+use crate::sweep::parameter::Parameter;
+use crate::sweep::{SweepPoint, SweepRun};
+use crate::utils::{Error, Result};
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+
+const LENGTH_SCALES: [f64; 7] = [0.05, 0.1, 0.2, 0.3, 0.5, 0.8, 1.5];
+const NOISE_LEVELS: [f64; 3] = [1e-6, 1e-3, 1e-1];
+const RANDOM_CANDIDATES: usize = 2000;
+const LOCAL_CANDIDATES: usize = 200;
+const CHEBYSHEV_AUGMENTATION: f64 = 0.05;
+
+pub struct GaussianProcess {
+    inputs: Vec<Vec<f64>>,
+    targets: Vec<f64>,
+    length_scale: f64,
+    noise: f64,
+    cholesky: Vec<Vec<f64>>,
+    alpha: Vec<f64>,
+    mean: f64,
+    scale: f64,
+}
+
+impl GaussianProcess {
+    // Squared-exponential kernel with unit signal variance on standardized targets.
+    // The length scale and noise are picked from a small grid by marginal likelihood.
+    pub fn fit(inputs: Vec<Vec<f64>>, targets: &[f64]) -> Result<Self> {
+        if inputs.is_empty() || inputs.len() != targets.len() {
+            return Err(Error::InvalidParameter(format!(
+                "Gaussian process needs matching non-empty inputs and targets, got {} and {}",
+                inputs.len(),
+                targets.len()
+            )));
+        }
+        let n = targets.len() as f64;
+        let mean = targets.iter().sum::<f64>() / n;
+        let variance = targets.iter().map(|y| (y - mean).powi(2)).sum::<f64>() / n;
+        let scale = if variance > 0.0 { variance.sqrt() } else { 1.0 };
+        let standardized: Vec<f64> = targets.iter().map(|y| (y - mean) / scale).collect();
+
+        let mut best: Option<(f64, Self)> = None;
+        for length_scale in LENGTH_SCALES {
+            for noise in NOISE_LEVELS {
+                let Some(gp) =
+                    Self::with_hyperparameters(&inputs, &standardized, length_scale, noise)
+                else {
+                    continue;
+                };
+                let fit: f64 = gp.alpha.iter().zip(&standardized).map(|(a, y)| a * y).sum();
+                let log_det: f64 = (0..gp.alpha.len()).map(|i| gp.cholesky[i][i].ln()).sum();
+                let likelihood = -0.5 * fit - log_det;
+                if best.as_ref().is_none_or(|(l, _)| likelihood > *l) {
+                    best = Some((likelihood, gp));
+                }
+            }
+        }
+        let (_, mut gp) = best.ok_or_else(|| {
+            Error::Numerical("Gaussian process covariance is not positive definite".to_string())
+        })?;
+        gp.mean = mean;
+        gp.scale = scale;
+        Ok(gp)
+    }
+
+    fn with_hyperparameters(
+        inputs: &[Vec<f64>],
+        targets: &[f64],
+        length_scale: f64,
+        noise: f64,
+    ) -> Option<Self> {
+        let n = inputs.len();
+        let covariance: Vec<Vec<f64>> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| {
+                        kernel(&inputs[i], &inputs[j], length_scale)
+                            + if i == j { noise } else { 0.0 }
+                    })
+                    .collect()
+            })
+            .collect();
+        let cholesky = cholesky(covariance)?;
+        let alpha = backward(&cholesky, &forward(&cholesky, targets));
+        Some(Self {
+            inputs: inputs.to_vec(),
+            targets: targets.to_vec(),
+            length_scale,
+            noise,
+            cholesky,
+            alpha,
+            mean: 0.0,
+            scale: 1.0,
+        })
+    }
+
+    pub fn length_scale(&self) -> f64 {
+        self.length_scale
+    }
+
+    pub fn predict(&self, x: &[f64]) -> (f64, f64) {
+        let k: Vec<f64> = self
+            .inputs
+            .iter()
+            .map(|xi| kernel(xi, x, self.length_scale))
+            .collect();
+        let mean: f64 = k.iter().zip(&self.alpha).map(|(a, b)| a * b).sum();
+        let v = forward(&self.cholesky, &k);
+        let variance = (1.0 - v.iter().map(|x| x * x).sum::<f64>()).max(0.0);
+        (self.mean + self.scale * mean, self.scale * variance.sqrt())
+    }
+
+    fn condition_on(&self, x: Vec<f64>, y: f64) -> Option<Self> {
+        let mut inputs = self.inputs.clone();
+        inputs.push(x);
+        let mut targets = self.targets.clone();
+        targets.push((y - self.mean) / self.scale);
+        let mut gp = Self::with_hyperparameters(&inputs, &targets, self.length_scale, self.noise)?;
+        gp.mean = self.mean;
+        gp.scale = self.scale;
+        Some(gp)
+    }
+}
+
+pub fn expected_improvement(mean: f64, sigma: f64, best: f64, exploration: f64) -> f64 {
+    let improvement = mean - best - exploration;
+    if sigma <= 0.0 {
+        return improvement.max(0.0);
+    }
+    let z = improvement / sigma;
+    improvement * normal_cdf(z) + sigma * (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+// Proposes the next `count` points by maximizing expected improvement of a GP fitted
+// to the successful runs. Batches use the kriging believer: each pick is added to the
+// model at its predicted mean before choosing the next one. With several objectives,
+// each pick instead fits a fresh GP to a randomly weighted Chebyshev scalarization of
+// the normalized objectives (ParEGO), so successive picks spread along the Pareto front.
+// The search settings (exploration) come from the first objective.
+pub fn propose(
+    objectives: &[ObjectiveConfig],
+    parameters: &[Parameter],
+    runs: &[SweepRun],
+    count: usize,
+    seed: Option<u64>,
+) -> Result<Vec<SweepPoint>> {
+    let Some(first) = objectives.first() else {
+        return Err(Error::InvalidParameter(
+            "Optimization needs at least one objective".to_string(),
+        ));
+    };
+    let signs = objectives
+        .iter()
+        .map(|objective| Ok(objective.goal()?.sign()))
+        .collect::<Result<Vec<f64>>>()?;
+    let mut rng = crate::utils::performance::seeded_rng(seed);
+    let to_point = |unit: &[f64]| SweepPoint {
+        values: parameters
+            .iter()
+            .zip(unit)
+            .map(|(p, &u)| (p.name.clone(), p.at(u)))
+            .collect(),
+    };
+    for objective in objectives {
+        objective.check_produced(runs)?;
+    }
+
+    let (inputs, values): (Vec<Vec<f64>>, Vec<Vec<f64>>) = runs
+        .iter()
+        .filter_map(|run| {
+            let values = objectives
+                .iter()
+                .zip(&signs)
+                .map(|(objective, sign)| Some(sign * objective.value(run)?))
+                .collect::<Option<Vec<f64>>>()?;
+            let unit = parameters
+                .iter()
+                .zip(&run.point.values)
+                .map(|(p, (_, v))| p.fraction(*v))
+                .collect();
+            Some((unit, values))
+        })
+        .unzip();
+    let dim = parameters.len();
+    let random_unit = |rng: &mut rand::rngs::StdRng| -> Vec<f64> {
+        (0..dim).map(|_| rng.random::<f64>()).collect()
+    };
+    if values.len() < 2 {
+        return Ok((0..count)
+            .map(|_| to_point(&random_unit(&mut rng)))
+            .collect());
+    }
+
+    let mut candidates: Vec<Vec<f64>> = (0..RANDOM_CANDIDATES)
+        .map(|_| random_unit(&mut rng))
+        .collect();
+    let mut chosen: Vec<Vec<f64>> = Vec::with_capacity(count);
+    if objectives.len() == 1 {
+        let targets: Vec<f64> = values.iter().map(|v| v[0]).collect();
+        let mut gp = GaussianProcess::fit(inputs.clone(), &targets)?;
+        add_local_candidates(&mut candidates, &inputs, &targets, &gp, &mut rng)?;
+        let mut best = targets.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        for _ in 0..count {
+            let Some(x) = take_best_candidate(&mut candidates, &gp, best, first.exploration) else {
+                break;
+            };
+            let (mean, _) = gp.predict(&x);
+            best = best.max(mean);
+            if let Some(next) = gp.condition_on(x.clone(), mean) {
+                gp = next;
+            }
+            chosen.push(x);
+        }
+    } else {
+        let (lo, hi) = values.iter().fold(
+            (
+                vec![f64::INFINITY; objectives.len()],
+                vec![f64::NEG_INFINITY; objectives.len()],
+            ),
+            |(lo, hi), v| {
+                (
+                    lo.iter().zip(v).map(|(a, b)| a.min(*b)).collect(),
+                    hi.iter().zip(v).map(|(a, b)| a.max(*b)).collect(),
+                )
+            },
+        );
+        for _ in 0..count {
+            let mut weights: Vec<f64> = (0..objectives.len())
+                .map(|_| -rng.random::<f64>().max(f64::MIN_POSITIVE).ln())
+                .collect();
+            let total: f64 = weights.iter().sum();
+            weights.iter_mut().for_each(|w| *w /= total);
+            let targets: Vec<f64> = values
+                .iter()
+                .map(|v| -chebyshev_distance(v, &lo, &hi, &weights))
+                .collect();
+            let gp = GaussianProcess::fit(inputs.clone(), &targets)?;
+            add_local_candidates(&mut candidates, &inputs, &targets, &gp, &mut rng)?;
+            let best = targets.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+            let Some(x) = take_best_candidate(&mut candidates, &gp, best, first.exploration) else {
+                break;
+            };
+            chosen.push(x);
+        }
+    }
+    Ok(chosen.iter().map(|unit| to_point(unit)).collect())
+}
+
+// Augmented Chebyshev distance of signed objective values from the ideal point, with
+// each objective rescaled to [0, 1] over the observed runs.
+fn chebyshev_distance(values: &[f64], lo: &[f64], hi: &[f64], weights: &[f64]) -> f64 {
+    let gaps: Vec<f64> = values
+        .iter()
+        .zip(lo.iter().zip(hi))
+        .zip(weights)
+        .map(|((v, (lo, hi)), w)| {
+            let normalized = if hi > lo { (v - lo) / (hi - lo) } else { 1.0 };
+            w * (1.0 - normalized)
+        })
+        .collect();
+    gaps.iter().copied().fold(0.0, f64::max) + CHEBYSHEV_AUGMENTATION * gaps.iter().sum::<f64>()
+}
+
+fn add_local_candidates(
+    candidates: &mut Vec<Vec<f64>>,
+    inputs: &[Vec<f64>],
+    targets: &[f64],
+    gp: &GaussianProcess,
+    rng: &mut rand::rngs::StdRng,
+) -> Result<()> {
+    let incumbent = &inputs[targets
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(i, _)| i)
+        .unwrap_or(0)];
+    let jitter = Normal::new(0.0, 0.5 * gp.length_scale())
+        .map_err(|e| Error::Numerical(format!("Invalid candidate perturbation: {}", e)))?;
+    candidates.extend((0..LOCAL_CANDIDATES).map(|_| {
+        incumbent
+            .iter()
+            .map(|x| (x + jitter.sample(rng)).clamp(0.0, 1.0))
+            .collect()
+    }));
+    Ok(())
+}
+
+fn take_best_candidate(
+    candidates: &mut Vec<Vec<f64>>,
+    gp: &GaussianProcess,
+    best: f64,
+    exploration: f64,
+) -> Option<Vec<f64>> {
+    let (index, _) = candidates
+        .iter()
+        .enumerate()
+        .map(|(i, x)| {
+            let (mean, sigma) = gp.predict(x);
+            (
+                i,
+                expected_improvement(mean, sigma, best, exploration * gp.scale),
+            )
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    Some(candidates.swap_remove(index))
+}
+
+fn kernel(a: &[f64], b: &[f64], length_scale: f64) -> f64 {
+    let distance: f64 = a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum();
+    (-0.5 * distance / (length_scale * length_scale)).exp()
+}
+
+fn cholesky(mut a: Vec<Vec<f64>>) -> Option<Vec<Vec<f64>>> {
+    let n = a.len();
+    for j in 0..n {
+        let diagonal = a[j][j] - (0..j).map(|k| a[j][k] * a[j][k]).sum::<f64>();
+        if diagonal <= 0.0 || !diagonal.is_finite() {
+            return None;
+        }
+        a[j][j] = diagonal.sqrt();
+        for i in j + 1..n {
+            let sum: f64 = (0..j).map(|k| a[i][k] * a[j][k]).sum();
+            a[i][j] = (a[i][j] - sum) / a[j][j];
+        }
+        for value in &mut a[j][j + 1..] {
+            *value = 0.0;
+        }
+    }
+    Some(a)
+}
+
+fn forward(l: &[Vec<f64>], b: &[f64]) -> Vec<f64> {
+    let mut x = vec![0.0; b.len()];
+    for i in 0..b.len() {
+        let sum: f64 = (0..i).map(|k| l[i][k] * x[k]).sum();
+        x[i] = (b[i] - sum) / l[i][i];
+    }
+    x
+}
+
+fn backward(l: &[Vec<f64>], b: &[f64]) -> Vec<f64> {
+    let n = b.len();
+    let mut x = vec![0.0; n];
+    for i in (0..n).rev() {
+        let sum: f64 = (i + 1..n).map(|k| l[k][i] * x[k]).sum();
+        x[i] = (b[i] - sum) / l[i][i];
+    }
+    x
+}
+
+fn normal_cdf(z: f64) -> f64 {
+    0.5 * erfc(-z / std::f64::consts::SQRT_2)
+}
+
+// Chebyshev fit with fractional error below 1.2e-7 (Numerical Recipes, erfcc).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.26551223
+        + t * (1.00002368
+            + t * (0.37409196
+                + t * (0.09678418
+                    + t * (-0.18628806
+                        + t * (0.27886807
+                            + t * (-1.13520398
+                                + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let value = t * poly.exp();
+    if x >= 0.0 {
+        value
+    } else {
+        2.0 - value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sweep::objective::Goal;
+    use crate::sweep::{SweepOutput, SweepStrategy};
+    use approx::assert_relative_eq;
+
+    fn evaluate(points: Vec<SweepPoint>, f: impl Fn(&[f64]) -> f64) -> Vec<SweepRun> {
+        points
+            .into_iter()
+            .map(|point| {
+                let x: Vec<f64> = point.values.iter().map(|(_, v)| *v).collect();
+                SweepRun {
+                    index: 0,
+                    point,
+                    outcome: Ok(SweepOutput {
+                        metrics: vec![("cost".to_string(), f(&x))],
+                        results: None,
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_gaussian_process_interpolates_and_reports_uncertainty() {
+        let inputs: Vec<Vec<f64>> = (0..8).map(|k| vec![k as f64 / 7.0]).collect();
+        let targets: Vec<f64> = inputs.iter().map(|x| (6.0 * x[0]).sin()).collect();
+        let gp = GaussianProcess::fit(inputs, &targets).unwrap();
+        let (mean, sigma) = gp.predict(&[3.0 / 7.0]);
+        assert_relative_eq!(mean, targets[3], epsilon = 1e-2);
+        assert!(sigma < 0.05);
+        let (mean, _) = gp.predict(&[0.5]);
+        assert_relative_eq!(mean, 3.0f64.sin(), epsilon = 0.05);
+        assert!(gp.predict(&[3.0]).1 > 10.0 * sigma);
+        assert_relative_eq!(normal_cdf(1.0), 0.841344746, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_bayes_opt_finds_minimum_with_few_evaluations() {
+        let cost = |x: &[f64]| (x[0] - 3.2).powi(2) + 4.0 * (x[1] - 1.1).powi(2);
+        let parameters = [
+            Parameter::new("system.parameters.omega_d", 0.0, 5.0),
+            Parameter::new("system.parameters.rabi_freq", 0.0, 2.0),
+        ];
+        let objective = ObjectiveConfig {
+            batch: 2,
+            ..ObjectiveConfig::new("cost", Goal::Minimize)
+        };
+        let initial = SweepStrategy::BayesOpt
+            .sample(&parameters, 8, Some(3))
+            .into_iter()
+            .map(|values| SweepPoint {
+                values: parameters
+                    .iter()
+                    .map(|p| p.name.clone())
+                    .zip(values)
+                    .collect(),
+            })
+            .collect();
+
+        let mut runs = evaluate(initial, cost);
+        let initial_best = objective
+            .value(objective.best(&runs).unwrap().unwrap())
+            .unwrap();
+        for round in 0..10 {
+            let points = propose(
+                std::slice::from_ref(&objective),
+                &parameters,
+                &runs,
+                2,
+                Some(round),
+            )
+            .unwrap();
+            assert_eq!(points.len(), 2);
+            runs.extend(evaluate(points, cost));
+        }
+
+        let best = objective.best(&runs).unwrap().unwrap();
+        let value = objective.value(best).unwrap();
+        assert!(
+            value < 0.02,
+            "best cost {} at {}",
+            value,
+            best.point.label()
+        );
+        assert!(value < initial_best);
+        assert!(ObjectiveConfig::new("missing", Goal::Maximize)
+            .best(&runs)
+            .unwrap()
+            .is_none());
+        assert!(propose(
+            &[ObjectiveConfig::new("missing", Goal::Maximize)],
+            &parameters,
+            &runs,
+            1,
+            None
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_multi_objective_proposals_spread_along_the_front() {
+        let parameters = [Parameter::new("system.parameters.rabi_freq", 0.0, 1.0)];
+        let objectives = [
+            ObjectiveConfig::new("fidelity", Goal::Maximize),
+            ObjectiveConfig::new("energy", Goal::Minimize),
+        ];
+        let evaluate = |points: Vec<SweepPoint>| -> Vec<SweepRun> {
+            points
+                .into_iter()
+                .map(|point| {
+                    let x = point.values[0].1;
+                    SweepRun {
+                        index: 0,
+                        point,
+                        outcome: Ok(SweepOutput {
+                            metrics: vec![
+                                ("fidelity".to_string(), -(x - 0.8).powi(2)),
+                                ("energy".to_string(), (x - 0.2).powi(2)),
+                            ],
+                            results: None,
+                        }),
+                    }
+                })
+                .collect()
+        };
+        let initial = SweepStrategy::BayesOpt
+            .sample(&parameters, 5, Some(2))
+            .into_iter()
+            .map(|values| SweepPoint {
+                values: vec![(parameters[0].name.clone(), values[0])],
+            })
+            .collect();
+
+        let mut runs = evaluate(initial);
+        for round in 0..6 {
+            let points = propose(&objectives, &parameters, &runs, 2, Some(round)).unwrap();
+            assert_eq!(points.len(), 2);
+            runs.extend(evaluate(points));
+        }
+
+        let front = crate::sweep::pareto_front(&objectives, &runs).unwrap();
+        let xs: Vec<f64> = front.iter().map(|run| run.point.values[0].1).collect();
+        assert!(xs.iter().all(|x| (0.1..=0.9).contains(x)), "{:?}", xs);
+        assert!(
+            xs.iter().filter(|x| (0.2..=0.8).contains(*x)).count() >= 8,
+            "{:?}",
+            xs
+        );
+        let span = xs.iter().copied().fold(f64::NEG_INFINITY, f64::max)
+            - xs.iter().copied().fold(f64::INFINITY, f64::min);
+        assert!(span > 0.4, "{:?}", xs);
+    }
+}
@@ -0,0 +1,420 @@
+use crate::sweep::objective::ObjectiveConfig;
+use crate::sweep::parameter::Parameter;
+use crate::sweep::{SweepPoint, SweepRun, SweepStrategy};
+use crate::utils::math::hermitian_eigen;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand_distr::{Distribution, StandardNormal};
+
+const INITIAL_SIGMA: f64 = 0.3;
+const MIN_SIGMA: f64 = 1e-12;
+const DIFFERENTIAL_WEIGHT: f64 = 0.8;
+const CROSSOVER_RATE: f64 = 0.9;
+
+// Covariance matrix adaptation on the unit cube, with the default weights and learning
+// rates of Hansen's tutorial. Samples outside the cube are clipped to it and the clipped
+// points are what the update sees.
+pub struct CmaEs {
+    mean: Vec<f64>,
+    sigma: f64,
+    covariance: Vec<Vec<f64>>,
+    path_sigma: Vec<f64>,
+    path_c: Vec<f64>,
+    generation: usize,
+}
+
+impl CmaEs {
+    pub fn new(mean: Vec<f64>, sigma: f64) -> Self {
+        let n = mean.len();
+        Self {
+            mean,
+            sigma,
+            covariance: (0..n)
+                .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+                .collect(),
+            path_sigma: vec![0.0; n],
+            path_c: vec![0.0; n],
+            generation: 0,
+        }
+    }
+
+    pub fn mean(&self) -> &[f64] {
+        &self.mean
+    }
+
+    pub fn sigma(&self) -> f64 {
+        self.sigma
+    }
+
+    // Eigenvalues and eigenvectors (as columns) of the covariance.
+    fn eigen(&self) -> (Vec<f64>, Vec<Vec<f64>>) {
+        let n = self.mean.len();
+        let matrix = Array2::from_shape_fn((n, n), |(i, j)| {
+            Complex64::new(0.5 * (self.covariance[i][j] + self.covariance[j][i]), 0.0)
+        });
+        let (values, vectors) = hermitian_eigen(&matrix.view());
+        let values = values.into_iter().map(|d| d.max(1e-20)).collect();
+        let vectors = (0..n)
+            .map(|i| (0..n).map(|k| vectors[[i, k]].re).collect())
+            .collect();
+        (values, vectors)
+    }
+
+    pub fn ask(&self, count: usize, rng: &mut StdRng) -> Vec<Vec<f64>> {
+        let n = self.mean.len();
+        let (values, vectors) = self.eigen();
+        (0..count)
+            .map(|_| {
+                let z: Vec<f64> = (0..n)
+                    .map(|k| {
+                        let z: f64 = StandardNormal.sample(rng);
+                        values[k].sqrt() * z
+                    })
+                    .collect();
+                (0..n)
+                    .map(|i| {
+                        let y: f64 = (0..n).map(|k| vectors[i][k] * z[k]).sum();
+                        (self.mean[i] + self.sigma * y).clamp(0.0, 1.0)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Updates the distribution from one generation of (point, fitness) pairs, where
+    // larger fitness is better.
+    pub fn tell(&mut self, samples: &[(Vec<f64>, f64)]) {
+        let n = self.mean.len();
+        if samples.is_empty() || n == 0 {
+            return;
+        }
+        let dim = n as f64;
+        let mu = (samples.len() / 2).max(1);
+        let raw: Vec<f64> = (1..=mu)
+            .map(|i| (mu as f64 + 0.5).ln() - (i as f64).ln())
+            .collect();
+        let total: f64 = raw.iter().sum();
+        let weights: Vec<f64> = raw.iter().map(|w| w / total).collect();
+        let mu_eff = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+
+        let c_sigma = (mu_eff + 2.0) / (dim + mu_eff + 5.0);
+        let d_sigma = 1.0 + 2.0 * (((mu_eff - 1.0) / (dim + 1.0)).sqrt() - 1.0).max(0.0) + c_sigma;
+        let c_c = (4.0 + mu_eff / dim) / (dim + 4.0 + 2.0 * mu_eff / dim);
+        let c_1 = 2.0 / ((dim + 1.3).powi(2) + mu_eff);
+        let c_mu =
+            (1.0 - c_1).min(2.0 * (mu_eff - 2.0 + 1.0 / mu_eff) / ((dim + 2.0).powi(2) + mu_eff));
+        let chi_n = dim.sqrt() * (1.0 - 1.0 / (4.0 * dim) + 1.0 / (21.0 * dim * dim));
+
+        let mut ranked: Vec<&(Vec<f64>, f64)> = samples.iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let steps: Vec<Vec<f64>> = ranked[..mu]
+            .iter()
+            .map(|(x, _)| {
+                x.iter()
+                    .zip(&self.mean)
+                    .map(|(x, m)| (x - m) / self.sigma)
+                    .collect()
+            })
+            .collect();
+        let step: Vec<f64> = (0..n)
+            .map(|i| weights.iter().zip(&steps).map(|(w, y)| w * y[i]).sum())
+            .collect();
+        for (m, y) in self.mean.iter_mut().zip(&step) {
+            *m += self.sigma * y;
+        }
+
+        let (values, vectors) = self.eigen();
+        let rotated: Vec<f64> = (0..n)
+            .map(|k| (0..n).map(|i| vectors[i][k] * step[i]).sum::<f64>() / values[k].sqrt())
+            .collect();
+        let whitened: Vec<f64> = (0..n)
+            .map(|i| (0..n).map(|k| vectors[i][k] * rotated[k]).sum())
+            .collect();
+        let rate = (c_sigma * (2.0 - c_sigma) * mu_eff).sqrt();
+        for (p, w) in self.path_sigma.iter_mut().zip(&whitened) {
+            *p = (1.0 - c_sigma) * *p + rate * w;
+        }
+        self.generation += 1;
+        let norm = self.path_sigma.iter().map(|p| p * p).sum::<f64>().sqrt();
+        let stalled = norm / (1.0 - (1.0 - c_sigma).powi(2 * self.generation as i32)).sqrt()
+            >= (1.4 + 2.0 / (dim + 1.0)) * chi_n;
+        let h_sigma = if stalled { 0.0 } else { 1.0 };
+        let rate = (c_c * (2.0 - c_c) * mu_eff).sqrt();
+        for (p, y) in self.path_c.iter_mut().zip(&step) {
+            *p = (1.0 - c_c) * *p + h_sigma * rate * y;
+        }
+
+        for i in 0..n {
+            for j in 0..n {
+                let rank_mu: f64 = weights
+                    .iter()
+                    .zip(&steps)
+                    .map(|(w, y)| w * y[i] * y[j])
+                    .sum();
+                let c = self.covariance[i][j];
+                self.covariance[i][j] = (1.0 - c_1 - c_mu) * c
+                    + c_1
+                        * (self.path_c[i] * self.path_c[j]
+                            + (1.0 - h_sigma) * c_c * (2.0 - c_c) * c)
+                    + c_mu * rank_mu;
+            }
+        }
+        self.sigma =
+            (self.sigma * ((c_sigma / d_sigma) * (norm / chi_n - 1.0)).exp()).clamp(MIN_SIGMA, 1.0);
+    }
+}
+
+// DE/rand/1/bin: each member competes with a trial built from three other members,
+// and the better of the two survives into the next generation.
+pub struct DifferentialEvolution {
+    members: Vec<(Vec<f64>, f64)>,
+}
+
+impl DifferentialEvolution {
+    pub fn new(members: Vec<(Vec<f64>, f64)>) -> Self {
+        Self { members }
+    }
+
+    pub fn members(&self) -> &[(Vec<f64>, f64)] {
+        &self.members
+    }
+
+    pub fn tell(&mut self, trials: &[(Vec<f64>, f64)]) {
+        for (member, trial) in self.members.iter_mut().zip(trials) {
+            if trial.1 >= member.1 {
+                *member = trial.clone();
+            }
+        }
+    }
+
+    pub fn ask(&self, rng: &mut StdRng) -> Vec<Vec<f64>> {
+        let size = self.members.len();
+        (0..size)
+            .map(|i| {
+                let current = &self.members[i].0;
+                let mut others: Vec<usize> = (0..size).filter(|&k| k != i).collect();
+                if others.len() < 3 {
+                    return current.clone();
+                }
+                let (picked, _) = others.partial_shuffle(rng, 3);
+                let [a, b, c] = [picked[0], picked[1], picked[2]].map(|k| &self.members[k].0);
+                let forced = rng.random_range(0..current.len().max(1));
+                current
+                    .iter()
+                    .enumerate()
+                    .map(|(k, x)| {
+                        if k == forced || rng.random::<f64>() < CROSSOVER_RATE {
+                            (a[k] + DIFFERENTIAL_WEIGHT * (b[k] - c[k])).clamp(0.0, 1.0)
+                        } else {
+                            *x
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+// Rebuilds the optimizer by replaying the earlier runs in blocks of `population`, in
+// index order, then asks it for the next generation. The first block is the initial
+// sample. Failed runs count as the worst possible fitness, and a trailing partial block
+// is ignored.
+pub fn propose(
+    strategy: SweepStrategy,
+    objective: &ObjectiveConfig,
+    parameters: &[Parameter],
+    runs: &[SweepRun],
+    population: usize,
+    seed: Option<u64>,
+) -> Result<Vec<SweepPoint>> {
+    if population == 0 {
+        return Err(Error::InvalidParameter(
+            "Evolutionary optimizers need a positive population".to_string(),
+        ));
+    }
+    objective.check_produced(runs)?;
+    let sign = objective.goal()?.sign();
+    let mut ordered: Vec<&SweepRun> = runs.iter().collect();
+    ordered.sort_by_key(|run| run.index);
+    let samples: Vec<(Vec<f64>, f64)> = ordered
+        .iter()
+        .map(|run| {
+            let unit = parameters
+                .iter()
+                .zip(&run.point.values)
+                .map(|(p, (_, v))| p.fraction(*v))
+                .collect();
+            let fitness = objective
+                .value(run)
+                .map_or(f64::NEG_INFINITY, |value| sign * value);
+            (unit, fitness)
+        })
+        .collect();
+    let mut generations = samples.chunks_exact(population);
+    let Some(initial) = generations.next() else {
+        return Ok(Vec::new());
+    };
+
+    let mut rng = crate::utils::performance::seeded_rng(seed);
+    let units = match strategy {
+        SweepStrategy::CmaEs => {
+            let mut cma = CmaEs::new(vec![0.5; parameters.len()], INITIAL_SIGMA);
+            cma.tell(initial);
+            for generation in generations {
+                cma.tell(generation);
+            }
+            cma.ask(population, &mut rng)
+        }
+        SweepStrategy::DifferentialEvolution => {
+            let mut de = DifferentialEvolution::new(initial.to_vec());
+            for generation in generations {
+                de.tell(generation);
+            }
+            de.ask(&mut rng)
+        }
+        other => {
+            return Err(Error::InvalidParameter(format!(
+                "{:?} is not an evolutionary strategy",
+                other
+            )))
+        }
+    };
+    Ok(units
+        .iter()
+        .map(|unit| SweepPoint {
+            values: parameters
+                .iter()
+                .zip(unit)
+                .map(|(p, &u)| (p.name.clone(), p.at(u)))
+                .collect(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sweep::objective::Goal;
+    use crate::sweep::SweepOutput;
+
+    // Rastrigin-like landscape with its global minimum at (1, -0.5) among many local ones.
+    fn rugged(x: &[f64]) -> f64 {
+        let shifted = [x[0] - 1.0, x[1] + 0.5];
+        shifted
+            .iter()
+            .map(|s| s * s + 1.0 - (2.0 * std::f64::consts::PI * s).cos())
+            .sum()
+    }
+
+    fn evaluate(points: Vec<SweepPoint>, offset: usize) -> Vec<SweepRun> {
+        points
+            .into_iter()
+            .enumerate()
+            .map(|(k, point)| {
+                let x: Vec<f64> = point.values.iter().map(|(_, v)| *v).collect();
+                SweepRun {
+                    index: offset + k,
+                    point,
+                    outcome: Ok(SweepOutput {
+                        metrics: vec![("cost".to_string(), rugged(&x))],
+                        results: None,
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_evolutionary_optimizers_escape_local_minima() {
+        let parameters = [
+            Parameter::new("system.parameters.omega_d", -4.0, 4.0),
+            Parameter::new("system.parameters.rabi_freq", -4.0, 4.0),
+        ];
+        let objective = ObjectiveConfig::new("cost", Goal::Minimize);
+        let population = 16;
+        for strategy in [SweepStrategy::CmaEs, SweepStrategy::DifferentialEvolution] {
+            let initial = strategy
+                .sample(&parameters, population, Some(5))
+                .into_iter()
+                .map(|values| SweepPoint {
+                    values: parameters
+                        .iter()
+                        .map(|p| p.name.clone())
+                        .zip(values)
+                        .collect(),
+                })
+                .collect();
+            let mut runs = evaluate(initial, 0);
+            for round in 0..40 {
+                let points = propose(
+                    strategy,
+                    &objective,
+                    &parameters,
+                    &runs,
+                    population,
+                    Some(round),
+                )
+                .unwrap();
+                assert_eq!(points.len(), population);
+                let offset = runs.len();
+                runs.extend(evaluate(points, offset));
+            }
+
+            let best = objective.best(&runs).unwrap().unwrap();
+            let value = objective.value(best).unwrap();
+            assert!(
+                value < 0.05,
+                "{:?}: best cost {} at {}",
+                strategy,
+                value,
+                best.point.label()
+            );
+            let replayed = propose(
+                strategy,
+                &objective,
+                &parameters,
+                &runs,
+                population,
+                Some(99),
+            )
+            .unwrap();
+            assert_eq!(
+                replayed,
+                propose(
+                    strategy,
+                    &objective,
+                    &parameters,
+                    &runs,
+                    population,
+                    Some(99)
+                )
+                .unwrap()
+            );
+        }
+        let runs = evaluate(Vec::new(), 0);
+        assert!(propose(
+            SweepStrategy::CmaEs,
+            &objective,
+            &parameters,
+            &runs,
+            4,
+            None
+        )
+        .unwrap()
+        .is_empty());
+        let runs = evaluate(
+            vec![SweepPoint {
+                values: vec![
+                    (parameters[0].name.clone(), 0.0),
+                    (parameters[1].name.clone(), 0.0),
+                ],
+            }],
+            0,
+        );
+        assert!(propose(SweepStrategy::Grid, &objective, &parameters, &runs, 1, None).is_err());
+    }
+}
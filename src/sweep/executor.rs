@@ -1,12 +1,866 @@
+use crate::core::floquet::{track_eigenvalues, DissipativeFloquetMap, FloquetSpectrum};
+use crate::core::lindblad::LindbladSolver;
+use crate::core::operator_cache::OperatorCache;
+use crate::core::Hamiltonian;
+use crate::data::Checkpoint;
+use crate::data::Config;
+use crate::data::ReportGenerator;
+use crate::gpu::BatchExecutor;
+use crate::simulation::{
+    JobControl, Scheduler, SimulationBuilder, SimulationResults, SimulationRunner, TransportMetrics,
+};
+use crate::sweep::hooks::{derive_metrics, PointHook};
+use crate::sweep::objective::ObjectiveConfig;
+use crate::sweep::parameter::Parameter;
+use crate::sweep::reduction::reduce_results;
+use crate::sweep::refine::{compare_coordinates, RefinementConfig};
+use crate::sweep::strategy::SweepStrategy;
+use crate::sweep::{bayes, evolution};
+use crate::utils::resources::ResourceUsage;
+use crate::utils::{Error, Result};
+use ndarray::Array2;
+use num_complex::Complex64;
+use rayon::prelude::*;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+const DEFAULT_CHECKPOINTS_PER_RUN: f64 = 10.0;
+
+pub(crate) const POINT_COLUMN: &str = "point";
+pub(crate) const CONFIG_COLUMN: &str = "config";
+
+const INITIAL_STATE_PARAMETERS: [(&str, &str); 5] = [
+    ("level", "fock"),
+    ("alpha", "coherent"),
+    ("alpha_phase", "coherent"),
+    ("theta", "bloch"),
+    ("phi", "bloch"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepPoint {
+    pub values: Vec<(String, f64)>,
+}
+
+impl SweepPoint {
+    pub fn label(&self) -> String {
+        self.values
+            .iter()
+            .map(|(name, value)| {
+                let key = name.rsplit('.').next().unwrap_or(name);
+                format!("{}={}", key, value)
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+pub struct SweepOutput {
+    pub metrics: Vec<(String, f64)>,
+    pub results: Option<SimulationResults>,
+}
+
+pub struct SweepRun {
+    pub index: usize,
+    pub point: SweepPoint,
+    pub outcome: std::result::Result<SweepOutput, String>,
+}
+
 pub struct ParameterSweep {
-    #[allow(dead_code)]
     batch_size: usize,
+    gpu_enabled: bool,
+    max_concurrent: Option<usize>,
+    timeout: Option<Duration>,
+    checkpoints: Option<(PathBuf, f64)>,
+    array_task: Option<(usize, usize)>,
+    operators: Arc<OperatorCache>,
+    hooks: Vec<PointHook>,
+    refinement: Option<RefinementConfig>,
+    objectives: Vec<ObjectiveConfig>,
+    parameters: Vec<Parameter>,
+    strategy: SweepStrategy,
+    num_points: usize,
+    seed: Option<u64>,
 }
 
 impl ParameterSweep {
     pub fn new() -> Self {
-        Self { batch_size: 256 }
+        Self {
+            batch_size: 256,
+            gpu_enabled: false,
+            max_concurrent: None,
+            timeout: None,
+            checkpoints: None,
+            array_task: None,
+            operators: Arc::new(OperatorCache::new()),
+            hooks: Vec::new(),
+            refinement: None,
+            objectives: Vec::new(),
+            parameters: Vec::new(),
+            strategy: SweepStrategy::Grid,
+            num_points: 0,
+            seed: None,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let sweep = &config.parameter_sweep;
+        let mut result = Self::new()
+            .strategy(SweepStrategy::parse(&sweep.strategy)?)
+            .num_points(sweep.num_points)
+            .gpu_batch_size(config.gpu.batch_size)
+            .gpu(config.gpu.enabled);
+        if let Some(seed) = sweep.seed {
+            result = result.seed(seed);
+        }
+        if let Some(refinement) = &sweep.refinement {
+            result = result.refinement(refinement.clone());
+        }
+        for objective in sweep.all_objectives() {
+            result = result.objective(objective);
+        }
+        if let Some(dir) = &sweep.checkpoint_dir {
+            let interval = sweep
+                .checkpoint_interval
+                .unwrap_or(config.simulation.duration / DEFAULT_CHECKPOINTS_PER_RUN);
+            result = result.checkpoints(dir, interval);
+        }
+
+        for parameter in sweep.all_parameters() {
+            check_parameter_path(config, &parameter.name)?;
+            let [min, max] = parameter.range[..] else {
+                return Err(Error::Config(format!(
+                    "Sweep range for '{}' must be [min, max], got {:?}",
+                    parameter.name, parameter.range
+                )));
+            };
+            result = result.parameter(parameter.name, min..max);
+        }
+        Ok(result)
+    }
+
+    pub fn parameter(mut self, name: impl Into<String>, range: std::ops::Range<f64>) -> Self {
+        self.parameters
+            .push(Parameter::new(name, range.start, range.end));
+        self
+    }
+
+    pub fn strategy(mut self, strategy: SweepStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    pub fn num_points(mut self, num_points: usize) -> Self {
+        self.num_points = num_points;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
     }
+
+    pub fn refinement(mut self, refinement: RefinementConfig) -> Self {
+        self.refinement = Some(refinement);
+        self
+    }
+
+    pub fn objective(mut self, objective: ObjectiveConfig) -> Self {
+        self.objectives.push(objective);
+        self
+    }
+
+    pub fn objectives(&self) -> &[ObjectiveConfig] {
+        &self.objectives
+    }
+
+    pub fn gpu_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub fn gpu(mut self, enabled: bool) -> Self {
+        self.gpu_enabled = enabled;
+        self
+    }
+
+    pub fn max_concurrent(mut self, max_concurrent: usize) -> Self {
+        self.max_concurrent = Some(max_concurrent);
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn checkpoints(mut self, dir: impl Into<PathBuf>, interval: f64) -> Self {
+        self.checkpoints = Some((dir.into(), interval));
+        self
+    }
+
+    pub fn array_task(mut self, index: usize, size: usize) -> Self {
+        self.array_task = Some((index, size));
+        self
+    }
+
+    pub fn operator_cache(mut self, operators: Arc<OperatorCache>) -> Self {
+        self.operators = operators;
+        self
+    }
+
+    pub fn post_process<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&SweepPoint, &SimulationResults) -> Result<Vec<(String, f64)>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.hooks.push(Arc::new(hook));
+        self
+    }
+
+    pub fn operators(&self) -> &Arc<OperatorCache> {
+        &self.operators
+    }
+
+    pub fn points(&self) -> Result<Vec<SweepPoint>> {
+        if self.parameters.is_empty() {
+            return Err(Error::Config(
+                "Parameter sweep needs at least one parameter".to_string(),
+            ));
+        }
+        if self.num_points == 0 {
+            return Err(Error::Config(
+                "Parameter sweep num_points must be positive".to_string(),
+            ));
+        }
+        if let Some(p) = self
+            .parameters
+            .iter()
+            .find(|p| !(p.min.is_finite() && p.max.is_finite() && p.min <= p.max))
+        {
+            return Err(Error::InvalidParameter(format!(
+                "Sweep range for '{}' must satisfy min <= max, got [{}, {}]",
+                p.name, p.min, p.max
+            )));
+        }
+
+        if let Some((_, interval)) = &self.checkpoints {
+            if !(*interval > 0.0 && interval.is_finite()) {
+                return Err(Error::InvalidParameter(format!(
+                    "Sweep checkpoint interval must be positive, got {}",
+                    interval
+                )));
+            }
+            if !matches!(self.strategy, SweepStrategy::Grid | SweepStrategy::Adaptive)
+                && self.seed.is_none()
+            {
+                return Err(Error::Config(
+                    "Checkpointed sweeps with random sampling need a seed so points can be \
+                     matched on resume"
+                        .to_string(),
+                ));
+            }
+        }
+
+        if matches!(self.strategy, SweepStrategy::Adaptive) {
+            let Some(refinement) = &self.refinement else {
+                return Err(Error::Config(
+                    "Adaptive sweeps need a [parameter_sweep.refinement] section".to_string(),
+                ));
+            };
+            refinement.validate()?;
+        }
+        if matches!(self.strategy, SweepStrategy::BayesOpt) {
+            if self.objectives.is_empty() {
+                return Err(Error::Config(
+                    "Bayesian optimization sweeps need a [parameter_sweep.objective] section"
+                        .to_string(),
+                ));
+            }
+            for objective in &self.objectives {
+                objective.validate()?;
+            }
+        }
+        if matches!(
+            self.strategy,
+            SweepStrategy::CmaEs | SweepStrategy::DifferentialEvolution
+        ) {
+            let [objective] = &self.objectives[..] else {
+                return Err(Error::Config(
+                    "CMA-ES and differential evolution sweeps need exactly one objective"
+                        .to_string(),
+                ));
+            };
+            objective.validate()?;
+            let minimum = match self.strategy {
+                SweepStrategy::CmaEs => 2,
+                _ => 4,
+            };
+            if self.num_points < minimum {
+                return Err(Error::Config(format!(
+                    "{:?} sweeps need a population (num_points) of at least {}, got {}",
+                    self.strategy, minimum, self.num_points
+                )));
+            }
+        }
+
+        Ok(self
+            .strategy
+            .sample(&self.parameters, self.num_points, self.seed)
+            .into_iter()
+            .map(|values| SweepPoint {
+                values: self
+                    .parameters
+                    .iter()
+                    .map(|p| p.name.clone())
+                    .zip(values)
+                    .collect(),
+            })
+            .collect())
+    }
+
+    pub fn assigned_points(&self) -> Result<Vec<(usize, SweepPoint)>> {
+        let points = self.points()?.into_iter().enumerate();
+        let Some((task, size)) = self.array_task else {
+            return Ok(points.collect());
+        };
+        if matches!(
+            self.strategy,
+            SweepStrategy::Adaptive
+                | SweepStrategy::BayesOpt
+                | SweepStrategy::CmaEs
+                | SweepStrategy::DifferentialEvolution
+        ) {
+            return Err(Error::Config(
+                "Adaptive and optimizing sweeps choose points from all earlier results and \
+                 cannot be split into array tasks"
+                    .to_string(),
+            ));
+        }
+        if size == 0 || task >= size {
+            return Err(Error::InvalidParameter(format!(
+                "Array index {} is out of range for an array of size {}",
+                task, size
+            )));
+        }
+        Ok(points.filter(|(index, _)| index % size == task).collect())
+    }
+
+    pub fn config_for(&self, base: &Config, point: &SweepPoint) -> Result<Config> {
+        let mut value =
+            toml::Value::try_from(base).map_err(|e| Error::Serialization(e.to_string()))?;
+        for (name, parameter) in &point.values {
+            set_path(&mut value, name, parameter_value(name, *parameter))?;
+        }
+
+        let mut config: Config = value
+            .try_into()
+            .map_err(|e: toml::de::Error| Error::Config(e.message().to_string()))?;
+        config.simulation.name = format!("{}[{}]", base.simulation.name, point.label());
+        config.parameter_sweep.enabled = false;
+        Ok(config)
+    }
+
+    pub fn run(&self, base: &Config) -> Result<Vec<SweepRun>> {
+        let mut runs = self.run_points(base, self.assigned_points()?)?;
+        for round in 0.. {
+            let points = self.next_points(&runs, round)?;
+            if points.is_empty() {
+                break;
+            }
+            runs.extend(self.run_points(base, points)?);
+        }
+        Ok(self.ordered(runs))
+    }
+
+    pub async fn run_async(
+        &self,
+        base: &Config,
+        control: Arc<JobControl>,
+    ) -> Result<Vec<SweepRun>> {
+        let points = self.assigned_points()?;
+        let mut runs = self.run_points_async(base, points, &control).await?;
+        for round in 0.. {
+            let points = self.next_points(&runs, round)?;
+            if points.is_empty() {
+                break;
+            }
+            runs.extend(self.run_points_async(base, points, &control).await?);
+        }
+        Ok(self.ordered(runs))
+    }
+
+    fn next_points(&self, runs: &[SweepRun], round: usize) -> Result<Vec<(usize, SweepPoint)>> {
+        let points = match (self.strategy, &self.refinement, self.objectives.first()) {
+            (SweepStrategy::Adaptive, Some(refinement), _) if round < refinement.max_rounds => {
+                let budget = refinement.max_points.saturating_sub(runs.len());
+                refinement.refine(runs, budget)?
+            }
+            (SweepStrategy::BayesOpt, _, Some(objective)) => {
+                let remaining = objective.iterations.saturating_sub(round * objective.batch);
+                if remaining == 0 {
+                    return Ok(Vec::new());
+                }
+                let seed = self.seed.map(|seed| seed.wrapping_add(round as u64 + 1));
+                bayes::propose(
+                    &self.objectives,
+                    &self.parameters,
+                    runs,
+                    remaining.min(objective.batch),
+                    seed,
+                )?
+            }
+            (SweepStrategy::CmaEs | SweepStrategy::DifferentialEvolution, _, Some(objective)) => {
+                let remaining = objective
+                    .iterations
+                    .saturating_sub(runs.len().saturating_sub(self.num_points));
+                if remaining == 0 {
+                    return Ok(Vec::new());
+                }
+                let seed = self.seed.map(|seed| seed.wrapping_add(round as u64 + 1));
+                let mut points = evolution::propose(
+                    self.strategy,
+                    objective,
+                    &self.parameters,
+                    runs,
+                    self.num_points,
+                    seed,
+                )?;
+                points.truncate(remaining);
+                points
+            }
+            _ => return Ok(Vec::new()),
+        };
+        if !points.is_empty() {
+            tracing::info!("Round {}: {} new sweep points", round + 1, points.len());
+        }
+        Ok(points
+            .into_iter()
+            .enumerate()
+            .map(|(k, point)| (runs.len() + k, point))
+            .collect())
+    }
+
+    fn ordered(&self, mut runs: Vec<SweepRun>) -> Vec<SweepRun> {
+        if matches!(self.strategy, SweepStrategy::Adaptive) {
+            runs.sort_by(|a, b| {
+                let values = |run: &SweepRun| -> Vec<f64> {
+                    run.point.values.iter().map(|(_, v)| *v).collect()
+                };
+                compare_coordinates(&values(a), &values(b))
+            });
+        }
+        runs
+    }
+
+    fn run_points(&self, base: &Config, points: Vec<(usize, SweepPoint)>) -> Result<Vec<SweepRun>> {
+        let mut jobs = Vec::new();
+        for (index, point) in points {
+            let config = self.config_for(base, &point)?;
+            jobs.push((index, self.point_checkpoint(index)?, point, config));
+        }
+
+        let threads = rayon::current_num_threads();
+        Scheduler::new(threads).run(jobs, |(index, checkpoint, point, config)| {
+            let outcome = prepare_point(checkpoint, &config)
+                .and_then(|plan| match plan {
+                    PointPlan::Done(results) => Ok(*results),
+                    PointPlan::Run(runner) => runner.run(),
+                })
+                .and_then(|results| sweep_output(&config, &point, results, &self.hooks))
+                .map_err(|e| {
+                    tracing::warn!("Sweep point {} failed: {}", point.label(), e);
+                    e.to_string()
+                });
+            SweepRun {
+                index,
+                point,
+                outcome,
+            }
+        })
+    }
+
+    async fn run_points_async(
+        &self,
+        base: &Config,
+        points: Vec<(usize, SweepPoint)>,
+        control: &Arc<JobControl>,
+    ) -> Result<Vec<SweepRun>> {
+        let limit = self
+            .max_concurrent
+            .unwrap_or_else(rayon::current_num_threads);
+        let permits = Arc::new(Semaphore::new(limit.max(1)));
+        let mut tasks = JoinSet::new();
+
+        for (slot, (index, point)) in points.iter().enumerate() {
+            let config = self.config_for(base, point)?;
+            let checkpoint = self.point_checkpoint(*index)?;
+            let permits = permits.clone();
+            let control = control.clone();
+            let hooks = self.hooks.clone();
+            let point = point.clone();
+            let timeout = self.timeout;
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                let outcome = async {
+                    control.check()?;
+                    let runner = match prepare_point(checkpoint, &config)? {
+                        PointPlan::Done(results) => {
+                            return sweep_output(&config, &point, *results, &hooks)
+                        }
+                        PointPlan::Run(runner) => runner.with_control(control),
+                    };
+                    let results = match timeout {
+                        Some(timeout) => runner.run_with_timeout(timeout).await?,
+                        None => runner.run_async().await?,
+                    };
+                    sweep_output(&config, &point, results, &hooks)
+                }
+                .await;
+                (slot, outcome)
+            });
+        }
+
+        let mut outcomes: Vec<Option<Result<SweepOutput>>> = points.iter().map(|_| None).collect();
+        while let Some(joined) = tasks.join_next().await {
+            let (slot, outcome) = joined.map_err(|e| Error::Other(e.into()))?;
+            outcomes[slot] = Some(outcome);
+        }
+
+        Ok(points
+            .into_iter()
+            .zip(outcomes)
+            .map(|((index, point), outcome)| {
+                let outcome = outcome.expect("every sweep task reports an outcome");
+                let outcome = outcome.map_err(|e| {
+                    tracing::warn!("Sweep point {} failed: {}", point.label(), e);
+                    e.to_string()
+                });
+                SweepRun {
+                    index,
+                    point,
+                    outcome,
+                }
+            })
+            .collect())
+    }
+
+    fn point_checkpoint(&self, index: usize) -> Result<Option<(PathBuf, f64)>> {
+        let Some((dir, interval)) = &self.checkpoints else {
+            return Ok(None);
+        };
+        std::fs::create_dir_all(dir)?;
+        Ok(Some((
+            dir.join(format!("point_{:05}.ckpt", index)),
+            *interval,
+        )))
+    }
+}
+
+enum PointPlan {
+    Done(Box<SimulationResults>),
+    Run(Box<SimulationRunner>),
+}
+
+fn prepare_point(checkpoint: Option<(PathBuf, f64)>, config: &Config) -> Result<PointPlan> {
+    let existing = match &checkpoint {
+        Some((path, _)) if path.exists() => Some(Checkpoint::load(path)?),
+        _ => None,
+    };
+    if let Some(Checkpoint {
+        complete: true,
+        results: Some(results),
+        ..
+    }) = existing
+    {
+        tracing::info!("Sweep point {} already complete", config.simulation.name);
+        return Ok(PointPlan::Done(Box::new(results)));
+    }
+
+    let mut runner = SimulationBuilder::from_config(config)?;
+    if let Some((path, interval)) = checkpoint.filter(|_| !runner.is_open()) {
+        runner = runner.with_checkpoints(path, interval)?;
+    }
+    if let Some(partial) = existing {
+        runner = runner.with_resume(partial)?;
+    }
+    Ok(PointPlan::Run(Box::new(runner)))
+}
+
+fn sweep_output(
+    config: &Config,
+    point: &SweepPoint,
+    results: SimulationResults,
+    hooks: &[PointHook],
+) -> Result<SweepOutput> {
+    let mut metrics = reduce_results(config, &results)?;
+    derive_metrics(&config.parameter_sweep.derived, point, &mut metrics)?;
+    for hook in hooks {
+        metrics.extend(hook(point, &results)?);
+    }
+    let resources = results.resources().copied().unwrap_or(ResourceUsage {
+        wall_time: f64::NAN,
+        ..ResourceUsage::default()
+    });
+    metrics.extend(resources.metrics());
+    let sweep = &config.parameter_sweep;
+    let keep = sweep.keep_traces
+        || (sweep.reductions.is_empty() && sweep.derived.is_empty() && hooks.is_empty());
+    Ok(SweepOutput {
+        metrics,
+        results: keep.then_some(results),
+    })
+}
+
+pub fn write_metrics_csv(runs: &[SweepRun], path: &Path) -> Result<()> {
+    write_runs_csv(&runs.iter().collect::<Vec<_>>(), path, None)
+}
+
+pub fn write_array_task_csv(runs: &[SweepRun], config_hash: &str, path: &Path) -> Result<()> {
+    write_runs_csv(&runs.iter().collect::<Vec<_>>(), path, Some(config_hash))
+}
+
+pub fn write_pareto_csv(front: &[&SweepRun], path: &Path) -> Result<()> {
+    write_runs_csv(front, path, None)
+}
+
+// A sweep over a full two-parameter grid gets one heatmap per metric, with the
+// cells spaced evenly between the first and last value of each parameter. Failed
+// points are left blank. Other sweeps write nothing.
+pub fn write_metric_heatmaps(
+    runs: &[SweepRun],
+    generator: &ReportGenerator,
+) -> Result<Vec<PathBuf>> {
+    let Some([(x_name, _), (y_name, _)]) = runs.first().map(|run| &run.point.values[..]) else {
+        return Ok(Vec::new());
+    };
+    let axis = |k: usize| {
+        let mut values: Vec<f64> = runs.iter().map(|run| run.point.values[k].1).collect();
+        values.sort_by(f64::total_cmp);
+        values.dedup();
+        values
+    };
+    let (xs, ys) = (axis(0), axis(1));
+    let Some(output) = runs.iter().find_map(|run| run.outcome.as_ref().ok()) else {
+        return Ok(Vec::new());
+    };
+    if xs.len() * ys.len() != runs.len() {
+        return Ok(Vec::new());
+    }
+
+    let cell = |axis: &[f64], value: f64| axis.partition_point(|&a| a < value);
+    output
+        .metrics
+        .iter()
+        .enumerate()
+        .map(|(m, (name, _))| {
+            let mut values = Array2::from_elem((ys.len(), xs.len()), f64::NAN);
+            for run in runs {
+                if let Ok(output) = &run.outcome {
+                    let (x, y) = (run.point.values[0].1, run.point.values[1].1);
+                    values[[cell(&ys, y), cell(&xs, x)]] = output.metrics[m].1;
+                }
+            }
+            generator.render_heatmap(
+                name,
+                (x_name, y_name),
+                values,
+                cell_range(&xs),
+                cell_range(&ys),
+            )
+        })
+        .collect()
+}
+
+fn cell_range(axis: &[f64]) -> (f64, f64) {
+    let (first, last) = (axis[0], axis[axis.len() - 1]);
+    let half = if axis.len() > 1 {
+        0.5 * (last - first) / (axis.len() - 1) as f64
+    } else {
+        0.5
+    };
+    (first - half, last + half)
+}
+
+fn write_runs_csv(runs: &[&SweepRun], path: &Path, config_hash: Option<&str>) -> Result<()> {
+    let Some(first) = runs.first() else {
+        return Err(Error::InvalidParameter(
+            "No sweep runs to write".to_string(),
+        ));
+    };
+    let metric_names: Vec<&str> = runs
+        .iter()
+        .find_map(|run| run.outcome.as_ref().ok())
+        .map(|output| output.metrics.iter().map(|(n, _)| n.as_str()).collect())
+        .unwrap_or_default();
+
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let header: Vec<&str> = config_hash
+        .map(|_| [POINT_COLUMN, CONFIG_COLUMN])
+        .into_iter()
+        .flatten()
+        .chain(first.point.values.iter().map(|(name, _)| name.as_str()))
+        .chain(metric_names.iter().copied())
+        .collect();
+    writeln!(writer, "{},error", header.join(","))?;
+
+    for run in runs {
+        let mut fields: Vec<String> = config_hash
+            .map(|hash| [run.index.to_string(), hash.to_string()])
+            .into_iter()
+            .flatten()
+            .chain(run.point.values.iter().map(|(_, v)| v.to_string()))
+            .collect();
+        match &run.outcome {
+            Ok(output) => {
+                fields.extend(output.metrics.iter().map(|(_, v)| v.to_string()));
+                fields.push(String::new());
+            }
+            Err(e) => {
+                fields.extend(metric_names.iter().map(|_| "NaN".to_string()));
+                fields.push(format!("\"{}\"", e.replace('"', "'").replace('\n', " ")));
+            }
+        }
+        writeln!(writer, "{}", fields.join(","))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+impl ParameterSweep {
+    pub fn floquet_spectra<H, F>(
+        &self,
+        period: f64,
+        num_steps: usize,
+        build: F,
+    ) -> Result<Vec<(SweepPoint, FloquetSpectrum)>>
+    where
+        H: Hamiltonian,
+        F: Fn(&SweepPoint) -> Result<H>,
+    {
+        let points = self.points()?;
+        let hamiltonians = points.iter().map(&build).collect::<Result<Vec<H>>>()?;
+        let refs: Vec<&dyn Hamiltonian> =
+            hamiltonians.iter().map(|h| h as &dyn Hamiltonian).collect();
+
+        let spectra = BatchExecutor::new(self.batch_size, self.gpu_enabled)
+            .floquet_spectra(&refs, period, num_steps)?;
+        Ok(points.into_iter().zip(spectra).collect())
+    }
+
+    pub fn floquet_map_tracks<F>(
+        &self,
+        period: f64,
+        num_steps: usize,
+        count: usize,
+        build: F,
+    ) -> Result<Vec<(SweepPoint, Vec<Complex64>)>>
+    where
+        F: Fn(&SweepPoint) -> Result<LindbladSolver> + Sync,
+    {
+        let points = self.points()?;
+        let maps = points
+            .par_iter()
+            .map(|point| DissipativeFloquetMap::compute(&build(point)?, period, num_steps, count))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(points.into_iter().zip(track_eigenvalues(&maps)).collect())
+    }
+
+    pub fn transport_map<F>(&self, threshold: f64, build: F) -> Result<Vec<SweepRun>>
+    where
+        F: Fn(&SweepPoint) -> Result<SimulationRunner> + Sync,
+    {
+        let mut runs: Vec<SweepRun> = self
+            .assigned_points()?
+            .into_par_iter()
+            .map(|(index, point)| {
+                let outcome = build(&point)
+                    .and_then(|runner| runner.run())
+                    .and_then(|results| {
+                        Ok(SweepOutput {
+                            metrics: TransportMetrics::from_results(&results, threshold)?.metrics(),
+                            results: None,
+                        })
+                    })
+                    .map_err(|e| e.to_string());
+                SweepRun {
+                    index,
+                    point,
+                    outcome,
+                }
+            })
+            .collect();
+        runs.sort_by_key(|run| run.index);
+        Ok(runs)
+    }
+}
+
+pub fn write_eigenvalue_tracks_csv(
+    tracks: &[(SweepPoint, Vec<Complex64>)],
+    path: &Path,
+) -> Result<()> {
+    let Some((first, eigenvalues)) = tracks.first() else {
+        return Err(Error::InvalidParameter(
+            "No eigenvalue tracks to write".to_string(),
+        ));
+    };
+
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut header: Vec<String> = first.values.iter().map(|(n, _)| n.clone()).collect();
+    for k in 0..eigenvalues.len() {
+        header.extend([format!("re{}", k), format!("im{}", k), format!("abs{}", k)]);
+    }
+    writeln!(writer, "{}", header.join(","))?;
+
+    for (point, eigenvalues) in tracks {
+        let fields: Vec<String> = point
+            .values
+            .iter()
+            .map(|(_, v)| v.to_string())
+            .chain(
+                eigenvalues
+                    .iter()
+                    .flat_map(|e| [e.re, e.im, e.norm()].map(|x| x.to_string())),
+            )
+            .collect();
+        writeln!(writer, "{}", fields.join(","))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn write_quasi_energies_csv(
+    spectra: &[(SweepPoint, FloquetSpectrum)],
+    path: &Path,
+) -> Result<()> {
+    let Some((first, spectrum)) = spectra.first() else {
+        return Err(Error::InvalidParameter(
+            "No Floquet spectra to write".to_string(),
+        ));
+    };
+
+    let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+    let mut header: Vec<String> = first.values.iter().map(|(n, _)| n.clone()).collect();
+    header.extend((0..spectrum.num_levels()).map(|k| format!("e{}", k)));
+    writeln!(writer, "{}", header.join(","))?;
+
+    for (point, spectrum) in spectra {
+        let fields: Vec<String> = point
+            .values
+            .iter()
+            .map(|(_, v)| v.to_string())
+            .chain(spectrum.quasi_energies.iter().map(|e| e.to_string()))
+            .collect();
+        writeln!(writer, "{}", fields.join(","))?;
+    }
+    writer.flush()?;
+    Ok(())
 }
 
 impl Default for ParameterSweep {
@@ -14,3 +868,503 @@ impl Default for ParameterSweep {
         Self::new()
     }
 }
+
+fn check_parameter_path(config: &Config, name: &str) -> Result<()> {
+    if let Some(key) = name.strip_prefix("initial_state.") {
+        let Some((_, kind)) = INITIAL_STATE_PARAMETERS.iter().find(|(k, _)| *k == key) else {
+            return Err(Error::Config(format!(
+                "Cannot sweep '{}', initial-state parameters are {}",
+                name,
+                INITIAL_STATE_PARAMETERS
+                    .iter()
+                    .map(|(k, _)| format!("initial_state.{}", k))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        };
+        if config.initial_state.kind != *kind {
+            return Err(Error::Config(format!(
+                "Sweeping '{}' requires initial_state.kind = \"{}\", got \"{}\"",
+                name, kind, config.initial_state.kind
+            )));
+        }
+        return Ok(());
+    }
+    if name.starts_with("system.parameters.") {
+        return Ok(());
+    }
+    Err(Error::Config(format!(
+        "Cannot sweep '{}', expected a system.parameters.* or initial_state.* key",
+        name
+    )))
+}
+
+fn parameter_value(name: &str, value: f64) -> toml::Value {
+    if name == "initial_state.level" {
+        toml::Value::Integer(value.round() as i64)
+    } else {
+        toml::Value::Float(value)
+    }
+}
+
+fn set_path(value: &mut toml::Value, path: &str, new_value: toml::Value) -> Result<()> {
+    let mut current = value;
+    let mut keys = path.split('.').peekable();
+    while let Some(key) = keys.next() {
+        let table = current.as_table_mut().ok_or_else(|| {
+            Error::Config(format!("Sweep parameter '{}' does not name a table", path))
+        })?;
+        if keys.peek().is_none() {
+            table.insert(key.to_string(), new_value);
+            return Ok(());
+        }
+        current = table
+            .entry(key.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn coherent_config() -> Config {
+        let mut config = Config::generate_template("driven_tls").unwrap();
+        config.system.hilbert_dim = 2;
+        config.initial_state.kind = "coherent".to_string();
+        config
+    }
+
+    #[test]
+    fn test_sweep_over_initial_state_and_hamiltonian() {
+        let mut config = coherent_config();
+        config.parameter_sweep.enabled = true;
+        config.parameter_sweep.num_points = 3;
+        config.parameter_sweep.parameter = "initial_state.alpha".to_string();
+        config.parameter_sweep.range = vec![0.0, 2.0];
+        config.parameter_sweep.parameters = vec![crate::data::config::SweepParameterConfig {
+            name: "system.parameters.omega_d".to_string(),
+            range: vec![4.5, 5.5],
+        }];
+        config.validate().unwrap();
+
+        let sweep = ParameterSweep::from_config(&config).unwrap();
+        let points = sweep.points().unwrap();
+        assert_eq!(points.len(), 9);
+
+        let point = &points[7];
+        assert_eq!(point.label(), "alpha=2,omega_d=5");
+        let swept = sweep.config_for(&config, point).unwrap();
+        assert_eq!(swept.initial_state.alpha, 2.0);
+        assert_eq!(swept.system.parameters.get("omega_d"), Some(5.0));
+        assert_eq!(swept.simulation.name, "driven_tls[alpha=2,omega_d=5]");
+        assert!(!swept.parameter_sweep.enabled);
+
+        for point in &points {
+            let swept = sweep.config_for(&config, point).unwrap();
+            sweep.operators().observables_for(&swept).unwrap();
+        }
+        let misses = sweep.operators().misses();
+        assert_eq!(misses, config.observables.list.len());
+        assert_eq!(sweep.operators().hits(), 8 * misses);
+    }
+
+    #[test]
+    fn test_sweep_reuses_completed_checkpoints() {
+        let dir = std::env::temp_dir().join(format!("chronophoton_sweep_{}", std::process::id()));
+        let config = Config::generate_template("driven_tls").unwrap();
+        let sweep = ParameterSweep::new()
+            .parameter("system.parameters.omega_d", 4.5..5.5)
+            .num_points(2)
+            .checkpoints(&dir, 1.0);
+
+        let mut results = SimulationResults::new();
+        results.add_observable("p1", 0.0, num_complex::Complex64::new(0.25, 0.0));
+        let (path, _) = sweep.point_checkpoint(0).unwrap().unwrap();
+        Checkpoint {
+            time: config.simulation.duration,
+            step: 100,
+            amplitudes: vec![num_complex::Complex64::new(1.0, 0.0); 2],
+            results: Some(results),
+            complete: true,
+        }
+        .save(&path)
+        .unwrap();
+
+        let runs = sweep.run(&config).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        let output = runs[0].outcome.as_ref().unwrap();
+        assert!(output.results.as_ref().unwrap().series("p1").is_some());
+        let fresh = runs[1].outcome.as_ref().unwrap();
+        assert!(fresh
+            .results
+            .as_ref()
+            .unwrap()
+            .series("population")
+            .is_some());
+        assert!(ParameterSweep::new()
+            .parameter("system.parameters.omega_d", 4.5..5.5)
+            .num_points(2)
+            .strategy(SweepStrategy::Random)
+            .checkpoints(&dir, 1.0)
+            .points()
+            .is_err());
+    }
+
+    #[test]
+    fn test_post_processing_replaces_traces_with_scalars() {
+        let dir = std::env::temp_dir().join(format!("chronophoton_hooks_{}", std::process::id()));
+        let mut config = Config::generate_template("driven_tls").unwrap();
+        config.parameter_sweep.reductions = vec![crate::sweep::ReductionConfig {
+            observable: "p1".to_string(),
+            reduction: "max".to_string(),
+            window: 1.0,
+            frequency: None,
+        }];
+        config.parameter_sweep.derived = vec![crate::sweep::DerivedMetricConfig {
+            name: "weighted".to_string(),
+            expr: "2 * p1_max / omega_d".to_string(),
+        }];
+        let sweep = ParameterSweep::new()
+            .parameter("system.parameters.omega_d", 4.0..5.0)
+            .num_points(2)
+            .checkpoints(&dir, 1.0)
+            .post_process(|point, results| {
+                Ok(vec![(
+                    "samples".to_string(),
+                    results.times().len() as f64 + point.values[0].1,
+                )])
+            });
+
+        for index in 0..2 {
+            let mut results = SimulationResults::new();
+            results.add_observable("p1", 0.0, Complex64::new(0.0, 0.0));
+            results.add_observable("p1", 1.0, Complex64::new(0.5, 0.0));
+            let (path, _) = sweep.point_checkpoint(index).unwrap().unwrap();
+            Checkpoint {
+                time: config.simulation.duration,
+                step: 500,
+                amplitudes: vec![Complex64::new(1.0, 0.0); 2],
+                results: Some(results),
+                complete: true,
+            }
+            .save(&path)
+            .unwrap();
+        }
+
+        let runs = sweep.run(&config).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+        for (run, omega_d) in runs.iter().zip([4.0, 5.0]) {
+            let output = run.outcome.as_ref().unwrap();
+            assert!(output.results.is_none());
+            assert_eq!(
+                output.metrics[..3],
+                [
+                    ("p1_max".to_string(), 0.5),
+                    ("weighted".to_string(), 1.0 / omega_d),
+                    ("samples".to_string(), 2.0 + omega_d),
+                ]
+            );
+        }
+
+        config.parameter_sweep.reductions.clear();
+        config.parameter_sweep.derived[0].expr = "2 * rabi_freq".to_string();
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("'rabi_freq'"), "{}", err);
+    }
+
+    #[test]
+    fn test_sweep_rejects_mismatched_initial_state_kind() {
+        let mut config = coherent_config();
+        config.parameter_sweep.parameter = "initial_state.theta".to_string();
+        config.parameter_sweep.range = vec![0.0, 3.0];
+        let err = ParameterSweep::from_config(&config).err().unwrap();
+        assert!(err.to_string().contains("bloch"));
+
+        config.parameter_sweep.parameter = "initial_state.beta".to_string();
+        assert!(ParameterSweep::from_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_write_metrics_csv() {
+        let point = |alpha: f64| SweepPoint {
+            values: vec![("initial_state.alpha".to_string(), alpha)],
+        };
+        let runs = vec![
+            SweepRun {
+                index: 0,
+                point: point(0.0),
+                outcome: Ok(SweepOutput {
+                    metrics: vec![("n_final".to_string(), 0.5)],
+                    results: None,
+                }),
+            },
+            SweepRun {
+                index: 1,
+                point: point(1.0),
+                outcome: Err("diverged".to_string()),
+            },
+        ];
+        let path = std::env::temp_dir().join("chronophoton_sweep_metrics.csv");
+        write_metrics_csv(&runs, &path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            contents,
+            "initial_state.alpha,n_final,error\n0,0.5,\n1,NaN,\"diverged\"\n"
+        );
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_grid_sweeps_write_metric_heatmaps() {
+        let runs: Vec<SweepRun> = (0..6)
+            .map(|index| SweepRun {
+                index,
+                point: SweepPoint {
+                    values: vec![
+                        ("system.parameters.g".to_string(), (index % 3) as f64),
+                        ("cumulant.kappa".to_string(), 0.1 * (index / 3) as f64),
+                    ],
+                },
+                outcome: if index == 4 {
+                    Err("diverged".to_string())
+                } else {
+                    Ok(SweepOutput {
+                        metrics: vec![("n_final".to_string(), index as f64)],
+                        results: None,
+                    })
+                },
+            })
+            .collect();
+        let dir = std::env::temp_dir().join("chronophoton_sweep_heatmaps");
+        let generator = ReportGenerator::new(
+            &dir,
+            crate::data::ReportStyle {
+                format: crate::data::report::FigureFormat::Svg,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let written = write_metric_heatmaps(&runs, &generator).unwrap();
+        assert_eq!(written, vec![dir.join("heatmap_n_final.svg")]);
+        assert!(written[0].exists());
+        assert!(write_metric_heatmaps(&runs[..5], &generator)
+            .unwrap()
+            .is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_array_tasks_partition_points() {
+        let sweep = || {
+            ParameterSweep::new()
+                .parameter("system.parameters.omega_d", 4.0..5.0)
+                .num_points(5)
+        };
+        let indices = |task: usize| -> Vec<usize> {
+            let points = sweep().array_task(task, 2).assigned_points().unwrap();
+            points.into_iter().map(|(index, _)| index).collect()
+        };
+        assert_eq!(indices(0), [0, 2, 4]);
+        assert_eq!(indices(1), [1, 3]);
+        assert!(sweep().array_task(2, 2).assigned_points().is_err());
+    }
+
+    #[test]
+    fn test_adaptive_sweeps_need_a_refinement_metric() {
+        let sweep = || {
+            ParameterSweep::new()
+                .parameter("system.parameters.omega_d", 4.0..5.0)
+                .num_points(5)
+                .strategy(SweepStrategy::Adaptive)
+        };
+        assert!(sweep().points().is_err());
+        let refined = sweep().refinement(RefinementConfig::new("p1_max"));
+        assert_eq!(refined.points().unwrap().len(), 5);
+        assert!(refined.array_task(0, 2).assigned_points().is_err());
+
+        let mut config = Config::generate_template("driven_tls").unwrap();
+        config.parameter_sweep.refinement = Some(RefinementConfig::new("p1_max"));
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("'p1_max'"), "{}", err);
+    }
+
+    #[test]
+    fn test_bayes_opt_needs_an_objective() {
+        let sweep = ParameterSweep::new()
+            .parameter("system.parameters.omega_d", 4.0..5.0)
+            .num_points(6)
+            .strategy(SweepStrategy::BayesOpt)
+            .seed(1);
+        assert!(sweep.points().is_err());
+        let sweep = sweep.objective(ObjectiveConfig::new(
+            "population_max",
+            crate::sweep::Goal::Maximize,
+        ));
+        assert_eq!(sweep.points().unwrap().len(), 6);
+
+        let mut config = Config::generate_template("driven_tls").unwrap();
+        config.parameter_sweep.enabled = true;
+        config.parameter_sweep.strategy = "bayes_opt".to_string();
+        config.parameter_sweep.parameter = "system.parameters.omega_d".to_string();
+        config.parameter_sweep.range = vec![4.0, 5.0];
+        config.parameter_sweep.num_points = 6;
+        config.parameter_sweep.reductions = vec![crate::sweep::ReductionConfig {
+            observable: "population".to_string(),
+            reduction: "max".to_string(),
+            window: 1.0,
+            frequency: None,
+        }];
+        let mut objective = ObjectiveConfig::new("population_max", crate::sweep::Goal::Maximize);
+        objective.goal = "largest".to_string();
+        config.parameter_sweep.objective = Some(objective);
+        assert!(config.validate().is_err());
+        config.parameter_sweep.objective.as_mut().unwrap().goal = "maximize".to_string();
+        config.validate().unwrap();
+
+        config.parameter_sweep.objectives = vec![ObjectiveConfig::new(
+            "population_max",
+            crate::sweep::Goal::Minimize,
+        )];
+        assert!(config.validate().is_err());
+        config.parameter_sweep.derived = vec![crate::sweep::DerivedMetricConfig {
+            name: "energy".to_string(),
+            expr: "omega_d^2".to_string(),
+        }];
+        config.parameter_sweep.objectives[0].metric = "energy".to_string();
+        config.validate().unwrap();
+        let sweep = ParameterSweep::from_config(&config).unwrap();
+        assert_eq!(sweep.objectives().len(), 2);
+        assert_eq!(sweep.objectives()[1].metric, "energy");
+    }
+
+    #[test]
+    fn test_evolutionary_sweeps_run_in_generations() {
+        let sweep = ParameterSweep::new()
+            .parameter("system.parameters.omega_d", 4.0..5.0)
+            .num_points(6)
+            .strategy(SweepStrategy::CmaEs)
+            .seed(2);
+        assert!(sweep.points().is_err());
+        let objective = ObjectiveConfig {
+            iterations: 10,
+            ..ObjectiveConfig::new("population_max", crate::sweep::Goal::Maximize)
+        };
+        let sweep = sweep.objective(objective.clone());
+        let evaluate = |points: Vec<(usize, SweepPoint)>| -> Vec<SweepRun> {
+            points
+                .into_iter()
+                .map(|(index, point)| SweepRun {
+                    index,
+                    outcome: Ok(SweepOutput {
+                        metrics: vec![("population_max".to_string(), point.values[0].1)],
+                        results: None,
+                    }),
+                    point,
+                })
+                .collect()
+        };
+        let mut runs = evaluate(sweep.assigned_points().unwrap());
+        let first = sweep.next_points(&runs, 0).unwrap();
+        assert_eq!(first.len(), 6);
+        assert_eq!(first[0].0, 6);
+        runs.extend(evaluate(first));
+        assert_eq!(sweep.next_points(&runs, 1).unwrap().len(), 4);
+        assert!(sweep.array_task(0, 2).assigned_points().is_err());
+
+        let de = ParameterSweep::new()
+            .parameter("system.parameters.omega_d", 4.0..5.0)
+            .num_points(3)
+            .strategy(SweepStrategy::DifferentialEvolution)
+            .objective(objective.clone());
+        assert!(de.points().is_err());
+        let pair = de
+            .num_points(8)
+            .objective(ObjectiveConfig::new("n_max", crate::sweep::Goal::Minimize));
+        assert!(pair.points().is_err());
+    }
+
+    #[test]
+    fn test_floquet_map_tracks_follow_branches() {
+        use crate::core::hamiltonian::TimeIndependentHamiltonian;
+        use crate::core::lindblad::LindbladOperator;
+
+        let sweep = ParameterSweep::new()
+            .parameter("omega", 1.0..2.5)
+            .num_points(4);
+        let tracks = sweep
+            .floquet_map_tracks(1.0, 200, 4, |point| {
+                let mut h = ndarray::Array2::zeros((2, 2));
+                h[[1, 1]] = Complex64::new(point.values[0].1, 0.0);
+                LindbladSolver::new(
+                    Box::new(TimeIndependentHamiltonian::new(h)),
+                    vec![LindbladOperator::annihilation(2, 0.3)?],
+                )
+            })
+            .unwrap();
+
+        assert_eq!(tracks.len(), 4);
+        let upper = tracks[0].1[1].im > 0.0;
+        for (point, eigenvalues) in &tracks {
+            let coherence = eigenvalues[1];
+            assert!((coherence.arg().abs() - point.values[0].1).abs() < 1e-6);
+            assert_eq!(coherence.im > 0.0, upper);
+        }
+
+        let path = std::env::temp_dir().join("chronophoton_floquet_map_tracks.csv");
+        write_eigenvalue_tracks_csv(&tracks, &path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(contents.starts_with("omega,re0,im0,abs0,re1,"));
+        assert_eq!(contents.lines().count(), 5);
+    }
+
+    #[test]
+    fn test_transport_map_finds_resonant_drive() {
+        use crate::core::lattice::Lattice;
+        use crate::simulation::Transport;
+
+        let lattice = Lattice::chain(3, 1.0).unwrap();
+        let sweep = ParameterSweep::new()
+            .parameter("omega_d", 5.0..8.0)
+            .num_points(2);
+        let runs = sweep
+            .transport_map(0.5, |point| {
+                SimulationBuilder::new()
+                    .hamiltonian(lattice.hamiltonian(5.0))
+                    .transport(Transport::new(0, 2).drive(0.05, point.values[0].1))
+                    .duration(20.0)
+                    .timestep(0.01)
+                    .build()
+            })
+            .unwrap();
+
+        let peak = |run: &SweepRun| run.outcome.as_ref().unwrap().metrics[0].1;
+        assert_eq!(runs.len(), 2);
+        assert!(peak(&runs[0]) > 10.0 * peak(&runs[1]));
+        assert!(runs[0].outcome.as_ref().unwrap().metrics[2].1.is_nan());
+    }
+
+    #[test]
+    fn test_floquet_spectra_over_drive_strength() {
+        use crate::core::systems::DrivenTLS;
+
+        let sweep = ParameterSweep::new()
+            .parameter("rabi_freq", 0.0..0.4)
+            .num_points(3)
+            .gpu_batch_size(2);
+        let period = 2.0 * std::f64::consts::PI / 5.0;
+        let spectra = sweep
+            .floquet_spectra(period, 400, |point| {
+                Ok(DrivenTLS::new(5.0, 5.0, point.values[0].1))
+            })
+            .unwrap();
+
+        assert_eq!(spectra.len(), 3);
+        let spacing = |k: usize| spectra[k].1.level_spacing(0).unwrap();
+        assert!(spacing(0) < 1e-6 || (spacing(0) - 5.0).abs() < 1e-6);
+        assert!((spacing(1) - spacing(2)).abs() > 1e-3);
+    }
+}
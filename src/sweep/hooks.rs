@@ -0,0 +1,312 @@
+use crate::simulation::SimulationResults;
+use crate::sweep::SweepPoint;
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+pub type PointHook =
+    Arc<dyn Fn(&SweepPoint, &SimulationResults) -> Result<Vec<(String, f64)>> + Send + Sync>;
+
+const FUNCTIONS: [&str; 9] = [
+    "abs", "sqrt", "exp", "ln", "log10", "sin", "cos", "tan", "atan",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DerivedMetricConfig {
+    pub name: String,
+    pub expr: String,
+}
+
+impl DerivedMetricConfig {
+    pub fn expression(&self) -> Result<Expression> {
+        Expression::parse(&self.expr).map_err(|e| match e {
+            Error::Config(message) => {
+                Error::Config(format!("Derived metric '{}': {}", self.name, message))
+            }
+            other => other,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Number(f64),
+    Variable(String),
+    Negate(Box<Expression>),
+    Binary(char, Box<Expression>, Box<Expression>),
+    Call(String, Box<Expression>),
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut parser = Parser {
+            chars: source.chars().collect(),
+            pos: 0,
+        };
+        let expression = parser.sum()?;
+        parser.skip_whitespace();
+        if parser.pos < parser.chars.len() {
+            return Err(Error::Config(format!(
+                "Unexpected '{}' at position {} of '{}'",
+                parser.chars[parser.pos], parser.pos, source
+            )));
+        }
+        Ok(expression)
+    }
+
+    pub fn variables(&self) -> Vec<&str> {
+        match self {
+            Expression::Number(_) => Vec::new(),
+            Expression::Variable(name) => vec![name.as_str()],
+            Expression::Negate(inner) | Expression::Call(_, inner) => inner.variables(),
+            Expression::Binary(_, lhs, rhs) => {
+                let mut names = lhs.variables();
+                names.extend(rhs.variables());
+                names
+            }
+        }
+    }
+
+    pub fn evaluate(&self, lookup: &dyn Fn(&str) -> Option<f64>) -> Result<f64> {
+        Ok(match self {
+            Expression::Number(value) => *value,
+            Expression::Variable(name) => lookup(name).ok_or_else(|| {
+                Error::Config(format!("Unknown variable '{}' in derived metric", name))
+            })?,
+            Expression::Negate(inner) => -inner.evaluate(lookup)?,
+            Expression::Binary(op, lhs, rhs) => {
+                let (a, b) = (lhs.evaluate(lookup)?, rhs.evaluate(lookup)?);
+                match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    '/' => a / b,
+                    _ => a.powf(b),
+                }
+            }
+            Expression::Call(function, inner) => {
+                let x = inner.evaluate(lookup)?;
+                match function.as_str() {
+                    "abs" => x.abs(),
+                    "sqrt" => x.sqrt(),
+                    "exp" => x.exp(),
+                    "ln" => x.ln(),
+                    "log10" => x.log10(),
+                    "sin" => x.sin(),
+                    "cos" => x.cos(),
+                    "tan" => x.tan(),
+                    _ => x.atan(),
+                }
+            }
+        })
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn skip_whitespace(&mut self) {
+        while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.pos).copied()
+    }
+
+    fn sum(&mut self) -> Result<Expression> {
+        let mut lhs = self.product()?;
+        while let Some(op @ ('+' | '-')) = self.peek() {
+            self.pos += 1;
+            lhs = Expression::Binary(op, Box::new(lhs), Box::new(self.product()?));
+        }
+        Ok(lhs)
+    }
+
+    fn product(&mut self) -> Result<Expression> {
+        let mut lhs = self.unary()?;
+        while let Some(op @ ('*' | '/')) = self.peek() {
+            self.pos += 1;
+            lhs = Expression::Binary(op, Box::new(lhs), Box::new(self.unary()?));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expression> {
+        if self.peek() == Some('-') {
+            self.pos += 1;
+            return Ok(Expression::Negate(Box::new(self.unary()?)));
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Expression> {
+        let base = self.atom()?;
+        if self.peek() == Some('^') {
+            self.pos += 1;
+            return Ok(Expression::Binary(
+                '^',
+                Box::new(base),
+                Box::new(self.unary()?),
+            ));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<Expression> {
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let inner = self.sum()?;
+                self.expect(')')?;
+                Ok(inner)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let start = self.pos;
+                while let Some(&c) = self.chars.get(self.pos) {
+                    let exponent_sign =
+                        matches!(c, '+' | '-') && matches!(self.chars[self.pos - 1], 'e' | 'E');
+                    if !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E') || exponent_sign) {
+                        break;
+                    }
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                text.parse()
+                    .map(Expression::Number)
+                    .map_err(|_| Error::Config(format!("Invalid number '{}'", text)))
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let start = self.pos;
+                while self
+                    .chars
+                    .get(self.pos)
+                    .is_some_and(|c| c.is_alphanumeric() || *c == '_' || *c == '.')
+                {
+                    self.pos += 1;
+                }
+                let name: String = self.chars[start..self.pos].iter().collect();
+                if self.peek() != Some('(') {
+                    return Ok(match name.as_str() {
+                        "pi" => Expression::Number(std::f64::consts::PI),
+                        _ => Expression::Variable(name),
+                    });
+                }
+                if !FUNCTIONS.contains(&name.as_str()) {
+                    return Err(Error::Config(format!(
+                        "Unknown function '{}', expected one of {}",
+                        name,
+                        FUNCTIONS.join(", ")
+                    )));
+                }
+                self.pos += 1;
+                let argument = self.sum()?;
+                self.expect(')')?;
+                Ok(Expression::Call(name, Box::new(argument)))
+            }
+            Some(c) => Err(Error::Config(format!(
+                "Unexpected '{}' at position {}",
+                c, self.pos
+            ))),
+            None => Err(Error::Config("Expression ends unexpectedly".to_string())),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(Error::Config(format!(
+                "Expected '{}' at position {}",
+                expected, self.pos
+            )))
+        }
+    }
+}
+
+pub fn point_variable(point: &SweepPoint, name: &str) -> Option<f64> {
+    point
+        .values
+        .iter()
+        .find(|(path, _)| path == name || path.rsplit('.').next() == Some(name))
+        .map(|(_, value)| *value)
+}
+
+pub fn derive_metrics(
+    derived: &[DerivedMetricConfig],
+    point: &SweepPoint,
+    metrics: &mut Vec<(String, f64)>,
+) -> Result<()> {
+    for metric in derived {
+        let expression = metric.expression()?;
+        let value = expression.evaluate(&|name| {
+            metrics
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| *v)
+                .or_else(|| point_variable(point, name))
+        })?;
+        metrics.push((metric.name.clone(), value));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_expression_precedence_and_functions() {
+        let lookup = |name: &str| match name {
+            "n_final" => Some(4.0),
+            "system.parameters.g" => Some(0.5),
+            _ => None,
+        };
+        let eval = |s: &str| Expression::parse(s).unwrap().evaluate(&lookup).unwrap();
+        assert_relative_eq!(eval("1 + 2 * 3 ^ 2"), 19.0);
+        assert_relative_eq!(eval("-2 ^ 2"), -4.0);
+        assert_relative_eq!(eval("sqrt(n_final) / system.parameters.g"), 4.0);
+        assert_relative_eq!(eval("(1 - n_final) * 2.5e-1"), -0.75);
+        assert_relative_eq!(eval("cos(pi)"), -1.0);
+        assert_eq!(
+            Expression::parse("n_final * g").unwrap().variables(),
+            ["n_final", "g"]
+        );
+
+        assert!(Expression::parse("1 +").is_err());
+        assert!(Expression::parse("floor(2)").is_err());
+        assert!(Expression::parse("(1 + 2").is_err());
+        assert!(Expression::parse("missing")
+            .unwrap()
+            .evaluate(&lookup)
+            .is_err());
+    }
+
+    #[test]
+    fn test_derived_metrics_see_earlier_metrics_and_parameters() {
+        let point = SweepPoint {
+            values: vec![("system.parameters.omega_d".to_string(), 2.0)],
+        };
+        let derived = [
+            DerivedMetricConfig {
+                name: "scaled".to_string(),
+                expr: "p1_max * omega_d".to_string(),
+            },
+            DerivedMetricConfig {
+                name: "shifted".to_string(),
+                expr: "scaled - 1".to_string(),
+            },
+        ];
+        let mut metrics = vec![("p1_max".to_string(), 0.25)];
+        derive_metrics(&derived, &point, &mut metrics).unwrap();
+        assert_eq!(metrics[1], ("scaled".to_string(), 0.5));
+        assert_eq!(metrics[2], ("shifted".to_string(), -0.5));
+    }
+}
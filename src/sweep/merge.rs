@@ -0,0 +1,388 @@
+use crate::data::hdf5::{Datatype, Hdf5Writer, ObjectRef};
+use crate::sweep::executor::{CONFIG_COLUMN, POINT_COLUMN};
+use crate::utils::{Error, Result};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    pub name: String,
+    pub count: usize,
+    pub mean: f64,
+    pub std: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct MergedSweep {
+    pub config_hash: String,
+    pub columns: Vec<String>,
+    rows: Vec<(usize, String)>,
+}
+
+impl MergedSweep {
+    pub fn load(inputs: &[PathBuf]) -> Result<Self> {
+        let mut merged: Option<MergedSweep> = None;
+        for input in inputs {
+            let contents = std::fs::read_to_string(input)?;
+            let mut lines = contents.lines();
+            let header = lines.next().unwrap_or_default();
+            let Some(columns) = header
+                .strip_prefix(&format!("{},{},", POINT_COLUMN, CONFIG_COLUMN))
+                .map(|rest| rest.split(',').map(str::to_string).collect::<Vec<_>>())
+            else {
+                return Err(Error::Config(format!(
+                    "{:?} is not an array-task output, expected '{}' and '{}' columns first",
+                    input, POINT_COLUMN, CONFIG_COLUMN
+                )));
+            };
+
+            for line in lines.filter(|l| !l.is_empty()) {
+                let mut fields = line.splitn(3, ',');
+                let (Some(index), Some(hash), Some(row)) =
+                    (fields.next(), fields.next(), fields.next())
+                else {
+                    return Err(Error::Config(format!(
+                        "Malformed row '{}' in {:?}",
+                        line, input
+                    )));
+                };
+                let index: usize = index.parse().map_err(|_| {
+                    Error::Config(format!("Invalid point index '{}' in {:?}", index, input))
+                })?;
+
+                let merged = merged.get_or_insert_with(|| MergedSweep {
+                    config_hash: hash.to_string(),
+                    columns: columns.clone(),
+                    rows: Vec::new(),
+                });
+                if merged.config_hash != hash {
+                    return Err(Error::Config(format!(
+                        "{:?} was produced by config {}, other outputs by config {}",
+                        input, hash, merged.config_hash
+                    )));
+                }
+                if merged.columns != columns {
+                    return Err(Error::Config(format!(
+                        "Columns of {:?} do not match the other array-task outputs",
+                        input
+                    )));
+                }
+                merged.rows.push((index, row.to_string()));
+            }
+        }
+
+        let Some(mut merged) = merged else {
+            return Err(Error::InvalidParameter(
+                "No array-task rows to merge".to_string(),
+            ));
+        };
+        merged.rows.sort_by_key(|(index, _)| *index);
+        if let Some(pair) = merged.rows.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(Error::Config(format!(
+                "Sweep point {} appears in more than one array-task output",
+                pair[0].0
+            )));
+        }
+        Ok(merged)
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn missing(&self) -> usize {
+        self.rows.last().map_or(0, |(last, _)| last + 1) - self.rows.len()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|(_, row)| !self.fields(row).last().is_some_and(|e| e.is_empty()))
+            .count()
+    }
+
+    pub fn statistics(&self) -> Vec<ColumnStats> {
+        let numeric = self.columns.len().saturating_sub(1);
+        let succeeded: Vec<Vec<&str>> = self
+            .rows
+            .iter()
+            .map(|(_, row)| self.fields(row))
+            .filter(|fields| fields.last().is_some_and(|e| e.is_empty()))
+            .collect();
+
+        self.columns[..numeric]
+            .iter()
+            .enumerate()
+            .map(|(k, name)| {
+                let values: Vec<f64> = succeeded
+                    .iter()
+                    .filter_map(|fields| fields.get(k)?.parse::<f64>().ok())
+                    .filter(|v| v.is_finite())
+                    .collect();
+                column_stats(name, &values)
+            })
+            .collect()
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        if is_hdf5(path) {
+            return self.write_hdf5(path);
+        }
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(writer, "{}", self.columns.join(","))?;
+        for (_, row) in &self.rows {
+            writeln!(writer, "{}", row)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    pub fn write_statistics(&self, path: &Path) -> Result<()> {
+        if is_hdf5(path) {
+            return Err(Error::Config(format!(
+                "Sweep statistics are written as CSV, got {:?}",
+                path
+            )));
+        }
+        let mut writer = std::io::BufWriter::new(std::fs::File::create(path)?);
+        writeln!(writer, "column,count,mean,std,min,max")?;
+        for stats in self.statistics() {
+            writeln!(
+                writer,
+                "{},{},{},{},{},{}",
+                stats.name, stats.count, stats.mean, stats.std, stats.min, stats.max
+            )?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    // One dataset per column, plus the point indices and the config hash. Error
+    // messages are fixed-length strings, empty for points that succeeded.
+    fn write_hdf5(&self, path: &Path) -> Result<()> {
+        let rows: Vec<Vec<&str>> = self.rows.iter().map(|(_, row)| self.fields(row)).collect();
+        let numeric = self.columns.len().saturating_sub(1);
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        let mut writer = Hdf5Writer::new(file, &[], self.columns.len() + 2)?;
+        let mut members: Vec<(String, ObjectRef)> = Vec::new();
+
+        let points: Vec<u8> = self
+            .rows
+            .iter()
+            .flat_map(|(index, _)| (*index as u64).to_le_bytes())
+            .collect();
+        members.push((
+            POINT_COLUMN.to_string(),
+            writer.dataset(Datatype::U64, &[rows.len() as u64], &points, &[])?,
+        ));
+        members.push((
+            CONFIG_COLUMN.to_string(),
+            writer.dataset(
+                Datatype::Ascii(self.config_hash.len().max(1)),
+                &[],
+                &padded_string(&self.config_hash, self.config_hash.len().max(1)),
+                &[],
+            )?,
+        ));
+
+        for (k, name) in self.columns[..numeric].iter().enumerate() {
+            let mut values = Vec::with_capacity(8 * rows.len());
+            for fields in &rows {
+                let value: f64 = fields
+                    .get(k)
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(f64::NAN);
+                values.extend_from_slice(&value.to_le_bytes());
+            }
+            members.push((
+                name.clone(),
+                writer.dataset(Datatype::F64, &[rows.len() as u64], &values, &[])?,
+            ));
+        }
+
+        if let Some(name) = self.columns.last() {
+            let errors: Vec<&str> = rows
+                .iter()
+                .map(|fields| fields.get(numeric).map_or("", |e| e.trim_matches('"')))
+                .collect();
+            let width = errors.iter().map(|e| e.len()).max().unwrap_or(0).max(1);
+            let data: Vec<u8> = errors
+                .iter()
+                .flat_map(|e| padded_string(e, width))
+                .collect();
+            members.push((
+                name.clone(),
+                writer.dataset(Datatype::Ascii(width), &[rows.len() as u64], &data, &[])?,
+            ));
+        }
+
+        writer.finish(&members)?;
+        Ok(())
+    }
+
+    fn fields<'a>(&self, row: &'a str) -> Vec<&'a str> {
+        row.splitn(self.columns.len(), ',').collect()
+    }
+}
+
+fn column_stats(name: &str, values: &[f64]) -> ColumnStats {
+    let count = values.len();
+    let mean = values.iter().sum::<f64>() / count.max(1) as f64;
+    let variance = if count > 1 {
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (count - 1) as f64
+    } else {
+        0.0
+    };
+    let (min, max) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        });
+    ColumnStats {
+        name: name.to_string(),
+        count,
+        mean: if count > 0 { mean } else { f64::NAN },
+        std: variance.sqrt(),
+        min: if count > 0 { min } else { f64::NAN },
+        max: if count > 0 { max } else { f64::NAN },
+    }
+}
+
+fn is_hdf5(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("h5" | "hdf5")
+    )
+}
+
+fn padded_string(value: &str, width: usize) -> Vec<u8> {
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.resize(width, 0);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sweep::{write_array_task_csv, SweepOutput, SweepPoint, SweepRun};
+    use approx::assert_relative_eq;
+
+    fn task_file(dir: &Path, task: usize, hash: &str, indices: &[usize]) -> PathBuf {
+        let runs: Vec<SweepRun> = indices
+            .iter()
+            .map(|&index| SweepRun {
+                index,
+                point: SweepPoint {
+                    values: vec![("system.parameters.omega_d".to_string(), index as f64)],
+                },
+                outcome: if index == 3 {
+                    Err("diverged, retry".to_string())
+                } else {
+                    Ok(SweepOutput {
+                        metrics: vec![("p_final".to_string(), 0.5 * index as f64)],
+                        results: None,
+                    })
+                },
+            })
+            .collect();
+        let path = dir.join(format!("metrics_task_{}.csv", task));
+        write_array_task_csv(&runs, hash, &path).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_merge_orders_points_and_recomputes_statistics() {
+        let dir = std::env::temp_dir().join(format!("chronophoton_merge_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let inputs = vec![
+            task_file(&dir, 0, "abcd1234", &[0, 2, 4]),
+            task_file(&dir, 1, "abcd1234", &[1, 3]),
+        ];
+
+        let merged = MergedSweep::load(&inputs).unwrap();
+        let path = dir.join("metrics.csv");
+        merged.write(&path).unwrap();
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            contents,
+            "system.parameters.omega_d,p_final,error\n0,0,\n1,0.5,\n2,1,\n\
+             3,NaN,\"diverged, retry\"\n4,2,\n"
+        );
+        assert_eq!((merged.len(), merged.missing(), merged.failed()), (5, 0, 1));
+
+        let stats = merged.statistics();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[1].count, 4);
+        assert_relative_eq!(stats[1].mean, 0.875);
+        assert_relative_eq!(stats[1].max, 2.0);
+        assert!(merged.write_statistics(&dir.join("stats.h5")).is_err());
+
+        let foreign = task_file(&dir, 2, "ffff0000", &[5]);
+        assert!(MergedSweep::load(&[inputs[0].clone(), foreign]).is_err());
+        assert!(MergedSweep::load(&[inputs[0].clone(), inputs[0].clone()]).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_merge_round_trips_through_hdf5() {
+        use crate::data::hdf5::reader::Hdf5Reader;
+
+        let dir =
+            std::env::temp_dir().join(format!("chronophoton_merge_h5_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let inputs = vec![
+            task_file(&dir, 0, "abcd1234", &[0, 3]),
+            task_file(&dir, 1, "abcd1234", &[1]),
+        ];
+        let path = dir.join("combined.h5");
+        MergedSweep::load(&inputs).unwrap().write(&path).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        let reader = Hdf5Reader::new(&bytes, 0);
+        let root = reader.root();
+        let names: Vec<&str> = root.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "config",
+                "error",
+                "p_final",
+                "point",
+                "system.parameters.omega_d"
+            ]
+        );
+        let dataset = |name: &str| {
+            let (_, header) = root.iter().find(|(n, _)| n == name).unwrap();
+            reader.dataset(*header)
+        };
+        let f64s = |data: &[u8]| -> Vec<f64> {
+            data.chunks(8)
+                .map(|c| f64::from_le_bytes(c.try_into().unwrap()))
+                .collect()
+        };
+
+        assert_eq!(dataset("config").1, b"abcd1234");
+        let (dims, points) = dataset("point");
+        assert_eq!(dims, [3]);
+        assert_eq!(points, [0u64, 1, 3].map(u64::to_le_bytes).concat());
+        assert_eq!(
+            f64s(dataset("system.parameters.omega_d").1),
+            [0.0, 1.0, 3.0]
+        );
+        let p_final = f64s(dataset("p_final").1);
+        assert_eq!(p_final[..2], [0.0, 0.5]);
+        assert!(p_final[2].is_nan());
+        let (dims, errors) = dataset("error");
+        assert_eq!(dims, [3]);
+        let width = errors.len() / 3;
+        assert_eq!(&errors[2 * width..], b"diverged, retry");
+        assert!(errors[..2 * width].iter().all(|&b| b == 0));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
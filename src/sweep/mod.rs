@@ -1,6 +1,21 @@
+pub mod bayes;
+pub mod evolution;
 pub mod executor;
+pub mod hooks;
+pub mod merge;
+pub mod objective;
 pub mod parameter;
+pub mod reduction;
+pub mod refine;
 pub mod strategy;
 
-pub use executor::ParameterSweep;
+pub use executor::{
+    write_array_task_csv, write_eigenvalue_tracks_csv, write_metric_heatmaps, write_metrics_csv,
+    write_pareto_csv, ParameterSweep, SweepOutput, SweepPoint, SweepRun,
+};
+pub use hooks::{DerivedMetricConfig, Expression, PointHook};
+pub use merge::{ColumnStats, MergedSweep};
+pub use objective::{pareto_front, Goal, ObjectiveConfig};
+pub use reduction::{Reduction, ReductionConfig};
+pub use refine::RefinementConfig;
 pub use strategy::SweepStrategy;
@@ -0,0 +1,218 @@
+use crate::sweep::SweepRun;
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    Maximize,
+    Minimize,
+}
+
+impl Goal {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "maximize" | "max" => Ok(Goal::Maximize),
+            "minimize" | "min" => Ok(Goal::Minimize),
+            other => Err(Error::Config(format!(
+                "Unknown optimization goal '{}', expected maximize or minimize",
+                other
+            ))),
+        }
+    }
+
+    pub fn sign(&self) -> f64 {
+        match self {
+            Goal::Maximize => 1.0,
+            Goal::Minimize => -1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ObjectiveConfig {
+    pub metric: String,
+    #[serde(default = "default_goal")]
+    pub goal: String,
+    #[serde(default = "default_iterations")]
+    pub iterations: usize,
+    #[serde(default = "default_batch")]
+    pub batch: usize,
+    #[serde(default = "default_exploration")]
+    pub exploration: f64,
+}
+
+fn default_goal() -> String {
+    "maximize".to_string()
+}
+
+fn default_iterations() -> usize {
+    20
+}
+
+fn default_batch() -> usize {
+    1
+}
+
+fn default_exploration() -> f64 {
+    0.01
+}
+
+impl ObjectiveConfig {
+    pub fn new(metric: impl Into<String>, goal: Goal) -> Self {
+        Self {
+            metric: metric.into(),
+            goal: match goal {
+                Goal::Maximize => "maximize",
+                Goal::Minimize => "minimize",
+            }
+            .to_string(),
+            iterations: default_iterations(),
+            batch: default_batch(),
+            exploration: default_exploration(),
+        }
+    }
+
+    pub fn goal(&self) -> Result<Goal> {
+        Goal::parse(&self.goal)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.goal()?;
+        if self.batch == 0 {
+            return Err(Error::InvalidParameter(
+                "Objective batch must be positive".to_string(),
+            ));
+        }
+        if !(self.exploration >= 0.0 && self.exploration.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "Objective exploration must be non-negative, got {}",
+                self.exploration
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn value(&self, run: &SweepRun) -> Option<f64> {
+        let output = run.outcome.as_ref().ok()?;
+        output
+            .metrics
+            .iter()
+            .find(|(name, _)| *name == self.metric)
+            .map(|(_, value)| *value)
+            .filter(|value| value.is_finite())
+    }
+
+    pub fn check_produced(&self, runs: &[SweepRun]) -> Result<()> {
+        if runs.iter().any(|run| run.outcome.is_ok())
+            && !runs.iter().any(|run| {
+                run.outcome
+                    .as_ref()
+                    .is_ok_and(|o| o.metrics.iter().any(|(n, _)| *n == self.metric))
+            })
+        {
+            return Err(Error::Config(format!(
+                "Objective metric '{}' is not produced by the sweep",
+                self.metric
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn best<'a>(&self, runs: &'a [SweepRun]) -> Result<Option<&'a SweepRun>> {
+        let sign = self.goal()?.sign();
+        Ok(runs
+            .iter()
+            .filter_map(|run| Some((run, sign * self.value(run)?)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(run, _)| run))
+    }
+}
+
+// A run is on the front when no other run is at least as good in every objective and
+// strictly better in one. Runs missing any objective are left out, and the front is
+// ordered from best to worst in the first objective.
+pub fn pareto_front<'a>(
+    objectives: &[ObjectiveConfig],
+    runs: &'a [SweepRun],
+) -> Result<Vec<&'a SweepRun>> {
+    if objectives.is_empty() {
+        return Err(Error::InvalidParameter(
+            "A Pareto front needs at least one objective".to_string(),
+        ));
+    }
+    let signs = objectives
+        .iter()
+        .map(|objective| Ok(objective.goal()?.sign()))
+        .collect::<Result<Vec<f64>>>()?;
+    let scored: Vec<(&SweepRun, Vec<f64>)> = runs
+        .iter()
+        .filter_map(|run| {
+            let values = objectives
+                .iter()
+                .zip(&signs)
+                .map(|(objective, sign)| Some(sign * objective.value(run)?))
+                .collect::<Option<Vec<f64>>>()?;
+            Some((run, values))
+        })
+        .collect();
+    let dominates = |a: &[f64], b: &[f64]| {
+        a.iter().zip(b).all(|(x, y)| x >= y) && a.iter().zip(b).any(|(x, y)| x > y)
+    };
+    let mut front: Vec<&(&SweepRun, Vec<f64>)> = scored
+        .iter()
+        .filter(|(_, v)| !scored.iter().any(|(_, w)| dominates(w, v)))
+        .collect();
+    front.sort_by(|a, b| b.1[0].total_cmp(&a.1[0]));
+    Ok(front.into_iter().map(|(run, _)| *run).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sweep::{SweepOutput, SweepPoint};
+
+    fn run(index: usize, fidelity: f64, energy: f64) -> SweepRun {
+        SweepRun {
+            index,
+            point: SweepPoint {
+                values: vec![("system.parameters.rabi_freq".to_string(), index as f64)],
+            },
+            outcome: Ok(SweepOutput {
+                metrics: vec![
+                    ("fidelity".to_string(), fidelity),
+                    ("energy".to_string(), energy),
+                ],
+                results: None,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_pareto_front_keeps_non_dominated_runs() {
+        let mut runs = vec![
+            run(0, 0.9, 4.0),
+            run(1, 0.7, 1.0),
+            run(2, 0.6, 2.0),
+            run(3, 0.99, 9.0),
+            run(4, 0.9, 5.0),
+            run(5, f64::NAN, 0.0),
+        ];
+        runs.push(SweepRun {
+            index: 6,
+            point: runs[0].point.clone(),
+            outcome: Err("diverged".to_string()),
+        });
+        let objectives = [
+            ObjectiveConfig::new("fidelity", Goal::Maximize),
+            ObjectiveConfig::new("energy", Goal::Minimize),
+        ];
+        let front = pareto_front(&objectives, &runs).unwrap();
+        let indices: Vec<usize> = front.iter().map(|run| run.index).collect();
+        assert_eq!(indices, [3, 0, 1]);
+
+        let single = pareto_front(&objectives[..1], &runs).unwrap();
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].index, 3);
+        assert!(pareto_front(&[], &runs).is_err());
+    }
+}
@@ -14,7 +14,22 @@ impl Parameter {
         }
     }
 
+    pub fn at(&self, fraction: f64) -> f64 {
+        self.min + (self.max - self.min) * fraction
+    }
+
+    pub fn fraction(&self, value: f64) -> f64 {
+        if self.max > self.min {
+            (value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
     pub fn linspace(&self, n: usize) -> Vec<f64> {
+        if n == 1 {
+            return vec![self.min];
+        }
         (0..n)
             .map(|i| self.min + (self.max - self.min) * i as f64 / (n - 1) as f64)
             .collect()
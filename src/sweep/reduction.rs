@@ -0,0 +1,221 @@
+use crate::data::Config;
+use crate::simulation::SimulationResults;
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reduction {
+    Final,
+    Mean,
+    Max,
+    Amplitude,
+    SubharmonicWeight,
+}
+
+impl Reduction {
+    pub const ALL: [Reduction; 5] = [
+        Reduction::Final,
+        Reduction::Mean,
+        Reduction::Max,
+        Reduction::Amplitude,
+        Reduction::SubharmonicWeight,
+    ];
+
+    pub fn parse(name: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|r| r.name() == name)
+            .ok_or_else(|| {
+                Error::Config(format!(
+                    "Unknown reduction '{}', expected one of {}",
+                    name,
+                    Self::ALL.map(|r| r.name()).join(", ")
+                ))
+            })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Reduction::Final => "final",
+            Reduction::Mean => "mean",
+            Reduction::Max => "max",
+            Reduction::Amplitude => "amplitude",
+            Reduction::SubharmonicWeight => "subharmonic_weight",
+        }
+    }
+
+    pub fn apply(&self, times: &[f64], values: &[f64], frequency: Option<f64>) -> Result<f64> {
+        let (Some(&last), Some(&t_last)) = (values.last(), times.last()) else {
+            return Err(Error::InvalidParameter(
+                "Cannot reduce an empty time series".to_string(),
+            ));
+        };
+
+        match self {
+            Reduction::Final => Ok(last),
+            Reduction::Max => Ok(values.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+            Reduction::Amplitude => {
+                let (min, max) = values
+                    .iter()
+                    .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                        (lo.min(v), hi.max(v))
+                    });
+                Ok((max - min) / 2.0)
+            }
+            Reduction::Mean => {
+                let span = t_last - times[0];
+                if span <= 0.0 {
+                    return Ok(last);
+                }
+                Ok(trapezoid(times, |k| values[k]) / span)
+            }
+            Reduction::SubharmonicWeight => {
+                let omega = frequency.ok_or_else(|| {
+                    Error::Config(
+                        "subharmonic_weight needs a frequency or a system.parameters.omega_d"
+                            .to_string(),
+                    )
+                })? / 2.0;
+                let span = t_last - times[0];
+                if span <= 0.0 {
+                    return Ok(0.0);
+                }
+                let mean = trapezoid(times, |k| values[k]) / span;
+                let re = trapezoid(times, |k| (values[k] - mean) * (omega * times[k]).cos());
+                let im = trapezoid(times, |k| (values[k] - mean) * (omega * times[k]).sin());
+                let amplitude = 2.0 * (re * re + im * im).sqrt() / span;
+                Ok(amplitude * amplitude)
+            }
+        }
+    }
+}
+
+fn trapezoid(times: &[f64], f: impl Fn(usize) -> f64) -> f64 {
+    (1..times.len())
+        .map(|k| 0.5 * (f(k) + f(k - 1)) * (times[k] - times[k - 1]))
+        .sum()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReductionConfig {
+    pub observable: String,
+    pub reduction: String,
+    #[serde(default = "default_window")]
+    pub window: f64,
+    #[serde(default)]
+    pub frequency: Option<f64>,
+}
+
+fn default_window() -> f64 {
+    1.0
+}
+
+impl ReductionConfig {
+    pub fn metric_name(&self) -> String {
+        format!("{}_{}", self.observable, self.reduction)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let reduction = Reduction::parse(&self.reduction)?;
+        if !(self.window > 0.0 && self.window <= 1.0) {
+            return Err(Error::InvalidParameter(format!(
+                "Reduction window for '{}' must be a fraction in (0, 1], got {}",
+                self.metric_name(),
+                self.window
+            )));
+        }
+        if reduction == Reduction::SubharmonicWeight
+            && self.frequency.is_some_and(|f| !(f > 0.0 && f.is_finite()))
+        {
+            return Err(Error::InvalidParameter(format!(
+                "Reduction frequency for '{}' must be positive",
+                self.metric_name()
+            )));
+        }
+        Ok(())
+    }
+
+    pub fn reduce(&self, results: &SimulationResults, drive_frequency: Option<f64>) -> Result<f64> {
+        let series = results.series(&self.observable).ok_or_else(|| {
+            Error::Config(format!(
+                "Reduction requested for unknown observable '{}'",
+                self.observable
+            ))
+        })?;
+        let times = results.times();
+        let values = series.to_vec_real();
+
+        let (Some(&t0), Some(&t1)) = (times.first(), times.last()) else {
+            return Err(Error::InvalidParameter(format!(
+                "Observable '{}' has no samples to reduce",
+                self.observable
+            )));
+        };
+        let start_time = t1 - self.window * (t1 - t0);
+        let start = times.partition_point(|&t| t < start_time);
+
+        Reduction::parse(&self.reduction)?.apply(
+            &times[start..],
+            &values[start..],
+            self.frequency.or(drive_frequency),
+        )
+    }
+}
+
+pub fn reduce_results(config: &Config, results: &SimulationResults) -> Result<Vec<(String, f64)>> {
+    let drive_frequency = config.system.parameters.get("omega_d");
+    config
+        .parameter_sweep
+        .reductions
+        .iter()
+        .map(|r| Ok((r.metric_name(), r.reduce(results, drive_frequency)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use num_complex::Complex64;
+
+    #[test]
+    fn test_reductions_of_period_doubled_signal() {
+        let omega = 2.0;
+        let period = 2.0 * std::f64::consts::PI / (omega / 2.0);
+        let times: Vec<f64> = (0..=4000)
+            .map(|k| k as f64 * 6.0 * period / 4000.0)
+            .collect();
+        let values: Vec<f64> = times
+            .iter()
+            .map(|t| 1.0 + 0.5 * (omega / 2.0 * t).cos())
+            .collect();
+
+        let apply = |r: Reduction| r.apply(&times, &values, Some(omega)).unwrap();
+        assert_relative_eq!(apply(Reduction::Final), values[4000]);
+        assert_relative_eq!(apply(Reduction::Mean), 1.0, epsilon = 1e-6);
+        assert_relative_eq!(apply(Reduction::Max), 1.5, epsilon = 1e-6);
+        assert_relative_eq!(apply(Reduction::Amplitude), 0.5, epsilon = 1e-6);
+        assert_relative_eq!(apply(Reduction::SubharmonicWeight), 0.25, epsilon = 1e-4);
+        assert!(Reduction::SubharmonicWeight
+            .apply(&times, &values, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_reduce_uses_window_and_names_metric() {
+        let mut results = SimulationResults::new();
+        for k in 0..=10 {
+            let t = k as f64;
+            results.add_observable("n", t, Complex64::new(if k < 5 { 10.0 } else { 1.0 }, 0.0));
+        }
+        let reduction = ReductionConfig {
+            observable: "n".to_string(),
+            reduction: "max".to_string(),
+            window: 0.5,
+            frequency: None,
+        };
+        assert_eq!(reduction.metric_name(), "n_max");
+        assert_relative_eq!(reduction.reduce(&results, None).unwrap(), 1.0);
+        assert!(Reduction::parse("median").is_err());
+    }
+}
@@ -0,0 +1,261 @@
+use crate::sweep::{SweepPoint, SweepRun};
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefinementConfig {
+    pub metric: String,
+    #[serde(default = "default_max_points")]
+    pub max_points: usize,
+    #[serde(default = "default_max_rounds")]
+    pub max_rounds: usize,
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+}
+
+fn default_max_points() -> usize {
+    200
+}
+
+fn default_max_rounds() -> usize {
+    6
+}
+
+fn default_tolerance() -> f64 {
+    0.05
+}
+
+impl RefinementConfig {
+    pub fn new(metric: impl Into<String>) -> Self {
+        Self {
+            metric: metric.into(),
+            max_points: default_max_points(),
+            max_rounds: default_max_rounds(),
+            tolerance: default_tolerance(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.max_points == 0 {
+            return Err(Error::InvalidParameter(
+                "Refinement max_points must be positive".to_string(),
+            ));
+        }
+        if !(self.tolerance > 0.0 && self.tolerance.is_finite()) {
+            return Err(Error::InvalidParameter(format!(
+                "Refinement tolerance must be positive, got {}",
+                self.tolerance
+            )));
+        }
+        Ok(())
+    }
+
+    // Edges join neighbours along one axis. They are scored by the metric jump and by
+    // the deviation of each middle point from its neighbours' chord, relative to the
+    // metric's spread.
+    pub fn refine(&self, runs: &[SweepRun], budget: usize) -> Result<Vec<SweepPoint>> {
+        let Some(names) = runs.first().map(|run| {
+            run.point
+                .values
+                .iter()
+                .map(|(n, _)| n.clone())
+                .collect::<Vec<_>>()
+        }) else {
+            return Ok(Vec::new());
+        };
+        let outputs: Vec<_> = runs
+            .iter()
+            .filter_map(|run| Some((run, run.outcome.as_ref().ok()?)))
+            .collect();
+        if !outputs.is_empty()
+            && !outputs
+                .iter()
+                .any(|(_, output)| output.metrics.iter().any(|(n, _)| *n == self.metric))
+        {
+            return Err(Error::Config(format!(
+                "Refinement metric '{}' is not produced by the sweep",
+                self.metric
+            )));
+        }
+
+        let samples: Vec<(Vec<f64>, f64)> = outputs
+            .iter()
+            .filter_map(|(run, output)| {
+                let value = output
+                    .metrics
+                    .iter()
+                    .find(|(n, _)| *n == self.metric)
+                    .map(|(_, v)| *v)
+                    .filter(|v| v.is_finite())?;
+                Some((run.point.values.iter().map(|(_, v)| *v).collect(), value))
+            })
+            .collect();
+        let (lo, hi) = samples
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), (_, v)| {
+                (lo.min(*v), hi.max(*v))
+            });
+        let spread = hi - lo;
+        if budget == 0 || spread <= 0.0 {
+            return Ok(Vec::new());
+        }
+
+        let existing: Vec<Vec<f64>> = runs
+            .iter()
+            .map(|run| run.point.values.iter().map(|(_, v)| *v).collect())
+            .collect();
+        let mut candidates: Vec<(f64, Vec<f64>)> = Vec::new();
+        for axis in 0..names.len() {
+            let mut lines: HashMap<Vec<u64>, Vec<(f64, f64)>> = HashMap::new();
+            for (coords, value) in &samples {
+                let key = coords
+                    .iter()
+                    .enumerate()
+                    .filter(|(k, _)| *k != axis)
+                    .map(|(_, x)| x.to_bits())
+                    .collect();
+                lines.entry(key).or_default().push((coords[axis], *value));
+            }
+            for (key, mut line) in lines {
+                line.sort_by(|a, b| a.0.total_cmp(&b.0));
+                line.dedup_by(|a, b| a.0 == b.0);
+                let mut scores: Vec<f64> = line
+                    .windows(2)
+                    .map(|w| (w[1].1 - w[0].1).abs() / spread)
+                    .collect();
+                for (k, w) in line.windows(3).enumerate() {
+                    let weight = (w[1].0 - w[0].0) / (w[2].0 - w[0].0);
+                    let linear = w[0].1 + weight * (w[2].1 - w[0].1);
+                    let curvature = (w[1].1 - linear).abs() / spread;
+                    scores[k] = scores[k].max(curvature);
+                    scores[k + 1] = scores[k + 1].max(curvature);
+                }
+                for (w, score) in line.windows(2).zip(scores) {
+                    if score <= self.tolerance {
+                        continue;
+                    }
+                    let mut others = key.iter().map(|bits| f64::from_bits(*bits));
+                    let midpoint: Vec<f64> = (0..names.len())
+                        .map(|k| {
+                            if k == axis {
+                                0.5 * (w[0].0 + w[1].0)
+                            } else {
+                                others.next().unwrap_or_default()
+                            }
+                        })
+                        .collect();
+                    candidates.push((score, midpoint));
+                }
+            }
+        }
+
+        candidates.sort_by(|a, b| {
+            b.0.total_cmp(&a.0)
+                .then_with(|| compare_coordinates(&a.1, &b.1))
+        });
+        let mut points: Vec<Vec<f64>> = Vec::new();
+        for (_, midpoint) in candidates {
+            if points.len() == budget {
+                break;
+            }
+            if !existing.contains(&midpoint) && !points.contains(&midpoint) {
+                points.push(midpoint);
+            }
+        }
+        Ok(points
+            .into_iter()
+            .map(|values| SweepPoint {
+                values: names.iter().cloned().zip(values).collect(),
+            })
+            .collect())
+    }
+}
+
+pub(crate) fn compare_coordinates(a: &[f64], b: &[f64]) -> std::cmp::Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| x.total_cmp(y))
+        .find(|o| o.is_ne())
+        .unwrap_or(std::cmp::Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sweep::parameter::Parameter;
+    use crate::sweep::{SweepOutput, SweepStrategy};
+
+    fn evaluate(points: Vec<SweepPoint>, offset: usize, f: impl Fn(f64) -> f64) -> Vec<SweepRun> {
+        points
+            .into_iter()
+            .enumerate()
+            .map(|(k, point)| {
+                let x = point.values[0].1;
+                SweepRun {
+                    index: offset + k,
+                    point,
+                    outcome: Ok(SweepOutput {
+                        metrics: vec![("n_max".to_string(), f(x))],
+                        results: None,
+                    }),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_refinement_resolves_narrow_resonance() {
+        let lorentzian = |x: f64| 1.0 / (1.0 + ((x - 3.3) / 0.02).powi(2));
+        let parameters = [Parameter::new("system.parameters.omega_d", 0.0, 10.0)];
+        let grid = SweepStrategy::Adaptive
+            .sample(&parameters, 21, None)
+            .into_iter()
+            .map(|values| SweepPoint {
+                values: vec![(parameters[0].name.clone(), values[0])],
+            })
+            .collect();
+        let refinement = RefinementConfig {
+            max_points: 80,
+            max_rounds: 10,
+            ..RefinementConfig::new("n_max")
+        };
+
+        let mut runs = evaluate(grid, 0, lorentzian);
+        for _ in 0..refinement.max_rounds {
+            let budget = refinement.max_points - runs.len();
+            let points = refinement.refine(&runs, budget).unwrap();
+            if points.is_empty() {
+                break;
+            }
+            let offset = runs.len();
+            runs.extend(evaluate(points, offset, lorentzian));
+        }
+
+        assert!(runs.len() <= 80);
+        let peak = runs
+            .iter()
+            .map(|run| run.outcome.as_ref().unwrap().metrics[0].1)
+            .fold(0.0, f64::max);
+        assert!(peak > 0.9, "peak {}", peak);
+        let far = runs
+            .iter()
+            .filter(|run| (run.point.values[0].1 - 3.3).abs() > 1.0)
+            .count();
+        assert!(far < 25, "{} points away from the resonance", far);
+
+        let flat = evaluate(
+            runs[..3].iter().map(|r| r.point.clone()).collect(),
+            0,
+            |_| 1.0,
+        );
+        assert!(refinement.refine(&flat, 10).unwrap().is_empty());
+        assert!(RefinementConfig::new("missing").refine(&runs, 10).is_err());
+        assert!(RefinementConfig {
+            tolerance: 0.0,
+            ..RefinementConfig::new("n_max")
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -1,6 +1,120 @@
+use crate::sweep::parameter::Parameter;
+use crate::utils::{Error, Result};
+use rand::seq::SliceRandom;
+use rand::Rng;
+
 #[derive(Debug, Clone, Copy)]
 pub enum SweepStrategy {
     Grid,
     Random,
     LatinHypercube,
+    Adaptive,
+    BayesOpt,
+    CmaEs,
+    DifferentialEvolution,
+}
+
+impl SweepStrategy {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "grid" => Ok(SweepStrategy::Grid),
+            "random" => Ok(SweepStrategy::Random),
+            "latin_hypercube" => Ok(SweepStrategy::LatinHypercube),
+            "adaptive" => Ok(SweepStrategy::Adaptive),
+            "bayes_opt" => Ok(SweepStrategy::BayesOpt),
+            "cma_es" => Ok(SweepStrategy::CmaEs),
+            "differential_evolution" => Ok(SweepStrategy::DifferentialEvolution),
+            other => Err(Error::Config(format!(
+                "Unknown sweep strategy '{}', expected grid, random, latin_hypercube, adaptive, \
+                 bayes_opt, cma_es or differential_evolution",
+                other
+            ))),
+        }
+    }
+
+    pub fn sample(
+        &self,
+        parameters: &[Parameter],
+        num_points: usize,
+        seed: Option<u64>,
+    ) -> Vec<Vec<f64>> {
+        if parameters.is_empty() || num_points == 0 {
+            return Vec::new();
+        }
+        let mut rng = crate::utils::performance::seeded_rng(seed);
+
+        match self {
+            SweepStrategy::Grid | SweepStrategy::Adaptive => {
+                let axes: Vec<Vec<f64>> =
+                    parameters.iter().map(|p| p.linspace(num_points)).collect();
+                let mut points = vec![Vec::new()];
+                for axis in &axes {
+                    points = points
+                        .into_iter()
+                        .flat_map(|point| {
+                            axis.iter().map(move |&value| {
+                                let mut point = point.clone();
+                                point.push(value);
+                                point
+                            })
+                        })
+                        .collect();
+                }
+                points
+            }
+            SweepStrategy::Random => (0..num_points)
+                .map(|_| {
+                    parameters
+                        .iter()
+                        .map(|p| p.at(rng.random::<f64>()))
+                        .collect()
+                })
+                .collect(),
+            SweepStrategy::LatinHypercube
+            | SweepStrategy::BayesOpt
+            | SweepStrategy::CmaEs
+            | SweepStrategy::DifferentialEvolution => {
+                let mut points = vec![Vec::with_capacity(parameters.len()); num_points];
+                for parameter in parameters {
+                    let mut strata: Vec<usize> = (0..num_points).collect();
+                    strata.shuffle(&mut rng);
+                    for (point, stratum) in points.iter_mut().zip(strata) {
+                        let u = (stratum as f64 + rng.random::<f64>()) / num_points as f64;
+                        point.push(parameter.at(u));
+                    }
+                }
+                points
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_is_cartesian_product() {
+        let parameters = [Parameter::new("a", 0.0, 1.0), Parameter::new("b", 2.0, 4.0)];
+        let points = SweepStrategy::Grid.sample(&parameters, 3, None);
+        assert_eq!(points.len(), 9);
+        assert_eq!(points[0], vec![0.0, 2.0]);
+        assert_eq!(points[8], vec![1.0, 4.0]);
+    }
+
+    #[test]
+    fn test_random_strategies_stay_in_range_and_are_seeded() {
+        let parameters = [
+            Parameter::new("a", -1.0, 1.0),
+            Parameter::new("b", 0.0, 10.0),
+        ];
+        for strategy in [SweepStrategy::Random, SweepStrategy::LatinHypercube] {
+            let points = strategy.sample(&parameters, 50, Some(7));
+            assert_eq!(points.len(), 50);
+            assert!(points
+                .iter()
+                .all(|p| (-1.0..=1.0).contains(&p[0]) && (0.0..=10.0).contains(&p[1])));
+            assert_eq!(points, strategy.sample(&parameters, 50, Some(7)));
+        }
+    }
 }
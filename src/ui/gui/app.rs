@@ -1,20 +1,830 @@
-use crate::data::Config;
+use crate::data::{Animation, AnimationKind, AnimationOptions, Campaign, Config};
+use crate::simulation::{CostEstimate, JobControl, SimulationBuilder, SimulationResults};
+use crate::ui::gui::console::LogConsole;
+use crate::ui::gui::explore::Explorer;
+use crate::ui::gui::jobs::JobsPanel;
+use crate::ui::gui::layout::{DockSlot, GuiSettings, PanelKind, ThemeChoice};
+use crate::ui::gui::palette::{CommandPalette, PaletteCommand, PaletteEntry};
+use crate::ui::gui::plots::PlotWidget;
+use crate::ui::gui::safety::{Launch, NextStep, PendingLaunch};
+use crate::ui::gui::session::Session;
+use crate::ui::gui::watch::ConfigWatcher;
+use crate::utils::{Error, Result};
 use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+
+struct RunningSimulation {
+    control: Arc<JobControl>,
+    receiver: Receiver<Result<SimulationResults>>,
+}
 
 pub struct App {
     config: Option<Config>,
+    config_path: Option<PathBuf>,
+    config_text: String,
+    watcher: Option<ConfigWatcher>,
+    changed_on_disk: bool,
+    results: Option<SimulationResults>,
+    hidden_series: BTreeSet<String>,
+    console: LogConsole,
+    explorer: Explorer,
+    jobs: JobsPanel,
+    palette: CommandPalette,
+    settings: GuiSettings,
+    open_path: String,
+    session_path: String,
+    animation_kind: AnimationKind,
+    animation_path: String,
+    animation_job: Option<Receiver<Result<(PathBuf, usize)>>>,
+    running: Option<RunningSimulation>,
+    probe: Option<Receiver<(Launch, Result<CostEstimate>)>>,
+    pending_launch: Option<PendingLaunch>,
+    status: String,
 }
 
 impl App {
     pub fn new() -> Self {
-        Self { config: None }
+        Self {
+            config: None,
+            config_path: None,
+            config_text: String::new(),
+            watcher: None,
+            changed_on_disk: false,
+            results: None,
+            hidden_series: BTreeSet::new(),
+            console: LogConsole::default(),
+            explorer: Explorer::default(),
+            jobs: JobsPanel::default(),
+            palette: CommandPalette::default(),
+            settings: GuiSettings::default(),
+            open_path: String::new(),
+            session_path: "session.cpsession".to_string(),
+            animation_kind: AnimationKind::Bloch,
+            animation_path: "animation.gif".to_string(),
+            animation_job: None,
+            running: None,
+            probe: None,
+            pending_launch: None,
+            status: String::new(),
+        }
     }
 
     pub fn with_config(config: Config) -> Self {
-        Self {
-            config: Some(config),
+        let mut app = Self::new();
+        app.config_text = toml::to_string_pretty(&config).unwrap_or_default();
+        app.config = Some(config);
+        app
+    }
+
+    pub fn restore(mut self, storage: Option<&dyn eframe::Storage>) -> Self {
+        if let Some(settings) = storage.and_then(|s| eframe::get_value(s, eframe::APP_KEY)) {
+            self.settings = settings;
+        }
+        if let Some(path) = &self.config_path {
+            self.settings.push_recent(path);
+        }
+        self
+    }
+
+    pub fn load_config(&mut self, path: &Path) -> Result<()> {
+        let config = Config::from_file(path)?;
+        self.config_text = std::fs::read_to_string(path)?;
+        self.config = Some(config);
+        self.config_path = Some(path.to_path_buf());
+        self.settings.push_recent(path);
+        self.watch_config();
+        Ok(())
+    }
+
+    fn watch_config(&mut self) {
+        self.changed_on_disk = false;
+        self.watcher =
+            self.config_path
+                .as_deref()
+                .and_then(|path| match ConfigWatcher::new(path) {
+                    Ok(watcher) => Some(watcher),
+                    Err(e) => {
+                        tracing::warn!("Config changes will not be picked up: {}", e);
+                        None
+                    }
+                });
+    }
+
+    fn poll_watcher(&mut self) {
+        if !self.watcher.as_ref().is_some_and(|w| w.changed()) {
+            return;
+        }
+        // Saving without edits, or saving what the editor panel already holds,
+        // is not worth a prompt.
+        let unchanged = self
+            .config_path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .is_some_and(|text| text == self.config_text);
+        if unchanged {
+            return;
+        }
+        if self.settings.rerun_on_change {
+            self.reload_config(true);
+        } else {
+            self.changed_on_disk = true;
+        }
+    }
+
+    fn reload_config(&mut self, rerun: bool) {
+        self.changed_on_disk = false;
+        let Some(path) = self.config_path.clone() else {
+            return;
+        };
+        let reloaded = Config::from_file(&path)
+            .and_then(|config| config.validate())
+            .and_then(|()| self.load_config(&path));
+        if let Err(e) = reloaded {
+            self.set_error(format!(
+                "{} changed but is not valid: {}",
+                path.display(),
+                e
+            ));
+            return;
+        }
+        self.set_status(format!("Reloaded {}", path.display()));
+        if rerun {
+            // The previous run is stale now, so it gives way to the new one.
+            if let Some(running) = self.running.take() {
+                running.control.cancel();
+            }
+            self.run_simulation();
+        }
+    }
+
+    pub fn session(&self) -> Session {
+        let mut session = Session::new(self.config_text.clone(), self.settings.layout.clone());
+        session.config_path = self.config_path.clone();
+        session.results = self.results.clone();
+        session.hidden_series = self.hidden_series.clone();
+        session
+    }
+
+    pub fn restore_session(&mut self, session: Session) {
+        self.config = match Config::from_toml_str(&session.config_text) {
+            Ok(config) => Some(config),
+            Err(e) => {
+                tracing::warn!("Session config no longer parses: {}", e);
+                None
+            }
+        };
+        self.config_text = session.config_text;
+        self.config_path = session.config_path;
+        self.watch_config();
+        self.results = session.results;
+        self.hidden_series = session.hidden_series;
+        self.settings.layout = session.layout;
+    }
+
+    pub fn load_session(&mut self, path: &Path) -> Result<()> {
+        let session = Session::load(path)?;
+        self.restore_session(session);
+        self.session_path = path.display().to_string();
+        Ok(())
+    }
+
+    fn save_session(&mut self) {
+        let path = PathBuf::from(&self.session_path);
+        match self.session().save(&path) {
+            Ok(()) => self.set_status(format!("Saved session to {}", path.display())),
+            Err(e) => self.set_error(format!("Failed to save session: {}", e)),
+        }
+    }
+
+    fn open_session(&mut self) {
+        let path = PathBuf::from(&self.session_path);
+        match self.load_session(&path) {
+            Ok(()) => self.set_status(format!("Restored session {}", path.display())),
+            Err(e) => self.set_error(format!("Failed to restore session: {}", e)),
+        }
+    }
+
+    fn open(&mut self, path: &Path) {
+        match self.load_config(path) {
+            Ok(()) => self.set_status(format!("Loaded {}", path.display())),
+            Err(e) => self.set_error(format!("Failed to load {}: {}", path.display(), e)),
+        }
+    }
+
+    fn set_status(&mut self, status: String) {
+        tracing::info!("{}", status);
+        self.status = status;
+    }
+
+    fn set_error(&mut self, status: String) {
+        tracing::error!("{}", status);
+        self.status = status;
+    }
+
+    fn apply_config_text(&mut self) {
+        match Config::from_toml_str(&self.config_text) {
+            Ok(config) => {
+                self.config = Some(config);
+                self.set_status("Configuration applied".to_string());
+            }
+            Err(e) => self.set_error(e.to_string()),
         }
     }
+
+    fn run_simulation(&mut self) {
+        let Some(config) = self.config.clone() else {
+            self.set_error("No configuration loaded".to_string());
+            return;
+        };
+        self.launch(Launch::Run(config));
+    }
+
+    fn queue_simulation(&mut self) {
+        let Some(config) = self.config.clone() else {
+            self.set_error("No configuration loaded".to_string());
+            return;
+        };
+        self.launch(Launch::Queue(config));
+    }
+
+    fn queue_campaign(&mut self, path: PathBuf) {
+        match Campaign::from_file(&path) {
+            Ok(campaign) => self.launch(Launch::Campaign(campaign)),
+            Err(e) => self.set_error(format!("Failed to load {}: {}", path.display(), e)),
+        }
+    }
+
+    // Every launch is checked against the budget before it starts: memory straight
+    // from the config, then run time from a dry run on a worker thread. Either one
+    // over budget waits for confirmation.
+    fn launch(&mut self, launch: Launch) {
+        match self.settings.budget.memory_violation(launch.memory_bytes()) {
+            None => self.probe(launch),
+            Some(violation) => self.confirm(launch, NextStep::Probe, vec![violation]),
+        }
+    }
+
+    fn probe(&mut self, launch: Launch) {
+        if self.probe.is_some() {
+            self.set_error(format!(
+                "{}: another launch is still being estimated",
+                launch.title()
+            ));
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let estimate = launch.estimate();
+            tx.send((launch, estimate)).ok();
+        });
+        self.probe = Some(rx);
+        self.set_status("Estimating run time".to_string());
+    }
+
+    fn poll_probe(&mut self) {
+        let Some(probe) = &self.probe else {
+            return;
+        };
+        let (launch, estimate) = match probe.try_recv() {
+            Ok(outcome) => outcome,
+            Err(mpsc::TryRecvError::Empty) => return,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.probe = None;
+                self.set_error("Dry run stopped without an estimate".to_string());
+                return;
+            }
+        };
+        self.probe = None;
+        match estimate {
+            Ok(estimate) => match self.settings.budget.time_violation(estimate.seconds) {
+                None => self.start(launch, estimate),
+                Some(violation) => self.confirm(launch, NextStep::Start(estimate), vec![violation]),
+            },
+            Err(e) => self.set_error(format!("{} failed its dry run: {}", launch.title(), e)),
+        }
+    }
+
+    fn confirm(&mut self, launch: Launch, next: NextStep, violations: Vec<String>) {
+        tracing::warn!("{}: {}", launch.title(), violations.join("; "));
+        self.pending_launch = Some(PendingLaunch {
+            launch,
+            next,
+            violations,
+        });
+    }
+
+    fn start(&mut self, launch: Launch, estimate: CostEstimate) {
+        let title = launch.title();
+        let queued = !matches!(launch, Launch::Run(_));
+        let started = match launch {
+            Launch::Run(config) => self.start_run(config),
+            Launch::Queue(config) => self.jobs.submit_simulation(config),
+            Launch::Campaign(campaign) => self.jobs.submit_campaign(campaign),
+        };
+        match started {
+            Ok(()) => {
+                if queued {
+                    self.settings.layout.set_visible(PanelKind::Jobs, true);
+                }
+                self.set_status(format!("{}: estimated {}", title, estimate.describe()));
+            }
+            Err(e) => self.set_error(format!("{} failed: {}", title, e)),
+        }
+    }
+
+    fn start_run(&mut self, config: Config) -> Result<()> {
+        if self.running.is_some() {
+            return Err(Error::Config(
+                "A simulation is already running, abort it or queue this one".to_string(),
+            ));
+        }
+        let control = Arc::new(JobControl::new());
+        let worker = control.clone();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let outcome = SimulationBuilder::from_config(&config)
+                .and_then(|sim| sim.with_record_states(true).with_control(worker).run());
+            tx.send(outcome).ok();
+        });
+        self.running = Some(RunningSimulation {
+            control,
+            receiver: rx,
+        });
+        Ok(())
+    }
+
+    fn poll_running(&mut self) {
+        let Some(running) = &self.running else {
+            return;
+        };
+        match running.receiver.try_recv() {
+            Ok(outcome) => {
+                self.running = None;
+                match outcome {
+                    Ok(results) => {
+                        self.results = Some(results);
+                        self.set_status("Simulation complete".to_string());
+                    }
+                    Err(Error::Cancelled(_)) => self.set_status("Simulation aborted".to_string()),
+                    Err(e) => self.set_error(format!("Simulation failed: {}", e)),
+                }
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => {
+                self.running = None;
+                self.set_error("Simulation stopped without a result".to_string());
+            }
+        }
+    }
+
+    fn abort_all(&mut self) {
+        let mut aborted = self.jobs.cancel_all();
+        if let Some(running) = &self.running {
+            running.control.cancel();
+            aborted += 1;
+        }
+        if self.probe.take().is_some() {
+            aborted += 1;
+        }
+        self.pending_launch = None;
+        self.set_status(match aborted {
+            0 => "Nothing to abort".to_string(),
+            n => format!("Aborting {} computations", n),
+        });
+    }
+
+    fn palette_entries(&self) -> Vec<PaletteEntry> {
+        let mut entries = vec![
+            PaletteEntry::new("Run simulation", PaletteCommand::RunSimulation),
+            PaletteEntry::new("Queue simulation", PaletteCommand::QueueSimulation),
+            PaletteEntry::new("Abort all computations", PaletteCommand::AbortAll),
+            PaletteEntry::new("Apply config", PaletteCommand::ApplyConfig),
+            PaletteEntry::new("Save session", PaletteCommand::SaveSession),
+            PaletteEntry::new("Restore session", PaletteCommand::RestoreSession),
+            PaletteEntry::new("Export animation", PaletteCommand::ExportAnimation),
+            PaletteEntry::new("Reset layout", PaletteCommand::ResetLayout),
+        ];
+        if !self.open_path.is_empty() {
+            entries.push(PaletteEntry::new(
+                format!("Open config: {}", self.open_path),
+                PaletteCommand::OpenConfig(PathBuf::from(&self.open_path)),
+            ));
+        }
+        for path in &self.settings.recent_files {
+            entries.push(PaletteEntry::new(
+                format!("Open recent: {}", path.display()),
+                PaletteCommand::OpenConfig(path.clone()),
+            ));
+        }
+        for kind in PanelKind::ALL {
+            let action = if self.settings.layout.is_visible(kind) {
+                "Hide"
+            } else {
+                "Show"
+            };
+            entries.push(PaletteEntry::new(
+                format!("{} panel: {}", action, kind.title()),
+                PaletteCommand::TogglePanel(kind),
+            ));
+        }
+        for (choice, label) in [
+            (ThemeChoice::System, "System"),
+            (ThemeChoice::Dark, "Dark"),
+            (ThemeChoice::Light, "Light"),
+        ] {
+            entries.push(PaletteEntry::new(
+                format!("Theme: {}", label),
+                PaletteCommand::SetTheme(choice),
+            ));
+        }
+        entries
+    }
+
+    fn execute(&mut self, ctx: &egui::Context, command: PaletteCommand) {
+        match command {
+            PaletteCommand::OpenConfig(path) => self.open(&path),
+            PaletteCommand::ApplyConfig => self.apply_config_text(),
+            PaletteCommand::RunSimulation => self.run_simulation(),
+            PaletteCommand::QueueSimulation => self.queue_simulation(),
+            PaletteCommand::AbortAll => self.abort_all(),
+            PaletteCommand::SaveSession => self.save_session(),
+            PaletteCommand::RestoreSession => self.open_session(),
+            PaletteCommand::ExportAnimation => {
+                if self
+                    .results
+                    .as_ref()
+                    .is_some_and(|r| !r.snapshots().is_empty())
+                {
+                    self.export_animation(ctx);
+                } else {
+                    self.set_error("No state snapshots to animate".to_string());
+                }
+            }
+            PaletteCommand::TogglePanel(kind) => {
+                let visible = self.settings.layout.is_visible(kind);
+                self.settings.layout.set_visible(kind, !visible);
+            }
+            PaletteCommand::SetTheme(choice) => self.settings.theme = choice,
+            PaletteCommand::ResetLayout => self.settings.layout = Default::default(),
+        }
+    }
+
+    fn menu_bar(&mut self, ui: &mut egui::Ui) {
+        egui::MenuBar::new().ui(ui, |ui| {
+            ui.menu_button("File", |ui| {
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.open_path);
+                    if ui.button("Open").clicked() && !self.open_path.is_empty() {
+                        let path = PathBuf::from(&self.open_path);
+                        self.open(&path);
+                        ui.close();
+                    }
+                });
+
+                ui.menu_button("Recent files", |ui| {
+                    if self.settings.recent_files.is_empty() {
+                        ui.label("No recent files");
+                    }
+                    let mut selected = None;
+                    for path in &self.settings.recent_files {
+                        if ui.button(path.display().to_string()).clicked() {
+                            selected = Some(path.clone());
+                        }
+                    }
+                    if let Some(path) = selected {
+                        self.open(&path);
+                        ui.close();
+                    }
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.session_path);
+                    if ui.button("Save session").clicked() && !self.session_path.is_empty() {
+                        self.save_session();
+                        ui.close();
+                    }
+                    if ui.button("Restore session").clicked() && !self.session_path.is_empty() {
+                        self.open_session();
+                        ui.close();
+                    }
+                });
+
+                ui.separator();
+                if ui.button("Run simulation").clicked() {
+                    self.run_simulation();
+                    ui.close();
+                }
+                if ui.button("Queue simulation").clicked() {
+                    self.queue_simulation();
+                    ui.close();
+                }
+                if ui.button("Abort all computations").clicked() {
+                    self.abort_all();
+                    ui.close();
+                }
+                ui.menu_button("Cost limits", |ui| self.settings.budget.editor(ui));
+            });
+
+            ui.menu_button("View", |ui| {
+                for kind in PanelKind::ALL {
+                    let mut visible = self.settings.layout.is_visible(kind);
+                    if ui.checkbox(&mut visible, kind.title()).changed() {
+                        self.settings.layout.set_visible(kind, visible);
+                    }
+                }
+
+                ui.separator();
+                for kind in PanelKind::ALL {
+                    ui.menu_button(format!("Dock {}", kind.title()), |ui| {
+                        for slot in DockSlot::ALL {
+                            if ui.button(slot.title()).clicked() {
+                                self.settings.layout.move_to(kind, slot);
+                                ui.close();
+                            }
+                        }
+                    });
+                }
+
+                ui.separator();
+                ui.menu_button("Theme", |ui| {
+                    for (choice, label) in [
+                        (ThemeChoice::System, "System"),
+                        (ThemeChoice::Dark, "Dark"),
+                        (ThemeChoice::Light, "Light"),
+                    ] {
+                        ui.radio_value(&mut self.settings.theme, choice, label);
+                    }
+                });
+
+                if ui.button("Reset layout").clicked() {
+                    self.settings.layout = Default::default();
+                    ui.close();
+                }
+
+                ui.separator();
+                if ui.button("Command palette (Ctrl+P)").clicked() {
+                    self.palette.toggle();
+                    ui.close();
+                }
+            });
+        });
+    }
+
+    fn dock(&mut self, ui: &mut egui::Ui, slot: DockSlot) {
+        let panels = self.settings.layout.panels_in(slot);
+        let Some(active) = self.settings.layout.active_in(slot) else {
+            return;
+        };
+
+        if panels.len() > 1 {
+            ui.horizontal(|ui| {
+                for kind in &panels {
+                    if ui.selectable_label(*kind == active, kind.title()).clicked() {
+                        self.settings.layout.focus(*kind);
+                    }
+                }
+            });
+            ui.separator();
+        }
+
+        match active {
+            PanelKind::ConfigEditor => self.config_editor(ui),
+            PanelKind::Plots => self.plots(ui),
+            PanelKind::StateViz => self.state_viz(ui),
+            PanelKind::LogConsole => self.log_console(ui),
+            PanelKind::Explore => self.explorer.show(ui),
+            PanelKind::Jobs => self.jobs_panel(ui),
+        }
+    }
+
+    fn config_editor(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            match &self.config_path {
+                Some(path) => ui.label(path.display().to_string()),
+                None => ui.label("Unsaved configuration"),
+            };
+            if ui.button("Apply").clicked() {
+                self.apply_config_text();
+            }
+            if ui.button("Run").clicked() {
+                self.run_simulation();
+            }
+            if ui.button("Queue").clicked() {
+                self.queue_simulation();
+            }
+            if self.watcher.is_some() {
+                ui.checkbox(&mut self.settings.rerun_on_change, "Re-run on save")
+                    .on_hover_text("Reload and run the config whenever it changes on disk");
+            }
+        });
+
+        if self.changed_on_disk {
+            ui.horizontal_wrapped(|ui| {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    "The file changed on disk. Reloading replaces any edits made here.",
+                );
+                if ui.button("Reload").clicked() {
+                    self.reload_config(false);
+                }
+                if ui.button("Reload and run").clicked() {
+                    self.reload_config(true);
+                }
+                if ui.button("Ignore").clicked() {
+                    self.changed_on_disk = false;
+                }
+            });
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add(
+                egui::TextEdit::multiline(&mut self.config_text)
+                    .code_editor()
+                    .desired_width(f32::INFINITY),
+            );
+        });
+    }
+
+    fn plots(&mut self, ui: &mut egui::Ui) {
+        let Some(results) = &self.results else {
+            ui.label("No results yet. Run a simulation to see observables.");
+            return;
+        };
+
+        ui.horizontal_wrapped(|ui| {
+            for series in results.iter_series() {
+                let mut shown = !self.hidden_series.contains(series.name);
+                if ui.checkbox(&mut shown, series.name).changed() {
+                    if shown {
+                        self.hidden_series.remove(series.name);
+                    } else {
+                        self.hidden_series.insert(series.name.to_string());
+                    }
+                }
+            }
+        });
+
+        Plot::new("observables")
+            .legend(Legend::default())
+            .x_axis_label("t")
+            .show(ui, |plot_ui| {
+                for series in results
+                    .iter_series()
+                    .filter(|s| !self.hidden_series.contains(s.name))
+                {
+                    let points: PlotPoints = series.iter().map(|(t, v)| [t, v.re]).collect();
+                    plot_ui.line(Line::new(series.name.to_string(), points));
+                }
+            });
+    }
+
+    fn export_animation(&mut self, ctx: &egui::Context) {
+        let Some(results) = &self.results else {
+            return;
+        };
+        let snapshots = results.snapshots().to_vec();
+        let options = AnimationOptions::new(self.animation_kind);
+        let path = PathBuf::from(&self.animation_path);
+
+        let (tx, rx) = mpsc::channel();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let outcome = Animation::new(&snapshots, options)
+                .and_then(|animation| animation.render(&path))
+                .map(|frames| (path, frames));
+            tx.send(outcome).ok();
+            ctx.request_repaint();
+        });
+        self.animation_job = Some(rx);
+        self.set_status(format!("Rendering {}", self.animation_path));
+    }
+
+    fn poll_animation(&mut self) {
+        let Some(rx) = &self.animation_job else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(outcome) => {
+                self.animation_job = None;
+                match outcome {
+                    Ok((path, frames)) => self.set_status(format!(
+                        "Wrote {} animation frames to {}",
+                        frames,
+                        path.display()
+                    )),
+                    Err(e) => self.set_error(format!("Animation export failed: {}", e)),
+                }
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => self.animation_job = None,
+        }
+    }
+
+    fn animation_controls(&mut self, ui: &mut egui::Ui) {
+        self.poll_animation();
+        let has_snapshots = self
+            .results
+            .as_ref()
+            .is_some_and(|r| !r.snapshots().is_empty());
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("animation_kind")
+                .selected_text(self.animation_kind.title())
+                .show_ui(ui, |ui| {
+                    for kind in AnimationKind::ALL {
+                        ui.selectable_value(&mut self.animation_kind, kind, kind.title());
+                    }
+                });
+            ui.text_edit_singleline(&mut self.animation_path);
+
+            if self.animation_job.is_some() {
+                ui.spinner();
+            } else if ui
+                .add_enabled(has_snapshots, egui::Button::new("Export animation"))
+                .on_disabled_hover_text("Run a simulation to record state snapshots")
+                .clicked()
+            {
+                self.export_animation(ui.ctx());
+            }
+        });
+        ui.separator();
+    }
+
+    fn state_viz(&mut self, ui: &mut egui::Ui) {
+        self.animation_controls(ui);
+        match self
+            .results
+            .as_ref()
+            .and_then(|r| r.iter_vector_series().next())
+        {
+            Some(series) => PlotWidget::new().heatmap(ui, &series),
+            None => {
+                ui.label("No vector observables recorded");
+            }
+        }
+    }
+
+    fn jobs_panel(&mut self, ui: &mut egui::Ui) {
+        if let Some(results) = self.jobs.show(ui) {
+            self.results = Some(results);
+            self.settings.layout.set_visible(PanelKind::Plots, true);
+            self.set_status("Opened job results".to_string());
+        }
+        if let Some(path) = self.jobs.take_campaign_request() {
+            self.queue_campaign(path);
+        }
+    }
+
+    fn log_console(&mut self, ui: &mut egui::Ui) {
+        self.console.show(ui);
+    }
+
+    fn status_bar(&mut self, ui: &mut egui::Ui) {
+        self.console.poll();
+        ui.horizontal(|ui| {
+            ui.label(if self.status.is_empty() {
+                "Ready"
+            } else {
+                &self.status
+            });
+
+            let active = self.jobs.active_count();
+            let running = self.running.as_ref().map(|r| r.control.progress());
+            if active > 0 || running.is_some() || self.probe.is_some() {
+                ui.ctx()
+                    .request_repaint_after(std::time::Duration::from_millis(500));
+            }
+            let warnings = self.console.warning_count();
+            if active > 0 || warnings > 0 || running.is_some() {
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if (active > 0 || running.is_some()) && ui.button("⏹ Abort all").clicked() {
+                        self.abort_all();
+                    }
+                    if let Some(progress) = running {
+                        ui.label(format!("Simulation {:.0}%", 100.0 * progress));
+                        ui.spinner();
+                    }
+                    if warnings > 0 {
+                        let text = egui::RichText::new(format!("⚠ {} warnings", warnings))
+                            .color(ui.visuals().warn_fg_color);
+                        if ui.link(text).clicked() {
+                            self.settings
+                                .layout
+                                .set_visible(PanelKind::LogConsole, true);
+                        }
+                    }
+                    if active > 0 && ui.link(format!("{} jobs running", active)).clicked() {
+                        self.settings.layout.set_visible(PanelKind::Jobs, true);
+                    }
+                });
+            }
+        });
+    }
 }
 
 impl Default for App {
@@ -25,21 +835,67 @@ impl Default for App {
 
 impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        egui::CentralPanel::default().show(ctx, |ui| {
-            ui.heading("ChronoPhoton");
-            ui.label("GPU-Accelerated Photonic Time Crystal Simulator");
+        ctx.set_theme(self.settings.theme.preference());
 
-            ui.separator();
-
-            if self.config.is_some() {
-                ui.label("Configuration loaded");
-            } else {
-                ui.label("No configuration loaded");
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::P)) {
+            self.palette.toggle();
+        }
+        if self.palette.is_open() {
+            let entries = self.palette_entries();
+            if let Some(command) = self.palette.show(ctx, &entries) {
+                self.execute(ctx, command);
+            }
+        }
+        self.poll_running();
+        self.poll_probe();
+        if self.watcher.is_some() {
+            self.poll_watcher();
+            ctx.request_repaint_after(std::time::Duration::from_millis(500));
+        }
+        if let Some(proceed) = self.pending_launch.as_ref().and_then(|p| p.show(ctx)) {
+            let PendingLaunch { launch, next, .. } = self
+                .pending_launch
+                .take()
+                .expect("pending launch was just shown");
+            match (proceed, next) {
+                (true, NextStep::Probe) => self.probe(launch),
+                (true, NextStep::Start(estimate)) => self.start(launch, estimate),
+                (false, _) => self.set_status(format!("{} cancelled", launch.title())),
             }
+        }
 
-            ui.button("Load Configuration").clicked();
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| self.menu_bar(ui));
+        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| self.status_bar(ui));
 
-            if ui.button("Run Simulation").clicked() {}
+        if self.settings.layout.active_in(DockSlot::Bottom).is_some() {
+            egui::TopBottomPanel::bottom("dock_bottom")
+                .resizable(true)
+                .default_height(160.0)
+                .show(ctx, |ui| self.dock(ui, DockSlot::Bottom));
+        }
+        if self.settings.layout.active_in(DockSlot::Left).is_some() {
+            egui::SidePanel::left("dock_left")
+                .resizable(true)
+                .default_width(320.0)
+                .show(ctx, |ui| self.dock(ui, DockSlot::Left));
+        }
+        if self.settings.layout.active_in(DockSlot::Right).is_some() {
+            egui::SidePanel::right("dock_right")
+                .resizable(true)
+                .default_width(280.0)
+                .show(ctx, |ui| self.dock(ui, DockSlot::Right));
+        }
+        egui::CentralPanel::default().show(ctx, |ui| {
+            if self.settings.layout.active_in(DockSlot::Center).is_some() {
+                self.dock(ui, DockSlot::Center);
+            } else {
+                ui.heading("ChronoPhoton");
+                ui.label("GPU-Accelerated Photonic Time Crystal Simulator");
+            }
         });
     }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.settings);
+    }
 }
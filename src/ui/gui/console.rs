@@ -0,0 +1,147 @@
+use crate::utils::logger::{self, LogBuffer, LogRecord};
+use eframe::egui;
+use std::sync::Arc;
+use tracing::Level;
+
+const MAX_RECORDS: usize = 5000;
+const LEVELS: [Level; 5] = [
+    Level::ERROR,
+    Level::WARN,
+    Level::INFO,
+    Level::DEBUG,
+    Level::TRACE,
+];
+
+pub struct LogConsole {
+    buffer: Arc<LogBuffer>,
+    records: Vec<LogRecord>,
+    next_seq: u64,
+    min_level: Level,
+    search: String,
+}
+
+impl LogConsole {
+    pub fn new(buffer: Arc<LogBuffer>) -> Self {
+        Self {
+            buffer,
+            records: Vec::new(),
+            next_seq: 0,
+            min_level: Level::INFO,
+            search: String::new(),
+        }
+    }
+
+    pub fn poll(&mut self) {
+        let fresh = self.buffer.records_since(self.next_seq);
+        if let Some(last) = fresh.last() {
+            self.next_seq = last.seq + 1;
+        }
+        self.records.extend(fresh);
+        if self.records.len() > MAX_RECORDS {
+            self.records.drain(..self.records.len() - MAX_RECORDS);
+        }
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.records
+            .iter()
+            .filter(|r| r.level <= Level::WARN)
+            .count()
+    }
+
+    fn matches(&self, record: &LogRecord) -> bool {
+        if record.level > self.min_level {
+            return false;
+        }
+        if self.search.is_empty() {
+            return true;
+        }
+        let needle = self.search.to_lowercase();
+        record.message.to_lowercase().contains(&needle)
+            || record.target.to_lowercase().contains(&needle)
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        self.poll();
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("log_level")
+                .selected_text(self.min_level.as_str())
+                .show_ui(ui, |ui| {
+                    for level in LEVELS {
+                        ui.selectable_value(&mut self.min_level, level, level.as_str());
+                    }
+                });
+            ui.add(egui::TextEdit::singleline(&mut self.search).hint_text("Search"));
+            if ui.button("Clear").clicked() {
+                self.records.clear();
+                self.buffer.clear();
+            }
+        });
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .stick_to_bottom(true)
+            .show(ui, |ui| {
+                for record in self.records.iter().filter(|r| self.matches(r)) {
+                    let color = match record.level {
+                        Level::ERROR => ui.visuals().error_fg_color,
+                        Level::WARN => ui.visuals().warn_fg_color,
+                        _ => ui.visuals().text_color(),
+                    };
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "{} {:>5} {}: {}",
+                            record.time.format("%H:%M:%S"),
+                            record.level,
+                            record.target,
+                            record.message
+                        ))
+                        .monospace()
+                        .color(color),
+                    );
+                }
+            });
+    }
+}
+
+impl Default for LogConsole {
+    fn default() -> Self {
+        Self::new(logger::console())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_and_search_filters() {
+        let buffer = Arc::new(LogBuffer::new(10));
+        buffer.push(
+            Level::WARN,
+            "chronophoton::simulation",
+            "Norm drift".to_string(),
+        );
+        buffer.push(Level::DEBUG, "chronophoton::core", "Step 100".to_string());
+        buffer.push(
+            Level::INFO,
+            "chronophoton",
+            "Simulation complete".to_string(),
+        );
+
+        let mut console = LogConsole::new(buffer.clone());
+        console.poll();
+        let shown = |c: &LogConsole| c.records.iter().filter(|r| c.matches(r)).count();
+        assert_eq!(shown(&console), 2);
+        assert_eq!(console.warning_count(), 1);
+
+        console.search = "NORM".to_string();
+        assert_eq!(shown(&console), 1);
+
+        buffer.push(Level::ERROR, "chronophoton", "Blow-up".to_string());
+        console.poll();
+        assert_eq!(console.records.len(), 4);
+    }
+}
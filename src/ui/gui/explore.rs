@@ -0,0 +1,235 @@
+use crate::core::observables::{NumberOperator, PopulationOperator};
+use crate::core::systems::{DrivenCavity, DrivenTLS};
+use crate::core::{Hamiltonian, Observable, QuantumState};
+use crate::simulation::{suggest_timestep, SimulationBuilder, SimulationResults, TimeGrid};
+use crate::utils::{Error, Result};
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+pub const MAX_EXPLORE_DIM: usize = 16;
+const OUTPUT_POINTS: f64 = 400.0;
+const MAX_TIMESTEP: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExploreSystem {
+    DrivenTLS,
+    ParametricCavity,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExploreParams {
+    pub system: ExploreSystem,
+    pub drive: f64,
+    pub detuning: f64,
+    pub drive_frequency: f64,
+    pub duration: f64,
+    pub dim: usize,
+}
+
+impl Default for ExploreParams {
+    fn default() -> Self {
+        Self {
+            system: ExploreSystem::DrivenTLS,
+            drive: 0.5,
+            detuning: 0.0,
+            drive_frequency: 5.0,
+            duration: 20.0,
+            dim: 8,
+        }
+    }
+}
+
+impl ExploreParams {
+    pub fn simulate(&self) -> Result<SimulationResults> {
+        match self.system {
+            ExploreSystem::DrivenTLS => self.run(
+                DrivenTLS::new(
+                    self.drive_frequency + self.detuning,
+                    self.drive_frequency,
+                    self.drive,
+                ),
+                "p_excited",
+                PopulationOperator::new(2, 1)?,
+            ),
+            ExploreSystem::ParametricCavity => {
+                if !(3..=MAX_EXPLORE_DIM).contains(&self.dim) {
+                    return Err(Error::InvalidParameter(format!(
+                        "Explore mode supports cavity truncations between 3 and {}, got {}",
+                        MAX_EXPLORE_DIM, self.dim
+                    )));
+                }
+                self.run(
+                    DrivenCavity::new(
+                        self.drive_frequency / 2.0 + self.detuning,
+                        self.drive_frequency,
+                        self.drive,
+                        self.dim,
+                    ),
+                    "n",
+                    NumberOperator::new(self.dim),
+                )
+            }
+        }
+    }
+
+    fn run(
+        &self,
+        hamiltonian: impl Hamiltonian + 'static,
+        name: &str,
+        observable: impl Observable + 'static,
+    ) -> Result<SimulationResults> {
+        let dim = hamiltonian.dim();
+        let timestep =
+            suggest_timestep(&hamiltonian).map_or(MAX_TIMESTEP, |dt| dt.min(MAX_TIMESTEP));
+
+        SimulationBuilder::new()
+            .hamiltonian(hamiltonian)
+            .initial_state(QuantumState::ground_state(dim))
+            .duration(self.duration)
+            .timestep(timestep)
+            .time_grid(TimeGrid::uniform(self.duration / OUTPUT_POINTS))
+            .observable(name, observable)
+            .build()?
+            .run()
+    }
+}
+
+type Job = (ExploreParams, Result<SimulationResults>, Duration);
+
+pub struct Explorer {
+    params: ExploreParams,
+    submitted: Option<ExploreParams>,
+    pending: Option<Receiver<Job>>,
+    results: Option<SimulationResults>,
+    elapsed: Option<Duration>,
+    error: Option<String>,
+}
+
+impl Explorer {
+    pub fn new() -> Self {
+        Self {
+            params: ExploreParams::default(),
+            submitted: None,
+            pending: None,
+            results: None,
+            elapsed: None,
+            error: None,
+        }
+    }
+
+    fn poll(&mut self) {
+        let Some(rx) = &self.pending else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok((params, outcome, elapsed)) => {
+                self.pending = None;
+                self.elapsed = Some(elapsed);
+                match outcome {
+                    Ok(results) => {
+                        self.results = Some(results);
+                        self.error = None;
+                    }
+                    Err(e) => {
+                        tracing::warn!("Explore run failed for {:?}: {}", params, e);
+                        self.error = Some(e.to_string());
+                    }
+                }
+            }
+            Err(mpsc::TryRecvError::Empty) => {}
+            Err(mpsc::TryRecvError::Disconnected) => self.pending = None,
+        }
+    }
+
+    fn submit_if_changed(&mut self, ctx: &egui::Context) {
+        if self.pending.is_some() || self.submitted.as_ref() == Some(&self.params) {
+            return;
+        }
+
+        let params = self.params.clone();
+        self.submitted = Some(params.clone());
+        let (tx, rx) = mpsc::channel();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let start = Instant::now();
+            let outcome = params.simulate();
+            tx.send((params, outcome, start.elapsed())).ok();
+            ctx.request_repaint();
+        });
+        self.pending = Some(rx);
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        self.poll();
+
+        let params = &mut self.params;
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut params.system, ExploreSystem::DrivenTLS, "Driven TLS");
+            ui.selectable_value(
+                &mut params.system,
+                ExploreSystem::ParametricCavity,
+                "Parametric cavity",
+            );
+        });
+        ui.add(egui::Slider::new(&mut params.drive, 0.0..=2.0).text("drive amplitude"));
+        ui.add(egui::Slider::new(&mut params.detuning, -2.0..=2.0).text("detuning"));
+        ui.add(egui::Slider::new(&mut params.drive_frequency, 0.5..=10.0).text("drive frequency"));
+        ui.add(egui::Slider::new(&mut params.duration, 1.0..=100.0).text("duration"));
+        if params.system == ExploreSystem::ParametricCavity {
+            ui.add(egui::Slider::new(&mut params.dim, 3..=MAX_EXPLORE_DIM).text("truncation"));
+        }
+
+        self.submit_if_changed(ui.ctx());
+
+        ui.horizontal(|ui| {
+            if self.pending.is_some() {
+                ui.spinner();
+            }
+            if let Some(elapsed) = self.elapsed {
+                ui.label(format!("last run {:.0} ms", elapsed.as_secs_f64() * 1e3));
+            }
+            if let Some(error) = &self.error {
+                ui.colored_label(ui.visuals().error_fg_color, error);
+            }
+        });
+
+        let Some(results) = &self.results else {
+            return;
+        };
+        Plot::new("explore_plot")
+            .legend(Legend::default())
+            .x_axis_label("t")
+            .show(ui, |plot_ui| {
+                for series in results.iter_series() {
+                    let points: PlotPoints = series.iter().map(|(t, v)| [t, v.re]).collect();
+                    plot_ui.line(Line::new(series.name.to_string(), points));
+                }
+            });
+    }
+}
+
+impl Default for Explorer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explore_simulations() {
+        let results = ExploreParams::default().simulate().unwrap();
+        assert_eq!(results.series("p_excited").unwrap().len(), 401);
+
+        let cavity = ExploreParams {
+            system: ExploreSystem::ParametricCavity,
+            dim: MAX_EXPLORE_DIM + 1,
+            ..ExploreParams::default()
+        };
+        assert!(cavity.simulate().is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::data::{Campaign, Config};
+use crate::simulation::{
+    Job, JobQueue, JobStatus, Scheduler, SimulationBuilder, SimulationResults,
+};
+use crate::utils::Result;
+use eframe::egui;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+const MAX_CONCURRENT_JOBS: usize = 2;
+
+pub enum JobOutput {
+    Simulation(SimulationResults),
+    Campaign {
+        output_dir: PathBuf,
+        succeeded: usize,
+        total: usize,
+    },
+}
+
+pub struct JobsPanel {
+    queue: Option<JobQueue>,
+    jobs: Vec<Arc<Job<JobOutput>>>,
+    campaign_path: String,
+    campaign_request: Option<PathBuf>,
+}
+
+impl JobsPanel {
+    pub fn new() -> Self {
+        Self {
+            queue: None,
+            jobs: Vec::new(),
+            campaign_path: String::new(),
+            campaign_request: None,
+        }
+    }
+
+    fn queue(&mut self) -> Result<&JobQueue> {
+        if self.queue.is_none() {
+            self.queue = Some(Scheduler::new(MAX_CONCURRENT_JOBS).queue()?);
+        }
+        Ok(self.queue.as_ref().expect("queue was just created"))
+    }
+
+    pub fn submit_simulation(&mut self, config: Config) -> Result<()> {
+        let name = config.simulation.name.clone();
+        let job = self.queue()?.submit(name, move |control| {
+            SimulationBuilder::from_config(&config)?
+                .with_record_states(true)
+                .with_control(control)
+                .run()
+                .map(JobOutput::Simulation)
+        });
+        tracing::info!("Queued simulation '{}' as job {}", job.name(), job.id());
+        self.jobs.push(job);
+        Ok(())
+    }
+
+    pub fn submit_campaign(&mut self, campaign: Campaign) -> Result<()> {
+        let name = format!("campaign {}", campaign.settings.name);
+        let job = self.queue()?.submit(name, move |control| {
+            let summary = campaign.run_with_control(&control)?;
+            control.check()?;
+            Ok(JobOutput::Campaign {
+                succeeded: summary.runs.len() - summary.failed().count(),
+                total: summary.runs.len(),
+                output_dir: summary.output_dir,
+            })
+        });
+        tracing::info!("Queued {} as job {}", job.name(), job.id());
+        self.jobs.push(job);
+        Ok(())
+    }
+
+    pub fn take_campaign_request(&mut self) -> Option<PathBuf> {
+        self.campaign_request.take()
+    }
+
+    pub fn cancel_all(&self) -> usize {
+        self.jobs
+            .iter()
+            .filter(|job| !job.status().is_done())
+            .inspect(|job| job.cancel())
+            .count()
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.jobs
+            .iter()
+            .filter(|job| !job.status().is_done())
+            .count()
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<SimulationResults> {
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.campaign_path).hint_text("campaign.toml"));
+            if ui.button("Queue campaign").clicked() && !self.campaign_path.is_empty() {
+                self.campaign_request = Some(PathBuf::from(&self.campaign_path));
+            }
+            if ui.button("Clear finished").clicked() {
+                self.jobs.retain(|job| !job.status().is_done());
+            }
+        });
+        ui.separator();
+
+        if self.jobs.is_empty() {
+            ui.label("No jobs. Use Queue in the config editor or queue a campaign above.");
+            return None;
+        }
+        if self.active_count() > 0 {
+            ui.ctx().request_repaint_after(Duration::from_millis(200));
+        }
+
+        let mut opened = None;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                egui::Grid::new("jobs_grid")
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for job in &self.jobs {
+                            let status = job.status();
+                            ui.label(format!("#{} {}", job.id(), job.name()));
+                            ui.label(status_label(status));
+                            ui.add(
+                                egui::ProgressBar::new(job.progress() as f32)
+                                    .desired_width(160.0)
+                                    .show_percentage(),
+                            );
+
+                            match status {
+                                JobStatus::Queued | JobStatus::Running => {
+                                    if ui.button("Cancel").clicked() {
+                                        job.cancel();
+                                    }
+                                }
+                                JobStatus::Finished => {
+                                    job.with_output(|output| match output {
+                                        JobOutput::Simulation(results) => {
+                                            if ui.button("Open results").clicked() {
+                                                opened = Some(results.clone());
+                                            }
+                                        }
+                                        JobOutput::Campaign {
+                                            output_dir,
+                                            succeeded,
+                                            total,
+                                        } => {
+                                            ui.label(format!(
+                                                "{}/{} succeeded in {}",
+                                                succeeded,
+                                                total,
+                                                output_dir.display()
+                                            ));
+                                        }
+                                    });
+                                }
+                                JobStatus::Failed => {
+                                    ui.colored_label(
+                                        ui.visuals().error_fg_color,
+                                        job.error().unwrap_or_default(),
+                                    );
+                                }
+                                JobStatus::Cancelled => {
+                                    ui.label("");
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        opened
+    }
+}
+
+impl Default for JobsPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn status_label(status: JobStatus) -> &'static str {
+    match status {
+        JobStatus::Queued => "queued",
+        JobStatus::Running => "running",
+        JobStatus::Finished => "finished",
+        JobStatus::Failed => "failed",
+        JobStatus::Cancelled => "cancelled",
+    }
+}
@@ -0,0 +1,265 @@
+use crate::ui::gui::safety::Budget;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const MAX_RECENT_FILES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum PanelKind {
+    ConfigEditor,
+    Plots,
+    StateViz,
+    LogConsole,
+    Explore,
+    Jobs,
+}
+
+impl PanelKind {
+    pub const ALL: [PanelKind; 6] = [
+        PanelKind::ConfigEditor,
+        PanelKind::Plots,
+        PanelKind::StateViz,
+        PanelKind::LogConsole,
+        PanelKind::Explore,
+        PanelKind::Jobs,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            PanelKind::ConfigEditor => "Config",
+            PanelKind::Plots => "Plots",
+            PanelKind::StateViz => "State",
+            PanelKind::LogConsole => "Log",
+            PanelKind::Explore => "Explore",
+            PanelKind::Jobs => "Jobs",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum DockSlot {
+    Left,
+    Right,
+    Bottom,
+    Center,
+}
+
+impl DockSlot {
+    pub const ALL: [DockSlot; 4] = [
+        DockSlot::Left,
+        DockSlot::Right,
+        DockSlot::Bottom,
+        DockSlot::Center,
+    ];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            DockSlot::Left => "Left",
+            DockSlot::Right => "Right",
+            DockSlot::Bottom => "Bottom",
+            DockSlot::Center => "Center",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemeChoice {
+    System,
+    Dark,
+    Light,
+}
+
+impl ThemeChoice {
+    pub fn preference(&self) -> egui::ThemePreference {
+        match self {
+            ThemeChoice::System => egui::ThemePreference::System,
+            ThemeChoice::Dark => egui::ThemePreference::Dark,
+            ThemeChoice::Light => egui::ThemePreference::Light,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PanelPlacement {
+    pub kind: PanelKind,
+    pub slot: DockSlot,
+    pub visible: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Layout {
+    pub panels: Vec<PanelPlacement>,
+    pub active: BTreeMap<DockSlot, PanelKind>,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        let placement = |kind, slot| PanelPlacement {
+            kind,
+            slot,
+            visible: true,
+        };
+        Self {
+            panels: vec![
+                placement(PanelKind::ConfigEditor, DockSlot::Left),
+                placement(PanelKind::Plots, DockSlot::Center),
+                placement(PanelKind::StateViz, DockSlot::Right),
+                placement(PanelKind::LogConsole, DockSlot::Bottom),
+                PanelPlacement {
+                    kind: PanelKind::Explore,
+                    slot: DockSlot::Center,
+                    visible: false,
+                },
+                PanelPlacement {
+                    kind: PanelKind::Jobs,
+                    slot: DockSlot::Bottom,
+                    visible: false,
+                },
+            ],
+            active: BTreeMap::new(),
+        }
+    }
+}
+
+impl Layout {
+    pub fn placement(&self, kind: PanelKind) -> Option<&PanelPlacement> {
+        self.panels.iter().find(|p| p.kind == kind)
+    }
+
+    fn placement_mut(&mut self, kind: PanelKind) -> &mut PanelPlacement {
+        if let Some(idx) = self.panels.iter().position(|p| p.kind == kind) {
+            return &mut self.panels[idx];
+        }
+        self.panels.push(PanelPlacement {
+            kind,
+            slot: DockSlot::Center,
+            visible: false,
+        });
+        self.panels.last_mut().expect("just pushed")
+    }
+
+    pub fn is_visible(&self, kind: PanelKind) -> bool {
+        self.placement(kind).is_some_and(|p| p.visible)
+    }
+
+    pub fn set_visible(&mut self, kind: PanelKind, visible: bool) {
+        self.placement_mut(kind).visible = visible;
+        if visible {
+            self.focus(kind);
+        }
+    }
+
+    pub fn move_to(&mut self, kind: PanelKind, slot: DockSlot) {
+        let placement = self.placement_mut(kind);
+        placement.slot = slot;
+        placement.visible = true;
+        self.focus(kind);
+    }
+
+    pub fn focus(&mut self, kind: PanelKind) {
+        if let Some(slot) = self.placement(kind).map(|p| p.slot) {
+            self.active.insert(slot, kind);
+        }
+    }
+
+    pub fn panels_in(&self, slot: DockSlot) -> Vec<PanelKind> {
+        self.panels
+            .iter()
+            .filter(|p| p.visible && p.slot == slot)
+            .map(|p| p.kind)
+            .collect()
+    }
+
+    pub fn active_in(&self, slot: DockSlot) -> Option<PanelKind> {
+        let panels = self.panels_in(slot);
+        self.active
+            .get(&slot)
+            .copied()
+            .filter(|kind| panels.contains(kind))
+            .or_else(|| panels.first().copied())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuiSettings {
+    pub layout: Layout,
+    pub theme: ThemeChoice,
+    pub recent_files: Vec<PathBuf>,
+    pub budget: Budget,
+    pub rerun_on_change: bool,
+}
+
+impl Default for GuiSettings {
+    fn default() -> Self {
+        Self {
+            layout: Layout::default(),
+            theme: ThemeChoice::System,
+            recent_files: Vec::new(),
+            budget: Budget::default(),
+            rerun_on_change: false,
+        }
+    }
+}
+
+impl GuiSettings {
+    pub fn push_recent(&mut self, path: &Path) {
+        self.recent_files.retain(|p| p != path);
+        self.recent_files.insert(0, path.to_path_buf());
+        self.recent_files.truncate(MAX_RECENT_FILES);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_and_focus_panels() {
+        let mut layout = Layout::default();
+        assert_eq!(
+            layout.active_in(DockSlot::Left),
+            Some(PanelKind::ConfigEditor)
+        );
+
+        layout.move_to(PanelKind::LogConsole, DockSlot::Left);
+        assert_eq!(
+            layout.panels_in(DockSlot::Left),
+            [PanelKind::ConfigEditor, PanelKind::LogConsole]
+        );
+        assert_eq!(
+            layout.active_in(DockSlot::Left),
+            Some(PanelKind::LogConsole)
+        );
+        assert!(layout.panels_in(DockSlot::Bottom).is_empty());
+
+        layout.set_visible(PanelKind::LogConsole, false);
+        assert_eq!(
+            layout.active_in(DockSlot::Left),
+            Some(PanelKind::ConfigEditor)
+        );
+    }
+
+    #[test]
+    fn test_recent_files_are_deduplicated() {
+        let mut settings = GuiSettings::default();
+        for i in 0..12 {
+            settings.push_recent(Path::new(&format!("config_{}.toml", i)));
+        }
+        settings.push_recent(Path::new("config_5.toml"));
+
+        assert_eq!(settings.recent_files.len(), MAX_RECENT_FILES);
+        assert_eq!(settings.recent_files[0], Path::new("config_5.toml"));
+        assert_eq!(
+            settings
+                .recent_files
+                .iter()
+                .filter(|p| p.as_path() == Path::new("config_5.toml"))
+                .count(),
+            1
+        );
+    }
+}
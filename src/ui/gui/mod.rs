@@ -1,6 +1,14 @@
 pub mod app;
+pub mod console;
+pub mod explore;
+pub mod jobs;
+pub mod layout;
+pub mod palette;
 pub mod plots;
+pub mod safety;
+pub mod session;
 pub mod state_viz;
+pub mod watch;
 pub mod widgets;
 
 pub use app::App;
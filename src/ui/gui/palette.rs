@@ -0,0 +1,212 @@
+use crate::ui::gui::layout::{PanelKind, ThemeChoice};
+use eframe::egui;
+use std::path::PathBuf;
+
+const MAX_VISIBLE_ENTRIES: usize = 12;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteCommand {
+    OpenConfig(PathBuf),
+    ApplyConfig,
+    RunSimulation,
+    QueueSimulation,
+    AbortAll,
+    SaveSession,
+    RestoreSession,
+    ExportAnimation,
+    TogglePanel(PanelKind),
+    SetTheme(ThemeChoice),
+    ResetLayout,
+}
+
+#[derive(Debug, Clone)]
+pub struct PaletteEntry {
+    pub label: String,
+    pub command: PaletteCommand,
+}
+
+impl PaletteEntry {
+    pub fn new(label: impl Into<String>, command: PaletteCommand) -> Self {
+        Self {
+            label: label.into(),
+            command,
+        }
+    }
+}
+
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let offset = candidate[position..].iter().position(|&c| c == q)?;
+        let index = position + offset;
+
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == index) {
+            score += 5;
+        }
+        if index == 0 || !candidate[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= offset.min(3) as i32;
+
+        previous = Some(index);
+        position = index + 1;
+    }
+
+    Some(score)
+}
+
+pub struct CommandPalette {
+    open: bool,
+    query: String,
+    selected: usize,
+}
+
+impl CommandPalette {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.query.clear();
+        self.selected = 0;
+    }
+
+    pub fn filter<'a>(&self, entries: &'a [PaletteEntry]) -> Vec<&'a PaletteEntry> {
+        let mut matches: Vec<(i32, &PaletteEntry)> = entries
+            .iter()
+            .filter_map(|entry| fuzzy_score(&self.query, &entry.label).map(|s| (s, entry)))
+            .collect();
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        matches.into_iter().map(|(_, entry)| entry).collect()
+    }
+
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        entries: &[PaletteEntry],
+    ) -> Option<PaletteCommand> {
+        if !self.open {
+            return None;
+        }
+
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+                i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+            )
+        });
+        if escape {
+            self.open = false;
+            return None;
+        }
+
+        let mut chosen = None;
+        egui::Window::new("Command palette")
+            .title_bar(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .fixed_size([420.0, 0.0])
+            .show(ctx, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text("Type a command")
+                        .desired_width(f32::INFINITY),
+                );
+                response.request_focus();
+                if response.changed() {
+                    self.selected = 0;
+                }
+
+                let matches = self.filter(entries);
+                if matches.is_empty() {
+                    ui.label("No matching commands");
+                    return;
+                }
+                let last = matches.len().min(MAX_VISIBLE_ENTRIES) - 1;
+                if down {
+                    self.selected += 1;
+                }
+                if up {
+                    self.selected = self.selected.saturating_sub(1);
+                }
+                self.selected = self.selected.min(last);
+
+                ui.separator();
+                for (index, entry) in matches.iter().take(MAX_VISIBLE_ENTRIES).enumerate() {
+                    let label = ui.selectable_label(index == self.selected, &entry.label);
+                    if label.clicked() {
+                        chosen = Some(entry.command.clone());
+                    }
+                }
+                if enter {
+                    chosen = Some(matches[self.selected].command.clone());
+                }
+            });
+
+        if chosen.is_some() {
+            self.open = false;
+        }
+        chosen
+    }
+}
+
+impl Default for CommandPalette {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Run simulation"), Some(0));
+        assert!(fuzzy_score("rsim", "Run simulation").is_some());
+        assert!(fuzzy_score("xyz", "Run simulation").is_none());
+        assert!(fuzzy_score("run", "Run simulation") > fuzzy_score("run", "Restore session"));
+    }
+
+    #[test]
+    fn test_filter_ranks_best_match_first() {
+        let entries = vec![
+            PaletteEntry::new(
+                "Toggle panel: Log",
+                PaletteCommand::TogglePanel(PanelKind::LogConsole),
+            ),
+            PaletteEntry::new("Run simulation", PaletteCommand::RunSimulation),
+            PaletteEntry::new("Reset layout", PaletteCommand::ResetLayout),
+        ];
+        let mut palette = CommandPalette::new();
+        palette.query = "log".to_string();
+        let matches = palette.filter(&entries);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            matches[0].command,
+            PaletteCommand::TogglePanel(PanelKind::LogConsole)
+        );
+
+        palette.query = "rs".to_string();
+        assert_eq!(
+            palette.filter(&entries)[0].command,
+            PaletteCommand::RunSimulation
+        );
+    }
+}
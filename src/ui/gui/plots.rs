@@ -1,8 +1,140 @@
-pub struct PlotWidget;
+use crate::simulation::{Spectrogram, VectorSeries};
+use eframe::egui;
+use egui_plot::{Legend, Line, Plot, PlotPoints};
+
+pub struct PlotWidget {
+    max_traces: usize,
+    offset: f64,
+}
 
 impl PlotWidget {
     pub fn new() -> Self {
-        Self
+        Self {
+            max_traces: 20,
+            offset: 0.1,
+        }
+    }
+
+    pub fn with_max_traces(mut self, max_traces: usize) -> Self {
+        self.max_traces = max_traces.max(1);
+        self
+    }
+
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn waterfall(&self, ui: &mut egui::Ui, series: &VectorSeries) {
+        let stride = series.len().div_ceil(self.max_traces).max(1);
+
+        Plot::new(format!("waterfall_{}", series.name))
+            .legend(Legend::default())
+            .x_axis_label("component")
+            .y_axis_label(format!("Re {} (offset by time)", series.name))
+            .show(ui, |plot_ui| {
+                for (k, (t, row)) in series.rows().enumerate().step_by(stride) {
+                    let base = (k / stride) as f64 * self.offset;
+                    let points: PlotPoints = row
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| [i as f64, base + v.re])
+                        .collect();
+                    plot_ui.line(Line::new(format!("t = {:.3}", t), points));
+                }
+            });
+    }
+
+    pub fn heatmap(&self, ui: &mut egui::Ui, series: &VectorSeries) {
+        ui.label(format!(
+            "{} ({} samples x {} components)",
+            series.name,
+            series.len(),
+            series.width
+        ));
+
+        paint_grid(
+            ui,
+            series.len(),
+            series.width,
+            |k, i| series.row(k)[i].re,
+            |k, i| {
+                format!(
+                    "t = {:.3}, n = {}: {:.4}",
+                    series.times[k],
+                    i,
+                    series.row(k)[i].re
+                )
+            },
+        );
+    }
+
+    pub fn spectrogram(&self, ui: &mut egui::Ui, name: &str, spectrogram: &Spectrogram) {
+        ui.label(format!("Spectrogram of {}", name));
+
+        let (frames, freqs) = spectrogram.power.dim();
+        paint_grid(
+            ui,
+            frames,
+            freqs,
+            |k, i| spectrogram.power[[k, i]].max(1e-300).log10(),
+            |k, i| {
+                format!(
+                    "t = {:.3}, ω = {:.4}: {:.3e}",
+                    spectrogram.times[k],
+                    spectrogram.frequencies[i],
+                    spectrogram.power[[k, i]]
+                )
+            },
+        );
+    }
+}
+
+fn paint_grid(
+    ui: &mut egui::Ui,
+    nx: usize,
+    ny: usize,
+    value: impl Fn(usize, usize) -> f64,
+    hover: impl Fn(usize, usize) -> String,
+) {
+    let size = egui::vec2(ui.available_width(), ui.available_height().max(200.0));
+    let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+    let rect = response.rect;
+    if nx == 0 || ny == 0 {
+        return;
+    }
+
+    let (v_min, v_max) = (0..nx)
+        .flat_map(|k| (0..ny).map(move |i| (k, i)))
+        .map(|(k, i)| value(k, i))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+    let span = if v_max > v_min { v_max - v_min } else { 1.0 };
+
+    let cell_w = rect.width() / nx as f32;
+    let cell_h = rect.height() / ny as f32;
+    for k in 0..nx {
+        for i in 0..ny {
+            let level = ((value(k, i) - v_min) / span) as f32;
+            let min = egui::pos2(
+                rect.left() + k as f32 * cell_w,
+                rect.bottom() - (i + 1) as f32 * cell_h,
+            );
+            painter.rect_filled(
+                egui::Rect::from_min_size(min, egui::vec2(cell_w.ceil(), cell_h.ceil())),
+                0.0,
+                egui::ecolor::Hsva::new(0.7 * (1.0 - level), 0.9, 0.9, 1.0),
+            );
+        }
+    }
+
+    if let Some(pos) = response.hover_pos() {
+        let k = ((pos.x - rect.left()) / cell_w) as usize;
+        let i = ((rect.bottom() - pos.y) / cell_h) as usize;
+        if k < nx && i < ny {
+            response.on_hover_text(hover(k, i));
+        }
     }
 }
 
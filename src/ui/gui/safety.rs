@@ -0,0 +1,202 @@
+use crate::data::{Campaign, Config};
+use crate::simulation::estimate::{format_seconds, megabytes};
+use crate::simulation::CostEstimate;
+use crate::utils::{performance, Result};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Budget {
+    pub max_seconds: f64,
+    pub max_memory_mb: f64,
+}
+
+impl Default for Budget {
+    fn default() -> Self {
+        Self {
+            max_seconds: 60.0,
+            max_memory_mb: 2048.0,
+        }
+    }
+}
+
+impl Budget {
+    pub fn violations(&self, estimate: &CostEstimate) -> Vec<String> {
+        self.time_violation(estimate.seconds)
+            .into_iter()
+            .chain(self.memory_violation(estimate.memory_bytes))
+            .collect()
+    }
+
+    pub fn time_violation(&self, seconds: f64) -> Option<String> {
+        (seconds > self.max_seconds).then(|| {
+            format!(
+                "Estimated run time {} exceeds the {} budget",
+                format_seconds(seconds),
+                format_seconds(self.max_seconds)
+            )
+        })
+    }
+
+    pub fn memory_violation(&self, memory_bytes: u64) -> Option<String> {
+        let memory_mb = megabytes(memory_bytes);
+        (memory_mb > self.max_memory_mb).then(|| {
+            format!(
+                "Estimated memory {:.1} MB exceeds the {:.0} MB budget",
+                memory_mb, self.max_memory_mb
+            )
+        })
+    }
+
+    pub fn editor(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Ask before runs longer than");
+            ui.add(
+                egui::DragValue::new(&mut self.max_seconds)
+                    .range(1.0..=f64::INFINITY)
+                    .suffix(" s"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Ask before runs using more than");
+            ui.add(
+                egui::DragValue::new(&mut self.max_memory_mb)
+                    .range(1.0..=f64::INFINITY)
+                    .suffix(" MB"),
+            );
+        });
+    }
+}
+
+pub enum Launch {
+    Run(Config),
+    Queue(Config),
+    Campaign(Campaign),
+}
+
+impl Launch {
+    pub fn title(&self) -> String {
+        match self {
+            Launch::Run(config) => format!("Run '{}'", config.simulation.name),
+            Launch::Queue(config) => format!("Queue '{}'", config.simulation.name),
+            Launch::Campaign(campaign) => format!(
+                "Queue campaign '{}' ({} simulations)",
+                campaign.settings.name,
+                campaign.simulations.len()
+            ),
+        }
+    }
+
+    // Read off the configs without building anything, so it is cheap enough for the
+    // UI thread. Runs may overlap, so a campaign needs the memory of its largest run.
+    pub fn memory_bytes(&self) -> u64 {
+        match self {
+            Launch::Run(config) | Launch::Queue(config) => {
+                performance::estimated_memory_bytes(config)
+            }
+            Launch::Campaign(campaign) => campaign
+                .simulations
+                .iter()
+                .map(performance::estimated_memory_bytes)
+                .max()
+                .unwrap_or(0),
+        }
+    }
+
+    // Builds every run and times a probe of it; call it off the UI thread.
+    pub fn estimate(&self) -> Result<CostEstimate> {
+        match self {
+            Launch::Run(config) | Launch::Queue(config) => CostEstimate::dry_run(config),
+            Launch::Campaign(campaign) => CostEstimate::dry_run_all(&campaign.simulations),
+        }
+    }
+}
+
+// What confirming a pending launch goes on to do.
+pub enum NextStep {
+    // The memory estimate was over budget; time the run next.
+    Probe,
+    Start(CostEstimate),
+}
+
+pub struct PendingLaunch {
+    pub launch: Launch,
+    pub next: NextStep,
+    pub violations: Vec<String>,
+}
+
+impl PendingLaunch {
+    // Returns Some(true) to go ahead and Some(false) to drop the launch.
+    pub fn show(&self, ctx: &egui::Context) -> Option<bool> {
+        let mut decision = None;
+        let modal = egui::Modal::new(egui::Id::new("confirm_launch")).show(ctx, |ui| {
+            ui.heading(self.launch.title());
+            match &self.next {
+                NextStep::Probe => ui.label(format!(
+                    "Memory estimate: {:.1} MB. The run time is timed after you continue.",
+                    megabytes(self.launch.memory_bytes())
+                )),
+                NextStep::Start(estimate) => {
+                    ui.label(format!("Dry run estimate: {}", estimate.describe()))
+                }
+            };
+            for violation in &self.violations {
+                ui.colored_label(ui.visuals().warn_fg_color, violation);
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                let proceed = match self.next {
+                    NextStep::Probe => "Continue anyway",
+                    NextStep::Start(_) => "Start anyway",
+                };
+                if ui.button(proceed).clicked() {
+                    decision = Some(true);
+                }
+                if ui.button("Cancel").clicked() {
+                    decision = Some(false);
+                }
+            });
+        });
+        if decision.is_none() && modal.should_close() {
+            decision = Some(false);
+        }
+        decision
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_flags_each_exceeded_limit() {
+        let budget = Budget::default();
+        let cheap = CostEstimate {
+            seconds: 1.0,
+            memory_bytes: 1024,
+        };
+        assert!(budget.violations(&cheap).is_empty());
+
+        let heavy = CostEstimate {
+            seconds: 600.0,
+            memory_bytes: 4096 * 1024 * 1024,
+        };
+        let violations = budget.violations(&heavy);
+        assert_eq!(violations.len(), 2);
+        assert!(violations[0].contains("10.0 min"), "{}", violations[0]);
+        assert!(violations[1].contains("4096.0 MB"), "{}", violations[1]);
+    }
+
+    #[test]
+    fn test_memory_check_reads_the_config_without_building_it() {
+        let mut config = Config::generate_template("driven_tls").unwrap();
+        config.system.hilbert_dim = 100_000;
+        // Far too large to build in a test; the memory estimate must not try.
+        let launch = Launch::Run(config);
+        let memory = launch.memory_bytes();
+        let violation = Budget::default().memory_violation(memory).unwrap();
+        assert!(violation.contains("MB budget"), "{}", violation);
+        assert!(Budget::default().time_violation(1.0).is_none());
+    }
+}
@@ -0,0 +1,124 @@
+use crate::simulation::SimulationResults;
+use crate::ui::gui::layout::Layout;
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+// Session files start with this magic and a little-endian format version, ahead
+// of the bincode payload, so a stale or foreign file is rejected before decoding.
+// Bump the version whenever `Session` or anything it embeds changes shape.
+const SESSION_MAGIC: &[u8; 8] = b"CPSESSN\0";
+const SESSION_FORMAT_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub config_path: Option<PathBuf>,
+    pub config_text: String,
+    pub results: Option<SimulationResults>,
+    pub layout: Layout,
+    pub hidden_series: BTreeSet<String>,
+}
+
+impl Session {
+    pub fn new(config_text: String, layout: Layout) -> Self {
+        Self {
+            config_path: None,
+            config_text,
+            results: None,
+            layout,
+            hidden_series: BTreeSet::new(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut data = SESSION_MAGIC.to_vec();
+        data.extend_from_slice(&SESSION_FORMAT_VERSION.to_le_bytes());
+        bincode::serde::encode_into_std_write(self, &mut data, bincode::config::standard())
+            .map_err(|e| Error::Serialization(e.to_string()))?;
+        std::fs::write(path, data)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        let not_a_session = |reason: String| {
+            Error::Serialization(format!(
+                "{} is not a ChronoPhoton session file: {}",
+                path.display(),
+                reason
+            ))
+        };
+
+        let payload = data
+            .strip_prefix(SESSION_MAGIC.as_slice())
+            .ok_or_else(|| not_a_session("missing session header".to_string()))?;
+        let (version, payload) = payload
+            .split_first_chunk::<4>()
+            .ok_or_else(|| not_a_session("truncated session header".to_string()))?;
+        let version = u32::from_le_bytes(*version);
+        if version != SESSION_FORMAT_VERSION {
+            return Err(Error::Serialization(format!(
+                "Session {} uses format version {}, expected {}",
+                path.display(),
+                version,
+                SESSION_FORMAT_VERSION
+            )));
+        }
+
+        let (session, _): (Self, _) =
+            bincode::serde::decode_from_slice(payload, bincode::config::standard())
+                .map_err(|e| not_a_session(e.to_string()))?;
+        Ok(session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::gui::layout::{DockSlot, PanelKind};
+    use num_complex::Complex64;
+
+    #[test]
+    fn test_session_roundtrip() {
+        let dir = std::env::temp_dir().join("chronophoton_session_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("analysis.cpsession");
+
+        let mut results = SimulationResults::new();
+        for i in 0..3 {
+            let t = i as f64;
+            results.add_observable("n", t, Complex64::new(t * t, 0.0));
+            results.add_snapshot(t, &[Complex64::new(1.0, 0.0)]);
+        }
+        let mut layout = Layout::default();
+        layout.move_to(PanelKind::LogConsole, DockSlot::Right);
+
+        let mut session = Session::new("[simulation]\nname = \"test\"\n".to_string(), layout);
+        session.config_path = Some(PathBuf::from("configs/test.toml"));
+        session.results = Some(results);
+        session.hidden_series.insert("n".to_string());
+        session.save(&path).unwrap();
+
+        let restored = Session::load(&path).unwrap();
+        assert_eq!(restored.config_text, session.config_text);
+        assert_eq!(restored.layout, session.layout);
+        assert_eq!(restored.hidden_series, session.hidden_series);
+        let results = restored.results.unwrap();
+        assert_eq!(
+            results.series("n").unwrap().to_vec_real(),
+            vec![0.0, 1.0, 4.0]
+        );
+        assert_eq!(results.snapshots().len(), 3);
+
+        std::fs::write(&path, b"not a session").unwrap();
+        assert!(Session::load(&path).is_err());
+
+        let mut stale = SESSION_MAGIC.to_vec();
+        stale.extend_from_slice(&1u32.to_le_bytes());
+        std::fs::write(&path, stale).unwrap();
+        let err = Session::load(&path).unwrap_err().to_string();
+        assert!(err.contains("format version 1"), "{}", err);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,89 @@
+use crate::utils::{Error, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+pub struct ConfigWatcher {
+    path: PathBuf,
+    events: Receiver<notify::Result<Event>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl ConfigWatcher {
+    // Editors often save by writing a temporary file and renaming it over the
+    // original, which drops a watch on the file itself, so the parent directory
+    // is watched instead and events are filtered by name.
+    pub fn new(path: &Path) -> Result<Self> {
+        let path = std::path::absolute(path)?;
+        let dir = path
+            .parent()
+            .ok_or_else(|| Error::Config(format!("{} has no parent directory", path.display())))?;
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            tx.send(event).ok();
+        })
+        .map_err(|e| Error::Config(format!("Cannot watch {}: {}", path.display(), e)))?;
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| Error::Config(format!("Cannot watch {}: {}", dir.display(), e)))?;
+        Ok(Self {
+            path,
+            events: rx,
+            _watcher: watcher,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Drains pending events so a burst from a single save is reported once.
+    pub fn changed(&self) -> bool {
+        let mut changed = false;
+        for event in self.events.try_iter() {
+            match event {
+                Ok(event) => {
+                    changed |= matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                        && event.paths.iter().any(|p| p == &self.path);
+                }
+                Err(e) => tracing::warn!("Watching {} failed: {}", self.path.display(), e),
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_reports_changes_to_the_watched_file_only() {
+        let dir = std::env::temp_dir().join(format!("chronophoton_watch_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, "a = 1").unwrap();
+
+        let watcher = ConfigWatcher::new(&path).unwrap();
+        let changed_within = |timeout: Duration| {
+            let start = Instant::now();
+            while start.elapsed() < timeout {
+                if watcher.changed() {
+                    return true;
+                }
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            false
+        };
+
+        std::fs::write(dir.join("other.toml"), "b = 2").unwrap();
+        assert!(!changed_within(Duration::from_millis(300)));
+
+        std::fs::write(dir.join("config.toml.tmp"), "a = 2").unwrap();
+        std::fs::rename(dir.join("config.toml.tmp"), &path).unwrap();
+        assert!(changed_within(Duration::from_secs(5)));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
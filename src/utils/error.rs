@@ -1,3 +1,5 @@
+use std::fmt;
+use std::path::PathBuf;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -7,6 +9,12 @@ pub enum Error {
     #[error("Configuration error: {0}")]
     Config(String),
 
+    #[error("Configuration error at {location}: {message}")]
+    ConfigAt {
+        location: ConfigLocation,
+        message: String,
+    },
+
     #[error("Hamiltonian error: {0}")]
     Hamiltonian(String),
 
@@ -31,9 +39,18 @@ pub enum Error {
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
 
+    #[error("Validation failed:\n{0}")]
+    Validation(Diagnostics),
+
     #[error("Not implemented: {0}")]
     NotImplemented(String),
 
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
+    #[error("Preempted by {signal} (checkpoint flushed: {flushed})")]
+    Preempted { signal: String, flushed: bool },
+
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -54,4 +71,266 @@ impl Error {
     pub fn dimension_mismatch(expected: usize, actual: usize) -> Self {
         Error::DimensionMismatch { expected, actual }
     }
+
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Error::Config(_) | Error::ConfigAt { .. } => ErrorCode::Config,
+            Error::Hamiltonian(_) => ErrorCode::Hamiltonian,
+            Error::DimensionMismatch { .. } => ErrorCode::DimensionMismatch,
+            Error::Gpu(_) => ErrorCode::Gpu,
+            Error::Integration(_) => ErrorCode::Integration,
+            Error::Numerical(_) => ErrorCode::Numerical,
+            Error::Io(_) => ErrorCode::Io,
+            Error::Serialization(_) => ErrorCode::Serialization,
+            Error::InvalidParameter(_) => ErrorCode::InvalidParameter,
+            Error::Validation(_) => ErrorCode::Validation,
+            Error::NotImplemented(_) => ErrorCode::NotImplemented,
+            Error::Cancelled(_) => ErrorCode::Cancelled,
+            Error::Preempted { .. } => ErrorCode::Preempted,
+            Error::Other(_) => ErrorCode::Other,
+        }
+    }
+
+    pub fn location(&self) -> Option<&ConfigLocation> {
+        match self {
+            Error::ConfigAt { location, .. } => Some(location),
+            _ => None,
+        }
+    }
+
+    pub fn diagnostics(&self) -> Diagnostics {
+        match self {
+            Error::Validation(diagnostics) => diagnostics.clone(),
+            Error::ConfigAt { location, message } => {
+                let mut diagnostics = Diagnostics::new();
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Error,
+                    code: ErrorCode::Config,
+                    message: message.clone(),
+                    location: Some(location.clone()),
+                });
+                diagnostics
+            }
+            other => {
+                let mut diagnostics = Diagnostics::new();
+                diagnostics.error(other.code(), other.to_string());
+                diagnostics
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    Config,
+    Hamiltonian,
+    DimensionMismatch,
+    Gpu,
+    Integration,
+    Numerical,
+    Io,
+    Serialization,
+    InvalidParameter,
+    Validation,
+    NotImplemented,
+    Cancelled,
+    Preempted,
+    Other,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::Config => "E0001_CONFIG",
+            ErrorCode::Hamiltonian => "E0002_HAMILTONIAN",
+            ErrorCode::DimensionMismatch => "E0003_DIMENSION_MISMATCH",
+            ErrorCode::Gpu => "E0004_GPU",
+            ErrorCode::Integration => "E0005_INTEGRATION",
+            ErrorCode::Numerical => "E0006_NUMERICAL",
+            ErrorCode::Io => "E0007_IO",
+            ErrorCode::Serialization => "E0008_SERIALIZATION",
+            ErrorCode::InvalidParameter => "E0009_INVALID_PARAMETER",
+            ErrorCode::Validation => "E0010_VALIDATION",
+            ErrorCode::NotImplemented => "E0011_NOT_IMPLEMENTED",
+            ErrorCode::Cancelled => "E0012_CANCELLED",
+            ErrorCode::Preempted => "E0013_PREEMPTED",
+            ErrorCode::Other => "E0099_OTHER",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigLocation {
+    pub file: Option<PathBuf>,
+    pub key: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+}
+
+impl fmt::Display for ConfigLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut wrote = false;
+        if let Some(file) = &self.file {
+            write!(f, "{}", file.display())?;
+            wrote = true;
+        }
+        if let Some(line) = self.line {
+            write!(f, "{}{}", if wrote { ":" } else { "line " }, line)?;
+            if let Some(column) = self.column {
+                write!(f, ":{}", column)?;
+            }
+            wrote = true;
+        }
+        if let Some(key) = &self.key {
+            write!(f, "{}key `{}`", if wrote { " " } else { "" }, key)?;
+            wrote = true;
+        }
+        if !wrote {
+            f.write_str("<unknown>")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: ErrorCode,
+    pub message: String,
+    pub location: Option<ConfigLocation>,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}[{}]: {}", level, self.code, self.message)?;
+        if let Some(location) = &self.location {
+            write!(f, " ({})", location)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    items: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.items.push(diagnostic);
+    }
+
+    pub fn error(&mut self, code: ErrorCode, message: impl Into<String>) {
+        self.push(Diagnostic {
+            severity: Severity::Error,
+            code,
+            message: message.into(),
+            location: None,
+        });
+    }
+
+    pub fn warning(&mut self, code: ErrorCode, message: impl Into<String>) {
+        self.push(Diagnostic {
+            severity: Severity::Warning,
+            code,
+            message: message.into(),
+            location: None,
+        });
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.items.iter().any(|d| d.severity == Severity::Error)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.items.iter()
+    }
+
+    pub fn into_result(self) -> Result<()> {
+        if self.has_errors() {
+            Err(Error::Validation(self))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, diagnostic) in self.items.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "  {}", diagnostic)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(Error::config("x").code(), ErrorCode::Config);
+        assert_eq!(
+            Error::dimension_mismatch(2, 3).code().as_str(),
+            "E0003_DIMENSION_MISMATCH"
+        );
+    }
+
+    #[test]
+    fn test_diagnostics_into_result() {
+        let mut diagnostics = Diagnostics::new();
+        diagnostics.warning(ErrorCode::Numerical, "large timestep");
+        assert!(diagnostics.clone().into_result().is_ok());
+
+        diagnostics.error(ErrorCode::DimensionMismatch, "bad dims");
+        let err = diagnostics.into_result().unwrap_err();
+        assert_eq!(err.diagnostics().len(), 2);
+        assert!(err.to_string().contains("E0003_DIMENSION_MISMATCH"));
+    }
+
+    #[test]
+    fn test_location_display() {
+        let location = ConfigLocation {
+            file: Some(PathBuf::from("run.toml")),
+            key: Some("system.parameters".to_string()),
+            line: Some(12),
+            column: Some(1),
+        };
+        assert_eq!(
+            location.to_string(),
+            "run.toml:12:1 key `system.parameters`"
+        );
+    }
 }
@@ -0,0 +1,82 @@
+use crate::utils::Error;
+use serde::Serialize;
+
+pub const SUCCESS: i32 = 0;
+pub const FAILURE: i32 = 1;
+pub const INVALID_CONFIG: i32 = 2;
+pub const PREEMPTED: i32 = 75;
+pub const PREEMPTED_UNFLUSHED: i32 = 143;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExitCodeInfo {
+    pub code: i32,
+    pub name: &'static str,
+    pub requeue: bool,
+    pub description: &'static str,
+}
+
+pub fn exit_codes() -> Vec<ExitCodeInfo> {
+    vec![
+        ExitCodeInfo {
+            code: SUCCESS,
+            name: "success",
+            requeue: false,
+            description: "Run finished and all outputs were written",
+        },
+        ExitCodeInfo {
+            code: FAILURE,
+            name: "failure",
+            requeue: false,
+            description: "Run failed; see the log for the error code",
+        },
+        ExitCodeInfo {
+            code: INVALID_CONFIG,
+            name: "invalid_config",
+            requeue: false,
+            description: "Configuration or command line was rejected before running",
+        },
+        ExitCodeInfo {
+            code: PREEMPTED,
+            name: "preempted",
+            requeue: true,
+            description: "Stopped by a preemption signal after flushing the checkpoint; \
+                          safe to requeue and resume",
+        },
+        ExitCodeInfo {
+            code: PREEMPTED_UNFLUSHED,
+            name: "preempted_unflushed",
+            requeue: true,
+            description: "Stopped by a preemption signal but the grace period expired; \
+                          resume uses the last periodic checkpoint",
+        },
+    ]
+}
+
+pub fn code_for(error: &Error) -> i32 {
+    match error {
+        Error::Preempted { flushed: true, .. } => PREEMPTED,
+        Error::Preempted { flushed: false, .. } => PREEMPTED_UNFLUSHED,
+        Error::Config(_) | Error::ConfigAt { .. } | Error::Validation(_) => INVALID_CONFIG,
+        _ => FAILURE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_are_unique_and_mapped() {
+        let codes = exit_codes();
+        for (i, a) in codes.iter().enumerate() {
+            assert!(codes[i + 1..].iter().all(|b| b.code != a.code));
+        }
+        let preempted = Error::Preempted {
+            signal: "SIGTERM".to_string(),
+            flushed: true,
+        };
+        assert_eq!(code_for(&preempted), PREEMPTED);
+        assert_eq!(code_for(&Error::Config("bad".to_string())), INVALID_CONFIG);
+        assert_eq!(code_for(&Error::Numerical("nan".to_string())), FAILURE);
+    }
+}
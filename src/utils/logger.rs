@@ -1,13 +1,184 @@
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::{fmt, fmt::MakeWriter, prelude::*, EnvFilter, Layer};
 
-pub fn init() -> anyhow::Result<()> {
-    let filter = EnvFilter::try_from_default_env()
+const CONSOLE_CAPACITY: usize = 5000;
+
+static LOG_FILE: OnceLock<Mutex<Option<File>>> = OnceLock::new();
+static CONSOLE: OnceLock<Arc<LogBuffer>> = OnceLock::new();
+
+fn log_file() -> &'static Mutex<Option<File>> {
+    LOG_FILE.get_or_init(|| Mutex::new(None))
+}
+
+pub fn log_to_file(path: &Path) -> std::io::Result<()> {
+    let file = File::create(path)?;
+    *log_file().lock().unwrap() = Some(file);
+    Ok(())
+}
+
+struct FileSink;
+
+impl Write for FileSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match log_file().lock().unwrap().as_mut() {
+            Some(file) => file.write(buf),
+            None => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match log_file().lock().unwrap().as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for FileSink {
+    type Writer = FileSink;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        FileSink
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    pub seq: u64,
+    pub time: chrono::DateTime<chrono::Utc>,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default)]
+struct LogRing {
+    records: VecDeque<LogRecord>,
+    next_seq: u64,
+}
+
+#[derive(Debug)]
+pub struct LogBuffer {
+    capacity: usize,
+    ring: Mutex<LogRing>,
+}
+
+impl LogBuffer {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ring: Mutex::new(LogRing::default()),
+        }
+    }
+
+    pub fn push(&self, level: Level, target: &str, message: String) {
+        let mut ring = self.ring.lock().unwrap();
+        let seq = ring.next_seq;
+        ring.next_seq += 1;
+        if ring.records.len() == self.capacity {
+            ring.records.pop_front();
+        }
+        ring.records.push_back(LogRecord {
+            seq,
+            time: chrono::Utc::now(),
+            level,
+            target: target.to_string(),
+            message,
+        });
+    }
+
+    pub fn records_since(&self, seq: u64) -> Vec<LogRecord> {
+        let ring = self.ring.lock().unwrap();
+        ring.records
+            .iter()
+            .filter(|r| r.seq >= seq)
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.ring.lock().unwrap().records.clear();
+    }
+}
+
+pub fn console() -> Arc<LogBuffer> {
+    CONSOLE
+        .get_or_init(|| Arc::new(LogBuffer::new(CONSOLE_CAPACITY)))
+        .clone()
+}
+
+pub struct ConsoleLayer {
+    buffer: Arc<LogBuffer>,
+}
+
+impl ConsoleLayer {
+    pub fn new(buffer: Arc<LogBuffer>) -> Self {
+        Self { buffer }
+    }
+}
+
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: Vec<String>,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push(format!("{}={:?}", field.name(), value));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push(format!("{}={}", field.name(), value));
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for ConsoleLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let mut message = visitor.message;
+        for field in visitor.fields {
+            message.push(' ');
+            message.push_str(&field);
+        }
+
+        let metadata = event.metadata();
+        self.buffer
+            .push(*metadata.level(), metadata.target(), message);
+    }
+}
+
+pub type ProfileGuard = tracing_flame::FlushGuard<std::io::BufWriter<File>>;
+
+fn default_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env()
         .or_else(|_| EnvFilter::try_new("chronophoton=info"))
-        .unwrap();
+        .unwrap()
+}
 
+pub fn init() -> anyhow::Result<()> {
     tracing_subscriber::registry()
-        .with(filter)
+        .with(default_filter())
         .with(fmt::layer())
+        .with(fmt::layer().with_ansi(false).with_writer(FileSink))
+        .with(ConsoleLayer::new(console()))
         .init();
 
     Ok(())
@@ -19,7 +190,87 @@ pub fn init_with_filter(filter: &str) -> anyhow::Result<()> {
     tracing_subscriber::registry()
         .with(filter)
         .with(fmt::layer())
+        .with(fmt::layer().with_ansi(false).with_writer(FileSink))
+        .with(ConsoleLayer::new(console()))
         .init();
 
     Ok(())
 }
+
+pub fn init_profiled(path: &Path) -> anyhow::Result<ProfileGuard> {
+    let (flame, guard) = tracing_flame::FlameLayer::with_file(path)?;
+    let flame = flame
+        .with_threads_collapsed(true)
+        .with_module_path(false)
+        .with_file_and_line(false);
+    let spans =
+        tracing_subscriber::filter::Targets::new().with_target("chronophoton", Level::TRACE);
+
+    tracing_subscriber::registry()
+        .with(flame.with_filter(spans))
+        .with(fmt::layer().with_filter(default_filter()))
+        .with(
+            fmt::layer()
+                .with_ansi(false)
+                .with_writer(FileSink)
+                .with_filter(default_filter()),
+        )
+        .with(ConsoleLayer::new(console()).with_filter(default_filter()))
+        .init();
+
+    Ok(guard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_console_layer_captures_events() {
+        let buffer = Arc::new(LogBuffer::new(2));
+        let subscriber = tracing_subscriber::registry().with(ConsoleLayer::new(buffer.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("first");
+            tracing::warn!(norm = 1.5, "Norm drift");
+            tracing::error!("third");
+        });
+
+        let records = buffer.records_since(0);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].level, Level::WARN);
+        assert_eq!(records[0].message, "Norm drift norm=1.5");
+        assert_eq!(buffer.records_since(2).len(), 1);
+    }
+
+    #[test]
+    fn test_flame_layer_records_hot_path_spans() {
+        use crate::core::integrator::{Integrator, RK4Integrator};
+        use crate::core::systems::DrivenTLS;
+        use crate::core::QuantumState;
+
+        let path =
+            std::env::temp_dir().join(format!("chronophoton_flame_{}.folded", std::process::id()));
+        let (flame, guard) = tracing_flame::FlameLayer::with_file(&path).unwrap();
+        let flame = flame
+            .with_threads_collapsed(true)
+            .with_module_path(false)
+            .with_file_and_line(false);
+        let spans =
+            tracing_subscriber::filter::Targets::new().with_target("chronophoton", Level::TRACE);
+        let subscriber = tracing_subscriber::registry().with(flame.with_filter(spans));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let mut state = QuantumState::ground_state(2);
+            RK4Integrator::new()
+                .step(&DrivenTLS::new(5.0, 5.0, 0.5), &mut state, 0.0, 0.01)
+                .unwrap();
+        });
+        guard.flush().unwrap();
+
+        let folded = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert!(folded.contains("integrator_step; hamiltonian_compute"));
+        assert!(folded.contains("integrator_step; derivative"));
+    }
+}
@@ -1,4 +1,5 @@
-use ndarray::{Array2, ArrayView2};
+use crate::utils::{Error, Result};
+use ndarray::{Array1, Array2, ArrayView2};
 use num_complex::Complex64;
 
 pub fn is_hermitian(matrix: &ArrayView2<Complex64>, tol: f64) -> bool {
@@ -63,6 +64,311 @@ pub fn frobenius_norm(matrix: &ArrayView2<Complex64>) -> f64 {
     matrix.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt()
 }
 
+pub fn spectral_norm(matrix: &ArrayView2<Complex64>, max_iterations: usize, tol: f64) -> f64 {
+    let n = matrix.ncols();
+    if n == 0 {
+        return 0.0;
+    }
+
+    let vector_norm = |v: &Array1<Complex64>| v.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+    let mut v = Array1::from_shape_fn(n, |i| {
+        Complex64::from_polar(1.0 + i as f64 / n as f64, 0.7 * i as f64)
+    });
+    v /= Complex64::new(vector_norm(&v), 0.0);
+
+    let adjoint = matrix.t().mapv(|x| x.conj());
+    let mut estimate = 0.0;
+    for _ in 0..max_iterations {
+        let w = matrix.dot(&v);
+        let sigma = vector_norm(&w);
+        let u = adjoint.dot(&w);
+        let u_norm = vector_norm(&u);
+        if sigma == 0.0 || u_norm == 0.0 {
+            return sigma;
+        }
+        v = u / Complex64::new(u_norm, 0.0);
+
+        if (sigma - estimate).abs() <= tol * sigma {
+            return sigma;
+        }
+        estimate = sigma;
+    }
+    estimate
+}
+
+pub fn hermitian_eigen(matrix: &ArrayView2<Complex64>) -> (Vec<f64>, Array2<Complex64>) {
+    let n = matrix.nrows();
+    let mut a = matrix.to_owned();
+    let mut v = identity(n);
+    let scale = frobenius_norm(matrix).max(f64::MIN_POSITIVE);
+
+    for _ in 0..100 {
+        let off: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[[i, j]].norm_sqr())
+            .sum::<f64>()
+            .sqrt();
+        if off <= 1e-14 * scale {
+            break;
+        }
+
+        for p in 0..n {
+            for q in p + 1..n {
+                let apq = a[[p, q]];
+                if apq.norm() <= 1e-300 {
+                    continue;
+                }
+                let phase = Complex64::from_polar(1.0, -apq.arg());
+                let theta = 0.5 * (2.0 * apq.norm()).atan2(a[[q, q]].re - a[[p, p]].re);
+                let (s, c) = theta.sin_cos();
+
+                for k in 0..n {
+                    let (akp, akq) = (a[[k, p]], a[[k, q]]);
+                    a[[k, p]] = akp * c - akq * phase * s;
+                    a[[k, q]] = akp * s + akq * phase * c;
+                    let (vkp, vkq) = (v[[k, p]], v[[k, q]]);
+                    v[[k, p]] = vkp * c - vkq * phase * s;
+                    v[[k, q]] = vkp * s + vkq * phase * c;
+                }
+                for k in 0..n {
+                    let (apk, aqk) = (a[[p, k]], a[[q, k]]);
+                    a[[p, k]] = apk * c - aqk * phase.conj() * s;
+                    a[[q, k]] = apk * s + aqk * phase.conj() * c;
+                }
+            }
+        }
+    }
+
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&i, &j| a[[i, i]].re.total_cmp(&a[[j, j]].re));
+    let eigenvalues = order.iter().map(|&i| a[[i, i]].re).collect();
+    let eigenvectors = Array2::from_shape_fn((n, n), |(row, col)| v[[row, order[col]]]);
+    (eigenvalues, eigenvectors)
+}
+
+pub struct SpectralDecomposition {
+    pub eigenvalues: Vec<f64>,
+    pub eigenvectors: Array2<Complex64>,
+}
+
+impl SpectralDecomposition {
+    pub fn new(matrix: &ArrayView2<Complex64>) -> Self {
+        let (eigenvalues, eigenvectors) = hermitian_eigen(matrix);
+        Self {
+            eigenvalues,
+            eigenvectors,
+        }
+    }
+
+    pub fn dim(&self) -> usize {
+        self.eigenvalues.len()
+    }
+
+    pub fn evolve(&self, psi: &Array1<Complex64>, t: f64) -> Array1<Complex64> {
+        let mut coefficients = self.eigenvectors.t().mapv(|x| x.conj()).dot(psi);
+        for (c, &lambda) in coefficients.iter_mut().zip(&self.eigenvalues) {
+            *c *= Complex64::from_polar(1.0, -lambda * t);
+        }
+        self.eigenvectors.dot(&coefficients)
+    }
+
+    pub fn propagator(&self, t: f64) -> Array2<Complex64> {
+        let phases: Vec<Complex64> = self
+            .eigenvalues
+            .iter()
+            .map(|&lambda| Complex64::from_polar(1.0, -lambda * t))
+            .collect();
+        let scaled = Array2::from_shape_fn(self.eigenvectors.dim(), |(i, k)| {
+            self.eigenvectors[[i, k]] * phases[k]
+        });
+        scaled.dot(&self.eigenvectors.t().mapv(|x| x.conj()))
+    }
+}
+
+pub fn complex_eigenvalues(matrix: &ArrayView2<Complex64>) -> Result<Vec<Complex64>> {
+    let n = matrix.nrows();
+    let mut a = hessenberg(matrix);
+    let mut eigenvalues = Vec::with_capacity(n);
+    let mut hi = n;
+    let mut iterations = 0;
+
+    while hi > 0 {
+        let last = hi - 1;
+        let mut lo = last;
+        while lo > 0 {
+            let scale = a[[lo, lo]].norm() + a[[lo - 1, lo - 1]].norm();
+            if a[[lo, lo - 1]].norm() <= f64::EPSILON * scale.max(f64::MIN_POSITIVE) {
+                a[[lo, lo - 1]] = Complex64::new(0.0, 0.0);
+                break;
+            }
+            lo -= 1;
+        }
+        if lo == last {
+            eigenvalues.push(a[[last, last]]);
+            hi -= 1;
+            iterations = 0;
+            continue;
+        }
+
+        iterations += 1;
+        if iterations > 30 * n {
+            return Err(Error::Numerical(
+                "Eigenvalue iteration did not converge".to_string(),
+            ));
+        }
+        let shift = if iterations % 11 == 0 {
+            a[[last, last]] + a[[last, last - 1]].norm()
+        } else {
+            wilkinson_shift(&a, last)
+        };
+        qr_step(&mut a, lo, last, shift);
+    }
+
+    Ok(eigenvalues)
+}
+
+pub fn eigenvector(matrix: &ArrayView2<Complex64>, eigenvalue: Complex64) -> Array1<Complex64> {
+    let n = matrix.nrows();
+    let offset = 1e-10 * frobenius_norm(matrix).max(1.0);
+    let mut shifted = matrix.to_owned();
+    for i in 0..n {
+        shifted[[i, i]] -= eigenvalue + offset;
+    }
+
+    let mut vector = Array1::from_elem(n, Complex64::new(1.0 / (n as f64).sqrt(), 0.0));
+    for _ in 0..3 {
+        vector = solve(&shifted, &vector);
+        let norm = vector.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+        if norm > 0.0 && norm.is_finite() {
+            vector.mapv_inplace(|x| x / norm);
+        }
+    }
+    vector
+}
+
+fn hessenberg(matrix: &ArrayView2<Complex64>) -> Array2<Complex64> {
+    let n = matrix.nrows();
+    let mut a = matrix.to_owned();
+    for k in 0..n.saturating_sub(2) {
+        let mut v: Vec<Complex64> = (k + 1..n).map(|i| a[[i, k]]).collect();
+        let norm = v.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+        if norm <= f64::MIN_POSITIVE {
+            continue;
+        }
+        let phase = if v[0].norm() > 0.0 {
+            v[0] / v[0].norm()
+        } else {
+            Complex64::new(1.0, 0.0)
+        };
+        v[0] += phase * norm;
+        let v_norm = v.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+        v.iter_mut().for_each(|x| *x /= v_norm);
+
+        for col in 0..n {
+            let dot: Complex64 = (0..v.len())
+                .map(|i| v[i].conj() * a[[k + 1 + i, col]])
+                .sum();
+            for (i, vi) in v.iter().enumerate() {
+                a[[k + 1 + i, col]] -= *vi * dot * 2.0;
+            }
+        }
+        for row in 0..n {
+            let dot: Complex64 = (0..v.len()).map(|i| a[[row, k + 1 + i]] * v[i]).sum();
+            for (i, vi) in v.iter().enumerate() {
+                a[[row, k + 1 + i]] -= dot * vi.conj() * 2.0;
+            }
+        }
+    }
+    a
+}
+
+fn wilkinson_shift(a: &Array2<Complex64>, last: usize) -> Complex64 {
+    let (p, q) = (a[[last - 1, last - 1]], a[[last, last]]);
+    let half_trace = (p + q) * 0.5;
+    let discriminant = ((p - q) * 0.5).powi(2) + a[[last - 1, last]] * a[[last, last - 1]];
+    let root = discriminant.sqrt();
+    let (mu1, mu2) = (half_trace + root, half_trace - root);
+    if (mu1 - q).norm() < (mu2 - q).norm() {
+        mu1
+    } else {
+        mu2
+    }
+}
+
+fn qr_step(a: &mut Array2<Complex64>, lo: usize, hi: usize, shift: Complex64) {
+    for i in lo..=hi {
+        a[[i, i]] -= shift;
+    }
+
+    let mut rotations = Vec::with_capacity(hi - lo);
+    for k in lo..hi {
+        let (x, y) = (a[[k, k]], a[[k + 1, k]]);
+        let r = (x.norm_sqr() + y.norm_sqr()).sqrt();
+        let (c, s) = if r == 0.0 {
+            (1.0, Complex64::new(0.0, 0.0))
+        } else if x.norm() == 0.0 {
+            (0.0, Complex64::new(1.0, 0.0))
+        } else {
+            (x.norm() / r, x / x.norm() * y.conj() / r)
+        };
+        for col in k..=hi {
+            let (u, w) = (a[[k, col]], a[[k + 1, col]]);
+            a[[k, col]] = u * c + s * w;
+            a[[k + 1, col]] = -s.conj() * u + w * c;
+        }
+        rotations.push((k, c, s));
+    }
+    for (k, c, s) in rotations {
+        for row in lo..=(k + 2).min(hi) {
+            let (u, w) = (a[[row, k]], a[[row, k + 1]]);
+            a[[row, k]] = u * c + w * s.conj();
+            a[[row, k + 1]] = -u * s + w * c;
+        }
+    }
+
+    for i in lo..=hi {
+        a[[i, i]] += shift;
+    }
+}
+
+pub(crate) fn solve(matrix: &Array2<Complex64>, rhs: &Array1<Complex64>) -> Array1<Complex64> {
+    let n = matrix.nrows();
+    let mut a = matrix.clone();
+    let mut b = rhs.clone();
+    let tiny = f64::EPSILON * frobenius_norm(&matrix.view()).max(f64::MIN_POSITIVE);
+
+    for k in 0..n {
+        let pivot = (k..n)
+            .max_by(|&i, &j| a[[i, k]].norm().total_cmp(&a[[j, k]].norm()))
+            .unwrap_or(k);
+        if pivot != k {
+            for col in 0..n {
+                a.swap([k, col], [pivot, col]);
+            }
+            b.swap(k, pivot);
+        }
+        if a[[k, k]].norm() < tiny {
+            a[[k, k]] = Complex64::new(tiny, 0.0);
+        }
+        for row in k + 1..n {
+            let factor = a[[row, k]] / a[[k, k]];
+            for col in k..n {
+                let value = a[[k, col]];
+                a[[row, col]] -= factor * value;
+            }
+            let value = b[k];
+            b[row] -= factor * value;
+        }
+    }
+
+    let mut x = Array1::zeros(n);
+    for k in (0..n).rev() {
+        let sum: Complex64 = (k + 1..n).map(|col| a[[k, col]] * x[col]).sum();
+        x[k] = (b[k] - sum) / a[[k, k]];
+    }
+    x
+}
+
 pub fn identity(dim: usize) -> Array2<Complex64> {
     let mut result = Array2::zeros((dim, dim));
     for i in 0..dim {
@@ -71,6 +377,36 @@ pub fn identity(dim: usize) -> Array2<Complex64> {
     result
 }
 
+pub fn kron(a: &Array2<Complex64>, b: &Array2<Complex64>) -> Array2<Complex64> {
+    let (rb, cb) = b.dim();
+    Array2::from_shape_fn((a.nrows() * rb, a.ncols() * cb), |(i, j)| {
+        a[[i / rb, j / cb]] * b[[i % rb, j % cb]]
+    })
+}
+
+pub fn power_spectrum(samples: &[Complex64], dt: f64) -> (Vec<f64>, Vec<f64>) {
+    let n = samples.len();
+    if n == 0 || dt <= 0.0 {
+        return (Vec::new(), Vec::new());
+    }
+
+    let mean = samples.iter().sum::<Complex64>() / n as f64;
+    let mut buffer: Vec<Complex64> = samples.iter().map(|&x| x - mean).collect();
+    rustfft::FftPlanner::new()
+        .plan_fft_forward(n)
+        .process(&mut buffer);
+
+    let num_freqs = n / 2 + 1;
+    let freqs = (0..num_freqs)
+        .map(|k| 2.0 * std::f64::consts::PI * k as f64 / (n as f64 * dt))
+        .collect();
+    let power = buffer[..num_freqs]
+        .iter()
+        .map(|c| c.norm_sqr() / (n * n) as f64)
+        .collect();
+    (freqs, power)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,6 +433,91 @@ mod tests {
         assert_relative_eq!(tr.im, 0.0);
     }
 
+    #[test]
+    fn test_spectral_norm() {
+        let mut matrix = Array2::zeros((3, 3));
+        matrix[[0, 0]] = Complex64::new(1.0, 0.0);
+        matrix[[1, 2]] = Complex64::new(0.0, -4.0);
+        matrix[[2, 1]] = Complex64::new(0.0, 4.0);
+
+        assert_relative_eq!(
+            spectral_norm(&matrix.view(), 200, 1e-12),
+            4.0,
+            epsilon = 1e-8
+        );
+        assert_eq!(spectral_norm(&Array2::zeros((2, 2)).view(), 10, 1e-6), 0.0);
+    }
+
+    #[test]
+    fn test_hermitian_eigen() {
+        let mut matrix = Array2::zeros((3, 3));
+        matrix[[0, 0]] = Complex64::new(2.0, 0.0);
+        matrix[[0, 1]] = Complex64::new(1.0, -1.0);
+        matrix[[1, 0]] = Complex64::new(1.0, 1.0);
+        matrix[[1, 2]] = Complex64::new(0.0, 0.5);
+        matrix[[2, 1]] = Complex64::new(0.0, -0.5);
+        matrix[[2, 2]] = Complex64::new(-1.0, 0.0);
+
+        let (values, vectors) = hermitian_eigen(&matrix.view());
+        assert!(values.windows(2).all(|w| w[0] <= w[1]));
+        assert!(is_unitary(&vectors.view(), 1e-10));
+        for (k, &lambda) in values.iter().enumerate() {
+            let column = vectors.column(k);
+            let residual = &matrix.dot(&column) - &column.mapv(|x| x * lambda);
+            assert!(residual.iter().all(|x| x.norm() < 1e-10));
+        }
+        assert_relative_eq!(values.iter().sum::<f64>(), 1.0, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn test_spectral_propagator_matches_closed_form() {
+        let mut matrix = Array2::zeros((2, 2));
+        matrix[[0, 0]] = Complex64::new(0.5, 0.0);
+        matrix[[0, 1]] = Complex64::new(0.0, -1.0);
+        matrix[[1, 0]] = Complex64::new(0.0, 1.0);
+        matrix[[1, 1]] = Complex64::new(-0.5, 0.0);
+
+        let spectral = SpectralDecomposition::new(&matrix.view());
+        let t = 2.3;
+        let u = spectral.propagator(t);
+        assert!(is_unitary(&u.view(), 1e-12));
+
+        let omega = 1.25f64.sqrt();
+        let (c, s) = ((omega * t).cos(), (omega * t).sin() / omega);
+        let expected = identity(2).mapv(|x| x * c) - matrix.mapv(|x| x * Complex64::new(0.0, s));
+        assert!(u.iter().zip(&expected).all(|(a, b)| (a - b).norm() < 1e-12));
+
+        let psi = Array1::from(vec![Complex64::new(0.6, 0.0), Complex64::new(0.0, 0.8)]);
+        let evolved = spectral.evolve(&psi, t);
+        assert!(evolved
+            .iter()
+            .zip(&u.dot(&psi))
+            .all(|(a, b)| (a - b).norm() < 1e-12));
+    }
+
+    #[test]
+    fn test_complex_eigenvalues_of_non_normal_matrix() {
+        let entries = [
+            [(1.0, 0.0), (2.0, 1.0), (0.0, -1.0)],
+            [(0.0, 0.0), (0.5, 0.5), (3.0, 0.0)],
+            [(0.25, 0.0), (0.0, 0.0), (-1.0, 2.0)],
+        ];
+        let matrix = Array2::from_shape_fn((3, 3), |(i, j)| {
+            Complex64::new(entries[i][j].0, entries[i][j].1)
+        });
+
+        let values = complex_eigenvalues(&matrix.view()).unwrap();
+        assert_eq!(values.len(), 3);
+        let sum: Complex64 = values.iter().sum();
+        assert_relative_eq!(sum.re, 0.5, epsilon = 1e-10);
+        assert_relative_eq!(sum.im, 2.5, epsilon = 1e-10);
+        for &lambda in &values {
+            let v = eigenvector(&matrix.view(), lambda);
+            let residual = &matrix.dot(&v) - &v.mapv(|x| x * lambda);
+            assert!(residual.iter().all(|x| x.norm() < 1e-8));
+        }
+    }
+
     #[test]
     fn test_identity() {
         let id = identity(3);
@@ -104,4 +525,23 @@ mod tests {
         let tr = trace(&id.view());
         assert_relative_eq!(tr.re, 3.0);
     }
+
+    #[test]
+    fn test_power_spectrum_peak() {
+        let dt = 0.1;
+        let omega = 2.0 * std::f64::consts::PI * 8.0 / (64.0 * dt);
+        let samples: Vec<Complex64> = (0..64)
+            .map(|i| Complex64::new((omega * i as f64 * dt).cos(), 0.0))
+            .collect();
+
+        let (freqs, power) = power_spectrum(&samples, dt);
+        let peak = power
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .unwrap()
+            .0;
+        assert_relative_eq!(freqs[peak], omega, epsilon = 1e-10);
+        assert_relative_eq!(power[peak], 0.25, epsilon = 1e-12);
+    }
 }
@@ -1,5 +1,10 @@
 pub mod error;
+pub mod exit;
 pub mod logger;
 pub mod math;
+pub mod performance;
+pub mod resources;
+pub mod tolerance;
 
-pub use error::{Error, Result};
+pub use error::{Diagnostics, Error, ErrorCode, Result};
+pub use tolerance::Tolerances;
@@ -0,0 +1,432 @@
+use crate::data::Config;
+use crate::gpu::{BackendType, GpuBackend};
+use crate::utils::{Error, Result};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+const INTEGRATOR_WORK_STATES: u64 = 6;
+const LINDBLAD_WORK_MATRICES: u64 = 16;
+const SUPEROPERATOR_WORK_MAPS: u64 = 2;
+const CUMULANT_OUTPUTS: u64 = 6;
+pub const DETERMINISTIC_SEED: u64 = 0;
+
+static DETERMINISTIC: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceConfig {
+    #[serde(default)]
+    pub threads: usize,
+    #[serde(default = "default_pinning")]
+    pub pinning: String,
+    #[serde(default)]
+    pub device_preference: Vec<String>,
+    #[serde(default)]
+    pub memory_limit_mb: Option<u64>,
+    #[serde(default)]
+    pub deterministic: bool,
+}
+
+impl Default for PerformanceConfig {
+    fn default() -> Self {
+        Self {
+            threads: 0,
+            pinning: default_pinning(),
+            device_preference: Vec::new(),
+            memory_limit_mb: None,
+            deterministic: false,
+        }
+    }
+}
+
+fn default_pinning() -> String {
+    "none".to_string()
+}
+
+impl PerformanceConfig {
+    pub fn validate(&self) -> Result<()> {
+        Pinning::parse(&self.pinning)?;
+        for device in &self.device_preference {
+            Device::parse(device)?;
+        }
+        if self.memory_limit_mb == Some(0) {
+            return Err(Error::InvalidParameter(
+                "performance.memory_limit_mb must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pinning {
+    None,
+    Compact,
+    Scatter,
+}
+
+impl Pinning {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "none" => Ok(Pinning::None),
+            "compact" => Ok(Pinning::Compact),
+            "scatter" => Ok(Pinning::Scatter),
+            other => Err(Error::Config(format!(
+                "Unknown pinning policy '{}', expected none, compact or scatter",
+                other
+            ))),
+        }
+    }
+
+    pub fn core_for(&self, thread: usize, threads: usize, cores: usize) -> Option<usize> {
+        if cores == 0 {
+            return None;
+        }
+        match self {
+            Pinning::None => None,
+            Pinning::Compact => Some(thread % cores),
+            Pinning::Scatter => Some((thread * cores / threads.max(1)) % cores),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Device {
+    Gpu,
+    Cpu,
+}
+
+impl Device {
+    fn parse(name: &str) -> Result<Self> {
+        match name {
+            "gpu" => Ok(Device::Gpu),
+            "cpu" => Ok(Device::Cpu),
+            other => Err(Error::Config(format!(
+                "Unknown device '{}' in performance.device_preference, expected gpu or cpu",
+                other
+            ))),
+        }
+    }
+}
+
+pub async fn apply(config: &mut Config) -> Result<()> {
+    let performance = config.performance.clone();
+    performance.validate()?;
+    config.tolerances.set_global()?;
+    configure_threads(&performance)?;
+    check_memory(config)?;
+    set_deterministic(performance.deterministic);
+    if performance.deterministic {
+        if config.gpu.enabled {
+            tracing::info!("Deterministic mode runs on the CPU, disabling the GPU");
+        }
+        config.gpu.enabled = false;
+    } else if !performance.device_preference.is_empty() {
+        let gpu_available = GpuBackend::available_devices()
+            .await?
+            .iter()
+            .any(|d| !matches!(d.backend_type, BackendType::Cpu));
+        config.gpu.enabled = select_device(&performance.device_preference, gpu_available)?;
+    }
+    Ok(())
+}
+
+pub fn set_deterministic(enabled: bool) {
+    DETERMINISTIC.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_deterministic() -> bool {
+    DETERMINISTIC.load(Ordering::Relaxed)
+}
+
+pub fn resolve_seed(seed: Option<u64>) -> Option<u64> {
+    seed.or_else(|| is_deterministic().then_some(DETERMINISTIC_SEED))
+}
+
+pub fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match resolve_seed(seed) {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    }
+}
+
+pub fn configure_threads(config: &PerformanceConfig) -> Result<()> {
+    let pinning = Pinning::parse(&config.pinning)?;
+    let threads = if config.threads == 0 {
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        config.threads
+    };
+    let cores = core_affinity::get_core_ids().unwrap_or_default();
+    if pinning != Pinning::None && cores.is_empty() {
+        tracing::warn!("Thread pinning is not supported on this platform, ignoring");
+    }
+
+    let built = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .start_handler(move |thread| {
+            if let Some(core) = pinning.core_for(thread, threads, cores.len()) {
+                core_affinity::set_for_current(cores[core]);
+            }
+        })
+        .build_global();
+    match built {
+        Ok(()) => {
+            tracing::info!(
+                "Using {} worker threads (pinning: {})",
+                threads,
+                config.pinning
+            );
+            Ok(())
+        }
+        Err(e) => {
+            tracing::warn!("Thread pool already initialised, keeping it: {}", e);
+            Ok(())
+        }
+    }
+}
+
+pub fn select_device(preference: &[String], gpu_available: bool) -> Result<bool> {
+    for name in preference {
+        match Device::parse(name)? {
+            Device::Gpu if gpu_available => return Ok(true),
+            Device::Gpu => tracing::info!("No GPU device available, trying next preference"),
+            Device::Cpu => return Ok(false),
+        }
+    }
+    Err(Error::Config(format!(
+        "None of the preferred devices {:?} is available",
+        preference
+    )))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Solver {
+    Auto,
+    Dense,
+    Superoperator,
+    Trajectories,
+    Cumulant,
+}
+
+impl Solver {
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "auto" => Ok(Solver::Auto),
+            "dense" => Ok(Solver::Dense),
+            "superoperator" => Ok(Solver::Superoperator),
+            "trajectories" => Ok(Solver::Trajectories),
+            "cumulant" => Ok(Solver::Cumulant),
+            other => Err(Error::Config(format!(
+                "Unknown solver '{}', expected auto, dense, superoperator, trajectories or cumulant",
+                other
+            ))),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Solver::Auto => "auto",
+            Solver::Dense => "dense",
+            Solver::Superoperator => "superoperator",
+            Solver::Trajectories => "trajectories",
+            Solver::Cumulant => "cumulant",
+        }
+    }
+}
+
+pub fn estimated_memory_bytes(config: &Config) -> u64 {
+    let solver = Solver::parse(&config.simulation.solver).unwrap_or(Solver::Dense);
+    solver_memory_bytes(config, solver)
+}
+
+pub fn solver_memory_bytes(config: &Config, solver: Solver) -> u64 {
+    let amplitude = std::mem::size_of::<num_complex::Complex64>() as u64;
+    let dim = config.system.hilbert_dim as u64;
+    let state = dim * amplitude;
+    let matrix = dim * state;
+    let open = config.lindblad.enabled;
+    let collapse_ops = if open {
+        config.lindblad.operators.len() as u64
+    } else {
+        0
+    };
+    let outputs = config
+        .observables
+        .time_grid()
+        .output_times(config.simulation.duration, config.simulation.timestep)
+        .len() as u64;
+    let observables = config.observables.list.len() as u64 * amplitude;
+
+    let mut per_output = std::mem::size_of::<f64>() as u64 + observables;
+    if config.output.states {
+        per_output += if open { matrix } else { state };
+    }
+    let operators = (collapse_ops + 1) * matrix;
+    // Every integrator step fills a dense copy of H(t), in f32 on the single-precision path.
+    let hamiltonian = match crate::core::Precision::parse(&config.simulation.precision) {
+        Ok(crate::core::Precision::Single) if !open => matrix / 2,
+        _ => matrix,
+    };
+
+    match solver {
+        Solver::Dense if open => {
+            (LINDBLAD_WORK_MATRICES + collapse_ops) * matrix + operators + outputs * per_output
+        }
+        Solver::Auto | Solver::Dense => {
+            hamiltonian + INTEGRATOR_WORK_STATES * state + outputs * per_output
+        }
+        Solver::Superoperator => {
+            SUPEROPERATOR_WORK_MAPS * matrix * dim * dim + operators + outputs * per_output
+        }
+        Solver::Trajectories => {
+            let threads = match config.performance.threads {
+                0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+                n => n,
+            } as u64;
+            let per_thread = hamiltonian
+                + (INTEGRATOR_WORK_STATES + collapse_ops) * state
+                + outputs * (std::mem::size_of::<f64>() as u64 + 2 * observables);
+            operators + threads * per_thread
+        }
+        Solver::Cumulant => {
+            outputs * (std::mem::size_of::<f64>() as u64 + CUMULANT_OUTPUTS * amplitude)
+        }
+    }
+}
+
+pub fn select_solver(config: &Config) -> Result<Solver> {
+    let requested = Solver::parse(&config.simulation.solver)?;
+    let Some(limit_mb) = config.performance.memory_limit_mb else {
+        return Ok(match requested {
+            Solver::Auto => Solver::Dense,
+            solver => solver,
+        });
+    };
+    let fits = |solver| solver_memory_bytes(config, solver) <= limit_mb * BYTES_PER_MB;
+
+    let candidates = match requested {
+        Solver::Auto if config.lindblad.enabled => vec![Solver::Dense, Solver::Trajectories],
+        Solver::Auto => vec![Solver::Dense],
+        solver => vec![solver],
+    };
+    if let Some(&solver) = candidates.iter().find(|&&solver| fits(solver)) {
+        if solver != candidates[0] {
+            tracing::info!(
+                "Dense density matrix exceeds performance.memory_limit_mb = {}, \
+                 switching to {} quantum trajectories",
+                limit_mb,
+                config.simulation.trajectories
+            );
+        }
+        return Ok(solver);
+    }
+
+    let solver = candidates[0];
+    let alternatives: Vec<String> = [Solver::Dense, Solver::Trajectories]
+        .into_iter()
+        .filter(|&other| config.lindblad.enabled && !candidates.contains(&other) && fits(other))
+        .map(|other| {
+            format!(
+                "set simulation.solver = \"{}\" (about {})",
+                other.name(),
+                megabytes(solver_memory_bytes(config, other))
+            )
+        })
+        .collect();
+    let advice = if alternatives.is_empty() {
+        "reduce system.hilbert_dim, increase the save interval or disable output.states".to_string()
+    } else {
+        alternatives.join(" or ")
+    };
+    Err(Error::Config(format!(
+        "Estimated memory use of {} for the {} solver at hilbert_dim = {} exceeds \
+         performance.memory_limit_mb = {}; {}, or raise the limit",
+        megabytes(solver_memory_bytes(config, solver)),
+        solver.name(),
+        config.system.hilbert_dim,
+        limit_mb,
+        advice
+    )))
+}
+
+pub fn check_memory(config: &Config) -> Result<()> {
+    select_solver(config).map(|_| ())
+}
+
+fn megabytes(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / BYTES_PER_MB as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinning_and_device_selection() {
+        assert_eq!(Pinning::Compact.core_for(5, 8, 4), Some(1));
+        assert_eq!(Pinning::Scatter.core_for(1, 2, 8), Some(4));
+        assert_eq!(Pinning::None.core_for(0, 1, 4), None);
+        assert!(Pinning::parse("round_robin").is_err());
+
+        let preference = vec!["gpu".to_string(), "cpu".to_string()];
+        assert!(select_device(&preference, true).unwrap());
+        assert!(!select_device(&preference, false).unwrap());
+        assert!(select_device(&["gpu".to_string()], false).is_err());
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let mut config = Config::generate_template("driven_tls").unwrap();
+        config.output.states = true;
+        config.performance.memory_limit_mb = Some(1);
+        assert!(check_memory(&config).is_ok());
+
+        config.system.hilbert_dim = 10_000;
+        assert!(check_memory(&config).is_err());
+        assert!(estimated_memory_bytes(&config) > BYTES_PER_MB);
+
+        // The dense H(t) built at every step dominates a closed system.
+        config.output.states = false;
+        config.system.hilbert_dim = 4096;
+        let double = estimated_memory_bytes(&config);
+        assert!(double > 4096 * 4096 * 16);
+        config.simulation.precision = "f32".to_string();
+        assert!(estimated_memory_bytes(&config) < double);
+    }
+
+    #[test]
+    fn test_auto_solver_switches_to_trajectories() {
+        let mut config = Config::generate_template("driven_tls").unwrap();
+        config.system.hilbert_dim = 1000;
+        config.performance.threads = 4;
+        config.lindblad.enabled = true;
+        config.lindblad.operators = vec![crate::data::config::LindbladOperatorConfig {
+            r#type: "annihilation".to_string(),
+            rate: 0.1,
+            temperature: 0.0,
+            site: None,
+        }];
+        config.performance.memory_limit_mb = Some(128);
+
+        config.simulation.solver = "auto".to_string();
+        assert_eq!(select_solver(&config).unwrap(), Solver::Trajectories);
+
+        config.simulation.solver = "dense".to_string();
+        let message = select_solver(&config).unwrap_err().to_string();
+        assert!(message.contains("simulation.solver = \"trajectories\""));
+
+        config.simulation.solver = "superoperator".to_string();
+        assert!(select_solver(&config).is_err());
+        assert!(
+            solver_memory_bytes(&config, Solver::Superoperator)
+                > 1000 * solver_memory_bytes(&config, Solver::Dense)
+        );
+
+        config.performance.memory_limit_mb = None;
+        config.simulation.solver = "auto".to_string();
+        assert_eq!(select_solver(&config).unwrap(), Solver::Dense);
+    }
+}
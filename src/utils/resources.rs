@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+const CLOCK_TICKS_PER_SECOND: f64 = 100.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceUsage {
+    pub wall_time: f64,
+    #[serde(default)]
+    pub cpu_time: Option<f64>,
+    #[serde(default)]
+    pub peak_rss_bytes: Option<u64>,
+    #[serde(default)]
+    pub gpu_peak_bytes: Option<u64>,
+}
+
+impl ResourceUsage {
+    pub fn combine(&self, other: &ResourceUsage) -> Self {
+        let max = |a: Option<u64>, b: Option<u64>| a.max(b);
+        Self {
+            wall_time: self.wall_time + other.wall_time,
+            cpu_time: match (self.cpu_time, other.cpu_time) {
+                (Some(a), Some(b)) => Some(a + b),
+                (a, b) => a.or(b),
+            },
+            peak_rss_bytes: max(self.peak_rss_bytes, other.peak_rss_bytes),
+            gpu_peak_bytes: max(self.gpu_peak_bytes, other.gpu_peak_bytes),
+        }
+    }
+
+    pub fn cpu_utilization(&self) -> Option<f64> {
+        self.cpu_time
+            .filter(|_| self.wall_time > 0.0)
+            .map(|cpu| cpu / self.wall_time)
+    }
+
+    pub fn metrics(&self) -> Vec<(String, f64)> {
+        let megabytes = |bytes: Option<u64>| bytes.map_or(f64::NAN, |b| b as f64 / BYTES_PER_MB);
+        [
+            ("wall_time_s", self.wall_time),
+            ("cpu_time_s", self.cpu_time.unwrap_or(f64::NAN)),
+            ("peak_rss_mb", megabytes(self.peak_rss_bytes)),
+            ("gpu_peak_mb", megabytes(self.gpu_peak_bytes)),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_string(), value))
+        .collect()
+    }
+
+    pub fn describe(&self) -> String {
+        let mut parts = vec![format!("wall {:.3} s", self.wall_time)];
+        if let Some(cpu) = self.cpu_time {
+            parts.push(format!("cpu {:.3} s", cpu));
+        }
+        if let Some(rss) = self.peak_rss_bytes {
+            parts.push(format!("peak RSS {:.1} MB", rss as f64 / BYTES_PER_MB));
+        }
+        if let Some(gpu) = self.gpu_peak_bytes {
+            parts.push(format!("GPU peak {:.1} MB", gpu as f64 / BYTES_PER_MB));
+        }
+        parts.join(", ")
+    }
+}
+
+pub struct ResourceMeter {
+    start: Instant,
+    cpu_start: Option<f64>,
+}
+
+impl ResourceMeter {
+    pub fn start() -> Self {
+        Self {
+            start: Instant::now(),
+            cpu_start: process_cpu_time(),
+        }
+    }
+
+    pub fn finish(&self) -> ResourceUsage {
+        let gpu_peak = crate::gpu::memory::peak_allocated() as u64;
+        ResourceUsage {
+            wall_time: self.start.elapsed().as_secs_f64(),
+            cpu_time: self
+                .cpu_start
+                .zip(process_cpu_time())
+                .map(|(start, end)| (end - start).max(0.0)),
+            peak_rss_bytes: peak_rss_bytes(),
+            gpu_peak_bytes: (gpu_peak > 0).then_some(gpu_peak),
+        }
+    }
+}
+
+fn process_cpu_time() -> Option<f64> {
+    let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+    let fields: Vec<&str> = stat
+        .get(stat.rfind(')')? + 2..)?
+        .split_whitespace()
+        .collect();
+    let utime: f64 = fields.get(11)?.parse().ok()?;
+    let stime: f64 = fields.get(12)?.parse().ok()?;
+    Some((utime + stime) / CLOCK_TICKS_PER_SECOND)
+}
+
+fn peak_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meter_and_combine() {
+        let meter = ResourceMeter::start();
+        let work: f64 = (0..200_000).map(|k| (k as f64).sqrt()).sum();
+        assert!(work > 0.0);
+        let usage = meter.finish();
+        assert!(usage.wall_time > 0.0);
+        if cfg!(target_os = "linux") {
+            assert!(usage.cpu_time.is_some());
+            assert!(usage.peak_rss_bytes.is_some_and(|rss| rss > 0));
+        }
+
+        let pilot = ResourceUsage {
+            wall_time: 2.0,
+            cpu_time: Some(6.0),
+            peak_rss_bytes: Some(100),
+            gpu_peak_bytes: None,
+        };
+        let total = pilot.combine(&ResourceUsage {
+            wall_time: 1.0,
+            cpu_time: None,
+            peak_rss_bytes: Some(300),
+            gpu_peak_bytes: Some(50),
+        });
+        assert_eq!(total.wall_time, 3.0);
+        assert_eq!(total.cpu_time, Some(6.0));
+        assert_eq!(total.peak_rss_bytes, Some(300));
+        assert_eq!(total.gpu_peak_bytes, Some(50));
+        assert_eq!(pilot.cpu_utilization(), Some(3.0));
+        assert!(pilot.metrics()[3].1.is_nan());
+    }
+}
@@ -0,0 +1,68 @@
+use crate::utils::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::sync::RwLock;
+
+static GLOBAL: RwLock<Tolerances> = RwLock::new(Tolerances::DEFAULT);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tolerances {
+    #[serde(default = "default_tolerance")]
+    pub normalization: f64,
+    #[serde(default = "default_tolerance")]
+    pub hermiticity: f64,
+    #[serde(default = "default_tolerance")]
+    pub trace: f64,
+}
+
+impl Default for Tolerances {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+fn default_tolerance() -> f64 {
+    Tolerances::DEFAULT.normalization
+}
+
+impl Tolerances {
+    pub const DEFAULT: Self = Self::uniform(1e-10);
+
+    pub const fn uniform(tolerance: f64) -> Self {
+        Self {
+            normalization: tolerance,
+            hermiticity: tolerance,
+            trace: tolerance,
+        }
+    }
+
+    pub const fn single_precision() -> Self {
+        Self::uniform(1e-5)
+    }
+
+    pub fn global() -> Self {
+        *GLOBAL.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set_global(self) -> Result<()> {
+        self.validate()?;
+        *GLOBAL.write().unwrap_or_else(|e| e.into_inner()) = self;
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        for (name, value) in [
+            ("normalization", self.normalization),
+            ("hermiticity", self.hermiticity),
+            ("trace", self.trace),
+        ] {
+            if !(value > 0.0 && value.is_finite()) {
+                return Err(Error::InvalidParameter(format!(
+                    "tolerances.{} must be positive, got {}",
+                    name, value
+                )));
+            }
+        }
+        Ok(())
+    }
+}
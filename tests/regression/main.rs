@@ -0,0 +1,260 @@
+use chronophoton::core::lindblad::LindbladOperator;
+use chronophoton::core::observables::{NumberOperator, PopulationOperator};
+use chronophoton::core::systems::{DrivenCavity, DrivenTLS};
+use chronophoton::core::{DensityMatrix, Observable, QuantumState};
+use chronophoton::quick;
+use chronophoton::simulation::{SimulationBuilder, TimeGrid, TransmissionSpectrum};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+const UPDATE_ENV: &str = "CHRONOPHOTON_UPDATE_GOLDEN";
+
+struct Trace {
+    times: Vec<f64>,
+    columns: Vec<(String, Vec<f64>)>,
+}
+
+impl Trace {
+    fn to_csv(&self) -> String {
+        let mut out = String::from("t");
+        for (name, _) in &self.columns {
+            write!(out, ",{}", name).unwrap();
+        }
+        out.push('\n');
+        for (k, t) in self.times.iter().enumerate() {
+            write!(out, "{:?}", t).unwrap();
+            for (_, values) in &self.columns {
+                write!(out, ",{:?}", values[k]).unwrap();
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn from_csv(contents: &str) -> Self {
+        let mut lines = contents.lines();
+        let header: Vec<&str> = lines
+            .next()
+            .expect("empty golden file")
+            .split(',')
+            .collect();
+        let mut trace = Trace {
+            times: Vec::new(),
+            columns: header[1..]
+                .iter()
+                .map(|name| (name.to_string(), Vec::new()))
+                .collect(),
+        };
+        for line in lines.filter(|l| !l.is_empty()) {
+            let values: Vec<f64> = line.split(',').map(|v| v.parse().unwrap()).collect();
+            trace.times.push(values[0]);
+            for (column, value) in trace.columns.iter_mut().zip(&values[1..]) {
+                column.1.push(*value);
+            }
+        }
+        trace
+    }
+}
+
+struct Tolerance {
+    rtol: f64,
+    atol: f64,
+}
+
+const DEFAULT_TOLERANCE: Tolerance = Tolerance {
+    rtol: 1e-7,
+    atol: 1e-9,
+};
+
+fn golden_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/regression/golden")
+        .join(format!("{}.csv", name))
+}
+
+fn check_golden(name: &str, trace: Trace, tolerance: Tolerance) {
+    let path = golden_path(name);
+    if std::env::var_os(UPDATE_ENV).is_some() {
+        std::fs::write(&path, trace.to_csv()).unwrap();
+        eprintln!("Updated golden trace {}", path.display());
+        return;
+    }
+
+    let contents = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "Missing golden trace {} ({}). Run with {}=1 to create it",
+            path.display(),
+            e,
+            UPDATE_ENV
+        )
+    });
+    let golden = Trace::from_csv(&contents);
+
+    assert_eq!(
+        golden.times.len(),
+        trace.times.len(),
+        "{}: number of samples changed",
+        name
+    );
+    let golden_names: Vec<_> = golden.columns.iter().map(|(n, _)| n).collect();
+    let names: Vec<_> = trace.columns.iter().map(|(n, _)| n).collect();
+    assert_eq!(golden_names, names, "{}: observables changed", name);
+
+    let mut failures = Vec::new();
+    for (k, (&expected, &actual)) in golden.times.iter().zip(&trace.times).enumerate() {
+        if (expected - actual).abs() > tolerance.atol + tolerance.rtol * expected.abs() {
+            failures.push(format!("t[{}]: expected {}, got {}", k, expected, actual));
+        }
+    }
+    for ((column, expected), (_, actual)) in golden.columns.iter().zip(&trace.columns) {
+        let worst = expected
+            .iter()
+            .zip(actual)
+            .enumerate()
+            .map(|(k, (e, a))| (k, (e - a).abs(), tolerance.atol + tolerance.rtol * e.abs()))
+            .filter(|(_, diff, allowed)| diff > allowed || diff.is_nan())
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+        if let Some((k, diff, _)) = worst {
+            failures.push(format!(
+                "{} at t = {}: expected {}, got {} (|diff| = {:.3e})",
+                column, golden.times[k], expected[k], actual[k], diff
+            ));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} deviates from golden trace {} (rtol = {:e}, atol = {:e}):\n  {}\nIf the change is intended, rerun with {}=1",
+        name,
+        path.display(),
+        tolerance.rtol,
+        tolerance.atol,
+        failures.join("\n  "),
+        UPDATE_ENV
+    );
+}
+
+fn runner_trace(results: &chronophoton::simulation::SimulationResults) -> Trace {
+    Trace {
+        times: results.times().to_vec(),
+        columns: results
+            .iter_series()
+            .map(|s| (s.name.to_string(), s.to_vec_real()))
+            .collect(),
+    }
+}
+
+#[test]
+fn golden_driven_tls_resonant() {
+    let results = SimulationBuilder::new()
+        .hamiltonian(DrivenTLS::new(5.0, 5.0, 0.5))
+        .initial_state(QuantumState::ground_state(2))
+        .duration(50.0)
+        .timestep(0.01)
+        .time_grid(TimeGrid::uniform(1.0))
+        .observable("p_ground", PopulationOperator::new(2, 0).unwrap())
+        .observable("p_excited", PopulationOperator::new(2, 1).unwrap())
+        .build()
+        .unwrap()
+        .run()
+        .unwrap();
+
+    check_golden(
+        "driven_tls_resonant",
+        runner_trace(&results),
+        DEFAULT_TOLERANCE,
+    );
+}
+
+#[test]
+fn golden_driven_tls_detuned() {
+    let results = SimulationBuilder::new()
+        .hamiltonian(DrivenTLS::new(5.5, 5.0, 0.5))
+        .initial_state(QuantumState::ground_state(2))
+        .duration(20.0)
+        .timestep(0.01)
+        .time_grid(TimeGrid::uniform(0.5))
+        .observable("p_excited", PopulationOperator::new(2, 1).unwrap())
+        .build()
+        .unwrap()
+        .run()
+        .unwrap();
+
+    check_golden(
+        "driven_tls_detuned",
+        runner_trace(&results),
+        DEFAULT_TOLERANCE,
+    );
+}
+
+#[test]
+fn golden_parametric_cavity() {
+    let dim = 12;
+    let results = SimulationBuilder::new()
+        .hamiltonian(DrivenCavity::new(1.0, 2.0, 0.05, dim))
+        .initial_state(QuantumState::ground_state(dim))
+        .duration(20.0)
+        .timestep(0.005)
+        .time_grid(TimeGrid::uniform(0.5))
+        .observable("n", NumberOperator::new(dim))
+        .build()
+        .unwrap()
+        .run()
+        .unwrap();
+
+    check_golden(
+        "parametric_cavity",
+        runner_trace(&results),
+        DEFAULT_TOLERANCE,
+    );
+}
+
+#[test]
+fn golden_lindblad_decay() {
+    let dim = 4;
+    let mut data = ndarray::Array1::zeros(dim);
+    data[3] = num_complex::Complex64::new(1.0, 0.0);
+    let rho0: DensityMatrix = QuantumState::new(data).unwrap().to_density_matrix();
+
+    let trajectory = quick::lindblad_evolve(
+        DrivenCavity::new(1.0, 2.0, 0.0, dim),
+        rho0,
+        vec![LindbladOperator::annihilation(dim, 0.2).unwrap()],
+        (0.0, 10.0),
+        0.01,
+    )
+    .unwrap();
+
+    let number = NumberOperator::new(dim);
+    let stride = 50;
+    let n: Vec<f64> = trajectory
+        .expectation(&number as &dyn Observable)
+        .iter()
+        .step_by(stride)
+        .map(|v| v.re)
+        .collect();
+    let trace = Trace {
+        times: trajectory.times.iter().step_by(stride).copied().collect(),
+        columns: vec![("n".to_string(), n)],
+    };
+
+    check_golden("lindblad_decay", trace, DEFAULT_TOLERANCE);
+}
+
+#[test]
+fn vacuum_rabi_example_splitting() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("examples/configs/vacuum_rabi.toml");
+    let mut config = chronophoton::data::config::Config::from_file(&path).unwrap();
+    config.spectroscopy.points = 61;
+
+    let spectrum = TransmissionSpectrum::from_config(&config).unwrap();
+    let modes = spectrum.normal_modes().unwrap();
+    let g = config.system.parameters.get("g").unwrap();
+    assert!(
+        (modes.splitting() - 2.0 * g).abs() < 0.01 * g,
+        "vacuum Rabi splitting {} differs from 2g = {}",
+        modes.splitting(),
+        2.0 * g
+    );
+    assert!((modes.lower + modes.upper).abs() < 1e-3 * g);
+}